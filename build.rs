@@ -0,0 +1,51 @@
+// build.rs
+//
+// Con la feature `step` compila el lado C++ de `graphics::cad_import`
+// (lectura de STEP/IGES y teselado) contra el OpenCASCADE (7.6 o 7.7) del
+// sistema, p. ej. los paquetes libocct-*-dev de Debian/Ubuntu. No se usa el
+// OCCT que compila occt-sys porque viene sin los módulos de IGES. Si OCCT no
+// está en las rutas habituales se indican con OCCT_INCLUDE_DIR y OCCT_LIB_DIR.
+// Sin la feature no hace nada.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "step")]
+    step::build();
+}
+
+#[cfg(feature = "step")]
+mod step {
+    /// Carpeta de los encabezados de OCCT si no se indica otra
+    const DEFAULT_INCLUDE_DIR: &str = "/usr/include/opencascade";
+
+    /// Bibliotecas de OCCT que usa cad_import.cpp
+    const OCCT_LIBS: &[&str] = &[
+        "TKIGES", "TKSTEP", "TKSTEP209", "TKSTEPAttr", "TKSTEPBase", "TKXSBase", "TKMesh", "TKBool", "TKBO",
+        "TKPrim", "TKShHealing", "TKTopAlgo", "TKGeomAlgo", "TKBRep", "TKGeomBase", "TKG3d", "TKG2d", "TKMath",
+        "TKernel",
+    ];
+
+    pub fn build() {
+        for var in ["OCCT_INCLUDE_DIR", "OCCT_LIB_DIR"] {
+            println!("cargo:rerun-if-env-changed={}", var);
+        }
+        let include_dir = std::env::var("OCCT_INCLUDE_DIR").unwrap_or_else(|_| DEFAULT_INCLUDE_DIR.to_string());
+
+        cxx_build::bridge("src/graphics/cad_import.rs")
+            .file("src/graphics/cad_import.cpp")
+            .flag_if_supported("-std=c++14")
+            .define("_USE_MATH_DEFINES", "TRUE")
+            .include(include_dir)
+            .compile("rust_engine_cad_import");
+
+        if let Ok(lib_dir) = std::env::var("OCCT_LIB_DIR") {
+            println!("cargo:rustc-link-search=native={}", lib_dir);
+        }
+        for lib in OCCT_LIBS {
+            println!("cargo:rustc-link-lib={}", lib);
+        }
+        for file in ["src/graphics/cad_import.rs", "src/graphics/cad_import.cpp", "src/graphics/cad_import.hxx"] {
+            println!("cargo:rerun-if-changed={}", file);
+        }
+    }
+}
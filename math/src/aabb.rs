@@ -0,0 +1,110 @@
+use crate::matrix_4_by_4::Matrix4;
+use crate::vec3::Vec3;
+
+// Caja alineada a los ejes (min, max)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    /// Caja vacía: cualquier `grow` la reemplaza
+    pub const EMPTY: Self = Self {
+        min: Vec3 { x: f32::INFINITY, y: f32::INFINITY, z: f32::INFINITY },
+        max: Vec3 { x: f32::NEG_INFINITY, y: f32::NEG_INFINITY, z: f32::NEG_INFINITY },
+    };
+
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    pub fn grow(&mut self, p: Vec3) {
+        self.min = Vec3::new(self.min.x.min(p.x), self.min.y.min(p.y), self.min.z.min(p.z));
+        self.max = Vec3::new(self.max.x.max(p.x), self.max.y.max(p.y), self.max.z.max(p.z));
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        if other.is_empty() {
+            return *self;
+        }
+        let mut result = *self;
+        result.grow(other.min);
+        result.grow(other.max);
+        result
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    /// Las 8 esquinas de la caja
+    pub fn corners(&self) -> [Vec3; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            Vec3::new(a.x, a.y, a.z), Vec3::new(b.x, a.y, a.z),
+            Vec3::new(a.x, b.y, a.z), Vec3::new(b.x, b.y, a.z),
+            Vec3::new(a.x, a.y, b.z), Vec3::new(b.x, a.y, b.z),
+            Vec3::new(a.x, b.y, b.z), Vec3::new(b.x, b.y, b.z),
+        ]
+    }
+
+    /// Caja que contiene a esta transformada por una matriz afín. En vez de
+    /// transformar las 8 esquinas transforma el centro y proyecta la mitad del
+    /// tamaño con los valores absolutos de la matriz (Arvo): dos productos
+    /// matriz * vector.
+    pub fn transformed(&self, matrix: &Matrix4) -> Aabb {
+        if self.is_empty() {
+            return Aabb::EMPTY;
+        }
+        let (c, h) = (self.center(), self.size() * 0.5);
+        let [cx, cy, cz, _] = matrix.transform_vec4([c.x, c.y, c.z, 1.0]);
+        let abs = Matrix4 { m: matrix.m.map(f32::abs) };
+        let [hx, hy, hz, _] = abs.transform_vec4([h.x, h.y, h.z, 0.0]);
+        let (center, half) = (Vec3::new(cx, cy, cz), Vec3::new(hx, hy, hz));
+        Aabb::new(center - half, center + half)
+    }
+
+    /// Distancia al cuadrado de `p` a la caja (0 si está dentro)
+    pub fn distance_squared(&self, p: Vec3) -> f32 {
+        let dx = (self.min.x - p.x).max(p.x - self.max.x).max(0.0);
+        let dy = (self.min.y - p.y).max(p.y - self.max.y).max(0.0);
+        let dz = (self.min.z - p.z).max(p.z - self.max.z).max(0.0);
+        dx * dx + dy * dy + dz * dz
+    }
+
+    /// Eje más largo (0 = x, 1 = y, 2 = z)
+    pub fn largest_axis(&self) -> usize {
+        let size = self.size();
+        if size.x >= size.y && size.x >= size.z {
+            0
+        } else if size.y >= size.z {
+            1
+        } else {
+            2
+        }
+    }
+}
+
+impl Default for Aabb {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+/// Componente `axis` de un vector (0 = x, 1 = y, 2 = z)
+pub fn axis(v: Vec3, axis: usize) -> f32 {
+    match axis {
+        0 => v.x,
+        1 => v.y,
+        _ => v.z,
+    }
+}
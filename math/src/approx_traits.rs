@@ -0,0 +1,72 @@
+// Traits del crate approx (assert_relative_eq!, assert_abs_diff_eq!...) para
+// los tipos de math. Todos se comparan componente a componente con las
+// tolerancias de f32.
+
+use approx::{AbsDiffEq, RelativeEq, UlpsEq};
+
+use crate::{matrix_4_by_4::Matrix4, quaternion::Quat, vec3::Vec3};
+
+/// Implementa los tres traits comparando los componentes que devuelve `$components`
+macro_rules! impl_approx {
+    ($ty:ty, $components:expr) => {
+        impl AbsDiffEq for $ty {
+            type Epsilon = f32;
+
+            fn default_epsilon() -> f32 {
+                crate::EPSILON
+            }
+
+            fn abs_diff_eq(&self, other: &Self, epsilon: f32) -> bool {
+                let components = $components;
+                components(self).iter().zip(components(other).iter()).all(|(a, b)| a.abs_diff_eq(b, epsilon))
+            }
+        }
+
+        impl RelativeEq for $ty {
+            fn default_max_relative() -> f32 {
+                crate::EPSILON
+            }
+
+            fn relative_eq(&self, other: &Self, epsilon: f32, max_relative: f32) -> bool {
+                let components = $components;
+                components(self)
+                    .iter()
+                    .zip(components(other).iter())
+                    .all(|(a, b)| a.relative_eq(b, epsilon, max_relative))
+            }
+        }
+
+        impl UlpsEq for $ty {
+            fn default_max_ulps() -> u32 {
+                4
+            }
+
+            fn ulps_eq(&self, other: &Self, epsilon: f32, max_ulps: u32) -> bool {
+                let components = $components;
+                components(self).iter().zip(components(other).iter()).all(|(a, b)| a.ulps_eq(b, epsilon, max_ulps))
+            }
+        }
+    };
+}
+
+impl_approx!(Vec3, |v: &Vec3| [v.x, v.y, v.z]);
+impl_approx!(Quat, |q: &Quat| [q.x, q.y, q.z, q.w]);
+impl_approx!(Matrix4, |m: &Matrix4| m.m);
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use approx::{assert_relative_eq, assert_ulps_ne};
+
+    use super::*;
+
+    #[test]
+    fn test_approx_traits() {
+        let a = Vec3::new(1.0, 2.0, 3.0);
+        assert_relative_eq!(a, Vec3::new(1.0, 2.0, 3.000001));
+        assert_ulps_ne!(a, Vec3::new(1.0, 2.0, 3.1));
+        let q = Quat::from_axis_angle(Vec3::UNIT_Y, 0.5);
+        assert_relative_eq!(q.to_matrix().multiply(&q.conjugate().to_matrix()), Matrix4::identity());
+        assert_relative_eq!(q * q.conjugate(), Quat::IDENTITY);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{aabb::Aabb, matrix_4_by_4::Matrix4, plane::{Plane, Side}, vec3::Vec3};
+
+// Volumen visible de una cámara: 6 planos con la normal hacia adentro
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    /// Un punto p está adentro si está adelante de los 6 (distancia >= 0)
+    pub planes: [Plane; 6],
+}
+
+impl Frustum {
+    /// Planos de una matriz projection * view (Gribb-Hartmann), en espacio mundo
+    pub fn from_matrix(view_projection: &Matrix4) -> Self {
+        let m = &view_projection.m;
+        let row = |r: usize| [m[r], m[r + 4], m[r + 8], m[r + 12]];
+        let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+        let plane = |sign: f32, other: [f32; 4]| {
+            let p = [0, 1, 2, 3].map(|i| w[i] + sign * other[i]);
+            Plane::new(Vec3::new(p[0], p[1], p[2]), p[3])
+        };
+        Self {
+            planes: [plane(1.0, x), plane(-1.0, x), plane(1.0, y), plane(-1.0, y), plane(1.0, z), plane(-1.0, z)],
+        }
+    }
+
+    /// Falso solo si la caja queda entera detrás de algún plano (puede dar
+    /// verdadero para cajas cerca de las esquinas que en realidad no se ven)
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        !aabb.is_empty() && self.planes.iter().all(|plane| plane.classify_aabb(aabb) != Side::Back)
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frustum_culls_boxes_outside() {
+        // Cámara en el origen mirando hacia -Z
+        let projection = Matrix4::perspective(60.0_f32.to_radians(), 1.0, 0.1, 100.0);
+        let frustum = Frustum::from_matrix(&projection);
+        let unit = |c: Vec3| Aabb::new(c - Vec3::new(0.5, 0.5, 0.5), c + Vec3::new(0.5, 0.5, 0.5));
+
+        assert!(frustum.intersects_aabb(&unit(Vec3::new(0.0, 0.0, -10.0))));
+        assert!(!frustum.intersects_aabb(&unit(Vec3::new(0.0, 0.0, 10.0))));
+        assert!(!frustum.intersects_aabb(&unit(Vec3::new(30.0, 0.0, -10.0))));
+        assert!(!frustum.intersects_aabb(&unit(Vec3::new(0.0, 0.0, -200.0))));
+        assert!(!frustum.intersects_aabb(&Aabb::EMPTY));
+    }
+}
@@ -0,0 +1,30 @@
+// math/src/lib.rs
+//
+// Matemática del motor (vectores, matrices, cuaterniones, transforms, cajas,
+// rayos y frustums) sin dependencias de OpenGL ni de la ventana: se puede usar en
+// pruebas, procesamiento de mallas en servidor o workers wasm. El motor la
+// re-exporta como `rust_engine::math`.
+// La feature `simd` usa SSE/NEON en los productos de Matrix4 y la feature
+// `approx` implementa los traits del crate approx para Vec3, Matrix4 y Quat.
+
+pub mod vec3;
+pub mod matrix_4_by_4;
+pub mod weld;
+pub mod quaternion;
+pub mod transform;
+pub mod aabb;
+pub mod ray;
+pub mod plane;
+pub mod frustum;
+mod simd;
+#[cfg(feature = "approx")]
+mod approx_traits;
+
+/// Tolerancia por defecto para comparar resultados en punto flotante
+pub const EPSILON: f32 = 1e-5;
+
+/// Igualdad aproximada: absoluta cerca de 0 y relativa a la magnitud para
+/// valores grandes (con `eps` = 1e-5, 1000.0 y 1000.009 son iguales)
+pub fn approx_eq(a: f32, b: f32, eps: f32) -> bool {
+    a == b || (a - b).abs() <= eps * a.abs().max(b.abs()).max(1.0)
+}
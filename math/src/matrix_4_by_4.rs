@@ -0,0 +1,335 @@
+use crate::quaternion::Quat;
+use crate::simd;
+use crate::vec3::Vec3;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Matrix4 {
+    pub m: [f32; 16], // almacenamos en columna mayor (OpenGL style)
+}
+
+impl Matrix4 {
+    pub fn identity() -> Self {
+        Self {
+            m: [
+                1.0, 0.0, 0.0, 0.0, // Columna 0
+                0.0, 1.0, 0.0, 0.0, // Columna 1
+                0.0, 0.0, 1.0, 0.0, // Columna 2
+                0.0, 0.0, 0.0, 1.0, // Columna 3
+            ],
+        }
+    }
+
+    pub fn multiply(&self, other: &Matrix4) ->Matrix4 {
+        Matrix4 { m: simd::multiply(&self.m, &other.m) }
+    }
+
+    pub fn translate(tx: f32, ty: f32, tz: f32) ->Matrix4 {
+        let mut matrix =Matrix4::identity();
+        matrix.m[12] = tx;
+        matrix.m[13] = ty;
+        matrix.m[14] = tz;
+        matrix
+    }
+
+    pub fn rotate_y(angle_radians: f32) ->Matrix4 {
+        let mut matrix =Matrix4::identity();
+        let c = angle_radians.cos();
+        let s = angle_radians.sin();
+        matrix.m[0] = c;
+        matrix.m[2] = s;
+        matrix.m[8] = -s;
+        matrix.m[10] = c;
+        matrix
+    }
+
+    pub fn rotate_x(angle: f32) ->Matrix4 {
+        let mut matrix =Matrix4::identity();
+        let c = angle.cos();
+        let s = angle.sin();
+        matrix.m[5] = c;
+        matrix.m[6] = -s;
+        matrix.m[9] = s;
+        matrix.m[10] = c;
+        matrix
+    }
+
+    /// Giro antihorario visto desde +Z, el mismo que
+    /// `Quat::from_axis_angle(Vec3::UNIT_Z, angle)`
+    pub fn rotate_z(angle: f32) -> Matrix4 {
+        let mut matrix = Matrix4::identity();
+        let (s, c) = angle.sin_cos();
+        matrix.m[0] = c;
+        matrix.m[1] = s;
+        matrix.m[4] = -s;
+        matrix.m[5] = c;
+        matrix
+    }
+
+    pub fn perspective(fov_radians: f32, aspect: f32, near: f32, far: f32) ->Matrix4 {
+        let f = 1.0 / (fov_radians / 2.0).tan();
+        let mut matrix =Matrix4 { m: [0.0; 16] };
+        matrix.m[0] = f / aspect;
+        matrix.m[5] = f;
+        matrix.m[10] = (far + near) / (near - far);
+        matrix.m[11] = -1.0;
+        matrix.m[14] = (2.0 * far * near) / (near - far);
+        matrix
+    }
+
+    /// Proyección perspectiva asimétrica (como glFrustum).
+    /// left/right/bottom/top se miden sobre el plano near.
+    pub fn frustum(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) ->Matrix4 {
+        let mut matrix =Matrix4 { m: [0.0; 16] };
+        matrix.m[0] = 2.0 * near / (right - left);
+        matrix.m[5] = 2.0 * near / (top - bottom);
+        matrix.m[8] = (right + left) / (right - left);
+        matrix.m[9] = (top + bottom) / (top - bottom);
+        matrix.m[10] = (far + near) / (near - far);
+        matrix.m[11] = -1.0;
+        matrix.m[14] = (2.0 * far * near) / (near - far);
+        matrix
+    }
+
+    /// Proyección ortográfica (como glOrtho)
+    pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) ->Matrix4 {
+        let mut matrix =Matrix4::identity();
+        matrix.m[0] = 2.0 / (right - left);
+        matrix.m[5] = 2.0 / (top - bottom);
+        matrix.m[10] = -2.0 / (far - near);
+        matrix.m[12] = -(right + left) / (right - left);
+        matrix.m[13] = -(top + bottom) / (top - bottom);
+        matrix.m[14] = -(far + near) / (far - near);
+        matrix
+    }
+
+    /// Cámara "LookAt" con `Vec3`
+    /// eye    = posición de la cámara
+    /// center = a dónde mira
+    /// up     = vector "arriba"
+    /// Si eye == center mira hacia -Z; si `up` es paralelo a la dirección de
+    /// vista usa otro eje como referencia.
+    pub fn look_at(eye: Vec3, center: Vec3, up: Vec3) ->Matrix4 {
+        // forward: dirección de la cámara
+        let f = (center - eye).normalize_or(Vec3::new(0.0, 0.0, -1.0));
+        // right
+        let helper = if f.y.abs() < 0.9 { Vec3::UNIT_Y } else { Vec3::UNIT_Z };
+        let s = f.cross(&up).try_normalize().unwrap_or_else(|| f.cross(&helper).normalize());
+        // verdadero up
+        let u = s.cross(&f);
+
+        let mut matrix =Matrix4::identity();
+        matrix.m[0] = s.x;
+        matrix.m[4] = s.y;
+        matrix.m[8] = s.z;
+
+        matrix.m[1] = u.x;
+        matrix.m[5] = u.y;
+        matrix.m[9] = u.z;
+
+        // En OpenGL se asume Z "hacia adentro", por eso -f
+        matrix.m[2] = -f.x;
+        matrix.m[6] = -f.y;
+        matrix.m[10] = -f.z;
+
+        // Trasladar la escena al opuesto de eye
+        matrix.multiply(&Matrix4::translate(-eye.x, -eye.y, -eye.z))
+    }
+
+    /// Multiplica la matriz por (x, y, z, w)
+    pub fn transform_vec4(&self, v: [f32; 4]) -> [f32; 4] {
+        simd::transform_vec4(&self.m, v)
+    }
+
+    /// Transforma un punto (w = 1)
+    pub fn transform_point(&self, p: Vec3) -> Vec3 {
+        let [x, y, z, _] = self.transform_vec4([p.x, p.y, p.z, 1.0]);
+        Vec3::new(x, y, z)
+    }
+
+    /// Traslación * rotación * escala (el orden de los nodos de glTF)
+    pub fn from_trs(translation: Vec3, rotation: Quat, scale: Vec3) -> Matrix4 {
+        let mut matrix = rotation.to_matrix();
+        for (col, factor) in [scale.x, scale.y, scale.z].into_iter().enumerate() {
+            for row in 0..3 {
+                matrix.m[row + col * 4] *= factor;
+            }
+        }
+        matrix.m[12] = translation.x;
+        matrix.m[13] = translation.y;
+        matrix.m[14] = translation.z;
+        matrix
+    }
+
+    /// Separa una matriz afín en (traslación, rotación, escala), el inverso de
+    /// `from_trs`. Si la matriz refleja (determinante negativo) la escala en X
+    /// queda negativa. Con cizalla el resultado es solo una aproximación.
+    pub fn decompose(&self) -> (Vec3, Quat, Vec3) {
+        let translation = Vec3::new(self.m[12], self.m[13], self.m[14]);
+        let column = |c: usize| Vec3::new(self.m[c * 4], self.m[c * 4 + 1], self.m[c * 4 + 2]);
+        let (x, y, z) = (column(0), column(1), column(2));
+        let mut scale = Vec3::new(x.magnitude(), y.magnitude(), z.magnitude());
+        if x.cross(&y).dot(&z) < 0.0 {
+            scale.x = -scale.x;
+        }
+
+        let mut rotation = Matrix4::identity();
+        let axes = [
+            (x / scale.x).normalize_or(Vec3::UNIT_X),
+            (y / scale.y).normalize_or(Vec3::UNIT_Y),
+            (z / scale.z).normalize_or(Vec3::UNIT_Z),
+        ];
+        for (col, axis) in axes.into_iter().enumerate() {
+            rotation.m[col * 4] = axis.x;
+            rotation.m[col * 4 + 1] = axis.y;
+            rotation.m[col * 4 + 2] = axis.z;
+        }
+        (translation, Quat::from_matrix(&rotation), scale)
+    }
+
+    /// Igualdad aproximada elemento a elemento (ver `crate::approx_eq`)
+    pub fn approx_eq(&self, other: &Matrix4, eps: f32) -> bool {
+        self.m.iter().zip(&other.m).all(|(&a, &b)| crate::approx_eq(a, b, eps))
+    }
+
+    pub fn as_ptr(&self) -> *const f32 {
+        self.m.as_ptr()
+    }
+    
+    pub fn scale(s: f32) ->Matrix4 {
+        Matrix4::scale_xyz(s, s, s)
+    }
+
+    /// Escala distinta en cada eje
+    pub fn scale_xyz(sx: f32, sy: f32, sz: f32) -> Matrix4 {
+        let mut matrix = Matrix4::identity();
+        matrix.m[0] = sx;
+        matrix.m[5] = sy;
+        matrix.m[10] = sz;
+        matrix
+    }
+
+    pub fn transpose(&self) -> Matrix4 {
+        let mut matrix = Matrix4 { m: [0.0; 16] };
+        for col in 0..4 {
+            for row in 0..4 {
+                matrix.m[row * 4 + col] = self.m[col * 4 + row];
+            }
+        }
+        matrix
+    }
+
+    /// Determinantes 2x2 de las dos primeras filas (s) y de las dos últimas
+    /// (c), con los que salen el determinante y la adjunta
+    fn sub_factors(&self) -> ([f32; 6], [f32; 6]) {
+        let a = |row: usize, col: usize| self.m[col * 4 + row];
+        let pair = |r0: usize, r1: usize, c0: usize, c1: usize| a(r0, c0) * a(r1, c1) - a(r1, c0) * a(r0, c1);
+        let s = [pair(0, 1, 0, 1), pair(0, 1, 0, 2), pair(0, 1, 0, 3), pair(0, 1, 1, 2), pair(0, 1, 1, 3), pair(0, 1, 2, 3)];
+        let c = [pair(2, 3, 0, 1), pair(2, 3, 0, 2), pair(2, 3, 0, 3), pair(2, 3, 1, 2), pair(2, 3, 1, 3), pair(2, 3, 2, 3)];
+        (s, c)
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let (s, c) = self.sub_factors();
+        Self::expand(&s, &c)
+    }
+
+    /// Determinante por Laplace con los factores de `sub_factors`
+    fn expand(s: &[f32; 6], c: &[f32; 6]) -> f32 {
+        s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+    }
+
+    /// Inversa general (también de proyecciones); None si es singular
+    pub fn inverse(&self) -> Option<Matrix4> {
+        let (s, c) = self.sub_factors();
+        let det = Self::expand(&s, &c);
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let a = |row: usize, col: usize| self.m[col * 4 + row];
+        // Adjunta por filas: rows[i][j] es la fila i, columna j de la inversa
+        let rows = [
+            [
+                a(1, 1) * c[5] - a(1, 2) * c[4] + a(1, 3) * c[3],
+                -a(0, 1) * c[5] + a(0, 2) * c[4] - a(0, 3) * c[3],
+                a(3, 1) * s[5] - a(3, 2) * s[4] + a(3, 3) * s[3],
+                -a(2, 1) * s[5] + a(2, 2) * s[4] - a(2, 3) * s[3],
+            ],
+            [
+                -a(1, 0) * c[5] + a(1, 2) * c[2] - a(1, 3) * c[1],
+                a(0, 0) * c[5] - a(0, 2) * c[2] + a(0, 3) * c[1],
+                -a(3, 0) * s[5] + a(3, 2) * s[2] - a(3, 3) * s[1],
+                a(2, 0) * s[5] - a(2, 2) * s[2] + a(2, 3) * s[1],
+            ],
+            [
+                a(1, 0) * c[4] - a(1, 1) * c[2] + a(1, 3) * c[0],
+                -a(0, 0) * c[4] + a(0, 1) * c[2] - a(0, 3) * c[0],
+                a(3, 0) * s[4] - a(3, 1) * s[2] + a(3, 3) * s[0],
+                -a(2, 0) * s[4] + a(2, 1) * s[2] - a(2, 3) * s[0],
+            ],
+            [
+                -a(1, 0) * c[3] + a(1, 1) * c[1] - a(1, 2) * c[0],
+                a(0, 0) * c[3] - a(0, 1) * c[1] + a(0, 2) * c[0],
+                -a(3, 0) * s[3] + a(3, 1) * s[1] - a(3, 2) * s[0],
+                a(2, 0) * s[3] - a(2, 1) * s[1] + a(2, 2) * s[0],
+            ],
+        ];
+        let mut matrix = Matrix4 { m: [0.0; 16] };
+        for (row, values) in rows.iter().enumerate() {
+            for (col, value) in values.iter().enumerate() {
+                matrix.m[col * 4 + row] = value / det;
+            }
+        }
+        Some(matrix)
+    }
+
+    /// Inversa traspuesta de la parte 3x3 (sin traslación), para llevar
+    /// normales con escala distinta en cada eje. Si la parte 3x3 es singular
+    /// (escala cero) devuelve la identidad.
+    pub fn normal_matrix(&self) -> Matrix4 {
+        let mut linear = Matrix4::identity();
+        for col in 0..3 {
+            linear.m[col * 4..col * 4 + 3].copy_from_slice(&self.m[col * 4..col * 4 + 3]);
+        }
+        linear.inverse().map_or_else(Matrix4::identity, |inverse| inverse.transpose())
+    }
+    
+    
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inverse_and_normal_matrix() {
+        let rotation = Matrix4::rotate_z(0.5);
+        assert!(rotation.approx_eq(&Quat::from_axis_angle(Vec3::UNIT_Z, 0.5).to_matrix(), 1e-6));
+
+        // Afín con escala distinta en cada eje y una proyección: M * M⁻¹ = I
+        let scale = Matrix4::scale_xyz(2.0, 4.0, 0.5);
+        let affine = Matrix4::translate(1.0, -2.0, 3.0).multiply(&rotation).multiply(&scale);
+        let projection = Matrix4::perspective(1.0, 1.5, 0.1, 100.0);
+        for matrix in [affine, projection, projection.multiply(&affine)] {
+            let inverse = matrix.inverse().unwrap();
+            assert!(matrix.multiply(&inverse).approx_eq(&Matrix4::identity(), 1e-5));
+            assert!(inverse.multiply(&matrix).approx_eq(&Matrix4::identity(), 1e-5));
+        }
+        assert!((affine.determinant() - 4.0).abs() < 1e-5);
+        assert!((Matrix4::rotate_x(0.3).determinant() - 1.0).abs() < 1e-6);
+        assert_eq!(Matrix4::scale_xyz(1.0, 0.0, 1.0).inverse(), None);
+        assert_eq!(affine.transpose().transpose(), affine);
+        assert_eq!(affine.transpose().m[3], affine.m[12]);
+
+        // Plano x + y = 0 aplastado en Y: la normal transformada sigue
+        // perpendicular al plano transformado (con la matriz de modelo no)
+        let squash = Matrix4::scale_xyz(1.0, 0.25, 1.0);
+        let along = squash.transform_point(Vec3::new(1.0, -1.0, 0.0));
+        let normal = squash.normal_matrix().transform_point(Vec3::new(1.0, 1.0, 0.0));
+        assert!(along.dot(&normal).abs() < 1e-6);
+        assert!(along.dot(&squash.transform_point(Vec3::new(1.0, 1.0, 0.0))).abs() > 0.5);
+        // La traslación no cuenta y una escala cero no rompe nada
+        assert_eq!(affine.normal_matrix().m[12..15], [0.0; 3]);
+        assert_eq!(Matrix4::scale(0.0).normal_matrix(), Matrix4::identity());
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{aabb::Aabb, vec3::Vec3};
+
+// Plano normal · p + d = 0. El lado positivo (normal · p + d > 0) es el de adelante.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    /// Unitaria si el plano se armó con los constructores
+    pub normal: Vec3,
+    pub d: f32,
+}
+
+/// De qué lado del plano queda un volumen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Front,
+    Back,
+    /// Cortado por el plano
+    Intersecting,
+}
+
+impl Plane {
+    /// Normaliza la ecuación (si la normal es nula el plano queda degenerado)
+    pub fn new(normal: Vec3, d: f32) -> Self {
+        let length = normal.magnitude();
+        if length > 0.0 {
+            Self { normal: normal / length, d: d / length }
+        } else {
+            Self { normal, d }
+        }
+    }
+
+    pub fn from_point_normal(point: Vec3, normal: Vec3) -> Self {
+        let normal = normal.normalize();
+        Self { normal, d: -normal.dot(&point) }
+    }
+
+    /// Plano de un triángulo, con la normal según el orden (a, b, c) antihorario.
+    /// None si los puntos están alineados.
+    pub fn from_points(a: Vec3, b: Vec3, c: Vec3) -> Option<Self> {
+        let normal = (b - a).cross(&(c - a)).try_normalize()?;
+        Some(Self { normal, d: -normal.dot(&a) })
+    }
+
+    /// Distancia con signo (positiva adelante)
+    pub fn distance(&self, p: Vec3) -> f32 {
+        self.normal.dot(&p) + self.d
+    }
+
+    /// Punto del plano más cercano a `p`
+    pub fn project(&self, p: Vec3) -> Vec3 {
+        p - self.normal * self.distance(p)
+    }
+
+    /// Lado de la caja respecto del plano (la caja vacía queda detrás)
+    pub fn classify_aabb(&self, aabb: &Aabb) -> Side {
+        if aabb.is_empty() {
+            return Side::Back;
+        }
+        // Radio de la caja proyectado sobre la normal
+        let half = aabb.size() * 0.5;
+        let radius = half.x * self.normal.x.abs() + half.y * self.normal.y.abs() + half.z * self.normal.z.abs();
+        let distance = self.distance(aabb.center());
+        if distance > radius {
+            Side::Front
+        } else if distance < -radius {
+            Side::Back
+        } else {
+            Side::Intersecting
+        }
+    }
+
+    /// Dos ejes unitarios perpendiculares a la normal (y entre sí) para pasar
+    /// puntos del plano a 2D
+    pub fn basis(&self) -> (Vec3, Vec3) {
+        let n = self.normal;
+        let helper = if n.x.abs() < 0.9 { Vec3::UNIT_X } else { Vec3::UNIT_Y };
+        let u = (helper - n * helper.dot(&n)).normalize();
+        let v = n.cross(&u);
+        (u, v)
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plane_classify() {
+        let floor = Plane::from_point_normal(Vec3::new(0.0, 2.0, 0.0), Vec3::new(0.0, 3.0, 0.0));
+        assert_eq!(floor.distance(Vec3::new(5.0, 5.0, 5.0)), 3.0);
+        assert_eq!(floor.project(Vec3::new(1.0, -4.0, 1.0)), Vec3::new(1.0, 2.0, 1.0));
+        assert_eq!(Plane::new(Vec3::new(0.0, 2.0, 0.0), -4.0), floor);
+
+        let unit = |y: f32| Aabb::new(Vec3::new(0.0, y, 0.0), Vec3::new(1.0, y + 1.0, 1.0));
+        assert_eq!(floor.classify_aabb(&unit(3.0)), Side::Front);
+        assert_eq!(floor.classify_aabb(&unit(-3.0)), Side::Back);
+        assert_eq!(floor.classify_aabb(&unit(1.5)), Side::Intersecting);
+
+        let tri = Plane::from_points(Vec3::ZERO, Vec3::UNIT_X, Vec3::UNIT_Y).unwrap();
+        assert_eq!(tri.normal, Vec3::UNIT_Z);
+        assert!(Plane::from_points(Vec3::ZERO, Vec3::UNIT_X, Vec3::UNIT_X * 2.0).is_none());
+    }
+}
@@ -0,0 +1,244 @@
+use std::ops::Mul;
+
+use crate::{matrix_4_by_4::Matrix4, vec3::Vec3};
+
+// Cuaternión unitario para representar rotaciones (x, y, z = parte vectorial, w = escalar)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quat {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Quat {
+    pub const IDENTITY: Self = Self { x: 0.0, y: 0.0, z: 0.0, w: 1.0 };
+
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+
+    /// Rotación de `angle` radianes alrededor de `axis` (no hace falta que venga
+    /// normalizado). Con un eje nulo devuelve la identidad.
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Self {
+        let Some(axis) = axis.try_normalize() else {
+            return Self::IDENTITY;
+        };
+        let (s, c) = (angle * 0.5).sin_cos();
+        Self::new(axis.x * s, axis.y * s, axis.z * s, c)
+    }
+
+    /// Rotación más corta que lleva la dirección `from` a `to`. Si son opuestas
+    /// gira media vuelta alrededor de un eje perpendicular cualquiera.
+    pub fn from_rotation_arc(from: Vec3, to: Vec3) -> Self {
+        let (Some(from), Some(to)) = (from.try_normalize(), to.try_normalize()) else {
+            return Self::IDENTITY;
+        };
+        let cos = from.dot(&to);
+        if cos < -0.999_999 {
+            let other = if from.x.abs() < 0.9 { Vec3::UNIT_X } else { Vec3::UNIT_Y };
+            return Self::from_axis_angle(from.cross(&other), std::f32::consts::PI);
+        }
+        let axis = from.cross(&to);
+        Self::new(axis.x, axis.y, axis.z, 1.0 + cos).normalize()
+    }
+
+    pub fn length(&self) -> f32 {
+        (self.x * self.x + self.y * self.y + self.z * self.z + self.w * self.w).sqrt()
+    }
+
+    pub fn normalize(&self) -> Self {
+        let len = self.length();
+        if len == 0.0 {
+            return Self::IDENTITY;
+        }
+        Self::new(self.x / len, self.y / len, self.z / len, self.w / len)
+    }
+
+    /// Igualdad aproximada componente a componente. `q` y `-q` son la misma
+    /// rotación pero no son aproximadamente iguales: para comparar rotaciones
+    /// usar `same_rotation`.
+    pub fn approx_eq(&self, other: &Self, eps: f32) -> bool {
+        crate::approx_eq(self.x, other.x, eps)
+            && crate::approx_eq(self.y, other.y, eps)
+            && crate::approx_eq(self.z, other.z, eps)
+            && crate::approx_eq(self.w, other.w, eps)
+    }
+
+    /// Si las dos representan la misma rotación (acepta `q` y `-q`)
+    pub fn same_rotation(&self, other: &Self, eps: f32) -> bool {
+        self.approx_eq(other, eps) || self.approx_eq(&Self::new(-other.x, -other.y, -other.z, -other.w), eps)
+    }
+
+    /// Para un cuaternión unitario el conjugado es la rotación inversa
+    pub fn conjugate(&self) -> Self {
+        Self::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    /// Rota un vector: q * v * q^-1
+    pub fn rotate(&self, v: Vec3) -> Vec3 {
+        let u = Vec3::new(self.x, self.y, self.z);
+        // Fórmula optimizada: v' = v + 2w(u x v) + 2u x (u x v)
+        let uv = u.cross(&v);
+        let uuv = u.cross(&uv);
+        v + uv * (2.0 * self.w) + uuv * 2.0
+    }
+
+    /// Rotación de la parte 3x3 de una matriz sin escala (Shepperd: toma la
+    /// diagonal más grande para no dividir por valores chicos)
+    pub fn from_matrix(matrix: &Matrix4) -> Self {
+        let r = |row: usize, col: usize| matrix.m[row + col * 4];
+        let trace = r(0, 0) + r(1, 1) + r(2, 2);
+        let q = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Self::new((r(2, 1) - r(1, 2)) / s, (r(0, 2) - r(2, 0)) / s, (r(1, 0) - r(0, 1)) / s, 0.25 * s)
+        } else if r(0, 0) > r(1, 1) && r(0, 0) > r(2, 2) {
+            let s = (1.0 + r(0, 0) - r(1, 1) - r(2, 2)).sqrt() * 2.0;
+            Self::new(0.25 * s, (r(0, 1) + r(1, 0)) / s, (r(0, 2) + r(2, 0)) / s, (r(2, 1) - r(1, 2)) / s)
+        } else if r(1, 1) > r(2, 2) {
+            let s = (1.0 + r(1, 1) - r(0, 0) - r(2, 2)).sqrt() * 2.0;
+            Self::new((r(0, 1) + r(1, 0)) / s, 0.25 * s, (r(1, 2) + r(2, 1)) / s, (r(0, 2) - r(2, 0)) / s)
+        } else {
+            let s = (1.0 + r(2, 2) - r(0, 0) - r(1, 1)).sqrt() * 2.0;
+            Self::new((r(0, 2) + r(2, 0)) / s, (r(1, 2) + r(2, 1)) / s, 0.25 * s, (r(1, 0) - r(0, 1)) / s)
+        };
+        q.normalize()
+    }
+
+    /// Ángulos de Euler en radianes (x, y, z): primero se rota en X, después
+    /// en Y y por último en Z, sobre los ejes fijos del mundo
+    pub fn from_euler(angles: Vec3) -> Self {
+        Self::from_axis_angle(Vec3::UNIT_Z, angles.z)
+            * Self::from_axis_angle(Vec3::UNIT_Y, angles.y)
+            * Self::from_axis_angle(Vec3::UNIT_X, angles.x)
+    }
+
+    /// Inverso de `from_euler`. Y queda en [-90°, 90°]; en el bloqueo de
+    /// cardán (Y = ±90°) X y Z giran sobre el mismo eje y se devuelve Z = 0.
+    pub fn to_euler(&self) -> Vec3 {
+        let m = self.to_matrix();
+        let r = |row: usize, col: usize| m.m[row + col * 4];
+        let sin_y = (-r(2, 0)).clamp(-1.0, 1.0);
+        if sin_y.abs() > 0.9999 {
+            let x = (r(0, 1) * sin_y).atan2(r(0, 2) * sin_y);
+            return Vec3::new(x, std::f32::consts::FRAC_PI_2.copysign(sin_y), 0.0);
+        }
+        Vec3::new(r(2, 1).atan2(r(2, 2)), sin_y.asin(), r(1, 0).atan2(r(0, 0)))
+    }
+
+    /// Matriz de rotación equivalente (columna mayor)
+    pub fn to_matrix(&self) -> Matrix4 {
+        let (x, y, z, w) = (self.x, self.y, self.z, self.w);
+        let (xx, yy, zz) = (x * x, y * y, z * z);
+        let (xy, xz, yz) = (x * y, x * z, y * z);
+        let (wx, wy, wz) = (w * x, w * y, w * z);
+
+        let mut matrix = Matrix4::identity();
+        matrix.m[0] = 1.0 - 2.0 * (yy + zz);
+        matrix.m[1] = 2.0 * (xy + wz);
+        matrix.m[2] = 2.0 * (xz - wy);
+
+        matrix.m[4] = 2.0 * (xy - wz);
+        matrix.m[5] = 1.0 - 2.0 * (xx + zz);
+        matrix.m[6] = 2.0 * (yz + wx);
+
+        matrix.m[8] = 2.0 * (xz + wy);
+        matrix.m[9] = 2.0 * (yz - wx);
+        matrix.m[10] = 1.0 - 2.0 * (xx + yy);
+        matrix
+    }
+}
+
+impl Default for Quat {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+// Composición de rotaciones: (a * b) aplica primero b y luego a
+impl Mul for Quat {
+    type Output = Self;
+
+    fn mul(self, o: Self) -> Self::Output {
+        Self::new(
+            self.w * o.x + self.x * o.w + self.y * o.z - self.z * o.y,
+            self.w * o.y - self.x * o.z + self.y * o.w + self.z * o.x,
+            self.w * o.z + self.x * o.y - self.y * o.x + self.z * o.w,
+            self.w * o.w - self.x * o.x - self.y * o.y - self.z * o.z,
+        )
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Vec3, b: Vec3) -> bool {
+        (a - b).magnitude() < 1e-5
+    }
+
+    #[test]
+    fn test_rotate_y() {
+        let q = Quat::from_axis_angle(Vec3::UNIT_Y, std::f32::consts::FRAC_PI_2);
+        let v = q.rotate(Vec3::new(0.0, 0.0, -1.0));
+        assert!(close(v, Vec3::new(-1.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_matrix_matches_rotate() {
+        let q = Quat::from_axis_angle(Vec3::new(1.0, 1.0, 0.0), 0.7);
+        let m = q.to_matrix().m;
+        let v = Vec3::new(0.5, -2.0, 3.0);
+        let mv = Vec3::new(
+            m[0] * v.x + m[4] * v.y + m[8] * v.z,
+            m[1] * v.x + m[5] * v.y + m[9] * v.z,
+            m[2] * v.x + m[6] * v.y + m[10] * v.z,
+        );
+        assert!(close(mv, q.rotate(v)));
+    }
+
+    #[test]
+    fn test_rotation_arc() {
+        let (from, to) = (Vec3::new(0.0, 0.0, 2.0), Vec3::new(1.0, 1.0, 0.0));
+        assert!(close(Quat::from_rotation_arc(from, to).rotate(Vec3::UNIT_Z), to.normalize()));
+        assert!(close(Quat::from_rotation_arc(Vec3::UNIT_X, Vec3::UNIT_X * -1.0).rotate(Vec3::UNIT_X), Vec3::UNIT_X * -1.0));
+    }
+
+    #[test]
+    fn test_conjugate_inverts() {
+        let q = Quat::from_axis_angle(Vec3::new(1.0, 2.0, 3.0), 1.2);
+        let v = Vec3::new(0.3, -4.0, 2.0);
+        assert!(close((q.conjugate() * q).rotate(v), v));
+    }
+
+    #[test]
+    fn test_euler_round_trip() {
+        let angles = Vec3::new(0.3, -0.7, 1.2);
+        let q = Quat::from_euler(angles);
+        assert!(q.to_euler().approx_eq(&angles, 1e-5));
+        assert!(Quat::from_matrix(&q.to_matrix()).same_rotation(&q, 1e-5));
+
+        // Bloqueo de cardán: otra combinación de X y Z, misma rotación
+        let locked = Quat::from_euler(Vec3::new(0.4, std::f32::consts::FRAC_PI_2, 0.1));
+        let euler = locked.to_euler();
+        assert_eq!(euler.z, 0.0);
+        assert!(Quat::from_euler(euler).same_rotation(&locked, 1e-4));
+    }
+
+    #[test]
+    fn test_decompose() {
+        let (t, r, s) = (Vec3::new(1.0, -2.0, 3.0), Quat::from_euler(Vec3::new(0.5, 0.2, -1.0)), Vec3::new(2.0, 0.5, 3.0));
+        let matrix = Matrix4::from_trs(t, r, s);
+        let (t2, r2, s2) = matrix.decompose();
+        assert!(t2.approx_eq(&t, 1e-5));
+        assert!(r2.same_rotation(&r, 1e-5));
+        assert!(s2.approx_eq(&s, 1e-5));
+
+        // Espejo: la escala negativa queda en X
+        let mirrored = Matrix4::from_trs(t, r, Vec3::new(-1.0, 1.0, 1.0));
+        let (_, r3, s3) = mirrored.decompose();
+        assert!(s3.approx_eq(&Vec3::new(-1.0, 1.0, 1.0), 1e-5));
+        assert!(r3.same_rotation(&r, 1e-5));
+    }
+}
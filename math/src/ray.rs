@@ -0,0 +1,174 @@
+use crate::{aabb::{axis, Aabb}, plane::Plane, vec3::Vec3};
+
+// Rayo paramétrico: origin + t * direction (direction no tiene por qué ser unitaria)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3,
+    pub direction: Vec3,
+}
+
+/// Corte de un rayo con un triángulo: punto = a * (1 - u - v) + b * u + c * v
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TriangleHit {
+    pub t: f32,
+    pub u: f32,
+    pub v: f32,
+}
+
+impl TriangleHit {
+    /// Pesos de (a, b, c) para interpolar atributos de los vértices
+    pub fn barycentrics(&self) -> [f32; 3] {
+        [1.0 - self.u - self.v, self.u, self.v]
+    }
+}
+
+impl Ray {
+    pub fn new(origin: Vec3, direction: Vec3) -> Self {
+        Self { origin, direction }
+    }
+
+    pub fn at(&self, t: f32) -> Vec3 {
+        self.origin + self.direction * t
+    }
+
+    /// Intersección con un triángulo (Möller–Trumbore, ambas caras).
+    /// Devuelve t >= 0 del punto de corte.
+    pub fn intersect_triangle(&self, a: Vec3, b: Vec3, c: Vec3) -> Option<f32> {
+        self.intersect_triangle_barycentric(a, b, c).map(|hit| hit.t)
+    }
+
+    /// Como `intersect_triangle`, con las coordenadas baricéntricas del corte
+    pub fn intersect_triangle_barycentric(&self, a: Vec3, b: Vec3, c: Vec3) -> Option<TriangleHit> {
+        let edge1 = b - a;
+        let edge2 = c - a;
+        let p = self.direction.cross(&edge2);
+        let det = edge1.dot(&p);
+        if det.abs() < 1e-12 {
+            return None; // paralelo o triángulo degenerado
+        }
+        let inv_det = 1.0 / det;
+        let s = self.origin - a;
+        let u = s.dot(&p) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = s.cross(&edge1);
+        let v = self.direction.dot(&q) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let t = edge2.dot(&q) * inv_det;
+        (t >= 0.0).then_some(TriangleHit { t, u, v })
+    }
+
+    /// Intersección con un plano (ambas caras). None si el rayo es paralelo o
+    /// el plano queda detrás.
+    pub fn intersect_plane(&self, plane: &Plane) -> Option<f32> {
+        let denom = plane.normal.dot(&self.direction);
+        if denom.abs() < 1e-12 {
+            return None;
+        }
+        let t = -plane.distance(self.origin) / denom;
+        (t >= 0.0).then_some(t)
+    }
+
+    /// Intersección con una esfera. Devuelve el t de entrada (0 si el origen
+    /// está dentro).
+    pub fn intersect_sphere(&self, center: Vec3, radius: f32) -> Option<f32> {
+        let a = self.direction.dot(&self.direction);
+        if a == 0.0 {
+            return None;
+        }
+        let oc = self.origin - center;
+        let half_b = oc.dot(&self.direction);
+        let c = oc.dot(&oc) - radius * radius;
+        if c <= 0.0 {
+            return Some(0.0);
+        }
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let t = (-half_b - discriminant.sqrt()) / a;
+        (t >= 0.0).then_some(t)
+    }
+
+    /// Intersección con una caja (slabs). Devuelve el t de entrada (0 si el
+    /// origen está dentro).
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        let mut t_min: f32 = 0.0;
+        let mut t_max = f32::INFINITY;
+        for i in 0..3 {
+            let origin = axis(self.origin, i);
+            let direction = axis(self.direction, i);
+            let (lo, hi) = (axis(aabb.min, i), axis(aabb.max, i));
+            if direction.abs() < 1e-12 {
+                if origin < lo || origin > hi {
+                    return None;
+                }
+                continue;
+            }
+            let inv = 1.0 / direction;
+            let (t0, t1) = ((lo - origin) * inv, (hi - origin) * inv);
+            t_min = t_min.max(t0.min(t1));
+            t_max = t_max.min(t0.max(t1));
+            if t_min > t_max {
+                return None;
+            }
+        }
+        Some(t_min)
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ray_triangle_hit() {
+        let ray = Ray::new(Vec3::new(0.2, 5.0, 0.2), Vec3::new(0.0, -1.0, 0.0));
+        let t = ray.intersect_triangle(
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(0.0, 1.0, 1.0),
+        );
+        assert_eq!(t, Some(4.0));
+    }
+
+    #[test]
+    fn test_ray_triangle_miss() {
+        let ray = Ray::new(Vec3::new(2.0, 5.0, 2.0), Vec3::new(0.0, -1.0, 0.0));
+        let t = ray.intersect_triangle(
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(0.0, 1.0, 1.0),
+        );
+        assert_eq!(t, None);
+    }
+
+    #[test]
+    fn test_ray_aabb() {
+        let aabb = Aabb::new(Vec3::new(-1.0, -1.0, -1.0), Vec3::new(1.0, 1.0, 1.0));
+        let ray = Ray::new(Vec3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        assert_eq!(ray.intersect_aabb(&aabb), Some(4.0));
+        let away = Ray::new(Vec3::new(-5.0, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0));
+        assert_eq!(away.intersect_aabb(&aabb), None);
+    }
+
+    #[test]
+    fn test_ray_plane_sphere_barycentric() {
+        let ray = Ray::new(Vec3::new(0.25, 0.5, 5.0), Vec3::new(0.0, 0.0, -2.0));
+        let hit = ray.intersect_triangle_barycentric(Vec3::ZERO, Vec3::UNIT_X, Vec3::UNIT_Y).unwrap();
+        assert_eq!(hit.t, 2.5);
+        assert_eq!(hit.barycentrics(), [0.25, 0.25, 0.5]);
+
+        let floor = Plane::from_point_normal(Vec3::new(0.0, 0.0, 1.0), Vec3::UNIT_Z);
+        assert_eq!(ray.intersect_plane(&floor), Some(2.0));
+        assert_eq!(Ray::new(Vec3::ZERO, Vec3::UNIT_X).intersect_plane(&floor), None);
+
+        assert_eq!(ray.intersect_sphere(Vec3::new(0.25, 0.5, 0.0), 1.0), Some(2.0));
+        assert_eq!(ray.intersect_sphere(Vec3::new(0.25, 0.5, 6.0), 2.0), Some(0.0));
+        assert_eq!(ray.intersect_sphere(Vec3::new(5.0, 0.0, 0.0), 1.0), None);
+    }
+}
@@ -0,0 +1,146 @@
+// Núcleos de Matrix4 (producto y matriz * vector), en columna mayor.
+// Con la feature `simd` se usan SSE en x86_64 y NEON en aarch64 (las dos
+// están siempre disponibles en esas arquitecturas, no hace falta detectar la
+// CPU en tiempo de ejecución); en cualquier otro caso, la versión escalar.
+// Cada columna del resultado es una combinación lineal de las columnas de la
+// izquierda, así que se calcula con 4 multiplicaciones-suma de 4 floats.
+
+/// Versión escalar: la que se usa sin la feature y la referencia de las pruebas
+#[cfg_attr(all(feature = "simd", any(target_arch = "x86_64", target_arch = "aarch64"), not(test)), allow(dead_code))]
+mod scalar {
+    pub fn multiply(a: &[f32; 16], b: &[f32; 16]) -> [f32; 16] {
+        let mut result = [0.0; 16];
+        for col in 0..4 {
+            for row in 0..4 {
+                let mut sum = 0.0;
+                for i in 0..4 {
+                    sum += a[row + i * 4] * b[i + col * 4];
+                }
+                result[row + col * 4] = sum;
+            }
+        }
+        result
+    }
+
+    pub fn transform_vec4(m: &[f32; 16], v: [f32; 4]) -> [f32; 4] {
+        let mut out = [0.0; 4];
+        for (row, value) in out.iter_mut().enumerate() {
+            *value = m[row] * v[0] + m[row + 4] * v[1] + m[row + 8] * v[2] + m[row + 12] * v[3];
+        }
+        out
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod sse {
+    use std::arch::x86_64::*;
+
+    /// m * (v0, v1, v2, v3) con las columnas de `m` ya cargadas
+    #[inline(always)]
+    unsafe fn combine(columns: &[__m128; 4], v: &[f32]) -> __m128 {
+        let mut sum = _mm_mul_ps(columns[0], _mm_set1_ps(v[0]));
+        sum = _mm_add_ps(sum, _mm_mul_ps(columns[1], _mm_set1_ps(v[1])));
+        sum = _mm_add_ps(sum, _mm_mul_ps(columns[2], _mm_set1_ps(v[2])));
+        _mm_add_ps(sum, _mm_mul_ps(columns[3], _mm_set1_ps(v[3])))
+    }
+
+    #[inline(always)]
+    unsafe fn columns(m: &[f32; 16]) -> [__m128; 4] {
+        [
+            _mm_loadu_ps(m.as_ptr()),
+            _mm_loadu_ps(m.as_ptr().add(4)),
+            _mm_loadu_ps(m.as_ptr().add(8)),
+            _mm_loadu_ps(m.as_ptr().add(12)),
+        ]
+    }
+
+    pub fn multiply(a: &[f32; 16], b: &[f32; 16]) -> [f32; 16] {
+        let mut result = [0.0; 16];
+        // SSE es parte de la base de x86_64
+        unsafe {
+            let columns = columns(a);
+            for col in 0..4 {
+                let value = combine(&columns, &b[col * 4..col * 4 + 4]);
+                _mm_storeu_ps(result.as_mut_ptr().add(col * 4), value);
+            }
+        }
+        result
+    }
+
+    pub fn transform_vec4(m: &[f32; 16], v: [f32; 4]) -> [f32; 4] {
+        let mut out = [0.0; 4];
+        unsafe {
+            _mm_storeu_ps(out.as_mut_ptr(), combine(&columns(m), &v));
+        }
+        out
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "aarch64"))]
+mod neon {
+    use std::arch::aarch64::*;
+
+    #[inline(always)]
+    unsafe fn combine(columns: &[float32x4_t; 4], v: &[f32]) -> float32x4_t {
+        let mut sum = vmulq_n_f32(columns[0], v[0]);
+        sum = vfmaq_n_f32(sum, columns[1], v[1]);
+        sum = vfmaq_n_f32(sum, columns[2], v[2]);
+        vfmaq_n_f32(sum, columns[3], v[3])
+    }
+
+    #[inline(always)]
+    unsafe fn columns(m: &[f32; 16]) -> [float32x4_t; 4] {
+        [
+            vld1q_f32(m.as_ptr()),
+            vld1q_f32(m.as_ptr().add(4)),
+            vld1q_f32(m.as_ptr().add(8)),
+            vld1q_f32(m.as_ptr().add(12)),
+        ]
+    }
+
+    pub fn multiply(a: &[f32; 16], b: &[f32; 16]) -> [f32; 16] {
+        let mut result = [0.0; 16];
+        // NEON es parte de la base de aarch64
+        unsafe {
+            let columns = columns(a);
+            for col in 0..4 {
+                let value = combine(&columns, &b[col * 4..col * 4 + 4]);
+                vst1q_f32(result.as_mut_ptr().add(col * 4), value);
+            }
+        }
+        result
+    }
+
+    pub fn transform_vec4(m: &[f32; 16], v: [f32; 4]) -> [f32; 4] {
+        let mut out = [0.0; 4];
+        unsafe {
+            vst1q_f32(out.as_mut_ptr(), combine(&columns(m), &v));
+        }
+        out
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+pub(crate) use sse::{multiply, transform_vec4};
+
+#[cfg(all(feature = "simd", target_arch = "aarch64"))]
+pub(crate) use neon::{multiply, transform_vec4};
+
+#[cfg(not(all(feature = "simd", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+pub(crate) use scalar::{multiply, transform_vec4};
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_scalar() {
+        let a: [f32; 16] = std::array::from_fn(|i| i as f32 * 0.5 - 3.0);
+        let b: [f32; 16] = std::array::from_fn(|i| (i as f32).sin());
+        let v = [1.5, -2.0, 0.25, 1.0];
+        let close = |x: &[f32], y: &[f32]| x.iter().zip(y).all(|(x, y)| (x - y).abs() < 1e-5);
+        assert!(close(&multiply(&a, &b), &scalar::multiply(&a, &b)));
+        assert!(close(&transform_vec4(&a, v), &scalar::transform_vec4(&a, v)));
+    }
+}
@@ -0,0 +1,145 @@
+// math/src/transform.rs
+//
+// Posición, giro y escala de un objeto por separado, en vez de una matriz
+// suelta: el giro puede ser sobre cualquier eje y la escala distinta en cada
+// eje. La matriz (traslación * rotación * escala, ver `Matrix4::from_trs`) se
+// calcula recién cuando se pide y se guarda hasta que cambie algún campo.
+// Los campos son públicos, así que el cambio se detecta comparando con los
+// valores con que se calculó.
+
+use std::cell::Cell;
+use std::fmt;
+
+use crate::{matrix_4_by_4::Matrix4, quaternion::Quat, vec3::Vec3};
+
+#[derive(Clone)]
+pub struct Transform {
+    pub position: Vec3,
+    /// Unitario
+    pub rotation: Quat,
+    /// Factor en cada eje propio (antes del giro)
+    pub scale: Vec3,
+    /// (posición, giro, escala) con que se calculó la matriz, y la matriz
+    matrix: Cell<Option<(Vec3, Quat, Vec3, Matrix4)>>,
+}
+
+impl Transform {
+    pub fn new(position: Vec3, rotation: Quat, scale: Vec3) -> Self {
+        Self { position, rotation, scale, matrix: Cell::new(None) }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(Vec3::ZERO, Quat::IDENTITY, Vec3::new(1.0, 1.0, 1.0))
+    }
+
+    pub fn from_translation(position: Vec3) -> Self {
+        Self { position, ..Self::identity() }
+    }
+
+    /// Separa una matriz afín (ver `Matrix4::decompose`); la cizalla se pierde
+    pub fn from_matrix(matrix: &Matrix4) -> Self {
+        let (position, rotation, scale) = matrix.decompose();
+        Self::new(position, rotation, scale)
+    }
+
+    /// Traslación * rotación * escala, calculada solo si cambió algún campo
+    pub fn matrix(&self) -> Matrix4 {
+        if let Some((position, rotation, scale, matrix)) = self.matrix.get() {
+            if position == self.position && rotation == self.rotation && scale == self.scale {
+                return matrix;
+            }
+        }
+        let matrix = Matrix4::from_trs(self.position, self.rotation, self.scale);
+        self.matrix.set(Some((self.position, self.rotation, self.scale, matrix)));
+        matrix
+    }
+
+    /// Punto en coordenadas propias llevado al espacio del padre
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        let scaled = Vec3::new(point.x * self.scale.x, point.y * self.scale.y, point.z * self.scale.z);
+        self.position + self.rotation.rotate(scaled)
+    }
+
+    /// Eje propio X, Y o Z (0, 1 o 2) en el espacio del padre, unitario
+    pub fn axis(&self, index: usize) -> Vec3 {
+        self.rotation.rotate([Vec3::UNIT_X, Vec3::UNIT_Y, Vec3::UNIT_Z][index])
+    }
+
+    /// Suma el giro `rotation` (en los ejes del padre) alrededor de `pivot`,
+    /// que queda quieto
+    pub fn rotate_about(&mut self, rotation: Quat, pivot: Vec3) {
+        self.position = pivot + rotation.rotate(self.position - pivot);
+        self.rotation = (rotation * self.rotation).normalize();
+    }
+
+    /// Aplica `matrix` (en el espacio del padre) después de esta transform
+    pub fn apply(&mut self, matrix: &Matrix4) {
+        *self = Self::from_matrix(&matrix.multiply(&self.matrix()));
+    }
+
+    pub fn approx_eq(&self, other: &Self, eps: f32) -> bool {
+        self.position.approx_eq(&other.position, eps)
+            && self.rotation.same_rotation(&other.rotation, eps)
+            && self.scale.approx_eq(&other.scale, eps)
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+// La matriz guardada no cuenta para comparar ni para mostrar
+impl PartialEq for Transform {
+    fn eq(&self, other: &Self) -> bool {
+        self.position == other.position && self.rotation == other.rotation && self.scale == other.scale
+    }
+}
+
+impl fmt::Debug for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Transform")
+            .field("position", &self.position)
+            .field("rotation", &self.rotation)
+            .field("scale", &self.scale)
+            .finish()
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lazy_matrix_and_rotation_about_pivot() {
+        // Escala distinta en cada eje y giro de 90° en Z
+        let mut transform = Transform::new(
+            Vec3::new(1.0, 0.0, 0.0),
+            Quat::from_axis_angle(Vec3::UNIT_Z, std::f32::consts::FRAC_PI_2),
+            Vec3::new(2.0, 3.0, 1.0),
+        );
+        let point = Vec3::new(1.0, 1.0, 1.0);
+        assert!(transform.transform_point(point).approx_eq(&Vec3::new(-2.0, 2.0, 1.0), 1e-5));
+        assert!(transform.matrix().transform_point(point).approx_eq(&transform.transform_point(point), 1e-5));
+        assert!(transform.axis(0).approx_eq(&Vec3::UNIT_Y, 1e-6));
+
+        // La matriz guardada se rehace al cambiar un campo
+        transform.position = Vec3::new(0.0, 0.0, 5.0);
+        assert_eq!(transform.matrix().m[14], 5.0);
+        assert!(Transform::from_matrix(&transform.matrix()).approx_eq(&transform, 1e-5));
+
+        // Girar alrededor de un eje arbitrario por un punto que queda quieto
+        let pivot = Vec3::new(0.0, 0.0, 4.0);
+        let corner = transform.transform_point(point);
+        let spin = Quat::from_axis_angle(Vec3::new(1.0, 1.0, 0.0), 1.0);
+        transform.rotate_about(spin, pivot);
+        let expected = pivot + spin.rotate(corner - pivot);
+        assert!(transform.transform_point(point).approx_eq(&expected, 1e-5));
+
+        let mut moved = Transform::identity();
+        moved.apply(&Matrix4::translate(0.0, 2.0, 0.0));
+        assert_eq!(moved, Transform::from_translation(Vec3::new(0.0, 2.0, 0.0)));
+    }
+}
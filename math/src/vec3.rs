@@ -1,7 +1,9 @@
 use std::ops::{Add, Sub, Mul, Div, AddAssign, SubAssign, MulAssign};
 
+use serde::{Deserialize, Serialize};
+
 // Estructura para representar un vector 3D
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Vec3 {
     pub x: f32,
     pub y: f32,
@@ -23,31 +25,36 @@ impl Vec3 {
         (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
     }
 
-    pub fn normalize(&self) -> Self {
+    /// Vector unitario en la misma dirección, o None si el vector es nulo
+    /// (o tiene componentes infinitas/NaN)
+    pub fn try_normalize(&self) -> Option<Self> {
         let mag = self.magnitude();
-        if mag == 0.0 {
-            panic!("Attempt to normalize a zero vector");
-        } else {
-            *self / mag
-        }
+        (mag > 0.0 && mag.is_finite()).then(|| *self / mag)
+    }
+
+    /// Vector unitario, o `fallback` si no se puede normalizar
+    pub fn normalize_or(&self, fallback: Self) -> Self {
+        self.try_normalize().unwrap_or(fallback)
+    }
+
+    /// Vector unitario; el vector nulo queda nulo (no entra en pánico con
+    /// triángulos degenerados)
+    pub fn normalize(&self) -> Self {
+        self.normalize_or(Self::ZERO)
     }
 
     pub fn dot(&self, other: &Self) -> f32 {
         self.x * other.x + self.y * other.y + self.z * other.z
     }
 
+    /// Producto vectorial (nulo si alguno de los dos es nulo o son paralelos)
     pub fn cross(&self, other: &Self) -> Self {
-        if self.magnitude() == 0.0 || other.magnitude() == 0.0 {
-            panic!("Cannot compute cross product with zero vector");
-        }
-    
         Self::new(
             self.y * other.z - self.z * other.y,
             self.z * other.x - self.x * other.z,
             self.x * other.y - self.y * other.x,
         )
     }
-    
 
     pub fn lerp(&self, other: &Self, t: f32) -> Self {
         let t_clamped = t.clamp(0.0, 1.0); // Clamp t between 0 and 1
@@ -67,6 +74,13 @@ impl Vec3 {
         *self * scalar
     }
 
+    /// Igualdad aproximada componente a componente (ver `crate::approx_eq`)
+    pub fn approx_eq(&self, other: &Self, eps: f32) -> bool {
+        crate::approx_eq(self.x, other.x, eps)
+            && crate::approx_eq(self.y, other.y, eps)
+            && crate::approx_eq(self.z, other.z, eps)
+    }
+
     pub fn angle_between(&self, other: &Self) -> f32 {
         let dot_product = self.dot(other);
         let magnitudes = self.magnitude() * other.magnitude();
@@ -233,4 +247,24 @@ mod tests {
         assert_eq!(arr, [1.0, 2.0, 3.0]);
     }
 
+    #[test]
+    fn test_approx_eq() {
+        let v = Vec3::new(0.1, 0.2, 0.3);
+        let sum = Vec3::new(0.1, 0.0, 0.0) + Vec3::new(0.0, 0.2, 0.0) + Vec3::new(0.0, 0.0, 0.3);
+        assert!(sum.approx_eq(&v, crate::EPSILON));
+        assert!(!v.approx_eq(&Vec3::new(0.1, 0.2, 0.31), crate::EPSILON));
+        // Relativa para valores grandes
+        assert!(Vec3::new(1e6, 0.0, 0.0).approx_eq(&Vec3::new(1e6 + 5.0, 0.0, 0.0), crate::EPSILON));
+    }
+
+    #[test]
+    fn test_degenerate_vectors() {
+        assert_eq!(Vec3::ZERO.try_normalize(), None);
+        assert_eq!(Vec3::new(f32::NAN, 0.0, 0.0).try_normalize(), None);
+        assert_eq!(Vec3::ZERO.normalize_or(Vec3::UNIT_Y), Vec3::UNIT_Y);
+        assert_eq!(Vec3::new(0.0, 3.0, 0.0).normalize_or(Vec3::UNIT_X), Vec3::UNIT_Y);
+        assert_eq!(Vec3::ZERO.cross(&Vec3::UNIT_X), Vec3::ZERO);
+        assert_eq!(Vec3::UNIT_X.cross(&Vec3::UNIT_Y), Vec3::UNIT_Z);
+    }
+
 }
\ No newline at end of file
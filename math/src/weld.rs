@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+// Soldadura de vértices con un hash espacial. Usar como clave el punto
+// redondeado a una grilla separa puntos casi iguales que caen a los dos lados
+// de un borde de celda y une puntos que están a más de la tolerancia en diagonal.
+// Acá la grilla solo sirve para encontrar candidatos: celdas de lado
+// `tolerance`, así todo punto a menos de esa distancia está en la celda del
+// punto o en una de sus 26 vecinas, y se une al más cercano de ellos si la
+// distancia euclídea no pasa la tolerancia.
+
+/// Tolerancia de `VertexWelder::default`: puntos a menos de esto se consideran el mismo
+pub const DEFAULT_TOLERANCE: f32 = 1e-4;
+
+#[derive(Debug, Clone)]
+pub struct VertexWelder {
+    tolerance: f32,
+    points: Vec<[f32; 3]>,
+    /// Índices de `points` en cada celda
+    cells: HashMap<[i64; 3], Vec<u32>>,
+}
+
+impl Default for VertexWelder {
+    fn default() -> Self {
+        Self::new(DEFAULT_TOLERANCE)
+    }
+}
+
+impl VertexWelder {
+    /// Tolerancia en unidades del modelo; tiene que ser positiva (si no, se
+    /// usa la más chica que no desborda la grilla)
+    pub fn new(tolerance: f32) -> Self {
+        Self { tolerance: tolerance.max(1e-12), points: Vec::new(), cells: HashMap::new() }
+    }
+
+    fn cell(&self, p: [f32; 3]) -> [i64; 3] {
+        p.map(|c| (c as f64 / self.tolerance as f64).floor() as i64)
+    }
+
+    /// Índice del vértice soldado para `p` y si es nuevo
+    pub fn weld(&mut self, p: [f32; 3]) -> (u32, bool) {
+        let cell = self.cell(p);
+        let limit = self.tolerance * self.tolerance;
+        let mut nearest: Option<(f32, u32)> = None;
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let Some(candidates) = self.cells.get(&[cell[0] + dx, cell[1] + dy, cell[2] + dz]) else {
+                        continue;
+                    };
+                    for &index in candidates {
+                        let q = self.points[index as usize];
+                        let distance = (0..3).map(|i| (p[i] - q[i]) * (p[i] - q[i])).sum::<f32>();
+                        if distance <= limit && nearest.is_none_or(|(best, _)| distance < best) {
+                            nearest = Some((distance, index));
+                        }
+                    }
+                }
+            }
+        }
+        if let Some((_, index)) = nearest {
+            return (index, false);
+        }
+        let index = self.points.len() as u32;
+        self.points.push(p);
+        self.cells.entry(cell).or_default().push(index);
+        (index, true)
+    }
+
+    /// Vértices soldados, en el orden en que aparecieron
+    pub fn points(&self) -> &[[f32; 3]] {
+        &self.points
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weld_euclidean_tolerance() {
+        // A los dos lados de un borde de celda, pero a 0.002: se une
+        let mut welder = VertexWelder::new(0.1);
+        assert_eq!(welder.weld([0.099, 0.0, 0.0]), (0, true));
+        assert_eq!(welder.weld([0.101, 0.0, 0.0]), (0, false));
+        assert_eq!(welder.weld([0.3, 0.0, 0.0]), (1, true));
+        // En la misma celda redondeada pero a más de 0.1 en diagonal: no se une
+        let mut welder = VertexWelder::new(0.1);
+        welder.weld([0.05, 0.05, 0.05]);
+        assert_eq!(welder.weld([0.14, 0.14, 0.14]), (1, true));
+        // Con dos candidatos se queda con el más cercano
+        let mut welder = VertexWelder::new(1.0);
+        welder.weld([0.0, 0.0, 0.0]);
+        welder.weld([1.5, 0.0, 0.0]);
+        assert_eq!(welder.weld([1.0, 0.0, 0.0]), (1, false));
+        assert_eq!(welder.len(), 2);
+        assert_eq!(welder.points()[1], [1.5, 0.0, 0.0]);
+    }
+}
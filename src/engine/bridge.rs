@@ -0,0 +1,243 @@
+// src/engine/bridge.rs
+//
+// Puente de poses en vivo: el visor como gemelo digital de una celda real. Un
+// socket UDP (solo std) recibe datagramas JSON con poses de objetos y estados
+// de articulaciones, y el `BridgePlugin` los aplica a los objetos y robots
+// con ese nombre en cada frame. Los mensajes copian los campos de ROS
+// (geometry_msgs/Pose y sensor_msgs/JointState, en metros y radianes), así
+// que un nodo de pocas líneas puede reenviar los tópicos sin convertir nada.
+// Se compila con la feature `bridge` y se activa con
+// RUST_ENGINE_BRIDGE=127.0.0.1:9870.
+//
+//   {"type": "pose", "object": "pieza", "position": [0.5, 0, 0.1], "orientation": [0, 0, 0.707, 0.707], "frame": "base"}
+//   {"type": "joint_state", "robot": "ur5", "name": ["elbow_joint"], "position": [1.57]}
+//
+// Sin `frame` la pose es en los ejes de la escena; sin `robot` las
+// articulaciones se buscan en todos los robots. De cada objeto o grupo de
+// articulaciones se aplica solo el último mensaje que llegó en el frame.
+
+use std::collections::HashSet;
+use std::net::UdpSocket;
+
+use serde::Deserialize;
+
+use crate::engine::plugin::{EngineContext, Plugin};
+use crate::graphics::scene::Scene;
+use crate::math::{quaternion::Quat, vec3::Vec3};
+
+/// Variable de entorno con la dirección en la que escucha el puente
+pub const BRIDGE_ENV: &str = "RUST_ENGINE_BRIDGE";
+
+/// Tamaño máximo de un datagrama
+const MAX_DATAGRAM: usize = 64 * 1024;
+/// Mensajes que se leen por frame como mucho (el resto espera al siguiente)
+const MAX_MESSAGES_PER_FRAME: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BridgeMessage {
+    /// Pose de los objetos llamados `object`: posición en metros y
+    /// orientación como cuaternión [x, y, z, w], en el marco `frame`
+    Pose {
+        object: String,
+        position: [f32; 3],
+        #[serde(default = "identity_orientation")]
+        orientation: [f32; 4],
+        #[serde(default)]
+        frame: Option<String>,
+    },
+    /// Posición (radianes o metros) de cada articulación de `name`
+    JointState {
+        #[serde(default)]
+        robot: Option<String>,
+        name: Vec<String>,
+        position: Vec<f32>,
+    },
+}
+
+fn identity_orientation() -> [f32; 4] {
+    [0.0, 0.0, 0.0, 1.0]
+}
+
+impl BridgeMessage {
+    /// Lo que pisa un mensaje posterior con la misma clave
+    fn key(&self) -> String {
+        match self {
+            Self::Pose { object, .. } => format!("pose:{}", object),
+            Self::JointState { robot, name, .. } => format!("joints:{}:{}", robot.as_deref().unwrap_or(""), name.join(",")),
+        }
+    }
+}
+
+/// Se queda con el último mensaje de cada clave, en el orden en que llegaron
+fn coalesce(messages: Vec<BridgeMessage>) -> Vec<BridgeMessage> {
+    let mut seen = HashSet::new();
+    let mut latest: Vec<BridgeMessage> = messages.into_iter().rev().filter(|message| seen.insert(message.key())).collect();
+    latest.reverse();
+    latest
+}
+
+/// Aplica un mensaje a la escena
+pub fn apply(scene: &mut Scene, message: &BridgeMessage) -> Result<(), String> {
+    match message {
+        BridgeMessage::Pose { object, position, orientation, frame } => {
+            let frame = scene.frame_or_scene(frame.as_deref())?;
+            let position = Vec3::from(position.map(|meters| scene.units.from_mm(meters * 1000.0)));
+            let [x, y, z, w] = *orientation;
+            let rotation = Quat::new(x, y, z, w).normalize();
+            let mut found = false;
+            for obj in scene.objects.iter_mut().filter(|obj| obj.name == *object) {
+                obj.set_pose_in(&frame, position, rotation);
+                found = true;
+            }
+            if found {
+                Ok(())
+            } else {
+                Err(format!("No hay objetos llamados '{}'", object))
+            }
+        }
+        BridgeMessage::JointState { robot, name, position } => {
+            if name.len() != position.len() {
+                return Err(format!("{} articulaciones y {} posiciones", name.len(), position.len()));
+            }
+            let robots: Vec<String> = match robot {
+                Some(robot) => vec![scene.robot(robot)?.name.clone()],
+                None => scene.robots.iter().map(|robot| robot.name.clone()).collect(),
+            };
+            for (joint, &value) in name.iter().zip(position) {
+                let owners: Vec<String> = robots
+                    .iter()
+                    .filter(|robot| scene.robot(robot).is_ok_and(|r| r.joints().iter().any(|j| j.name == *joint)))
+                    .cloned()
+                    .collect();
+                if owners.is_empty() {
+                    return Err(format!("Ningún robot tiene la articulación '{}'", joint));
+                }
+                for owner in &owners {
+                    scene.set_joint_position(owner, joint, value)?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+pub struct BridgePlugin {
+    address: String,
+    socket: Option<UdpSocket>,
+    buffer: Vec<u8>,
+    /// Errores ya informados: a cien mensajes por segundo se repetirían sin fin
+    reported: HashSet<String>,
+}
+
+impl BridgePlugin {
+    pub fn new(address: &str) -> Self {
+        Self { address: address.to_string(), socket: None, buffer: vec![0; MAX_DATAGRAM], reported: HashSet::new() }
+    }
+
+    /// Plugin de RUST_ENGINE_BRIDGE, si está definida
+    pub fn from_env() -> Option<Self> {
+        std::env::var(BRIDGE_ENV).ok().map(|address| Self::new(&address))
+    }
+
+    /// Abre el socket sin bloquear; devuelve la dirección real (con el puerto 0 elige uno libre)
+    fn bind(&mut self) -> Result<String, String> {
+        let socket = UdpSocket::bind(&self.address).map_err(|e| format!("No se pudo escuchar en {}: {}", self.address, e))?;
+        socket.set_nonblocking(true).map_err(|e| e.to_string())?;
+        let address = socket.local_addr().map_err(|e| e.to_string())?;
+        self.socket = Some(socket);
+        Ok(address.to_string())
+    }
+
+    /// Mensajes que llegaron desde el último frame, ya sin los repetidos
+    fn receive(&mut self) -> Vec<BridgeMessage> {
+        let Some(socket) = &self.socket else { return Vec::new() };
+        let mut messages = Vec::new();
+        while messages.len() < MAX_MESSAGES_PER_FRAME {
+            let Ok(length) = socket.recv(&mut self.buffer) else { break };
+            match serde_json::from_slice(&self.buffer[..length]) {
+                Ok(message) => messages.push(message),
+                Err(e) => report(&mut self.reported, format!("Mensaje inválido en el puente: {}", e)),
+            }
+        }
+        coalesce(messages)
+    }
+
+}
+
+/// Informa un error la primera vez que aparece
+fn report(reported: &mut HashSet<String>, error: String) {
+    if reported.insert(error.clone()) {
+        eprintln!("{}", error);
+    }
+}
+
+impl Plugin for BridgePlugin {
+    fn name(&self) -> &str {
+        "bridge"
+    }
+
+    fn init(&mut self, _ctx: &mut EngineContext) -> Result<(), String> {
+        let address = self.bind()?;
+        println!("Puente de poses en udp://{}", address);
+        Ok(())
+    }
+
+    fn update(&mut self, ctx: &mut EngineContext, _dt: f32) {
+        for message in self.receive() {
+            if let Err(e) = apply(ctx.scene, &message) {
+                report(&mut self.reported, e);
+            }
+        }
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::frames::ReferenceFrame;
+    use crate::graphics::scene_object::SceneObject;
+
+    #[test]
+    fn test_udp_poses_applied_to_named_objects() {
+        let mut bridge = BridgePlugin::new("127.0.0.1:0");
+        let address = bridge.bind().unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let datagrams = [
+            r#"{"type": "pose", "object": "pieza", "position": [9, 9, 9]}"#,
+            r#"{"type": "volar"}"#,
+            r#"{"type": "pose", "object": "pieza", "position": [0.5, 0, 0.1], "orientation": [0, 0, 0.7071068, 0.7071068], "frame": "base"}"#,
+            r#"{"type": "joint_state", "name": ["codo"], "position": [1.0]}"#,
+        ];
+        for datagram in datagrams {
+            sender.send_to(datagram.as_bytes(), &address).unwrap();
+        }
+        let mut messages = Vec::new();
+        for _ in 0..100 {
+            messages.extend(bridge.receive());
+            if messages.len() >= 2 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        // La primera pose la pisa la segunda y el mensaje inválido se descarta
+        assert_eq!(messages.len(), 2);
+        assert!(matches!(&messages[0], BridgeMessage::Pose { position, .. } if *position == [0.5, 0.0, 0.1]));
+
+        // Escena en milímetros con el marco "base" corrido 1 m en X
+        let mut scene = Scene::new();
+        let mut obj = SceneObject::new(0, 0);
+        obj.name = "pieza".to_string();
+        scene.objects.push(obj);
+        scene.set_frame(ReferenceFrame::new("base", Vec3::new(1000.0, 0.0, 0.0), Quat::IDENTITY));
+        apply(&mut scene, &messages[0]).unwrap();
+        let transform = &scene.objects[0].transform;
+        assert!(transform.position.approx_eq(&Vec3::new(1500.0, 0.0, 100.0), 1e-3));
+        assert!(transform.axis(0).approx_eq(&Vec3::UNIT_Y, 1e-5));
+        // Sin robots no hay a quién mover las articulaciones
+        assert!(apply(&mut scene, &messages[1]).is_err());
+        let missing = BridgeMessage::Pose { object: "otra".to_string(), position: [0.0; 3], orientation: [0.0; 4], frame: None };
+        assert!(apply(&mut scene, &missing).is_err());
+    }
+}
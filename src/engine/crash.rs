@@ -0,0 +1,259 @@
+// src/engine/crash.rs
+//
+// Informe de cierre inesperado. `install` cambia el hook de panic para que,
+// antes del mensaje de siempre, se escriba un `crash-<segundos>.log` con el
+// panic, el backtrace, los errores GL pendientes, el driver, los assets
+// cargados y un resumen de la escena. Así un "se cerró solo" llega con algo
+// que mirar. El visor va actualizando lo que se sabe con `update_context`.
+//
+// El archivo va a la carpeta de RUST_ENGINE_CRASH_DIR (por defecto la
+// actual). Con RUST_ENGINE_CRASH_DIALOG=1 además se avisa en una ventana del
+// sistema (zenity, osascript o PowerShell, si están).
+
+use std::backtrace::Backtrace;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock, TryLockError};
+use std::thread::ThreadId;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::graphics::scene::Scene;
+
+/// Variable de entorno con la carpeta de los informes
+pub const CRASH_DIR_ENV: &str = "RUST_ENGINE_CRASH_DIR";
+/// Variable de entorno que activa el aviso en una ventana
+pub const CRASH_DIALOG_ENV: &str = "RUST_ENGINE_CRASH_DIALOG";
+
+/// Objetos de la escena que se listan uno por uno
+const MAX_LISTED_OBJECTS: usize = 50;
+
+/// Lo que se sabe del motor para el próximo informe
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CrashContext {
+    /// Versión de OpenGL, driver y fabricante
+    pub gl: Option<String>,
+    /// Archivos cargados (modelos, texturas...)
+    pub assets: Vec<String>,
+    /// Ver `scene_summary`
+    pub scene: Vec<String>,
+}
+
+static CONTEXT: Mutex<Option<CrashContext>> = Mutex::new(None);
+/// Hilo dueño del contexto GL (el que llamó a `install`)
+static GL_THREAD: OnceLock<ThreadId> = OnceLock::new();
+
+/// Instala el hook de panic; se llama una vez desde el hilo que va a tener el contexto GL
+pub fn install() {
+    if GL_THREAD.set(std::thread::current().id()).is_err() {
+        return;
+    }
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let thread = std::thread::current();
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "(panic sin mensaje)".to_string());
+        let report = CrashReport {
+            message,
+            location: info.location().map(|l| l.to_string()).unwrap_or_default(),
+            thread: thread.name().unwrap_or("sin nombre").to_string(),
+            gl_errors: pending_gl_errors(),
+            context: current_context(),
+            backtrace: Backtrace::force_capture().to_string(),
+        };
+        match report.write() {
+            Ok(path) => {
+                eprintln!("Informe del error guardado en {}", path.display());
+                if std::env::var(CRASH_DIALOG_ENV).is_ok_and(|v| v == "1") {
+                    show_dialog(&report.message, &path);
+                }
+            }
+            Err(e) => eprintln!("No se pudo guardar el informe del error: {}", e),
+        }
+        previous(info);
+    }));
+}
+
+/// Modifica lo que va a incluir el próximo informe
+pub fn update_context(f: impl FnOnce(&mut CrashContext)) {
+    let mut context = CONTEXT.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(context.get_or_insert_with(CrashContext::default));
+}
+
+/// Sin bloquear: el panic pudo ocurrir con el lock tomado
+fn current_context() -> CrashContext {
+    let guard = match CONTEXT.try_lock() {
+        Ok(guard) => guard,
+        Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        Err(TryLockError::WouldBlock) => return CrashContext::default(),
+    };
+    guard.clone().unwrap_or_default()
+}
+
+/// Errores de `glGetError`; solo se pueden leer desde el hilo del contexto
+fn pending_gl_errors() -> Option<Vec<String>> {
+    if GL_THREAD.get() != Some(&std::thread::current().id()) || !gl::GetError::is_loaded() {
+        return None;
+    }
+    let mut errors = Vec::new();
+    // Con el contexto perdido algunos drivers repiten el error para siempre
+    for _ in 0..16 {
+        let error = unsafe { gl::GetError() };
+        if error == gl::NO_ERROR {
+            break;
+        }
+        errors.push(gl_error_name(error));
+    }
+    Some(errors)
+}
+
+fn gl_error_name(error: u32) -> String {
+    match error {
+        gl::INVALID_ENUM => "GL_INVALID_ENUM".to_string(),
+        gl::INVALID_VALUE => "GL_INVALID_VALUE".to_string(),
+        gl::INVALID_OPERATION => "GL_INVALID_OPERATION".to_string(),
+        gl::INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION".to_string(),
+        gl::OUT_OF_MEMORY => "GL_OUT_OF_MEMORY".to_string(),
+        gl::STACK_OVERFLOW => "GL_STACK_OVERFLOW".to_string(),
+        gl::STACK_UNDERFLOW => "GL_STACK_UNDERFLOW".to_string(),
+        other => format!("0x{:04X}", other),
+    }
+}
+
+/// Una línea por objeto (nombre, origen, vértices, triángulos, material) y los totales
+pub fn scene_summary(scene: &Scene) -> Vec<String> {
+    let triangles: usize = scene.objects.iter().map(|obj| obj.mesh.indices.len() / 3).sum();
+    let mut lines = vec![format!(
+        "{} objetos, {} triángulos, {} bookmarks",
+        scene.objects.len(),
+        triangles,
+        scene.bookmarks.len()
+    )];
+    for obj in scene.objects.iter().take(MAX_LISTED_OBJECTS) {
+        lines.push(format!(
+            "{} ({}): {} vértices, {} triángulos, material {}",
+            obj.name,
+            obj.source.as_deref().unwrap_or("generado"),
+            obj.mesh.positions.len(),
+            obj.mesh.indices.len() / 3,
+            obj.material.as_deref().unwrap_or("-")
+        ));
+    }
+    if scene.objects.len() > MAX_LISTED_OBJECTS {
+        lines.push(format!("... y {} más", scene.objects.len() - MAX_LISTED_OBJECTS));
+    }
+    lines
+}
+
+/// Todo lo que va al archivo
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrashReport {
+    pub message: String,
+    /// archivo:línea:columna del panic
+    pub location: String,
+    pub thread: String,
+    /// None si el panic fue en otro hilo que el del contexto GL
+    pub gl_errors: Option<Vec<String>>,
+    pub context: CrashContext,
+    pub backtrace: String,
+}
+
+impl CrashReport {
+    pub fn text(&self) -> String {
+        let mut text = format!(
+            "rust_engine {} se cerró por un error\n\nPanic en el hilo '{}': {}\nUbicación: {}\n",
+            env!("CARGO_PKG_VERSION"),
+            self.thread,
+            self.message,
+            self.location
+        );
+        text += "\n== OpenGL\n";
+        text += &format!("{}\n", self.context.gl.as_deref().unwrap_or("(sin contexto todavía)"));
+        match &self.gl_errors {
+            None => text += "Errores pendientes: no se consultaron (otro hilo)\n",
+            Some(errors) if errors.is_empty() => text += "Errores pendientes: ninguno\n",
+            Some(errors) => text += &format!("Errores pendientes: {}\n", errors.join(", ")),
+        }
+        for (title, lines) in [("Assets cargados", &self.context.assets), ("Escena", &self.context.scene)] {
+            text += &format!("\n== {}\n", title);
+            if lines.is_empty() {
+                text += "(nada)\n";
+            }
+            for line in lines {
+                text += &format!("{}\n", line);
+            }
+        }
+        text += &format!("\n== Backtrace\n{}\n", self.backtrace);
+        text
+    }
+
+    fn write(&self) -> Result<PathBuf, String> {
+        let dir = std::env::var(CRASH_DIR_ENV).map(PathBuf::from).unwrap_or_else(|_| PathBuf::from("."));
+        let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let path = dir.join(format!("crash-{}.log", seconds));
+        std::fs::write(&path, self.text()).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(path)
+    }
+}
+
+/// Aviso con la herramienta de diálogos del sistema; si no hay, solo queda la consola
+fn show_dialog(message: &str, path: &std::path::Path) {
+    use std::process::Command;
+    let text = format!("Rust_Engine se cerró por un error:\n{}\n\nInforme: {}", message, path.display());
+    let result = if cfg!(target_os = "macos") {
+        let escaped = text.replace('\\', "\\\\").replace('"', "\\\"");
+        Command::new("osascript").arg("-e").arg(format!("display alert \"Rust_Engine\" message \"{}\"", escaped)).status()
+    } else if cfg!(windows) {
+        let escaped = text.replace('\'', "''");
+        Command::new("powershell")
+            .arg("-Command")
+            .arg(format!(
+                "Add-Type -AssemblyName PresentationFramework; [System.Windows.MessageBox]::Show('{}', 'Rust_Engine')",
+                escaped
+            ))
+            .status()
+    } else {
+        Command::new("zenity").arg("--error").arg("--title=Rust_Engine").arg(format!("--text={}", text)).status()
+    };
+    if let Err(e) = result {
+        eprintln!("No se pudo mostrar el aviso: {}", e);
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_text() {
+        let mut report = CrashReport {
+            message: "índice fuera de rango".to_string(),
+            location: "src/graphics/passes.rs:120:5".to_string(),
+            thread: "main".to_string(),
+            gl_errors: Some(vec![gl_error_name(gl::INVALID_OPERATION), gl_error_name(0x1234)]),
+            context: CrashContext {
+                gl: Some("OpenGL 3.3 - llvmpipe (Mesa)".to_string()),
+                assets: vec!["modelos/pieza.stl".to_string()],
+                scene: Vec::new(),
+            },
+            backtrace: "0: main".to_string(),
+        };
+        let text = report.text();
+        assert!(text.contains("Panic en el hilo 'main': índice fuera de rango"));
+        assert!(text.contains("Errores pendientes: GL_INVALID_OPERATION, 0x1234"));
+        assert!(text.contains("== Assets cargados\nmodelos/pieza.stl\n"));
+        assert!(text.contains("== Escena\n(nada)\n"));
+
+        report.gl_errors = None;
+        assert!(report.text().contains("no se consultaron"));
+
+        update_context(|context| context.assets.push("a.png".to_string()));
+        assert_eq!(current_context().assets, vec!["a.png"]);
+
+        let summary = scene_summary(&Scene::new());
+        assert_eq!(summary, vec!["0 objetos, 0 triángulos, 0 bookmarks"]);
+    }
+}
@@ -0,0 +1,153 @@
+// src/engine/input.rs
+//
+// Posición del cursor para todo lo que se hace con el mouse (picking, cubo de
+// navegación, medidas...). winit entrega el cursor en píxeles físicos con el
+// origen arriba a la izquierda, igual que los framebuffers, así que se guarda
+// así; los píxeles lógicos solo sirven para UI y se sacan con la escala. Para
+// pasar a NDC se usa el viewport que está bajo el cursor, de modo que funciona
+// igual con una sola vista que con varias (p. ej. vistas ortogonales lado a lado).
+
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event::WindowEvent;
+
+/// Rectángulo de una vista en píxeles físicos, origen arriba a la izquierda
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ViewportRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl ViewportRect {
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x as f64 && y >= self.y as f64 && x < (self.x + self.width) as f64 && y < (self.y + self.height) as f64
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Input {
+    /// Píxeles físicos; None si el cursor está fuera de la ventana
+    cursor: Option<PhysicalPosition<f64>>,
+    window_size: PhysicalSize<u32>,
+    scale_factor: f64,
+    /// Vistas en que está dividida la ventana (vacío: una sola, la ventana entera)
+    viewports: Vec<ViewportRect>,
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Self::new(PhysicalSize::new(0, 0), 1.0)
+    }
+}
+
+impl Input {
+    pub fn new(window_size: PhysicalSize<u32>, scale_factor: f64) -> Self {
+        Self { cursor: None, window_size, scale_factor, viewports: Vec::new() }
+    }
+
+    /// Actualiza el estado con un evento de la ventana
+    pub fn handle_event(&mut self, event: &WindowEvent) {
+        match event {
+            WindowEvent::CursorMoved { position, .. } => self.move_cursor(Some(*position)),
+            WindowEvent::CursorLeft { .. } => self.move_cursor(None),
+            WindowEvent::Resized(size) => self.window_size = *size,
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => self.scale_factor = *scale_factor,
+            _ => {}
+        }
+    }
+
+    /// Tamaño y escala de la ventana (al crearla o recrearla)
+    pub fn set_window(&mut self, size: PhysicalSize<u32>, scale_factor: f64) {
+        self.window_size = size;
+        self.scale_factor = scale_factor;
+    }
+
+    pub fn move_cursor(&mut self, position: Option<PhysicalPosition<f64>>) {
+        self.cursor = position;
+    }
+
+    /// Divide la ventana en varias vistas (vacío vuelve a una sola)
+    pub fn set_viewports(&mut self, viewports: Vec<ViewportRect>) {
+        self.viewports = viewports;
+    }
+
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Cursor en píxeles físicos de la ventana
+    pub fn cursor_physical(&self) -> Option<(f64, f64)> {
+        self.cursor.map(|p| (p.x, p.y))
+    }
+
+    /// Cursor en píxeles lógicos (para UI que se dibuja con escala)
+    pub fn cursor_logical(&self) -> Option<(f64, f64)> {
+        self.cursor.map(|p| (p.x / self.scale_factor, p.y / self.scale_factor))
+    }
+
+    /// Píxel de la ventana bajo el cursor, como lo esperan `Renderer::pick`
+    /// y el cubo de navegación
+    pub fn cursor_pixel(&self) -> Option<(i32, i32)> {
+        self.cursor.map(|p| (p.x.floor() as i32, p.y.floor() as i32))
+    }
+
+    /// Vista bajo el cursor: índice en el layout y su rectángulo
+    pub fn viewport_under_cursor(&self) -> Option<(usize, ViewportRect)> {
+        let (x, y) = self.cursor_physical()?;
+        if self.viewports.is_empty() {
+            let window = ViewportRect { x: 0, y: 0, width: self.window_size.width as i32, height: self.window_size.height as i32 };
+            return window.contains(x, y).then_some((0, window));
+        }
+        self.viewports.iter().copied().enumerate().find(|(_, rect)| rect.contains(x, y))
+    }
+
+    /// Cursor en píxeles relativos a la vista bajo él (origen arriba a la izquierda)
+    pub fn cursor_in_viewport(&self) -> Option<(usize, (f64, f64))> {
+        let (x, y) = self.cursor_physical()?;
+        let (index, rect) = self.viewport_under_cursor()?;
+        Some((index, (x - rect.x as f64, y - rect.y as f64)))
+    }
+
+    /// Cursor en coordenadas normalizadas de la vista bajo él: [-1, 1] en
+    /// ambos ejes, y hacia arriba (como la proyección de OpenGL)
+    pub fn cursor_ndc(&self) -> Option<[f32; 2]> {
+        let (x, y) = self.cursor_physical()?;
+        let (_, rect) = self.viewport_under_cursor()?;
+        Some([
+            (2.0 * (x - rect.x as f64) / rect.width as f64 - 1.0) as f32,
+            (1.0 - 2.0 * (y - rect.y as f64) / rect.height as f64) as f32,
+        ])
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_mapping() {
+        let mut input = Input::new(PhysicalSize::new(800, 600), 2.0);
+        assert_eq!(input.cursor_ndc(), None);
+
+        input.move_cursor(Some(PhysicalPosition::new(400.0, 150.0)));
+        assert_eq!(input.cursor_pixel(), Some((400, 150)));
+        assert_eq!(input.cursor_logical(), Some((200.0, 75.0)));
+        assert_eq!(input.cursor_ndc(), Some([0.0, 0.5]));
+
+        // Dos vistas lado a lado: el NDC es relativo a la de la derecha
+        let left = ViewportRect { x: 0, y: 0, width: 400, height: 600 };
+        let right = ViewportRect { x: 400, ..left };
+        input.set_viewports(vec![left, right]);
+        assert_eq!(input.viewport_under_cursor(), Some((1, right)));
+        assert_eq!(input.cursor_in_viewport(), Some((1, (0.0, 150.0))));
+        assert_eq!(input.cursor_ndc(), Some([-1.0, 0.5]));
+
+        // Fuera de la ventana no hay vista
+        input.move_cursor(Some(PhysicalPosition::new(900.0, 10.0)));
+        assert_eq!(input.cursor_ndc(), None);
+        input.move_cursor(None);
+        assert_eq!(input.cursor_pixel(), None);
+    }
+}
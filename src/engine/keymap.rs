@@ -0,0 +1,105 @@
+// src/engine/keymap.rs
+//
+// Registro de controles: cada tecla con lo que hace. El visor registra los
+// suyos al arrancar y los plugins pueden sumar los propios; la ayuda en
+// pantalla (F1) se arma desde acá, así no hay una lista aparte que se quede vieja.
+
+use winit::keyboard::KeyCode;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    /// Tecla que dispara la acción; None para controles de mouse o combinaciones
+    pub key: Option<KeyCode>,
+    /// Cómo se muestra el control en la ayuda
+    pub label: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    bindings: Vec<Binding>,
+}
+
+impl Keymap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registra una tecla. Falla si ya la usa otra acción.
+    pub fn bind(&mut self, key: KeyCode, description: &str) -> Result<(), String> {
+        if let Some(existing) = self.binding(key) {
+            return Err(format!("La tecla {} ya está asignada a '{}'", key_label(key), existing.description));
+        }
+        self.bindings.push(Binding { key: Some(key), label: key_label(key), description: description.to_string() });
+        Ok(())
+    }
+
+    /// Registra un control que no es una sola tecla (mouse, grupos de teclas)
+    pub fn bind_input(&mut self, label: &str, description: &str) {
+        self.bindings.push(Binding { key: None, label: label.to_string(), description: description.to_string() });
+    }
+
+    pub fn binding(&self, key: KeyCode) -> Option<&Binding> {
+        self.bindings.iter().find(|b| b.key == Some(key))
+    }
+
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+
+    /// Una línea por control, en orden de registro, con las descripciones alineadas
+    pub fn help_lines(&self) -> Vec<String> {
+        let width = self.bindings.iter().map(|b| b.label.chars().count()).max().unwrap_or(0);
+        self.bindings
+            .iter()
+            .map(|b| format!("{:width$}  {}", b.label, b.description, width = width))
+            .collect()
+    }
+}
+
+/// Nombre corto de una tecla ("Q", "1", "F5", "RePág"...)
+pub fn key_label(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    if let Some(letter) = name.strip_prefix("Key") {
+        return letter.to_string();
+    }
+    if let Some(digit) = name.strip_prefix("Digit") {
+        return digit.to_string();
+    }
+    match key {
+        KeyCode::Escape => "Esc".to_string(),
+        KeyCode::Home => "Inicio".to_string(),
+        KeyCode::PageUp => "RePág".to_string(),
+        KeyCode::PageDown => "AvPág".to_string(),
+        KeyCode::Space => "Espacio".to_string(),
+        _ => name,
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bindings_and_help() {
+        let mut keymap = Keymap::new();
+        keymap.bind(KeyCode::F1, "Ayuda").unwrap();
+        keymap.bind(KeyCode::KeyQ, "Agrandar").unwrap();
+        keymap.bind_input("Click derecho", "Girar la cámara");
+        assert!(keymap.bind(KeyCode::KeyQ, "Otra cosa").is_err());
+        assert_eq!(keymap.binding(KeyCode::KeyQ).map(|b| b.description.as_str()), Some("Agrandar"));
+        assert_eq!(keymap.binding(KeyCode::KeyW), None);
+
+        assert_eq!(
+            keymap.help_lines(),
+            vec![
+                "F1             Ayuda".to_string(),
+                "Q              Agrandar".to_string(),
+                "Click derecho  Girar la cámara".to_string(),
+            ]
+        );
+        assert_eq!(key_label(KeyCode::Digit3), "3");
+        assert_eq!(key_label(KeyCode::PageUp), "RePág");
+    }
+}
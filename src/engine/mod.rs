@@ -0,0 +1,14 @@
+pub mod resources;
+pub mod plugin;
+pub mod time;
+pub mod profiler;
+pub mod crash;
+pub mod settings;
+pub mod input;
+pub mod keymap;
+pub mod startup;
+pub mod remote;
+#[cfg(feature = "bridge")]
+pub mod bridge;
+pub mod watch;
+pub mod progress;
@@ -0,0 +1,149 @@
+// src/engine/plugin.rs
+//
+// Subsistemas opcionales (física, UI, grabación...) como plugins: el Engine
+// los inicializa en orden de registro, les pasa cada evento de la ventana y
+// cada frame, y los cierra en orden inverso. Comparten datos por medio de
+// `Resources` y ven la escena, el renderer y la cámara a través del contexto.
+
+use winit::event::WindowEvent;
+
+use crate::engine::input::Input;
+use crate::engine::keymap::Keymap;
+use crate::engine::resources::Resources;
+use crate::engine::settings::{EditSettings, EngineSettings};
+use crate::graphics::camara::Camera;
+use crate::graphics::path_tracer::Rng;
+use crate::graphics::render::Renderer;
+use crate::graphics::scene::Scene;
+
+/// Lo que un plugin puede tocar en cada llamada
+pub struct EngineContext<'a> {
+    pub scene: &'a mut Scene,
+    pub renderer: &'a mut Renderer,
+    pub camera: &'a mut Camera,
+    pub global_scale: f32,
+    pub resources: &'a mut Resources,
+    /// Cursor del último evento (ya actualizado cuando llega `Plugin::event`)
+    pub input: &'a Input,
+    /// Controles registrados; lo que se agregue aparece en la ayuda (F1)
+    pub keymap: &'a mut Keymap,
+}
+
+pub trait Plugin {
+    fn name(&self) -> &str;
+
+    /// Al arrancar, con la ventana y el renderer ya creados
+    fn init(&mut self, _ctx: &mut EngineContext) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Una vez por frame, antes de dibujar
+    fn update(&mut self, _ctx: &mut EngineContext, _dt: f32) {}
+
+    /// Cada evento de la ventana, antes de que lo procese el motor
+    fn event(&mut self, _ctx: &mut EngineContext, _event: &WindowEvent) {}
+
+    /// Al cerrar la aplicación
+    fn shutdown(&mut self, _ctx: &mut EngineContext) {}
+}
+
+#[derive(Default)]
+pub struct Engine {
+    plugins: Vec<Box<dyn Plugin>>,
+    /// Los primeros `initialized` plugins ya pasaron por `init`
+    initialized: usize,
+    /// Datos compartidos entre plugins (uno por tipo). Siempre incluye un
+    /// `Rng` para el contenido procedural; ver `seed`.
+    pub resources: Resources,
+    /// Ritmo de frames sin el foco (ver `engine::settings`)
+    pub settings: EngineSettings,
+    /// Ajustes de la edición de objetos (ver `engine::settings`)
+    pub editing: EditSettings,
+    /// Estado del mouse; lo actualiza `event`
+    pub input: Input,
+    /// Teclas y controles del visor y de los plugins
+    pub keymap: Keymap,
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        let mut engine = Self::default();
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        engine.seed(nanos);
+        engine
+    }
+
+    /// Reinicia el Rng compartido con una semilla fija (modo determinista)
+    pub fn seed(&mut self, seed: u64) {
+        self.resources.insert(Rng::new(seed));
+    }
+
+    /// Registra un plugin. Si el motor ya arrancó, el plugin se inicializa en
+    /// el próximo `update`.
+    pub fn add_plugin(&mut self, plugin: impl Plugin + 'static) -> Result<(), String> {
+        if self.plugin_names().contains(&plugin.name()) {
+            return Err(format!("Ya hay un plugin llamado '{}'", plugin.name()));
+        }
+        self.plugins.push(Box::new(plugin));
+        Ok(())
+    }
+
+    pub fn plugin_names(&self) -> Vec<&str> {
+        self.plugins.iter().map(|p| p.name()).collect()
+    }
+
+    /// Arma el contexto con los recursos del Engine y llama a `f` con cada
+    /// plugin ya inicializado
+    fn each(
+        &mut self,
+        scene: &mut Scene,
+        renderer: &mut Renderer,
+        camera: &mut Camera,
+        global_scale: f32,
+        mut f: impl FnMut(&mut dyn Plugin, &mut EngineContext),
+    ) {
+        let mut ctx = EngineContext { scene, renderer, camera, global_scale, resources: &mut self.resources, input: &self.input, keymap: &mut self.keymap };
+        for plugin in &mut self.plugins[..self.initialized] {
+            f(plugin.as_mut(), &mut ctx);
+        }
+    }
+
+    /// Inicializa en orden de registro los plugins que todavía no arrancaron.
+    /// Los que fallan se informan y se quitan.
+    pub fn init(&mut self, scene: &mut Scene, renderer: &mut Renderer, camera: &mut Camera, global_scale: f32) {
+        let mut ctx = EngineContext { scene, renderer, camera, global_scale, resources: &mut self.resources, input: &self.input, keymap: &mut self.keymap };
+        let pending = self.plugins.split_off(self.initialized);
+        for mut plugin in pending {
+            match plugin.init(&mut ctx) {
+                Ok(()) => self.plugins.push(plugin),
+                Err(e) => eprintln!("Plugin '{}' desactivado: {}", plugin.name(), e),
+            }
+        }
+        self.initialized = self.plugins.len();
+    }
+
+    pub fn update(&mut self, scene: &mut Scene, renderer: &mut Renderer, camera: &mut Camera, global_scale: f32, dt: f32) {
+        if self.initialized < self.plugins.len() {
+            self.init(scene, renderer, camera, global_scale);
+        }
+        self.each(scene, renderer, camera, global_scale, |plugin, ctx| plugin.update(ctx, dt));
+    }
+
+    pub fn event(&mut self, scene: &mut Scene, renderer: &mut Renderer, camera: &mut Camera, global_scale: f32, event: &WindowEvent) {
+        self.input.handle_event(event);
+        self.each(scene, renderer, camera, global_scale, |plugin, ctx| plugin.event(ctx, event));
+    }
+
+    /// Cierra los plugins en orden inverso al de inicialización y los quita
+    pub fn shutdown(&mut self, scene: &mut Scene, renderer: &mut Renderer, camera: &mut Camera, global_scale: f32) {
+        let mut ctx = EngineContext { scene, renderer, camera, global_scale, resources: &mut self.resources, input: &self.input, keymap: &mut self.keymap };
+        for plugin in self.plugins[..self.initialized].iter_mut().rev() {
+            plugin.shutdown(&mut ctx);
+        }
+        self.plugins.clear();
+        self.initialized = 0;
+    }
+}
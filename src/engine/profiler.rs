@@ -0,0 +1,220 @@
+// src/engine/profiler.rs
+//
+// Perfilador en pantalla: un gráfico con la duración de los últimos frames,
+// el tiempo de CPU de cada etapa del loop (animación, plugins, render...) y
+// el de GPU de cada pase del grafo (ver `RenderGraph::set_profiling`). Los
+// promedios en texto esconden los picos aislados, por ejemplo los de una
+// carga en segundo plano; en el gráfico se ven como barras rojas.
+
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use crate::graphics::text::{ScreenRect, TextPanel};
+
+/// Frames que muestra el gráfico
+pub const PROFILER_HISTORY: usize = 240;
+
+/// Duración de un frame a 60 fps, en ms
+pub const FRAME_BUDGET_MS: f32 = 1000.0 / 60.0;
+
+/// Tamaño del gráfico en píxeles lógicos
+const GRAPH_WIDTH: f32 = 240.0;
+const GRAPH_HEIGHT: f32 = 80.0;
+/// Alto de las barras apiladas de CPU y GPU
+const BAR_HEIGHT: f32 = 8.0;
+const MARGIN: f32 = 16.0;
+const GAP: f32 = 4.0;
+
+const OK_COLOR: [f32; 4] = [0.2, 0.8, 0.3, 0.9];
+const SLOW_COLOR: [f32; 4] = [0.9, 0.8, 0.2, 0.9];
+const SPIKE_COLOR: [f32; 4] = [1.0, 0.2, 0.15, 0.9];
+const BACKGROUND: [f32; 4] = [0.0, 0.0, 0.0, 0.7];
+
+/// Colores de las etapas, en orden (se repiten si hay más)
+const PALETTE: [[f32; 3]; 6] = [
+    [0.3, 0.6, 1.0],
+    [1.0, 0.6, 0.2],
+    [0.7, 0.4, 1.0],
+    [0.3, 0.9, 0.8],
+    [1.0, 0.4, 0.6],
+    [0.8, 0.8, 0.3],
+];
+
+#[derive(Debug, Default)]
+pub struct Profiler {
+    /// Duración de los últimos frames en ms, del más viejo al más nuevo
+    frames: VecDeque<f32>,
+    /// Etapas medidas en el frame en curso
+    sections: Vec<(&'static str, f32)>,
+    /// Etapas del último frame completo
+    cpu: Vec<(&'static str, f32)>,
+    gpu: Vec<(String, f32)>,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ejecuta `f` y suma su duración a la etapa `name` del frame en curso
+    pub fn measure<R>(&mut self, name: &'static str, f: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        let result = f();
+        self.record(name, start.elapsed().as_secs_f32() * 1000.0);
+        result
+    }
+
+    /// Suma `ms` a la etapa `name` del frame en curso
+    pub fn record(&mut self, name: &'static str, ms: f32) {
+        match self.sections.iter_mut().find(|(section, _)| *section == name) {
+            Some((_, total)) => *total += ms,
+            None => self.sections.push((name, ms)),
+        }
+    }
+
+    /// Cierra el frame con su duración total (de inicio a inicio)
+    pub fn end_frame(&mut self, frame_ms: f32) {
+        if self.frames.len() == PROFILER_HISTORY {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame_ms);
+        self.cpu = std::mem::take(&mut self.sections);
+    }
+
+    /// Mediciones de GPU por pase (ver `RenderGraph::gpu_timings`)
+    pub fn set_gpu_timings(&mut self, timings: Vec<(String, f32)>) {
+        self.gpu = timings;
+    }
+
+    pub fn frame_times(&self) -> impl Iterator<Item = f32> + '_ {
+        self.frames.iter().copied()
+    }
+
+    /// Etapas de CPU del último frame, en ms
+    pub fn cpu_timings(&self) -> &[(&'static str, f32)] {
+        &self.cpu
+    }
+
+    pub fn average(&self) -> f32 {
+        if self.frames.is_empty() {
+            return 0.0;
+        }
+        self.frames.iter().sum::<f32>() / self.frames.len() as f32
+    }
+
+    pub fn max(&self) -> f32 {
+        self.frames.iter().copied().fold(0.0, f32::max)
+    }
+
+    /// Gráfico y barras arriba a la derecha de una pantalla de `screen` píxeles
+    /// lógicos, con el texto debajo. Las líneas de cada etapa tienen el color
+    /// de su tramo en la barra.
+    pub fn overlay(&self, screen: (f32, f32)) -> (TextPanel, Vec<ScreenRect>) {
+        let left = (screen.0 - MARGIN - GRAPH_WIDTH).max(MARGIN);
+        let top = MARGIN;
+        // Escala vertical: al menos dos frames de presupuesto, o el peor frame
+        let scale = self.max().max(2.0 * FRAME_BUDGET_MS);
+        let bars_top = top + GRAPH_HEIGHT + GAP;
+        let bottom = bars_top + 2.0 * (BAR_HEIGHT + GAP);
+
+        let mut rects = vec![ScreenRect {
+            min: (left - GAP, top - GAP),
+            max: (left + GRAPH_WIDTH + GAP, bottom),
+            color: BACKGROUND,
+        }];
+        let width = GRAPH_WIDTH / PROFILER_HISTORY as f32;
+        // Los frames más nuevos quedan a la derecha
+        let first = PROFILER_HISTORY - self.frames.len();
+        for (index, &ms) in self.frames.iter().enumerate() {
+            let x = left + (first + index) as f32 * width;
+            let height = (ms / scale).min(1.0) * GRAPH_HEIGHT;
+            let color = if ms <= FRAME_BUDGET_MS * 1.05 {
+                OK_COLOR
+            } else if ms <= 2.0 * FRAME_BUDGET_MS {
+                SLOW_COLOR
+            } else {
+                SPIKE_COLOR
+            };
+            rects.push(ScreenRect { min: (x, top + GRAPH_HEIGHT - height), max: (x + width, top + GRAPH_HEIGHT), color });
+        }
+        // Línea de 60 fps
+        let budget = top + GRAPH_HEIGHT * (1.0 - FRAME_BUDGET_MS / scale);
+        rects.push(ScreenRect {
+            min: (left, budget),
+            max: (left + GRAPH_WIDTH, budget + 1.0),
+            color: [1.0, 1.0, 1.0, 0.5],
+        });
+
+        let last = self.frames.back().copied().unwrap_or(0.0);
+        let fps = if last > 0.0 { 1000.0 / last } else { 0.0 };
+        let mut lines = vec![
+            format!("Frame {:.1} ms ({:.0} fps)", last, fps),
+            format!("Prom {:.1} ms, máx {:.1} ms", self.average(), self.max()),
+        ];
+        let mut line_colors = vec![[0.9; 3]; 2];
+        let stages = [
+            ("CPU", self.cpu.iter().map(|(name, ms)| (*name, *ms)).collect::<Vec<_>>()),
+            ("GPU", self.gpu.iter().map(|(name, ms)| (name.as_str(), *ms)).collect()),
+        ];
+        for (row, (label, stages)) in stages.iter().enumerate() {
+            let y = bars_top + row as f32 * (BAR_HEIGHT + GAP);
+            let total: f32 = stages.iter().map(|(_, ms)| ms).sum();
+            if stages.is_empty() {
+                continue;
+            }
+            lines.push(format!("{} {:.2} ms", label, total));
+            line_colors.push([0.9; 3]);
+            let mut x = left;
+            for (index, (name, ms)) in stages.iter().enumerate() {
+                let [r, g, b] = PALETTE[index % PALETTE.len()];
+                let end = (x + ms / scale * GRAPH_WIDTH).min(left + GRAPH_WIDTH);
+                rects.push(ScreenRect { min: (x, y), max: (end, y + BAR_HEIGHT), color: [r, g, b, 0.9] });
+                x = end;
+                lines.push(format!("  {:<12}{:6.2} ms", name, ms));
+                line_colors.push([r, g, b]);
+            }
+        }
+
+        let panel = TextPanel {
+            position: (left - GAP, bottom + GAP),
+            line_colors,
+            ..TextPanel::new(lines)
+        };
+        (panel, rects)
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_and_overlay() {
+        let mut profiler = Profiler::new();
+        for frame in 0..PROFILER_HISTORY + 10 {
+            profiler.record("render", 2.0);
+            profiler.record("plugins", 1.0);
+            profiler.record("render", 1.0);
+            // Un pico cada 100 frames
+            profiler.end_frame(if frame % 100 == 0 { 80.0 } else { 16.0 });
+        }
+        assert_eq!(profiler.frame_times().count(), PROFILER_HISTORY);
+        assert_eq!(profiler.max(), 80.0);
+        assert!(profiler.average() > 16.0 && profiler.average() < 17.0);
+        assert_eq!(profiler.cpu_timings(), &[("render", 3.0), ("plugins", 1.0)]);
+        assert_eq!(profiler.measure("espera", || 7), 7);
+
+        profiler.set_gpu_timings(vec![("opaque".to_string(), 4.0)]);
+        let (panel, rects) = profiler.overlay((1200.0, 900.0));
+        // Fondo, una barra por frame, la línea de 60 fps y 2 + 1 tramos
+        assert_eq!(rects.len(), 1 + PROFILER_HISTORY + 1 + 3);
+        assert_eq!(rects.iter().filter(|r| r.color == SPIKE_COLOR).count(), 2);
+        assert!(rects.iter().all(|r| r.max.0 <= 1200.0 - MARGIN + GAP));
+        // Las barras usan toda la altura para el peor frame
+        assert!(rects.iter().any(|r| r.min.1 == MARGIN && r.color == SPIKE_COLOR));
+        assert_eq!(panel.lines.len(), 2 + 3 + 2);
+        assert_eq!(panel.lines[2], "CPU 4.00 ms");
+        assert_eq!(panel.line_colors[3], PALETTE[0]);
+    }
+}
@@ -0,0 +1,271 @@
+// src/engine/progress.rs
+//
+// Avance y cancelación de operaciones largas (carga de STL, decimación,
+// horneado de lightmaps). Quien lanza la operación crea un `ProgressToken` y
+// se lo pasa; la operación llama a `step(hecho, total)?` cada tanto, que
+// informa el avance y devuelve `Err(CANCELLED)` si pidieron cancelar, así
+// corta con `?` sin dejar nada a medias (el resultado parcial se descarta).
+//
+// Un token se puede partir (`part`) para que cada etapa de una operación
+// compuesta informe su tramo del total. La función de `with_callback` recibe
+// la fracción global (0..1) a lo sumo cada milésima.
+//
+// `BackgroundTask` corre la operación en un hilo: el visor sigue dibujando,
+// muestra la barra de avance (`overlay`) y con Esc la cancela.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::graphics::text::{ScreenRect, TextPanel};
+
+/// Error con que terminan las operaciones canceladas
+pub const CANCELLED: &str = "Operación cancelada";
+
+/// Capa del `TextOverlay` con la barra de avance de la tarea en curso
+pub const PROGRESS_LAYER: &str = "progreso";
+
+/// Cambio mínimo de la fracción para volver a llamar a la función de avance
+const REPORT_STEP: f32 = 0.001;
+
+const BAR_WIDTH: f32 = 320.0;
+const BAR_HEIGHT: f32 = 8.0;
+
+type Callback = Box<dyn FnMut(f32) + Send>;
+
+struct Shared {
+    cancelled: AtomicBool,
+    /// Fracción global (bits de un f32)
+    fraction: AtomicU32,
+    callback: Mutex<Option<Callback>>,
+}
+
+/// Avance y pedido de cancelación compartidos entre quien lanza y quien trabaja
+#[derive(Clone)]
+pub struct ProgressToken {
+    shared: Arc<Shared>,
+    /// Tramo de la fracción global que cubre este token
+    range: (f32, f32),
+}
+
+impl Default for ProgressToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for ProgressToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ProgressToken")
+            .field("fraction", &self.fraction())
+            .field("cancelled", &self.is_cancelled())
+            .field("range", &self.range)
+            .finish()
+    }
+}
+
+impl ProgressToken {
+    pub fn new() -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                cancelled: AtomicBool::new(false),
+                fraction: AtomicU32::new(0f32.to_bits()),
+                callback: Mutex::new(None),
+            }),
+            range: (0.0, 1.0),
+        }
+    }
+
+    /// Token que además llama a `callback` con la fracción global
+    pub fn with_callback(callback: impl FnMut(f32) + Send + 'static) -> Self {
+        let token = Self::new();
+        *token.shared.callback.lock().unwrap() = Some(Box::new(callback));
+        token
+    }
+
+    /// Token para la parte `index` de `count` partes iguales de este
+    pub fn part(&self, index: usize, count: usize) -> Self {
+        let (start, end) = self.range;
+        let width = (end - start) / count.max(1) as f32;
+        Self { shared: self.shared.clone(), range: (start + width * index as f32, start + width * (index + 1) as f32) }
+    }
+
+    /// Informa que se hicieron `done` de `total` pasos de este tramo
+    pub fn report(&self, done: usize, total: usize) {
+        let local = if total == 0 { 1.0 } else { (done as f32 / total as f32).clamp(0.0, 1.0) };
+        let fraction = self.range.0 + (self.range.1 - self.range.0) * local;
+        let previous = f32::from_bits(self.shared.fraction.load(Ordering::Relaxed));
+        if (fraction - previous).abs() < REPORT_STEP && fraction < self.range.1 {
+            return;
+        }
+        self.shared.fraction.store(fraction.to_bits(), Ordering::Relaxed);
+        if let Some(callback) = self.shared.callback.lock().unwrap().as_mut() {
+            callback(fraction);
+        }
+    }
+
+    /// `report` y después `check`
+    pub fn step(&self, done: usize, total: usize) -> Result<(), String> {
+        self.report(done, total);
+        self.check()
+    }
+
+    /// Fracción global hecha (0..1)
+    pub fn fraction(&self) -> f32 {
+        f32::from_bits(self.shared.fraction.load(Ordering::Relaxed))
+    }
+
+    /// Pide cortar la operación (a este token y a todas sus partes)
+    pub fn cancel(&self) {
+        self.shared.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.shared.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// `Err(CANCELLED)` si pidieron cancelar
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Operación corriendo en un hilo aparte. Al soltarla se cancela y se espera
+/// a que el hilo termine.
+pub struct BackgroundTask<T> {
+    /// Qué se está haciendo, para mostrarlo
+    pub label: String,
+    token: ProgressToken,
+    worker: Option<JoinHandle<Result<T, String>>>,
+}
+
+impl<T: Send + 'static> BackgroundTask<T> {
+    pub fn spawn(label: &str, work: impl FnOnce(&ProgressToken) -> Result<T, String> + Send + 'static) -> Self {
+        let token = ProgressToken::new();
+        let worker = {
+            let token = token.clone();
+            thread::spawn(move || work(&token))
+        };
+        Self { label: label.to_string(), token, worker: Some(worker) }
+    }
+
+    pub fn token(&self) -> &ProgressToken {
+        &self.token
+    }
+
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// El resultado si ya terminó (None mientras corre o si ya se tomó)
+    pub fn try_finish(&mut self) -> Option<Result<T, String>> {
+        if !self.worker.as_ref().is_some_and(|worker| worker.is_finished()) {
+            return None;
+        }
+        let worker = self.worker.take()?;
+        Some(worker.join().unwrap_or_else(|_| Err(format!("{}: la tarea terminó con un error interno", self.label))))
+    }
+
+    /// Etiqueta con porcentaje y barra, centradas abajo en una pantalla de
+    /// `screen` píxeles lógicos
+    pub fn overlay(&self, screen: (f32, f32)) -> (TextPanel, Vec<ScreenRect>) {
+        progress_overlay(&self.label, self.token.fraction(), screen)
+    }
+}
+
+impl<T> Drop for BackgroundTask<T> {
+    fn drop(&mut self) {
+        self.token.cancel();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Panel "`label` 42 % (Esc cancela)" y barra de avance, centrados abajo
+pub fn progress_overlay(label: &str, fraction: f32, screen: (f32, f32)) -> (TextPanel, Vec<ScreenRect>) {
+    let left = ((screen.0 - BAR_WIDTH) * 0.5).max(0.0);
+    let top = screen.1 - 64.0;
+    let fraction = fraction.clamp(0.0, 1.0);
+    let rects = vec![
+        ScreenRect { min: (left, top), max: (left + BAR_WIDTH, top + BAR_HEIGHT), color: [0.0, 0.0, 0.0, 0.7] },
+        ScreenRect {
+            min: (left, top),
+            max: (left + BAR_WIDTH * fraction, top + BAR_HEIGHT),
+            color: [0.3, 0.7, 1.0, 0.9],
+        },
+    ];
+    let panel = TextPanel {
+        position: (left, top - 40.0),
+        ..TextPanel::new(vec![format!("{} {:.0} % (Esc cancela)", label, fraction * 100.0)])
+    };
+    (panel, rects)
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parts_callbacks_and_cancel() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let token = {
+            let seen = seen.clone();
+            ProgressToken::with_callback(move |fraction| seen.lock().unwrap().push(fraction))
+        };
+        // Dos etapas iguales: la segunda informa entre 0.5 y 1
+        let second = token.part(1, 2);
+        second.report(1, 2);
+        assert_eq!(token.fraction(), 0.75);
+        // Cambios menores a una milésima no llaman a la función
+        second.report(1001, 2000);
+        assert_eq!(seen.lock().unwrap().as_slice(), &[0.75]);
+        second.report(2, 2);
+        assert_eq!(seen.lock().unwrap().last(), Some(&1.0));
+
+        assert!(second.step(1, 1).is_ok());
+        token.cancel();
+        assert!(second.is_cancelled());
+        assert_eq!(second.step(1, 1), Err(CANCELLED.to_string()));
+
+        // En un hilo: termina con el resultado o con CANCELLED
+        let mut task = BackgroundTask::spawn("Sumar", |progress| {
+            let mut sum = 0u64;
+            for i in 0..1000 {
+                progress.step(i, 1000)?;
+                sum += i as u64;
+            }
+            Ok(sum)
+        });
+        let result = loop {
+            if let Some(result) = task.try_finish() {
+                break result;
+            }
+            thread::yield_now();
+        };
+        assert_eq!(result, Ok(499500));
+        assert!(task.try_finish().is_none());
+
+        let mut task = BackgroundTask::spawn("Esperar", |progress: &ProgressToken| loop {
+            progress.check()?;
+            thread::yield_now();
+        });
+        task.cancel();
+        let result: Result<(), String> = loop {
+            if let Some(result) = task.try_finish() {
+                break result;
+            }
+            thread::yield_now();
+        };
+        assert_eq!(result, Err(CANCELLED.to_string()));
+
+        let (panel, rects) = progress_overlay("Horneando", 0.5, (800.0, 600.0));
+        assert_eq!(panel.lines, vec!["Horneando 50 % (Esc cancela)".to_string()]);
+        assert_eq!(rects[1].max.0 - rects[1].min.0, BAR_WIDTH * 0.5);
+    }
+}
@@ -0,0 +1,579 @@
+// src/engine/remote.rs
+//
+// Control remoto del visor mientras corre, para herramientas externas y
+// scripts de prueba. Un servidor HTTP mínimo (solo std, en su propio hilo)
+// recibe comandos JSON y el `RemotePlugin` los ejecuta en el hilo principal
+// entre frames, donde está el contexto GL. Se activa con
+// RUST_ENGINE_REMOTE=127.0.0.1:7878 o con `remote` en el script de arranque.
+// No hay autenticación: conviene escuchar solo en 127.0.0.1.
+//
+//   curl -d '{"command": "load_model", "path": "pieza.stl", "frame": true}' http://127.0.0.1:7878/command
+//   curl -d '{"command": "screenshot", "path": "vista.png", "size": [1920, 1080]}' http://127.0.0.1:7878/command
+//   curl -d '{"command": "rotate", "index": 0, "axis": [0, 0, 1], "degrees": 90}' http://127.0.0.1:7878/command
+//   curl -d '{"command": "set_frame", "name": "base", "origin_mm": [500, 0, 0], "rotation_deg": [0, 0, 90]}' http://127.0.0.1:7878/command
+//   curl -d '{"command": "set_pose", "index": 0, "frame": "base", "position_mm": [0, 0, 120]}' http://127.0.0.1:7878/command
+//   curl -d '{"command": "load_model", "path": "ur5/urdf/ur5.urdf"}' http://127.0.0.1:7878/command
+//   curl -d '{"command": "set_joint", "robot": "ur5", "joint": "elbow_joint", "position": 1.57}' http://127.0.0.1:7878/command
+//   curl http://127.0.0.1:7878/status
+//
+// Cada conexión lleva un comando y se responde {"ok": true, "result": ...} o
+// {"ok": false, "error": "..."}. No hay WebSocket ni eventos empujados: el
+// estado se consulta con /status.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::engine::plugin::{EngineContext, Plugin};
+use crate::graphics::camara::CameraPose;
+use crate::graphics::frames::{to_degrees, ReferenceFrame};
+use crate::graphics::render::FOV_Y_DEGREES;
+use crate::graphics::scene::load_model_file_with;
+use crate::graphics::scene_object::{TransformSpace, Weld};
+use crate::math::{matrix_4_by_4::Matrix4, quaternion::Quat, vec3::Vec3};
+
+/// Variable de entorno con la dirección en la que escucha el servidor
+pub const REMOTE_ENV: &str = "RUST_ENGINE_REMOTE";
+
+/// Tamaño máximo del cuerpo de un pedido
+const MAX_BODY: usize = 1 << 20;
+/// Cuánto espera una conexión a que el visor ejecute su comando
+const REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum RemoteCommand {
+    /// Objetos, cámara y modo de dibujo
+    Status,
+    /// Agrega a la escena los objetos de un archivo de modelo o un robot .urdf
+    LoadModel {
+        path: String,
+        /// Encuadrar la escena después de cargar
+        #[serde(default)]
+        frame: bool,
+        /// Soldadura de vértices de los STL: "Off" o {"Distance": 0.001}
+        /// (sin ella, la de `WELD_ENV`)
+        #[serde(default)]
+        weld: Option<Weld>,
+    },
+    /// Quita todos los objetos
+    ClearScene,
+    /// Reemplaza el objeto `index` por sus piezas sueltas (ver `Scene::split_object`)
+    SplitObject { index: usize },
+    /// Junta los objetos `ids` en uno (ver `Scene::merge_objects`); `weld` es
+    /// la distancia a la que se unen los vértices de las costuras
+    MergeObjects {
+        ids: Vec<usize>,
+        #[serde(default)]
+        weld: Option<f32>,
+    },
+    /// Mueve el objeto `index` a `position_mm` (milímetros, cualquiera sea la
+    /// unidad de la escena)
+    SetPosition { index: usize, position_mm: [f32; 3] },
+    /// Mueve el objeto `index` `delta_mm` milímetros en los ejes de `space`
+    Translate {
+        index: usize,
+        delta_mm: [f32; 3],
+        #[serde(default)]
+        space: TransformSpace,
+    },
+    /// Gira el objeto `index` `degrees` grados alrededor de `axis` (en los
+    /// ejes de `space`), por `pivot_mm` (milímetros) o por su punto de giro
+    Rotate {
+        index: usize,
+        axis: [f32; 3],
+        degrees: f32,
+        #[serde(default)]
+        pivot_mm: Option<[f32; 3]>,
+        #[serde(default)]
+        space: TransformSpace,
+    },
+    /// Punto de giro propio del objeto `index`, en coordenadas de su malla
+    /// (null vuelve al centro de su caja)
+    SetPivot { index: usize, pivot: Option<[f32; 3]> },
+    /// Agrega el marco de referencia `name` (o reemplaza al que se llame así)
+    /// con origen en `origin_mm` y los ángulos de Euler `rotation_deg`
+    SetFrame {
+        name: String,
+        origin_mm: [f32; 3],
+        #[serde(default)]
+        rotation_deg: [f32; 3],
+    },
+    RemoveFrame { name: String },
+    /// Posición (milímetros) y giro (ángulos de Euler en grados) del objeto
+    /// `index` en el marco `frame`, o en los ejes de la escena sin él
+    ObjectPose {
+        index: usize,
+        #[serde(default)]
+        frame: Option<String>,
+    },
+    /// Mueve la articulación `joint` del robot `robot` a `position` (radianes
+    /// o metros, como en URDF), recortada a sus límites
+    SetJoint { robot: String, joint: String, position: f32 },
+    /// Lleva la articulación hacia `position` a su velocidad máxima, de a poco
+    /// en cada frame
+    MoveJoint { robot: String, joint: String, position: f32 },
+    /// Reproduce en el robot la trayectoria del CSV `path` (tiempo en la
+    /// primera columna, una articulación por columna)
+    PlayTrajectory {
+        robot: String,
+        path: String,
+        #[serde(default)]
+        looping: bool,
+    },
+    /// Corta la trayectoria y los movimientos en curso del robot
+    StopRobot { robot: String },
+    /// Articulaciones del robot con su tipo, posición y límites
+    Joints { robot: String },
+    /// Lleva la raíz del robot a `position_mm` con el giro `rotation_deg`,
+    /// ambos en el marco `frame` (o en los ejes de la escena sin él)
+    PlaceRobot {
+        robot: String,
+        position_mm: [f32; 3],
+        #[serde(default)]
+        rotation_deg: [f32; 3],
+        #[serde(default)]
+        frame: Option<String>,
+    },
+    /// Pone el objeto `index` en `position_mm` con el giro `rotation_deg`,
+    /// ambos en el marco `frame` (o en los ejes de la escena sin él)
+    SetPose {
+        index: usize,
+        position_mm: [f32; 3],
+        #[serde(default)]
+        rotation_deg: [f32; 3],
+        #[serde(default)]
+        frame: Option<String>,
+    },
+    /// Cámara en `position` mirando a `target`, o con yaw / pitch en grados
+    SetCamera {
+        position: [f32; 3],
+        #[serde(default)]
+        target: Option<[f32; 3]>,
+        #[serde(default)]
+        yaw: f32,
+        #[serde(default)]
+        pitch: f32,
+    },
+    /// Encuadra toda la escena
+    FrameScene,
+    /// Guarda la vista actual (formato según la extensión). Sin `size`, el de la ventana.
+    /// `transparent` fuerza o quita el fondo transparente solo para esta captura.
+    Screenshot {
+        path: String,
+        #[serde(default)]
+        size: Option<(i32, i32)>,
+        #[serde(default)]
+        transparent: Option<bool>,
+    },
+    SetRenderMode { mode: RenderMode },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderMode {
+    Solid,
+    Wireframe,
+    /// Dibujo técnico con líneas ocultas
+    HiddenLine,
+}
+
+/// Un comando recibido y por dónde contestarlo
+struct RemoteRequest {
+    command: RemoteCommand,
+    reply: Sender<Result<Value, String>>,
+}
+
+/// Lee un pedido HTTP/1.1 y devuelve (método, ruta, cuerpo)
+fn read_request(reader: &mut impl BufRead) -> Result<(String, String, Vec<u8>), String> {
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(|e| e.to_string())?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Err(format!("Pedido HTTP inválido: {:?}", line.trim()));
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+
+    let mut length = 0;
+    loop {
+        line.clear();
+        reader.read_line(&mut line).map_err(|e| e.to_string())?;
+        let header = line.trim();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().map_err(|_| format!("Content-Length inválido: {}", value.trim()))?;
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return Err(format!("Cuerpo de {} bytes, el máximo es {}", length, MAX_BODY));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).map_err(|e| e.to_string())?;
+    Ok((method, path, body))
+}
+
+fn write_response(stream: &mut TcpStream, status: &str, body: &Value) {
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    // El cliente pudo cerrar la conexión; no hay a quién avisarle
+    let _ = stream.write_all(response.as_bytes());
+}
+
+/// Atiende una conexión: pasa el comando al visor y espera su resultado
+fn handle_connection(mut stream: TcpStream, requests: &Sender<RemoteRequest>) {
+    let _ = stream.set_read_timeout(Some(REPLY_TIMEOUT));
+    let parsed = stream
+        .try_clone()
+        .map_err(|e| e.to_string())
+        .and_then(|clone| read_request(&mut BufReader::new(clone)));
+    let command = match parsed {
+        Ok((method, path, _)) if method == "GET" && path == "/status" => Ok(RemoteCommand::Status),
+        Ok((method, path, body)) if method == "POST" && path == "/command" => {
+            serde_json::from_slice(&body).map_err(|e| format!("Comando inválido: {}", e))
+        }
+        Ok((method, path, _)) => {
+            let error = format!("Ruta desconocida: {} {} (usar POST /command o GET /status)", method, path);
+            write_response(&mut stream, "404 Not Found", &json!({ "ok": false, "error": error }));
+            return;
+        }
+        Err(e) => Err(e),
+    };
+    let command = match command {
+        Ok(command) => command,
+        Err(e) => {
+            write_response(&mut stream, "400 Bad Request", &json!({ "ok": false, "error": e }));
+            return;
+        }
+    };
+
+    let (reply, result) = channel();
+    if requests.send(RemoteRequest { command, reply }).is_err() {
+        write_response(&mut stream, "503 Service Unavailable", &json!({ "ok": false, "error": "El visor se cerró" }));
+        return;
+    }
+    match result.recv_timeout(REPLY_TIMEOUT) {
+        Ok(Ok(value)) => write_response(&mut stream, "200 OK", &json!({ "ok": true, "result": value })),
+        Ok(Err(e)) => write_response(&mut stream, "422 Unprocessable Entity", &json!({ "ok": false, "error": e })),
+        Err(_) => write_response(
+            &mut stream,
+            "504 Gateway Timeout",
+            &json!({ "ok": false, "error": "El visor no respondió a tiempo" }),
+        ),
+    }
+}
+
+/// Abre el puerto y atiende conexiones en un hilo; los comandos salen por el Receiver
+fn spawn_server(address: &str) -> Result<(SocketAddr, Receiver<RemoteRequest>), String> {
+    let listener = TcpListener::bind(address).map_err(|e| format!("No se pudo escuchar en {}: {}", address, e))?;
+    let local = listener.local_addr().map_err(|e| e.to_string())?;
+    let (sender, receiver) = channel();
+    std::thread::Builder::new()
+        .name("remote".to_string())
+        .spawn(move || {
+            for stream in listener.incoming().flatten() {
+                handle_connection(stream, &sender);
+            }
+        })
+        .map_err(|e| format!("No se pudo crear el hilo del servidor remoto: {}", e))?;
+    Ok((local, receiver))
+}
+
+/// Ejecuta en el visor los comandos que llegan al servidor
+pub struct RemotePlugin {
+    address: String,
+    requests: Option<Receiver<RemoteRequest>>,
+}
+
+impl RemotePlugin {
+    pub fn new(address: &str) -> Self {
+        Self { address: address.to_string(), requests: None }
+    }
+
+    /// Plugin de RUST_ENGINE_REMOTE, si está definida
+    pub fn from_env() -> Option<Self> {
+        std::env::var(REMOTE_ENV).ok().map(|address| Self::new(&address))
+    }
+
+    fn execute(ctx: &mut EngineContext, command: RemoteCommand) -> Result<Value, String> {
+        match command {
+            RemoteCommand::Status => {
+                let settings = ctx.renderer.settings();
+                let mode = if settings.hidden_line {
+                    RenderMode::HiddenLine
+                } else if settings.wireframe {
+                    RenderMode::Wireframe
+                } else {
+                    RenderMode::Solid
+                };
+                let pose = ctx.camera.pose();
+                let objects: Vec<&str> = ctx.scene.objects.iter().map(|obj| obj.name.as_str()).collect();
+                let frames: Vec<&str> = ctx.scene.frames.iter().map(|frame| frame.name.as_str()).collect();
+                let robots: Vec<&str> = ctx.scene.robots.iter().map(|robot| robot.name.as_str()).collect();
+                Ok(json!({
+                    "objects": objects,
+                    "frames": frames,
+                    "robots": robots,
+                    "camera": {
+                        "position": [pose.position.x, pose.position.y, pose.position.z],
+                        "yaw": pose.yaw.to_degrees(),
+                        "pitch": pose.pitch.to_degrees(),
+                    },
+                    "render_mode": mode,
+                }))
+            }
+            RemoteCommand::LoadModel { path, frame, weld } => {
+                let before = ctx.scene.objects.len();
+                if path.to_lowercase().ends_with(".urdf") {
+                    ctx.scene.load_urdf(&path)?;
+                } else {
+                    ctx.scene.objects.extend(load_model_file_with(&path, weld.unwrap_or_else(Weld::from_env))?);
+                }
+                let count = ctx.scene.objects.len() - before;
+                if frame {
+                    Self::frame_scene(ctx);
+                }
+                Ok(json!({ "loaded": count, "objects": ctx.scene.objects.len() }))
+            }
+            RemoteCommand::ClearScene => {
+                ctx.scene.objects.clear();
+                ctx.scene.robots.clear();
+                Ok(Value::Null)
+            }
+            RemoteCommand::SplitObject { index } => {
+                let pieces = ctx.scene.split_object(index)?;
+                Ok(json!({ "pieces": pieces, "objects": ctx.scene.objects.len() }))
+            }
+            RemoteCommand::MergeObjects { ids, weld } => {
+                let index = ctx.scene.merge_objects(&ids, weld)?;
+                Ok(json!({ "index": index, "objects": ctx.scene.objects.len() }))
+            }
+            RemoteCommand::SetPosition { index, position_mm } => {
+                let units = ctx.scene.units;
+                let obj = ctx.scene.object_mut(index)?;
+                obj.set_position_mm(Vec3::from(position_mm), units);
+                Ok(json!({ "position": obj.position() }))
+            }
+            RemoteCommand::Translate { index, delta_mm, space } => {
+                let (units, view) = (ctx.scene.units, ctx.camera.get_view_matrix());
+                let obj = ctx.scene.object_mut(index)?;
+                obj.translate_in(space, Vec3::from(delta_mm.map(|mm| units.from_mm(mm))), &view);
+                Ok(json!({ "position": obj.position() }))
+            }
+            RemoteCommand::Rotate { index, axis, degrees, pivot_mm, space } => {
+                let (units, view) = (ctx.scene.units, ctx.camera.get_view_matrix());
+                let pivot = pivot_mm.map(|p| Vec3::from(p.map(|mm| units.from_mm(mm))));
+                let obj = ctx.scene.object_mut(index)?;
+                let axis = obj.space_vector(space, Vec3::from(axis), &view);
+                obj.rotate_degrees(axis, degrees, pivot)?;
+                Ok(json!({ "position": obj.position() }))
+            }
+            RemoteCommand::SetPivot { index, pivot } => {
+                let obj = ctx.scene.object_mut(index)?;
+                obj.pivot = pivot.map(Vec3::from);
+                Ok(json!({ "pivot": obj.pivot_point() }))
+            }
+            RemoteCommand::SetFrame { name, origin_mm, rotation_deg } => {
+                let origin = Vec3::from(origin_mm.map(|mm| ctx.scene.units.from_mm(mm)));
+                let rotation = Quat::from_euler(Vec3::from(rotation_deg.map(f32::to_radians)));
+                ctx.scene.set_frame(ReferenceFrame::new(&name, origin, rotation));
+                Ok(json!({ "frames": ctx.scene.frames.len() }))
+            }
+            RemoteCommand::RemoveFrame { name } => {
+                if !ctx.scene.remove_frame(&name) {
+                    return Err(format!("No existe el marco '{}'", name));
+                }
+                Ok(json!({ "frames": ctx.scene.frames.len() }))
+            }
+            RemoteCommand::ObjectPose { index, frame } => {
+                let frame = ctx.scene.frame_or_scene(frame.as_deref())?;
+                let units = ctx.scene.units;
+                let (position, rotation) = ctx.scene.object(index)?.pose_in(&frame);
+                let position = [position.x, position.y, position.z].map(|length| units.to_mm(length));
+                Ok(json!({ "position_mm": position, "rotation_deg": to_degrees(rotation.to_euler()) }))
+            }
+            RemoteCommand::SetJoint { robot, joint, position } => {
+                let position = ctx.scene.set_joint_position(&robot, &joint, position)?;
+                Ok(json!({ "position": position }))
+            }
+            RemoteCommand::MoveJoint { robot, joint, position } => {
+                let target = ctx.scene.move_joint(&robot, &joint, position)?;
+                Ok(json!({ "target": target }))
+            }
+            RemoteCommand::PlayTrajectory { robot, path, looping } => {
+                let duration = ctx.scene.play_trajectory(&robot, &path, looping)?;
+                Ok(json!({ "duration": duration }))
+            }
+            RemoteCommand::StopRobot { robot } => {
+                ctx.scene.stop_robot(&robot)?;
+                Ok(Value::Null)
+            }
+            RemoteCommand::Joints { robot } => {
+                let joints: Vec<Value> = ctx
+                    .scene
+                    .robot(&robot)?
+                    .joints()
+                    .iter()
+                    .map(|joint| {
+                        json!({
+                            "name": joint.name,
+                            "type": format!("{:?}", joint.kind).to_lowercase(),
+                            "position": joint.position,
+                            "limits": joint.limits,
+                            "velocity": joint.velocity,
+                            "target": joint.target,
+                        })
+                    })
+                    .collect();
+                Ok(json!({ "joints": joints }))
+            }
+            RemoteCommand::PlaceRobot { robot, position_mm, rotation_deg, frame } => {
+                let frame = ctx.scene.frame_or_scene(frame.as_deref())?;
+                let position = Vec3::from(position_mm.map(|mm| ctx.scene.units.from_mm(mm)));
+                let rotation = Quat::from_euler(Vec3::from(rotation_deg.map(f32::to_radians)));
+                let base = frame.absolute(position, rotation, &Matrix4::identity());
+                ctx.scene.set_robot_base(&robot, base)?;
+                Ok(Value::Null)
+            }
+            RemoteCommand::SetPose { index, position_mm, rotation_deg, frame } => {
+                let frame = ctx.scene.frame_or_scene(frame.as_deref())?;
+                let position = Vec3::from(position_mm.map(|mm| ctx.scene.units.from_mm(mm)));
+                let rotation = Quat::from_euler(Vec3::from(rotation_deg.map(f32::to_radians)));
+                let obj = ctx.scene.object_mut(index)?;
+                obj.set_pose_in(&frame, position, rotation);
+                Ok(json!({ "position": obj.position() }))
+            }
+            RemoteCommand::SetCamera { position, target, yaw, pitch } => {
+                let position = Vec3::from(position);
+                let pose = match target.map(Vec3::from) {
+                    Some(target) => {
+                        let offset = position - target;
+                        let direction = offset.try_normalize().ok_or("La cámara no puede estar en el punto que mira")?;
+                        ctx.camera.pivot = target;
+                        CameraPose::orbiting(target, direction, offset.magnitude(), ctx.camera.pose().yaw)
+                    }
+                    None => CameraPose { position, yaw: yaw.to_radians(), pitch: pitch.to_radians() },
+                };
+                ctx.camera.fly_to(pose, 0.0);
+                Ok(Value::Null)
+            }
+            RemoteCommand::FrameScene => {
+                Self::frame_scene(ctx);
+                Ok(Value::Null)
+            }
+            RemoteCommand::Screenshot { path, size, transparent } => {
+                let mut viewport = [0i32; 4];
+                unsafe {
+                    gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+                }
+                let size = size.unwrap_or((viewport[2], viewport[3]));
+                if size.0 <= 0 || size.1 <= 0 {
+                    return Err(format!("Tamaño de captura inválido: {}x{}", size.0, size.1));
+                }
+                let previous = ctx.renderer.settings().transparent_background;
+                ctx.renderer.set_transparent_background(transparent.unwrap_or(previous));
+                let result = ctx.renderer.capture(&ctx.scene.objects, ctx.camera, ctx.global_scale, size, &path);
+                ctx.renderer.set_transparent_background(previous);
+                // La captura deja el viewport del framebuffer propio
+                unsafe {
+                    gl::Viewport(viewport[0], viewport[1], viewport[2], viewport[3]);
+                }
+                result.map(|()| json!({ "path": path, "size": [size.0, size.1] }))
+            }
+            RemoteCommand::SetRenderMode { mode } => {
+                ctx.renderer.set_hidden_line(mode == RenderMode::HiddenLine);
+                ctx.renderer.set_wireframe(mode == RenderMode::Wireframe);
+                Ok(Value::Null)
+            }
+        }
+    }
+
+    fn frame_scene(ctx: &mut EngineContext) {
+        let bounds = ctx.scene.bounds(ctx.global_scale);
+        if !bounds.is_empty() {
+            ctx.camera.frame(&bounds, FOV_Y_DEGREES.to_radians(), 0.0);
+        }
+    }
+}
+
+impl Plugin for RemotePlugin {
+    fn name(&self) -> &str {
+        "remote"
+    }
+
+    fn init(&mut self, _ctx: &mut EngineContext) -> Result<(), String> {
+        let (address, requests) = spawn_server(&self.address)?;
+        println!("Control remoto en http://{}", address);
+        self.requests = Some(requests);
+        Ok(())
+    }
+
+    fn update(&mut self, ctx: &mut EngineContext, _dt: f32) {
+        let Some(requests) = &self.requests else { return };
+        while let Ok(request) = requests.try_recv() {
+            let result = Self::execute(ctx, request.command);
+            // La conexión pudo vencer mientras tanto
+            let _ = request.reply.send(result);
+        }
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_server_roundtrip() {
+        let (address, requests) = spawn_server("127.0.0.1:0").unwrap();
+        let send = move |request: String| {
+            std::thread::spawn(move || {
+                let mut stream = TcpStream::connect(address).unwrap();
+                stream.write_all(request.as_bytes()).unwrap();
+                let mut response = String::new();
+                stream.read_to_string(&mut response).unwrap();
+                response
+            })
+        };
+
+        // El visor (acá, la prueba) recibe el comando ya parseado y contesta
+        let body = r#"{"command": "set_camera", "position": [0, 1, 5], "target": [0, 0, 0]}"#;
+        let client = send(format!("POST /command HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body));
+        let request = requests.recv_timeout(REPLY_TIMEOUT).unwrap();
+        assert_eq!(
+            request.command,
+            RemoteCommand::SetCamera { position: [0.0, 1.0, 5.0], target: Some([0.0; 3]), yaw: 0.0, pitch: 0.0 }
+        );
+        request.reply.send(Ok(json!({ "listo": 1 }))).unwrap();
+        let response = client.join().unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with(r#"{"ok":true,"result":{"listo":1}}"#));
+
+        // Los errores del comando vuelven como 422
+        let client = send("GET /status HTTP/1.1\r\n\r\n".to_string());
+        let request = requests.recv_timeout(REPLY_TIMEOUT).unwrap();
+        assert_eq!(request.command, RemoteCommand::Status);
+        request.reply.send(Err("no".to_string())).unwrap();
+        assert!(client.join().unwrap().starts_with("HTTP/1.1 422"));
+
+        // JSON inválido y rutas desconocidas se contestan sin pasar por el visor
+        let body = r#"{"command": "volar"}"#;
+        let client = send(format!("POST /command HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body));
+        assert!(client.join().unwrap().starts_with("HTTP/1.1 400"));
+        assert!(send("GET /otra HTTP/1.1\r\n\r\n".to_string()).join().unwrap().starts_with("HTTP/1.1 404"));
+        assert!(requests.try_recv().is_err());
+    }
+}
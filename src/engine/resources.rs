@@ -0,0 +1,75 @@
+// src/engine/resources.rs
+//
+// Datos compartidos entre plugins, uno por tipo. Un plugin de física puede
+// publicar su mundo y otro de grabación leerlo sin que se conozcan entre sí.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct Resources {
+    values: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl Resources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Guarda el valor de tipo `T`; devuelve el anterior si había
+    pub fn insert<T: 'static>(&mut self, value: T) -> Option<T> {
+        self.values
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|old| old.downcast().ok())
+            .map(|old| *old)
+    }
+
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.values.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.values.get_mut(&TypeId::of::<T>())?.downcast_mut()
+    }
+
+    /// El valor de tipo `T`, creándolo con `Default` si no existía
+    pub fn get_or_default<T: Default + 'static>(&mut self) -> &mut T {
+        self.values
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(T::default()))
+            .downcast_mut()
+            .expect("el recurso se guarda con su propio TypeId")
+    }
+
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.values.remove(&TypeId::of::<T>())?.downcast().ok().map(|value| *value)
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Gravity(f32);
+
+    #[test]
+    fn test_resources_by_type() {
+        let mut resources = Resources::new();
+        assert!(resources.insert(Gravity(9.8)).is_none());
+        resources.insert(String::from("grabando"));
+        assert_eq!(resources.get::<Gravity>(), Some(&Gravity(9.8)));
+
+        resources.get_mut::<Gravity>().unwrap().0 = 1.6;
+        assert_eq!(resources.insert(Gravity(3.7)), Some(Gravity(1.6)));
+        assert_eq!(resources.remove::<Gravity>(), Some(Gravity(3.7)));
+        assert!(!resources.contains::<Gravity>());
+        assert_eq!(resources.get_or_default::<Gravity>(), &mut Gravity(0.0));
+        assert_eq!(resources.get::<String>().map(String::as_str), Some("grabando"));
+    }
+}
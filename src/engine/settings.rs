@@ -0,0 +1,144 @@
+// src/engine/settings.rs
+//
+// Ajustes del bucle principal. Con la ventana en primer plano se dibuja un
+// frame tras otro (el vsync marca el ritmo); sin el foco se baja a
+// `background_fps` para no gastar CPU ni GPU en algo que nadie mira, y con la
+// ventana minimizada o tapada no se dibuja nada hasta que vuelva a verse.
+//
+// `EditSettings` son los ajustes de la edición de objetos (por ahora, en qué
+// ejes se interpretan los movimientos y giros).
+
+use std::time::{Duration, Instant};
+
+use crate::graphics::scene_object::TransformSpace;
+
+/// Frames por segundo sin el foco si no se indica otra cosa
+pub const DEFAULT_BACKGROUND_FPS: f32 = 10.0;
+
+/// Variable de entorno con los frames por segundo sin el foco (0 pausa)
+pub const BACKGROUND_FPS_ENV: &str = "RUST_ENGINE_BACKGROUND_FPS";
+
+/// Variable de entorno con el espacio de transformación inicial (world, local o screen)
+pub const TRANSFORM_SPACE_ENV: &str = "RUST_ENGINE_TRANSFORM_SPACE";
+
+/// Estado de la ventana para decidir cuándo dibujar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowActivity {
+    Focused,
+    /// Visible pero sin el foco
+    Background,
+    /// Minimizada, tapada o de tamaño 0: no hay nada que dibujar
+    Hidden,
+}
+
+/// Cuándo toca el próximo frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameSchedule {
+    Now,
+    At(Instant),
+    /// Hasta que llegue un evento que cambie el estado de la ventana
+    Paused,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EngineSettings {
+    /// Frames por segundo sin el foco; 0 pausa los frames hasta recuperarlo
+    pub background_fps: f32,
+}
+
+impl Default for EngineSettings {
+    fn default() -> Self {
+        Self { background_fps: DEFAULT_BACKGROUND_FPS }
+    }
+}
+
+impl EngineSettings {
+    /// Lee los frames por segundo sin el foco ("0" pausa)
+    pub fn parse_background_fps(value: &str) -> Result<f32, String> {
+        let fps = value.trim().parse::<f32>().map_err(|e| format!("Frecuencia inválida '{}': {}", value, e))?;
+        if !(fps.is_finite() && fps >= 0.0) {
+            return Err(format!("La frecuencia no puede ser negativa: {}", fps));
+        }
+        Ok(fps)
+    }
+
+    /// Ajustes por defecto con lo que pida el entorno (un valor inválido se
+    /// informa y se ignora)
+    pub fn from_env() -> Self {
+        let mut settings = Self::default();
+        if let Ok(value) = std::env::var(BACKGROUND_FPS_ENV) {
+            match Self::parse_background_fps(&value) {
+                Ok(fps) => settings.background_fps = fps,
+                Err(e) => eprintln!("{} ignorado: {}", BACKGROUND_FPS_ENV, e),
+            }
+        }
+        settings
+    }
+
+    /// Próximo frame según el estado de la ventana y cuándo empezó el último
+    pub fn schedule(&self, activity: WindowActivity, last_frame: Instant, now: Instant) -> FrameSchedule {
+        match activity {
+            WindowActivity::Focused => FrameSchedule::Now,
+            WindowActivity::Hidden => FrameSchedule::Paused,
+            WindowActivity::Background if self.background_fps <= 0.0 => FrameSchedule::Paused,
+            WindowActivity::Background => {
+                let next = last_frame + Duration::from_secs_f32(1.0 / self.background_fps);
+                if next <= now {
+                    FrameSchedule::Now
+                } else {
+                    FrameSchedule::At(next)
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EditSettings {
+    /// Ejes de los movimientos y giros con el teclado (ver `SceneObject::rotate_in`)
+    pub transform_space: TransformSpace,
+}
+
+impl EditSettings {
+    /// Ajustes por defecto con lo que pida el entorno (un valor inválido se
+    /// informa y se ignora)
+    pub fn from_env() -> Self {
+        let mut settings = Self::default();
+        if let Ok(value) = std::env::var(TRANSFORM_SPACE_ENV) {
+            match TransformSpace::parse(&value) {
+                Ok(space) => settings.transform_space = space,
+                Err(e) => eprintln!("{} ignorado: {}", TRANSFORM_SPACE_ENV, e),
+            }
+        }
+        settings
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_background_schedule() {
+        assert_eq!(EngineSettings::parse_background_fps(" 5 "), Ok(5.0));
+        assert_eq!(EngineSettings::parse_background_fps("0"), Ok(0.0));
+        assert!(EngineSettings::parse_background_fps("-1").is_err());
+        assert!(EngineSettings::parse_background_fps("rápido").is_err());
+
+        let settings = EngineSettings { background_fps: 4.0 };
+        let last = Instant::now();
+        let soon = last + Duration::from_millis(100);
+        assert_eq!(settings.schedule(WindowActivity::Focused, last, soon), FrameSchedule::Now);
+        assert_eq!(settings.schedule(WindowActivity::Hidden, last, soon), FrameSchedule::Paused);
+        assert_eq!(
+            settings.schedule(WindowActivity::Background, last, soon),
+            FrameSchedule::At(last + Duration::from_millis(250))
+        );
+        let later = last + Duration::from_millis(300);
+        assert_eq!(settings.schedule(WindowActivity::Background, last, later), FrameSchedule::Now);
+
+        let paused = EngineSettings { background_fps: 0.0 };
+        assert_eq!(paused.schedule(WindowActivity::Background, last, later), FrameSchedule::Paused);
+    }
+}
@@ -0,0 +1,207 @@
+// src/engine/startup.rs
+//
+// Script de arranque para instalaciones sin código propio (p. ej. un kiosco
+// que muestra un producto girando sin parar). Se indica con
+// `rust_engine startup kiosco.ron` o con RUST_ENGINE_STARTUP=kiosco.ron; las
+// rutas relativas se toman desde la carpeta del script.
+//
+// (
+//     scene: Some("producto/"),
+//     environment: Some("estudio.hdr"),
+//     materials: Some("materiales.ron"),
+//     render_presets: Some("presets.ron"),
+//     render_preset: Some("Presentación"),
+//     bookmark: Some("principal"),
+//     animation: Some("presentacion"),
+//     remote: Some("127.0.0.1:7878"),
+//     watch: true,
+//     turntable: Some((speed: 15.0, elevation: 25.0, resume_after: 10.0)),
+//     fullscreen: true,
+//     hide_cursor: true,
+// )
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use winit::event::WindowEvent;
+use winit::window::{Fullscreen, Window};
+
+use crate::engine::plugin::{EngineContext, Plugin};
+use crate::graphics::camara::CameraPose;
+use crate::graphics::render::FOV_Y_DEGREES;
+use crate::math::vec3::Vec3;
+
+/// Variable de entorno con la ruta del script de arranque
+pub const STARTUP_ENV: &str = "RUST_ENGINE_STARTUP";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StartupScript {
+    /// Carpeta de modelos, escena `.ron` o archivo de modelo
+    pub scene: Option<String>,
+    /// Mapa HDR para el cielo y la iluminación
+    pub environment: Option<String>,
+    /// Biblioteca de materiales (ver `graphics::material`)
+    pub materials: Option<String>,
+    /// Presets de render propios (ver `graphics::render_preset`)
+    pub render_presets: Option<String>,
+    /// Preset con el que arranca
+    pub render_preset: Option<String>,
+    /// Bookmark de la escena con el que empieza la cámara
+    pub bookmark: Option<String>,
+    /// Clip que se reproduce en bucle en los objetos que lo tienen
+    pub animation: Option<String>,
+    /// Dirección del control remoto por HTTP (ver `engine::remote`)
+    pub remote: Option<String>,
+    /// Recargar los modelos y la escena cuando cambian en disco (ver `engine::watch`)
+    pub watch: bool,
+    /// Cámara girando alrededor de la escena
+    pub turntable: Option<Turntable>,
+    pub fullscreen: bool,
+    pub hide_cursor: bool,
+}
+
+impl StartupScript {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("No se pudo leer {}: {}", path, e))?;
+        let mut script: Self =
+            ron::from_str(&text).map_err(|e| format!("Script de arranque inválido {}: {}", path, e))?;
+        if let Some(dir) = Path::new(path).parent() {
+            script.resolve_paths(dir);
+        }
+        Ok(script)
+    }
+
+    /// Script de la línea de comandos (`startup <archivo>`) o de RUST_ENGINE_STARTUP
+    pub fn from_args_or_env(args: &[String]) -> Result<Option<Self>, String> {
+        let path = match args.first().map(String::as_str) {
+            Some("startup") => Some(args.get(1).cloned().ok_or("Uso: rust_engine startup <archivo.ron>")?),
+            _ => std::env::var(STARTUP_ENV).ok(),
+        };
+        path.map(|path| Self::load(&path)).transpose()
+    }
+
+    /// Hace absolutas respecto de `dir` las rutas relativas del script
+    fn resolve_paths(&mut self, dir: &Path) {
+        let paths = [&mut self.scene, &mut self.environment, &mut self.materials, &mut self.render_presets];
+        for path in paths.into_iter().flatten() {
+            if Path::new(path.as_str()).is_relative() {
+                *path = dir.join(path.as_str()).to_string_lossy().into_owned();
+            }
+        }
+    }
+
+    /// Pantalla completa y cursor; también al recrear la ventana
+    pub fn apply_window(&self, window: &Window) {
+        if self.fullscreen {
+            window.set_fullscreen(Some(Fullscreen::Borderless(None)));
+        }
+        if self.hide_cursor {
+            window.set_cursor_visible(false);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Turntable {
+    /// Grados por segundo alrededor del eje Y (negativo: sentido horario visto desde arriba)
+    pub speed: f32,
+    /// Altura inicial de la cámara sobre el horizonte, en grados
+    pub elevation: f32,
+    /// Segundos sin teclado ni mouse hasta que vuelve a girar (0: no se detiene)
+    pub resume_after: f32,
+}
+
+impl Default for Turntable {
+    fn default() -> Self {
+        Self { speed: 20.0, elevation: 20.0, resume_after: 10.0 }
+    }
+}
+
+/// Pose girada `angle` radianes alrededor del eje Y que pasa por `pivot`,
+/// mirando al mismo punto que antes
+pub fn orbit_around_y(pose: CameraPose, pivot: Vec3, angle: f32) -> CameraPose {
+    let (sin, cos) = angle.sin_cos();
+    let offset = pose.position - pivot;
+    let rotated = Vec3::new(offset.x * cos + offset.z * sin, offset.y, offset.z * cos - offset.x * sin);
+    CameraPose { position: pivot + rotated, yaw: pose.yaw + angle, pitch: pose.pitch }
+}
+
+/// Gira la cámara alrededor del pivot; se detiene mientras alguien la usa
+pub struct TurntablePlugin {
+    settings: Turntable,
+    /// Segundos desde el último input
+    idle: f32,
+}
+
+impl TurntablePlugin {
+    pub fn new(settings: Turntable) -> Self {
+        Self { settings, idle: settings.resume_after }
+    }
+}
+
+impl Plugin for TurntablePlugin {
+    fn name(&self) -> &str {
+        "turntable"
+    }
+
+    /// Encuadra la escena desde el frente, a la altura pedida
+    fn init(&mut self, ctx: &mut EngineContext) -> Result<(), String> {
+        let bounds = ctx.scene.bounds(ctx.global_scale);
+        if bounds.is_empty() {
+            return Ok(());
+        }
+        let elevation = self.settings.elevation.to_radians();
+        let direction = Vec3::new(0.0, elevation.sin(), elevation.cos());
+        ctx.camera.orbit_to(direction, bounds.center(), 0.0);
+        ctx.camera.frame(&bounds, FOV_Y_DEGREES.to_radians(), 0.0);
+        Ok(())
+    }
+
+    fn update(&mut self, ctx: &mut EngineContext, dt: f32) {
+        self.idle += dt;
+        let paused = self.settings.resume_after > 0.0 && self.idle < self.settings.resume_after;
+        if paused || ctx.camera.is_animating() {
+            return;
+        }
+        let pose = orbit_around_y(ctx.camera.pose(), ctx.camera.pivot, self.settings.speed.to_radians() * dt);
+        ctx.camera.fly_to(pose, 0.0);
+    }
+
+    fn event(&mut self, _ctx: &mut EngineContext, event: &WindowEvent) {
+        if matches!(
+            event,
+            WindowEvent::KeyboardInput { .. } | WindowEvent::MouseInput { .. } | WindowEvent::MouseWheel { .. }
+        ) {
+            self.idle = 0.0;
+        }
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_and_turntable_orbit() {
+        let mut script: StartupScript = ron::from_str(
+            r#"(scene: Some("producto/"), environment: Some("/hdr/estudio.hdr"), turntable: Some((speed: 30.0)), fullscreen: true)"#,
+        )
+        .unwrap();
+        assert_eq!(script.turntable, Some(Turntable { speed: 30.0, ..Turntable::default() }));
+        assert!(!script.hide_cursor);
+        script.resolve_paths(Path::new("kiosco"));
+        assert_eq!(script.scene.as_deref(), Some(Path::new("kiosco").join("producto/").to_str().unwrap()));
+        assert_eq!(script.environment.as_deref(), Some("/hdr/estudio.hdr"));
+
+        // Un cuarto de vuelta desde el frente deja la cámara a la derecha, mirando al pivot
+        let pivot = Vec3::new(1.0, 2.0, 0.0);
+        let front = CameraPose { position: Vec3::new(1.0, 3.0, 5.0), yaw: 0.0, pitch: -0.2 };
+        let side = orbit_around_y(front, pivot, std::f32::consts::FRAC_PI_2);
+        assert!((side.position - Vec3::new(6.0, 3.0, 0.0)).magnitude() < 1e-5);
+        assert_eq!(side.yaw, std::f32::consts::FRAC_PI_2);
+        assert_eq!(side.pitch, -0.2);
+    }
+}
@@ -0,0 +1,111 @@
+// src/engine/time.rs
+//
+// Reloj de frames. En modo normal el paso es el tiempo real entre frames; en
+// modo determinista (`RUST_ENGINE_DETERMINISTIC=<semilla>[:<hz>]`) cada frame
+// avanza un paso fijo y el Rng compartido de los plugins arranca de la
+// semilla, así dos corridas graban exactamente la misma secuencia de frames
+// sin importar la máquina ni la carga del sistema.
+
+use std::time::Instant;
+
+/// Frecuencia del paso fijo si no se indica otra
+pub const DEFAULT_FIXED_HZ: f32 = 60.0;
+
+/// Variable de entorno que activa el modo determinista
+pub const DETERMINISM_ENV: &str = "RUST_ENGINE_DETERMINISTIC";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Determinism {
+    /// Semilla del Rng compartido
+    pub seed: u64,
+    /// Segundos que avanza cada frame
+    pub timestep: f32,
+}
+
+impl Determinism {
+    /// Lee "<semilla>" o "<semilla>:<hz>"
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let (seed, hz) = match value.split_once(':') {
+            Some((seed, hz)) => (seed, hz.trim().parse::<f32>().map_err(|e| format!("Frecuencia inválida '{}': {}", hz, e))?),
+            None => (value, DEFAULT_FIXED_HZ),
+        };
+        let seed = seed.trim().parse::<u64>().map_err(|e| format!("Semilla inválida '{}': {}", seed, e))?;
+        if !(hz.is_finite() && hz > 0.0) {
+            return Err(format!("La frecuencia tiene que ser positiva: {}", hz));
+        }
+        Ok(Self { seed, timestep: 1.0 / hz })
+    }
+
+    /// Configuración pedida en el entorno, si hay (un valor inválido se informa y se ignora)
+    pub fn from_env() -> Option<Self> {
+        let value = std::env::var(DETERMINISM_ENV).ok()?;
+        match Self::parse(&value) {
+            Ok(determinism) => Some(determinism),
+            Err(e) => {
+                eprintln!("{} ignorado: {}", DETERMINISM_ENV, e);
+                None
+            }
+        }
+    }
+}
+
+/// Cuenta los frames y entrega el paso de cada uno
+pub struct FrameClock {
+    fixed: Option<f32>,
+    last: Instant,
+    frame: u64,
+    time: f64,
+}
+
+impl FrameClock {
+    pub fn new(determinism: Option<&Determinism>) -> Self {
+        Self { fixed: determinism.map(|d| d.timestep), last: Instant::now(), frame: 0, time: 0.0 }
+    }
+
+    /// Pasa al próximo frame y devuelve los segundos a simular
+    pub fn tick(&mut self) -> f32 {
+        let now = Instant::now();
+        let dt = self.fixed.unwrap_or_else(|| (now - self.last).as_secs_f32());
+        self.last = now;
+        self.frame += 1;
+        self.time += dt as f64;
+        dt
+    }
+
+    /// Frames transcurridos
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Tiempo simulado en segundos (en modo determinista, frames * paso)
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        self.fixed.is_some()
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_timestep() {
+        let determinism = Determinism::parse("42:30").unwrap();
+        assert_eq!(determinism.seed, 42);
+        assert_eq!(Determinism::parse("7").unwrap().timestep, 1.0 / DEFAULT_FIXED_HZ);
+        assert!(Determinism::parse("7:0").is_err());
+        assert!(Determinism::parse("semilla").is_err());
+
+        let mut clock = FrameClock::new(Some(&determinism));
+        assert!(clock.is_deterministic());
+        for _ in 0..30 {
+            assert_eq!(clock.tick(), 1.0 / 30.0);
+        }
+        assert_eq!(clock.frame(), 30);
+        assert!((clock.time() - 1.0).abs() < 1e-5);
+    }
+}
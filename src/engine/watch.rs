@@ -0,0 +1,227 @@
+// src/engine/watch.rs
+//
+// Modo watch: vuelve a cargar los modelos y la escena `.ron` cuando cambian
+// en disco, así exportar un STL nuevo desde el CAD actualiza la vista sin
+// reiniciar. Se activa con RUST_ENGINE_WATCH=1 o `watch: true` en el script
+// de arranque.
+//
+// Se revisa la fecha de modificación de los archivos cada medio segundo (sin
+// dependencias de notificaciones del sistema). Un cambio se aplica recién
+// cuando el archivo dejó de cambiar entre dos revisiones, para no leer un STL
+// a medio escribir. Un modelo cambiado solo reemplaza la malla de sus objetos:
+// posición, escala, color y material se conservan. Si cambia la escena `.ron`
+// se vuelve a abrir entera (las transformaciones vienen de ella).
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::engine::plugin::{EngineContext, Plugin};
+use crate::graphics::scene::{load_mesh_file_with, source_file, Scene};
+
+/// Variable de entorno que activa el modo watch
+pub const WATCH_ENV: &str = "RUST_ENGINE_WATCH";
+
+/// Segundos entre revisiones
+const POLL_INTERVAL: f32 = 0.5;
+
+/// Fecha y tamaño de un archivo (None si no se puede leer)
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Fechas de modificación de un conjunto de archivos
+#[derive(Debug, Default)]
+pub struct FileWatcher {
+    /// Último estado aplicado y, si cambió, el nuevo que todavía se está escribiendo
+    files: HashMap<PathBuf, (Stamp, Option<Stamp>)>,
+}
+
+impl FileWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Empieza a vigilar `path` (si ya estaba, no hace nada)
+    pub fn watch(&mut self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        if !self.files.contains_key(path) {
+            self.files.insert(path.to_path_buf(), (stamp(path), None));
+        }
+    }
+
+    /// Deja de vigilar los archivos que no cumplen `keep`
+    pub fn retain(&mut self, mut keep: impl FnMut(&Path) -> bool) {
+        self.files.retain(|path, _| keep(path));
+    }
+
+    pub fn is_watching(&self, path: impl AsRef<Path>) -> bool {
+        self.files.contains_key(path.as_ref())
+    }
+
+    /// Archivos que cambiaron y ya no están cambiando
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for (path, (applied, pending)) in &mut self.files {
+            let current = stamp(path);
+            if current == *applied {
+                *pending = None;
+            } else if *pending == Some(current) {
+                // Igual que en la revisión anterior: terminó de escribirse
+                *applied = current;
+                *pending = None;
+                // Borrado: se espera a que vuelva a aparecer
+                if current.is_some() {
+                    changed.push(path.clone());
+                }
+            } else {
+                *pending = Some(current);
+            }
+        }
+        changed.sort();
+        changed
+    }
+}
+
+/// Vuelve a cargar la escena y los modelos que cambian en disco
+pub struct WatchPlugin {
+    /// Escena `.ron` abierta, si hay
+    scene_file: Option<PathBuf>,
+    watcher: FileWatcher,
+    elapsed: f32,
+}
+
+impl WatchPlugin {
+    pub fn new(scene_file: Option<&str>) -> Self {
+        Self { scene_file: scene_file.map(PathBuf::from), watcher: FileWatcher::new(), elapsed: 0.0 }
+    }
+
+    /// true si RUST_ENGINE_WATCH=1
+    pub fn enabled_by_env() -> bool {
+        std::env::var(WATCH_ENV).is_ok_and(|value| value == "1")
+    }
+
+    /// Vigila exactamente la escena y los archivos de los objetos actuales
+    fn sync_files(&mut self, scene: &Scene) {
+        let mut files: Vec<PathBuf> = scene
+            .objects
+            .iter()
+            .filter_map(|obj| obj.source.as_deref())
+            .map(|source| PathBuf::from(source_file(source)))
+            .collect();
+        files.extend(self.scene_file.clone());
+        self.watcher.retain(|path| files.iter().any(|file| file == path));
+        for file in files {
+            self.watcher.watch(file);
+        }
+    }
+
+    fn reload_scene(ctx: &mut EngineContext, path: &Path) {
+        match Scene::open(&path.to_string_lossy()) {
+            Ok(scene) => {
+                *ctx.scene = scene;
+                println!("Escena recargada: {}", path.display());
+            }
+            Err(e) => eprintln!("No se pudo recargar la escena: {}", e),
+        }
+    }
+
+    /// Reemplaza la malla de los objetos que vienen de `path`
+    fn reload_model(ctx: &mut EngineContext, path: &Path) {
+        let mut reloaded = 0;
+        for obj in &mut ctx.scene.objects {
+            let Some(source) = obj.source.clone() else { continue };
+            if Path::new(source_file(&source)) != path {
+                continue;
+            }
+            match load_mesh_file_with(&source, obj.weld) {
+                Ok(fresh) => {
+                    obj.set_mesh(fresh.mesh);
+                    reloaded += 1;
+                }
+                Err(e) => eprintln!("No se pudo recargar {}: {}", obj.name, e),
+            }
+        }
+        if reloaded > 0 {
+            println!("{} recargado ({} objetos)", path.display(), reloaded);
+        }
+    }
+}
+
+impl Plugin for WatchPlugin {
+    fn name(&self) -> &str {
+        "watch"
+    }
+
+    fn init(&mut self, ctx: &mut EngineContext) -> Result<(), String> {
+        self.sync_files(ctx.scene);
+        println!("Modo watch: se recargan los archivos que cambien");
+        Ok(())
+    }
+
+    fn update(&mut self, ctx: &mut EngineContext, dt: f32) {
+        self.elapsed += dt;
+        if self.elapsed < POLL_INTERVAL {
+            return;
+        }
+        self.elapsed = 0.0;
+        // Los objetos pudieron cambiar (carga remota, otros plugins)
+        self.sync_files(ctx.scene);
+        let changed = self.watcher.poll();
+        if changed.is_empty() {
+            return;
+        }
+        if let Some(scene_file) = self.scene_file.clone().filter(|file| changed.contains(file)) {
+            // La escena nueva ya trae los modelos actuales
+            Self::reload_scene(ctx, &scene_file);
+            self.sync_files(ctx.scene);
+            return;
+        }
+        for path in changed {
+            Self::reload_model(ctx, &path);
+        }
+        // La vista de UVs se armó con las mallas anteriores
+        ctx.renderer.uv_layout().refresh();
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_changes_settle_before_reporting() {
+        let dir = std::env::temp_dir().join(format!("rust_engine_watch_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("pieza.stl");
+        std::fs::write(&path, "solid a").unwrap();
+
+        let mut watcher = FileWatcher::new();
+        watcher.watch(&path);
+        assert!(watcher.is_watching(&path));
+        assert!(watcher.poll().is_empty());
+
+        // Se reporta en la revisión siguiente a la que vio el cambio, una sola vez
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10)).unwrap();
+        assert!(watcher.poll().is_empty());
+        assert_eq!(watcher.poll(), vec![path.clone()]);
+        assert!(watcher.poll().is_empty());
+
+        // Borrado no es un cambio
+        std::fs::remove_file(&path).unwrap();
+        watcher.poll();
+        assert!(watcher.poll().is_empty());
+        watcher.retain(|_| false);
+        assert!(!watcher.is_watching(&path));
+
+        assert_eq!(source_file("caja.step#2"), "caja.step");
+        assert_eq!(source_file("pieza#a.stl"), "pieza#a.stl");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
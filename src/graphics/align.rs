@@ -0,0 +1,296 @@
+// src/graphics/align.rs
+//
+// Alinear una pieza con otra a partir de dos elementos elegidos con el
+// picking de sub-objetos, para armar un conjunto sin tipear matrices. El
+// primer elemento es de la pieza que se mueve y el segundo de la que queda
+// quieta:
+//
+// - Caras: la cara de la primera queda apoyada sobre la de la segunda
+//   (coplanares y enfrentadas); solo se corre a lo largo de la normal.
+// - Ejes: la superficie curva bajo cada click se ajusta a un cilindro y los
+//   ejes quedan alineados; solo se corre perpendicular al eje.
+// - Puntos: el punto elegido de la primera va al de la segunda, sin girar.
+//
+// El cilindro sale de la geometría: desde el triángulo elegido se juntan los
+// vecinos con normales parecidas (la superficie lisa alrededor), el eje es
+// perpendicular a todas esas normales y pasa por donde se cruzan.
+
+use std::collections::HashSet;
+
+use crate::graphics::mesh::Mesh;
+use crate::graphics::picking::{SubObjectElement, SubObjectHit};
+use crate::graphics::scene_object::SceneObject;
+use crate::math::{matrix_4_by_4::Matrix4, quaternion::Quat, vec3::Vec3};
+
+/// Ángulo máximo entre normales de triángulos vecinos de una superficie lisa
+const SMOOTH_ANGLE_DEGREES: f32 = 30.0;
+/// Normales que difieren menos que esto se consideran la misma (cara plana)
+const FLAT_ANGLE_DEGREES: f32 = 5.0;
+/// Cuánto puede apartarse una normal de ser perpendicular al eje (seno)
+const CYLINDER_TOLERANCE: f32 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mate {
+    /// Caras coplanares y enfrentadas
+    Coplanar,
+    /// Ejes de cilindros alineados
+    Concentric,
+    /// Un punto sobre otro
+    Coincident,
+}
+
+impl Mate {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Coplanar => "caras",
+            Self::Concentric => "ejes",
+            Self::Coincident => "puntos",
+        }
+    }
+}
+
+/// Referencia geométrica en espacio mundo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Feature {
+    /// Un punto de la cara y su normal
+    Plane { point: Vec3, normal: Vec3 },
+    /// Un punto del eje, su dirección (unitaria) y el radio
+    Cylinder { point: Vec3, axis: Vec3, radius: f32 },
+    Point(Vec3),
+}
+
+impl Feature {
+    /// En una línea, para el log
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Plane { point, normal } => format!(
+                "cara en ({:.3}, {:.3}, {:.3}) normal ({:.3}, {:.3}, {:.3})",
+                point.x, point.y, point.z, normal.x, normal.y, normal.z
+            ),
+            Self::Cylinder { point, axis, radius } => format!(
+                "cilindro de radio {:.3} por ({:.3}, {:.3}, {:.3}) eje ({:.3}, {:.3}, {:.3})",
+                radius, point.x, point.y, point.z, axis.x, axis.y, axis.z
+            ),
+            Self::Point(point) => format!("punto ({:.3}, {:.3}, {:.3})", point.x, point.y, point.z),
+        }
+    }
+
+    /// Referencia que usa `mate` en el elemento elegido del objeto
+    pub fn detect(obj: &SceneObject, hit: &SubObjectHit, mate: Mate, global_scale: f32) -> Result<Self, String> {
+        let matrix = obj.model_matrix(global_scale);
+        match (mate, hit.element) {
+            (Mate::Coincident, _) => Ok(Self::Point(hit.point)),
+            (Mate::Coplanar, SubObjectElement::Face(face)) => {
+                let (normal, point) = world_face(&obj.mesh, &matrix, face)
+                    .ok_or_else(|| format!("{}: la cara {} es degenerada", obj.name, face))?;
+                Ok(Self::Plane { point, normal })
+            }
+            (Mate::Concentric, SubObjectElement::Face(face)) => fit_cylinder(&obj.mesh, &matrix, face)
+                .map_err(|e| format!("{}: {}", obj.name, e)),
+            _ => Err(format!("Para alinear {} hay que elegir caras", mate.name())),
+        }
+    }
+}
+
+/// Normal unitaria y centro del triángulo en espacio mundo
+fn world_face(mesh: &Mesh, matrix: &Matrix4, face: usize) -> Option<(Vec3, Vec3)> {
+    let [a, b, c] = mesh.triangle(face)?.map(|v| matrix.transform_point(mesh.position(v)));
+    let normal = (b - a).cross(&(c - a)).try_normalize()?;
+    Some((normal, (a + b + c) / 3.0))
+}
+
+/// Cilindro que mejor ajusta la superficie lisa alrededor de `face`
+fn fit_cylinder(mesh: &Mesh, matrix: &Matrix4, face: usize) -> Result<Feature, String> {
+    let first = world_face(mesh, matrix, face).ok_or_else(|| format!("la cara {} es degenerada", face))?;
+    let seed = first.0;
+    let topology = mesh.topology();
+    let smooth = SMOOTH_ANGLE_DEGREES.to_radians().cos();
+    let mut faces = vec![first];
+    let mut vertices = HashSet::new();
+    let mut visited = HashSet::from([face]);
+    let mut stack = vec![(face, seed)];
+    while let Some((current, normal)) = stack.pop() {
+        vertices.extend(mesh.triangle(current).into_iter().flatten());
+        for neighbor in topology.face_neighbors(current) {
+            let neighbor = neighbor as usize;
+            let Some(found) = world_face(mesh, matrix, neighbor) else { continue };
+            if found.0.dot(&normal) >= smooth && visited.insert(neighbor) {
+                faces.push(found);
+                stack.push((neighbor, found.0));
+            }
+        }
+    }
+
+    // El eje es perpendicular a la normal elegida y a la más distinta de ella
+    let (widest, _) = faces.iter().copied().min_by(|a, b| a.0.dot(&seed).abs().total_cmp(&b.0.dot(&seed).abs())).unwrap();
+    if widest.dot(&seed).abs() > FLAT_ANGLE_DEGREES.to_radians().cos() {
+        return Err("la cara elegida es plana (para caras planas, alinear caras)".to_string());
+    }
+    let axis = seed.cross(&widest).normalize();
+    if faces.iter().any(|(normal, _)| normal.dot(&axis).abs() > CYLINDER_TOLERANCE) {
+        return Err("la superficie elegida no es cilíndrica".to_string());
+    }
+
+    // Cada triángulo define un plano que contiene al eje (el de su normal y el
+    // eje): el punto del eje es el más cercano a todos, a la altura del centro
+    let center = faces.iter().fold(Vec3::new(0.0, 0.0, 0.0), |sum, &(_, point)| sum + point) / faces.len() as f32;
+    let mut rows = [axis * axis.x, axis * axis.y, axis * axis.z];
+    let mut rhs = axis * axis.dot(&center);
+    for &(normal, point) in &faces {
+        let side = normal.cross(&axis);
+        rows = [rows[0] + side * side.x, rows[1] + side * side.y, rows[2] + side * side.z];
+        rhs += side * side.dot(&point);
+    }
+    let point = solve(rows, rhs).ok_or("no se pudo ubicar el eje del cilindro")?;
+
+    let radius = vertices
+        .iter()
+        .map(|&v| {
+            let offset = matrix.transform_point(mesh.position(v)) - point;
+            (offset - axis * offset.dot(&axis)).magnitude()
+        })
+        .sum::<f32>()
+        / vertices.len().max(1) as f32;
+    Ok(Feature::Cylinder { point, axis, radius })
+}
+
+/// Solución de un sistema simétrico de 3x3 por Cramer (None si es singular)
+fn solve(columns: [Vec3; 3], rhs: Vec3) -> Option<Vec3> {
+    let det = |a: Vec3, b: Vec3, c: Vec3| a.dot(&b.cross(&c));
+    let [a, b, c] = columns;
+    let d = det(a, b, c);
+    if d.abs() < 1e-12 {
+        return None;
+    }
+    Some(Vec3::new(det(rhs, b, c) / d, det(a, rhs, c) / d, det(a, b, rhs) / d))
+}
+
+/// Giro (alrededor del origen) y traslación en espacio mundo que llevan
+/// `moving` sobre `fixed`
+pub fn mate_motion(moving: &Feature, fixed: &Feature) -> Result<(Quat, Vec3), String> {
+    match (*moving, *fixed) {
+        (Feature::Point(from), Feature::Point(to)) => Ok((Quat::IDENTITY, to - from)),
+        (Feature::Plane { point: from, normal: a }, Feature::Plane { point: to, normal: b }) => {
+            let rotation = Quat::from_rotation_arc(a, b * -1.0);
+            let offset = to - rotation.rotate(from);
+            Ok((rotation, b * offset.dot(&b)))
+        }
+        (Feature::Cylinder { point: from, axis: a, .. }, Feature::Cylinder { point: to, axis: b, .. }) => {
+            // Con el menor giro: el eje puede quedar en cualquiera de los dos sentidos
+            let target = if a.dot(&b) < 0.0 { b * -1.0 } else { b };
+            let rotation = Quat::from_rotation_arc(a, target);
+            let offset = to - rotation.rotate(from);
+            Ok((rotation, offset - b * offset.dot(&b)))
+        }
+        _ => Err("Las dos referencias tienen que ser del mismo tipo".to_string()),
+    }
+}
+
+/// Mueve el objeto de `moving` para alinear su elemento con el de `fixed`.
+/// El objeto que se mueve no puede estar en un grupo transformado ni girando
+/// (el cambio se aplica a su `transform`). Devuelve las dos referencias
+/// usadas, descriptas.
+pub fn mate(
+    objects: &mut [SceneObject],
+    moving: &SubObjectHit,
+    fixed: &SubObjectHit,
+    mate: Mate,
+    global_scale: f32,
+) -> Result<String, String> {
+    if moving.object == fixed.object {
+        return Err("Hay que elegir elementos de dos objetos distintos".to_string());
+    }
+    let (Some(obj), Some(other)) = (objects.get(moving.object), objects.get(fixed.object)) else {
+        return Err("El objeto elegido ya no está en la escena".to_string());
+    };
+    if obj.group_transform != Matrix4::identity() || obj.angular_velocity != Vec3::ZERO {
+        return Err(format!("{}: no se puede alinear un objeto girando o en un grupo movido", obj.name));
+    }
+    let from = Feature::detect(obj, moving, mate, global_scale)?;
+    let to = Feature::detect(other, fixed, mate, global_scale)?;
+    let (rotation, translation) = mate_motion(&from, &to)?;
+
+    // La matriz de modelo es la escala global por `transform`: la
+    // traslación del mundo se pasa a la escena dividiéndola por la escala
+    let translation = translation / global_scale;
+    let transform = &mut objects[moving.object].transform;
+    transform.rotate_about(rotation, Vec3::ZERO);
+    transform.position += translation;
+    Ok(format!("{} sobre {}", from.describe(), to.describe()))
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::transform::Transform;
+
+    /// Tubo abierto de radio 1 y largo 2 a lo largo de Z
+    fn tube() -> Mesh {
+        let segments = 16;
+        let mut positions = Vec::new();
+        for i in 0..segments {
+            let angle = i as f32 / segments as f32 * std::f32::consts::TAU;
+            positions.push([angle.cos(), angle.sin(), 0.0]);
+            positions.push([angle.cos(), angle.sin(), 2.0]);
+        }
+        let mut indices = Vec::new();
+        for i in 0..segments {
+            let (a, b) = (2 * i, 2 * ((i + 1) % segments));
+            indices.extend([a, b, b + 1, a, b + 1, a + 1]);
+        }
+        Mesh::new(positions, indices)
+    }
+
+    fn object(mesh: Mesh, base: Matrix4) -> SceneObject {
+        let mut obj = SceneObject::new(0, 0);
+        obj.mesh = mesh;
+        obj.transform = Transform::from_matrix(&base);
+        obj
+    }
+
+    fn face_hit(object: usize) -> SubObjectHit {
+        SubObjectHit { object, element: SubObjectElement::Face(0), point: Vec3::new(0.0, 0.0, 0.0) }
+    }
+
+    #[test]
+    fn test_mates() {
+        // Caras: un triángulo mirando a +Z contra otro en x = 5 mirando a +X
+        let triangle = || Mesh::new(vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]], vec![0, 1, 2]);
+        let facing_x = Matrix4::translate(5.0, 0.0, 0.0).multiply(&Quat::from_axis_angle(Vec3::UNIT_Y, 1.5707964).to_matrix());
+        let mut objects = vec![object(triangle(), Matrix4::translate(0.0, 2.0, 0.0)), object(triangle(), facing_x)];
+        mate(&mut objects, &face_hit(0), &face_hit(1), Mate::Coplanar, 1.0).unwrap();
+        let moved = Feature::detect(&objects[0], &face_hit(0), Mate::Coplanar, 1.0).unwrap();
+        let Feature::Plane { point, normal } = moved else { panic!("{:?}", moved) };
+        assert!(normal.approx_eq(&Vec3::new(-1.0, 0.0, 0.0), 1e-5));
+        assert!((point.x - 5.0).abs() < 1e-5);
+
+        // Ejes: un tubo sobre Z contra otro sobre X que pasa por (0, 3, 0)
+        let along_x = Matrix4::translate(0.0, 3.0, 0.0).multiply(&Quat::from_axis_angle(Vec3::UNIT_Y, 1.5707964).to_matrix());
+        let mut objects = vec![object(tube(), Matrix4::translate(4.0, 0.0, 0.0)), object(tube(), along_x)];
+        let fixed = Feature::detect(&objects[1], &face_hit(1), Mate::Concentric, 1.0).unwrap();
+        let Feature::Cylinder { point, axis, radius } = fixed else { panic!("{:?}", fixed) };
+        assert!(axis.dot(&Vec3::UNIT_X).abs() > 0.9999);
+        assert!((point.y - 3.0).abs() < 1e-4 && point.z.abs() < 1e-4);
+        assert!((radius - 1.0).abs() < 1e-4);
+        mate(&mut objects, &face_hit(0), &face_hit(1), Mate::Concentric, 2.0).unwrap();
+        let Feature::Cylinder { point, axis, .. } = Feature::detect(&objects[0], &face_hit(0), Mate::Concentric, 1.0).unwrap()
+        else {
+            panic!()
+        };
+        assert!(axis.dot(&Vec3::UNIT_X).abs() > 0.9999);
+        assert!((point.y - 3.0).abs() < 1e-4 && point.z.abs() < 1e-4);
+
+        // Puntos, y los errores
+        let mut hits = [face_hit(0), face_hit(1)];
+        hits[1].point = Vec3::new(1.0, 2.0, 3.0);
+        let before = objects[0].position();
+        mate(&mut objects, &hits[0], &hits[1], Mate::Coincident, 1.0).unwrap();
+        assert!(objects[0].position().approx_eq(&(before + Vec3::new(1.0, 2.0, 3.0)), 1e-5));
+        assert!(mate(&mut objects, &hits[0], &hits[0], Mate::Coincident, 1.0).is_err());
+        let edge = SubObjectHit { element: SubObjectElement::Edge(0, 1), ..hits[0] };
+        assert!(mate(&mut objects, &edge, &hits[1], Mate::Coplanar, 1.0).is_err());
+        let flat = object(triangle(), Matrix4::identity());
+        assert!(Feature::detect(&flat, &face_hit(0), Mate::Concentric, 1.0).is_err());
+    }
+}
@@ -0,0 +1,337 @@
+// src/graphics/animation.rs
+//
+// Reproducción de animaciones de un objeto. Un `AnimationClip` junta pistas
+// de pesos de morph targets (ver `graphics::morph`) y de poses de
+// articulaciones (ver `graphics::skeleton`) bajo un nombre; el `Animator` del
+// objeto elige el clip, lo reproduce, pausa, busca y hace fundidos de un clip
+// a otro. Cada frame `SceneObject::advance_animation` avanza el tiempo y
+// escribe los pesos y poses resultantes en el objeto.
+
+use crate::graphics::morph::{MorphAnimation, MAX_MORPH_TARGETS};
+use crate::graphics::skeleton::Skeleton;
+use crate::math::{matrix_4_by_4::Matrix4, quaternion::Quat, vec3::Vec3};
+
+/// Transform local de una articulación en un instante
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointKeyframe {
+    /// Segundos desde el inicio del clip
+    pub time: f32,
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+/// Poses de una articulación, ordenadas por tiempo
+#[derive(Debug, Clone, PartialEq)]
+pub struct JointTrack {
+    pub joint: usize,
+    pub keyframes: Vec<JointKeyframe>,
+}
+
+/// (traslación, rotación, escala)
+type Trs = (Vec3, Quat, Vec3);
+
+impl JointTrack {
+    fn sample(&self, time: f32) -> Option<Trs> {
+        let first = self.keyframes.first()?;
+        let trs = |k: &JointKeyframe| (k.translation, k.rotation, k.scale);
+        if time <= first.time {
+            return Some(trs(first));
+        }
+        let next = self.keyframes.partition_point(|k| k.time <= time);
+        let Some(b) = self.keyframes.get(next) else {
+            return self.keyframes.last().map(trs);
+        };
+        let a = &self.keyframes[next - 1];
+        Some(blend(trs(a), trs(b), (time - a.time) / (b.time - a.time)))
+    }
+}
+
+/// Interpolación lineal de traslación y escala; la rotación por el camino corto
+fn blend(a: Trs, b: Trs, t: f32) -> Trs {
+    let (qa, mut qb) = (a.1, b.1);
+    if qa.x * qb.x + qa.y * qb.y + qa.z * qb.z + qa.w * qb.w < 0.0 {
+        qb = Quat::new(-qb.x, -qb.y, -qb.z, -qb.w);
+    }
+    let rotation = Quat::new(
+        qa.x + (qb.x - qa.x) * t,
+        qa.y + (qb.y - qa.y) * t,
+        qa.z + (qb.z - qa.z) * t,
+        qa.w + (qb.w - qa.w) * t,
+    )
+    .normalize();
+    (a.0 + (b.0 - a.0) * t, rotation, a.2 + (b.2 - a.2) * t)
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AnimationClip {
+    pub name: String,
+    /// Pesos de los morph targets (el `looping` propio se ignora: manda el Animator)
+    pub morph: Option<MorphAnimation>,
+    pub joints: Vec<JointTrack>,
+}
+
+impl AnimationClip {
+    /// Tiempo del último keyframe de todas las pistas
+    pub fn duration(&self) -> f32 {
+        let joints = self.joints.iter().filter_map(|track| track.keyframes.last()).map(|k| k.time);
+        joints.chain(self.morph.as_ref().map(MorphAnimation::duration)).fold(0.0, f32::max)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Playback {
+    clip: usize,
+    time: f32,
+}
+
+/// Fundido en curso desde el clip anterior
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Fade {
+    from: Playback,
+    elapsed: f32,
+    duration: f32,
+}
+
+/// Controles de reproducción de los clips de un objeto
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animator {
+    clips: Vec<AnimationClip>,
+    current: Option<Playback>,
+    fade: Option<Fade>,
+    playing: bool,
+    /// Multiplica el tiempo (negativo: hacia atrás)
+    pub speed: f32,
+    /// Al terminar vuelve a empezar; si no, se detiene en el último cuadro
+    pub looping: bool,
+}
+
+impl Default for Animator {
+    fn default() -> Self {
+        Self { clips: Vec::new(), current: None, fade: None, playing: false, speed: 1.0, looping: true }
+    }
+}
+
+impl Animator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Agrega un clip o reemplaza el del mismo nombre; devuelve su índice
+    pub fn add_clip(&mut self, clip: AnimationClip) -> usize {
+        match self.clips.iter().position(|c| c.name == clip.name) {
+            Some(index) => {
+                self.clips[index] = clip;
+                index
+            }
+            None => {
+                self.clips.push(clip);
+                self.clips.len() - 1
+            }
+        }
+    }
+
+    pub fn clips(&self) -> &[AnimationClip] {
+        &self.clips
+    }
+
+    fn find(&self, name: &str) -> Result<usize, String> {
+        self.clips.iter().position(|c| c.name == name).ok_or_else(|| format!("No hay un clip '{}'", name))
+    }
+
+    /// Reproduce un clip desde el principio, sin fundido
+    pub fn play(&mut self, name: &str) -> Result<(), String> {
+        let clip = self.find(name)?;
+        self.current = Some(Playback { clip, time: 0.0 });
+        self.fade = None;
+        self.playing = true;
+        Ok(())
+    }
+
+    /// Pasa a otro clip mezclándolo con el actual durante `duration` segundos
+    pub fn crossfade(&mut self, name: &str, duration: f32) -> Result<(), String> {
+        let clip = self.find(name)?;
+        match self.current.filter(|_| duration > 0.0) {
+            Some(from) => {
+                self.fade = Some(Fade { from, elapsed: 0.0, duration });
+                self.current = Some(Playback { clip, time: 0.0 });
+                self.playing = true;
+                Ok(())
+            }
+            None => self.play(name),
+        }
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    /// Sigue desde donde quedó (nada si no hay clip elegido)
+    pub fn resume(&mut self) {
+        self.playing = self.current.is_some();
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Nombre del clip elegido
+    pub fn current_clip(&self) -> Option<&str> {
+        self.current.map(|p| self.clips[p.clip].name.as_str())
+    }
+
+    /// Segundos reproducidos del clip elegido
+    pub fn time(&self) -> f32 {
+        self.current.map_or(0.0, |p| p.time)
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.current.map_or(0.0, |p| self.clips[p.clip].duration())
+    }
+
+    /// Salta a `time` segundos del clip elegido; corta un fundido en curso
+    pub fn seek(&mut self, time: f32) {
+        let duration = self.duration();
+        if let Some(current) = &mut self.current {
+            current.time = wrap_time(time, duration, self.looping);
+        }
+        self.fade = None;
+    }
+
+    /// Salta a una posición relativa (0 principio, 1 final), para una barra
+    /// de tiempo; el final no da la vuelta aunque haya bucle
+    pub fn scrub(&mut self, fraction: f32) {
+        let duration = self.duration();
+        if let Some(current) = &mut self.current {
+            current.time = fraction.clamp(0.0, 1.0) * duration;
+        }
+        self.fade = None;
+    }
+
+    /// Avanza el tiempo; sin `looping` se detiene al llegar a un extremo
+    pub fn update(&mut self, dt: f32) {
+        if !self.playing {
+            return;
+        }
+        let step = dt * self.speed;
+        let looping = self.looping;
+        if let Some(fade) = &mut self.fade {
+            let duration = self.clips[fade.from.clip].duration();
+            fade.from.time = wrap_time(fade.from.time + step, duration, looping);
+            fade.elapsed += dt;
+            if fade.elapsed >= fade.duration {
+                self.fade = None;
+            }
+        }
+        let Some(current) = &mut self.current else { return };
+        let duration = self.clips[current.clip].duration();
+        let time = current.time + step;
+        current.time = wrap_time(time, duration, looping);
+        if !looping && (time > duration || time < 0.0) {
+            self.playing = false;
+        }
+    }
+
+    /// Escribe los pesos y poses del instante actual, mezclados si hay fundido.
+    /// Lo que ningún clip anima queda como estaba.
+    pub fn apply(&self, morph_weights: &mut [f32; MAX_MORPH_TARGETS], skeleton: Option<&mut Skeleton>) {
+        let Some(current) = self.current else { return };
+        let (from, t) = match self.fade {
+            Some(fade) => (Some(fade.from), (fade.elapsed / fade.duration).clamp(0.0, 1.0)),
+            None => (None, 1.0),
+        };
+        let morph = |p: Playback| self.clips[p.clip].morph.as_ref().map(|m| m.sample(p.time));
+        let to_weights = morph(current);
+        let from_weights = from.and_then(morph);
+        if to_weights.is_some() || from_weights.is_some() {
+            let a = from_weights.or(to_weights).unwrap_or(*morph_weights);
+            let b = to_weights.unwrap_or(*morph_weights);
+            *morph_weights = std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t);
+        }
+
+        let Some(skeleton) = skeleton else { return };
+        let pose = |p: Playback, joint: usize| {
+            self.clips[p.clip].joints.iter().find(|track| track.joint == joint).and_then(|track| track.sample(p.time))
+        };
+        for joint in 0..skeleton.joints().len() {
+            let to = pose(current, joint);
+            let from = from.and_then(|p| pose(p, joint));
+            if to.is_none() && from.is_none() {
+                continue;
+            }
+            let bind = skeleton.joints()[joint].bind.decompose();
+            let (translation, rotation, scale) = blend(from.or(to).unwrap_or(bind), to.unwrap_or(bind), t);
+            // El índice siempre existe: se recorren las articulaciones del esqueleto
+            skeleton.set_pose(joint, Matrix4::from_trs(translation, rotation, scale)).ok();
+        }
+    }
+}
+
+/// Tiempo dentro de [0, duration]: da la vuelta con `looping`, si no se limita
+pub fn wrap_time(time: f32, duration: f32, looping: bool) -> f32 {
+    if duration <= 0.0 {
+        0.0
+    } else if looping {
+        time.rem_euclid(duration)
+    } else {
+        time.clamp(0.0, duration)
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::morph::MorphKeyframe;
+
+    #[test]
+    fn test_animator_playback_and_crossfade() {
+        let weights = |time, w| MorphKeyframe { time, weights: [w, 0.0, 0.0, 0.0] };
+        let key = |time, x| JointKeyframe { time, translation: Vec3::new(x, 1.0, 0.0), rotation: Quat::IDENTITY, scale: Vec3::new(1.0, 1.0, 1.0) };
+        let mut animator = Animator::new();
+        animator.add_clip(AnimationClip {
+            name: "abrir".to_string(),
+            morph: Some(MorphAnimation { keyframes: vec![weights(0.0, 0.0), weights(2.0, 1.0)], looping: false }),
+            joints: vec![JointTrack { joint: 1, keyframes: vec![key(0.0, 0.0), key(2.0, 2.0)] }],
+        });
+        animator.add_clip(AnimationClip {
+            name: "cerrar".to_string(),
+            morph: Some(MorphAnimation { keyframes: vec![weights(0.0, 0.0), weights(1.0, 0.0)], looping: false }),
+            joints: Vec::new(),
+        });
+        assert!(animator.play("saltar").is_err());
+        animator.play("abrir").unwrap();
+        assert_eq!(animator.duration(), 2.0);
+
+        // Con bucle da la vuelta; sin bucle se detiene en el final
+        animator.update(2.5);
+        assert!((animator.time() - 0.5).abs() < 1e-6);
+        animator.looping = false;
+        animator.speed = 2.0;
+        animator.update(1.0);
+        assert_eq!(animator.time(), 2.0);
+        assert!(!animator.is_playing());
+
+        // Buscar a la mitad aplica pesos y pose interpolados
+        animator.scrub(0.5);
+        let mut skeleton = Skeleton::new();
+        skeleton.add_joint("base", None, Matrix4::identity()).unwrap();
+        skeleton.add_joint("tapa", Some(0), Matrix4::translate(0.0, 1.0, 0.0)).unwrap();
+        let mut morph_weights = [0.0; MAX_MORPH_TARGETS];
+        animator.apply(&mut morph_weights, Some(&mut skeleton));
+        assert_eq!(morph_weights[0], 0.5);
+        assert!((skeleton.joints()[1].pose.m[12] - 1.0).abs() < 1e-5);
+        assert_eq!(skeleton.joints()[0].pose, Matrix4::identity());
+
+        // A mitad del fundido la tapa queda entre la pose del clip y el reposo
+        animator.resume();
+        animator.speed = 0.0;
+        animator.crossfade("cerrar", 1.0).unwrap();
+        animator.update(0.5);
+        animator.apply(&mut morph_weights, Some(&mut skeleton));
+        assert_eq!(animator.current_clip(), Some("cerrar"));
+        assert_eq!(morph_weights[0], 0.25);
+        assert!((skeleton.joints()[1].pose.m[12] - 0.5).abs() < 1e-5);
+        assert!((skeleton.joints()[1].pose.m[13] - 1.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,239 @@
+// src/graphics/batch.rs
+//
+// Procesamiento por lotes sin interacción: un script RON con una lista de
+// operaciones que se aplican en orden a todos los objetos cargados hasta ese
+// momento. Pensado para CI (p. ej. validar los STL que llegan a un repositorio):
+// `rust_engine batch script.ron` termina con código 1 si alguna operación falla.
+//
+// (
+//     operations: [
+//         Load("piezas/"),
+//         Repair(),
+//         Validate(watertight: true, max_triangles: Some(200000)),
+//         Decimate(ratio: 0.5),
+//         BakeTransforms,
+//         Export(output: "salida/"),
+//         Thumbnails(output: "miniaturas/", size: 256),
+//     ],
+// )
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::graphics::camara::Camera;
+use crate::graphics::dataset::{camera_poses, CameraSampling};
+use crate::graphics::mesh_ops::{decimate, edge_report, repair, save_stl, transformed};
+use crate::graphics::render::{Renderer, FOV_Y_DEGREES};
+use crate::graphics::scene::{load_model_file, model_files, Scene};
+use crate::graphics::scene_object::SceneObject;
+use crate::math::{transform::Transform, vec3::Vec3};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BatchOp {
+    /// Archivo de modelo, carpeta de modelos o escena `.ron`; se suman a los ya cargados
+    Load(String),
+    /// Suelda vértices a menos de `tolerance` y quita triángulos degenerados o repetidos
+    Repair {
+        #[serde(default = "default_tolerance")]
+        tolerance: f32,
+    },
+    /// Falla si algún objeto está vacío, tiene bordes abiertos (si `watertight`)
+    /// o supera `max_triangles`
+    Validate {
+        #[serde(default)]
+        watertight: bool,
+        #[serde(default)]
+        max_triangles: Option<usize>,
+    },
+    /// Deja aproximadamente `ratio` de los triángulos de cada objeto
+    Decimate { ratio: f32 },
+    /// Aplica la transformación de cada objeto a sus vértices y la deja en identidad
+    BakeTransforms,
+    /// Un STL binario por objeto en la carpeta `output`
+    Export { output: String },
+    /// Una imagen PNG por objeto en la carpeta `output`
+    Thumbnails {
+        output: String,
+        #[serde(default = "default_thumbnail_size")]
+        size: u32,
+    },
+}
+
+fn default_tolerance() -> f32 {
+    1e-4
+}
+
+fn default_thumbnail_size() -> u32 {
+    256
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchScript {
+    pub operations: Vec<BatchOp>,
+}
+
+impl BatchScript {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("No se pudo leer {}: {}", path, e))?;
+        ron::from_str(&text).map_err(|e| format!("Script de lotes inválido {}: {}", path, e))
+    }
+}
+
+/// Ejecuta el script y devuelve los objetos resultantes. Se detiene en la
+/// primera operación que falla. El renderer solo se usa para las miniaturas.
+pub fn run(renderer: &mut Renderer, script: &BatchScript) -> Result<Vec<SceneObject>, String> {
+    let mut objects = Vec::new();
+    for (step, op) in script.operations.iter().enumerate() {
+        println!("[{}/{}] {:?}", step + 1, script.operations.len(), op);
+        apply(renderer, &mut objects, op)?;
+    }
+    Ok(objects)
+}
+
+fn apply(renderer: &mut Renderer, objects: &mut Vec<SceneObject>, op: &BatchOp) -> Result<(), String> {
+    match op {
+        BatchOp::Load(path) => {
+            let loaded = load(path)?;
+            println!("  {} objetos cargados de {}", loaded.len(), path);
+            objects.extend(loaded);
+        }
+        BatchOp::Repair { tolerance } => {
+            for obj in objects.iter_mut() {
+                let (mesh, report) = repair(&obj.mesh, *tolerance);
+                if !report.is_clean() {
+                    println!(
+                        "  {}: {} vértices soldados, {} triángulos degenerados, {} repetidos",
+                        obj.name, report.welded_vertices, report.degenerate_triangles, report.duplicate_triangles
+                    );
+                    obj.set_mesh(mesh);
+                }
+            }
+        }
+        BatchOp::Validate { watertight, max_triangles } => {
+            let problems = validate(objects, *watertight, *max_triangles);
+            for problem in &problems {
+                println!("  {}", problem);
+            }
+            if !problems.is_empty() {
+                return Err(format!("{} problemas en la validación", problems.len()));
+            }
+        }
+        BatchOp::Decimate { ratio } => {
+            for obj in objects.iter_mut() {
+                let before = obj.mesh.triangle_count();
+                let mesh = decimate(&obj.mesh, *ratio);
+                println!("  {}: {} -> {} triángulos", obj.name, before, mesh.triangle_count());
+                obj.set_mesh(mesh);
+            }
+        }
+        BatchOp::BakeTransforms => {
+            for obj in objects.iter_mut() {
+                let mesh = transformed(&obj.mesh, &obj.model_matrix(1.0));
+                obj.set_mesh(mesh);
+                obj.transform = Transform::identity();
+            }
+        }
+        BatchOp::Export { output } => {
+            fs::create_dir_all(output).map_err(|e| format!("No se pudo crear {}: {}", output, e))?;
+            for (index, obj) in objects.iter().enumerate() {
+                let path = Path::new(output).join(format!("{}.stl", file_name(obj, index)));
+                save_stl(&obj.mesh, &path.to_string_lossy())?;
+            }
+        }
+        BatchOp::Thumbnails { output, size } => {
+            fs::create_dir_all(output).map_err(|e| format!("No se pudo crear {}: {}", output, e))?;
+            let sampling = CameraSampling::Orbit { count: 1, elevations: vec![30.0] };
+            let mut camera = Camera::new(Vec3::ZERO);
+            for (index, obj) in objects.iter().enumerate() {
+                // Cada objeto solo, encuadrado desde adelante y arriba
+                let bounds = obj.world_bounds(1.0);
+                let pose = camera_poses(&sampling, &bounds, FOV_Y_DEGREES.to_radians(), 1.1)[0];
+                camera.position = pose.position;
+                camera.yaw = pose.yaw;
+                camera.pitch = pose.pitch;
+                let path = Path::new(output).join(format!("{}.png", file_name(obj, index)));
+                let size = (*size as i32, *size as i32);
+                renderer.capture(std::slice::from_ref(obj), &camera, 1.0, size, &path.to_string_lossy())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Objetos de un archivo, de una carpeta (sin reacomodar) o de una escena `.ron`
+fn load(path: &str) -> Result<Vec<SceneObject>, String> {
+    if Path::new(path).is_dir() {
+        let mut objects = Vec::new();
+        for file in model_files(path)? {
+            objects.extend(load_model_file(&file.to_string_lossy())?);
+        }
+        Ok(objects)
+    } else if path.to_lowercase().ends_with(".ron") {
+        Ok(Scene::load(path)?.objects)
+    } else {
+        load_model_file(path)
+    }
+}
+
+/// Problemas encontrados, uno por línea
+fn validate(objects: &[SceneObject], watertight: bool, max_triangles: Option<usize>) -> Vec<String> {
+    let mut problems = Vec::new();
+    for obj in objects {
+        let triangles = obj.mesh.triangle_count();
+        if triangles == 0 {
+            problems.push(format!("{}: la malla está vacía", obj.name));
+            continue;
+        }
+        if let Some(max) = max_triangles.filter(|&max| triangles > max) {
+            problems.push(format!("{}: {} triángulos (máximo {})", obj.name, triangles, max));
+        }
+        let edges = edge_report(&obj.mesh);
+        if watertight && !edges.is_watertight() {
+            problems.push(format!(
+                "{}: no es cerrada ({} bordes abiertos, {} no-manifold)",
+                obj.name, edges.open_edges, edges.non_manifold_edges
+            ));
+        }
+    }
+    problems
+}
+
+/// Nombre de archivo de salida del objeto (su nombre o su posición)
+fn file_name(obj: &SceneObject, index: usize) -> String {
+    if obj.name.is_empty() {
+        format!("objeto_{:03}", index)
+    } else {
+        obj.name.clone()
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::mesh::Mesh;
+
+    #[test]
+    fn test_script_from_ron() {
+        let script: BatchScript = ron::from_str(
+            r#"(operations: [Load("piezas/"), Repair(), Validate(watertight: true), BakeTransforms, Export(output: "salida/")])"#,
+        )
+        .unwrap();
+        assert_eq!(script.operations.len(), 5);
+        assert!(matches!(script.operations[1], BatchOp::Repair { tolerance } if tolerance == default_tolerance()));
+    }
+
+    #[test]
+    fn test_validate_reports_problems() {
+        let mut open = SceneObject::new(0, 0);
+        open.name = "triangulo".to_string();
+        open.set_mesh(Mesh::new(vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]], vec![0, 1, 2]));
+        let empty = SceneObject::new(0, 0);
+
+        let objects = [open, empty];
+        assert_eq!(validate(&objects, false, None).len(), 1);
+        assert_eq!(validate(&objects, true, Some(0)).len(), 3);
+    }
+}
@@ -0,0 +1,424 @@
+// src/graphics/bvh.rs
+//
+// Jerarquía de cajas (BVH) por malla, en espacio del objeto. Se construye una
+// vez y sirve aunque el objeto se mueva: el rayo se lleva al espacio del objeto.
+
+use crate::graphics::mesh::Mesh;
+use crate::graphics::scene_object::SceneObject;
+use crate::math::{aabb::{axis, Aabb}, matrix_4_by_4::Matrix4, ray::Ray, vec3::Vec3};
+
+/// Triángulos por hoja como máximo
+const LEAF_SIZE: usize = 4;
+
+#[derive(Debug, Clone)]
+struct BvhNode {
+    bounds: Aabb,
+    /// Hoja: rango `first..first + count` en `Bvh::triangles`.
+    /// Interno (count == 0): el hijo izquierdo es el nodo siguiente y el derecho `first`.
+    first: u32,
+    count: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    /// Índices de triángulos de la malla, reordenados por hoja
+    triangles: Vec<u32>,
+}
+
+/// Resultado de lanzar un rayo contra la escena (todo en espacio mundo)
+#[derive(Debug, Clone, Copy)]
+pub struct RayHit {
+    /// Índice del objeto en la escena
+    pub object_id: usize,
+    pub point: Vec3,
+    /// Normal unitaria de la cara según su orden de vértices (no se da vuelta
+    /// hacia el rayo)
+    pub normal: Vec3,
+    /// Distancia desde el origen del rayo, aunque la dirección no sea unitaria
+    pub distance: f32,
+    /// Índice del triángulo en la malla del objeto
+    pub triangle: usize,
+}
+
+fn triangle_bounds(mesh: &Mesh, triangle: usize) -> Aabb {
+    let mut bounds = Aabb::EMPTY;
+    if let Some(tri) = mesh.triangle(triangle) {
+        for v in tri {
+            bounds.grow(mesh.position(v));
+        }
+    }
+    bounds
+}
+
+impl Bvh {
+    pub fn build(mesh: &Mesh) -> Self {
+        let count = mesh.triangle_count();
+        let mut bvh = Bvh {
+            nodes: Vec::with_capacity(count.max(1) * 2 / LEAF_SIZE + 1),
+            triangles: (0..count as u32).collect(),
+        };
+        if count == 0 {
+            return bvh;
+        }
+        let bounds: Vec<Aabb> = (0..count).map(|i| triangle_bounds(mesh, i)).collect();
+        bvh.build_node(&bounds, 0, count);
+        bvh
+    }
+
+    /// Construye el nodo de `triangles[start..end]` y devuelve su índice
+    fn build_node(&mut self, bounds: &[Aabb], start: usize, end: usize) -> usize {
+        let mut node_bounds = Aabb::EMPTY;
+        let mut centroids = Aabb::EMPTY;
+        for &tri in &self.triangles[start..end] {
+            node_bounds = node_bounds.union(&bounds[tri as usize]);
+            centroids.grow(bounds[tri as usize].center());
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(BvhNode { bounds: node_bounds, first: start as u32, count: (end - start) as u32 });
+        if end - start <= LEAF_SIZE {
+            return index;
+        }
+
+        // Partir por la mediana en el eje más largo de los centroides
+        let split_axis = centroids.largest_axis();
+        let mid = (start + end) / 2;
+        self.triangles[start..end].select_nth_unstable_by(mid - start, |a, b| {
+            let ca = axis(bounds[*a as usize].center(), split_axis);
+            let cb = axis(bounds[*b as usize].center(), split_axis);
+            ca.total_cmp(&cb)
+        });
+
+        self.build_node(bounds, start, mid);
+        let right = self.build_node(bounds, mid, end);
+        self.nodes[index].first = right as u32;
+        self.nodes[index].count = 0;
+        index
+    }
+
+    pub fn bounds(&self) -> Aabb {
+        self.nodes.first().map_or(Aabb::EMPTY, |node| node.bounds)
+    }
+
+    /// Corte más cercano con t <= `max_t`: (t, triángulo)
+    pub fn raycast(&self, mesh: &Mesh, ray: &Ray, max_t: f32) -> Option<(f32, usize)> {
+        let mut best: Option<(f32, usize)> = None;
+        let mut stack = vec![0usize];
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let limit = best.map_or(max_t, |(t, _)| t);
+            match ray.intersect_aabb(&node.bounds) {
+                Some(t) if t <= limit => {}
+                _ => continue,
+            }
+
+            if node.count == 0 {
+                stack.push(node.first as usize);
+                stack.push(index + 1);
+                continue;
+            }
+
+            let first = node.first as usize;
+            for &tri in &self.triangles[first..first + node.count as usize] {
+                let Some([a, b, c]) = mesh.triangle(tri as usize) else { continue };
+                let hit = ray.intersect_triangle(mesh.position(a), mesh.position(b), mesh.position(c));
+                if let Some(t) = hit {
+                    if t <= best.map_or(max_t, |(best_t, _)| best_t) {
+                        best = Some((t, tri as usize));
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// Todos los cortes con t <= `max_t`, ordenados por t: (t, triángulo)
+    pub fn raycast_all(&self, mesh: &Mesh, ray: &Ray, max_t: f32) -> Vec<(f32, usize)> {
+        let mut hits = Vec::new();
+        let mut stack = vec![0usize];
+        if self.nodes.is_empty() {
+            return hits;
+        }
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            match ray.intersect_aabb(&node.bounds) {
+                Some(t) if t <= max_t => {}
+                _ => continue,
+            }
+
+            if node.count == 0 {
+                stack.push(node.first as usize);
+                stack.push(index + 1);
+                continue;
+            }
+
+            let first = node.first as usize;
+            for &tri in &self.triangles[first..first + node.count as usize] {
+                let Some([a, b, c]) = mesh.triangle(tri as usize) else { continue };
+                if let Some(t) = ray.intersect_triangle(mesh.position(a), mesh.position(b), mesh.position(c)) {
+                    if t <= max_t {
+                        hits.push((t, tri as usize));
+                    }
+                }
+            }
+        }
+        hits.sort_by(|a, b| a.0.total_cmp(&b.0));
+        hits
+    }
+
+    /// Punto de la malla más cercano a `point` a menos de `max_distance`:
+    /// (punto, distancia, triángulo)
+    pub fn closest_point(&self, mesh: &Mesh, point: Vec3, max_distance: f32) -> Option<(Vec3, f32, usize)> {
+        let mut best: Option<(Vec3, f32, usize)> = None;
+        let mut best_sq = max_distance * max_distance;
+        let mut stack = vec![0usize];
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if node.bounds.distance_squared(point) > best_sq {
+                continue;
+            }
+
+            if node.count == 0 {
+                // Visitar primero el hijo más cercano para achicar la búsqueda antes
+                let (left, right) = (index + 1, node.first as usize);
+                let dl = self.nodes[left].bounds.distance_squared(point);
+                let dr = self.nodes[right].bounds.distance_squared(point);
+                if dl < dr {
+                    stack.push(right);
+                    stack.push(left);
+                } else {
+                    stack.push(left);
+                    stack.push(right);
+                }
+                continue;
+            }
+
+            let first = node.first as usize;
+            for &tri in &self.triangles[first..first + node.count as usize] {
+                let Some([a, b, c]) = mesh.triangle(tri as usize) else { continue };
+                let closest = closest_point_on_triangle(point, mesh.position(a), mesh.position(b), mesh.position(c));
+                let d = closest - point;
+                let distance_sq = d.dot(&d);
+                if distance_sq <= best_sq {
+                    best_sq = distance_sq;
+                    best = Some((closest, distance_sq.sqrt(), tri as usize));
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Punto del triángulo (a, b, c) más cercano a `p` (por regiones de Voronoi)
+fn closest_point_on_triangle(p: Vec3, a: Vec3, b: Vec3, c: Vec3) -> Vec3 {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = p - a;
+    let d1 = ab.dot(&ap);
+    let d2 = ac.dot(&ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+
+    let bp = p - b;
+    let d3 = ab.dot(&bp);
+    let d4 = ac.dot(&bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+
+    let cp = p - c;
+    let d5 = ab.dot(&cp);
+    let d6 = ac.dot(&cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+
+    // Dentro de la cara
+    let denom = 1.0 / (va + vb + vc);
+    a + ab * (vb * denom) + ac * (vc * denom)
+}
+
+/// Rayo de espacio mundo llevado al espacio del objeto. La dirección se
+/// transforma sin normalizar para conservar el mismo t.
+fn local_ray(inverse: &Matrix4, ray: &Ray) -> Ray {
+    let origin = inverse.transform_point(ray.origin);
+    let [dx, dy, dz, _] = inverse.transform_vec4([ray.direction.x, ray.direction.y, ray.direction.z, 0.0]);
+    Ray::new(origin, Vec3::new(dx, dy, dz))
+}
+
+/// Arma el `RayHit` en espacio mundo de un corte (t, triángulo) con el objeto `object_id`
+fn world_hit(obj: &SceneObject, object_id: usize, inverse: &Matrix4, ray: &Ray, t: f32, triangle: usize) -> RayHit {
+    let mut normal = Vec3::ZERO;
+    if let Some(corners) = obj.mesh.triangle(triangle) {
+        let [a, b, c] = corners.map(|v| obj.mesh.position(v));
+        let (e1, e2) = (b - a, c - a);
+        let n = [e1.y * e2.z - e1.z * e2.y, e1.z * e2.x - e1.x * e2.z, e1.x * e2.y - e1.y * e2.x];
+        // Las normales se transforman con la inversa transpuesta del modelo
+        let world = Vec3::from([0, 1, 2].map(|i| (0..3).map(|j| inverse.m[j + i * 4] * n[j]).sum::<f32>()));
+        let length = world.magnitude();
+        if length > 0.0 {
+            normal = world * (1.0 / length);
+        }
+    }
+    RayHit {
+        object_id,
+        point: ray.at(t),
+        normal,
+        distance: t * ray.direction.magnitude(),
+        triangle,
+    }
+}
+
+/// Corte más cercano del rayo con un objeto, a menos de `max_distance`
+pub fn raycast_object(obj: &SceneObject, object_id: usize, global_scale: f32, ray: &Ray, max_distance: f32) -> Option<RayHit> {
+    let inverse = obj.model_matrix(global_scale).inverse()?;
+    let max_t = max_distance / ray.direction.magnitude();
+    let (t, triangle) = obj.mesh.bvh().raycast(&obj.mesh, &local_ray(&inverse, ray), max_t)?;
+    Some(world_hit(obj, object_id, &inverse, ray, t, triangle))
+}
+
+/// Todos los cortes del rayo con un objeto, ordenados por distancia
+pub fn raycast_all_object(obj: &SceneObject, object_id: usize, global_scale: f32, ray: &Ray) -> Vec<RayHit> {
+    let Some(inverse) = obj.model_matrix(global_scale).inverse() else { return Vec::new() };
+    obj.mesh
+        .bvh()
+        .raycast_all(&obj.mesh, &local_ray(&inverse, ray), f32::INFINITY)
+        .into_iter()
+        .map(|(t, triangle)| world_hit(obj, object_id, &inverse, ray, t, triangle))
+        .collect()
+}
+
+/// Lanza un rayo en espacio mundo contra todos los objetos y devuelve el corte más cercano
+pub fn raycast_objects(objects: &[SceneObject], global_scale: f32, ray: &Ray) -> Option<RayHit> {
+    let mut best: Option<RayHit> = None;
+    for (index, obj) in objects.iter().enumerate() {
+        let max_distance = best.map_or(f32::INFINITY, |hit| hit.distance);
+        if let Some(hit) = raycast_object(obj, index, global_scale, ray, max_distance) {
+            best = Some(hit);
+        }
+    }
+    best
+}
+
+/// Todos los cortes del rayo con los objetos, del más cercano al más lejano
+pub fn raycast_all_objects(objects: &[SceneObject], global_scale: f32, ray: &Ray) -> Vec<RayHit> {
+    let mut hits: Vec<RayHit> = objects
+        .iter()
+        .enumerate()
+        .flat_map(|(index, obj)| raycast_all_object(obj, index, global_scale, ray))
+        .collect();
+    hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    hits
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::transform::Transform;
+
+    /// Rejilla de n x n quads en el plano y = height
+    fn grid(n: u32, height: f32) -> Mesh {
+        let mut positions = Vec::new();
+        let mut indices = Vec::new();
+        for z in 0..=n {
+            for x in 0..=n {
+                positions.push([x as f32, height, z as f32]);
+            }
+        }
+        for z in 0..n {
+            for x in 0..n {
+                let i = z * (n + 1) + x;
+                indices.extend_from_slice(&[i, i + 1, i + n + 1, i + 1, i + n + 2, i + n + 1]);
+            }
+        }
+        Mesh::new(positions, indices)
+    }
+
+    #[test]
+    fn test_bvh_raycast() {
+        let mesh = grid(8, 2.0);
+        let bvh = Bvh::build(&mesh);
+        let ray = Ray::new(Vec3::new(3.3, 10.0, 5.6), Vec3::new(0.0, -1.0, 0.0));
+        let (t, triangle) = bvh.raycast(&mesh, &ray, f32::INFINITY).unwrap();
+        assert!((t - 8.0).abs() < 1e-5);
+        // El triángulo encontrado contiene el punto (3.3, 5.6) en XZ
+        let [a, _, _] = mesh.triangle(triangle).unwrap();
+        let corner = mesh.position(a);
+        assert!((corner.x - 3.3).abs() <= 1.0 && (corner.z - 5.6).abs() <= 1.0);
+
+        let outside = Ray::new(Vec3::new(-3.0, 10.0, 5.0), Vec3::new(0.0, -1.0, 0.0));
+        assert!(bvh.raycast(&mesh, &outside, f32::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_bvh_closest_point() {
+        let mesh = grid(8, 2.0);
+        let bvh = Bvh::build(&mesh);
+        // Encima de la rejilla: el punto más cercano está justo debajo
+        let (point, distance, _) = bvh.closest_point(&mesh, Vec3::new(3.3, 5.0, 5.6), f32::INFINITY).unwrap();
+        assert!((distance - 3.0).abs() < 1e-5);
+        assert!((point - Vec3::new(3.3, 2.0, 5.6)).magnitude() < 1e-5);
+        // Fuera del borde: la esquina más cercana
+        let (point, _, _) = bvh.closest_point(&mesh, Vec3::new(-1.0, 2.0, -1.0), f32::INFINITY).unwrap();
+        assert!((point - Vec3::new(0.0, 2.0, 0.0)).magnitude() < 1e-5);
+        assert!(bvh.closest_point(&mesh, Vec3::new(3.0, 5.0, 3.0), 1.0).is_none());
+    }
+
+    #[test]
+    fn test_raycast_transformed_object() {
+        let mut obj = SceneObject::new(0, 0);
+        obj.mesh = grid(2, 0.0);
+        obj.transform = Transform::from_translation(Vec3::new(0.0, 3.0, 0.0));
+        let ray = Ray::new(Vec3::new(1.0, 10.0, 1.0), Vec3::new(0.0, -1.0, 0.0));
+        let hit = raycast_objects(&[obj], 1.0, &ray).unwrap();
+        assert!((hit.point.y - 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_raycast_all_sorted_with_normals() {
+        // Dos rejillas apiladas, la de arriba escalada: el rayo las cruza a las dos
+        let mut low = SceneObject::new(0, 0);
+        low.mesh = grid(2, 0.0);
+        let mut high = SceneObject::new(0, 0);
+        high.mesh = grid(2, 1.0);
+        high.transform.scale = Vec3::new(2.0, 2.0, 2.0);
+        let objects = [high, low];
+        let ray = Ray::new(Vec3::new(0.3, 10.0, 0.6), Vec3::new(0.0, -2.0, 0.0));
+
+        let hits = raycast_all_objects(&objects, 1.0, &ray);
+        assert_eq!(hits.len(), 2);
+        assert_eq!((hits[0].object_id, hits[1].object_id), (0, 1));
+        assert!((hits[0].distance - 8.0).abs() < 1e-5);
+        assert!((hits[1].distance - 10.0).abs() < 1e-5);
+        // La rejilla está orientada hacia -Y (i, i + 1, i + n + 1)
+        assert!((hits[0].normal - Vec3::new(0.0, -1.0, 0.0)).magnitude() < 1e-5);
+        assert_eq!(raycast_objects(&objects, 1.0, &ray).unwrap().object_id, 0);
+    }
+}
@@ -0,0 +1,118 @@
+// src/graphics/cad_import.cpp
+//
+// Lectura de STEP/IGES y teselado por sólido con OpenCASCADE (ver
+// cad_import.rs). Los errores salen como excepciones, que cxx convierte en el
+// Err del lado de Rust.
+
+#include "rust_engine/src/graphics/cad_import.hxx"
+#include "rust_engine/src/graphics/cad_import.rs.h"
+
+#include <BRepLib_ToolTriangulatedShape.hxx>
+#include <BRepMesh_IncrementalMesh.hxx>
+#include <BRep_Tool.hxx>
+#include <IGESControl_Reader.hxx>
+#include <Poly_Triangulation.hxx>
+#include <STEPControl_Reader.hxx>
+#include <TopExp_Explorer.hxx>
+#include <TopLoc_Location.hxx>
+#include <TopoDS.hxx>
+#include <TopoDS_Face.hxx>
+#include <TopoDS_Shape.hxx>
+
+#include <stdexcept>
+#include <string>
+#include <utility>
+
+namespace rust_engine {
+
+namespace {
+
+// Forma con todas las raíces del archivo
+template <typename Reader> TopoDS_Shape read_shape(const std::string &path) {
+  Reader reader;
+  if (reader.ReadFile(path.c_str()) != IFSelect_RetDone) {
+    throw std::runtime_error("el archivo no se pudo interpretar");
+  }
+  if (reader.TransferRoots() == 0) {
+    throw std::runtime_error("el archivo no tiene geometría");
+  }
+  return reader.OneShape();
+}
+
+// Agrega los triángulos de una cara, con sus propios vértices. Las caras
+// invertidas dan vuelta el orden de las esquinas y las normales.
+void append_face(const TopoDS_Face &face, SolidMesh &mesh) {
+  TopLoc_Location location;
+  const Handle(Poly_Triangulation) triangulation = BRep_Tool::Triangulation(face, location);
+  if (triangulation.IsNull() || triangulation->NbTriangles() == 0) {
+    return;
+  }
+  if (!triangulation->HasNormals()) {
+    BRepLib_ToolTriangulatedShape::ComputeNormals(face, triangulation);
+  }
+  const gp_Trsf transform = location.Transformation();
+  const bool reversed = face.Orientation() == TopAbs_REVERSED;
+
+  const uint32_t first = static_cast<uint32_t>(mesh.positions.size() / 3);
+  for (Standard_Integer i = 1; i <= triangulation->NbNodes(); ++i) {
+    const gp_Pnt point = triangulation->Node(i).Transformed(transform);
+    gp_Dir normal = triangulation->Normal(i).Transformed(transform);
+    if (reversed) {
+      normal.Reverse();
+    }
+    mesh.positions.push_back(static_cast<float>(point.X()));
+    mesh.positions.push_back(static_cast<float>(point.Y()));
+    mesh.positions.push_back(static_cast<float>(point.Z()));
+    mesh.normals.push_back(static_cast<float>(normal.X()));
+    mesh.normals.push_back(static_cast<float>(normal.Y()));
+    mesh.normals.push_back(static_cast<float>(normal.Z()));
+  }
+  for (Standard_Integer i = 1; i <= triangulation->NbTriangles(); ++i) {
+    Standard_Integer a, b, c;
+    triangulation->Triangle(i).Get(a, b, c);
+    if (reversed) {
+      std::swap(b, c);
+    }
+    mesh.indices.push_back(first + static_cast<uint32_t>(a - 1));
+    mesh.indices.push_back(first + static_cast<uint32_t>(b - 1));
+    mesh.indices.push_back(first + static_cast<uint32_t>(c - 1));
+  }
+}
+
+// Caras de `shape` (sin las de los sólidos si `avoid_solids`) en una malla
+SolidMesh mesh_faces(const TopoDS_Shape &shape, bool avoid_solids) {
+  SolidMesh mesh;
+  // TopAbs_SHAPE como tipo a evitar no evita nada
+  TopExp_Explorer faces(shape, TopAbs_FACE, avoid_solids ? TopAbs_SOLID : TopAbs_SHAPE);
+  for (; faces.More(); faces.Next()) {
+    append_face(TopoDS::Face(faces.Current()), mesh);
+  }
+  return mesh;
+}
+
+} // namespace
+
+rust::Vec<SolidMesh> tessellate_cad(rust::Str path, bool iges, double deflection) {
+  const std::string file(path);
+  const TopoDS_Shape shape =
+      iges ? read_shape<IGESControl_Reader>(file) : read_shape<STEPControl_Reader>(file);
+  BRepMesh_IncrementalMesh mesher(shape, deflection);
+  if (!mesher.IsDone()) {
+    throw std::runtime_error("no se pudo teselar");
+  }
+
+  rust::Vec<SolidMesh> solids;
+  for (TopExp_Explorer solid(shape, TopAbs_SOLID); solid.More(); solid.Next()) {
+    SolidMesh mesh = mesh_faces(solid.Current(), false);
+    if (!mesh.indices.empty()) {
+      solids.push_back(std::move(mesh));
+    }
+  }
+  SolidMesh loose = mesh_faces(shape, true);
+  if (!loose.indices.empty()) {
+    solids.push_back(std::move(loose));
+  }
+  return solids;
+}
+
+} // namespace rust_engine
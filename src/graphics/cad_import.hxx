@@ -0,0 +1,15 @@
+// src/graphics/cad_import.hxx
+//
+// Lado C++ de `graphics::cad_import` (ver cad_import.rs).
+
+#pragma once
+
+#include "rust/cxx.h"
+
+namespace rust_engine {
+
+struct SolidMesh;
+
+rust::Vec<SolidMesh> tessellate_cad(rust::Str path, bool iges, double deflection);
+
+} // namespace rust_engine
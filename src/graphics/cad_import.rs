@@ -0,0 +1,88 @@
+// src/graphics/cad_import.rs
+//
+// Importación de STEP e IGES con OpenCASCADE (feature `step`). La lectura y el
+// teselado están en C++ (`cad_import.cpp`, que build.rs compila contra el
+// OCCT del sistema): se recorren los sólidos del B-rep y se tesela cada
+// uno por separado, así que cada sólido es un SceneObject aunque toque a otro
+// o comparta vértices con él. Las caras que no son de ningún sólido (los IGES
+// suelen traer solo superficies) van todas juntas en un objeto más.
+//
+// OpenCASCADE tesela cada cara por separado: los vértices no se comparten
+// entre caras y cada objeto conserva las normales de su cara (aristas vivas
+// del modelo CAD).
+
+use std::path::Path;
+
+use crate::graphics::scene_object::{file_stem, SceneObject};
+
+/// Distancia máxima entre la superficie y sus triángulos, en unidades del archivo
+const DEFLECTION: f64 = 0.01;
+
+#[cxx::bridge(namespace = "rust_engine")]
+mod ffi {
+    /// Triángulos de un sólido, con posiciones y normales xyz seguidas
+    struct SolidMesh {
+        positions: Vec<f32>,
+        normals: Vec<f32>,
+        indices: Vec<u32>,
+    }
+
+    unsafe extern "C++" {
+        include!("rust_engine/src/graphics/cad_import.hxx");
+
+        /// Lee el archivo (IGES si `iges`, si no STEP) y tesela cada sólido
+        fn tessellate_cad(path: &str, iges: bool, deflection: f64) -> Result<Vec<SolidMesh>>;
+    }
+}
+
+/// Si la extensión es de IGES (si no, se lee como STEP)
+fn is_iges(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("iges") || ext.eq_ignore_ascii_case("igs"))
+}
+
+/// Carga un archivo STEP o IGES y devuelve un objeto por sólido.
+/// `source` de cada objeto es `ruta#índice`, para poder guardarlo en la escena.
+pub fn load_cad(path: &str) -> Result<Vec<SceneObject>, String> {
+    let solids = ffi::tessellate_cad(path, is_iges(path), DEFLECTION)
+        .map_err(|e| format!("No se pudo leer {}: {}", path, e))?;
+    if solids.is_empty() {
+        return Err(format!("{} no tiene superficies teselables", path));
+    }
+
+    let stem = file_stem(path);
+    let objects = solids
+        .into_iter()
+        .enumerate()
+        .map(|(index, solid)| {
+            let mut obj = SceneObject::from_buffers(&solid.positions, &solid.normals, solid.indices);
+            obj.source = Some(format!("{}#{}", path, index));
+            obj.name = format!("{}_{}", stem, index + 1);
+            obj
+        })
+        .collect();
+    Ok(objects)
+}
+
+/// Carga un único sólido de un STEP o IGES (lo que referencia `ruta#índice`)
+pub fn load_cad_solid(path: &str, index: usize) -> Result<SceneObject, String> {
+    load_cad(path)?
+        .into_iter()
+        .nth(index)
+        .ok_or_else(|| format!("{} no tiene el sólido {}", path, index))
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iges_by_extension() {
+        assert!(is_iges("piezas/soporte.IGS"));
+        assert!(is_iges("soporte.iges"));
+        assert!(!is_iges("soporte.step"));
+        assert!(!is_iges("iges"));
+    }
+}
@@ -1,8 +1,113 @@
 use std::collections::HashSet;
 
-use glutin::event::VirtualKeyCode;
+use winit::keyboard::KeyCode;
+use serde::{Deserialize, Serialize};
 
-use crate::math::{matrix_4_by_4::Matrix4, vec3::Vec3};
+use crate::math::{aabb::Aabb, matrix_4_by_4::Matrix4, vec3::Vec3};
+
+/// Modo de navegación de la cámara
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraMode {
+    /// Vuelo libre (WASD + Space/Shift)
+    Fly,
+    /// Primera persona caminando sobre la geometría, con gravedad
+    Walk,
+}
+
+/// Parámetros y estado del modo caminar (unidades de mundo)
+#[derive(Debug, Clone, Copy)]
+pub struct WalkState {
+    pub eye_height: f32,   // altura de los ojos sobre el suelo
+    pub step_height: f32,  // escalón máximo que se sube sin saltar
+    pub gravity: f32,      // aceleración hacia abajo
+    pub jump_speed: f32,   // velocidad vertical inicial del salto
+    pub vertical_velocity: f32,
+    pub grounded: bool,
+}
+
+impl Default for WalkState {
+    fn default() -> Self {
+        Self {
+            eye_height: 1.7,
+            step_height: 0.4,
+            gravity: 9.81,
+            jump_speed: 4.5,
+            vertical_velocity: 0.0,
+            grounded: false,
+        }
+    }
+}
+
+/// Vistas estándar alrededor del pivot de la cámara
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    Front,
+    Back,
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// Isométrica desde (+X, +Y, +Z)
+    Iso,
+}
+
+impl View {
+    /// Dirección (unitaria) desde el pivot hacia la cámara
+    pub fn direction(&self) -> Vec3 {
+        match self {
+            View::Front => Vec3::UNIT_Z,
+            View::Back => Vec3::new(0.0, 0.0, -1.0),
+            View::Left => Vec3::new(-1.0, 0.0, 0.0),
+            View::Right => Vec3::UNIT_X,
+            View::Top => Vec3::UNIT_Y,
+            View::Bottom => Vec3::new(0.0, -1.0, 0.0),
+            View::Iso => Vec3::new(1.0, 1.0, 1.0).normalize(),
+        }
+    }
+}
+
+/// Posición y orientación de la cámara (lo que guarda un bookmark)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CameraPose {
+    pub position: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl CameraPose {
+    /// Pose que mira a `pivot` desde `direction` (vector unitario del pivot hacia
+    /// la cámara) a `distance`. En vistas verticales el yaw queda en `vertical_yaw`.
+    pub fn orbiting(pivot: Vec3, direction: Vec3, distance: f32, vertical_yaw: f32) -> Self {
+        let pitch = direction.y.clamp(-1.0, 1.0).asin().clamp(-MAX_PITCH, MAX_PITCH);
+        let yaw = if direction.x.abs() < 1e-6 && direction.z.abs() < 1e-6 {
+            vertical_yaw
+        } else {
+            direction.x.atan2(direction.z)
+        };
+        let position = pivot - Camera::forward_from(yaw, pitch) * distance;
+        Self { position, yaw, pitch }
+    }
+}
+
+/// Duración de las transiciones a vistas estándar y bookmarks (segundos)
+pub const VIEW_TRANSITION: f32 = 0.4;
+
+/// Transición suave entre dos poses de la cámara
+#[derive(Debug, Clone, Copy)]
+struct CameraAnimation {
+    from: CameraPose,
+    to: CameraPose,
+    elapsed: f32,
+    duration: f32,
+}
+
+/// Curva de aceleración/frenado (smoothstep) para t en [0, 1]
+fn ease_in_out(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Límite de pitch: en ±90º exactos look_at queda indefinido
+const MAX_PITCH: f32 = 1.5;
 
 pub struct Camera {
     pub position: Vec3,
@@ -10,6 +115,11 @@ pub struct Camera {
     pub pitch: f32, // rotación alrededor de X
     pub speed: f32, // velocidad de movimiento
     pub vertical_speed: f32, // Nueva velocidad para movimiento vertical
+    pub mode: CameraMode,
+    pub walk: WalkState,
+    /// Punto alrededor del que se orbita en las vistas estándar (p. ej. el centro de la escena)
+    pub pivot: Vec3,
+    animation: Option<CameraAnimation>,
 }
 
 impl Camera {
@@ -20,6 +130,10 @@ impl Camera {
             pitch: 0.0,
             speed: 10.0,          // Velocidad de movimiento horizontal (Unidades por segundo)
             vertical_speed: 10.0, // Velocidad de movimiento vertical (Unidades por segundo)
+            mode: CameraMode::Fly,
+            walk: WalkState::default(),
+            pivot: Vec3::ZERO,
+            animation: None,
         }
     }
 
@@ -29,28 +143,30 @@ impl Camera {
     }
 
     /// Retorna el vector forward basado en yaw y pitch
-    fn get_forward_vector(&self) -> Vec3 {
+    pub fn get_forward_vector(&self) -> Vec3 {
+        Self::forward_from(self.yaw, self.pitch)
+    }
+
+    fn forward_from(yaw: f32, pitch: f32) -> Vec3 {
         // . Calcular la dirección "forward" según yaw/pitch
         //    yaw   = rotación en Y
         //    pitch = rotación en X
-        let cos_pitch = self.pitch.cos();
-        let sin_pitch = self.pitch.sin();
-        let cos_yaw = self.yaw.cos();
-        let sin_yaw = self.yaw.sin();
+        let cos_pitch = pitch.cos();
+        let sin_pitch = pitch.sin();
+        let cos_yaw = yaw.cos();
+        let sin_yaw = yaw.sin();
 
         // Dirección "forward" en 3D
         // alternativo, mira en -Z
-        let forward = Vec3::new(
+        Vec3::new(
             - (sin_yaw * cos_pitch),
             - sin_pitch,
             - (cos_yaw * cos_pitch),
-        );
-        
-        return  forward;
+        )
     }
 
      /// Procesa múltiples teclas presionadas para mover la cámara
-     pub fn process_keys(&mut self, pressed: &HashSet<VirtualKeyCode>, dt: f32) {
+     pub fn process_keys(&mut self, pressed: &HashSet<KeyCode>, dt: f32) {
         let velocity = self.speed * dt;
         let vertical_velocity = self.vertical_speed * dt;
 
@@ -59,28 +175,202 @@ impl Camera {
         let up = Vec3::UNIT_Y;
 
         // Movimiento horizontal
-        if pressed.contains(&VirtualKeyCode::W) {
+        if pressed.contains(&KeyCode::KeyW) {
             self.position += forward * velocity;
         }
-        if pressed.contains(&VirtualKeyCode::S) {
+        if pressed.contains(&KeyCode::KeyS) {
             self.position -= forward * velocity;
         }
-        if pressed.contains(&VirtualKeyCode::A) {
+        if pressed.contains(&KeyCode::KeyA) {
             self.position -= right * velocity;
         }
-        if pressed.contains(&VirtualKeyCode::D) {
+        if pressed.contains(&KeyCode::KeyD) {
             self.position += right * velocity;
         }
 
         // Movimiento vertical
-        if pressed.contains(&VirtualKeyCode::Space) {
+        if pressed.contains(&KeyCode::Space) {
             self.position += up * vertical_velocity;
         }
-        if pressed.contains(&VirtualKeyCode::LShift) || pressed.contains(&VirtualKeyCode::RShift) {
+        if pressed.contains(&KeyCode::ShiftLeft) || pressed.contains(&KeyCode::ShiftRight) {
             self.position -= up * vertical_velocity;
         }
     }
     
+    /// Pose actual
+    pub fn pose(&self) -> CameraPose {
+        CameraPose {
+            position: self.position,
+            yaw: self.yaw,
+            pitch: self.pitch,
+        }
+    }
+
+    /// Transición animada a una vista estándar alrededor de `self.pivot`
+    pub fn set_view(&mut self, view: View) {
+        self.orbit_to(view.direction(), self.pivot, VIEW_TRANSITION);
+    }
+
+    /// Transición animada a una pose guardada
+    pub fn recall_pose(&mut self, pose: CameraPose) {
+        self.fly_to(pose, VIEW_TRANSITION);
+    }
+
+    /// Anima la cámara para mirar a `pivot` desde `direction` (vector del pivot
+    /// hacia la cámara), conservando la distancia actual al pivot.
+    pub fn orbit_to(&mut self, direction: Vec3, pivot: Vec3, duration: f32) {
+        let distance = (self.position - pivot).magnitude().max(1.0);
+        // Vista desde arriba/abajo: conservar el yaw actual
+        self.fly_to(CameraPose::orbiting(pivot, direction, distance, self.yaw), duration);
+    }
+
+    /// Encuadra una caja en espacio mundo sin cambiar la orientación: la cámara
+    /// retrocede sobre su eje de vista hasta que la esfera que contiene la caja
+    /// entra en el campo de visión vertical `fov_y` (radianes).
+    pub fn frame(&mut self, bounds: &Aabb, fov_y: f32, duration: f32) {
+        if bounds.is_empty() {
+            return;
+        }
+        let center = bounds.center();
+        let radius = (bounds.size().magnitude() * 0.5).max(1e-3);
+        let distance = radius / (fov_y * 0.5).sin();
+        let position = center - self.get_forward_vector() * distance;
+        self.pivot = center;
+        self.fly_to(CameraPose { position, yaw: self.yaw, pitch: self.pitch }, duration);
+    }
+
+    /// Interpola posición y orientación (yaw/pitch) hasta `target` con
+    /// aceleración y frenado suaves. El yaw gira por el camino más corto.
+    /// Con `duration` <= 0 el cambio es inmediato.
+    pub fn fly_to(&mut self, target: CameraPose, duration: f32) {
+        let mut delta = (target.yaw - self.yaw) % std::f32::consts::TAU;
+        if delta > std::f32::consts::PI {
+            delta -= std::f32::consts::TAU;
+        } else if delta < -std::f32::consts::PI {
+            delta += std::f32::consts::TAU;
+        }
+
+        let animation = CameraAnimation {
+            from: self.pose(),
+            to: CameraPose {
+                position: target.position,
+                yaw: self.yaw + delta,
+                pitch: target.pitch.clamp(-MAX_PITCH, MAX_PITCH),
+            },
+            elapsed: 0.0,
+            duration,
+        };
+        self.animation = Some(animation);
+        self.update_animation(0.0);
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    /// Avanza la animación en curso, si hay
+    pub fn update_animation(&mut self, dt: f32) {
+        let Some(animation) = self.animation.as_mut() else {
+            return;
+        };
+        animation.elapsed += dt;
+        let t = if animation.duration <= 0.0 {
+            1.0
+        } else {
+            (animation.elapsed / animation.duration).min(1.0)
+        };
+        let s = ease_in_out(t);
+
+        let (from, to) = (animation.from, animation.to);
+        self.position = from.position + (to.position - from.position) * s;
+        self.yaw = from.yaw + (to.yaw - from.yaw) * s;
+        self.pitch = from.pitch + (to.pitch - from.pitch) * s;
+        if t >= 1.0 {
+            self.animation = None;
+        }
+    }
+
+    /// Cambia de modo; al entrar a caminar se empieza cayendo hasta encontrar suelo
+    pub fn set_mode(&mut self, mode: CameraMode) {
+        self.mode = mode;
+        self.walk.vertical_velocity = 0.0;
+        self.walk.grounded = false;
+    }
+
+    /// Modo caminar: WASD en el plano horizontal, Space salta.
+    /// `cast_down(origin)` devuelve la altura de la primera superficie bajo `origin`
+    /// (por ejemplo un rayo contra el BVH de la escena). Si no hay nada debajo la
+    /// cámara se queda a la altura actual en lugar de caer al vacío.
+    pub fn process_walk(
+        &mut self,
+        pressed: &HashSet<KeyCode>,
+        dt: f32,
+        cast_down: impl Fn(Vec3) -> Option<f32>,
+    ) {
+        let velocity = self.speed * dt;
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        // forward sin pitch, para no caminar hacia el cielo
+        let forward = Vec3::new(-sin_yaw, 0.0, -cos_yaw);
+        let right = Vec3::new(cos_yaw, 0.0, -sin_yaw);
+
+        let mut step = Vec3::ZERO;
+        if pressed.contains(&KeyCode::KeyW) {
+            step += forward * velocity;
+        }
+        if pressed.contains(&KeyCode::KeyS) {
+            step -= forward * velocity;
+        }
+        if pressed.contains(&KeyCode::KeyA) {
+            step -= right * velocity;
+        }
+        if pressed.contains(&KeyCode::KeyD) {
+            step += right * velocity;
+        }
+
+        let walk = &mut self.walk;
+        let feet = self.position.y - walk.eye_height;
+
+        // Bloquear el paso si en el destino hay algo más alto que un escalón
+        let target = self.position + step;
+        match cast_down(target) {
+            Some(ground) if ground > feet + walk.step_height => {}
+            _ => self.position = target,
+        }
+
+        // Salto
+        if walk.grounded && pressed.contains(&KeyCode::Space) {
+            walk.vertical_velocity = walk.jump_speed;
+            walk.grounded = false;
+        }
+
+        // Suelo bajo los pies, buscando desde la altura de un escalón
+        let probe = Vec3::new(self.position.x, feet + walk.step_height, self.position.z);
+        let ground = cast_down(probe);
+
+        if walk.grounded {
+            match ground {
+                // Seguir el suelo, incluso al bajar escalones
+                Some(ground) if ground >= feet - walk.step_height => {
+                    self.position.y = ground + walk.eye_height;
+                    return;
+                }
+                _ => walk.grounded = false,
+            }
+        }
+
+        let Some(ground) = ground else {
+            walk.vertical_velocity = 0.0;
+            return;
+        };
+
+        walk.vertical_velocity -= walk.gravity * dt;
+        self.position.y += walk.vertical_velocity * dt;
+        if walk.vertical_velocity <= 0.0 && self.position.y - walk.eye_height <= ground {
+            self.position.y = ground + walk.eye_height;
+            walk.vertical_velocity = 0.0;
+            walk.grounded = true;
+        }
+    }
 
     /// Actualizar la orientación (yaw/pitch) con el mouse
     pub fn process_mouse(&mut self, delta_x: f32, delta_y: f32) {
@@ -90,11 +380,37 @@ impl Camera {
         self.pitch -= delta_y * sensitivity; // resta, para que mover mouse arriba gire la cámara hacia arriba
 
         // Limitar pitch para que no gire 180º
-        if self.pitch > 1.5 {
-            self.pitch = 1.5;
-        }
-        if self.pitch < -1.5 {
-            self.pitch = -1.5;
-        }
+        self.pitch = self.pitch.clamp(-MAX_PITCH, MAX_PITCH);
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fly_to_eases_and_finishes() {
+        let mut camera = Camera::new(Vec3::ZERO);
+        let target = CameraPose { position: Vec3::new(10.0, 0.0, 0.0), yaw: 1.0, pitch: 0.5 };
+        camera.fly_to(target, 1.0);
+
+        camera.update_animation(0.5);
+        assert!((camera.position.x - 5.0).abs() < 1e-4);
+        camera.update_animation(0.1);
+        assert!(camera.is_animating());
+
+        camera.update_animation(1.0);
+        assert!(!camera.is_animating());
+        assert_eq!(camera.pose(), target);
+    }
+
+    #[test]
+    fn test_fly_to_takes_shortest_yaw() {
+        let mut camera = Camera::new(Vec3::ZERO);
+        camera.yaw = 3.0;
+        camera.fly_to(CameraPose { position: Vec3::ZERO, yaw: -3.0, pitch: 0.0 }, 0.0);
+        // -3.0 equivale a 3.28: se gira 0.28 rad en lugar de 6
+        assert!((camera.yaw - (-3.0 + std::f32::consts::TAU)).abs() < 1e-4);
     }
 }
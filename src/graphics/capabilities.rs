@@ -0,0 +1,138 @@
+// src/graphics/capabilities.rs
+//
+// Qué ofrece el contexto OpenGL que se obtuvo. La ventana pide un contexto
+// core 3.3 (lo mínimo que necesitan los shaders) y los drivers suelen dar la
+// versión más alta compatible; lo que venga de 4.x o de extensiones se usa
+// solo si está, consultando estas banderas en lugar de asumirlo.
+
+use std::collections::BTreeSet;
+use std::ffi::CStr;
+
+/// Versión mínima con la que funciona el motor
+pub const MIN_GL_VERSION: (u32, u32) = (3, 3);
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GlCapabilities {
+    /// (mayor, menor) del contexto creado
+    pub version: (u32, u32),
+    pub vendor: String,
+    pub renderer: String,
+    pub extensions: BTreeSet<String>,
+    /// glDrawElementsInstanced + glVertexAttribDivisor
+    pub instancing: bool,
+    /// Compute shaders
+    pub compute: bool,
+    /// glDebugMessageCallback
+    pub debug_output: bool,
+    /// glGetProgramBinary (ver `ProgramCache`)
+    pub program_binary: bool,
+}
+
+impl GlCapabilities {
+    /// Banderas para una versión y lista de extensiones dadas
+    pub fn new(version: (u32, u32), extensions: impl IntoIterator<Item = String>) -> Self {
+        let extensions: BTreeSet<String> = extensions.into_iter().collect();
+        let has = |name: &str| extensions.contains(name);
+        Self {
+            instancing: version >= (3, 3) || has("GL_ARB_instanced_arrays"),
+            compute: version >= (4, 3) || has("GL_ARB_compute_shader"),
+            debug_output: version >= (4, 3) || has("GL_KHR_debug") || has("GL_ARB_debug_output"),
+            program_binary: version >= (4, 1) || has("GL_ARB_get_program_binary"),
+            version,
+            vendor: String::new(),
+            renderer: String::new(),
+            extensions,
+        }
+    }
+
+    /// Consulta el contexto GL actual
+    pub fn query() -> Self {
+        let (mut major, mut minor, mut count) = (0, 0, 0);
+        unsafe {
+            gl::GetIntegerv(gl::MAJOR_VERSION, &mut major);
+            gl::GetIntegerv(gl::MINOR_VERSION, &mut minor);
+            gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut count);
+        }
+        let extensions = (0..count.max(0) as u32).filter_map(|i| unsafe { gl_string(gl::GetStringi(gl::EXTENSIONS, i)) });
+        let mut capabilities = Self::new((major.max(0) as u32, minor.max(0) as u32), extensions);
+        // Sin el puntero cargado la bandera no sirve aunque la versión diga que sí
+        capabilities.debug_output &= gl::DebugMessageCallback::is_loaded();
+        capabilities.program_binary &= gl::GetProgramBinary::is_loaded();
+        capabilities.vendor = unsafe { gl_string(gl::GetString(gl::VENDOR)) }.unwrap_or_default();
+        capabilities.renderer = unsafe { gl_string(gl::GetString(gl::RENDERER)) }.unwrap_or_default();
+        capabilities
+    }
+
+    pub fn has_extension(&self, name: &str) -> bool {
+        self.extensions.contains(name)
+    }
+
+    /// Error si el contexto no llega a la versión mínima
+    pub fn check_minimum(&self) -> Result<(), String> {
+        if self.version < MIN_GL_VERSION {
+            return Err(format!(
+                "El driver ({}) solo ofrece OpenGL {}.{}; hace falta {}.{} core",
+                self.renderer, self.version.0, self.version.1, MIN_GL_VERSION.0, MIN_GL_VERSION.1,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Reenvía los mensajes de error y advertencia del driver a stderr (si
+    /// el contexto tiene salida de depuración)
+    pub fn enable_debug_output(&self) {
+        if !self.debug_output {
+            return;
+        }
+        unsafe {
+            gl::Enable(gl::DEBUG_OUTPUT);
+            gl::Enable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
+            gl::DebugMessageCallback(Some(debug_callback), std::ptr::null());
+        }
+    }
+}
+
+unsafe fn gl_string(text: *const u8) -> Option<String> {
+    if text.is_null() {
+        return None;
+    }
+    Some(CStr::from_ptr(text as *const _).to_string_lossy().into_owned())
+}
+
+extern "system" fn debug_callback(
+    _source: u32,
+    _kind: u32,
+    _id: u32,
+    severity: u32,
+    length: i32,
+    message: *const gl::types::GLchar,
+    _user: *mut std::ffi::c_void,
+) {
+    if severity == gl::DEBUG_SEVERITY_NOTIFICATION || severity == gl::DEBUG_SEVERITY_LOW {
+        return;
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(message as *const u8, length.max(0) as usize) };
+    eprintln!("GL: {}", String::from_utf8_lossy(bytes));
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities_from_version() {
+        let core33 = GlCapabilities::new((3, 3), Vec::new());
+        assert!(core33.instancing && !core33.compute && !core33.debug_output && !core33.program_binary);
+        assert!(core33.check_minimum().is_ok());
+
+        let extended = GlCapabilities::new((3, 3), ["GL_KHR_debug".to_string(), "GL_ARB_get_program_binary".to_string()]);
+        assert!(extended.debug_output && extended.program_binary && !extended.compute);
+        assert!(extended.has_extension("GL_KHR_debug"));
+
+        let core46 = GlCapabilities::new((4, 6), Vec::new());
+        assert!(core46.compute && core46.debug_output && core46.program_binary);
+
+        assert!(GlCapabilities::new((3, 2), Vec::new()).check_minimum().is_err());
+    }
+}
@@ -0,0 +1,454 @@
+// src/graphics/capture.rs
+//
+// Salida de imágenes: capturas de la vista, buffers auxiliares (profundidad,
+// normales, ids) y guardado de los renders offline.
+// Las capturas se dibujan en un framebuffer propio en punto flotante (RGBA16F)
+// sin corrección gamma, así guardan la radiancia lineal. EXR (16 o 32 bits) y
+// HDR conservan los valores > 1; los formatos de 8 bits (PNG, JPEG) se recortan
+// a [0, 1] y se codifican con gamma 2.2.
+// Los buffers auxiliares son datos, no color: en EXR/HDR se guardan los valores
+// crudos y en 8 bits se codifican para poder verlos (ver `AuxBuffer`).
+// Con fondo transparente (`RenderSettings::transparent_background`) el fondo
+// queda en alfa 0 y el color con alfa premultiplicado: el MSAA promedia los
+// bordes contra negro transparente, así no queda un halo del color de fondo al
+// componer. El EXR se guarda premultiplicado (lo que esperan los programas de
+// composición) y el PNG con alfa directo; el resto de los formatos no tiene
+// alfa y queda compuesto sobre negro.
+
+#[cfg(feature = "image-export")]
+use std::fs::File;
+#[cfg(feature = "image-export")]
+use std::io::BufWriter;
+use std::path::Path;
+use std::ptr;
+
+#[cfg(feature = "image-export")]
+use exr::prelude::f16;
+
+/// Precisión de los canales al guardar EXR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExrPrecision {
+    /// 16 bits (half): suficiente para composición, la mitad de tamaño
+    Half,
+    /// 32 bits (float)
+    Float,
+}
+
+/// Buffers auxiliares de la vista que se pueden exportar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuxBuffer {
+    /// Distancia a la cámara a lo largo de la vista (0 en el fondo).
+    /// En 8 bits se normaliza con la profundidad máxima visible.
+    Depth,
+    /// Normal en espacio mundo del lado visible (0 en el fondo).
+    /// En 8 bits se guarda como n * 0.5 + 0.5.
+    Normal,
+    /// Índice del objeto + 1 (0 en el fondo). En 8 bits, un color distinto por objeto.
+    ObjectId,
+    /// 1 donde se ve el objeto indicado, 0 en el resto
+    ObjectMask(usize),
+}
+
+impl AuxBuffer {
+    /// Valor del uniform `mode` de aux.frag
+    pub(crate) fn shader_mode(&self) -> i32 {
+        match self {
+            AuxBuffer::Depth => 0,
+            AuxBuffer::Normal => 1,
+            AuxBuffer::ObjectId => 2,
+            AuxBuffer::ObjectMask(_) => 3,
+        }
+    }
+
+    /// Convierte los valores crudos a algo visible en una imagen de 8 bits
+    pub fn encode_8bit(&self, pixels: &mut [[f32; 3]]) {
+        match self {
+            AuxBuffer::Depth => {
+                let max = pixels.iter().map(|p| p[0]).fold(0.0_f32, f32::max);
+                if max > 0.0 {
+                    for p in pixels.iter_mut() {
+                        *p = [p[0] / max; 3];
+                    }
+                }
+            }
+            AuxBuffer::Normal => {
+                for p in pixels.iter_mut().filter(|p| **p != [0.0; 3]) {
+                    *p = p.map(|c| c * 0.5 + 0.5);
+                }
+            }
+            AuxBuffer::ObjectId => {
+                for p in pixels.iter_mut() {
+                    *p = id_color(p[0].round() as u32);
+                }
+            }
+            AuxBuffer::ObjectMask(_) => {}
+        }
+    }
+}
+
+/// Color fijo y bien distinto para cada id (negro para 0)
+fn id_color(id: u32) -> [f32; 3] {
+    if id == 0 {
+        return [0.0; 3];
+    }
+    // Tonos separados por la razón áurea, saturación y brillo altos
+    let hue = (id as f32 * 0.618_034).fract() * 6.0;
+    let x = 1.0 - (hue % 2.0 - 1.0).abs();
+    let (r, g, b) = match hue as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    [r * 0.9 + 0.1, g * 0.9 + 0.1, b * 0.9 + 0.1]
+}
+
+fn extension(path: &str) -> String {
+    Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Formatos que guardan los valores en punto flotante
+pub fn is_float_format(path: &str) -> bool {
+    matches!(extension(path).as_str(), "exr" | "hdr")
+}
+
+/// Guarda una imagen de valores lineales (filas de arriba hacia abajo). El
+/// formato sale de la extensión: `.exr` (16 bits), `.hdr`, o cualquiera de 8 bits.
+pub fn save_image(path: &str, width: u32, height: u32, pixels: &[[f32; 3]]) -> Result<(), String> {
+    if pixels.len() != (width * height) as usize {
+        return Err(format!("Tamaño de imagen inválido para {}", path));
+    }
+    match extension(path).as_str() {
+        "exr" => save_exr(path, width, height, pixels, ExrPrecision::Half),
+        "hdr" => save_hdr(path, width, height, pixels),
+        _ => save_8bit(path, width, height, pixels, 2.2),
+    }
+}
+
+/// Como `save_image` pero con alfa premultiplicado (0 = fondo): EXR RGBA de
+/// 16 bits o PNG RGBA. Los demás formatos se guardan sin alfa.
+pub fn save_image_rgba(path: &str, width: u32, height: u32, pixels: &[[f32; 4]]) -> Result<(), String> {
+    if pixels.len() != (width * height) as usize {
+        return Err(format!("Tamaño de imagen inválido para {}", path));
+    }
+    match extension(path).as_str() {
+        "exr" => save_exr_rgba(path, width, height, pixels),
+        #[cfg(feature = "image-export")]
+        "png" => {
+            let bytes: Vec<u8> = pixels
+                .iter()
+                .flat_map(|&pixel| {
+                    let [r, g, b, a] = unpremultiply(pixel);
+                    let [r, g, b] = [r, g, b].map(|c| c.clamp(0.0, 1.0).powf(1.0 / 2.2));
+                    [r, g, b, a.clamp(0.0, 1.0)].map(|c| (c * 255.0).round() as u8)
+                })
+                .collect();
+            image::save_buffer(path, &bytes, width, height, image::ColorType::Rgba8)
+                .map_err(|e| format!("No se pudo guardar {}: {}", path, e))
+        }
+        _ => {
+            let rgb: Vec<[f32; 3]> = pixels.iter().map(|&[r, g, b, _]| [r, g, b]).collect();
+            save_image(path, width, height, &rgb)
+        }
+    }
+}
+
+/// Color con alfa directo (sin premultiplicar); el fondo queda en negro
+pub fn unpremultiply([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    if a <= 0.0 {
+        return [0.0; 4];
+    }
+    [r / a, g / a, b / a, a]
+}
+
+/// Guarda datos (no color): EXR de 32 bits, HDR, o 8 bits sin gamma
+pub fn save_data_image(path: &str, width: u32, height: u32, pixels: &[[f32; 3]]) -> Result<(), String> {
+    if pixels.len() != (width * height) as usize {
+        return Err(format!("Tamaño de imagen inválido para {}", path));
+    }
+    match extension(path).as_str() {
+        "exr" => save_exr(path, width, height, pixels, ExrPrecision::Float),
+        "hdr" => save_hdr(path, width, height, pixels),
+        _ => save_8bit(path, width, height, pixels, 1.0),
+    }
+}
+
+/// PNG/JPEG/... RGB de 8 bits, recortado a [0, 1] y con la gamma indicada
+#[cfg(feature = "image-export")]
+fn save_8bit(path: &str, width: u32, height: u32, pixels: &[[f32; 3]], gamma: f32) -> Result<(), String> {
+    let bytes: Vec<u8> = pixels
+        .iter()
+        .flat_map(|pixel| pixel.map(|c| (c.clamp(0.0, 1.0).powf(1.0 / gamma) * 255.0).round() as u8))
+        .collect();
+    image::save_buffer(path, &bytes, width, height, image::ColorType::Rgb8)
+        .map_err(|e| format!("No se pudo guardar {}: {}", path, e))
+}
+
+#[cfg(not(feature = "image-export"))]
+fn save_8bit(path: &str, _width: u32, _height: u32, _pixels: &[[f32; 3]], _gamma: f32) -> Result<(), String> {
+    Err(format!("No se pudo guardar {}: compilado sin la feature image-export", path))
+}
+
+/// Guarda un EXR RGB con la precisión indicada
+#[cfg(feature = "image-export")]
+pub fn save_exr(path: &str, width: u32, height: u32, pixels: &[[f32; 3]], precision: ExrPrecision) -> Result<(), String> {
+    let pixel = |x: usize, y: usize| pixels[y * width as usize + x];
+    let result = match precision {
+        ExrPrecision::Half => exr::prelude::write_rgb_file(path, width as usize, height as usize, |x, y| {
+            let [r, g, b] = pixel(x, y);
+            (f16::from_f32(r), f16::from_f32(g), f16::from_f32(b))
+        }),
+        ExrPrecision::Float => exr::prelude::write_rgb_file(path, width as usize, height as usize, |x, y| {
+            let [r, g, b] = pixel(x, y);
+            (r, g, b)
+        }),
+    };
+    result.map_err(|e| format!("No se pudo guardar {}: {}", path, e))
+}
+
+/// EXR RGBA de 16 bits
+#[cfg(feature = "image-export")]
+fn save_exr_rgba(path: &str, width: u32, height: u32, pixels: &[[f32; 4]]) -> Result<(), String> {
+    exr::prelude::write_rgba_file(path, width as usize, height as usize, |x, y| {
+        let [r, g, b, a] = pixels[y * width as usize + x];
+        (f16::from_f32(r), f16::from_f32(g), f16::from_f32(b), f16::from_f32(a))
+    })
+    .map_err(|e| format!("No se pudo guardar {}: {}", path, e))
+}
+
+#[cfg(not(feature = "image-export"))]
+fn save_exr_rgba(path: &str, _width: u32, _height: u32, _pixels: &[[f32; 4]]) -> Result<(), String> {
+    Err(format!("No se pudo guardar {}: compilado sin la feature image-export", path))
+}
+
+#[cfg(not(feature = "image-export"))]
+pub fn save_exr(path: &str, _width: u32, _height: u32, _pixels: &[[f32; 3]], _precision: ExrPrecision) -> Result<(), String> {
+    Err(format!("No se pudo guardar {}: compilado sin la feature image-export", path))
+}
+
+/// Guarda un HDR de Radiance (RGBE)
+#[cfg(feature = "image-export")]
+pub fn save_hdr(path: &str, width: u32, height: u32, pixels: &[[f32; 3]]) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("No se pudo crear {}: {}", path, e))?;
+    let data: Vec<image::Rgb<f32>> = pixels.iter().map(|&p| image::Rgb(p)).collect();
+    image::codecs::hdr::HdrEncoder::new(BufWriter::new(file))
+        .encode(&data, width as usize, height as usize)
+        .map_err(|e| format!("No se pudo guardar {}: {}", path, e))
+}
+
+#[cfg(not(feature = "image-export"))]
+pub fn save_hdr(path: &str, _width: u32, _height: u32, _pixels: &[[f32; 3]]) -> Result<(), String> {
+    Err(format!("No se pudo guardar {}: compilado sin la feature image-export", path))
+}
+
+/// Framebuffer propio con color en punto flotante (RGBA16F o RGBA32F) y profundidad
+pub(crate) struct OffscreenTarget {
+    pub fbo: u32,
+    color: u32,
+    depth: u32,
+    pub width: i32,
+    pub height: i32,
+    /// Framebuffer multisample (fbo, color, profundidad) donde se dibuja; se
+    /// resuelve en `fbo` al leer
+    multisample: Option<(u32, u32, u32)>,
+}
+
+impl OffscreenTarget {
+    pub fn new(width: i32, height: i32, internal_format: u32) -> Result<Self, String> {
+        Self::with_samples(width, height, internal_format, 0)
+    }
+
+    /// Con `samples` > 1 se dibuja con MSAA (ver `draw_fbo`)
+    pub fn with_samples(width: i32, height: i32, internal_format: u32, samples: u16) -> Result<Self, String> {
+        let mut target = Self { fbo: 0, color: 0, depth: 0, width, height, multisample: None };
+        if samples > 1 {
+            let (mut fbo, mut color, mut depth) = (0, 0, 0);
+            unsafe {
+                gl::GenFramebuffers(1, &mut fbo);
+                gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+                gl::GenRenderbuffers(1, &mut color);
+                gl::BindRenderbuffer(gl::RENDERBUFFER, color);
+                gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, samples as i32, internal_format, width, height);
+                gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::RENDERBUFFER, color);
+                gl::GenRenderbuffers(1, &mut depth);
+                gl::BindRenderbuffer(gl::RENDERBUFFER, depth);
+                gl::RenderbufferStorageMultisample(gl::RENDERBUFFER, samples as i32, gl::DEPTH_COMPONENT24, width, height);
+                gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, gl::RENDERBUFFER, depth);
+            }
+            // Se guarda antes de revisar el estado para que Drop lo libere si falla
+            target.multisample = Some((fbo, color, depth));
+            let status = unsafe { gl::CheckFramebufferStatus(gl::FRAMEBUFFER) };
+            unsafe {
+                gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+                gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            }
+            if status != gl::FRAMEBUFFER_COMPLETE {
+                return Err(format!("Framebuffer de captura con {} muestras incompleto (0x{:x})", samples, status));
+            }
+        }
+        unsafe {
+            gl::GenFramebuffers(1, &mut target.fbo);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, target.fbo);
+
+            gl::GenTextures(1, &mut target.color);
+            gl::BindTexture(gl::TEXTURE_2D, target.color);
+            gl::TexImage2D(
+                gl::TEXTURE_2D, 0, internal_format as i32, width, height, 0,
+                gl::RGBA, gl::FLOAT, ptr::null(),
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, target.color, 0);
+
+            gl::GenRenderbuffers(1, &mut target.depth);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, target.depth);
+            gl::RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH_COMPONENT24, width, height);
+            gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, gl::RENDERBUFFER, target.depth);
+
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            if status != gl::FRAMEBUFFER_COMPLETE {
+                return Err(format!("Framebuffer de captura incompleto (0x{:x})", status));
+            }
+        }
+        Ok(target)
+    }
+
+    /// Framebuffer en el que hay que dibujar
+    pub fn draw_fbo(&self) -> u32 {
+        self.multisample.map_or(self.fbo, |(fbo, _, _)| fbo)
+    }
+
+    /// Promedia las muestras del MSAA en la textura que se lee
+    fn resolve(&self) {
+        let Some((fbo, _, _)) = self.multisample else { return };
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, fbo);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.fbo);
+            gl::BlitFramebuffer(
+                0, 0, self.width, self.height, 0, 0, self.width, self.height,
+                gl::COLOR_BUFFER_BIT, gl::NEAREST,
+            );
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+
+    /// Lee el color como RGBA lineal, filas de arriba hacia abajo
+    pub fn read_rgba(&self) -> Vec<[f32; 4]> {
+        self.resolve();
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut raw = vec![0.0f32; width * height * 4];
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.fbo);
+            gl::ReadBuffer(gl::COLOR_ATTACHMENT0);
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::ReadPixels(
+                0, 0, self.width, self.height,
+                gl::RGBA, gl::FLOAT, raw.as_mut_ptr() as *mut _,
+            );
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+        }
+        raw.chunks_exact(width * 4)
+            .rev()
+            .flat_map(|row| row.chunks_exact(4).map(|p| [p[0], p[1], p[2], p[3]]))
+            .collect()
+    }
+
+    /// Lee el color como RGB lineal, filas de arriba hacia abajo
+    pub fn read_rgb(&self) -> Vec<[f32; 3]> {
+        self.resolve();
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut raw = vec![0.0f32; width * height * 3];
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.fbo);
+            gl::ReadBuffer(gl::COLOR_ATTACHMENT0);
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::ReadPixels(
+                0, 0, self.width, self.height,
+                gl::RGB, gl::FLOAT, raw.as_mut_ptr() as *mut _,
+            );
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+        }
+        // OpenGL devuelve la fila de abajo primero
+        raw.chunks_exact(width * 3)
+            .rev()
+            .flat_map(|row| row.chunks_exact(3).map(|p| [p[0], p[1], p[2]]))
+            .collect()
+    }
+}
+
+impl Drop for OffscreenTarget {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.fbo);
+            gl::DeleteTextures(1, &self.color);
+            gl::DeleteRenderbuffers(1, &self.depth);
+            if let Some((fbo, color, depth)) = self.multisample {
+                gl::DeleteFramebuffers(1, &fbo);
+                gl::DeleteRenderbuffers(1, &color);
+                gl::DeleteRenderbuffers(1, &depth);
+            }
+        }
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_rejects_wrong_size() {
+        let path = std::env::temp_dir().join("rust_engine_capture_test.png");
+        assert!(save_image(&path.to_string_lossy(), 2, 2, &[[0.0; 3]; 3]).is_err());
+    }
+
+    #[cfg(feature = "image-export")]
+    #[test]
+    fn test_save_png_with_alpha() {
+        let path = std::env::temp_dir().join(format!("rust_engine_capture_alpha_{}.png", std::process::id()));
+        let path = path.to_string_lossy();
+        // Un borde a medio cubrir: color premultiplicado por el alfa
+        save_image_rgba(&path, 3, 1, &[[0.0; 4], [1.0, 0.5, 0.0, 1.0], [0.5, 0.25, 0.0, 0.5]]).unwrap();
+        let image = image::open(&*path).unwrap().to_rgba8();
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(1, 0).0[3], 255);
+        // En el PNG el borde tiene el mismo color que el interior, sin oscurecer
+        assert_eq!(image.get_pixel(2, 0).0, [255, 186, 0, 128]);
+        assert_eq!(unpremultiply([0.2, 0.1, 0.0, 0.5]), [0.4, 0.2, 0.0, 0.5]);
+        let _ = std::fs::remove_file(&*path);
+    }
+
+    #[test]
+    fn test_encode_aux_8bit() {
+        let mut depth = vec![[0.0; 3], [2.0; 3], [4.0; 3]];
+        AuxBuffer::Depth.encode_8bit(&mut depth);
+        assert_eq!(depth, vec![[0.0; 3], [0.5; 3], [1.0; 3]]);
+
+        let mut ids = vec![[0.0; 3], [1.0; 3], [2.0; 3]];
+        AuxBuffer::ObjectId.encode_8bit(&mut ids);
+        assert_eq!(ids[0], [0.0; 3]);
+        assert_ne!(ids[1], ids[2]);
+        assert!(ids[1].iter().chain(&ids[2]).all(|c| (0.0..=1.0).contains(c)));
+    }
+
+    #[cfg(feature = "image-export")]
+    #[test]
+    fn test_save_exr_and_hdr() {
+        let pixels = [[2.0, 0.5, 0.0], [0.0, 1.0, 4.0]];
+        for name in ["rust_engine_capture_test.exr", "rust_engine_capture_test.hdr"] {
+            let path = std::env::temp_dir().join(name);
+            let path = path.to_string_lossy();
+            save_image(&path, 2, 1, &pixels).unwrap();
+            assert!(std::fs::metadata(&*path).unwrap().len() > 0);
+            let _ = std::fs::remove_file(&*path);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+// src/graphics/color.rs
+//
+// Conversión entre sRGB (como se escriben los colores en la API) y
+// RGB lineal (como se calcula la iluminación en los shaders), y paletas.
+
+/// Componente sRGB [0,1] -> lineal
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Componente lineal [0,1] -> sRGB
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+pub fn srgb_to_linear_rgb(rgb: [f32; 3]) -> [f32; 3] {
+    [srgb_to_linear(rgb[0]), srgb_to_linear(rgb[1]), srgb_to_linear(rgb[2])]
+}
+
+pub fn linear_to_srgb_rgb(rgb: [f32; 3]) -> [f32; 3] {
+    [linear_to_srgb(rgb[0]), linear_to_srgb(rgb[1]), linear_to_srgb(rgb[2])]
+}
+
+/// HSV (todo en [0,1]) -> RGB en el mismo espacio
+pub fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> [f32; 3] {
+    let h = hue.rem_euclid(1.0) * 6.0;
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = value - chroma;
+    [r + m, g + m, b + m]
+}
+
+/// `count` colores sRGB fáciles de distinguir. El tono avanza por la razón
+/// áurea, así dos colores seguidos nunca quedan parecidos aunque sean muchos;
+/// cada vuelta completa alterna saturación y brillo.
+pub fn distinct_colors(count: usize) -> Vec<[f32; 3]> {
+    const GOLDEN: f32 = 0.618_034;
+    (0..count)
+        .map(|i| {
+            let turn = (i as f32 * GOLDEN) as usize;
+            let (saturation, value) = if turn.is_multiple_of(2) { (0.6, 0.9) } else { (0.45, 0.75) };
+            hsv_to_rgb(i as f32 * GOLDEN, saturation, value)
+        })
+        .collect()
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for i in 0..=20 {
+            let c = i as f32 / 20.0;
+            assert!((linear_to_srgb(srgb_to_linear(c)) - c).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_distinct_colors() {
+        assert_eq!(hsv_to_rgb(0.0, 1.0, 1.0), [1.0, 0.0, 0.0]);
+        assert_eq!(hsv_to_rgb(0.5, 1.0, 1.0), [0.0, 1.0, 1.0]);
+        let colors = distinct_colors(12);
+        assert_eq!(colors.len(), 12);
+        // Ningún par seguido se parece
+        for pair in colors.windows(2) {
+            let distance: f32 = (0..3).map(|i| (pair[0][i] - pair[1][i]).powi(2)).sum();
+            assert!(distance.sqrt() > 0.2);
+        }
+    }
+
+    #[test]
+    fn test_mid_gray() {
+        // sRGB 0.5 ≈ 0.214 lineal
+        assert!((srgb_to_linear(0.5) - 0.2140).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,164 @@
+// src/graphics/compare.rs
+//
+// Comparación de dos versiones de una pieza (escaneo contra CAD, revisión
+// contra revisión): para cada vértice de la malla medida se busca el punto más
+// cercano de la superficie de referencia con su BVH. La distancia lleva signo
+// según la normal de la referencia (positiva = material de más, por fuera) y
+// se muestra como mapa de calor con colores por vértice. La referencia se
+// pasa entera al espacio mundo: con escala distinta en cada eje el punto más
+// cercano en coordenadas propias no es el más cercano en el mundo.
+
+use crate::graphics::mesh_ops::transformed;
+use crate::graphics::scene_object::SceneObject;
+
+/// Resumen de las desviaciones (en unidades del mundo)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DeviationStats {
+    /// Más negativa (hacia adentro de la referencia)
+    pub min: f32,
+    /// Más positiva (hacia afuera de la referencia)
+    pub max: f32,
+    /// Promedio del valor absoluto
+    pub mean: f32,
+    pub rms: f32,
+    /// Vértices sin superficie de referencia dentro de la distancia máxima
+    pub unmatched: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Deviation {
+    /// Distancia con signo por vértice de la malla medida (None = sin correspondencia)
+    pub distances: Vec<Option<f32>>,
+    pub stats: DeviationStats,
+}
+
+/// Desviación de cada vértice de `measured` respecto de la superficie de
+/// `reference`, en espacio mundo. Los vértices a más de `max_distance` quedan
+/// sin correspondencia.
+pub fn compare(measured: &SceneObject, reference: &SceneObject, global_scale: f32, max_distance: f32) -> Deviation {
+    let measured_model = measured.model_matrix(global_scale);
+    let surface = transformed(&reference.mesh, &reference.model_matrix(global_scale));
+
+    let bvh = surface.bvh();
+    let distances = (0..measured.mesh.positions.len() as u32)
+        .map(|vertex| {
+            let world = measured_model.transform_point(measured.mesh.position(vertex));
+            let (closest, distance, triangle) = bvh.closest_point(&surface, world, max_distance)?;
+            let offset = world - closest;
+            let corners = surface.triangle(triangle)?;
+            let [a, b, c] = corners.map(|v| surface.position(v));
+            let (e1, e2) = (b - a, c - a);
+            let normal = e1.cross(&e2);
+            Some(if offset.dot(&normal) < 0.0 { -distance } else { distance })
+        })
+        .collect::<Vec<_>>();
+
+    let stats = statistics(&distances);
+    Deviation { distances, stats }
+}
+
+fn statistics(distances: &[Option<f32>]) -> DeviationStats {
+    let matched: Vec<f32> = distances.iter().flatten().copied().collect();
+    if matched.is_empty() {
+        return DeviationStats { unmatched: distances.len(), ..Default::default() };
+    }
+    let count = matched.len() as f32;
+    DeviationStats {
+        min: matched.iter().copied().fold(f32::INFINITY, f32::min),
+        max: matched.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+        mean: matched.iter().map(|d| d.abs()).sum::<f32>() / count,
+        rms: (matched.iter().map(|d| d * d).sum::<f32>() / count).sqrt(),
+        unmatched: distances.len() - matched.len(),
+    }
+}
+
+/// Color de una desviación: azul (-range, adentro), verde (0), rojo (+range,
+/// afuera). Sin correspondencia = gris.
+pub fn heatmap_color(distance: Option<f32>, range: f32) -> [f32; 3] {
+    let Some(distance) = distance else {
+        return [0.5, 0.5, 0.5];
+    };
+    let t = (distance / range.max(f32::EPSILON)).clamp(-1.0, 1.0);
+    if t < 0.0 {
+        [0.0, 1.0 + t, -t]
+    } else {
+        [t, 1.0 - t, 0.0]
+    }
+}
+
+impl Deviation {
+    /// Colores por vértice del mapa de calor. Con `range` None se usa la mayor
+    /// desviación absoluta.
+    pub fn heatmap(&self, range: Option<f32>) -> Vec<[f32; 3]> {
+        let range = range.unwrap_or_else(|| self.stats.min.abs().max(self.stats.max.abs()));
+        self.distances.iter().map(|&d| heatmap_color(d, range)).collect()
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::mesh::Mesh;
+    use crate::math::{quaternion::Quat, transform::Transform, vec3::Vec3};
+
+    /// Cuadrado de 2x2 en el plano y = 0 con la normal hacia +y
+    fn plane() -> Mesh {
+        Mesh::new(
+            vec![[-1.0, 0.0, -1.0], [1.0, 0.0, -1.0], [1.0, 0.0, 1.0], [-1.0, 0.0, 1.0]],
+            vec![0, 2, 1, 0, 3, 2],
+        )
+    }
+
+    #[test]
+    fn test_signed_deviation() {
+        let mut reference = SceneObject::new(0, 0);
+        reference.mesh = plane();
+        let mut measured = SceneObject::new(0, 0);
+        measured.mesh = Mesh::new(vec![[0.0, 0.5, 0.0], [0.5, -0.25, 0.5], [5.0, 0.0, 0.0]], vec![0, 1, 2]);
+
+        let deviation = compare(&measured, &reference, 1.0, 1.0);
+        assert!((deviation.distances[0].unwrap() - 0.5).abs() < 1e-5);
+        assert!((deviation.distances[1].unwrap() + 0.25).abs() < 1e-5);
+        assert_eq!(deviation.distances[2], None);
+        assert_eq!(deviation.stats.unmatched, 1);
+        assert!((deviation.stats.min + 0.25).abs() < 1e-5);
+        assert!((deviation.stats.max - 0.5).abs() < 1e-5);
+
+        // El mapa de calor se normaliza a la mayor desviación
+        let colors = deviation.heatmap(None);
+        assert_eq!(colors[0], [1.0, 0.0, 0.0]);
+        assert_eq!(colors[2], [0.5, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_deviation_uses_world_space() {
+        // La referencia escalada x2: la distancia se mide en el mundo
+        let mut reference = SceneObject::new(0, 0);
+        reference.mesh = plane();
+        reference.transform = Transform::from_translation(Vec3::new(0.0, 1.0, 0.0));
+        let mut measured = SceneObject::new(0, 0);
+        measured.mesh = Mesh::new(vec![[0.0, 0.0, 0.0]], vec![]);
+
+        let deviation = compare(&measured, &reference, 2.0, 10.0);
+        assert!((deviation.distances[0].unwrap() + 2.0).abs() < 1e-5);
+        assert!((deviation.stats.rms - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_deviation_with_non_uniform_scale() {
+        // Plano x = y estirado x2 en X: en el mundo queda con normal (1, -2, 0)
+        let mut reference = SceneObject::new(0, 0);
+        reference.mesh = Mesh::new(
+            vec![[-1.0, -1.0, -1.0], [1.0, 1.0, -1.0], [1.0, 1.0, 1.0], [-1.0, -1.0, 1.0]],
+            vec![0, 1, 2, 0, 2, 3],
+        );
+        reference.transform = Transform::new(Vec3::ZERO, Quat::IDENTITY, Vec3::new(2.0, 1.0, 1.0));
+        let mut measured = SceneObject::new(0, 0);
+        measured.mesh = Mesh::new(vec![[0.0, 1.0, 0.0]], vec![]);
+
+        // Perpendicular en el mundo: 2/√5 (en coordenadas propias daría √1.25)
+        let deviation = compare(&measured, &reference, 1.0, 10.0);
+        assert!((deviation.distances[0].unwrap() + 2.0 / 5f32.sqrt()).abs() < 1e-5);
+    }
+}
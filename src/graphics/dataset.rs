@@ -0,0 +1,347 @@
+// src/graphics/dataset.rs
+//
+// Generación de datasets sintéticos para entrenar modelos de visión con las
+// piezas de la escena: se renderiza desde muchas poses de cámara y con varias
+// luces, y por cada imagen se guarda un JSON con la cámara, la pose de cada
+// objeto, su caja 2D y la máscara de ids. Se puede correr sin ventana visible
+// con `rust_engine dataset spec.ron`.
+//
+// Archivos por pose `p` y luz `l` (frame = p * luces + l):
+//   frame_NNNNN.png   imagen
+//   frame_NNNNN.json  anotaciones
+//   pose_PPPPP_ids.png   máscara de ids (16 bits, índice del objeto + 1, 0 = fondo)
+//   pose_PPPPP_depth.exr profundidad lineal (opcional)
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::graphics::camara::{Camera, CameraPose};
+use crate::graphics::capture::{save_data_image, AuxBuffer};
+use crate::graphics::lighting::Lighting;
+use crate::graphics::path_tracer::Rng;
+use crate::graphics::render::{Renderer, FOV_Y_DEGREES};
+use crate::graphics::scene_object::SceneObject;
+use crate::math::{aabb::Aabb, vec3::Vec3};
+
+/// Cómo se eligen las poses de la cámara
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CameraSampling {
+    /// Poses explícitas
+    Poses(Vec<CameraPose>),
+    /// `count` vistas equiespaciadas alrededor de la escena por cada elevación (grados)
+    Orbit { count: u32, elevations: Vec<f32> },
+    /// `count` direcciones al azar mirando al centro de la escena
+    Random {
+        count: u32,
+        seed: u64,
+        /// Solo desde arriba del horizonte
+        #[serde(default)]
+        upper_hemisphere: bool,
+    },
+}
+
+/// Descripción de un dataset (se puede leer de un archivo RON)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetSpec {
+    /// Escena (.ron) o carpeta de modelos
+    pub scene: String,
+    /// Carpeta de salida (se crea si no existe)
+    pub output: String,
+    #[serde(default = "default_width")]
+    pub width: u32,
+    #[serde(default = "default_height")]
+    pub height: u32,
+    #[serde(default = "default_global_scale")]
+    pub global_scale: f32,
+    pub cameras: CameraSampling,
+    /// Multiplica la distancia que encuadra justo la escena en las vistas generadas
+    #[serde(default = "default_distance_scale")]
+    pub distance_scale: f32,
+    /// Variaciones de luz; cada pose se renderiza con todas. Vacío = la luz actual
+    #[serde(default)]
+    pub lightings: Vec<Lighting>,
+    /// Guardar también la profundidad lineal (EXR)
+    #[serde(default)]
+    pub depth: bool,
+}
+
+fn default_width() -> u32 {
+    640
+}
+
+fn default_height() -> u32 {
+    480
+}
+
+fn default_global_scale() -> f32 {
+    1.0
+}
+
+fn default_distance_scale() -> f32 {
+    1.2
+}
+
+impl DatasetSpec {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("No se pudo leer {}: {}", path, e))?;
+        ron::from_str(&text).map_err(|e| format!("Especificación de dataset inválida {}: {}", path, e))
+    }
+}
+
+#[derive(Serialize)]
+struct CameraAnnotation {
+    position: Vec3,
+    yaw: f32,
+    pitch: f32,
+    fov_y_degrees: f32,
+    /// fx, fy, cx, cy en píxeles (modelo pinhole, origen arriba a la izquierda)
+    intrinsics: [f32; 4],
+    /// Matriz de vista (column-major)
+    view: [f32; 16],
+}
+
+#[derive(Serialize)]
+struct ObjectAnnotation {
+    index: usize,
+    /// Valor del objeto en la máscara de ids
+    mask_id: usize,
+    name: String,
+    /// Matriz de modelo en mundo (column-major)
+    model: [f32; 16],
+    bounds_min: Vec3,
+    bounds_max: Vec3,
+    /// Caja 2D visible [x_min, y_min, x_max, y_max] en píxeles; None si no se ve
+    bbox: Option<[u32; 4]>,
+    visible_pixels: u32,
+}
+
+#[derive(Serialize)]
+struct FrameAnnotation {
+    image: String,
+    ids: String,
+    depth: Option<String>,
+    width: u32,
+    height: u32,
+    camera: CameraAnnotation,
+    lighting: Lighting,
+    objects: Vec<ObjectAnnotation>,
+}
+
+/// Centro de `bounds` y distancia a la que la esfera que la contiene entra en
+/// el campo de visión vertical `fov_y`, multiplicada por `distance_scale`
+pub fn framing(bounds: &Aabb, fov_y: f32, distance_scale: f32) -> (Vec3, f32) {
+    let center = if bounds.is_empty() { Vec3::ZERO } else { bounds.center() };
+    let radius = if bounds.is_empty() { 1.0 } else { (bounds.size().magnitude() * 0.5).max(1e-3) };
+    (center, radius / (fov_y * 0.5).sin() * distance_scale)
+}
+
+/// Poses de cámara que mira al centro de `bounds` según `sampling`
+pub fn camera_poses(sampling: &CameraSampling, bounds: &Aabb, fov_y: f32, distance_scale: f32) -> Vec<CameraPose> {
+    let (center, distance) = framing(bounds, fov_y, distance_scale);
+
+    match sampling {
+        CameraSampling::Poses(poses) => poses.clone(),
+        CameraSampling::Orbit { count, elevations } => elevations
+            .iter()
+            .flat_map(|elevation| {
+                let elevation = elevation.to_radians();
+                (0..*count).map(move |i| {
+                    let azimuth = i as f32 / *count as f32 * std::f32::consts::TAU;
+                    let direction = Vec3::new(
+                        elevation.cos() * azimuth.sin(),
+                        elevation.sin(),
+                        elevation.cos() * azimuth.cos(),
+                    );
+                    CameraPose::orbiting(center, direction, distance, 0.0)
+                })
+            })
+            .collect(),
+        CameraSampling::Random { count, seed, upper_hemisphere } => {
+            let mut rng = Rng::new(*seed);
+            (0..*count)
+                .map(|_| {
+                    // Uniforme sobre la esfera
+                    let y = if *upper_hemisphere { rng.next_f32() } else { rng.next_f32() * 2.0 - 1.0 };
+                    let phi = rng.next_f32() * std::f32::consts::TAU;
+                    let r = (1.0 - y * y).max(0.0).sqrt();
+                    let direction = Vec3::new(r * phi.sin(), y, r * phi.cos());
+                    CameraPose::orbiting(center, direction, distance, 0.0)
+                })
+                .collect()
+        }
+    }
+}
+
+/// Caja 2D y píxeles visibles de cada objeto a partir del buffer de ids
+fn boxes_from_ids(ids: &[[f32; 3]], width: u32, object_count: usize) -> Vec<(Option<[u32; 4]>, u32)> {
+    let mut result: Vec<(Option<[u32; 4]>, u32)> = vec![(None, 0); object_count];
+    for (index, pixel) in ids.iter().enumerate() {
+        let id = pixel[0].round() as usize;
+        if id == 0 || id > object_count {
+            continue;
+        }
+        let (x, y) = (index as u32 % width, index as u32 / width);
+        let (bbox, count) = &mut result[id - 1];
+        *count += 1;
+        *bbox = Some(match *bbox {
+            None => [x, y, x, y],
+            Some([x0, y0, x1, y1]) => [x0.min(x), y0.min(y), x1.max(x), y1.max(y)],
+        });
+    }
+    result
+}
+
+/// Máscara de ids exacta como PNG de 16 bits en escala de grises
+#[cfg(feature = "image-export")]
+fn save_id_map(path: &Path, width: u32, height: u32, ids: &[[f32; 3]]) -> Result<(), String> {
+    let values: Vec<u16> = ids.iter().map(|p| p[0].round() as u16).collect();
+    image::ImageBuffer::<image::Luma<u16>, _>::from_raw(width, height, values)
+        .ok_or_else(|| format!("Tamaño de máscara inválido para {}", path.display()))?
+        .save(path)
+        .map_err(|e| format!("No se pudo guardar {}: {}", path.display(), e))
+}
+
+#[cfg(not(feature = "image-export"))]
+fn save_id_map(path: &Path, _width: u32, _height: u32, _ids: &[[f32; 3]]) -> Result<(), String> {
+    Err(format!("No se pudo guardar {}: compilado sin la feature image-export", path.display()))
+}
+
+/// Renderiza el dataset de `spec` con los objetos dados. Devuelve cuántas
+/// imágenes se generaron. Deja la luz del renderer como estaba; el viewport
+/// queda en el tamaño del dataset.
+pub fn generate(renderer: &mut Renderer, objects: &[SceneObject], spec: &DatasetSpec) -> Result<usize, String> {
+    let previous = renderer.lighting;
+    let result = generate_frames(renderer, objects, spec);
+    renderer.lighting = previous;
+    result
+}
+
+fn generate_frames(renderer: &mut Renderer, objects: &[SceneObject], spec: &DatasetSpec) -> Result<usize, String> {
+    let output = Path::new(&spec.output);
+    fs::create_dir_all(output).map_err(|e| format!("No se pudo crear {}: {}", spec.output, e))?;
+
+    let scale = spec.global_scale;
+    let bounds = objects
+        .iter()
+        .fold(Aabb::EMPTY, |acc, obj| acc.union(&obj.world_bounds(scale)));
+    let fov_y = FOV_Y_DEGREES.to_radians();
+    let poses = camera_poses(&spec.cameras, &bounds, fov_y, spec.distance_scale);
+    let lightings = if spec.lightings.is_empty() { vec![renderer.lighting] } else { spec.lightings.clone() };
+
+    let size = (spec.width as i32, spec.height as i32);
+    let focal = spec.height as f32 * 0.5 / (fov_y * 0.5).tan();
+    let intrinsics = [focal, focal, spec.width as f32 * 0.5, spec.height as f32 * 0.5];
+
+    let mut camera = Camera::new(Vec3::ZERO);
+    let mut frame = 0;
+    for (pose_index, pose) in poses.iter().enumerate() {
+        camera.position = pose.position;
+        camera.yaw = pose.yaw;
+        camera.pitch = pose.pitch;
+
+        // Máscara y profundidad no dependen de la luz: una vez por pose
+        let (ids, _) = renderer.capture_aux_view(objects, &camera, scale, size, AuxBuffer::ObjectId)?;
+        let ids_name = format!("pose_{:05}_ids.png", pose_index);
+        save_id_map(&output.join(&ids_name), spec.width, spec.height, &ids)?;
+        let depth_name = if spec.depth {
+            let (depth, _) = renderer.capture_aux_view(objects, &camera, scale, size, AuxBuffer::Depth)?;
+            let name = format!("pose_{:05}_depth.exr", pose_index);
+            save_data_image(&output.join(&name).to_string_lossy(), spec.width, spec.height, &depth)?;
+            Some(name)
+        } else {
+            None
+        };
+        let boxes = boxes_from_ids(&ids, spec.width, objects.len());
+
+        for lighting in &lightings {
+            renderer.lighting = *lighting;
+            let image_name = format!("frame_{:05}.png", frame);
+            renderer.capture(objects, &camera, scale, size, &output.join(&image_name).to_string_lossy())?;
+
+            let annotation = FrameAnnotation {
+                image: image_name,
+                ids: ids_name.clone(),
+                depth: depth_name.clone(),
+                width: spec.width,
+                height: spec.height,
+                camera: CameraAnnotation {
+                    position: pose.position,
+                    yaw: pose.yaw,
+                    pitch: pose.pitch,
+                    fov_y_degrees: FOV_Y_DEGREES,
+                    intrinsics,
+                    view: camera.get_view_matrix().m,
+                },
+                lighting: *lighting,
+                objects: objects
+                    .iter()
+                    .zip(&boxes)
+                    .enumerate()
+                    .map(|(index, (obj, (bbox, visible_pixels)))| {
+                        let world = obj.world_bounds(scale);
+                        ObjectAnnotation {
+                            index,
+                            mask_id: index + 1,
+                            name: obj.name.clone(),
+                            model: obj.model_matrix(scale).m,
+                            bounds_min: world.min,
+                            bounds_max: world.max,
+                            bbox: *bbox,
+                            visible_pixels: *visible_pixels,
+                        }
+                    })
+                    .collect(),
+            };
+            let json_path = output.join(format!("frame_{:05}.json", frame));
+            let text = serde_json::to_string_pretty(&annotation)
+                .map_err(|e| format!("No se pudo serializar {}: {}", json_path.display(), e))?;
+            fs::write(&json_path, text).map_err(|e| format!("No se pudo escribir {}: {}", json_path.display(), e))?;
+            frame += 1;
+        }
+    }
+    Ok(frame)
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orbit_poses_look_at_center() {
+        let bounds = Aabb::new(Vec3::new(-1.0, -1.0, -1.0), Vec3::new(1.0, 1.0, 1.0));
+        let sampling = CameraSampling::Orbit { count: 4, elevations: vec![0.0, 30.0] };
+        let poses = camera_poses(&sampling, &bounds, 45.0_f32.to_radians(), 1.0);
+        assert_eq!(poses.len(), 8);
+        for pose in &poses {
+            let mut camera = Camera::new(pose.position);
+            camera.yaw = pose.yaw;
+            camera.pitch = pose.pitch;
+            // El centro queda delante de la cámara
+            let to_center = (Vec3::ZERO - pose.position).normalize();
+            assert!(camera.get_forward_vector().dot(&to_center) > 0.999);
+        }
+    }
+
+    #[test]
+    fn test_boxes_from_ids() {
+        // 3x2: objeto 0 en (0,0) y (1,1), objeto 2 en (2,0)
+        let ids = [[1.0; 3], [0.0; 3], [3.0; 3], [0.0; 3], [1.0; 3], [0.0; 3]];
+        let boxes = boxes_from_ids(&ids, 3, 3);
+        assert_eq!(boxes[0], (Some([0, 0, 1, 1]), 2));
+        assert_eq!(boxes[1], (None, 0));
+        assert_eq!(boxes[2], (Some([2, 0, 2, 0]), 1));
+    }
+
+    #[test]
+    fn test_spec_from_ron() {
+        let spec: DatasetSpec = ron::from_str(
+            r#"(scene: "piezas/", output: "dataset/", cameras: Random(count: 10, seed: 3, upper_hemisphere: true))"#,
+        )
+        .unwrap();
+        assert_eq!((spec.width, spec.height), (640, 480));
+        assert!(spec.lightings.is_empty());
+    }
+}
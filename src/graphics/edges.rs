@@ -0,0 +1,328 @@
+// src/graphics/edges.rs
+//
+// Aristas para el modo de líneas ocultas (el "dibujo técnico" con que se
+// revisan los STL): se dibujan los cantos vivos, donde el ángulo diedro entre
+// las dos caras supera `RenderSettings::crease_angle`, los bordes abiertos y
+// los contornos, donde una cara mira a la cámara y la otra no. Las caras se
+// rellenan con el color de fondo y un corrimiento de profundidad, así tapan
+// las aristas de atrás sin pelearse con las de adelante.
+//
+// Los STL suelen traer cada triángulo con sus propios vértices, así que las
+// aristas se unen por posición y no por índice. Los contornos dependen de la
+// vista: el shader decide por arista con las normales de sus dos caras.
+//
+// Con `RenderSettings::outlines` el mismo pase dibuja solo las aristas, en
+// negro, sobre la escena sombreada por el pase opaco.
+
+use std::collections::HashMap;
+
+use crate::graphics::gpu_resources::ContextGeneration;
+use crate::graphics::gl_validation;
+use crate::graphics::mesh::Mesh;
+use crate::graphics::render_graph::{FrameContext, PassStage, RenderPass, ResourceId, BACKBUFFER, SCENE_DEPTH};
+use crate::graphics::render_settings::RenderSettings;
+use crate::graphics::shaders::{build_program, uniform_location, ShaderLibrary};
+use crate::math::vec3::Vec3;
+
+/// Arista con las normales de las dos caras que la comparten. En un borde
+/// abierto (o una arista de más de dos caras) la segunda es la opuesta de la
+/// primera, así siempre cuenta como canto vivo.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeshEdge {
+    pub a: Vec3,
+    pub b: Vec3,
+    pub normals: [Vec3; 2],
+}
+
+impl MeshEdge {
+    /// Ángulo entre las dos caras en radianes (0 en una superficie plana)
+    pub fn dihedral_angle(&self) -> f32 {
+        self.normals[0].dot(&self.normals[1]).clamp(-1.0, 1.0).acos()
+    }
+
+    pub fn is_crease(&self, angle: f32) -> bool {
+        self.dihedral_angle() > angle
+    }
+
+    /// Una cara mira hacia `eye` y la otra no
+    pub fn is_silhouette(&self, eye: Vec3) -> bool {
+        let to_eye = eye - (self.a + self.b) * 0.5;
+        (self.normals[0].dot(&to_eye) > 0.0) != (self.normals[1].dot(&to_eye) > 0.0)
+    }
+}
+
+/// Todas las aristas de la malla, unidas por posición. Los triángulos
+/// degenerados no aportan normal y se ignoran.
+pub fn mesh_edges(mesh: &Mesh) -> Vec<MeshEdge> {
+    // -0.0 y 0.0 son el mismo punto
+    let key = |p: Vec3| [p.x + 0.0, p.y + 0.0, p.z + 0.0].map(f32::to_bits);
+    let mut index: HashMap<([u32; 3], [u32; 3]), usize> = HashMap::new();
+    let mut edges: Vec<(MeshEdge, u32)> = Vec::new();
+    for triangle in 0..mesh.triangle_count() {
+        let Some([a, b, c]) = mesh.triangle(triangle) else { continue };
+        let [pa, pb, pc] = [a, b, c].map(|v| mesh.position(v));
+        let Some(normal) = (pb - pa).cross(&(pc - pa)).try_normalize() else { continue };
+        for (u, v) in [(pa, pb), (pb, pc), (pc, pa)] {
+            let (ku, kv) = (key(u), key(v));
+            let edge_key = if ku <= kv { (ku, kv) } else { (kv, ku) };
+            let slot = *index.entry(edge_key).or_insert_with(|| {
+                edges.push((MeshEdge { a: u, b: v, normals: [normal, normal * -1.0] }, 0));
+                edges.len() - 1
+            });
+            let (edge, faces) = &mut edges[slot];
+            *faces += 1;
+            match *faces {
+                2 => edge.normals[1] = normal,
+                3 => edge.normals[1] = edge.normals[0] * -1.0,
+                _ => {}
+            }
+        }
+    }
+    edges.into_iter().map(|(edge, _)| edge).collect()
+}
+
+/// Cantos vivos y bordes abiertos (no dependen de la vista), como segmentos
+pub fn crease_edges(mesh: &Mesh, angle: f32) -> Vec<[Vec3; 2]> {
+    mesh_edges(mesh).iter().filter(|e| e.is_crease(angle)).map(|e| [e.a, e.b]).collect()
+}
+
+/// Contornos vistos desde `eye` (en el mismo espacio que la malla)
+pub fn silhouette_edges(mesh: &Mesh, eye: Vec3) -> Vec<[Vec3; 2]> {
+    mesh_edges(mesh).iter().filter(|e| e.is_silhouette(eye)).map(|e| [e.a, e.b]).collect()
+}
+
+/// Aristas de un objeto ya subidas: por vértice posición, punto medio de la
+/// arista y las dos normales
+struct EdgeBuffer {
+    vao: u32,
+    vbo: u32,
+    vertex_count: i32,
+    /// Vértices e índices de la malla de la que salió (para notar si cambió)
+    source: (usize, usize),
+}
+
+impl EdgeBuffer {
+    fn new(mesh: &Mesh) -> Self {
+        let vertices: Vec<[f32; 12]> = mesh_edges(mesh)
+            .iter()
+            .flat_map(|edge| {
+                let mid = (edge.a + edge.b) * 0.5;
+                let [n0, n1] = edge.normals;
+                [edge.a, edge.b].map(|p| [p.x, p.y, p.z, mid.x, mid.y, mid.z, n0.x, n0.y, n0.z, n1.x, n1.y, n1.z])
+            })
+            .collect();
+        let (mut vao, mut vbo) = (0, 0);
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+            gl::GenBuffers(1, &mut vbo);
+            gl::BindVertexArray(vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                std::mem::size_of_val(vertices.as_slice()) as isize,
+                vertices.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+            let stride = (12 * std::mem::size_of::<f32>()) as i32;
+            // (location=0) posición, (1) punto medio, (2) y (3) normales de las caras
+            for location in 0..4 {
+                let offset = (3 * location as usize * std::mem::size_of::<f32>()) as *const _;
+                gl::VertexAttribPointer(location, 3, gl::FLOAT, gl::FALSE, stride, offset);
+                gl::EnableVertexAttribArray(location);
+            }
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
+        }
+        Self { vao, vbo, vertex_count: vertices.len() as i32, source: (mesh.positions.len(), mesh.indices.len()) }
+    }
+
+    fn delete(&self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteVertexArrays(1, &self.vao);
+        }
+    }
+}
+
+/// Modo de líneas ocultas: reemplaza al pase opaco mientras
+/// `RenderSettings::hidden_line` está activo
+pub struct HiddenLinePass {
+    fill_program: u32,
+    edge_program: u32,
+    /// Aristas por VAO del objeto; se extraen la primera vez que se dibuja
+    buffers: HashMap<u32, EdgeBuffer>,
+    generation: ContextGeneration,
+}
+
+impl HiddenLinePass {
+    pub fn new() -> Result<Self, String> {
+        let fill_program = build_program(
+            include_str!("shaders/hidden_line_fill.vert"),
+            include_str!("shaders/hidden_line.frag"),
+        )?;
+        let edge_program = build_program(
+            include_str!("shaders/hidden_line_edge.vert"),
+            include_str!("shaders/hidden_line.frag"),
+        )?;
+        Ok(Self { fill_program, edge_program, buffers: HashMap::new(), generation: ContextGeneration::current() })
+    }
+
+    unsafe fn bind_frame_uniforms(program: u32, frame: &FrameContext, color: [f32; 3]) {
+        gl::UseProgram(program);
+        gl::UniformMatrix4fv(uniform_location(program, "view"), 1, gl::FALSE, frame.view.as_ptr());
+        gl::UniformMatrix4fv(uniform_location(program, "projection"), 1, gl::FALSE, frame.projection.as_ptr());
+        gl::Uniform3f(uniform_location(program, "color"), color[0], color[1], color[2]);
+        gl::Uniform1f(uniform_location(program, "gamma"), frame.settings.gamma);
+    }
+}
+
+impl RenderPass for HiddenLinePass {
+    fn name(&self) -> &str {
+        "hidden_line"
+    }
+
+    fn stage(&self) -> PassStage {
+        PassStage::Opaque
+    }
+
+    fn outputs(&self) -> &[ResourceId] {
+        &[BACKBUFFER, SCENE_DEPTH]
+    }
+
+    fn settings(&self, global: &RenderSettings) -> RenderSettings {
+        if !global.hidden_line {
+            return *global;
+        }
+        // Caras siempre rellenas y empujadas hacia atrás para que las aristas les ganen
+        let [factor, units] = global.polygon_offset;
+        RenderSettings {
+            wireframe: false,
+            depth_test: true,
+            polygon_offset: [factor.max(1.0), units.max(1.0)],
+            ..*global
+        }
+    }
+
+    fn rebuild_gpu(&mut self, _shaders: &ShaderLibrary) -> Result<(), String> {
+        *self = Self::new()?;
+        Ok(())
+    }
+
+    fn execute(&mut self, frame: &FrameContext) {
+        let hidden_line = frame.settings.hidden_line;
+        let outlines = frame.settings.outlines && !frame.settings.wireframe;
+        if !hidden_line && !outlines {
+            return;
+        }
+        let [r, g, b, _] = frame.settings.clear_color;
+        let background = frame.settings.shader_color([r, g, b]);
+        // Líneas negras sobre fondo claro y blancas sobre fondo oscuro; los contornos, negros
+        let line = if !hidden_line || 0.2126 * r + 0.7152 * g + 0.0722 * b > 0.5 { [0.0; 3] } else { [1.0; 3] };
+        let order = frame.draw_order(false);
+
+        // Objetos que ya no existen o cambiaron de malla
+        let live: HashMap<u32, (usize, usize)> = frame
+            .objects
+            .iter()
+            .map(|obj| (obj.vao, (obj.mesh.positions.len(), obj.mesh.indices.len())))
+            .collect();
+        self.buffers.retain(|vao, buffer| {
+            let keep = live.get(vao) == Some(&buffer.source);
+            if !keep {
+                buffer.delete();
+            }
+            keep
+        });
+
+        unsafe {
+            if hidden_line {
+                Self::bind_frame_uniforms(self.fill_program, frame, background);
+                let model_loc = uniform_location(self.fill_program, "model");
+                for &index in &order {
+                    let obj = &frame.objects[index];
+                    gl::UniformMatrix4fv(model_loc, 1, gl::FALSE, obj.model_matrix(frame.global_scale).as_ptr());
+                    gl::BindVertexArray(obj.vao);
+                    gl_validation::draw_elements(gl::TRIANGLES, obj.index_count, 0);
+                }
+            }
+
+            Self::bind_frame_uniforms(self.edge_program, frame, line);
+            let crease_cos = frame.settings.crease_angle.to_radians().cos();
+            gl::Uniform1f(uniform_location(self.edge_program, "creaseCos"), crease_cos);
+            let model_loc = uniform_location(self.edge_program, "model");
+            let normal_loc = uniform_location(self.edge_program, "normalMatrix");
+            gl::DepthFunc(gl::LEQUAL);
+            for &index in &order {
+                let obj = &frame.objects[index];
+                let buffer = self.buffers.entry(obj.vao).or_insert_with(|| EdgeBuffer::new(&obj.mesh));
+                gl::UniformMatrix4fv(model_loc, 1, gl::FALSE, obj.model_matrix(frame.global_scale).as_ptr());
+                gl::UniformMatrix4fv(normal_loc, 1, gl::FALSE, obj.normal_matrix(frame.global_scale).as_ptr());
+                gl::BindVertexArray(buffer.vao);
+                gl_validation::draw_arrays(gl::LINES, 0, buffer.vertex_count);
+            }
+            gl::DepthFunc(gl::LESS);
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+impl Drop for HiddenLinePass {
+    fn drop(&mut self) {
+        if !self.generation.is_current() {
+            return;
+        }
+        for buffer in self.buffers.values() {
+            buffer.delete();
+        }
+        unsafe {
+            gl::DeleteProgram(self.fill_program);
+            gl::DeleteProgram(self.edge_program);
+        }
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cubo unitario con 8 vértices compartidos y las caras hacia afuera
+    fn cube() -> Mesh {
+        let positions = (0..8).map(|i| [(i & 1) as f32, ((i >> 1) & 1) as f32, ((i >> 2) & 1) as f32]).collect();
+        #[rustfmt::skip]
+        let indices = vec![
+            0, 2, 1, 1, 2, 3, // z = 0
+            4, 5, 6, 5, 7, 6, // z = 1
+            0, 1, 4, 1, 5, 4, // y = 0
+            2, 6, 3, 3, 6, 7, // y = 1
+            0, 4, 2, 2, 4, 6, // x = 0
+            1, 3, 5, 3, 7, 5, // x = 1
+        ];
+        Mesh::new(positions, indices)
+    }
+
+    #[test]
+    fn test_crease_and_silhouette_edges() {
+        let cube = cube();
+        // 12 aristas del cubo + 6 diagonales planas
+        let edges = mesh_edges(&cube);
+        assert_eq!(edges.len(), 18);
+        assert_eq!(crease_edges(&cube, 30f32.to_radians()).len(), 12);
+        assert!(crease_edges(&cube, 100f32.to_radians()).is_empty());
+
+        // Desde una esquina lejana el contorno es un hexágono
+        assert_eq!(silhouette_edges(&cube, Vec3::new(10.0, 10.0, 10.0)).len(), 6);
+        // De frente, los cuatro bordes de la cara z = 1
+        assert_eq!(silhouette_edges(&cube, Vec3::new(0.5, 0.5, 10.0)).len(), 4);
+
+        // Sin vértices compartidos (como en un STL) da lo mismo
+        let positions: Vec<[f32; 3]> = cube.indices.iter().map(|&i| cube.positions[i as usize]).collect();
+        let soup = Mesh::new(positions, (0..36).collect());
+        assert_eq!(mesh_edges(&soup).len(), 18);
+        assert_eq!(crease_edges(&soup, 30f32.to_radians()).len(), 12);
+
+        // Un triángulo suelto: todos sus lados son bordes abiertos
+        let triangle = Mesh::new(vec![[0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]], vec![0, 1, 2]);
+        assert_eq!(crease_edges(&triangle, 170f32.to_radians()).len(), 3);
+    }
+}
@@ -0,0 +1,309 @@
+// src/graphics/environment.rs
+//
+// Entorno HDR para el cielo y los reflejos: un panorama equirectangular se
+// convierte en la GPU a un cubemap, y de ahí a un cubemap prefiltrado con
+// un nivel de mip por rugosidad (IBL especular).
+
+use std::ptr;
+
+use crate::graphics::gpu_resources::ContextGeneration;
+use crate::graphics::gl_validation;
+use crate::graphics::shaders::{build_program, uniform_location};
+use crate::math::{matrix_4_by_4::Matrix4, vec3::Vec3};
+
+/// Ancho, alto y píxeles RGB en punto flotante de un panorama
+#[cfg(feature = "image-export")]
+fn read_hdr(path: &str) -> Result<(u32, u32, Vec<f32>), String> {
+    let image = image::open(path)
+        .map_err(|e| format!("No se pudo leer {}: {}", path, e))?
+        .to_rgb32f();
+    let (width, height) = image.dimensions();
+    Ok((width, height, image.into_raw()))
+}
+
+#[cfg(not(feature = "image-export"))]
+fn read_hdr(path: &str) -> Result<(u32, u32, Vec<f32>), String> {
+    Err(format!("No se pudo leer {}: compilado sin la feature image-export", path))
+}
+
+/// Lado de cada cara del cubemap de entorno
+const CUBE_SIZE: i32 = 512;
+/// Lado del mip 0 del cubemap prefiltrado
+const PREFILTER_SIZE: i32 = 128;
+const PREFILTER_LEVELS: i32 = 5;
+
+pub struct Environment {
+    /// Cubemap a resolución completa (cielo)
+    pub cubemap: u32,
+    /// Cubemap prefiltrado: mip i = rugosidad i / (levels - 1)
+    pub prefiltered: u32,
+    pub prefiltered_levels: i32,
+    /// Multiplicador de intensidad del HDR
+    pub exposure: f32,
+    generation: ContextGeneration,
+}
+
+impl Environment {
+    /// Carga un panorama .hdr y genera el cubemap y los mips prefiltrados.
+    /// Requiere un contexto GL activo.
+    pub fn from_hdr(path: &str) -> Result<Self, String> {
+        let (width, height, pixels) = read_hdr(path)?;
+
+        let mut equirect = 0;
+        unsafe {
+            gl::GenTextures(1, &mut equirect);
+            gl::BindTexture(gl::TEXTURE_2D, equirect);
+            gl::TexImage2D(
+                gl::TEXTURE_2D, 0, gl::RGB16F as i32, width as i32, height as i32, 0,
+                gl::RGB, gl::FLOAT, pixels.as_ptr() as *const _,
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        }
+
+        let result = Self::from_equirect_texture(equirect);
+        unsafe {
+            gl::DeleteTextures(1, &equirect);
+        }
+        result
+    }
+
+    fn from_equirect_texture(equirect: u32) -> Result<Self, String> {
+        let cube_vert = include_str!("shaders/cubemap.vert");
+        let convert = build_program(cube_vert, include_str!("shaders/equirect_to_cube.frag"))?;
+        let prefilter = build_program(cube_vert, include_str!("shaders/prefilter.frag"));
+        let prefilter = match prefilter {
+            Ok(p) => p,
+            Err(e) => {
+                unsafe { gl::DeleteProgram(convert) };
+                return Err(e);
+            }
+        };
+
+        let cube = CubeMesh::new();
+        let mut saved_viewport = [0i32; 4];
+        let mut fbo = 0;
+        let mut depth = 0;
+
+        let cubemap = create_cubemap(CUBE_SIZE, true);
+        let prefiltered = create_cubemap(PREFILTER_SIZE, true);
+
+        unsafe {
+            gl::GetIntegerv(gl::VIEWPORT, saved_viewport.as_mut_ptr());
+            // Se dibuja el cubo desde dentro: sin culling mientras se captura
+            let culling = gl::IsEnabled(gl::CULL_FACE) == gl::TRUE;
+            gl::Disable(gl::CULL_FACE);
+            gl::GenFramebuffers(1, &mut fbo);
+            gl::GenRenderbuffers(1, &mut depth);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+
+            // 1) Equirectangular -> cubemap
+            gl::UseProgram(convert);
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, equirect);
+            gl::Uniform1i(uniform_location(convert, "equirect"), 0);
+            render_faces(convert, cubemap, 0, CUBE_SIZE, depth, &cube);
+
+            // Mips del cubemap para que el prefiltrado muestree sin aliasing
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, cubemap);
+            gl::GenerateMipmap(gl::TEXTURE_CUBE_MAP);
+
+            // 2) Prefiltrado GGX, un mip por nivel de rugosidad
+            gl::UseProgram(prefilter);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, cubemap);
+            gl::Uniform1i(uniform_location(prefilter, "environment"), 0);
+            gl::Uniform1f(uniform_location(prefilter, "resolution"), CUBE_SIZE as f32);
+            for level in 0..PREFILTER_LEVELS {
+                let size = (PREFILTER_SIZE >> level).max(1);
+                let roughness = level as f32 / (PREFILTER_LEVELS - 1) as f32;
+                gl::Uniform1f(uniform_location(prefilter, "roughness"), roughness);
+                render_faces(prefilter, prefiltered, level, size, depth, &cube);
+            }
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, prefiltered);
+            gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_MAX_LEVEL, PREFILTER_LEVELS - 1);
+
+            // Restaurar estado
+            if culling {
+                gl::Enable(gl::CULL_FACE);
+            }
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::Viewport(saved_viewport[0], saved_viewport[1], saved_viewport[2], saved_viewport[3]);
+            gl::DeleteFramebuffers(1, &fbo);
+            gl::DeleteRenderbuffers(1, &depth);
+            gl::DeleteProgram(convert);
+            gl::DeleteProgram(prefilter);
+        }
+
+        Ok(Self {
+            cubemap,
+            prefiltered,
+            prefiltered_levels: PREFILTER_LEVELS,
+            exposure: 1.0,
+            generation: ContextGeneration::current(),
+        })
+    }
+
+    /// Enlaza el cubemap prefiltrado en la unidad `unit` para materiales reflectantes
+    pub fn bind_prefiltered(&self, unit: u32) {
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0 + unit);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, self.prefiltered);
+        }
+    }
+
+    /// Mip que corresponde a una rugosidad en [0, 1]
+    pub fn lod_for_roughness(&self, roughness: f32) -> f32 {
+        roughness.clamp(0.0, 1.0) * (self.prefiltered_levels - 1) as f32
+    }
+}
+
+impl Drop for Environment {
+    fn drop(&mut self) {
+        if !self.generation.is_current() {
+            return;
+        }
+        unsafe {
+            gl::DeleteTextures(1, &self.cubemap);
+            gl::DeleteTextures(1, &self.prefiltered);
+        }
+    }
+}
+
+/// Vistas de captura para las 6 caras (+X, -X, +Y, -Y, +Z, -Z)
+pub fn capture_views() -> [Matrix4; 6] {
+    let eye = Vec3::ZERO;
+    [
+        Matrix4::look_at(eye, Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, -1.0, 0.0)),
+        Matrix4::look_at(eye, Vec3::new(-1.0, 0.0, 0.0), Vec3::new(0.0, -1.0, 0.0)),
+        Matrix4::look_at(eye, Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)),
+        Matrix4::look_at(eye, Vec3::new(0.0, -1.0, 0.0), Vec3::new(0.0, 0.0, -1.0)),
+        Matrix4::look_at(eye, Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.0, -1.0, 0.0)),
+        Matrix4::look_at(eye, Vec3::new(0.0, 0.0, -1.0), Vec3::new(0.0, -1.0, 0.0)),
+    ]
+}
+
+fn create_cubemap(size: i32, mipmapped: bool) -> u32 {
+    let mut texture = 0;
+    unsafe {
+        gl::GenTextures(1, &mut texture);
+        gl::BindTexture(gl::TEXTURE_CUBE_MAP, texture);
+        for face in 0..6 {
+            gl::TexImage2D(
+                gl::TEXTURE_CUBE_MAP_POSITIVE_X + face, 0, gl::RGB16F as i32,
+                size, size, 0, gl::RGB, gl::FLOAT, ptr::null(),
+            );
+        }
+        gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+        gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+        gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_WRAP_R, gl::CLAMP_TO_EDGE as i32);
+        let min_filter = if mipmapped { gl::LINEAR_MIPMAP_LINEAR } else { gl::LINEAR };
+        gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_MIN_FILTER, min_filter as i32);
+        gl::TexParameteri(gl::TEXTURE_CUBE_MAP, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        if mipmapped {
+            // Reservar la cadena de mips
+            gl::GenerateMipmap(gl::TEXTURE_CUBE_MAP);
+        }
+        gl::Enable(gl::TEXTURE_CUBE_MAP_SEAMLESS);
+        gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
+    }
+    texture
+}
+
+/// Dibuja el cubo unitario en las 6 caras del nivel `level` de `target`.
+/// Espera el framebuffer de captura ya enlazado y el programa activo.
+unsafe fn render_faces(program: u32, target: u32, level: i32, size: i32, depth: u32, cube: &CubeMesh) {
+    let projection = Matrix4::perspective(90.0_f32.to_radians(), 1.0, 0.1, 10.0);
+    gl::UniformMatrix4fv(uniform_location(program, "projection"), 1, gl::FALSE, projection.as_ptr());
+
+    gl::BindRenderbuffer(gl::RENDERBUFFER, depth);
+    gl::RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH_COMPONENT24, size, size);
+    gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, gl::RENDERBUFFER, depth);
+    gl::Viewport(0, 0, size, size);
+
+    let view_loc = uniform_location(program, "view");
+    for (face, view) in capture_views().iter().enumerate() {
+        gl::UniformMatrix4fv(view_loc, 1, gl::FALSE, view.as_ptr());
+        gl::FramebufferTexture2D(
+            gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0,
+            gl::TEXTURE_CUBE_MAP_POSITIVE_X + face as u32, target, level,
+        );
+        gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        cube.draw();
+    }
+}
+
+/// Cubo unitario [-1, 1]^3 solo con posiciones, visto desde dentro
+pub struct CubeMesh {
+    vao: u32,
+    vbo: u32,
+    generation: ContextGeneration,
+}
+
+impl CubeMesh {
+    pub fn new() -> Self {
+        #[rustfmt::skip]
+        let vertices: [f32; 108] = [
+            -1.0,  1.0, -1.0,  -1.0, -1.0, -1.0,   1.0, -1.0, -1.0,
+             1.0, -1.0, -1.0,   1.0,  1.0, -1.0,  -1.0,  1.0, -1.0,
+            -1.0, -1.0,  1.0,  -1.0, -1.0, -1.0,  -1.0,  1.0, -1.0,
+            -1.0,  1.0, -1.0,  -1.0,  1.0,  1.0,  -1.0, -1.0,  1.0,
+             1.0, -1.0, -1.0,   1.0, -1.0,  1.0,   1.0,  1.0,  1.0,
+             1.0,  1.0,  1.0,   1.0,  1.0, -1.0,   1.0, -1.0, -1.0,
+            -1.0, -1.0,  1.0,  -1.0,  1.0,  1.0,   1.0,  1.0,  1.0,
+             1.0,  1.0,  1.0,   1.0, -1.0,  1.0,  -1.0, -1.0,  1.0,
+            -1.0,  1.0, -1.0,   1.0,  1.0, -1.0,   1.0,  1.0,  1.0,
+             1.0,  1.0,  1.0,  -1.0,  1.0,  1.0,  -1.0,  1.0, -1.0,
+            -1.0, -1.0, -1.0,  -1.0, -1.0,  1.0,   1.0, -1.0, -1.0,
+             1.0, -1.0, -1.0,  -1.0, -1.0,  1.0,   1.0, -1.0,  1.0,
+        ];
+
+        let mut vao = 0;
+        let mut vbo = 0;
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+            gl::GenBuffers(1, &mut vbo);
+            gl::BindVertexArray(vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                std::mem::size_of_val(&vertices) as isize,
+                vertices.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+            gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, 0, ptr::null());
+            gl::EnableVertexAttribArray(0);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
+        }
+        Self { vao, vbo, generation: ContextGeneration::current() }
+    }
+
+    pub fn draw(&self) {
+        unsafe {
+            gl::BindVertexArray(self.vao);
+            gl_validation::draw_arrays(gl::TRIANGLES, 0, 36);
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+impl Default for CubeMesh {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for CubeMesh {
+    fn drop(&mut self) {
+        if !self.generation.is_current() {
+            return;
+        }
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteBuffers(1, &self.vbo);
+        }
+    }
+}
@@ -0,0 +1,155 @@
+// src/graphics/frames.rs
+//
+// Marcos de referencia con nombre (origen + orientación) guardados con la
+// escena, p. ej. la base de un robot o el cero de una mesa. Se dibujan como
+// una terna de ejes (X rojo, Y verde, Z azul) con el nombre al lado, y las
+// poses de los objetos se pueden leer y escribir relativas a uno de ellos en
+// vez de al origen de la escena. Los marcos viven en el mismo espacio que
+// `SceneObject::transform`: sin grupo y sin la escala global del visor.
+
+use serde::{Deserialize, Serialize};
+
+use crate::graphics::lines::Polyline;
+use crate::graphics::text::TextPanel;
+use crate::math::{matrix_4_by_4::Matrix4, quaternion::Quat, vec3::Vec3};
+
+/// Capa del `LineOverlay` con las ternas de ejes
+pub const FRAMES_LAYER: &str = "marcos";
+/// Comienzo del nombre de los paneles de texto con los nombres de los marcos
+pub const LABEL_PREFIX: &str = "marco:";
+
+/// Largo de los ejes respecto de la diagonal de la escena
+pub const AXIS_LENGTH: f32 = 0.08;
+/// Separación en píxeles lógicos entre el origen y su nombre
+const LABEL_OFFSET: f32 = 6.0;
+
+const AXIS_COLORS: [[f32; 3]; 3] = [[1.0, 0.1, 0.1], [0.1, 1.0, 0.1], [0.2, 0.4, 1.0]];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReferenceFrame {
+    pub name: String,
+    /// Origen en unidades de la escena
+    pub origin: Vec3,
+    /// Ángulos de Euler en grados (ver `Quat::from_euler`), como el `rpy` de URDF
+    pub rotation: Vec3,
+}
+
+impl ReferenceFrame {
+    pub fn new(name: &str, origin: Vec3, orientation: Quat) -> Self {
+        Self { name: name.to_string(), origin, rotation: to_degrees(orientation.to_euler()) }
+    }
+
+    pub fn orientation(&self) -> Quat {
+        Quat::from_euler(to_radians(self.rotation))
+    }
+
+    /// Transform del marco: lleva coordenadas del marco a la escena
+    pub fn matrix(&self) -> Matrix4 {
+        Matrix4::from_trs(self.origin, self.orientation(), Vec3::new(1.0, 1.0, 1.0))
+    }
+
+    /// Punto de la escena en coordenadas del marco
+    pub fn to_frame(&self, point: Vec3) -> Vec3 {
+        self.orientation().conjugate().rotate(point - self.origin)
+    }
+
+    /// Punto en coordenadas del marco en la escena
+    pub fn from_frame(&self, point: Vec3) -> Vec3 {
+        self.origin + self.orientation().rotate(point)
+    }
+
+    /// Posición y giro de `transform` (la matriz de una `Transform`) vistos desde el
+    /// marco; la escala se descarta
+    pub fn relative(&self, transform: &Matrix4) -> (Vec3, Quat) {
+        let (translation, rotation, _) = transform.decompose();
+        (self.to_frame(translation), self.orientation().conjugate() * rotation)
+    }
+
+    /// Inverso de `relative`: la transform de escena con esa posición y giro
+    /// en el marco, con la escala de `transform`
+    pub fn absolute(&self, position: Vec3, rotation: Quat, transform: &Matrix4) -> Matrix4 {
+        let (_, _, scale) = transform.decompose();
+        Matrix4::from_trs(self.from_frame(position), self.orientation() * rotation, scale)
+    }
+}
+
+/// Ángulos de Euler en radianes pasados a grados, componente a componente
+pub fn to_degrees(angles: Vec3) -> Vec3 {
+    Vec3::new(angles.x.to_degrees(), angles.y.to_degrees(), angles.z.to_degrees())
+}
+
+fn to_radians(angles: Vec3) -> Vec3 {
+    Vec3::new(angles.x.to_radians(), angles.y.to_radians(), angles.z.to_radians())
+}
+
+/// Ternas de ejes de `length` (en unidades de mundo) en espacio mundo
+pub fn frame_lines(frames: &[ReferenceFrame], global_scale: f32, length: f32) -> Vec<Polyline> {
+    let mut polylines = Vec::new();
+    for frame in frames {
+        let origin = frame.origin * global_scale;
+        let orientation = frame.orientation();
+        for (axis, color) in [Vec3::UNIT_X, Vec3::UNIT_Y, Vec3::UNIT_Z].into_iter().zip(AXIS_COLORS) {
+            polylines.push(Polyline { points: vec![origin, origin + orientation.rotate(axis) * length], color, closed: false });
+        }
+    }
+    polylines
+}
+
+/// Un panel por marco con su nombre junto al origen, con el nombre del panel
+/// (`LABEL_PREFIX` + nombre del marco). `screen` es el tamaño de la ventana en
+/// píxeles lógicos; los marcos detrás de la cámara o fuera de la ventana no llevan.
+pub fn frame_labels(
+    frames: &[ReferenceFrame],
+    global_scale: f32,
+    view_projection: &Matrix4,
+    screen: (f32, f32),
+) -> Vec<(String, TextPanel)> {
+    let mut labels = Vec::new();
+    for frame in frames {
+        let origin = frame.origin * global_scale;
+        let [x, y, _, w] = view_projection.transform_vec4([origin.x, origin.y, origin.z, 1.0]);
+        if w <= 1e-6 || x.abs() > w || y.abs() > w {
+            continue;
+        }
+        let position = ((x / w + 1.0) * 0.5 * screen.0 + LABEL_OFFSET, (1.0 - y / w) * 0.5 * screen.1 + LABEL_OFFSET);
+        let mut panel = TextPanel::new(vec![frame.name.clone()]);
+        panel.position = position;
+        panel.background = [0.0, 0.0, 0.0, 0.4];
+        labels.push((format!("{}{}", LABEL_PREFIX, frame.name), panel));
+    }
+    labels
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_poses() {
+        // Base de robot en (10, 0, 0) girada 90° en Z: su X es la Y de la escena
+        let base = ReferenceFrame::new("base", Vec3::new(10.0, 0.0, 0.0), Quat::from_axis_angle(Vec3::UNIT_Z, 90f32.to_radians()));
+        assert!(base.rotation.approx_eq(&Vec3::new(0.0, 0.0, 90.0), 1e-4));
+        assert!(base.from_frame(Vec3::new(2.0, 0.0, 0.0)).approx_eq(&Vec3::new(10.0, 2.0, 0.0), 1e-5));
+        assert!(base.to_frame(Vec3::new(10.0, 2.0, 0.0)).approx_eq(&Vec3::new(2.0, 0.0, 0.0), 1e-5));
+        assert!(base.matrix().transform_point(Vec3::new(2.0, 0.0, 0.0)).approx_eq(&Vec3::new(10.0, 2.0, 0.0), 1e-5));
+
+        // Pieza escalada al doble, girada con la base y 3 unidades sobre su X
+        let spin = Quat::from_axis_angle(Vec3::UNIT_Z, 90f32.to_radians());
+        let part = Matrix4::from_trs(Vec3::new(10.0, 3.0, 0.0), spin, Vec3::new(2.0, 2.0, 2.0));
+        let (position, rotation) = base.relative(&part);
+        assert!(position.approx_eq(&Vec3::new(3.0, 0.0, 0.0), 1e-5));
+        assert!(rotation.same_rotation(&Quat::IDENTITY, 1e-5));
+        assert!(base.absolute(position, rotation, &part).approx_eq(&part, 1e-5));
+
+        let lines = frame_lines(std::slice::from_ref(&base), 2.0, 1.0);
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].points[1].approx_eq(&Vec3::new(20.0, 1.0, 0.0), 1e-5));
+
+        // Con la identidad como cámara el origen (escalado) tiene que caer en pantalla
+        let labels = frame_labels(&[ReferenceFrame::new("cero", Vec3::ZERO, Quat::IDENTITY), base], 1.0, &Matrix4::identity(), (200.0, 100.0));
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[0].0, "marco:cero");
+        assert_eq!(labels[0].1.position, (106.0, 56.0));
+    }
+}
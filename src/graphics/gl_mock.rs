@@ -0,0 +1,419 @@
+// src/graphics/gl_mock.rs
+//
+// Backend GL falso para las pruebas: en lugar de hablar con un driver, los
+// punteros de `gl` apuntan a funciones que anotan cada llamada en un registro
+// por hilo. Así se puede correr el código de los pases y de SceneObject en CI
+// sin GPU y revisar qué se dibujó, en qué orden y con qué uniformes.
+// Solo cubre las funciones de la lista de `resolve`; llamar a otra hace que
+// `gl` entre en pánico avisando que no está cargada.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{c_void, CStr};
+use std::os::raw::c_char;
+use std::ptr;
+use std::sync::Once;
+
+/// Valor asignado a un uniform
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UniformValue {
+    Int(i32),
+    Float(f32),
+    Vec3([f32; 3]),
+    Mat4([f32; 16]),
+}
+
+/// Llamada GL registrada
+#[derive(Debug, Clone, PartialEq)]
+pub enum GlCall {
+    UseProgram(u32),
+    Uniform(String, UniformValue),
+    ActiveTexture(u32),
+    BindTexture(u32, u32),
+    BindVertexArray(u32),
+    BindBuffer(u32, u32),
+    /// (target, tamaño en bytes)
+    BufferData(u32, isize),
+    VertexAttribPointer(u32, i32),
+    EnableVertexAttribArray(u32),
+    /// (modo, cantidad de índices)
+    DrawElements(u32, i32),
+    Enable(u32),
+    Disable(u32),
+    CullFace(u32),
+    PolygonMode(u32, u32),
+    ClearColor([f32; 4]),
+    DepthFunc(u32),
+    /// Máscara de buffers
+    Clear(u32),
+    DeleteVertexArrays(Vec<u32>),
+    DeleteBuffers(Vec<u32>),
+    DeleteTextures(Vec<u32>),
+    /// Código que recibe un shader (ya preprocesado)
+    ShaderSource(String),
+    DeleteProgram(u32),
+}
+
+#[derive(Default)]
+struct MockState {
+    calls: Vec<GlCall>,
+    /// Nombre de cada ubicación de uniform (la ubicación es el índice)
+    uniforms: Vec<String>,
+    /// Último id entregado por Gen*
+    last_name: u32,
+    /// Programa y VAO enlazados, para las consultas de estado
+    program: u32,
+    vao: u32,
+    /// Buffer de índices de cada VAO
+    element_buffers: HashMap<u32, u32>,
+}
+
+thread_local! {
+    static STATE: RefCell<MockState> = RefCell::new(MockState::default());
+}
+
+static INSTALL: Once = Once::new();
+
+/// Carga el backend falso (una sola vez por proceso) y vacía el registro del hilo actual
+pub fn install() {
+    INSTALL.call_once(|| gl::load_with(resolve));
+    STATE.with(|state| *state.borrow_mut() = MockState::default());
+}
+
+/// Devuelve las llamadas registradas en este hilo y vacía el registro
+pub fn take_calls() -> Vec<GlCall> {
+    STATE.with(|state| std::mem::take(&mut state.borrow_mut().calls))
+}
+
+/// Último valor asignado a un uniform
+pub fn uniform(calls: &[GlCall], name: &str) -> Option<UniformValue> {
+    calls.iter().rev().find_map(|call| match call {
+        GlCall::Uniform(n, value) if n == name => Some(*value),
+        _ => None,
+    })
+}
+
+/// VAOs dibujados, en orden
+pub fn drawn_vaos(calls: &[GlCall]) -> Vec<u32> {
+    let mut bound = 0;
+    let mut drawn = Vec::new();
+    for call in calls {
+        match call {
+            GlCall::BindVertexArray(vao) => bound = *vao,
+            GlCall::DrawElements(..) => drawn.push(bound),
+            _ => {}
+        }
+    }
+    drawn
+}
+
+fn record(call: GlCall) {
+    STATE.with(|state| state.borrow_mut().calls.push(call));
+}
+
+fn uniform_name(location: i32) -> String {
+    STATE.with(|state| {
+        let state = state.borrow();
+        usize::try_from(location)
+            .ok()
+            .and_then(|i| state.uniforms.get(i).cloned())
+            .unwrap_or_else(|| format!("#{}", location))
+    })
+}
+
+fn record_uniform(location: i32, value: UniformValue) {
+    record(GlCall::Uniform(uniform_name(location), value));
+}
+
+/// Copia `n` ids de un puntero de la API
+unsafe fn names(n: i32, ids: *const u32) -> Vec<u32> {
+    (0..n.max(0) as usize).map(|i| *ids.add(i)).collect()
+}
+
+/// Llena `n` ids nuevos (nunca 0)
+unsafe fn generate(n: i32, ids: *mut u32) {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        for i in 0..n.max(0) as usize {
+            state.last_name += 1;
+            *ids.add(i) = state.last_name;
+        }
+    });
+}
+
+extern "system" fn use_program(program: u32) {
+    STATE.with(|state| state.borrow_mut().program = program);
+    record(GlCall::UseProgram(program));
+}
+
+extern "system" fn get_uniform_location(_program: u32, name: *const c_char) -> i32 {
+    let name = unsafe { CStr::from_ptr(name) }.to_string_lossy().into_owned();
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let index = match state.uniforms.iter().position(|n| *n == name) {
+            Some(index) => index,
+            None => {
+                state.uniforms.push(name);
+                state.uniforms.len() - 1
+            }
+        };
+        index as i32
+    })
+}
+
+extern "system" fn uniform_1i(location: i32, v: i32) {
+    record_uniform(location, UniformValue::Int(v));
+}
+
+extern "system" fn uniform_1f(location: i32, v: f32) {
+    record_uniform(location, UniformValue::Float(v));
+}
+
+extern "system" fn uniform_3f(location: i32, x: f32, y: f32, z: f32) {
+    record_uniform(location, UniformValue::Vec3([x, y, z]));
+}
+
+extern "system" fn uniform_matrix_4fv(location: i32, _count: i32, _transpose: u8, value: *const f32) {
+    let mut m = [0.0; 16];
+    unsafe { ptr::copy_nonoverlapping(value, m.as_mut_ptr(), 16) };
+    record_uniform(location, UniformValue::Mat4(m));
+}
+
+/// Último valor asignado a la ubicación (ceros si nunca se asignó)
+fn last_uniform(location: i32) -> Option<UniformValue> {
+    let name = uniform_name(location);
+    STATE.with(|state| uniform(&state.borrow().calls, &name))
+}
+
+extern "system" fn get_uniform_fv(_program: u32, location: i32, out: *mut f32) {
+    let values: Vec<f32> = match last_uniform(location) {
+        Some(UniformValue::Float(v)) => vec![v],
+        Some(UniformValue::Vec3(v)) => v.to_vec(),
+        Some(UniformValue::Mat4(m)) => m.to_vec(),
+        Some(UniformValue::Int(v)) => vec![v as f32],
+        None => vec![0.0],
+    };
+    unsafe { ptr::copy_nonoverlapping(values.as_ptr(), out, values.len()) };
+}
+
+extern "system" fn get_uniform_iv(_program: u32, location: i32, out: *mut i32) {
+    let value = match last_uniform(location) {
+        Some(UniformValue::Int(v)) => v,
+        _ => 0,
+    };
+    unsafe { *out = value };
+}
+
+extern "system" fn active_texture(unit: u32) {
+    record(GlCall::ActiveTexture(unit));
+}
+
+extern "system" fn bind_texture(target: u32, texture: u32) {
+    record(GlCall::BindTexture(target, texture));
+}
+
+extern "system" fn bind_vertex_array(vao: u32) {
+    STATE.with(|state| state.borrow_mut().vao = vao);
+    record(GlCall::BindVertexArray(vao));
+}
+
+extern "system" fn bind_buffer(target: u32, buffer: u32) {
+    if target == gl::ELEMENT_ARRAY_BUFFER {
+        STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            let vao = state.vao;
+            state.element_buffers.insert(vao, buffer);
+        });
+    }
+    record(GlCall::BindBuffer(target, buffer));
+}
+
+extern "system" fn buffer_data(target: u32, size: isize, _data: *const c_void, _usage: u32) {
+    record(GlCall::BufferData(target, size));
+}
+
+extern "system" fn vertex_attrib_pointer(index: u32, size: i32, _ty: u32, _normalized: u8, _stride: i32, _offset: *const c_void) {
+    record(GlCall::VertexAttribPointer(index, size));
+}
+
+extern "system" fn enable_vertex_attrib_array(index: u32) {
+    record(GlCall::EnableVertexAttribArray(index));
+}
+
+extern "system" fn draw_elements(mode: u32, count: i32, _ty: u32, _indices: *const c_void) {
+    record(GlCall::DrawElements(mode, count));
+}
+
+extern "system" fn enable(cap: u32) {
+    record(GlCall::Enable(cap));
+}
+
+extern "system" fn disable(cap: u32) {
+    record(GlCall::Disable(cap));
+}
+
+extern "system" fn cull_face(mode: u32) {
+    record(GlCall::CullFace(mode));
+}
+
+extern "system" fn polygon_mode(face: u32, mode: u32) {
+    record(GlCall::PolygonMode(face, mode));
+}
+
+extern "system" fn clear_color(r: f32, g: f32, b: f32, a: f32) {
+    record(GlCall::ClearColor([r, g, b, a]));
+}
+
+extern "system" fn depth_func(func: u32) {
+    record(GlCall::DepthFunc(func));
+}
+
+extern "system" fn clear(mask: u32) {
+    record(GlCall::Clear(mask));
+}
+
+extern "system" fn scissor(_x: i32, _y: i32, _width: i32, _height: i32) {}
+
+/// Consultas de estado: programa, VAO y buffer de índices enlazados; el
+/// resto en 0 (el viewport y el scissor tienen 4 valores)
+extern "system" fn get_integer_v(pname: u32, out: *mut i32) {
+    let bound = STATE.with(|state| {
+        let state = state.borrow();
+        match pname {
+            gl::CURRENT_PROGRAM => state.program,
+            gl::VERTEX_ARRAY_BINDING => state.vao,
+            gl::ELEMENT_ARRAY_BUFFER_BINDING => state.element_buffers.get(&state.vao).copied().unwrap_or(0),
+            _ => 0,
+        }
+    });
+    let count = match pname {
+        gl::VIEWPORT | gl::SCISSOR_BOX => 4,
+        _ => 1,
+    };
+    for i in 0..count {
+        unsafe { *out.add(i) = 0 };
+    }
+    unsafe { *out = bound as i32 };
+}
+
+extern "system" fn gen_names(n: i32, ids: *mut u32) {
+    unsafe { generate(n, ids) };
+}
+
+extern "system" fn delete_vertex_arrays(n: i32, ids: *const u32) {
+    record(GlCall::DeleteVertexArrays(unsafe { names(n, ids) }));
+}
+
+extern "system" fn delete_buffers(n: i32, ids: *const u32) {
+    record(GlCall::DeleteBuffers(unsafe { names(n, ids) }));
+}
+
+extern "system" fn create_object(_ty: u32) -> u32 {
+    let mut id = 0;
+    unsafe { generate(1, &mut id) };
+    id
+}
+
+extern "system" fn create_program() -> u32 {
+    create_object(0)
+}
+
+extern "system" fn shader_source(_shader: u32, count: i32, strings: *const *const c_char, _lengths: *const i32) {
+    let source = (0..count.max(0) as usize)
+        .map(|i| unsafe { CStr::from_ptr(*strings.add(i)) }.to_string_lossy().into_owned())
+        .collect();
+    record(GlCall::ShaderSource(source));
+}
+
+/// Compilar, enlazar y adjuntar siempre funciona
+extern "system" fn object_noop(_object: u32) {}
+
+extern "system" fn attach_noop(_program: u32, _shader: u32) {}
+
+/// Estado de compilación / enlace: siempre correcto y sin uniforms ni atributos activos
+extern "system" fn get_object_iv(_object: u32, pname: u32, out: *mut i32) {
+    let value = match pname {
+        gl::COMPILE_STATUS | gl::LINK_STATUS => gl::TRUE as i32,
+        _ => 0,
+    };
+    unsafe { *out = value };
+}
+
+extern "system" fn delete_program(program: u32) {
+    record(GlCall::DeleteProgram(program));
+}
+
+extern "system" fn delete_textures(n: i32, ids: *const u32) {
+    record(GlCall::DeleteTextures(unsafe { names(n, ids) }));
+}
+
+/// Puntero de cada función GL que sabe imitar el mock
+fn resolve(name: &str) -> *const c_void {
+    match name {
+        "glUseProgram" => use_program as *const c_void,
+        "glGetUniformLocation" => get_uniform_location as *const c_void,
+        "glUniform1i" => uniform_1i as *const c_void,
+        "glUniform1f" => uniform_1f as *const c_void,
+        "glUniform3f" => uniform_3f as *const c_void,
+        "glUniformMatrix4fv" => uniform_matrix_4fv as *const c_void,
+        "glGetUniformfv" => get_uniform_fv as *const c_void,
+        "glGetUniformiv" => get_uniform_iv as *const c_void,
+        "glActiveTexture" => active_texture as *const c_void,
+        "glBindTexture" => bind_texture as *const c_void,
+        "glBindVertexArray" => bind_vertex_array as *const c_void,
+        "glBindBuffer" => bind_buffer as *const c_void,
+        "glBufferData" => buffer_data as *const c_void,
+        "glVertexAttribPointer" => vertex_attrib_pointer as *const c_void,
+        "glEnableVertexAttribArray" => enable_vertex_attrib_array as *const c_void,
+        "glDrawElements" => draw_elements as *const c_void,
+        "glEnable" => enable as *const c_void,
+        "glDisable" => disable as *const c_void,
+        "glCullFace" => cull_face as *const c_void,
+        "glPolygonMode" => polygon_mode as *const c_void,
+        "glClearColor" => clear_color as *const c_void,
+        "glDepthFunc" => depth_func as *const c_void,
+        "glClear" => clear as *const c_void,
+        "glScissor" => scissor as *const c_void,
+        "glGetIntegerv" => get_integer_v as *const c_void,
+        "glGenVertexArrays" | "glGenBuffers" | "glGenTextures" => gen_names as *const c_void,
+        "glDeleteVertexArrays" => delete_vertex_arrays as *const c_void,
+        "glDeleteBuffers" => delete_buffers as *const c_void,
+        "glDeleteTextures" => delete_textures as *const c_void,
+        "glCreateShader" => create_object as *const c_void,
+        "glCreateProgram" => create_program as *const c_void,
+        "glShaderSource" => shader_source as *const c_void,
+        "glCompileShader" | "glLinkProgram" | "glDeleteShader" => object_noop as *const c_void,
+        "glAttachShader" | "glDetachShader" => attach_noop as *const c_void,
+        "glGetShaderiv" | "glGetProgramiv" => get_object_iv as *const c_void,
+        "glDeleteProgram" => delete_program as *const c_void,
+        _ => ptr::null(),
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::scene_object::SceneObject;
+
+    #[test]
+    fn test_upload_records_buffers() {
+        install();
+        let positions = [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let normals = [0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0];
+        let obj = SceneObject::from_buffers(&positions, &normals, vec![0, 1, 2]);
+        assert_ne!(obj.vao, 0);
+        assert_eq!(obj.index_count, 3);
+
+        let calls = take_calls();
+        let sizes: Vec<isize> = calls
+            .iter()
+            .filter_map(|call| match call {
+                GlCall::BufferData(_, size) => Some(*size),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(sizes, vec![36, 36, 12]);
+        assert_eq!(calls.last(), Some(&GlCall::BindVertexArray(0)));
+    }
+}
@@ -0,0 +1,250 @@
+// src/graphics/gl_validation.rs
+//
+// Validación de los draws en builds de debug. Un programa sin enlazar, un VAO
+// sin el atributo que lee el shader, cero índices o una matriz con NaN no dan
+// error en GL: simplemente no se dibuja nada. Los pases dibujan con
+// `draw_elements` / `draw_arrays` de este módulo, que antes del draw revisan
+// esas condiciones y avisan por consola qué falló y en qué pase (cada aviso
+// una sola vez). En release se compila solo el draw; en debug se puede apagar
+// con RUST_ENGINE_GL_VALIDATION=0 (las consultas al driver cuestan).
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::ffi::CString;
+use std::sync::OnceLock;
+
+use crate::graphics::shaders::uniform_location;
+use crate::graphics::uniforms::active_uniforms;
+
+/// Variable de entorno que apaga la validación en debug
+pub const GL_VALIDATION_ENV: &str = "RUST_ENGINE_GL_VALIDATION";
+
+thread_local! {
+    /// Pase que está dibujando (lo fija el grafo)
+    static SCOPE: RefCell<String> = const { RefCell::new(String::new()) };
+    /// Avisos ya mostrados
+    static REPORTED: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+fn enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    cfg!(debug_assertions) && *ENABLED.get_or_init(|| std::env::var(GL_VALIDATION_ENV).map_or(true, |v| v != "0"))
+}
+
+/// Nombre con que se identifican los avisos de los próximos draws
+pub fn set_scope(name: &str) {
+    SCOPE.with(|scope| {
+        let mut scope = scope.borrow_mut();
+        scope.clear();
+        scope.push_str(name);
+    });
+}
+
+/// Estado GL del que depende un draw
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DrawState {
+    pub program: u32,
+    pub vao: u32,
+    /// Buffer de índices del VAO (None en un draw sin índices)
+    pub element_buffer: Option<u32>,
+    /// Atributos activos del programa: (nombre, location, habilitado en el VAO)
+    pub attributes: Vec<(String, i32, bool)>,
+    /// Uniforms mat4 del programa y su valor actual
+    pub matrices: Vec<(String, [f32; 16])>,
+    /// Índices o vértices a dibujar
+    pub count: i32,
+}
+
+impl DrawState {
+    /// Consulta el estado actual al driver
+    pub fn query(count: i32, indexed: bool) -> Self {
+        let get = |pname| {
+            let mut value = 0;
+            unsafe { gl::GetIntegerv(pname, &mut value) };
+            value as u32
+        };
+        let mut state = Self {
+            program: get(gl::CURRENT_PROGRAM),
+            vao: get(gl::VERTEX_ARRAY_BINDING),
+            element_buffer: indexed.then(|| get(gl::ELEMENT_ARRAY_BUFFER_BINDING)),
+            count,
+            ..Self::default()
+        };
+        if state.program == 0 {
+            return state;
+        }
+        state.attributes = active_attributes(state.program)
+            .into_iter()
+            .map(|(name, location)| {
+                let mut enabled = 0;
+                if state.vao != 0 {
+                    unsafe { gl::GetVertexAttribiv(location as u32, gl::VERTEX_ATTRIB_ARRAY_ENABLED, &mut enabled) };
+                }
+                (name, location, enabled != 0)
+            })
+            .collect();
+        state.matrices = active_uniforms(state.program)
+            .into_iter()
+            .filter(|(_, kind)| *kind == gl::FLOAT_MAT4)
+            .map(|(name, _)| {
+                let mut value = [0.0; 16];
+                let location = uniform_location(state.program, &name);
+                unsafe { gl::GetUniformfv(state.program, location, value.as_mut_ptr()) };
+                (name, value)
+            })
+            .collect();
+        state
+    }
+}
+
+/// Atributos de entrada del programa con location asignada: (nombre, location)
+fn active_attributes(program: u32) -> Vec<(String, i32)> {
+    let (mut count, mut max_length) = (0, 0);
+    unsafe {
+        gl::GetProgramiv(program, gl::ACTIVE_ATTRIBUTES, &mut count);
+        gl::GetProgramiv(program, gl::ACTIVE_ATTRIBUTE_MAX_LENGTH, &mut max_length);
+    }
+    (0..count.max(0) as u32)
+        .filter_map(|index| {
+            let mut buffer = vec![0u8; max_length.max(1) as usize];
+            let (mut length, mut size, mut kind) = (0, 0, 0);
+            unsafe {
+                gl::GetActiveAttrib(
+                    program, index, buffer.len() as i32,
+                    &mut length, &mut size, &mut kind, buffer.as_mut_ptr() as *mut i8,
+                );
+            }
+            buffer.truncate(length.max(0) as usize);
+            let name = String::from_utf8_lossy(&buffer).into_owned();
+            // gl_VertexID y compañía no vienen del VAO
+            if name.starts_with("gl_") {
+                return None;
+            }
+            let c_name = CString::new(name.as_str()).ok()?;
+            let location = unsafe { gl::GetAttribLocation(program, c_name.as_ptr()) };
+            (location >= 0).then_some((name, location))
+        })
+        .collect()
+}
+
+/// Problemas que harían que el draw no dibuje nada (o basura)
+pub fn check_draw(state: &DrawState) -> Vec<String> {
+    let mut errors = Vec::new();
+    if state.program == 0 {
+        errors.push("no hay programa activo (falta glUseProgram o el shader no enlazó)".to_string());
+    }
+    if state.vao == 0 {
+        errors.push("no hay VAO enlazado".to_string());
+    }
+    if state.element_buffer == Some(0) && state.vao != 0 {
+        errors.push(format!("el VAO {} no tiene buffer de índices", state.vao));
+    }
+    match state.element_buffer {
+        Some(_) if state.count <= 0 => errors.push(format!(
+            "draw con {} índices en el VAO {} (¿malla vacía o sub-malla sin rango?)",
+            state.count, state.vao
+        )),
+        None if state.count < 0 => errors.push(format!("draw con {} vértices", state.count)),
+        _ => {}
+    }
+    if state.vao != 0 {
+        for (name, location, _) in state.attributes.iter().filter(|(_, _, enabled)| !enabled) {
+            errors.push(format!(
+                "el programa {} lee el atributo '{}' (location {}) pero el VAO {} no lo tiene habilitado",
+                state.program, name, location, state.vao
+            ));
+        }
+    }
+    for (name, value) in &state.matrices {
+        if value.iter().any(|v| !v.is_finite()) {
+            errors.push(format!("la matriz '{}' tiene valores NaN o infinitos", name));
+        }
+    }
+    errors
+}
+
+fn validate(count: i32, indexed: bool) {
+    if !enabled() {
+        return;
+    }
+    let errors = check_draw(&DrawState::query(count, indexed));
+    if errors.is_empty() {
+        return;
+    }
+    let scope = SCOPE.with(|scope| scope.borrow().clone());
+    REPORTED.with(|reported| {
+        let mut reported = reported.borrow_mut();
+        for error in errors {
+            let message = if scope.is_empty() { error } else { format!("[{}] {}", scope, error) };
+            if reported.insert(message.clone()) {
+                eprintln!("Validación GL: {}", message);
+            }
+        }
+    });
+}
+
+/// `glDrawElements` con índices u32, empezando `offset` bytes dentro del buffer
+pub fn draw_elements(mode: u32, count: i32, offset: usize) {
+    validate(count, true);
+    unsafe {
+        gl::DrawElements(mode, count, gl::UNSIGNED_INT, offset as *const _);
+    }
+}
+
+/// `glDrawArrays`
+pub fn draw_arrays(mode: u32, first: i32, count: i32) {
+    validate(count, false);
+    unsafe {
+        gl::DrawArrays(mode, first, count);
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::gl_mock;
+
+    #[test]
+    fn test_draw_checks() {
+        let ok = DrawState {
+            program: 3,
+            vao: 7,
+            element_buffer: Some(9),
+            attributes: vec![("position".to_string(), 0, true), ("normal".to_string(), 1, true)],
+            matrices: vec![("model".to_string(), [0.0; 16])],
+            count: 36,
+        };
+        assert!(check_draw(&ok).is_empty());
+
+        let mut broken = ok.clone();
+        broken.attributes[1].2 = false;
+        broken.matrices[0].1[5] = f32::NAN;
+        broken.count = 0;
+        let errors = check_draw(&broken);
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].contains("0 índices"));
+        assert!(errors[1].contains("'normal' (location 1)"));
+        assert!(errors[2].contains("'model'"));
+
+        let unbound = DrawState { count: 3, ..DrawState::default() };
+        assert_eq!(check_draw(&unbound).len(), 2);
+        // Sin índices se pueden dibujar 0 vértices (capas vacías)
+        assert!(check_draw(&DrawState { element_buffer: None, count: 0, ..ok }).is_empty());
+
+        // Con el backend falso se lee el programa, el VAO y sus índices
+        gl_mock::install();
+        unsafe {
+            gl::UseProgram(4);
+            gl::BindVertexArray(8);
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 11);
+            gl::BindVertexArray(0);
+            gl::BindVertexArray(8);
+        }
+        let state = DrawState::query(6, true);
+        assert_eq!((state.program, state.vao, state.element_buffer), (4, 8, Some(11)));
+        assert!(check_draw(&state).is_empty());
+        draw_elements(gl::TRIANGLES, 6, 12);
+        assert_eq!(gl_mock::take_calls().last(), Some(&gl_mock::GlCall::DrawElements(gl::TRIANGLES, 6)));
+    }
+}
@@ -0,0 +1,86 @@
+// src/graphics/gpu_resources.rs
+//
+// Recuperación después de perder el contexto OpenGL (reset del driver o una
+// ventana recreada, p. ej. al pasar a pantalla completa en algunos drivers).
+// Con el contexto se pierden todos los objetos GL: el Renderer los vuelve a
+// crear desde lo que guarda en CPU (ver `Renderer::rebuild_gpu`).
+//
+// Los nombres viejos no se pueden borrar: en el contexto nuevo el mismo número
+// puede ser ya otro objeto. Cada dueño de objetos GL guarda la generación del
+// contexto en que los creó y su Drop solo los borra si sigue siendo la actual.
+
+use std::cell::Cell;
+
+thread_local! {
+    // El contexto GL es de un hilo, así que la generación también
+    static GENERATION: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Contexto GL en el que se crearon unos objetos
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextGeneration(u64);
+
+impl ContextGeneration {
+    pub fn current() -> Self {
+        Self(GENERATION.with(Cell::get))
+    }
+
+    /// Los objetos siguen vivos (no se perdió el contexto desde que se crearon)
+    pub fn is_current(self) -> bool {
+        self == Self::current()
+    }
+
+    /// Marca como perdidos todos los objetos creados hasta ahora; se llama con
+    /// el contexto nuevo ya activo y antes de crear nada en él
+    pub fn advance() -> Self {
+        GENERATION.with(|generation| generation.set(generation.get() + 1));
+        Self::current()
+    }
+}
+
+impl Default for ContextGeneration {
+    fn default() -> Self {
+        Self::current()
+    }
+}
+
+/// El driver reseteó el contexto actual (solo se detecta en contextos
+/// creados con robustez, ver `Window`); hay que recrearlo
+pub fn context_lost() -> bool {
+    gl::GetGraphicsResetStatus::is_loaded() && unsafe { gl::GetGraphicsResetStatus() } != gl::NO_ERROR
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::gl_mock::{self, GlCall};
+    use crate::graphics::scene_object::SceneObject;
+
+    #[test]
+    fn test_rebuild_after_context_loss() {
+        gl_mock::install();
+        let positions = [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let normals = [0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0];
+        let mut obj = SceneObject::from_buffers(&positions, &normals, vec![0, 1, 2]);
+        obj.set_uvs(vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]);
+        let generation = ContextGeneration::current();
+        assert!(generation.is_current());
+
+        ContextGeneration::advance();
+        assert!(!generation.is_current());
+        let old_vao = obj.vao;
+        gl_mock::take_calls();
+        obj.rebuild_gpu();
+
+        let calls = gl_mock::take_calls();
+        assert_ne!(obj.vao, old_vao);
+        // Se vuelven a subir posiciones, normales, índices y UVs, sin borrar nada
+        let uploads = calls.iter().filter(|c| matches!(c, GlCall::BufferData(..))).count();
+        assert_eq!(uploads, 4);
+        assert!(calls.contains(&GlCall::EnableVertexAttribArray(2)));
+        assert!(!calls.iter().any(|c| matches!(c, GlCall::DeleteVertexArrays(_) | GlCall::DeleteBuffers(_))));
+        assert_eq!(obj.index_count, 3);
+        assert_eq!(obj.mesh.uvs.len(), 3);
+    }
+}
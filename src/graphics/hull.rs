@@ -0,0 +1,313 @@
+// src/graphics/hull.rs
+//
+// Envolvente convexa (quickhull) y descomposición convexa aproximada, para
+// usar volúmenes simples como colisionadores en vez de la malla completa.
+//
+// La descomposición parte la malla en dos por el medio de su eje más largo
+// mientras alguna parte sea demasiado cóncava: cuánto queda alguna de sus caras
+// por debajo de la envolvente, medido sobre la normal de la cara y relativo a
+// la diagonal de la malla. No es tan fina como V-HACD pero es rápida y
+// determinista.
+
+use crate::graphics::mesh::Mesh;
+use crate::math::{aabb::{axis, Aabb}, vec3::Vec3};
+
+/// Cara de la envolvente en construcción (orientada hacia afuera)
+struct HullFace {
+    vertices: [usize; 3],
+    normal: [f64; 3],
+    offset: f64,
+    /// Puntos por encima de la cara que todavía no están en la envolvente
+    outside: Vec<usize>,
+    alive: bool,
+}
+
+type Point = [f64; 3];
+
+fn sub(a: Point, b: Point) -> Point {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: Point, b: Point) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Point, b: Point) -> Point {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+impl HullFace {
+    fn new(points: &[Point], vertices: [usize; 3]) -> Self {
+        let [a, b, c] = vertices.map(|v| points[v]);
+        let n = cross(sub(b, a), sub(c, a));
+        let length = dot(n, n).sqrt().max(f64::MIN_POSITIVE);
+        let normal = n.map(|x| x / length);
+        Self { vertices, normal, offset: dot(normal, a), outside: Vec::new(), alive: true }
+    }
+
+    fn distance(&self, p: Point) -> f64 {
+        dot(self.normal, p) - self.offset
+    }
+}
+
+/// Envolvente convexa de un conjunto de puntos (quickhull). Falla si los
+/// puntos no abarcan un volumen (menos de 4 o todos en un plano).
+pub fn convex_hull(positions: &[[f32; 3]]) -> Result<Mesh, String> {
+    let points: Vec<Point> = positions.iter().map(|p| p.map(f64::from)).collect();
+    if points.len() < 4 {
+        return Err("Se necesitan al menos 4 puntos para la envolvente convexa".to_string());
+    }
+
+    // 1) Tetraedro inicial con puntos extremos
+    let extent = (0..3)
+        .map(|axis| {
+            let values = points.iter().map(|p| p[axis]);
+            values.clone().fold(f64::NEG_INFINITY, f64::max) - values.fold(f64::INFINITY, f64::min)
+        })
+        .fold(0.0, f64::max);
+    let epsilon = extent * 1e-9;
+
+    let farthest = |score: &dyn Fn(Point) -> f64| {
+        (0..points.len()).max_by(|&a, &b| score(points[a]).total_cmp(&score(points[b]))).unwrap()
+    };
+    let p0 = farthest(&|p| p[0]);
+    let p1 = farthest(&|p| dot(sub(p, points[p0]), sub(p, points[p0])));
+    let line = sub(points[p1], points[p0]);
+    let p2 = farthest(&|p| {
+        let c = cross(line, sub(p, points[p0]));
+        dot(c, c)
+    });
+    let plane = cross(line, sub(points[p2], points[p0]));
+    let p3 = farthest(&|p| dot(plane, sub(p, points[p0])).abs());
+    let volume = dot(plane, sub(points[p3], points[p0]));
+    if dot(line, line).sqrt() <= epsilon || volume.abs() <= epsilon * extent * extent {
+        return Err("Los puntos están en un plano: no hay envolvente con volumen".to_string());
+    }
+
+    let mut faces: Vec<HullFace> = if volume > 0.0 {
+        // p3 del lado de la normal de (p0, p1, p2): esa cara va al revés
+        vec![[p0, p2, p1], [p0, p1, p3], [p1, p2, p3], [p2, p0, p3]]
+    } else {
+        vec![[p0, p1, p2], [p0, p3, p1], [p1, p3, p2], [p2, p3, p0]]
+    }
+    .into_iter()
+    .map(|vertices| HullFace::new(&points, vertices))
+    .collect();
+
+    // 2) Cada punto queda en la primera cara que ve
+    let assign = |faces: &mut [HullFace], candidates: &[usize], first_face: usize| {
+        for &point in candidates {
+            if let Some(face) = faces[first_face..]
+                .iter_mut()
+                .find(|f| f.alive && f.distance(points[point]) > epsilon)
+            {
+                face.outside.push(point);
+            }
+        }
+    };
+    let all: Vec<usize> = (0..points.len()).filter(|p| ![p0, p1, p2, p3].contains(p)).collect();
+    assign(&mut faces, &all, 0);
+
+    // 3) Agregar el punto más lejano de alguna cara hasta que no quede ninguno afuera
+    while let Some(face_index) = faces.iter().position(|f| f.alive && !f.outside.is_empty()) {
+        let face = &faces[face_index];
+        let apex = *face
+            .outside
+            .iter()
+            .max_by(|&&a, &&b| face.distance(points[a]).total_cmp(&face.distance(points[b])))
+            .unwrap();
+
+        let visible: Vec<usize> = (0..faces.len())
+            .filter(|&f| faces[f].alive && faces[f].distance(points[apex]) > epsilon)
+            .collect();
+        // Horizonte: aristas de caras visibles cuya gemela no es visible
+        let edges: Vec<(usize, usize)> = visible
+            .iter()
+            .flat_map(|&f| {
+                let [a, b, c] = faces[f].vertices;
+                [(a, b), (b, c), (c, a)]
+            })
+            .collect();
+        let horizon: Vec<(usize, usize)> = edges.iter().copied().filter(|&(a, b)| !edges.contains(&(b, a))).collect();
+
+        let mut orphans = Vec::new();
+        for &f in &visible {
+            faces[f].alive = false;
+            orphans.append(&mut faces[f].outside);
+        }
+        orphans.retain(|&p| p != apex);
+
+        let first_new = faces.len();
+        for (a, b) in horizon {
+            faces.push(HullFace::new(&points, [a, b, apex]));
+        }
+        assign(&mut faces, &orphans, first_new);
+    }
+
+    // 4) Compactar los vértices usados
+    let mut remap = vec![u32::MAX; points.len()];
+    let mut hull_positions = Vec::new();
+    let mut indices = Vec::new();
+    for face in faces.iter().filter(|f| f.alive) {
+        for v in face.vertices {
+            if remap[v] == u32::MAX {
+                remap[v] = hull_positions.len() as u32;
+                hull_positions.push(positions[v]);
+            }
+            indices.push(remap[v]);
+        }
+    }
+    Ok(Mesh::new(hull_positions, indices))
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DecompositionSettings {
+    /// Concavidad aceptable por parte, relativa a la diagonal de la malla
+    pub concavity: f32,
+    /// Cortes como máximo (hasta 2^max_depth partes)
+    pub max_depth: u32,
+}
+
+impl Default for DecompositionSettings {
+    fn default() -> Self {
+        Self { concavity: 0.02, max_depth: 4 }
+    }
+}
+
+/// Concavidad de una parte: la mayor distancia que hay que recorrer desde el
+/// centro de un triángulo, a lo largo de su normal, hasta salir de la
+/// envolvente. Es 0 si todas las caras están sobre la envolvente.
+fn concavity(part: &Mesh, hull: &Mesh) -> f32 {
+    let hull_points: Vec<Point> = hull.positions.iter().map(|p| p.map(f64::from)).collect();
+    let faces: Vec<HullFace> = (0..hull.triangle_count())
+        .filter_map(|t| hull.triangle(t))
+        .map(|tri| HullFace::new(&hull_points, tri.map(|v| v as usize)))
+        .collect();
+    (0..part.triangle_count())
+        .filter_map(|t| part.triangle(t))
+        .filter_map(|corners| {
+            let [a, b, c] = corners.map(|v| part.positions[v as usize].map(f64::from));
+            let n = cross(sub(b, a), sub(c, a));
+            let length = dot(n, n).sqrt();
+            if length <= 0.0 {
+                return None;
+            }
+            let direction = n.map(|x| x / length);
+            let center = [0, 1, 2].map(|i| (a[i] + b[i] + c[i]) / 3.0);
+            // Salida del rayo: el plano más cercano entre los que mira de frente
+            faces
+                .iter()
+                .filter(|f| dot(f.normal, direction) > 1e-9)
+                .map(|f| -f.distance(center) / dot(f.normal, direction))
+                .reduce(f64::min)
+        })
+        .fold(0.0, f64::max) as f32
+}
+
+/// Partes convexas que cubren la malla (cada una es una envolvente)
+pub fn convex_decomposition(mesh: &Mesh, settings: &DecompositionSettings) -> Vec<Mesh> {
+    let diagonal = mesh.bvh().bounds().size().magnitude();
+    let mut parts = Vec::new();
+    decompose(mesh, diagonal * settings.concavity, settings.max_depth, &mut parts);
+    parts
+}
+
+fn decompose(part: &Mesh, tolerance: f32, depth: u32, parts: &mut Vec<Mesh>) {
+    let Ok(hull) = convex_hull(&part.positions) else { return };
+    if depth == 0 || concavity(part, &hull) <= tolerance {
+        parts.push(hull);
+        return;
+    }
+
+    // Partir por el medio del eje más largo, recortando los triángulos que cruzan
+    let mut bounds = Aabb::EMPTY;
+    for p in &part.positions {
+        bounds.grow(Vec3::from(*p));
+    }
+    let split_axis = bounds.largest_axis();
+    let middle = axis(bounds.center(), split_axis);
+    let mut halves = [Vec::new(), Vec::new()];
+    for triangle in 0..part.triangle_count() {
+        let Some(corners) = part.triangle(triangle) else { continue };
+        let corners = corners.map(|v| part.positions[v as usize]);
+        for (side, half) in halves.iter_mut().enumerate() {
+            let sign = if side == 0 { -1.0 } else { 1.0 };
+            let polygon = clip(&corners, |p| sign * (p[split_axis] - middle));
+            // Abanico desde el primer vértice del polígono recortado
+            for i in 1..polygon.len().saturating_sub(1) {
+                half.extend([polygon[0], polygon[i], polygon[i + 1]]);
+            }
+        }
+    }
+    if halves.iter().any(|h| h.len() < 4) {
+        parts.push(hull);
+        return;
+    }
+    for positions in halves {
+        // Triángulos sueltos (sin soldar): alcanza para la envolvente y las normales
+        let indices = (0..positions.len() as u32).collect();
+        decompose(&Mesh::new(positions, indices), tolerance, depth - 1, parts);
+    }
+}
+
+/// Parte del polígono donde `side(p) >= 0` (Sutherland-Hodgman con un plano)
+fn clip(polygon: &[[f32; 3]], side: impl Fn([f32; 3]) -> f32) -> Vec<[f32; 3]> {
+    let mut clipped = Vec::new();
+    for (i, &current) in polygon.iter().enumerate() {
+        let next = polygon[(i + 1) % polygon.len()];
+        let (dc, dn) = (side(current), side(next));
+        if dc >= 0.0 {
+            clipped.push(current);
+        }
+        if (dc >= 0.0) != (dn >= 0.0) {
+            let t = dc / (dc - dn);
+            clipped.push([0, 1, 2].map(|k| current[k] + (next[k] - current[k]) * t));
+        }
+    }
+    clipped
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::mesh_ops::edge_report;
+
+    fn box_points(min: [f32; 3], max: [f32; 3]) -> Vec<[f32; 3]> {
+        (0..8)
+            .map(|i| [0, 1, 2].map(|axis| if i & (1 << axis) != 0 { max[axis] } else { min[axis] }))
+            .collect()
+    }
+
+    #[test]
+    fn test_hull_of_cube_with_interior_points() {
+        let mut points = box_points([0.0; 3], [1.0; 3]);
+        points.extend([[0.5, 0.5, 0.5], [0.2, 0.7, 0.4], [0.9, 0.1, 0.3]]);
+        let hull = convex_hull(&points).unwrap();
+        assert_eq!(hull.positions.len(), 8);
+        assert_eq!(hull.triangle_count(), 12);
+        assert!(edge_report(&hull).is_watertight());
+        let props = hull.mass_properties(1.0).unwrap();
+        assert!((props.volume - 1.0).abs() < 1e-5);
+
+        assert!(convex_hull(&[[0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 1.0, 0.0]]).is_err());
+    }
+
+    #[test]
+    fn test_decomposition_splits_l_shape() {
+        // Dos cajas en L: la envolvente única tapa la esquina vacía
+        let mut positions = box_points([0.0; 3], [4.0, 1.0, 1.0]);
+        positions.extend(box_points([0.0, 1.0, 0.0], [1.0, 4.0, 1.0]));
+        let faces = [0, 2, 1, 1, 2, 3, 4, 5, 6, 5, 7, 6, 0, 1, 4, 1, 5, 4, 2, 6, 3, 3, 6, 7, 0, 4, 2, 2, 4, 6, 1, 3, 5, 3, 7, 5];
+        let indices = faces.iter().copied().chain(faces.iter().map(|i| i + 8)).collect();
+        let mesh = Mesh::new(positions, indices);
+
+        let single = convex_decomposition(&mesh, &DecompositionSettings { max_depth: 0, ..Default::default() });
+        assert_eq!(single.len(), 1);
+        let parts = convex_decomposition(&mesh, &DecompositionSettings::default());
+        assert!(parts.len() > 1);
+        let total: f32 = parts.iter().map(|p| p.mass_properties(1.0).unwrap().volume).sum();
+        // Más ajustado que la envolvente única (volumen real: 7)
+        assert!(total < single[0].mass_properties(1.0).unwrap().volume);
+    }
+}
@@ -0,0 +1,193 @@
+// src/graphics/lighting.rs
+//
+// Luz de la escena: un sol direccional, luces de relleno, un cielo
+// de color uniforme y el color difuso de las superficies. La usan el render en
+// tiempo real (OpaquePass) y el trazador de caminos, así las dos vistas
+// coinciden. Los `LightingRig` arman una iluminación completa de una vez para
+// quien no quiere configurar luces.
+
+use serde::{Deserialize, Serialize};
+
+use crate::math::vec3::Vec3;
+
+/// Luces de relleno que entiende el shader principal
+pub const FILL_LIGHTS: usize = 2;
+
+/// Luz direccional que se suma al sol (relleno, contraluz). Negra = apagada.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct FillLight {
+    /// Dirección hacia la luz (unitaria)
+    pub direction: Vec3,
+    pub color: [f32; 3],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Lighting {
+    /// Dirección hacia el sol (unitaria)
+    pub sun_direction: Vec3,
+    pub sun_color: [f32; 3],
+    /// Radiancia del cielo en todas las direcciones
+    pub sky_color: [f32; 3],
+    /// Color difuso de todas las superficies
+    pub albedo: [f32; 3],
+    /// Fracción del color base que se ve sin luz directa en tiempo real
+    #[serde(default = "default_ambient")]
+    pub ambient: f32,
+    #[serde(default)]
+    pub fill_lights: [FillLight; FILL_LIGHTS],
+}
+
+fn default_ambient() -> f32 {
+    0.1
+}
+
+impl Default for Lighting {
+    fn default() -> Self {
+        Self {
+            sun_direction: Vec3::new(1.0, 1.0, 1.0).normalize(),
+            sun_color: [1.0, 1.0, 1.0],
+            sky_color: [0.25, 0.27, 0.3],
+            albedo: [0.8, 0.8, 0.8],
+            ambient: default_ambient(),
+            fill_lights: [FillLight::default(); FILL_LIGHTS],
+        }
+    }
+}
+
+/// Iluminaciones armadas; se guardan en el archivo de escena
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LightingRig {
+    /// Estudio de tres puntos: principal arriba a la derecha, relleno suave
+    /// a la izquierda y contraluz desde atrás, respecto de la vista frontal
+    Studio,
+    /// Sol y cielo según la hora (0 a 24). `speed` en horas por segundo hace
+    /// correr el día; 0 la deja fija.
+    Outdoor { hour: f32, speed: f32 },
+    /// Sin luces direccionales: todo igual de iluminado
+    Ambient,
+}
+
+impl LightingRig {
+    /// Rig al aire libre a media mañana, quieto
+    pub const MORNING: Self = Self::Outdoor { hour: 10.0, speed: 0.0 };
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LightingRig::Studio => "estudio",
+            LightingRig::Outdoor { .. } => "exterior",
+            LightingRig::Ambient => "ambiente uniforme",
+        }
+    }
+
+    /// El siguiente rig para recorrerlos con una tecla
+    pub fn next(&self) -> Self {
+        match self {
+            LightingRig::Studio => Self::MORNING,
+            LightingRig::Outdoor { .. } => LightingRig::Ambient,
+            LightingRig::Ambient => LightingRig::Studio,
+        }
+    }
+
+    /// Avanza el día de un rig exterior animado; devuelve si cambió la luz
+    pub fn advance(&mut self, dt: f32) -> bool {
+        match self {
+            LightingRig::Outdoor { hour, speed } if *speed != 0.0 => {
+                *hour = (*hour + *speed * dt).rem_euclid(24.0);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn lighting(&self) -> Lighting {
+        let base = Lighting::default();
+        match *self {
+            LightingRig::Studio => Lighting {
+                sun_direction: Vec3::new(1.0, 1.2, 1.5).normalize(),
+                sun_color: [1.0, 0.97, 0.92],
+                sky_color: [0.2, 0.2, 0.22],
+                ambient: 0.06,
+                fill_lights: [
+                    FillLight { direction: Vec3::new(-1.5, 0.3, 1.0).normalize(), color: [0.3, 0.32, 0.36] },
+                    FillLight { direction: Vec3::new(0.0, 1.0, -1.5).normalize(), color: [0.5, 0.5, 0.5] },
+                ],
+                ..base
+            },
+            LightingRig::Outdoor { hour, .. } => outdoor(hour, base),
+            LightingRig::Ambient => Lighting {
+                sun_color: [0.0; 3],
+                sky_color: [0.8, 0.8, 0.8],
+                ambient: 0.85,
+                ..base
+            },
+        }
+    }
+}
+
+/// Sol que sale por +X a las 6, pasa alto hacia +Z al mediodía y se pone
+/// por -X a las 18; de noche queda una luna tenue
+fn outdoor(hour: f32, base: Lighting) -> Lighting {
+    let angle = (hour - 6.0) / 12.0 * std::f32::consts::PI;
+    let height = angle.sin();
+    let sun_direction = Vec3::new(angle.cos(), height, 0.4).normalize();
+    // Qué tanto es de día, y qué tan blanco está el sol (rojizo cerca del horizonte)
+    let day = smoothstep(-0.1, 0.2, height);
+    let white = smoothstep(0.0, 0.4, height);
+    let sun_strength = smoothstep(-0.02, 0.1, height);
+    let mix = |a: [f32; 3], b: [f32; 3], t: f32| [0, 1, 2].map(|i| a[i] + (b[i] - a[i]) * t);
+    let sun_color = mix([1.0, 0.45, 0.2], [1.0, 0.96, 0.9], white).map(|c| c * sun_strength);
+    let sky_color = mix([0.02, 0.03, 0.06], [0.35, 0.45, 0.6], day);
+    Lighting {
+        sun_direction,
+        sun_color,
+        sky_color,
+        ambient: 0.04 + 0.08 * day,
+        fill_lights: [
+            // Luz del cielo desde arriba
+            FillLight { direction: Vec3::UNIT_Y, color: sky_color.map(|c| c * 0.5) },
+            // Luna
+            FillLight {
+                direction: Vec3::new(-0.3, 1.0, 0.2).normalize(),
+                color: [0.08, 0.1, 0.16].map(|c| c * (1.0 - day)),
+            },
+        ],
+        ..base
+    }
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outdoor_day_and_night() {
+        let noon = LightingRig::Outdoor { hour: 12.0, speed: 0.0 }.lighting();
+        let dusk = LightingRig::Outdoor { hour: 18.5, speed: 0.0 }.lighting();
+        let night = LightingRig::Outdoor { hour: 0.0, speed: 0.0 }.lighting();
+        assert!(noon.sun_direction.y > 0.9);
+        assert!(noon.sun_color[2] > 0.85);
+        assert_eq!(night.sun_color, [0.0; 3]);
+        assert!(dusk.sky_color[2] < noon.sky_color[2]);
+        assert!(night.fill_lights[1].color[2] > 0.1);
+
+        // El día animado da la vuelta a las 24
+        let mut rig = LightingRig::Outdoor { hour: 23.0, speed: 2.0 };
+        assert!(rig.advance(1.0));
+        assert_eq!(rig, LightingRig::Outdoor { hour: 1.0, speed: 2.0 });
+        assert!(!LightingRig::Studio.advance(1.0));
+
+        // Las iluminaciones guardadas sin ambient ni relleno se siguen leyendo
+        let lighting: Lighting = ron::from_str(
+            "(sun_direction: (x: 0.0, y: 1.0, z: 0.0), sun_color: (1.0, 1.0, 1.0), sky_color: (0.0, 0.0, 0.0), albedo: (0.5, 0.5, 0.5))",
+        )
+        .unwrap();
+        assert_eq!(lighting.ambient, 0.1);
+        assert_eq!(lighting.fill_lights, [FillLight::default(); FILL_LIGHTS]);
+    }
+}
@@ -0,0 +1,286 @@
+// src/graphics/lightmap.rs
+//
+// Horneado offline de lightmaps por objeto con el trazador de caminos en CPU.
+// Cada texel del lightmap se ubica en la malla por sus UVs de lightmap (un
+// juego aparte de las de textura y sin solapes, ver `uv::lightmap_uvs`) y
+// guarda oclusión ambiental o iluminación global. El resultado se sube como
+// textura y el shader principal lo aplica. El lightmap corresponde a la pose
+// de los objetos al hornear, así que sirve para escenas estáticas.
+
+use crate::engine::progress::ProgressToken;
+use crate::graphics::capture::save_image;
+use crate::graphics::path_tracer::{Rng, TraceScene};
+
+/// Atributo del shader principal con las UVs del lightmap
+pub const LIGHTMAP_UV_LOCATION: u32 = 12;
+
+/// Qué se guarda en el lightmap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightmapMode {
+    /// Oclusión ambiental: multiplica la iluminación en tiempo real
+    AmbientOcclusion,
+    /// Irradiancia total (sol con sombras + cielo + rebotes): reemplaza la iluminación en tiempo real
+    GlobalIllumination,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BakeSettings {
+    pub mode: LightmapMode,
+    /// Lado del lightmap en texels
+    pub resolution: u32,
+    /// Rayos por texel
+    pub samples: u32,
+    /// Alcance de la oclusión, como fracción de la diagonal de la escena
+    pub ao_distance: f32,
+    /// Rebotes difusos después del primero (solo iluminación global)
+    pub bounces: u32,
+    pub seed: u64,
+}
+
+impl Default for BakeSettings {
+    fn default() -> Self {
+        Self {
+            mode: LightmapMode::AmbientOcclusion,
+            resolution: 128,
+            samples: 64,
+            ao_distance: 0.2,
+            bounces: 1,
+            seed: 1,
+        }
+    }
+}
+
+/// Resultado del horneado en CPU (valores lineales, fila 0 = v = 0)
+#[derive(Debug, Clone)]
+pub struct Lightmap {
+    pub width: u32,
+    pub height: u32,
+    pub mode: LightmapMode,
+    pub texels: Vec<[f32; 3]>,
+}
+
+/// Lightmap ya subido a la GPU
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LightmapTexture {
+    pub texture: u32,
+    pub mode: LightmapMode,
+}
+
+impl Lightmap {
+    /// Sube el lightmap como textura RGB16F con filtrado lineal
+    pub fn upload(&self) -> LightmapTexture {
+        let mut texture = 0;
+        unsafe {
+            gl::GenTextures(1, &mut texture);
+            gl::BindTexture(gl::TEXTURE_2D, texture);
+            gl::TexImage2D(
+                gl::TEXTURE_2D, 0, gl::RGB16F as i32,
+                self.width as i32, self.height as i32, 0,
+                gl::RGB, gl::FLOAT, self.texels.as_ptr() as *const _,
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+        LightmapTexture { texture, mode: self.mode }
+    }
+
+    /// Guarda el lightmap como imagen (`.exr` con los valores lineales, o una
+    /// vista previa recortada a [0, 1] en cualquier otro formato)
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        save_image(path, self.width, self.height, &self.texels)
+    }
+}
+
+/// Hornea el lightmap del objeto `object` de `scene`, informando por
+/// triángulo a `progress` (con `Err(CANCELLED)` si lo cancelan)
+pub fn bake(scene: &TraceScene, object: usize, settings: &BakeSettings, progress: &ProgressToken) -> Result<Lightmap, String> {
+    let mesh = scene.mesh(object);
+    if mesh.lightmap_uvs.is_empty() || mesh.lightmap_uvs.len() != mesh.indices.len() {
+        return Err(format!("El objeto {} no tiene UVs de lightmap", object));
+    }
+    let size = settings.resolution.max(1);
+    let model = scene.model(object);
+    let ao_distance = settings.ao_distance * scene.bounds().size().magnitude();
+    let mut rng = Rng::new(settings.seed.wrapping_add(object as u64));
+
+    let mut sums = vec![[0.0_f32; 3]; (size * size) as usize];
+    let mut counts = vec![0u32; sums.len()];
+
+    for triangle in 0..mesh.triangle_count() {
+        progress.step(triangle, mesh.triangle_count())?;
+        let Some(corners) = mesh.triangle(triangle) else { continue };
+        let Some(normal) = scene.triangle_normal(object, triangle) else { continue };
+        let world = corners.map(|v| model.transform_point(mesh.position(v)));
+        // UVs en coordenadas de texel
+        let uv = [0, 1, 2].map(|k| {
+            let [u, v] = mesh.lightmap_uvs[triangle * 3 + k];
+            [u * size as f32, v * size as f32]
+        });
+
+        let area = edge(uv[0], uv[1], uv[2]);
+        if area.abs() < 1e-12 {
+            continue;
+        }
+        let min_x = uv.iter().map(|p| p[0]).fold(f32::INFINITY, f32::min).floor().max(0.0) as u32;
+        let min_y = uv.iter().map(|p| p[1]).fold(f32::INFINITY, f32::min).floor().max(0.0) as u32;
+        let max_x = (uv.iter().map(|p| p[0]).fold(f32::NEG_INFINITY, f32::max).ceil() as i64).min(size as i64 - 1);
+        let max_y = (uv.iter().map(|p| p[1]).fold(f32::NEG_INFINITY, f32::max).ceil() as i64).min(size as i64 - 1);
+
+        for y in min_y as i64..=max_y {
+            for x in min_x as i64..=max_x {
+                let center = [x as f32 + 0.5, y as f32 + 0.5];
+                let w0 = edge(uv[1], uv[2], center) / area;
+                let w1 = edge(uv[2], uv[0], center) / area;
+                let w2 = 1.0 - w0 - w1;
+                if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                    continue;
+                }
+                let point = world[0] * w0 + world[1] * w1 + world[2] * w2;
+                let value = match settings.mode {
+                    LightmapMode::AmbientOcclusion => {
+                        let ao = scene.ambient_occlusion(point, normal, settings.samples, ao_distance, &mut rng);
+                        [ao; 3]
+                    }
+                    LightmapMode::GlobalIllumination => {
+                        scene.irradiance(point, normal, settings.samples, settings.bounces, &mut rng)
+                    }
+                };
+                let index = (y as u32 * size + x as u32) as usize;
+                for (sum, v) in sums[index].iter_mut().zip(value) {
+                    *sum += v;
+                }
+                counts[index] += 1;
+            }
+        }
+    }
+
+    let mut texels: Vec<Option<[f32; 3]>> = sums
+        .iter()
+        .zip(&counts)
+        .map(|(sum, &count)| (count > 0).then(|| sum.map(|s| s / count as f32)))
+        .collect();
+    // Extender los bordes de las islas para que el filtrado no mezcle texels vacíos
+    for _ in 0..2 {
+        texels = dilate(&texels, size);
+    }
+    let empty = match settings.mode {
+        LightmapMode::AmbientOcclusion => [1.0; 3],
+        LightmapMode::GlobalIllumination => [0.0; 3],
+    };
+
+    Ok(Lightmap {
+        width: size,
+        height: size,
+        mode: settings.mode,
+        texels: texels.into_iter().map(|t| t.unwrap_or(empty)).collect(),
+    })
+}
+
+/// Hornea los lightmaps de todos los objetos de `scene` que tienen UVs de lightmap, cada
+/// uno en su parte de `progress`: (índice del objeto, lightmap). Los que no se
+/// pueden hornear se informan y se saltean; si lo cancelan no devuelve ninguno,
+/// así los objetos quedan como estaban.
+pub fn bake_scene(scene: &TraceScene, settings: &BakeSettings, progress: &ProgressToken) -> Result<Vec<(usize, Lightmap)>, String> {
+    let count = scene.object_count();
+    let mut lightmaps = Vec::new();
+    for index in 0..count {
+        match bake(scene, index, settings, &progress.part(index, count)) {
+            Ok(lightmap) => lightmaps.push((index, lightmap)),
+            Err(e) if progress.is_cancelled() => return Err(e),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+    progress.report(1, 1);
+    Ok(lightmaps)
+}
+
+/// Función de arista: doble del área con signo de (a, b, p)
+fn edge(a: [f32; 2], b: [f32; 2], p: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+/// Rellena cada texel vacío con el promedio de sus vecinos llenos
+fn dilate(texels: &[Option<[f32; 3]>], size: u32) -> Vec<Option<[f32; 3]>> {
+    let size = size as i64;
+    (0..size * size)
+        .map(|index| {
+            if let Some(texel) = texels[index as usize] {
+                return Some(texel);
+            }
+            let (x, y) = (index % size, index / size);
+            let mut sum = [0.0; 3];
+            let mut count = 0;
+            for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= size || ny >= size {
+                    continue;
+                }
+                if let Some(texel) = texels[(ny * size + nx) as usize] {
+                    for (s, v) in sum.iter_mut().zip(texel) {
+                        *s += v;
+                    }
+                    count += 1;
+                }
+            }
+            (count > 0).then(|| sum.map(|s| s / count as f32))
+        })
+        .collect()
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::progress::CANCELLED;
+    use crate::graphics::lighting::Lighting;
+    use crate::graphics::mesh::Mesh;
+    use crate::graphics::scene_object::SceneObject;
+    use crate::graphics::uv::lightmap_uvs;
+
+    #[test]
+    fn test_bake_ao_open_and_covered() {
+        // Piso con un techo que tapa solo la mitad x < 0
+        let mut floor = SceneObject::new(0, 0);
+        floor.mesh = Mesh::new(
+            vec![[-1.0, 0.0, -1.0], [1.0, 0.0, -1.0], [1.0, 0.0, 1.0], [-1.0, 0.0, 1.0]],
+            vec![0, 2, 1, 0, 3, 2],
+        );
+        floor.mesh.lightmap_uvs = lightmap_uvs(&floor.mesh, 8);
+        let mut roof = SceneObject::new(0, 0);
+        roof.mesh = Mesh::new(
+            vec![[-3.0, 0.05, -3.0], [0.0, 0.05, -3.0], [0.0, 0.05, 3.0], [-3.0, 0.05, 3.0]],
+            vec![0, 1, 2, 0, 2, 3],
+        );
+
+        let scene = TraceScene::from_objects(&[floor, roof], 1.0, Lighting::default());
+        let settings = BakeSettings { resolution: 8, samples: 32, ..BakeSettings::default() };
+        let lightmap = bake(&scene, 0, &settings, &ProgressToken::new()).unwrap();
+        assert_eq!(lightmap.texels.len(), 64);
+
+        // Por la mitad en z: el texel de x = -0.75 tapado, el de x = 0.75 abierto.
+        // El piso es una sola isla, así que sus UVs son lineales en x y z.
+        let uvs = &scene.mesh(0).lightmap_uvs;
+        let texel = |x: f32| {
+            // Esquinas 0 (-1, -1) y 1 (1, 1) del primer triángulo
+            let t = [(x + 1.0) / 2.0, 0.5];
+            let [u, v] = [0, 1].map(|i| uvs[0][i] + (uvs[1][i] - uvs[0][i]) * t[i]);
+            let [col, row] = [u, v].map(|c| (c * 8.0) as usize);
+            lightmap.texels[row * 8 + col][0]
+        };
+        assert!(texel(-0.75) < 0.3);
+        assert!(texel(0.75) > 0.9);
+
+        assert!(bake(&scene, 1, &settings, &ProgressToken::new()).is_err());
+
+        // Solo el piso tiene UVs de lightmap; cancelado no devuelve nada
+        let progress = ProgressToken::new();
+        let baked = bake_scene(&scene, &settings, &progress).unwrap();
+        assert_eq!(baked.iter().map(|(index, _)| *index).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(progress.fraction(), 1.0);
+        progress.cancel();
+        assert_eq!(bake_scene(&scene, &settings, &progress).err(), Some(CANCELLED.to_string()));
+    }
+}
@@ -0,0 +1,196 @@
+// src/graphics/lines.rs
+//
+// Líneas en espacio mundo dibujadas encima de la escena (contornos de corte,
+// guías, depuración). Cada herramienta escribe su propia capa por nombre para
+// no pisar las de las demás; el pase vuelve a subir los vértices solo cuando
+// algo cambió.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use crate::graphics::render_graph::{FrameContext, PassStage, RenderPass, ResourceId, BACKBUFFER};
+use crate::graphics::render_settings::RenderSettings;
+use crate::graphics::gpu_resources::ContextGeneration;
+use crate::graphics::gl_validation;
+use crate::graphics::shaders::{build_program, uniform_location, ShaderLibrary};
+use crate::math::vec3::Vec3;
+
+/// Línea quebrada de un color
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polyline {
+    pub points: Vec<Vec3>,
+    /// Color lineal
+    pub color: [f32; 3],
+    /// Une el último punto con el primero
+    pub closed: bool,
+}
+
+/// Capas de líneas por nombre
+#[derive(Debug, Default)]
+pub struct LineOverlay {
+    layers: BTreeMap<String, Vec<Polyline>>,
+    dirty: bool,
+}
+
+impl LineOverlay {
+    /// Reemplaza el contenido de la capa `name`
+    pub fn set(&mut self, name: &str, polylines: Vec<Polyline>) {
+        self.layers.insert(name.to_string(), polylines);
+        self.dirty = true;
+    }
+
+    pub fn clear(&mut self, name: &str) {
+        if self.layers.remove(name).is_some() {
+            self.dirty = true;
+        }
+    }
+
+    pub fn layer(&self, name: &str) -> Option<&[Polyline]> {
+        self.layers.get(name).map(|l| l.as_slice())
+    }
+
+    /// Segmentos de todas las capas como pares de vértices (posición, color)
+    fn segments(&self) -> Vec<[f32; 6]> {
+        let mut vertices = Vec::new();
+        for polyline in self.layers.values().flatten() {
+            let points = &polyline.points;
+            let count = if polyline.closed && points.len() > 2 { points.len() } else { points.len().saturating_sub(1) };
+            for i in 0..count {
+                for p in [points[i], points[(i + 1) % points.len()]] {
+                    let [r, g, b] = polyline.color;
+                    vertices.push([p.x, p.y, p.z, r, g, b]);
+                }
+            }
+        }
+        vertices
+    }
+}
+
+/// Pase que dibuja el `LineOverlay` sin test de profundidad
+pub struct LinePass {
+    program: u32,
+    vao: u32,
+    vbo: u32,
+    vertex_count: i32,
+    overlay: Rc<RefCell<LineOverlay>>,
+    generation: ContextGeneration,
+}
+
+impl LinePass {
+    pub fn new(overlay: Rc<RefCell<LineOverlay>>) -> Result<Self, String> {
+        let program = build_program(
+            include_str!("shaders/line.vert"),
+            include_str!("shaders/line.frag"),
+        )?;
+        let (mut vao, mut vbo) = (0, 0);
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+            gl::GenBuffers(1, &mut vbo);
+            gl::BindVertexArray(vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+            let stride = (6 * std::mem::size_of::<f32>()) as i32;
+            // (location=0) posición, (location=1) color
+            gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, stride, std::ptr::null());
+            gl::EnableVertexAttribArray(0);
+            gl::VertexAttribPointer(1, 3, gl::FLOAT, gl::FALSE, stride, (3 * std::mem::size_of::<f32>()) as *const _);
+            gl::EnableVertexAttribArray(1);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
+        }
+        Ok(Self { program, vao, vbo, vertex_count: 0, overlay, generation: ContextGeneration::current() })
+    }
+}
+
+impl RenderPass for LinePass {
+    fn name(&self) -> &str {
+        "lines"
+    }
+
+    fn stage(&self) -> PassStage {
+        PassStage::Debug
+    }
+
+    fn outputs(&self) -> &[ResourceId] {
+        &[BACKBUFFER]
+    }
+
+    fn settings(&self, global: &RenderSettings) -> RenderSettings {
+        RenderSettings {
+            depth_test: false,
+            wireframe: false,
+            ..*global
+        }
+    }
+
+    fn rebuild_gpu(&mut self, _shaders: &ShaderLibrary) -> Result<(), String> {
+        *self = Self::new(self.overlay.clone())?;
+        // Las líneas se vuelven a subir en el próximo frame
+        self.overlay.borrow_mut().dirty = true;
+        Ok(())
+    }
+
+    fn execute(&mut self, frame: &FrameContext) {
+        let mut overlay = self.overlay.borrow_mut();
+        if overlay.dirty {
+            let vertices = overlay.segments();
+            self.vertex_count = vertices.len() as i32;
+            unsafe {
+                gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+                gl::BufferData(
+                    gl::ARRAY_BUFFER,
+                    std::mem::size_of_val(vertices.as_slice()) as isize,
+                    vertices.as_ptr() as *const _,
+                    gl::DYNAMIC_DRAW,
+                );
+                gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            }
+            overlay.dirty = false;
+        }
+        if self.vertex_count == 0 {
+            return;
+        }
+
+        unsafe {
+            gl::UseProgram(self.program);
+            gl::UniformMatrix4fv(uniform_location(self.program, "view"), 1, gl::FALSE, frame.view.as_ptr());
+            gl::UniformMatrix4fv(uniform_location(self.program, "projection"), 1, gl::FALSE, frame.projection.as_ptr());
+            gl::Uniform1f(uniform_location(self.program, "gamma"), frame.settings.gamma);
+            gl::BindVertexArray(self.vao);
+            gl_validation::draw_arrays(gl::LINES, 0, self.vertex_count);
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+impl Drop for LinePass {
+    fn drop(&mut self) {
+        if !self.generation.is_current() {
+            return;
+        }
+        unsafe {
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteProgram(self.program);
+        }
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closed_polyline_segments() {
+        let mut overlay = LineOverlay::default();
+        let square = vec![Vec3::ZERO, Vec3::UNIT_X, Vec3::new(1.0, 1.0, 0.0), Vec3::UNIT_Y];
+        overlay.set("corte", vec![Polyline { points: square.clone(), color: [1.0, 0.0, 0.0], closed: true }]);
+        overlay.set("guia", vec![Polyline { points: square, color: [0.0, 1.0, 0.0], closed: false }]);
+        // 4 segmentos cerrados + 3 abiertos, dos vértices cada uno
+        assert_eq!(overlay.segments().len(), 14);
+        overlay.clear("corte");
+        assert_eq!(overlay.segments().len(), 6);
+        assert!(overlay.layer("corte").is_none());
+    }
+}
@@ -0,0 +1,526 @@
+// src/graphics/loaders/gltf.rs
+//
+// glTF 2.0 (.gltf con sus buffers aparte o embebidos en base64, y .glb
+// binario), lo que exporta Blender. Se leen las posiciones, normales,
+// primera capa de UVs e índices de todas las primitivas de triángulos de la
+// escena, con la transformación de cada nodo aplicada, y se juntan en una
+// sola malla. Los materiales, texturas, animaciones, skins y morph targets
+// todavía no se leen. Sin normales en el archivo se calculan suavizadas, como
+// en los STL.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::graphics::loaders::LoadedMesh;
+use crate::graphics::mesh::Mesh;
+use crate::graphics::mesh_ops::vertex_normals;
+use crate::math::{matrix_4_by_4::Matrix4, quaternion::Quat, vec3::Vec3};
+
+const GLB_MAGIC: &[u8; 4] = b"glTF";
+const CHUNK_JSON: u32 = 0x4E4F_534A;
+const CHUNK_BIN: u32 = 0x004E_4942;
+/// `mode` de las primitivas de triángulos sueltos (el único que se lee)
+const TRIANGLES: u32 = 4;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Document {
+    #[serde(default)]
+    scene: Option<usize>,
+    #[serde(default)]
+    scenes: Vec<SceneDef>,
+    #[serde(default)]
+    nodes: Vec<Node>,
+    #[serde(default)]
+    meshes: Vec<MeshDef>,
+    #[serde(default)]
+    accessors: Vec<Accessor>,
+    #[serde(default)]
+    buffer_views: Vec<BufferView>,
+    #[serde(default)]
+    buffers: Vec<Buffer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SceneDef {
+    #[serde(default)]
+    nodes: Vec<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Node {
+    #[serde(default)]
+    mesh: Option<usize>,
+    #[serde(default)]
+    children: Vec<usize>,
+    #[serde(default)]
+    matrix: Option<[f32; 16]>,
+    #[serde(default)]
+    translation: Option<[f32; 3]>,
+    /// (x, y, z, w)
+    #[serde(default)]
+    rotation: Option<[f32; 4]>,
+    #[serde(default)]
+    scale: Option<[f32; 3]>,
+}
+
+impl Node {
+    /// Transformación respecto del padre (`matrix` o traslación * rotación * escala)
+    fn local_matrix(&self) -> Matrix4 {
+        if let Some(m) = self.matrix {
+            return Matrix4 { m };
+        }
+        let [tx, ty, tz] = self.translation.unwrap_or([0.0; 3]);
+        let [x, y, z, w] = self.rotation.unwrap_or([0.0, 0.0, 0.0, 1.0]);
+        let [sx, sy, sz] = self.scale.unwrap_or([1.0; 3]);
+        Matrix4::from_trs(Vec3::new(tx, ty, tz), Quat::new(x, y, z, w).normalize(), Vec3::new(sx, sy, sz))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MeshDef {
+    #[serde(default)]
+    primitives: Vec<Primitive>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Primitive {
+    attributes: HashMap<String, usize>,
+    #[serde(default)]
+    indices: Option<usize>,
+    #[serde(default = "default_mode")]
+    mode: u32,
+}
+
+fn default_mode() -> u32 {
+    TRIANGLES
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Accessor {
+    #[serde(default)]
+    buffer_view: Option<usize>,
+    #[serde(default)]
+    byte_offset: usize,
+    component_type: u32,
+    #[serde(default)]
+    normalized: bool,
+    count: usize,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    sparse: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BufferView {
+    buffer: usize,
+    #[serde(default)]
+    byte_offset: usize,
+    byte_length: usize,
+    #[serde(default)]
+    byte_stride: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Buffer {
+    #[serde(default)]
+    uri: Option<String>,
+    byte_length: usize,
+}
+
+/// Lee un .gltf o .glb y junta todas sus primitivas de triángulos
+pub fn load(path: &str) -> Result<LoadedMesh, String> {
+    let bytes = fs::read(path).map_err(|e| format!("No se pudo leer {}: {}", path, e))?;
+    let (json, bin) = if bytes.starts_with(GLB_MAGIC) { split_glb(&bytes)? } else { (bytes.as_slice(), None) };
+    let doc: Document = serde_json::from_slice(json).map_err(|e| format!("glTF inválido {}: {}", path, e))?;
+    let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+    let buffers = doc
+        .buffers
+        .iter()
+        .enumerate()
+        .map(|(index, buffer)| load_buffer(buffer, index, bin, dir))
+        .collect::<Result<Vec<_>, String>>()
+        .map_err(|e| format!("{}: {}", path, e))?;
+
+    let mut builder = Builder::default();
+    for (mesh, matrix) in mesh_instances(&doc).map_err(|e| format!("{}: {}", path, e))? {
+        let primitives = &doc.meshes.get(mesh).ok_or_else(|| format!("{}: no hay malla {}", path, mesh))?.primitives;
+        for primitive in primitives {
+            if primitive.mode != TRIANGLES {
+                eprintln!("{}: primitiva con modo {} ignorada (solo se leen triángulos)", path, primitive.mode);
+                continue;
+            }
+            builder.append(&doc, &buffers, primitive, &matrix).map_err(|e| format!("{}: {}", path, e))?;
+        }
+    }
+    if builder.mesh.indices.is_empty() {
+        return Err(format!("{} no tiene triángulos", path));
+    }
+    Ok(builder.finish())
+}
+
+/// Separa un .glb en el JSON y el buffer binario
+fn split_glb(bytes: &[u8]) -> Result<(&[u8], Option<&[u8]>), String> {
+    let word = |offset: usize| -> Result<u32, String> {
+        bytes
+            .get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or_else(|| "GLB truncado".to_string())
+    };
+    if word(4)? != 2 {
+        return Err(format!("GLB versión {} no soportada (solo 2)", word(4)?));
+    }
+    let end = (word(8)? as usize).min(bytes.len());
+    let (mut offset, mut json, mut bin) = (12, None, None);
+    while offset + 8 <= end {
+        let (length, kind) = (word(offset)? as usize, word(offset + 4)?);
+        let data = bytes.get(offset + 8..offset + 8 + length).ok_or("GLB truncado")?;
+        match kind {
+            CHUNK_JSON if json.is_none() => json = Some(data),
+            CHUNK_BIN if bin.is_none() => bin = Some(data),
+            _ => {}
+        }
+        offset += 8 + length;
+    }
+    Ok((json.ok_or("GLB sin JSON")?, bin))
+}
+
+/// Contenido de un buffer: el bloque binario del .glb, base64 embebido o un archivo al lado
+fn load_buffer(buffer: &Buffer, index: usize, bin: Option<&[u8]>, dir: &Path) -> Result<Vec<u8>, String> {
+    let data = match &buffer.uri {
+        None => bin.ok_or_else(|| format!("el buffer {} no tiene datos", index))?.to_vec(),
+        Some(uri) if uri.starts_with("data:") => {
+            let (_, encoded) = uri.split_once(";base64,").ok_or_else(|| format!("buffer {}: URI de datos sin base64", index))?;
+            decode_base64(encoded).ok_or_else(|| format!("buffer {}: base64 inválido", index))?
+        }
+        Some(uri) => {
+            let file = dir.join(decode_percent(uri));
+            fs::read(&file).map_err(|e| format!("No se pudo leer el buffer {}: {}", file.display(), e))?
+        }
+    };
+    if data.len() < buffer.byte_length {
+        return Err(format!("el buffer {} tiene {} bytes y declara {}", index, data.len(), buffer.byte_length));
+    }
+    Ok(data)
+}
+
+/// Decodifica base64 estándar (con o sin relleno)
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let (mut bits, mut count) = (0u32, 0);
+    for c in text.bytes().filter(|c| !c.is_ascii_whitespace() && *c != b'=') {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        bits = bits << 6 | value as u32;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            out.push((bits >> count) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// Las URIs relativas pueden traer caracteres escapados ("mi%20pieza.bin")
+fn decode_percent(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| u8::from_str_radix(std::str::from_utf8(h).ok()?, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// (malla, transformación en la escena) de cada nodo con malla de la escena
+/// principal; sin escenas, cada malla una vez sin transformar
+fn mesh_instances(doc: &Document) -> Result<Vec<(usize, Matrix4)>, String> {
+    let scene = doc.scene.or((!doc.scenes.is_empty()).then_some(0));
+    let Some(scene) = scene else {
+        return Ok((0..doc.meshes.len()).map(|mesh| (mesh, Matrix4::identity())).collect());
+    };
+    let roots = &doc.scenes.get(scene).ok_or_else(|| format!("no hay escena {}", scene))?.nodes;
+    let mut stack: Vec<(usize, Matrix4)> = roots.iter().rev().map(|&node| (node, Matrix4::identity())).collect();
+    let mut instances = Vec::new();
+    let mut visits = 0;
+    while let Some((index, parent)) = stack.pop() {
+        // Los nodos forman árboles: más visitas que nodos es un ciclo
+        visits += 1;
+        if visits > doc.nodes.len() {
+            return Err("la jerarquía de nodos tiene un ciclo".to_string());
+        }
+        let node = doc.nodes.get(index).ok_or_else(|| format!("no hay nodo {}", index))?;
+        let matrix = parent.multiply(&node.local_matrix());
+        if let Some(mesh) = node.mesh {
+            instances.push((mesh, matrix));
+        }
+        stack.extend(node.children.iter().rev().map(|&child| (child, matrix)));
+    }
+    Ok(instances)
+}
+
+/// Valores de un accessor, `components` por elemento, como f64 sin normalizar
+fn read_accessor<'a>(
+    doc: &'a Document,
+    buffers: &[Vec<u8>],
+    index: usize,
+) -> Result<(&'a Accessor, usize, Vec<f64>), String> {
+    let accessor = doc.accessors.get(index).ok_or_else(|| format!("no hay accessor {}", index))?;
+    if accessor.sparse.is_some() {
+        return Err(format!("accessor {}: los accessors dispersos no están soportados", index));
+    }
+    let components = match accessor.kind.as_str() {
+        "SCALAR" => 1,
+        "VEC2" => 2,
+        "VEC3" => 3,
+        "VEC4" => 4,
+        other => return Err(format!("accessor {}: tipo {} no soportado", index, other)),
+    };
+    let size = match accessor.component_type {
+        5120 | 5121 => 1,
+        5122 | 5123 => 2,
+        5125 | 5126 => 4,
+        other => return Err(format!("accessor {}: componentType {} inválido", index, other)),
+    };
+    let total = accessor.count * components;
+    // Sin bufferView el accessor es todo ceros
+    let Some(view_index) = accessor.buffer_view else { return Ok((accessor, components, vec![0.0; total])) };
+    let view = doc.buffer_views.get(view_index).ok_or_else(|| format!("no hay bufferView {}", view_index))?;
+    let buffer = buffers.get(view.buffer).ok_or_else(|| format!("no hay buffer {}", view.buffer))?;
+    let element = size * components;
+    let stride = view.byte_stride.unwrap_or(element);
+    let start = view.byte_offset + accessor.byte_offset;
+    let needed = if accessor.count == 0 { 0 } else { accessor.byte_offset + stride * (accessor.count - 1) + element };
+    if needed > view.byte_length || view.byte_offset + view.byte_length > buffer.len() {
+        return Err(format!("accessor {}: se sale de su bufferView", index));
+    }
+
+    let mut values = Vec::with_capacity(total);
+    for i in 0..accessor.count {
+        for c in 0..components {
+            let at = start + i * stride + c * size;
+            let b = &buffer[at..at + size];
+            values.push(match accessor.component_type {
+                5120 => b[0] as i8 as f64,
+                5121 => b[0] as f64,
+                5122 => i16::from_le_bytes([b[0], b[1]]) as f64,
+                5123 => u16::from_le_bytes([b[0], b[1]]) as f64,
+                5125 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                _ => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            });
+        }
+    }
+    Ok((accessor, components, values))
+}
+
+/// Vectores de `N` componentes de un accessor de punto flotante o normalizado
+fn read_vectors<const N: usize>(doc: &Document, buffers: &[Vec<u8>], index: usize) -> Result<Vec<[f32; N]>, String> {
+    let (accessor, components, values) = read_accessor(doc, buffers, index)?;
+    if components != N {
+        return Err(format!("accessor {}: se esperaban {} componentes y tiene {}", index, N, components));
+    }
+    // Los enteros normalizados van a [0, 1] o [-1, 1]; con signo, el menor
+    // valor queda fuera del rango y se recorta a -1 (max(c / 127, -1))
+    let normalize = |value: f64| match (accessor.normalized, accessor.component_type) {
+        (true, 5120) => (value / 127.0).max(-1.0),
+        (true, 5121) => value / 255.0,
+        (true, 5122) => (value / 32767.0).max(-1.0),
+        (true, 5123) => value / 65535.0,
+        _ => value,
+    };
+    Ok(values
+        .chunks_exact(N)
+        .map(|chunk| std::array::from_fn(|c| normalize(chunk[c]) as f32))
+        .collect())
+}
+
+/// Malla que se arma primitiva por primitiva
+#[derive(Default)]
+struct Builder {
+    mesh: Mesh,
+    normals: Vec<[f32; 3]>,
+    /// Alguna primitiva trajo UVs (a las otras se les ponen ceros)
+    has_uvs: bool,
+}
+
+impl Builder {
+    fn append(&mut self, doc: &Document, buffers: &[Vec<u8>], primitive: &Primitive, matrix: &Matrix4) -> Result<(), String> {
+        let position = *primitive.attributes.get("POSITION").ok_or("primitiva sin POSITION")?;
+        let positions: Vec<[f32; 3]> = read_vectors::<3>(doc, buffers, position)?
+            .into_iter()
+            .map(|p| matrix.transform_point(Vec3::from(p)).into())
+            .collect();
+        let count = positions.len();
+        let mut indices = match primitive.indices {
+            Some(index) => {
+                let (accessor, components, values) = read_accessor(doc, buffers, index)?;
+                if components != 1 || !matches!(accessor.component_type, 5121 | 5123 | 5125) {
+                    return Err(format!("accessor {}: los índices tienen que ser enteros sin signo", index));
+                }
+                values.into_iter().map(|v| v as u32).collect()
+            }
+            None => (0..count as u32).collect::<Vec<_>>(),
+        };
+        indices.truncate(indices.len() / 3 * 3);
+        if let Some(&bad) = indices.iter().find(|&&i| i as usize >= count) {
+            return Err(format!("índice {} fuera de los {} vértices de la primitiva", bad, count));
+        }
+
+        // Las normales se transforman con la inversa transpuesta: los
+        // cofactores de la matriz, con el signo del determinante
+        let [c0, c1, c2] = [0, 4, 8].map(|i| Vec3::new(matrix.m[i], matrix.m[i + 1], matrix.m[i + 2]));
+        let determinant = c0.dot(&c1.cross(&c2));
+        // Una transformación espejada da vuelta las caras
+        if determinant < 0.0 {
+            indices.chunks_exact_mut(3).for_each(|triangle| triangle.swap(1, 2));
+        }
+        let normals = match primitive.attributes.get("NORMAL") {
+            Some(&normal) => {
+                let cofactors = [c1.cross(&c2), c2.cross(&c0), c0.cross(&c1)];
+                let sign = determinant.signum();
+                read_vectors::<3>(doc, buffers, normal)?
+                    .into_iter()
+                    .map(|n| {
+                        let n = (cofactors[0] * n[0] + cofactors[1] * n[1] + cofactors[2] * n[2]) * sign;
+                        n.normalize_or(Vec3::ZERO).into()
+                    })
+                    .collect()
+            }
+            None => vertex_normals(&Mesh::new(positions.clone(), indices.clone())),
+        };
+        if normals.len() != count {
+            return Err(format!("la primitiva tiene {} posiciones y {} normales", count, normals.len()));
+        }
+        let uvs = match primitive.attributes.get("TEXCOORD_0") {
+            Some(&uv) => {
+                self.has_uvs = true;
+                read_vectors::<2>(doc, buffers, uv)?
+            }
+            None => vec![[0.0; 2]; count],
+        };
+        if uvs.len() != count {
+            return Err(format!("la primitiva tiene {} posiciones y {} UVs", count, uvs.len()));
+        }
+
+        let base = self.mesh.positions.len() as u32;
+        self.mesh.positions.extend(positions);
+        self.mesh.indices.extend(indices.into_iter().map(|i| base + i));
+        self.mesh.uvs.extend(uvs);
+        self.normals.extend(normals);
+        Ok(())
+    }
+
+    fn finish(mut self) -> LoadedMesh {
+        if !self.has_uvs {
+            self.mesh.uvs.clear();
+        }
+        LoadedMesh { mesh: self.mesh, normals: self.normals, submeshes: Vec::new() }
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// .glb con un triángulo (normales y UVs) instanciado en dos nodos: uno
+    /// corrido en Z y otro espejado en X. El primer vértice y su UV tienen
+    /// coordenadas menores que -1, que no se tienen que recortar.
+    fn glb() -> Vec<u8> {
+        let mut bin = Vec::new();
+        for value in [-5.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0] {
+            bin.extend(value.to_le_bytes());
+        }
+        for _ in 0..3 {
+            bin.extend([0.0f32, 0.0, 1.0].iter().flat_map(|v| v.to_le_bytes()));
+        }
+        for value in [-2.0f32, 0.0, 1.0, 0.0, 0.0, 1.0] {
+            bin.extend(value.to_le_bytes());
+        }
+        bin.extend([0u16, 1, 2, 0].iter().flat_map(|v| v.to_le_bytes()));
+        let json = r#"{
+            "asset": {"version": "2.0"},
+            "scene": 0,
+            "scenes": [{"nodes": [0]}],
+            "nodes": [
+                {"children": [1, 2]},
+                {"mesh": 0, "translation": [0, 0, 5]},
+                {"mesh": 0, "scale": [-1, 1, 1]}
+            ],
+            "meshes": [{"primitives": [{"attributes": {"POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2}, "indices": 3}]}],
+            "buffers": [{"byteLength": 104}],
+            "bufferViews": [
+                {"buffer": 0, "byteOffset": 0, "byteLength": 72, "byteStride": 12},
+                {"buffer": 0, "byteOffset": 72, "byteLength": 24},
+                {"buffer": 0, "byteOffset": 96, "byteLength": 6}
+            ],
+            "accessors": [
+                {"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3"},
+                {"bufferView": 0, "byteOffset": 36, "componentType": 5126, "count": 3, "type": "VEC3"},
+                {"bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC2"},
+                {"bufferView": 2, "componentType": 5123, "count": 3, "type": "SCALAR"}
+            ]
+        }"#;
+        let mut json = json.as_bytes().to_vec();
+        json.resize(json.len().next_multiple_of(4), b' ');
+        let mut glb = Vec::new();
+        glb.extend(GLB_MAGIC);
+        glb.extend(2u32.to_le_bytes());
+        glb.extend(((12 + 8 + json.len() + 8 + bin.len()) as u32).to_le_bytes());
+        glb.extend((json.len() as u32).to_le_bytes());
+        glb.extend(CHUNK_JSON.to_le_bytes());
+        glb.extend(json);
+        glb.extend((bin.len() as u32).to_le_bytes());
+        glb.extend(CHUNK_BIN.to_le_bytes());
+        glb.extend(bin);
+        glb
+    }
+
+    #[test]
+    fn test_load_glb() {
+        let path = std::env::temp_dir().join(format!("rust_engine_gltf_{}.glb", std::process::id()));
+        fs::write(&path, glb()).unwrap();
+        let loaded = load(path.to_str().unwrap()).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(loaded.mesh.positions.len(), 6);
+        assert_eq!(loaded.mesh.positions[0], [-5.0, 0.0, 5.0]);
+        assert_eq!(loaded.mesh.positions[3], [5.0, 0.0, 0.0]);
+        assert_eq!(loaded.mesh.uvs[0], [-2.0, 0.0]);
+        assert_eq!(loaded.mesh.positions[1], [1.0, 0.0, 5.0]);
+        assert_eq!(loaded.mesh.positions[4], [-1.0, 0.0, 0.0]);
+        assert_eq!(loaded.mesh.uvs[2], [0.0, 1.0]);
+        // El espejado invierte el orden de las esquinas y deja la normal hacia +Z
+        assert_eq!(loaded.mesh.indices, vec![0, 1, 2, 3, 5, 4]);
+        assert!(loaded.normals.iter().all(|&n| n == [0.0, 0.0, 1.0]));
+
+        assert_eq!(decode_base64("SGVsbG8=").unwrap(), b"Hello");
+        assert_eq!(decode_base64("SGVsbG8h").unwrap(), b"Hello!");
+        assert!(decode_base64("no*valido").is_none());
+        assert_eq!(decode_percent("mi%20pieza.bin"), "mi pieza.bin");
+        assert!(split_glb(&glb()[..20]).is_err());
+    }
+}
@@ -0,0 +1,27 @@
+// src/graphics/loaders/mod.rs
+//
+// Lectores de formatos de modelo además del STL (que sigue en
+// `graphics::stl` y `SceneObject::load_stl`). Cada uno devuelve la geometría
+// en CPU como la arma el cargador de STL: una `Mesh` (posiciones, índices y
+// UVs) más las normales por vértice, y `SceneObject` la sube a la GPU. El de
+// URDF devuelve un robot y las mallas que lo forman (ver `Scene::load_urdf`).
+// El de glTF y el de URDF se compilan con las features `gltf` y `urdf`.
+
+#[cfg(feature = "gltf")]
+pub mod gltf;
+pub mod obj;
+#[cfg(feature = "urdf")]
+pub mod urdf;
+
+use crate::graphics::mesh::Mesh;
+use crate::graphics::scene_object::SubMesh;
+
+/// Geometría leída de un archivo, lista para `SceneObject::from_buffers`
+#[derive(Debug, Clone, Default)]
+pub struct LoadedMesh {
+    pub mesh: Mesh,
+    /// Una por vértice de `mesh`, unitarias
+    pub normals: Vec<[f32; 3]>,
+    /// Rangos de `mesh.indices` por material; vacío es un solo material
+    pub submeshes: Vec<SubMesh>,
+}
@@ -0,0 +1,208 @@
+// src/graphics/loaders/obj.rs
+//
+// Wavefront OBJ, lo que exportan casi todos los programas de CAD. Se leen
+// posiciones, coordenadas de textura, normales y caras (los polígonos se
+// parten en abanico), y las caras se agrupan por `usemtl`: un sub-mesh por
+// material, en el orden en que aparecen. Los `.mtl` de `mtllib` se leen con
+// `parse_mtl` y el `Kd` de cada material queda como color del sub-mesh, que
+// se usa si la biblioteca del Renderer no tiene un material con ese nombre.
+// Los grupos (`g`, `o`), el suavizado (`s`), líneas y puntos se ignoran.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::graphics::loaders::LoadedMesh;
+use crate::graphics::material::MaterialLibrary;
+use crate::graphics::mesh::Mesh;
+use crate::graphics::mesh_ops::vertex_normals;
+use crate::graphics::scene_object::SubMesh;
+
+/// Esquina de una cara: índices (desde 0) de posición, UV y normal
+type Corner = (usize, Option<usize>, Option<usize>);
+
+/// Lee un .obj y los .mtl que nombra (desde la carpeta del .obj)
+pub fn load(path: &str) -> Result<LoadedMesh, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("No se pudo leer {}: {}", path, e))?;
+    let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+    let (mut loaded, libraries) = parse(&text).map_err(|e| format!("OBJ inválido {}: {}", path, e))?;
+
+    let mut materials = MaterialLibrary::new();
+    for library in libraries {
+        let file = dir.join(&library);
+        match MaterialLibrary::load(&file.to_string_lossy()) {
+            Ok(library) => {
+                for name in library.names() {
+                    materials.insert(name, library.get(name).cloned().unwrap_or_default());
+                }
+            }
+            Err(e) => eprintln!("{}: {}", path, e),
+        }
+    }
+    for sub in &mut loaded.submeshes {
+        if let Some(name) = &sub.material {
+            sub.color = materials.get(name).map(|material| material.color);
+            if sub.color.is_none() {
+                eprintln!("{}: el material '{}' no está en sus .mtl", path, name);
+            }
+        }
+    }
+    Ok(loaded)
+}
+
+/// Geometría y sub-meshes del texto de un OBJ (sin colores) y los .mtl que nombra
+fn parse(text: &str) -> Result<(LoadedMesh, Vec<String>), String> {
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut uvs: Vec<[f32; 2]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+    let mut libraries = Vec::new();
+    // Triángulos de cada material, en el orden en que aparecen los materiales
+    let mut groups: Vec<(Option<String>, Vec<[Corner; 3]>)> = vec![(None, Vec::new())];
+    let mut current = 0;
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let error = |what: &str| format!("línea {}: {} '{}'", number + 1, what, rest);
+        let floats = || -> Result<Vec<f32>, String> {
+            rest.split_whitespace().map(|v| v.parse::<f32>().map_err(|_| error("número inválido"))).collect()
+        };
+        match keyword {
+            "v" => match floats()?[..] {
+                [x, y, z, ..] => positions.push([x, y, z]),
+                _ => return Err(error("vértice con menos de 3 coordenadas")),
+            },
+            "vt" => match floats()?[..] {
+                [u] => uvs.push([u, 0.0]),
+                [u, v, ..] => uvs.push([u, v]),
+                _ => return Err(error("coordenada de textura vacía")),
+            },
+            "vn" => match floats()?[..] {
+                [x, y, z] => normals.push([x, y, z]),
+                _ => return Err(error("normal sin 3 coordenadas")),
+            },
+            "f" => {
+                let corners = rest
+                    .split_whitespace()
+                    .map(|corner| parse_corner(corner, [positions.len(), uvs.len(), normals.len()]))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| error("cara inválida"))?;
+                if corners.len() < 3 {
+                    return Err(error("cara con menos de 3 vértices"));
+                }
+                let triangles = &mut groups[current].1;
+                triangles.extend((1..corners.len() - 1).map(|i| [corners[0], corners[i], corners[i + 1]]));
+            }
+            "usemtl" => {
+                let name = Some(rest.to_string());
+                current = match groups.iter().position(|(material, _)| *material == name) {
+                    Some(index) => index,
+                    None => {
+                        groups.push((name, Vec::new()));
+                        groups.len() - 1
+                    }
+                };
+            }
+            "mtllib" => libraries.extend(rest.split_whitespace().map(str::to_string)),
+            _ => {}
+        }
+    }
+
+    // Un vértice por combinación distinta de posición, UV y normal
+    let mut vertices: HashMap<Corner, u32> = HashMap::new();
+    let mut corners: Vec<Corner> = Vec::new();
+    let mut indices = Vec::new();
+    let mut submeshes = Vec::new();
+    for (material, triangles) in groups.into_iter().filter(|(_, triangles)| !triangles.is_empty()) {
+        let start = indices.len() as u32;
+        for corner in triangles.into_iter().flatten() {
+            let index = *vertices.entry(corner).or_insert_with(|| {
+                corners.push(corner);
+                corners.len() as u32 - 1
+            });
+            indices.push(index);
+        }
+        submeshes.push(SubMesh { start, count: indices.len() as u32 - start, material, color: None });
+    }
+    if indices.is_empty() {
+        return Err("no tiene caras".to_string());
+    }
+    // Sin materiales no hace falta dividir el dibujo
+    if submeshes.len() == 1 && submeshes[0].material.is_none() {
+        submeshes.clear();
+    }
+
+    let mut mesh = Mesh::new(corners.iter().map(|&(p, _, _)| positions[p]).collect(), indices);
+    if corners.iter().any(|&(_, uv, _)| uv.is_some()) {
+        mesh.uvs = corners.iter().map(|&(_, uv, _)| uv.map_or([0.0; 2], |uv| uvs[uv])).collect();
+    }
+    // Si a alguna esquina le falta la normal se calculan todas
+    let normals = match corners.iter().map(|&(_, _, n)| n.map(|n| normals[n])).collect::<Option<Vec<_>>>() {
+        Some(normals) => normals,
+        None => vertex_normals(&mesh),
+    };
+    Ok((LoadedMesh { mesh, normals, submeshes }, libraries))
+}
+
+/// "v", "v/vt", "v//vn" o "v/vt/vn", con índices desde 1 o negativos (desde
+/// el final de lo leído hasta ahí). `counts` son las posiciones, UVs y normales leídas.
+fn parse_corner(corner: &str, counts: [usize; 3]) -> Option<Corner> {
+    let mut parts = corner.split('/');
+    let mut index = |count: usize, required: bool| -> Option<Option<usize>> {
+        match parts.next().filter(|part| !part.is_empty()) {
+            None if required => None,
+            None => Some(None),
+            Some(part) => {
+                let value: i64 = part.parse().ok()?;
+                let index = if value < 0 { count as i64 + value } else { value - 1 };
+                (0..count as i64).contains(&index).then_some(Some(index as usize))
+            }
+        }
+    };
+    let position = index(counts[0], true)??;
+    let uv = index(counts[1], false)?;
+    let normal = index(counts[2], false)?;
+    Some((position, uv, normal))
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_obj_with_materials() {
+        let dir = std::env::temp_dir().join(format!("rust_engine_obj_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("pieza.mtl"), "newmtl rojo\nKd 1 0 0\nnewmtl azul\nKd 0 0 1\n").unwrap();
+        // Un cuadrado rojo (partido en dos triángulos), un triángulo azul y
+        // otro rojo más adelante: dos sub-meshes
+        let obj = "mtllib pieza.mtl\n\
+                   v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n\
+                   vt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\n\
+                   vn 0 0 1\n\
+                   usemtl rojo\nf 1/1/1 2/2/1 3/3/1 4/4/1\n\
+                   usemtl azul\nf -4//1 -2//1 -1//1\n\
+                   usemtl rojo\nf 1/1/1 3/3/1 4/4/1\n";
+        let path = dir.join("pieza.obj");
+        fs::write(&path, obj).unwrap();
+        let loaded = load(path.to_str().unwrap()).unwrap();
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(loaded.mesh.triangle_count(), 4);
+        assert_eq!(loaded.submeshes.len(), 2);
+        let rojo = &loaded.submeshes[0];
+        assert_eq!((rojo.start, rojo.count, rojo.material.as_deref()), (0, 9, Some("rojo")));
+        assert_eq!(rojo.color, Some([1.0, 0.0, 0.0]));
+        assert_eq!((loaded.submeshes[1].start, loaded.submeshes[1].color), (9, Some([0.0, 0.0, 1.0])));
+        // Las esquinas sin UV del triángulo azul son vértices aparte
+        assert_eq!(loaded.mesh.positions.len(), 7);
+        assert_eq!(loaded.mesh.uvs.len(), 7);
+        assert!(loaded.normals.iter().all(|&n| n == [0.0, 0.0, 1.0]));
+
+        assert_eq!(parse_corner("-1//2", [3, 0, 2]), Some((2, None, Some(1))));
+        assert_eq!(parse_corner("4", [3, 0, 0]), None);
+        assert!(parse("v 0 0 0\nf 1 2 3\n").is_err());
+    }
+}
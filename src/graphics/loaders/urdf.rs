@@ -0,0 +1,396 @@
+// src/graphics/loaders/urdf.rs
+//
+// URDF, la descripción de robots de ROS: eslabones con su geometría visual y
+// articulaciones que los unen. Se arma el `Robot` (ver `graphics::robot`) y
+// la lista de piezas visuales de cada eslabón; las mallas las carga la escena
+// con los cargadores de siempre (STL, OBJ, glTF), y las cajas, cilindros y
+// esferas se generan acá. Las rutas `package://paquete/...` se buscan en las
+// carpetas de ROS_PACKAGE_PATH y subiendo desde la carpeta del .urdf (la
+// disposición habitual es paquete/urdf/robot.urdf con paquete/meshes/).
+// Las geometrías de colisión, las inercias y `mimic` se ignoran.
+
+use std::collections::HashMap;
+use std::f32::consts::TAU;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use roxmltree::{Document, Node};
+
+use crate::graphics::mesh::Mesh;
+use crate::graphics::robot::{JointKind, Robot, RobotJoint};
+use crate::math::{matrix_4_by_4::Matrix4, quaternion::Quat, vec3::Vec3};
+
+/// Variable de entorno de ROS con las carpetas de paquetes (separadas por ':')
+pub const PACKAGE_PATH_ENV: &str = "ROS_PACKAGE_PATH";
+
+/// Lados de los cilindros y meridianos de las esferas generados
+const SEGMENTS: usize = 24;
+
+#[derive(Debug, Clone)]
+pub enum Geometry {
+    /// Archivo de malla ya resuelto, con la escala de `scale`
+    Mesh { path: String, scale: Vec3 },
+    /// Caja, cilindro o esfera
+    Shape(Box<Mesh>),
+}
+
+/// Pieza visual de un eslabón
+#[derive(Debug, Clone)]
+pub struct UrdfVisual {
+    pub link: String,
+    /// Nombre de la pieza, o el del eslabón si no tiene
+    pub name: String,
+    /// Origen en el sistema del eslabón
+    pub origin: Matrix4,
+    pub geometry: Geometry,
+    /// Color sRGB del material, propio o definido aparte en el robot
+    pub color: Option<[f32; 3]>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UrdfModel {
+    pub robot: Robot,
+    pub visuals: Vec<UrdfVisual>,
+}
+
+pub fn load(path: &str) -> Result<UrdfModel, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("No se pudo leer {}: {}", path, e))?;
+    let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+    let mut model = parse(&text, dir).map_err(|e| format!("URDF inválido {}: {}", path, e))?;
+    model.robot.source = Some(path.to_string());
+    Ok(model)
+}
+
+fn parse(text: &str, dir: &Path) -> Result<UrdfModel, String> {
+    let document = Document::parse(text).map_err(|e| e.to_string())?;
+    let root = document.root_element();
+    if !root.has_tag_name("robot") {
+        return Err(format!("se esperaba <robot> y empieza con <{}>", root.tag_name().name()));
+    }
+    let name = root.attribute("name").unwrap_or("robot");
+
+    // Materiales con nombre que se usan desde las piezas
+    let mut materials = HashMap::new();
+    for material in children(root, "material") {
+        if let (Some(name), Some(color)) = (material.attribute("name"), material_color(material)?) {
+            materials.insert(name.to_string(), color);
+        }
+    }
+
+    let mut links = Vec::new();
+    let mut visuals = Vec::new();
+    for link in children(root, "link") {
+        let link_name = required(link, "name")?;
+        for visual in children(link, "visual") {
+            let geometry = child(visual, "geometry")
+                .and_then(|geometry| geometry.children().find(Node::is_element))
+                .ok_or_else(|| format!("una pieza de '{}' no tiene geometría", link_name))?;
+            let Some(geometry) = parse_geometry(geometry, dir)? else { continue };
+            let color = match child(visual, "material") {
+                Some(material) => match material_color(material)? {
+                    Some(color) => Some(color),
+                    None => material.attribute("name").and_then(|name| materials.get(name).copied()),
+                },
+                None => None,
+            };
+            visuals.push(UrdfVisual {
+                link: link_name.to_string(),
+                name: visual.attribute("name").unwrap_or(link_name).to_string(),
+                origin: origin(visual)?,
+                geometry,
+                color,
+            });
+        }
+        links.push(link_name.to_string());
+    }
+
+    let link_index = |node: Node, tag: &str| -> Result<usize, String> {
+        let name = child(node, tag).map(|n| required(n, "link")).transpose()?.unwrap_or_default();
+        links.iter().position(|link| link == name).ok_or_else(|| format!("no existe el eslabón '{}'", name))
+    };
+    let mut joints = Vec::new();
+    for joint in children(root, "joint") {
+        let joint_name = required(joint, "name")?;
+        let kind_name = required(joint, "type")?;
+        let kind = JointKind::parse(kind_name).ok_or_else(|| format!("'{}' es de tipo desconocido: {}", joint_name, kind_name))?;
+        if matches!(kind_name, "floating" | "planar") {
+            eprintln!("La articulación '{}' es {}: queda fija", joint_name, kind_name);
+        }
+        let axis = match child(joint, "axis").and_then(|axis| axis.attribute("xyz")) {
+            Some(xyz) => Vec3::from(floats::<3>(xyz)?).try_normalize().ok_or_else(|| format!("'{}' tiene el eje nulo", joint_name))?,
+            None => Vec3::UNIT_X,
+        };
+        let limits = match (kind, child(joint, "limit")) {
+            (JointKind::Revolute | JointKind::Prismatic, Some(limit)) => {
+                Some((number(limit.attribute("lower").unwrap_or("0"))?, number(limit.attribute("upper").unwrap_or("0"))?))
+            }
+            _ => None,
+        };
+        // Sin velocidad (o con cero) se mueve a `DEFAULT_JOINT_SPEED`
+        let velocity = match child(joint, "limit").and_then(|limit| limit.attribute("velocity")) {
+            Some(velocity) if kind.is_movable() => Some(number(velocity)?).filter(|&v| v > 0.0),
+            _ => None,
+        };
+        joints.push(RobotJoint {
+            name: joint_name.to_string(),
+            kind,
+            parent: link_index(joint, "parent")?,
+            child: link_index(joint, "child")?,
+            origin: origin(joint)?,
+            axis,
+            limits,
+            position: 0.0,
+            velocity,
+            target: None,
+        });
+    }
+    // Una articulación con límites que no incluyen al cero arranca en el más cercano
+    for joint in &mut joints {
+        if let Some((lower, upper)) = joint.limits {
+            joint.position = 0.0f32.clamp(lower, upper.max(lower));
+        }
+    }
+
+    Ok(UrdfModel { robot: Robot::new(name, links, joints)?, visuals })
+}
+
+fn children<'a, 'input>(node: Node<'a, 'input>, tag: &'a str) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children().filter(move |child| child.has_tag_name(tag))
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, tag: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(tag))
+}
+
+fn required<'a>(node: Node<'a, '_>, attribute: &str) -> Result<&'a str, String> {
+    node.attribute(attribute)
+        .ok_or_else(|| format!("<{}> sin el atributo '{}'", node.tag_name().name(), attribute))
+}
+
+fn number(text: &str) -> Result<f32, String> {
+    text.trim().parse().map_err(|_| format!("número inválido: '{}'", text))
+}
+
+/// Exactamente `N` números separados por espacios
+fn floats<const N: usize>(text: &str) -> Result<[f32; N], String> {
+    let values = text.split_whitespace().map(number).collect::<Result<Vec<_>, _>>()?;
+    values.try_into().map_err(|_| format!("se esperaban {} números: '{}'", N, text))
+}
+
+/// `<origin xyz rpy>` del nodo (identidad si no tiene). rpy son giros en X,
+/// Y y Z sobre los ejes fijos, como `Quat::from_euler`.
+fn origin(node: Node) -> Result<Matrix4, String> {
+    let Some(origin) = child(node, "origin") else { return Ok(Matrix4::identity()) };
+    let xyz = origin.attribute("xyz").map(floats::<3>).transpose()?.unwrap_or([0.0; 3]);
+    let rpy = origin.attribute("rpy").map(floats::<3>).transpose()?.unwrap_or([0.0; 3]);
+    Ok(Matrix4::from_trs(Vec3::from(xyz), Quat::from_euler(Vec3::from(rpy)), Vec3::new(1.0, 1.0, 1.0)))
+}
+
+/// RGB de `<color rgba>` dentro de un `<material>`, si tiene
+fn material_color(material: Node) -> Result<Option<[f32; 3]>, String> {
+    match child(material, "color").and_then(|color| color.attribute("rgba")) {
+        Some(rgba) => {
+            let [r, g, b, _] = floats::<4>(rgba)?;
+            Ok(Some([r, g, b]))
+        }
+        None => Ok(None),
+    }
+}
+
+/// None (con aviso) para las mallas que no se pueden cargar, así el resto del robot se ve igual
+fn parse_geometry(node: Node, dir: &Path) -> Result<Option<Geometry>, String> {
+    let geometry = match node.tag_name().name() {
+        "mesh" => {
+            let filename = required(node, "filename")?;
+            let scale = node.attribute("scale").map(floats::<3>).transpose()?.unwrap_or([1.0; 3]);
+            match resolve_mesh(filename, dir) {
+                Ok(path) => Geometry::Mesh { path: path.to_string_lossy().into_owned(), scale: Vec3::from(scale) },
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return Ok(None);
+                }
+            }
+        }
+        "box" => Geometry::Shape(Box::new(box_mesh(Vec3::from(floats::<3>(required(node, "size")?)?)))),
+        "cylinder" => Geometry::Shape(Box::new(cylinder_mesh(number(required(node, "radius")?)?, number(required(node, "length")?)?))),
+        "sphere" => Geometry::Shape(Box::new(sphere_mesh(number(required(node, "radius")?)?))),
+        other => return Err(format!("geometría desconocida: <{}>", other)),
+    };
+    Ok(Some(geometry))
+}
+
+/// Ruta de un `filename` de malla: `package://`, `file://` o relativa al .urdf
+fn resolve_mesh(filename: &str, dir: &Path) -> Result<PathBuf, String> {
+    if let Some(path) = filename.strip_prefix("file://") {
+        return Ok(PathBuf::from(path));
+    }
+    let Some(rest) = filename.strip_prefix("package://") else {
+        return Ok(dir.join(filename));
+    };
+    let (package, relative) = rest.split_once('/').unwrap_or((rest, ""));
+    let packages = std::env::var(PACKAGE_PATH_ENV).unwrap_or_default();
+    let roots = packages.split(':').filter(|root| !root.is_empty()).map(PathBuf::from);
+    for root in roots.chain(dir.ancestors().map(Path::to_path_buf)) {
+        for candidate in [root.join(package), root.clone()] {
+            let path = candidate.join(relative);
+            if candidate.file_name().is_some_and(|name| name == package) && path.is_file() {
+                return Ok(path);
+            }
+        }
+    }
+    Err(format!("No se encontró {} (agregar la carpeta del paquete a {})", filename, PACKAGE_PATH_ENV))
+}
+
+/// Caja centrada en el origen; cada cara con sus vértices para que quede plana
+fn box_mesh(size: Vec3) -> Mesh {
+    let half = size * 0.5;
+    let mut positions = Vec::new();
+    let mut indices = Vec::new();
+    for (normal, u, v) in [
+        (Vec3::UNIT_X, Vec3::UNIT_Y, Vec3::UNIT_Z),
+        (Vec3::UNIT_Y, Vec3::UNIT_Z, Vec3::UNIT_X),
+        (Vec3::UNIT_Z, Vec3::UNIT_X, Vec3::UNIT_Y),
+    ] {
+        for sign in [1.0, -1.0] {
+            let (normal, u) = (normal * sign, u * sign);
+            let start = positions.len() as u32;
+            for (a, b) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+                let corner = normal + u * a + v * b;
+                positions.push([corner.x * half.x, corner.y * half.y, corner.z * half.z]);
+            }
+            indices.extend([start, start + 1, start + 2, start, start + 2, start + 3]);
+        }
+    }
+    Mesh::new(positions, indices)
+}
+
+/// Cilindro a lo largo de Z, centrado en el origen, con tapas
+fn cylinder_mesh(radius: f32, length: f32) -> Mesh {
+    let half = length * 0.5;
+    let ring = |i: usize| {
+        let (sin, cos) = (i as f32 / SEGMENTS as f32 * TAU).sin_cos();
+        (radius * cos, radius * sin)
+    };
+    let mut positions = Vec::new();
+    let mut indices = Vec::new();
+    // Lateral: pares (abajo, arriba) por segmento
+    for i in 0..SEGMENTS {
+        let (x, y) = ring(i);
+        positions.extend([[x, y, -half], [x, y, half]]);
+    }
+    for i in 0..SEGMENTS as u32 {
+        let (a, b) = (2 * i, 2 * ((i + 1) % SEGMENTS as u32));
+        indices.extend([a, b, b + 1, a, b + 1, a + 1]);
+    }
+    // Tapas: un centro y su anillo
+    for z in [-half, half] {
+        let center = positions.len() as u32;
+        positions.push([0.0, 0.0, z]);
+        positions.extend((0..SEGMENTS).map(|i| {
+            let (x, y) = ring(i);
+            [x, y, z]
+        }));
+        for i in 0..SEGMENTS as u32 {
+            let (a, b) = (center + 1 + i, center + 1 + (i + 1) % SEGMENTS as u32);
+            indices.extend(if z > 0.0 { [center, a, b] } else { [center, b, a] });
+        }
+    }
+    Mesh::new(positions, indices)
+}
+
+/// Esfera por meridianos y paralelos, centrada en el origen
+fn sphere_mesh(radius: f32) -> Mesh {
+    let rings = SEGMENTS / 2;
+    let mut positions = Vec::new();
+    for ring in 0..=rings {
+        let (sin_polar, cos_polar) = (ring as f32 / rings as f32 * TAU * 0.5).sin_cos();
+        for segment in 0..=SEGMENTS {
+            let (sin, cos) = (segment as f32 / SEGMENTS as f32 * TAU).sin_cos();
+            positions.push([radius * sin_polar * cos, radius * sin_polar * sin, radius * cos_polar]);
+        }
+    }
+    let row = SEGMENTS as u32 + 1;
+    let mut indices = Vec::new();
+    for ring in 0..rings as u32 {
+        for segment in 0..SEGMENTS as u32 {
+            let a = ring * row + segment;
+            let b = a + row;
+            indices.extend([a, b, a + 1, a + 1, b, b + 1]);
+        }
+    }
+    Mesh::new(positions, indices)
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_urdf() {
+        let dir = std::env::temp_dir().join(format!("rust_engine_urdf_{}", std::process::id()));
+        fs::create_dir_all(dir.join("brazo/meshes")).unwrap();
+        fs::create_dir_all(dir.join("brazo/urdf")).unwrap();
+        fs::write(dir.join("brazo/meshes/base.stl"), "solid vacio\nendsolid vacio\n").unwrap();
+        let urdf = r#"<?xml version="1.0"?>
+            <robot name="brazo">
+              <material name="naranja"><color rgba="1 0.5 0 1"/></material>
+              <link name="base">
+                <visual>
+                  <geometry><mesh filename="package://brazo/meshes/base.stl" scale="0.001 0.001 0.001"/></geometry>
+                  <material name="naranja"/>
+                </visual>
+                <visual><geometry><mesh filename="package://otro/meshes/falta.dae"/></geometry></visual>
+              </link>
+              <link name="antebrazo">
+                <visual name="tubo">
+                  <origin xyz="0 0 0.25" rpy="0 0 0"/>
+                  <geometry><cylinder radius="0.05" length="0.5"/></geometry>
+                </visual>
+              </link>
+              <link name="pinza"><visual><geometry><box size="0.1 0.2 0.3"/></geometry></visual></link>
+              <joint name="codo" type="revolute">
+                <parent link="base"/><child link="antebrazo"/>
+                <origin xyz="0 0 0.1" rpy="0 0 1.5707963"/>
+                <axis xyz="0 2 0"/>
+                <limit lower="0.5" upper="2" effort="10" velocity="1"/>
+              </joint>
+              <joint name="muñeca" type="continuous">
+                <parent link="antebrazo"/><child link="pinza"/>
+                <origin xyz="0 0 0.5"/>
+              </joint>
+            </robot>"#;
+        let model = parse(urdf, &dir.join("brazo/urdf")).unwrap();
+        fs::remove_dir_all(&dir).ok();
+
+        let robot = &model.robot;
+        assert_eq!((robot.name.as_str(), robot.links().len(), robot.joints().len()), ("brazo", 3, 2));
+        let elbow = &robot.joints()[0];
+        assert_eq!((elbow.kind, elbow.limits, elbow.position), (JointKind::Revolute, Some((0.5, 2.0)), 0.5));
+        assert_eq!((elbow.velocity, robot.joints()[1].velocity), (Some(1.0), None));
+        assert_eq!(elbow.axis, Vec3::UNIT_Y);
+        assert_eq!((robot.joints()[1].axis, robot.joints()[1].limits), (Vec3::UNIT_X, None));
+
+        // La malla que falta se saltea
+        assert_eq!(model.visuals.len(), 3);
+        let base = &model.visuals[0];
+        assert_eq!(base.color, Some([1.0, 0.5, 0.0]));
+        match &base.geometry {
+            Geometry::Mesh { path, scale } => {
+                assert!(path.ends_with("base.stl"));
+                assert_eq!(*scale, Vec3::new(0.001, 0.001, 0.001));
+            }
+            Geometry::Shape(_) => panic!("la base es una malla"),
+        }
+        assert_eq!((model.visuals[1].name.as_str(), model.visuals[1].link.as_str()), ("tubo", "antebrazo"));
+        assert!(model.visuals[1].origin.transform_point(Vec3::ZERO).approx_eq(&Vec3::new(0.0, 0.0, 0.25), 1e-6));
+        let Geometry::Shape(tube) = &model.visuals[1].geometry else { panic!("el tubo es un cilindro") };
+        assert_eq!(tube.triangle_count(), SEGMENTS * 4);
+        let Geometry::Shape(cube) = &model.visuals[2].geometry else { panic!("la pinza es una caja") };
+        assert_eq!((cube.positions.len(), cube.triangle_count()), (24, 12));
+        assert!(cube.positions.iter().all(|p| p[0].abs() == 0.05 && p[1].abs() == 0.1 && p[2].abs() == 0.15));
+
+        assert!(parse("<robot name='x'><joint name='j' type='revolute'/></robot>", &dir).is_err());
+        assert!(parse("<modelo/>", &dir).is_err());
+    }
+}
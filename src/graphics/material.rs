@@ -0,0 +1,272 @@
+// src/graphics/material.rs
+//
+// Biblioteca de materiales con nombre, en un archivo RON o JSON aparte del
+// código y de la escena, así el mismo "aluminio_anodizado" sirve para todas.
+// Los objetos guardan solo el nombre (`SceneObject::set_material`) y el pase
+// opaco lo busca en la biblioteca del Renderer al dibujar. También se leen
+// los `.mtl` de Wavefront (ver `parse_mtl`), los que acompañan a los OBJ.
+//
+// (
+//     materials: {
+//         "aluminio_anodizado": (color: (0.55, 0.6, 0.7), roughness: 0.35, metallic: 1.0),
+//         "madera": (color: (1.0, 1.0, 1.0), color_map: Some("texturas/roble.png")),
+//     },
+// )
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Variable de entorno con la ruta de la biblioteca que se carga al arrancar
+pub const MATERIALS_ENV: &str = "RUST_ENGINE_MATERIALS";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Material {
+    /// Color base sRGB (multiplica a la textura de color si hay)
+    pub color: [f32; 3],
+    /// 0 pulido, 1 mate (sin brillo especular)
+    pub roughness: f32,
+    /// 0 dieléctrico, 1 metal (el brillo toma el color base)
+    pub metallic: f32,
+    /// Textura de color sRGB; necesita UVs en el objeto
+    pub color_map: Option<String>,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self { color: [0.8, 0.8, 0.8], roughness: 1.0, metallic: 0.0, color_map: None }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaterialLibrary {
+    materials: BTreeMap<String, Material>,
+    /// Id GL de la textura de color de cada material (lo completa el Renderer)
+    #[serde(skip)]
+    color_maps: HashMap<String, u32>,
+}
+
+impl MaterialLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Carga un `.ron`, un `.json` o un `.mtl`. Las rutas relativas de las
+    /// texturas se toman desde la carpeta del archivo.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("No se pudo leer {}: {}", path, e))?;
+        let lower = path.to_lowercase();
+        let mut library: Self = if lower.ends_with(".json") {
+            serde_json::from_str(&text).map_err(|e| format!("Biblioteca de materiales inválida {}: {}", path, e))?
+        } else if lower.ends_with(".mtl") {
+            parse_mtl(&text).map_err(|e| format!("MTL inválido {}: {}", path, e))?
+        } else {
+            ron::from_str(&text).map_err(|e| format!("Biblioteca de materiales inválida {}: {}", path, e))?
+        };
+        if let Some(dir) = Path::new(path).parent() {
+            library.resolve_paths(dir);
+        }
+        Ok(library)
+    }
+
+    /// Biblioteca de RUST_ENGINE_MATERIALS, si está definida y se puede leer
+    pub fn from_env() -> Option<Self> {
+        let path = std::env::var(MATERIALS_ENV).ok()?;
+        match Self::load(&path) {
+            Ok(library) => Some(library),
+            Err(e) => {
+                eprintln!("{} ignorado: {}", MATERIALS_ENV, e);
+                None
+            }
+        }
+    }
+
+    fn resolve_paths(&mut self, dir: &Path) {
+        for path in self.materials.values_mut().filter_map(|m| m.color_map.as_mut()) {
+            if Path::new(path.as_str()).is_relative() {
+                *path = dir.join(path.as_str()).to_string_lossy().into_owned();
+            }
+        }
+    }
+
+    /// Agrega o reemplaza un material
+    pub fn insert(&mut self, name: &str, material: Material) {
+        self.materials.insert(name.to_string(), material);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Material> {
+        self.materials.get(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.materials.contains_key(name)
+    }
+
+    /// Nombres en orden alfabético
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.materials.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.materials.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.materials.is_empty()
+    }
+
+    /// Textura de color ya pedida al caché del Renderer
+    pub fn color_map(&self, name: &str) -> Option<u32> {
+        self.color_maps.get(name).copied()
+    }
+
+    pub(crate) fn set_color_map(&mut self, name: &str, texture: u32) {
+        self.color_maps.insert(name.to_string(), texture);
+    }
+
+    /// (material, ruta) de cada textura de color
+    pub(crate) fn color_map_paths(&self) -> Vec<(String, String)> {
+        self.materials
+            .iter()
+            .filter_map(|(name, m)| Some((name.clone(), m.color_map.clone()?)))
+            .collect()
+    }
+}
+
+/// Lee los materiales de un `.mtl` de Wavefront:
+///
+/// - `Kd` es el color base y `map_Kd` su textura.
+/// - `Ns` (exponente de Phong, 0 a 1000) pasa a rugosidad; con `Ks` negro
+///   o `illum` 0 / 1 no hay brillo y el material queda mate.
+/// - Con `illum` 3 o más (reflejos) la intensidad de `Ks` es la metalicidad.
+/// - `Pr` / `Pm` de la extensión PBR ganan sobre lo anterior.
+///
+/// Transparencias, relieve y el resto de los mapas se ignoran.
+pub fn parse_mtl(text: &str) -> Result<MaterialLibrary, String> {
+    struct Entry {
+        name: String,
+        material: Material,
+        specular: f32,
+        shininess: f32,
+        illum: u32,
+        pbr_roughness: Option<f32>,
+        pbr_metallic: Option<f32>,
+    }
+    fn finish(entry: Entry, library: &mut MaterialLibrary) {
+        let mut material = entry.material;
+        if entry.illum >= 2 && entry.specular > 0.0 {
+            material.roughness = (2.0 / (entry.shininess.max(0.0) + 2.0)).powf(0.25);
+        }
+        if entry.illum >= 3 {
+            material.metallic = entry.specular.min(1.0);
+        }
+        material.roughness = entry.pbr_roughness.unwrap_or(material.roughness).clamp(0.0, 1.0);
+        material.metallic = entry.pbr_metallic.unwrap_or(material.metallic).clamp(0.0, 1.0);
+        library.insert(&entry.name, material);
+    }
+
+    let mut library = MaterialLibrary::new();
+    let mut current: Option<Entry> = None;
+    for (number, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let floats = || -> Result<Vec<f32>, String> {
+            rest.split_whitespace()
+                .map(|v| v.parse::<f32>().map_err(|_| format!("línea {}: número inválido '{}'", number + 1, v)))
+                .collect()
+        };
+        if keyword == "newmtl" {
+            if let Some(entry) = current.take() {
+                finish(entry, &mut library);
+            }
+            current = Some(Entry {
+                name: rest.to_string(),
+                material: Material::default(),
+                specular: 0.0,
+                shininess: 0.0,
+                illum: 2,
+                pbr_roughness: None,
+                pbr_metallic: None,
+            });
+            continue;
+        }
+        let Some(entry) = current.as_mut() else { continue };
+        match keyword {
+            "Kd" => {
+                if let [r, g, b] = floats()?[..] {
+                    entry.material.color = [r, g, b];
+                }
+            }
+            "Ks" => {
+                if let [r, g, b] = floats()?[..] {
+                    entry.specular = r.max(g).max(b);
+                }
+            }
+            "Ns" => entry.shininess = floats()?.first().copied().unwrap_or(0.0),
+            "Pr" => entry.pbr_roughness = floats()?.first().copied(),
+            "Pm" => entry.pbr_metallic = floats()?.first().copied(),
+            "illum" => {
+                entry.illum = rest.parse().map_err(|_| format!("línea {}: illum inválido '{}'", number + 1, rest))?;
+            }
+            // Las opciones (-o, -s, -bm ...) van antes de la ruta
+            "map_Kd" => entry.material.color_map = rest.split_whitespace().last().map(str::to_string),
+            _ => {}
+        }
+    }
+    if let Some(entry) = current {
+        finish(entry, &mut library);
+    }
+    Ok(library)
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_library_formats_and_paths() {
+        let mut library: MaterialLibrary = ron::from_str(
+            r#"(materials: {"aluminio": (color: (0.6, 0.6, 0.7), roughness: 0.3, metallic: 1.0), "madera": (color_map: Some("roble.png"))})"#,
+        )
+        .unwrap();
+        library.resolve_paths(Path::new("materiales"));
+        assert_eq!(library.names().collect::<Vec<_>>(), vec!["aluminio", "madera"]);
+        assert_eq!(library.get("aluminio").unwrap().metallic, 1.0);
+        let madera = library.get("madera").unwrap();
+        assert_eq!(madera.roughness, 1.0);
+        assert_eq!(madera.color_map.as_deref(), Some(Path::new("materiales").join("roble.png").to_str().unwrap()));
+        assert_eq!(library.color_map_paths().len(), 1);
+
+        let json: MaterialLibrary =
+            serde_json::from_str(r#"{"materials": {"goma": {"color": [0.1, 0.1, 0.1], "roughness": 0.9}}}"#).unwrap();
+        assert_eq!(json.get("goma").unwrap().color, [0.1, 0.1, 0.1]);
+        assert!(json.get("aluminio").is_none());
+    }
+
+    #[test]
+    fn test_mtl_mapping() {
+        let library = parse_mtl(
+            "# exportado\n\
+             newmtl pintura\nKd 0.8 0.1 0.1\nKs 0.5 0.5 0.5\nNs 250\nillum 2\n\
+             newmtl cromo\nKd 0.9 0.9 0.9\nKs 1 1 1\nNs 900\nillum 3\n\
+             newmtl madera\nKd 1 1 1\nKs 0 0 0\nmap_Kd -s 2 2 1 texturas/roble.png\n\
+             newmtl pbr\nillum 2\nPr 0.4\nPm 1\n",
+        )
+        .unwrap();
+        assert_eq!(library.len(), 4);
+        let pintura = library.get("pintura").unwrap();
+        assert_eq!(pintura.color, [0.8, 0.1, 0.1]);
+        assert!(pintura.roughness < 0.5 && pintura.metallic == 0.0);
+        assert_eq!(library.get("cromo").unwrap().metallic, 1.0);
+        let madera = library.get("madera").unwrap();
+        assert_eq!(madera.roughness, 1.0);
+        assert_eq!(madera.color_map.as_deref(), Some("texturas/roble.png"));
+        let pbr = library.get("pbr").unwrap();
+        assert_eq!((pbr.roughness, pbr.metallic), (0.4, 1.0));
+        assert!(parse_mtl("newmtl x\nKd 1 a 0\n").unwrap_err().contains("línea 2"));
+    }
+}
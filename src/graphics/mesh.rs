@@ -0,0 +1,288 @@
+// src/graphics/mesh.rs
+//
+// Copia en CPU de la geometría que se sube a la GPU. La usan el picking de
+// sub-objetos y cualquier herramienta que necesite consultar vértices o caras.
+
+use std::cell::OnceCell;
+
+use crate::graphics::bvh::Bvh;
+use crate::graphics::hull::{self, DecompositionSettings};
+use crate::graphics::mesh_ops::edge_report;
+use crate::graphics::topology::MeshTopology;
+use crate::math::vec3::Vec3;
+
+#[derive(Debug, Clone, Default)]
+pub struct Mesh {
+    /// Posiciones en espacio del objeto
+    pub positions: Vec<[f32; 3]>,
+    /// Índices de triángulos (mismo orden que el EBO)
+    pub indices: Vec<u32>,
+    /// Coordenadas de textura por vértice (vacío si la malla no tiene)
+    pub uvs: Vec<[f32; 2]>,
+    /// UVs del lightmap, una por esquina de triángulo (mismo orden que
+    /// `indices`) y sin solapes (ver `uv::lightmap_uvs`); vacío si no se generaron
+    pub lightmap_uvs: Vec<[f32; 2]>,
+    /// Se construye la primera vez que se lanza un rayo
+    bvh: OnceCell<Bvh>,
+    /// Se construye la primera vez que se consulta la adyacencia
+    topology: OnceCell<MeshTopology>,
+}
+
+impl Mesh {
+    pub fn new(positions: Vec<[f32; 3]>, indices: Vec<u32>) -> Self {
+        Self { positions, indices, uvs: Vec::new(), lightmap_uvs: Vec::new(), bvh: OnceCell::new(), topology: OnceCell::new() }
+    }
+
+    pub fn bvh(&self) -> &Bvh {
+        self.bvh.get_or_init(|| Bvh::build(self))
+    }
+
+    /// Caras por vértice, caras por arista y piezas sueltas (ver `graphics::topology`)
+    pub fn topology(&self) -> &MeshTopology {
+        self.topology.get_or_init(|| MeshTopology::build(self))
+    }
+
+    /// Llamar después de modificar `positions` o `indices` (descarta también
+    /// la topología)
+    pub fn invalidate_bvh(&mut self) {
+        self.bvh = OnceCell::new();
+        self.topology = OnceCell::new();
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// Índices de los vértices del triángulo `index`
+    pub fn triangle(&self, index: usize) -> Option<[u32; 3]> {
+        let start = index * 3;
+        if start + 3 > self.indices.len() {
+            return None;
+        }
+        Some([self.indices[start], self.indices[start + 1], self.indices[start + 2]])
+    }
+
+    pub fn position(&self, vertex: u32) -> Vec3 {
+        let [x, y, z] = self.positions[vertex as usize];
+        Vec3::new(x, y, z)
+    }
+
+    /// Suma de las áreas de los triángulos (vale también para mallas abiertas)
+    pub fn surface_area(&self) -> f32 {
+        (0..self.triangle_count())
+            .filter_map(|triangle| self.triangle(triangle))
+            .map(|[a, b, c]| {
+                let [a, b, c] = [a, b, c].map(|v| self.positions[v as usize].map(f64::from));
+                0.5 * norm(cross(sub(b, a), sub(c, a)))
+            })
+            .sum::<f64>() as f32
+    }
+
+    /// Volumen, área, centro de masa y tensor de inercia de un sólido de
+    /// densidad uniforme `density`. La malla tiene que ser cerrada; si las
+    /// normales apuntan hacia adentro el resultado es el mismo.
+    pub fn mass_properties(&self, density: f32) -> Result<MassProperties, String> {
+        let edges = edge_report(self);
+        if self.triangle_count() == 0 || !edges.is_watertight() {
+            return Err(format!(
+                "La malla no es cerrada ({} bordes abiertos, {} no-manifold)",
+                edges.open_edges, edges.non_manifold_edges
+            ));
+        }
+
+        // Integrales de volumen por el teorema de la divergencia (Eberly,
+        // "Polyhedral Mass Properties"): 1, x, y, z, x², y², z², xy, yz, zx
+        let mut integrals = [0.0_f64; 10];
+        for triangle in 0..self.triangle_count() {
+            let Some(corners) = self.triangle(triangle) else { continue };
+            let [p0, p1, p2] = corners.map(|v| self.positions[v as usize].map(f64::from));
+            let d = cross(sub(p1, p0), sub(p2, p0));
+            let [fx, fy, fz] = [0, 1, 2].map(|axis| subexpressions(p0[axis], p1[axis], p2[axis]));
+
+            integrals[0] += d[0] * fx.f1;
+            integrals[1] += d[0] * fx.f2;
+            integrals[2] += d[1] * fy.f2;
+            integrals[3] += d[2] * fz.f2;
+            integrals[4] += d[0] * fx.f3;
+            integrals[5] += d[1] * fy.f3;
+            integrals[6] += d[2] * fz.f3;
+            integrals[7] += d[0] * (p0[1] * fx.g[0] + p1[1] * fx.g[1] + p2[1] * fx.g[2]);
+            integrals[8] += d[1] * (p0[2] * fy.g[0] + p1[2] * fy.g[1] + p2[2] * fy.g[2]);
+            integrals[9] += d[2] * (p0[0] * fz.g[0] + p1[0] * fz.g[1] + p2[0] * fz.g[2]);
+        }
+        let factors = [6.0, 24.0, 24.0, 24.0, 60.0, 60.0, 60.0, 120.0, 120.0, 120.0];
+        for (integral, factor) in integrals.iter_mut().zip(factors) {
+            *integral /= factor;
+        }
+        // Normales hacia adentro: todas las integrales cambian de signo
+        if integrals[0] < 0.0 {
+            integrals = integrals.map(|i| -i);
+        }
+
+        let volume = integrals[0];
+        if volume <= f64::EPSILON {
+            return Err("La malla no encierra volumen".to_string());
+        }
+        let [cx, cy, cz] = [integrals[1] / volume, integrals[2] / volume, integrals[3] / volume];
+        let mass = volume * density as f64;
+
+        // Inercia respecto del centro de masa (teorema de Steiner)
+        let xx = integrals[5] + integrals[6] - volume * (cy * cy + cz * cz);
+        let yy = integrals[4] + integrals[6] - volume * (cz * cz + cx * cx);
+        let zz = integrals[4] + integrals[5] - volume * (cx * cx + cy * cy);
+        let xy = -(integrals[7] - volume * cx * cy);
+        let yz = -(integrals[8] - volume * cy * cz);
+        let zx = -(integrals[9] - volume * cz * cx);
+        let inertia = [[xx, xy, zx], [xy, yy, yz], [zx, yz, zz]].map(|row| row.map(|v| (v * density as f64) as f32));
+
+        Ok(MassProperties {
+            volume: volume as f32,
+            surface_area: self.surface_area(),
+            mass: mass as f32,
+            center_of_mass: Vec3::new(cx as f32, cy as f32, cz as f32),
+            inertia,
+        })
+    }
+
+    /// Envolvente convexa de los vértices (ver `graphics::hull`)
+    pub fn convex_hull(&self) -> Result<Mesh, String> {
+        hull::convex_hull(&self.positions)
+    }
+
+    /// Partes convexas aproximadas, para colisiones más baratas que la malla completa
+    pub fn convex_decomposition(&self, settings: &DecompositionSettings) -> Vec<Mesh> {
+        hull::convex_decomposition(self, settings)
+    }
+
+    /// Una malla por pieza suelta (ver `MeshTopology::connected_components`),
+    /// en el orden de su primer triángulo y solo con sus vértices. Un STL de
+    /// un ensamble exportado como un solo archivo da una malla por pieza.
+    pub fn split_components(&self) -> Vec<Mesh> {
+        self.topology()
+            .connected_components()
+            .iter()
+            .map(|triangles| {
+                let mut remap = vec![u32::MAX; self.positions.len()];
+                let mut part = Mesh::default();
+                for &triangle in triangles {
+                    let Some(corners) = self.triangle(triangle as usize) else { continue };
+                    for vertex in corners {
+                        let slot = &mut remap[vertex as usize];
+                        if *slot == u32::MAX {
+                            *slot = part.positions.len() as u32;
+                            part.positions.push(self.positions[vertex as usize]);
+                            if let Some(&uv) = self.uvs.get(vertex as usize) {
+                                part.uvs.push(uv);
+                            }
+                        }
+                        part.indices.push(*slot);
+                    }
+                }
+                part
+            })
+            .collect()
+    }
+}
+
+/// Propiedades de masa de un sólido cerrado, en las unidades de la malla
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MassProperties {
+    pub volume: f32,
+    pub surface_area: f32,
+    /// volumen * densidad
+    pub mass: f32,
+    pub center_of_mass: Vec3,
+    /// Tensor de inercia respecto del centro de masa (fila = eje x, y, z)
+    pub inertia: [[f32; 3]; 3],
+}
+
+/// Subexpresiones de las integrales de un triángulo sobre un eje
+struct Subexpressions {
+    f1: f64,
+    f2: f64,
+    f3: f64,
+    g: [f64; 3],
+}
+
+fn subexpressions(w0: f64, w1: f64, w2: f64) -> Subexpressions {
+    let temp0 = w0 + w1;
+    let f1 = temp0 + w2;
+    let temp1 = w0 * w0;
+    let temp2 = temp1 + w1 * temp0;
+    let f2 = temp2 + w2 * f1;
+    let f3 = w0 * temp1 + w1 * temp2 + w2 * f2;
+    Subexpressions { f1, f2, f3, g: [w0, w1, w2].map(|w| f2 + w * (f1 + w)) }
+}
+
+// Álgebra en f64: las integrales acumulan muchos términos y en f32 pierden precisión
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn norm(a: [f64; 3]) -> f64 {
+    (a[0] * a[0] + a[1] * a[1] + a[2] * a[2]).sqrt()
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Caja de lados (2, 4, 6) con la esquina mínima en (1, 1, 1)
+    fn box_mesh() -> Mesh {
+        let (min, max) = ([1.0, 1.0, 1.0], [3.0, 5.0, 7.0]);
+        let positions = (0..8)
+            .map(|i| [0, 1, 2].map(|axis| if i & (1 << axis) != 0 { max[axis] } else { min[axis] }))
+            .collect();
+        let indices = vec![
+            0, 2, 1, 1, 2, 3, // z = min
+            4, 5, 6, 5, 7, 6, // z = max
+            0, 1, 4, 1, 5, 4, // y = min
+            2, 6, 3, 3, 6, 7, // y = max
+            0, 4, 2, 2, 4, 6, // x = min
+            1, 3, 5, 3, 7, 5, // x = max
+        ];
+        Mesh::new(positions, indices)
+    }
+
+    #[test]
+    fn test_box_mass_properties() {
+        let props = box_mesh().mass_properties(2.0).unwrap();
+        assert!((props.volume - 48.0).abs() < 1e-4);
+        assert!((props.surface_area - 88.0).abs() < 1e-4);
+        assert!((props.mass - 96.0).abs() < 1e-4);
+        assert!((props.center_of_mass - Vec3::new(2.0, 3.0, 4.0)).magnitude() < 1e-5);
+        // Caja: I_xx = m (b² + c²) / 12
+        assert!((props.inertia[0][0] - 96.0 * (16.0 + 36.0) / 12.0).abs() < 1e-2);
+        assert!((props.inertia[2][2] - 96.0 * (4.0 + 16.0) / 12.0).abs() < 1e-2);
+        assert!(props.inertia[0][1].abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_split_components() {
+        // Dos cajas separadas en una sola malla
+        let first = box_mesh();
+        let mut mesh = first.clone();
+        mesh.positions.extend(first.positions.iter().map(|p| [p[0] + 10.0, p[1], p[2]]));
+        mesh.indices.extend(first.indices.iter().map(|i| i + 8));
+        let parts = mesh.split_components();
+        assert_eq!(parts.len(), 2);
+        assert_eq!((parts[1].positions.len(), parts[1].indices.len()), (8, 36));
+        assert_eq!(parts[1].positions[0], [11.0, 1.0, 1.0]);
+        assert!((parts[1].mass_properties(1.0).unwrap().volume - 48.0).abs() < 1e-4);
+        assert_eq!(first.split_components().len(), 1);
+    }
+
+    #[test]
+    fn test_open_mesh_has_no_volume() {
+        let mut mesh = box_mesh();
+        mesh.indices.truncate(30);
+        assert!(mesh.mass_properties(1.0).is_err());
+        // Sin la cara x = max (4 x 6)
+        assert!((mesh.surface_area() - 64.0).abs() < 1e-4);
+    }
+}
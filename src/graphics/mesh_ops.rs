@@ -0,0 +1,375 @@
+// src/graphics/mesh_ops.rs
+//
+// Operaciones sobre la copia en CPU de la malla: reparación (soldar vértices,
+// quitar triángulos degenerados o repetidos), chequeo de bordes, decimado por
+// agrupamiento de vértices en una grilla, aplicar una transformación y exportar
+// a STL. Devuelven mallas nuevas; para verlas hay que volver a subirlas con
+// `SceneObject::set_mesh`.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::BufWriter;
+
+use crate::engine::progress::ProgressToken;
+use crate::graphics::mesh::Mesh;
+use crate::math::{matrix_4_by_4::Matrix4, vec3::Vec3, weld::VertexWelder};
+
+/// Qué cambió `repair`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RepairReport {
+    pub welded_vertices: usize,
+    pub degenerate_triangles: usize,
+    pub duplicate_triangles: usize,
+    pub unused_vertices: usize,
+}
+
+impl RepairReport {
+    pub fn is_clean(&self) -> bool {
+        *self == RepairReport::default()
+    }
+}
+
+/// Aristas que no comparten exactamente dos triángulos
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EdgeReport {
+    /// Aristas de un solo triángulo (agujeros)
+    pub open_edges: usize,
+    /// Aristas de tres o más triángulos
+    pub non_manifold_edges: usize,
+}
+
+impl EdgeReport {
+    /// Malla cerrada: sirve para imprimir o calcular volumen
+    pub fn is_watertight(&self) -> bool {
+        self.open_edges == 0 && self.non_manifold_edges == 0
+    }
+}
+
+/// Normal sin normalizar del triángulo: su largo es el doble del área (cero si
+/// es degenerado)
+fn face_normal([a, b, c]: [Vec3; 3]) -> Vec3 {
+    let (e1, e2) = (b - a, c - a);
+    e1.cross(&e2)
+}
+
+/// Suelda vértices a menos de `tolerance`, quita triángulos degenerados (área
+/// cero o vértices repetidos) y repetidos, y descarta los vértices sin usar.
+pub fn repair(mesh: &Mesh, tolerance: f32) -> (Mesh, RepairReport) {
+    let mut report = RepairReport::default();
+    let tolerance = tolerance.max(f32::EPSILON);
+
+    // 1) Soldar: el primer vértice de cada grupo representa a los demás
+    let mut welder = VertexWelder::new(tolerance);
+    let mut representatives = Vec::new();
+    let remap: Vec<u32> = mesh
+        .positions
+        .iter()
+        .enumerate()
+        .map(|(index, &p)| {
+            let (welded, new) = welder.weld(p);
+            if new {
+                representatives.push(index as u32);
+            }
+            representatives[welded as usize]
+        })
+        .collect();
+    report.welded_vertices = mesh.positions.len() - welder.len();
+
+    // 2) Triángulos válidos y sin repetir (mismo conjunto de vértices)
+    let mut seen = HashSet::new();
+    let mut indices = Vec::with_capacity(mesh.indices.len());
+    for triangle in 0..mesh.triangle_count() {
+        let Some(corners) = mesh.triangle(triangle) else { continue };
+        let [a, b, c] = corners.map(|v| remap[v as usize]);
+        let area = face_normal([a, b, c].map(|v| mesh.position(v))).magnitude();
+        if a == b || b == c || a == c || area <= f32::EPSILON {
+            report.degenerate_triangles += 1;
+            continue;
+        }
+        let mut key = [a, b, c];
+        key.sort_unstable();
+        if !seen.insert(key) {
+            report.duplicate_triangles += 1;
+            continue;
+        }
+        indices.extend([a, b, c]);
+    }
+
+    // 3) Compactar los vértices usados
+    let mut compact = vec![u32::MAX; mesh.positions.len()];
+    let mut positions = Vec::new();
+    let mut uvs = Vec::new();
+    for index in indices.iter_mut() {
+        let old = *index as usize;
+        if compact[old] == u32::MAX {
+            compact[old] = positions.len() as u32;
+            positions.push(mesh.positions[old]);
+            if let Some(&uv) = mesh.uvs.get(old) {
+                uvs.push(uv);
+            }
+        }
+        *index = compact[old];
+    }
+    report.unused_vertices = welder.len() - positions.len();
+
+    let mut repaired = Mesh::new(positions, indices);
+    if uvs.len() == repaired.positions.len() {
+        repaired.uvs = uvs;
+    }
+    (repaired, report)
+}
+
+/// Cuenta las aristas abiertas y no-manifold (por índice de vértice: conviene
+/// soldar antes con `repair`)
+pub fn edge_report(mesh: &Mesh) -> EdgeReport {
+    let topology = mesh.topology();
+    EdgeReport {
+        open_edges: topology.open_edges().count(),
+        non_manifold_edges: topology.non_manifold_edges().count(),
+    }
+}
+
+/// Agrupa los vértices en una grilla de `resolution` celdas sobre el lado más
+/// largo de la malla; cada grupo queda en el promedio de sus posiciones.
+fn cluster(mesh: &Mesh, resolution: u32) -> Mesh {
+    let bounds = mesh.bvh().bounds();
+    let extent = bounds.size();
+    let cell = extent.x.max(extent.y).max(extent.z) / resolution.max(1) as f32;
+    if bounds.is_empty() || cell <= 0.0 {
+        return mesh.clone();
+    }
+
+    let mut groups: HashMap<[i64; 3], u32> = HashMap::new();
+    let mut sums: Vec<(Vec3, u32)> = Vec::new();
+    let remap: Vec<u32> = mesh
+        .positions
+        .iter()
+        .map(|&p| {
+            let relative: [f32; 3] = (Vec3::from(p) - bounds.min).into();
+            let key = relative.map(|c| (c / cell).floor() as i64);
+            let group = *groups.entry(key).or_insert_with(|| {
+                sums.push((Vec3::ZERO, 0));
+                sums.len() as u32 - 1
+            });
+            sums[group as usize].0 += Vec3::from(p);
+            sums[group as usize].1 += 1;
+            group
+        })
+        .collect();
+
+    let positions = sums.iter().map(|&(sum, count)| (sum / count as f32).into()).collect();
+    let indices = mesh.indices.iter().map(|&i| remap[i as usize]).collect();
+    // Los triángulos que colapsaron se quitan al reparar
+    repair(&Mesh::new(positions, indices), f32::EPSILON).0
+}
+
+/// Reduce la malla a aproximadamente `ratio` (0..1] de sus triángulos por
+/// agrupamiento de vértices. Rápido y robusto, pero no preserva bordes finos.
+pub fn decimate(mesh: &Mesh, ratio: f32) -> Mesh {
+    decimate_with_progress(mesh, ratio, &ProgressToken::new()).expect("Sin cancelación no falla")
+}
+
+/// `decimate` informando el avance a `progress` (una parte por paso de la
+/// búsqueda); si lo cancelan devuelve `Err(CANCELLED)`
+pub fn decimate_with_progress(mesh: &Mesh, ratio: f32, progress: &ProgressToken) -> Result<Mesh, String> {
+    let target = (mesh.triangle_count() as f32 * ratio.clamp(0.0, 1.0)) as usize;
+    if target >= mesh.triangle_count() {
+        return Ok(mesh.clone());
+    }
+    // Búsqueda binaria de la grilla más fina que no supere el objetivo
+    // (12 pasos de 1 a 4096, más la grilla inicial)
+    const STEPS: usize = 13;
+    let (mut low, mut high) = (1u32, 4096u32);
+    let mut best = cluster(mesh, low);
+    let mut step = 1;
+    while low < high {
+        progress.step(step, STEPS)?;
+        step += 1;
+        let middle = (low + high).div_ceil(2);
+        let candidate = cluster(mesh, middle);
+        if candidate.triangle_count() <= target {
+            best = candidate;
+            low = middle;
+        } else {
+            high = middle - 1;
+        }
+    }
+    progress.report(STEPS, STEPS);
+    Ok(best)
+}
+
+/// Copia de la malla con `transform` aplicado a las posiciones. Si la
+/// transformación invierte la orientación se da vuelta el orden de los
+/// triángulos para que las normales sigan hacia afuera.
+pub fn transformed(mesh: &Mesh, transform: &Matrix4) -> Mesh {
+    let positions = mesh
+        .positions
+        .iter()
+        .map(|&p| transform.transform_point(Vec3::from(p)).into())
+        .collect();
+    let m = &transform.m;
+    let (x, y, z) = (Vec3::new(m[0], m[1], m[2]), Vec3::new(m[4], m[5], m[6]), Vec3::new(m[8], m[9], m[10]));
+    let mut indices = mesh.indices.clone();
+    let mut lightmap_uvs = mesh.lightmap_uvs.clone();
+    if face_normal([Vec3::ZERO, x, y]).dot(&z) < 0.0 {
+        for triangle in indices.chunks_exact_mut(3) {
+            triangle.swap(1, 2);
+        }
+        for corners in lightmap_uvs.chunks_exact_mut(3) {
+            corners.swap(1, 2);
+        }
+    }
+    let mut result = Mesh::new(positions, indices);
+    result.uvs = mesh.uvs.clone();
+    result.lightmap_uvs = lightmap_uvs;
+    result
+}
+
+/// Una sola malla con todas las de `meshes` (ya en el mismo espacio, ver
+/// `transformed`). Con `weld` se unen los vértices a menos de esa distancia,
+/// así las piezas que se tocan quedan conectadas en las costuras. Las UVs se
+/// conservan solo si todas las mallas tienen.
+pub fn merge(meshes: &[Mesh], weld: Option<f32>) -> Mesh {
+    let keep_uvs = meshes.iter().all(|mesh| mesh.uvs.len() == mesh.positions.len());
+    let mut welder = weld.map(VertexWelder::new);
+    let mut merged = Mesh::default();
+    for mesh in meshes {
+        let remap: Vec<u32> = mesh
+            .positions
+            .iter()
+            .enumerate()
+            .map(|(vertex, &p)| {
+                let (index, new) = match welder.as_mut() {
+                    Some(welder) => welder.weld(p),
+                    None => (merged.positions.len() as u32, true),
+                };
+                if new {
+                    merged.positions.push(p);
+                    if keep_uvs {
+                        merged.uvs.push(mesh.uvs[vertex]);
+                    }
+                }
+                index
+            })
+            .collect();
+        merged.indices.extend(mesh.indices.iter().map(|&i| remap[i as usize]));
+    }
+    merged
+}
+
+/// Normales por vértice promediadas por área (las que usa el shader)
+pub fn vertex_normals(mesh: &Mesh) -> Vec<[f32; 3]> {
+    let mut normals = vec![Vec3::ZERO; mesh.positions.len()];
+    for triangle in 0..mesh.triangle_count() {
+        let Some(corners) = mesh.triangle(triangle) else { continue };
+        let normal = face_normal(corners.map(|v| mesh.position(v)));
+        for v in corners {
+            normals[v as usize] += normal;
+        }
+    }
+    normals
+        .into_iter()
+        .map(|n| n.normalize().into())
+        .collect()
+}
+
+/// Guarda la malla como STL binario
+pub fn save_stl(mesh: &Mesh, path: &str) -> Result<(), String> {
+    let triangles: Vec<stl_io::Triangle> = (0..mesh.triangle_count())
+        .filter_map(|triangle| mesh.triangle(triangle))
+        .map(|corners| {
+            let [a, b, c] = corners.map(|v| mesh.position(v));
+            let normal = face_normal([a, b, c]).normalize();
+            stl_io::Triangle {
+                normal: normal.into(),
+                vertices: [a, b, c].map(Into::into),
+            }
+        })
+        .collect();
+    let file = File::create(path).map_err(|e| format!("No se pudo crear {}: {}", path, e))?;
+    stl_io::write_stl(&mut BufWriter::new(file), triangles.iter())
+        .map_err(|e| format!("No se pudo escribir {}: {}", path, e))
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cubo unitario con las caras sin compartir vértices (como sale de un STL)
+    fn cube_soup() -> Mesh {
+        let corners = [
+            [0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0], [1.0, 0.0, 1.0], [1.0, 1.0, 1.0], [0.0, 1.0, 1.0],
+        ];
+        let faces = [
+            [0, 2, 1, 0, 3, 2], [4, 5, 6, 4, 6, 7], [0, 1, 5, 0, 5, 4],
+            [3, 6, 2, 3, 7, 6], [0, 4, 7, 0, 7, 3], [1, 2, 6, 1, 6, 5],
+        ];
+        let positions: Vec<[f32; 3]> = faces.iter().flatten().map(|&i| corners[i]).collect();
+        let indices = (0..positions.len() as u32).collect();
+        Mesh::new(positions, indices)
+    }
+
+    #[test]
+    fn test_repair_welds_and_closes() {
+        let mut soup = cube_soup();
+        assert_eq!(edge_report(&soup).open_edges, 36);
+        // Un triángulo repetido y uno degenerado
+        soup.indices.extend([0, 1, 2, 0, 0, 1]);
+
+        let (mesh, report) = repair(&soup, 1e-5);
+        assert_eq!(mesh.positions.len(), 8);
+        assert_eq!(mesh.triangle_count(), 12);
+        assert_eq!(report.welded_vertices, 28);
+        assert_eq!((report.degenerate_triangles, report.duplicate_triangles), (1, 1));
+        assert!(edge_report(&mesh).is_watertight());
+        assert!(repair(&mesh, 1e-5).1.is_clean());
+    }
+
+    #[test]
+    fn test_merge_welds_seams() {
+        let cube = cube_soup();
+        let mut moved = cube.clone();
+        moved.positions.iter_mut().for_each(|p| p[0] += 1.0);
+        // Sin soldar: los vértices de cada malla, uno detrás del otro
+        let merged = merge(&[cube.clone(), moved.clone()], None);
+        assert_eq!((merged.positions.len(), merged.triangle_count()), (72, 24));
+        assert_eq!(merged.indices[36], 36);
+        // Soldando: dos cubos que comparten la cara x = 1 (su contorno y su
+        // diagonal quedan con cuatro triángulos)
+        let merged = merge(&[cube, moved], Some(1e-5));
+        assert_eq!(merged.positions.len(), 12);
+        assert_eq!(merged.topology().non_manifold_edges().count(), 5);
+    }
+
+    #[test]
+    fn test_decimate_reduces_triangles() {
+        // Grilla de 32x32 celdas en el plano XZ
+        let n = 33;
+        let positions = (0..n * n).map(|i| [(i % n) as f32, 0.0, (i / n) as f32]).collect();
+        let mut indices = Vec::new();
+        for z in 0..n - 1 {
+            for x in 0..n - 1 {
+                let i = z * n + x;
+                indices.extend([i, i + n, i + 1, i + 1, i + n, i + n + 1]);
+            }
+        }
+        let mesh = Mesh::new(positions, indices);
+        let decimated = decimate(&mesh, 0.25);
+        assert!(decimated.triangle_count() <= mesh.triangle_count() / 4);
+        assert!(decimated.triangle_count() > mesh.triangle_count() / 16);
+    }
+
+    #[test]
+    fn test_mirror_transform_flips_winding() {
+        let mesh = Mesh::new(vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]], vec![0, 1, 2]);
+        let mut mirror = Matrix4::identity();
+        mirror.m[0] = -1.0;
+        let result = transformed(&mesh, &mirror);
+        assert_eq!(result.positions[1], [-1.0, 0.0, 0.0]);
+        assert_eq!(result.indices, vec![0, 2, 1]);
+        // La normal sigue apuntando a +z
+        assert!(vertex_normals(&result)[0][2] > 0.99);
+    }
+}
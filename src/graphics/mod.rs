@@ -1,5 +1,66 @@
 pub mod camara;
 pub mod scene_object;
+pub mod stl;
+pub mod loaders;
+pub mod scene;
+#[cfg(feature = "step")]
+pub mod cad_import;
 pub mod shaders;
+pub mod shader_variants;
+pub mod program_cache;
+pub mod uniforms;
 pub mod window;
-pub mod render;
\ No newline at end of file
+pub mod capabilities;
+pub mod gpu_resources;
+pub mod gl_validation;
+pub mod render;
+pub mod render_graph;
+pub mod render_targets;
+pub mod passes;
+pub mod render_plugin;
+pub mod render_settings;
+pub mod render_preset;
+#[cfg(test)]
+pub mod gl_mock;
+pub mod color;
+pub mod material;
+pub mod texture;
+pub mod environment;
+pub mod picking;
+pub mod nav_cube;
+pub mod lines;
+pub mod edges;
+pub mod normal_debug;
+pub mod skeleton;
+pub mod frames;
+pub mod robot;
+pub mod trajectory;
+pub mod text;
+pub mod mesh;
+pub mod topology;
+pub mod align;
+pub mod animation;
+pub mod morph;
+pub mod uv;
+pub mod uv_debug;
+pub mod mesh_ops;
+pub mod hull;
+pub mod compare;
+pub mod printability;
+pub mod thickness;
+pub mod slicing;
+pub mod ortho_views;
+pub mod scale_bar;
+pub mod lighting;
+pub mod path_tracer;
+pub mod lightmap;
+pub mod still_render;
+pub mod capture;
+pub mod dataset;
+pub mod batch;
+pub mod thumbnails;
+pub mod bvh;
+pub mod spatial;
+pub mod stereo;
+#[cfg(feature = "openxr")]
+pub mod xr;
@@ -0,0 +1,137 @@
+// src/graphics/morph.rs
+//
+// Morph targets (blend shapes): cada target guarda cuánto se mueve cada
+// vértice, y el vertex shader suma `peso * delta` a la posición y a la normal
+// de reposo. Los deltas se suben como atributos extra del VAO del objeto
+// (posiciones en las locations 4 a 7, normales en 8 a 11), así el costo es
+// nulo para los objetos sin targets (variante MORPH_TARGETS del shader).
+//
+// Sirve para las caras y correctivos de los glTF y también para ver
+// resultados de simulación: un target con el desplazamiento de un cálculo de
+// elementos finitos, con el peso como factor de exageración.
+//
+// Picking, aristas y capturas auxiliares usan la malla en reposo.
+
+use crate::math::vec3::Vec3;
+
+/// Targets que entiende el shader principal por objeto
+pub const MAX_MORPH_TARGETS: usize = 4;
+
+/// Primera location de los deltas de posición; las normales van a continuación
+pub const MORPH_POSITION_LOCATION: u32 = 4;
+pub const MORPH_NORMAL_LOCATION: u32 = MORPH_POSITION_LOCATION + MAX_MORPH_TARGETS as u32;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MorphTarget {
+    pub name: String,
+    /// Desplazamiento de cada vértice, en espacio del objeto
+    pub positions: Vec<[f32; 3]>,
+    /// Cambio de la normal de cada vértice; vacío si el target no la mueve
+    pub normals: Vec<[f32; 3]>,
+}
+
+impl MorphTarget {
+    /// Target que lleva la malla de `rest` a `deformed` con peso 1
+    /// (p. ej. el resultado de una simulación con los mismos vértices)
+    pub fn from_deformed(name: &str, rest: &[[f32; 3]], deformed: &[[f32; 3]]) -> Result<Self, String> {
+        if rest.len() != deformed.len() {
+            return Err(format!(
+                "La malla deformada tiene {} vértices y la original {}",
+                deformed.len(),
+                rest.len()
+            ));
+        }
+        let positions = rest
+            .iter()
+            .zip(deformed)
+            .map(|(a, b)| [b[0] - a[0], b[1] - a[1], b[2] - a[2]])
+            .collect();
+        Ok(Self { name: name.to_string(), positions, normals: Vec::new() })
+    }
+
+    /// Mayor desplazamiento de un vértice con peso 1
+    pub fn max_displacement(&self) -> f32 {
+        self.positions.iter().map(|d| Vec3::from(*d).magnitude()).fold(0.0, f32::max)
+    }
+}
+
+/// Pesos de todos los targets en un instante
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MorphKeyframe {
+    /// Segundos desde el inicio
+    pub time: f32,
+    pub weights: [f32; MAX_MORPH_TARGETS],
+}
+
+/// Pesos animados por interpolación lineal entre keyframes
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MorphAnimation {
+    /// Ordenados por tiempo
+    pub keyframes: Vec<MorphKeyframe>,
+    /// Vuelve a empezar al llegar al último keyframe
+    pub looping: bool,
+}
+
+impl MorphAnimation {
+    /// Tiempo del último keyframe
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |k| k.time)
+    }
+
+    pub fn sample(&self, time: f32) -> [f32; MAX_MORPH_TARGETS] {
+        let (Some(first), Some(last)) = (self.keyframes.first(), self.keyframes.last()) else {
+            return [0.0; MAX_MORPH_TARGETS];
+        };
+        let time = if self.looping && last.time > 0.0 { time.rem_euclid(last.time) } else { time };
+        if time <= first.time {
+            return first.weights;
+        }
+        let next = self.keyframes.partition_point(|k| k.time <= time);
+        let Some(b) = self.keyframes.get(next) else {
+            return last.weights;
+        };
+        let a = &self.keyframes[next - 1];
+        let t = (time - a.time) / (b.time - a.time);
+        std::array::from_fn(|i| a.weights[i] + (b.weights[i] - a.weights[i]) * t)
+    }
+}
+
+/// Posiciones deformadas en CPU, lo mismo que hace el vertex shader
+pub fn morphed_positions(rest: &[[f32; 3]], targets: &[MorphTarget], weights: &[f32]) -> Vec<[f32; 3]> {
+    let mut positions = rest.to_vec();
+    for (target, &weight) in targets.iter().zip(weights).filter(|(_, w)| **w != 0.0) {
+        for (p, d) in positions.iter_mut().zip(&target.positions) {
+            for axis in 0..3 {
+                p[axis] += d[axis] * weight;
+            }
+        }
+    }
+    positions
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_morph_sampling_and_deformation() {
+        let rest = [[0.0; 3], [1.0, 0.0, 0.0]];
+        let target = MorphTarget::from_deformed("flexion", &rest, &[[0.0; 3], [1.0, 0.5, 0.0]]).unwrap();
+        assert_eq!(target.max_displacement(), 0.5);
+        assert!(MorphTarget::from_deformed("x", &rest, &[[0.0; 3]]).is_err());
+
+        // Peso 2 exagera el desplazamiento
+        let deformed = morphed_positions(&rest, &[target], &[2.0]);
+        assert_eq!(deformed[1], [1.0, 1.0, 0.0]);
+
+        let key = |time, w| MorphKeyframe { time, weights: [w, 0.0, 0.0, 0.0] };
+        let mut animation = MorphAnimation { keyframes: vec![key(0.0, 0.0), key(1.0, 1.0), key(2.0, 0.0)], looping: false };
+        assert_eq!(animation.sample(0.5)[0], 0.5);
+        assert_eq!(animation.sample(1.5)[0], 0.5);
+        assert_eq!(animation.sample(5.0)[0], 0.0);
+        animation.looping = true;
+        assert_eq!(animation.sample(2.5)[0], 0.5);
+        assert_eq!(MorphAnimation::default().sample(1.0), [0.0; MAX_MORPH_TARGETS]);
+    }
+}
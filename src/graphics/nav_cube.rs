@@ -0,0 +1,250 @@
+// src/graphics/nav_cube.rs
+//
+// Cubo de navegación (estilo CAD) en una esquina de la ventana. Muestra la
+// orientación de la cámara y, al hacer click en una cara, arista o esquina,
+// la cámara se anima hacia esa vista estándar.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::graphics::environment::CubeMesh;
+use crate::graphics::render_graph::{FrameContext, PassStage, RenderPass};
+use crate::graphics::render_settings::RenderSettings;
+use crate::graphics::gpu_resources::ContextGeneration;
+use crate::graphics::shaders::{build_program, uniform_location, ShaderLibrary};
+use crate::math::{aabb::Aabb, matrix_4_by_4::Matrix4, ray::Ray, vec3::Vec3};
+
+/// Ancho de la franja de aristas/esquinas sobre cada cara (el cubo mide 2)
+const BORDER: f32 = 0.3;
+/// Mitad del área ortográfica que ve el widget; cabe la diagonal del cubo
+const HALF_EXTENT: f32 = 1.8;
+
+/// Cara, arista o esquina del cubo: signo en cada eje (-1, 0, 1).
+/// Una cara tiene un eje distinto de cero, una arista dos y una esquina tres.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NavRegion {
+    pub x: i8,
+    pub y: i8,
+    pub z: i8,
+}
+
+impl NavRegion {
+    pub const FRONT: Self = Self { x: 0, y: 0, z: 1 };
+    pub const TOP: Self = Self { x: 0, y: 1, z: 0 };
+    pub const RIGHT: Self = Self { x: 1, y: 0, z: 0 };
+    pub const ISO: Self = Self { x: 1, y: 1, z: 1 };
+
+    /// Dirección (unitaria) desde el punto observado hacia la cámara
+    pub fn direction(&self) -> Vec3 {
+        Vec3::new(self.x as f32, self.y as f32, self.z as f32).normalize()
+    }
+}
+
+/// Estado del widget compartido entre el pase y el Renderer
+pub struct NavCube {
+    /// Lado del widget en píxeles
+    pub size: i32,
+    /// Separación del borde de la ventana en píxeles
+    pub margin: i32,
+    hovered: Option<NavRegion>,
+    /// Rotación de la cámara del último frame
+    rotation: Matrix4,
+    viewport: (i32, i32),
+}
+
+impl Default for NavCube {
+    fn default() -> Self {
+        Self {
+            size: 120,
+            margin: 10,
+            hovered: None,
+            rotation: Matrix4::identity(),
+            viewport: (0, 0),
+        }
+    }
+}
+
+impl NavCube {
+    /// Esquina superior izquierda del widget en coordenadas de ventana
+    fn origin(&self) -> (i32, i32) {
+        (self.viewport.0 - self.margin - self.size, self.margin)
+    }
+
+    /// Región del cubo bajo el píxel (x, y) de la ventana (origen arriba a la izquierda)
+    pub fn hit(&self, x: i32, y: i32) -> Option<NavRegion> {
+        let (left, top) = self.origin();
+        if x < left || y < top || x >= left + self.size || y >= top + self.size {
+            return None;
+        }
+
+        // Punto en el plano de vista del widget
+        let u = ((x - left) as f32 + 0.5) / self.size as f32 * 2.0 - 1.0;
+        let v = 1.0 - ((y - top) as f32 + 0.5) / self.size as f32 * 2.0;
+        let view_point = Vec3::new(u * HALF_EXTENT, v * HALF_EXTENT, 5.0);
+        let view_dir = Vec3::new(0.0, 0.0, -1.0);
+
+        // De vista a mundo con la rotación traspuesta (es ortonormal)
+        let r = &self.rotation.m;
+        let to_world = |p: Vec3| {
+            Vec3::new(
+                r[0] * p.x + r[1] * p.y + r[2] * p.z,
+                r[4] * p.x + r[5] * p.y + r[6] * p.z,
+                r[8] * p.x + r[9] * p.y + r[10] * p.z,
+            )
+        };
+        let ray = Ray::new(to_world(view_point), to_world(view_dir));
+        let cube = Aabb::new(Vec3::new(-1.0, -1.0, -1.0), Vec3::new(1.0, 1.0, 1.0));
+        let point = ray.at(ray.intersect_aabb(&cube)?);
+
+        let sign = |value: f32| {
+            if value > 1.0 - BORDER {
+                1
+            } else if value < -(1.0 - BORDER) {
+                -1
+            } else {
+                0
+            }
+        };
+        Some(NavRegion { x: sign(point.x), y: sign(point.y), z: sign(point.z) })
+    }
+
+    /// Actualiza la región resaltada según la posición del cursor
+    pub fn set_hover(&mut self, x: i32, y: i32) {
+        self.hovered = self.hit(x, y);
+    }
+}
+
+/// Pase que dibuja el cubo encima de todo en la esquina superior derecha
+pub struct NavCubePass {
+    program: u32,
+    cube: CubeMesh,
+    state: Rc<RefCell<NavCube>>,
+    generation: ContextGeneration,
+}
+
+impl NavCubePass {
+    pub fn new(state: Rc<RefCell<NavCube>>) -> Result<Self, String> {
+        let program = build_program(
+            include_str!("shaders/navcube.vert"),
+            include_str!("shaders/navcube.frag"),
+        )?;
+        Ok(Self {
+            program,
+            cube: CubeMesh::new(),
+            state,
+            generation: ContextGeneration::current(),
+        })
+    }
+}
+
+impl RenderPass for NavCubePass {
+    fn name(&self) -> &str {
+        "nav_cube"
+    }
+
+    fn stage(&self) -> PassStage {
+        PassStage::Post
+    }
+
+    fn settings(&self, global: &RenderSettings) -> RenderSettings {
+        RenderSettings {
+            backface_culling: false,
+            wireframe: false,
+            depth_test: true,
+            ..*global
+        }
+    }
+
+    fn rebuild_gpu(&mut self, _shaders: &ShaderLibrary) -> Result<(), String> {
+        *self = Self::new(self.state.clone())?;
+        Ok(())
+    }
+
+    fn execute(&mut self, frame: &FrameContext) {
+        let mut state = self.state.borrow_mut();
+        let (width, height) = frame.viewport;
+        if width < state.size + state.margin || height < state.size + state.margin {
+            return;
+        }
+
+        // Rotación de la cámara sin traslación
+        let mut rotation = frame.view;
+        rotation.m[12] = 0.0;
+        rotation.m[13] = 0.0;
+        rotation.m[14] = 0.0;
+        state.rotation = rotation;
+        state.viewport = frame.viewport;
+
+        let view = Matrix4::translate(0.0, 0.0, -5.0).multiply(&rotation);
+        let projection = Matrix4::orthographic(-HALF_EXTENT, HALF_EXTENT, -HALF_EXTENT, HALF_EXTENT, 0.1, 10.0);
+        let (left, _) = state.origin();
+        let bottom = height - state.margin - state.size;
+        let hovered = state.hovered;
+
+        unsafe {
+            // Profundidad propia en el rectángulo del widget
+            gl::Viewport(left, bottom, state.size, state.size);
+            gl::Enable(gl::SCISSOR_TEST);
+            gl::Scissor(left, bottom, state.size, state.size);
+            gl::Clear(gl::DEPTH_BUFFER_BIT);
+            gl::Disable(gl::SCISSOR_TEST);
+
+            gl::UseProgram(self.program);
+            gl::UniformMatrix4fv(uniform_location(self.program, "view"), 1, gl::FALSE, view.as_ptr());
+            gl::UniformMatrix4fv(uniform_location(self.program, "projection"), 1, gl::FALSE, projection.as_ptr());
+            gl::Uniform1f(uniform_location(self.program, "border"), BORDER);
+            match hovered {
+                Some(region) => {
+                    gl::Uniform3i(
+                        uniform_location(self.program, "hovered"),
+                        region.x as i32, region.y as i32, region.z as i32,
+                    );
+                    gl::Uniform1i(uniform_location(self.program, "hasHover"), 1);
+                }
+                None => gl::Uniform1i(uniform_location(self.program, "hasHover"), 0),
+            }
+        }
+        self.cube.draw();
+        unsafe {
+            gl::Viewport(0, 0, width, height);
+        }
+    }
+}
+
+impl Drop for NavCubePass {
+    fn drop(&mut self) {
+        if !self.generation.is_current() {
+            return;
+        }
+        unsafe {
+            gl::DeleteProgram(self.program);
+        }
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn widget() -> NavCube {
+        NavCube { viewport: (800, 600), ..NavCube::default() }
+    }
+
+    #[test]
+    fn test_hit_front_face() {
+        // Con la cámara sin rotar se ve la cara +Z en el centro del widget
+        let cube = widget();
+        let center = (800 - 10 - 60, 10 + 60);
+        assert_eq!(cube.hit(center.0, center.1), Some(NavRegion::FRONT));
+    }
+
+    #[test]
+    fn test_hit_edge_and_outside() {
+        let cube = widget();
+        // Cerca del borde superior de la cara frontal -> arista (0, 1, 1)
+        let top_edge = cube.hit(800 - 10 - 60, 10 + 60 - 30);
+        assert_eq!(top_edge, Some(NavRegion { x: 0, y: 1, z: 1 }));
+        assert_eq!(cube.hit(10, 10), None);
+    }
+}
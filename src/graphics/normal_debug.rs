@@ -0,0 +1,187 @@
+// src/graphics/normal_debug.rs
+//
+// Inspección de las normales por vértice con que se sombrea cada objeto.
+// Cada normal se dibuja como un segmento corto coloreado por su dirección
+// (X rojo, Y verde, Z azul, como un mapa de normales) en una capa del
+// `LineOverlay`, y al hacer click en un vértice se informa su posición, su
+// normal y cuánto se aparta de las caras que lo usan: una normal promediada
+// que queda lejos de todas sus caras es la que produce el sombreado raro.
+// Con UVs también se puede ver el espacio tangente: tangente en rojo y
+// bitangente en verde, que es lo que usaría un mapa de normales.
+
+use crate::graphics::lines::Polyline;
+use crate::graphics::picking::{SubObjectElement, SubObjectHit};
+use crate::graphics::scene_object::SceneObject;
+use crate::graphics::uv::vertex_tangents;
+use crate::math::vec3::Vec3;
+
+/// Capa del `LineOverlay` con las normales
+pub const NORMALS_LAYER: &str = "normales";
+
+/// Capa del `LineOverlay` con tangentes y bitangentes
+pub const TANGENTS_LAYER: &str = "tangentes";
+
+/// Largo de los segmentos respecto de la diagonal de cada objeto
+pub const NORMAL_LENGTH: f32 = 0.03;
+
+/// Color lineal de una normal: cada componente de [-1, 1] a [0, 1]
+pub fn direction_color(normal: Vec3) -> [f32; 3] {
+    [normal.x, normal.y, normal.z].map(|c| (c * 0.5 + 0.5).clamp(0.0, 1.0))
+}
+
+/// Normal de un vértice en espacio mundo (None si no tiene o no es válida)
+fn world_normal(obj: &SceneObject, vertex: u32, global_scale: f32) -> Option<Vec3> {
+    let normal = Vec3::from(*obj.normals().get(vertex as usize)?);
+    let model = obj.model_matrix(global_scale);
+    let position = obj.mesh.position(vertex);
+    (model.transform_point(position + normal) - model.transform_point(position)).try_normalize()
+}
+
+/// Un segmento por vértice, en espacio mundo. Los vértices con normal nula
+/// o inválida no tienen dirección que mostrar: se cuentan con `invalid_normals`.
+pub fn normal_lines(obj: &SceneObject, global_scale: f32) -> Vec<Polyline> {
+    let length = obj.world_bounds(global_scale).size().magnitude() * NORMAL_LENGTH;
+    let model = obj.model_matrix(global_scale);
+    (0..obj.mesh.positions.len() as u32)
+        .filter_map(|vertex| {
+            let normal = world_normal(obj, vertex, global_scale)?;
+            let start = model.transform_point(obj.mesh.position(vertex));
+            Some(Polyline { points: vec![start, start + normal * length], color: direction_color(normal), closed: false })
+        })
+        .collect()
+}
+
+/// Tangente (roja) y bitangente (verde) de cada vértice, en espacio mundo.
+/// Vacío si el objeto no tiene UVs.
+pub fn tangent_lines(obj: &SceneObject, global_scale: f32) -> Vec<Polyline> {
+    let length = obj.world_bounds(global_scale).size().magnitude() * NORMAL_LENGTH;
+    let model = obj.model_matrix(global_scale);
+    let mut polylines = Vec::new();
+    for (vertex, [x, y, z, w]) in vertex_tangents(&obj.mesh, obj.normals()).into_iter().enumerate() {
+        let position = obj.mesh.position(vertex as u32);
+        let tangent = Vec3::new(x, y, z);
+        let bitangent = Vec3::from(obj.normals()[vertex]).normalize_or(Vec3::ZERO).cross(&tangent) * w;
+        let start = model.transform_point(position);
+        for (direction, color) in [(tangent, [1.0, 0.1, 0.1]), (bitangent, [0.1, 1.0, 0.1])] {
+            let Some(direction) = (model.transform_point(position + direction) - start).try_normalize() else {
+                continue;
+            };
+            polylines.push(Polyline { points: vec![start, start + direction * length], color, closed: false });
+        }
+    }
+    polylines
+}
+
+/// Cuántas normales del objeto no sirven para sombrear
+pub fn invalid_normals(obj: &SceneObject) -> usize {
+    let count = obj.mesh.positions.len();
+    let valid = obj
+        .normals()
+        .iter()
+        .take(count)
+        .filter(|n| n.iter().all(|c| c.is_finite()) && Vec3::from(**n).try_normalize().is_some())
+        .count();
+    count - valid
+}
+
+/// Lo que se informa de un vértice al hacer click
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VertexNormalReport {
+    pub object: usize,
+    pub vertex: u32,
+    /// En espacio mundo
+    pub position: Vec3,
+    /// Tal como se subió, en espacio del objeto
+    pub normal: [f32; 3],
+    /// Caras que usan el vértice
+    pub faces: usize,
+    /// Mayor ángulo (grados) entre la normal y las de esas caras
+    pub max_face_angle: f32,
+}
+
+impl VertexNormalReport {
+    pub fn new(obj: &SceneObject, object: usize, vertex: u32, global_scale: f32) -> Option<Self> {
+        if vertex as usize >= obj.mesh.positions.len() {
+            return None;
+        }
+        let normal = obj.normals().get(vertex as usize).copied().unwrap_or([0.0; 3]);
+        let mut faces = 0;
+        let mut max_face_angle: f32 = 0.0;
+        for triangle in 0..obj.mesh.triangle_count() {
+            let Some(corners) = obj.mesh.triangle(triangle).filter(|c| c.contains(&vertex)) else { continue };
+            let [a, b, c] = corners.map(|v| obj.mesh.position(v));
+            faces += 1;
+            if let Some(face) = (b - a).cross(&(c - a)).try_normalize() {
+                let cos = Vec3::from(normal).normalize_or(Vec3::ZERO).dot(&face).clamp(-1.0, 1.0);
+                max_face_angle = max_face_angle.max(cos.acos().to_degrees());
+            }
+        }
+        let position = obj.model_matrix(global_scale).transform_point(obj.mesh.position(vertex));
+        Some(Self { object, vertex, position, normal, faces, max_face_angle })
+    }
+
+    /// Vértice más cercano al punto de un picking de sub-objetos
+    pub fn from_hit(obj: &SceneObject, hit: &SubObjectHit, global_scale: f32) -> Option<Self> {
+        let candidates = match hit.element {
+            SubObjectElement::Vertex(v) => vec![v],
+            SubObjectElement::Edge(a, b) => vec![a, b],
+            SubObjectElement::Face(triangle) => obj.mesh.triangle(triangle)?.to_vec(),
+        };
+        let model = obj.model_matrix(global_scale);
+        let distance = |v: &u32| (model.transform_point(obj.mesh.position(*v)) - hit.point).magnitude();
+        let vertex = candidates.into_iter().min_by(|a, b| distance(a).total_cmp(&distance(b)))?;
+        Self::new(obj, hit.object, vertex, global_scale)
+    }
+
+    /// Texto para el log y el panel en pantalla
+    pub fn lines(&self) -> Vec<String> {
+        let [nx, ny, nz] = self.normal;
+        let p = self.position;
+        vec![
+            format!("Objeto {}, vértice {}", self.object, self.vertex),
+            format!("Posición ({:.4}, {:.4}, {:.4})", p.x, p.y, p.z),
+            format!("Normal ({:.4}, {:.4}, {:.4}), largo {:.4}", nx, ny, nz, Vec3::from(self.normal).magnitude()),
+            format!("{} caras, hasta {:.1}° de la normal", self.faces, self.max_face_angle),
+        ]
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::mesh::Mesh;
+
+    #[test]
+    fn test_normal_lines_and_report() {
+        // Dos triángulos en el plano z = 0
+        let mut obj = SceneObject::new(0, 6);
+        obj.set_mesh(Mesh::new(vec![[0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 1.0, 0.0]], vec![0, 1, 2, 1, 3, 2]));
+        assert_eq!(obj.normals().len(), 4);
+        assert_eq!(invalid_normals(&obj), 0);
+
+        let lines = normal_lines(&obj, 2.0);
+        assert_eq!(lines.len(), 4);
+        // Normal +Z: azul, y el segmento sale hacia +Z desde el vértice escalado
+        assert_eq!(lines[3].color, [0.5, 0.5, 1.0]);
+        assert_eq!(lines[3].points[0], Vec3::new(2.0, 2.0, 0.0));
+        assert!(lines[3].points[1].z > 0.0);
+
+        let report = VertexNormalReport::new(&obj, 7, 1, 1.0).unwrap();
+        assert_eq!(report.faces, 2);
+        assert!(report.max_face_angle < 1e-3);
+        assert_eq!(report.lines()[0], "Objeto 7, vértice 1");
+
+        // Un click en la cara elige el vértice más cercano al punto
+        let hit = SubObjectHit { object: 7, element: SubObjectElement::Face(1), point: Vec3::new(0.9, 0.9, 0.0) };
+        assert_eq!(VertexNormalReport::from_hit(&obj, &hit, 1.0).map(|r| r.vertex), Some(3));
+
+        // Sin UVs no hay espacio tangente; con UVs, tangente y bitangente por vértice
+        assert!(tangent_lines(&obj, 1.0).is_empty());
+        obj.set_uvs(vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]]);
+        let lines = tangent_lines(&obj, 1.0);
+        assert_eq!(lines.len(), 8);
+        assert!(lines[0].points[1].x > lines[0].points[0].x);
+        assert!(lines[1].points[1].y > lines[1].points[0].y);
+    }
+}
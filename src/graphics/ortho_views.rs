@@ -0,0 +1,525 @@
+// src/graphics/ortho_views.rs
+//
+// Hoja de vistas ortogonales acotadas, una ficha rápida de una pieza: vista
+// frontal, superior y lateral derecha (tercer diedro: la superior arriba de
+// la frontal, la lateral a su derecha) con las medidas de la caja envolvente
+// y un título con las tres medidas. Se guarda como SVG (vectorial) o PNG.
+//
+// Las líneas son las mismas del modo de líneas ocultas (ver `graphics::edges`):
+// cantos vivos, bordes abiertos y contornos, pero calculadas en CPU. Cada
+// arista se parte en tramos y un tramo se dibuja si el rayo desde su punto
+// medio hacia el observador no corta la pieza (`TraceScene`), así las aristas
+// de atrás no aparecen. Las coordenadas de la hoja están en unidades del
+// modelo, con y hacia arriba.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+#[cfg(feature = "ui")]
+use ab_glyph::{point, Font, FontVec, PxScale, ScaleFont};
+
+use crate::graphics::camara::View;
+use crate::graphics::edges::mesh_edges;
+use crate::graphics::lighting::Lighting;
+use crate::graphics::mesh_ops::transformed;
+use crate::graphics::path_tracer::TraceScene;
+use crate::graphics::scene_object::SceneObject;
+#[cfg(feature = "ui")]
+use crate::graphics::text::load_font;
+use crate::math::{aabb::Aabb, ray::Ray, vec3::Vec3};
+
+/// Vistas de la hoja y si llevan la cota horizontal (debajo) y la vertical (a la izquierda)
+const SHEET_VIEWS: [(View, bool, bool); 3] = [(View::Front, true, true), (View::Top, false, true), (View::Right, true, false)];
+
+/// Proporciones respecto de la medida mayor de la pieza
+const VIEW_GAP: f32 = 0.45;
+const DIMENSION_OFFSET: f32 = 0.15;
+const TEXT_SIZE: f32 = 0.06;
+/// Tramos en que se parte la diagonal de la pieza para probar la visibilidad
+const VISIBILITY_STEPS: f32 = 300.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrthoSettings {
+    /// Ángulo diedro en grados a partir del cual una arista es canto vivo
+    pub crease_angle: f32,
+    /// Ancho del PNG en píxeles (el alto sale de la hoja)
+    pub width: u32,
+    /// Título; sin él, los nombres de los objetos
+    pub title: Option<String>,
+}
+
+impl Default for OrthoSettings {
+    fn default() -> Self {
+        Self { crease_angle: 30.0, width: 1600, title: None }
+    }
+}
+
+/// Dónde se apoya un texto respecto de su posición (centrado en vertical)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextAnchor {
+    Middle,
+    End,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub position: [f32; 2],
+    pub text: String,
+    pub anchor: TextAnchor,
+}
+
+/// Hoja armada, lista para guardar
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Drawing {
+    /// Aristas visibles de las vistas
+    pub outlines: Vec<[[f32; 2]; 2]>,
+    /// Líneas de cota y de referencia
+    pub dimension_lines: Vec<[[f32; 2]; 2]>,
+    /// Puntas de flecha (triángulos rellenos)
+    pub arrows: Vec<[[f32; 2]; 3]>,
+    pub labels: Vec<Label>,
+    /// Alto de los textos
+    pub text_size: f32,
+    pub min: [f32; 2],
+    pub max: [f32; 2],
+}
+
+/// Ejes de la imagen (derecha, arriba) al mirar desde `view`. En las vistas
+/// desde arriba y abajo el frente de la pieza queda hacia abajo y arriba.
+pub fn view_axes(view: View) -> (Vec3, Vec3) {
+    let direction = view.direction();
+    let up = if direction.y.abs() > 0.99 { Vec3::new(0.0, 0.0, -direction.y.signum()) } else { Vec3::UNIT_Y };
+    (up.cross(&direction), up)
+}
+
+/// Aristas visibles de los objetos vistos desde `view`, proyectadas sobre sus ejes
+pub fn visible_edges(objects: &[SceneObject], global_scale: f32, view: View, crease_angle: f32) -> Vec<[[f32; 2]; 2]> {
+    let trace = TraceScene::from_objects(objects, global_scale, Lighting::default());
+    let bounds = trace.bounds();
+    if bounds.is_empty() {
+        return Vec::new();
+    }
+    let direction = view.direction();
+    let (right, up) = view_axes(view);
+    let project = |p: Vec3| [p.dot(&right), p.dot(&up)];
+    let diagonal = bounds.size().magnitude();
+    let step = diagonal / VISIBILITY_STEPS;
+    let bias = diagonal * 1e-4;
+    let visible = |p: Vec3| trace.intersect(&Ray::new(p + direction * bias, direction), f32::INFINITY).is_none();
+
+    let mut segments = Vec::new();
+    for obj in objects {
+        let mesh = transformed(&obj.mesh, &obj.model_matrix(global_scale));
+        for edge in mesh_edges(&mesh) {
+            let facing = edge.normals.map(|n| n.dot(&direction) > 0.0);
+            if !edge.is_crease(crease_angle.to_radians()) && facing[0] == facing[1] {
+                continue;
+            }
+            // Tramos visibles seguidos se unen en un solo segmento
+            let pieces = ((edge.b - edge.a).magnitude() / step).ceil().max(1.0) as usize;
+            let mut start: Option<Vec3> = None;
+            for piece in 0..pieces {
+                let t0 = piece as f32 / pieces as f32;
+                let t1 = (piece + 1) as f32 / pieces as f32;
+                let point = |t: f32| edge.a + (edge.b - edge.a) * t;
+                if visible(point((t0 + t1) * 0.5)) {
+                    start.get_or_insert(point(t0));
+                } else if let Some(from) = start.take() {
+                    segments.push([project(from), project(point(t0))]);
+                }
+            }
+            if let Some(from) = start {
+                segments.push([project(from), project(edge.b)]);
+            }
+        }
+    }
+    segments
+}
+
+fn min_max(points: impl IntoIterator<Item = [f32; 2]>) -> ([f32; 2], [f32; 2]) {
+    points.into_iter().fold(([f32::INFINITY; 2], [f32::NEG_INFINITY; 2]), |(min, max), [x, y]| {
+        ([min[0].min(x), min[1].min(y)], [max[0].max(x), max[1].max(y)])
+    })
+}
+
+fn format_length(value: f32) -> String {
+    format!("{:.2}", value)
+}
+
+impl Drawing {
+    /// Cota de `from` a `to` (horizontal o vertical) corrida `offset` hacia
+    /// afuera, con líneas de referencia, flechas y el valor
+    fn add_dimension(&mut self, from: [f32; 2], to: [f32; 2], horizontal: bool, offset: f32) {
+        let axis = if horizontal { 0 } else { 1 };
+        let across = 1 - axis;
+        let value = (to[axis] - from[axis]).abs();
+        let line = from[across] + offset;
+        let at = |along: f32, across_value: f32| {
+            let mut p = [0.0; 2];
+            p[axis] = along;
+            p[across] = across_value;
+            p
+        };
+        let overshoot = offset.signum() * self.text_size * 0.3;
+        for end in [from, to] {
+            self.dimension_lines.push([at(end[axis], end[across]), at(end[axis], line + overshoot)]);
+        }
+        let (a, b) = (at(from[axis], line), at(to[axis], line));
+        self.dimension_lines.push([a, b]);
+        let arrow = self.text_size * 0.5;
+        let sign = (to[axis] - from[axis]).signum();
+        for (tip, inward) in [(a, sign), (b, -sign)] {
+            self.arrows.push([
+                tip,
+                at(tip[axis] + inward * arrow, line - arrow * 0.3),
+                at(tip[axis] + inward * arrow, line + arrow * 0.3),
+            ]);
+        }
+        let middle = (from[axis] + to[axis]) * 0.5;
+        let text = format_length(value);
+        let label = if horizontal {
+            Label { position: at(middle, line - self.text_size * 0.8), text, anchor: TextAnchor::Middle }
+        } else {
+            Label { position: at(middle, line - self.text_size * 0.4), text, anchor: TextAnchor::End }
+        };
+        self.labels.push(label);
+    }
+
+    /// Caja que contiene todo (los textos de forma aproximada)
+    fn update_bounds(&mut self) {
+        let size = self.text_size;
+        let points = self.outlines.iter().chain(&self.dimension_lines).flatten().copied();
+        let labels = self.labels.iter().flat_map(|label| {
+            let width = label.text.chars().count() as f32 * size * 0.6;
+            let (left, right) = match label.anchor {
+                TextAnchor::Middle => (label.position[0] - width * 0.5, label.position[0] + width * 0.5),
+                TextAnchor::End => (label.position[0] - width, label.position[0]),
+            };
+            [[left, label.position[1] - size], [right, label.position[1] + size]]
+        });
+        (self.min, self.max) = min_max(points.chain(labels));
+    }
+}
+
+/// Arma la hoja con las tres vistas de los objetos juntos
+pub fn drawing(objects: &[SceneObject], global_scale: f32, settings: &OrthoSettings) -> Result<Drawing, String> {
+    let bounds = objects.iter().fold(Aabb::EMPTY, |acc, obj| acc.union(&obj.world_bounds(global_scale)));
+    if bounds.is_empty() {
+        return Err("No hay geometría para las vistas".to_string());
+    }
+    let size = bounds.size();
+    let extent = size.x.max(size.y).max(size.z).max(1e-6);
+    let gap = extent * VIEW_GAP;
+    let mut sheet = Drawing { text_size: extent * TEXT_SIZE, ..Drawing::default() };
+
+    // Caja de la pieza proyectada en cada vista
+    let corners: Vec<Vec3> = (0..8)
+        .map(|i| {
+            Vec3::new(
+                if i & 1 == 0 { bounds.min.x } else { bounds.max.x },
+                if i & 2 == 0 { bounds.min.y } else { bounds.max.y },
+                if i & 4 == 0 { bounds.min.z } else { bounds.max.z },
+            )
+        })
+        .collect();
+    let view_box = |view: View| {
+        let (right, up) = view_axes(view);
+        min_max(corners.iter().map(|p| [p.dot(&right), p.dot(&up)]))
+    };
+    let (front_min, front_max) = view_box(View::Front);
+    let front_size = [front_max[0] - front_min[0], front_max[1] - front_min[1]];
+
+    for (view, horizontal, vertical) in SHEET_VIEWS {
+        let (min, max) = view_box(view);
+        // Esquina inferior izquierda de la vista en la hoja
+        let origin = match view {
+            View::Top => [0.0, front_size[1] + gap],
+            View::Right => [front_size[0] + gap, 0.0],
+            _ => [0.0, 0.0],
+        };
+        let place = |[x, y]: [f32; 2]| [x - min[0] + origin[0], y - min[1] + origin[1]];
+        for [a, b] in visible_edges(objects, global_scale, view, settings.crease_angle) {
+            sheet.outlines.push([place(a), place(b)]);
+        }
+        let (low, high) = (place(min), place(max));
+        let offset = extent * DIMENSION_OFFSET;
+        if horizontal {
+            sheet.add_dimension(low, [high[0], low[1]], true, -offset);
+        }
+        if vertical {
+            sheet.add_dimension(low, [low[0], high[1]], false, -offset);
+        }
+    }
+
+    let title = settings.title.clone().unwrap_or_else(|| {
+        let names: Vec<&str> = objects.iter().map(|obj| obj.name.as_str()).filter(|name| !name.is_empty()).collect();
+        if names.is_empty() { "Pieza".to_string() } else { names.join(", ") }
+    });
+    sheet.labels.push(Label {
+        position: [front_size[0] * 0.5, -extent * (DIMENSION_OFFSET + TEXT_SIZE * 4.0)],
+        text: format!(
+            "{}: {} x {} x {}",
+            title,
+            format_length(size.x),
+            format_length(size.y),
+            format_length(size.z)
+        ),
+        anchor: TextAnchor::Middle,
+    });
+    sheet.update_bounds();
+    Ok(sheet)
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// SVG con y hacia abajo (se invierte la de la hoja)
+pub fn save_svg(drawing: &Drawing, path: &str) -> Result<(), String> {
+    let margin = drawing.text_size;
+    let (min, max) = (drawing.min, drawing.max);
+    let (width, height) = (max[0] - min[0] + 2.0 * margin, max[1] - min[1] + 2.0 * margin);
+    let flip = |[x, y]: [f32; 2]| [x, -y];
+    let outline = drawing.text_size * 0.08;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+        min[0] - margin, -max[1] - margin, width, height
+    );
+    let _ = writeln!(
+        svg,
+        r#"  <rect x="{}" y="{}" width="{}" height="{}" fill="white"/>"#,
+        min[0] - margin, -max[1] - margin, width, height
+    );
+    for (lines, stroke) in [(&drawing.outlines, outline), (&drawing.dimension_lines, outline * 0.4)] {
+        let _ = writeln!(svg, r#"  <g stroke="black" stroke-width="{}" stroke-linecap="round">"#, stroke);
+        for [a, b] in lines.iter().map(|line| line.map(flip)) {
+            let _ = writeln!(svg, r#"    <line x1="{}" y1="{}" x2="{}" y2="{}"/>"#, a[0], a[1], b[0], b[1]);
+        }
+        svg.push_str("  </g>\n");
+    }
+    for arrow in &drawing.arrows {
+        let points: Vec<String> = arrow.iter().map(|&p| flip(p)).map(|[x, y]| format!("{},{}", x, y)).collect();
+        let _ = writeln!(svg, r#"  <polygon points="{}" fill="black"/>"#, points.join(" "));
+    }
+    for label in &drawing.labels {
+        let [x, y] = flip(label.position);
+        let anchor = match label.anchor {
+            TextAnchor::Middle => "middle",
+            TextAnchor::End => "end",
+        };
+        let _ = writeln!(
+            svg,
+            r#"  <text x="{}" y="{}" font-family="sans-serif" font-size="{}" text-anchor="{}" dominant-baseline="middle">{}</text>"#,
+            x, y, drawing.text_size, anchor, escape_xml(&label.text)
+        );
+    }
+    svg.push_str("</svg>\n");
+    fs::write(path, svg).map_err(|e| format!("No se pudo escribir {}: {}", path, e))
+}
+
+/// Tinta (0 = blanco, 1 = negro) de una imagen en escala de grises
+struct Canvas {
+    width: usize,
+    height: usize,
+    ink: Vec<f32>,
+}
+
+impl Canvas {
+    fn plot(&mut self, x: i64, y: i64, coverage: f32) {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            let ink = &mut self.ink[y as usize * self.width + x as usize];
+            *ink = ink.max(coverage.clamp(0.0, 1.0));
+        }
+    }
+
+    /// Segmento de `width` píxeles de grosor con bordes suavizados
+    fn line(&mut self, a: [f32; 2], b: [f32; 2], width: f32) {
+        let radius = width * 0.5;
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        let length_sq = (dx * dx + dy * dy).max(1e-12);
+        let (min_x, max_x) = ((a[0].min(b[0]) - radius - 1.0).floor(), (a[0].max(b[0]) + radius + 1.0).ceil());
+        let (min_y, max_y) = ((a[1].min(b[1]) - radius - 1.0).floor(), (a[1].max(b[1]) + radius + 1.0).ceil());
+        for y in min_y as i64..=max_y as i64 {
+            for x in min_x as i64..=max_x as i64 {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let t = (((px - a[0]) * dx + (py - a[1]) * dy) / length_sq).clamp(0.0, 1.0);
+                let (cx, cy) = (a[0] + dx * t - px, a[1] + dy * t - py);
+                let distance = (cx * cx + cy * cy).sqrt();
+                if distance < radius + 0.5 {
+                    self.plot(x, y, radius + 0.5 - distance);
+                }
+            }
+        }
+    }
+
+    /// Triángulo relleno, con 4 muestras por píxel
+    fn triangle(&mut self, [a, b, c]: [[f32; 2]; 3]) {
+        let edge = |p: [f32; 2], q: [f32; 2], r: [f32; 2]| (q[0] - p[0]) * (r[1] - p[1]) - (q[1] - p[1]) * (r[0] - p[0]);
+        let area = edge(a, b, c);
+        if area.abs() < 1e-9 {
+            return;
+        }
+        let (min, max) = min_max([a, b, c]);
+        for y in min[1].floor() as i64..=max[1].ceil() as i64 {
+            for x in min[0].floor() as i64..=max[0].ceil() as i64 {
+                let inside = [(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)]
+                    .iter()
+                    .filter(|(sx, sy)| {
+                        let p = [x as f32 + sx, y as f32 + sy];
+                        [edge(a, b, p), edge(b, c, p), edge(c, a, p)].iter().all(|e| e * area >= 0.0)
+                    })
+                    .count();
+                if inside > 0 {
+                    self.plot(x, y, inside as f32 / 4.0);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "ui")]
+    fn text(&mut self, font: &FontVec, px: f32, label: &Label, position: [f32; 2]) {
+        let scaled = font.as_scaled(PxScale::from(px));
+        let width: f32 = label.text.chars().map(|c| scaled.h_advance(scaled.glyph_id(c))).sum();
+        let mut pen = match label.anchor {
+            TextAnchor::Middle => position[0] - width * 0.5,
+            TextAnchor::End => position[0] - width,
+        };
+        // Centrado en vertical entre la línea de base y la altura de las mayúsculas
+        let baseline = position[1] + (scaled.ascent() + scaled.descent()) * 0.5;
+        for c in label.text.chars() {
+            let mut glyph = scaled.scaled_glyph(c);
+            glyph.position = point(pen, baseline);
+            pen += scaled.h_advance(glyph.id);
+            let Some(outline) = scaled.outline_glyph(glyph) else { continue };
+            let bounds = outline.px_bounds();
+            outline.draw(|gx, gy, coverage| {
+                self.plot(bounds.min.x as i64 + gx as i64, bounds.min.y as i64 + gy as i64, coverage);
+            });
+        }
+    }
+}
+
+/// PNG en escala de grises de `width` píxeles de ancho. Sin fuente (o sin la
+/// feature `ui`) los textos no se dibujan (se avisa).
+pub fn save_png(drawing: &Drawing, path: &str, width: u32) -> Result<(), String> {
+    let margin = drawing.text_size;
+    let sheet = [drawing.max[0] - drawing.min[0] + 2.0 * margin, drawing.max[1] - drawing.min[1] + 2.0 * margin];
+    let scale = width.max(1) as f32 / sheet[0].max(1e-6);
+    let height = (sheet[1] * scale).ceil().max(1.0) as u32;
+    let to_pixels = |[x, y]: [f32; 2]| [(x - drawing.min[0] + margin) * scale, (drawing.max[1] + margin - y) * scale];
+
+    let mut canvas = Canvas { width: width as usize, height: height as usize, ink: vec![0.0; (width * height) as usize] };
+    let outline = (drawing.text_size * 0.08 * scale).max(1.0);
+    for [a, b] in &drawing.outlines {
+        canvas.line(to_pixels(*a), to_pixels(*b), outline);
+    }
+    for [a, b] in &drawing.dimension_lines {
+        canvas.line(to_pixels(*a), to_pixels(*b), (outline * 0.4).max(1.0));
+    }
+    for arrow in &drawing.arrows {
+        canvas.triangle(arrow.map(to_pixels));
+    }
+    #[cfg(feature = "ui")]
+    match load_font() {
+        Ok(font) => {
+            for label in &drawing.labels {
+                canvas.text(&font, drawing.text_size * scale, label, to_pixels(label.position));
+            }
+        }
+        Err(e) => eprintln!("Vistas sin textos: {}", e),
+    }
+    #[cfg(not(feature = "ui"))]
+    eprintln!("Vistas sin textos: compilado sin la feature ui");
+
+    let bytes: Vec<u8> = canvas.ink.iter().map(|ink| ((1.0 - ink) * 255.0).round() as u8).collect();
+    save_gray(path, &bytes, width, height)
+}
+
+#[cfg(feature = "image-export")]
+fn save_gray(path: &str, bytes: &[u8], width: u32, height: u32) -> Result<(), String> {
+    image::save_buffer(path, bytes, width, height, image::ColorType::L8)
+        .map_err(|e| format!("No se pudo guardar {}: {}", path, e))
+}
+
+#[cfg(not(feature = "image-export"))]
+fn save_gray(path: &str, _bytes: &[u8], _width: u32, _height: u32) -> Result<(), String> {
+    Err(format!("No se pudo guardar {}: compilado sin la feature image-export", path))
+}
+
+/// Arma la hoja y la guarda: `.svg` vectorial, cualquier otra extensión PNG
+pub fn export(objects: &[SceneObject], global_scale: f32, settings: &OrthoSettings, path: &str) -> Result<(), String> {
+    let sheet = drawing(objects, global_scale, settings)?;
+    let is_svg = Path::new(path).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("svg"));
+    if is_svg {
+        save_svg(&sheet, path)
+    } else {
+        save_png(&sheet, path, settings.width)
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_axes_and_dimensions() {
+        let axes = |view| {
+            let (right, up) = view_axes(view);
+            [right, up].map(|v| [v.x.round(), v.y.round(), v.z.round()])
+        };
+        assert_eq!(axes(View::Front), [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+        assert_eq!(axes(View::Top), [[1.0, 0.0, 0.0], [0.0, 0.0, -1.0]]);
+        assert_eq!(axes(View::Right), [[0.0, 0.0, -1.0], [0.0, 1.0, 0.0]]);
+
+        let mut sheet = Drawing { text_size: 1.0, ..Drawing::default() };
+        sheet.add_dimension([0.0, 0.0], [12.5, 0.0], true, -3.0);
+        sheet.add_dimension([0.0, 0.0], [0.0, 4.0], false, -3.0);
+        // Dos líneas de referencia y la de cota por medida, con flecha en cada punta
+        assert_eq!(sheet.dimension_lines.len(), 6);
+        assert_eq!(sheet.arrows.len(), 4);
+        assert_eq!(sheet.dimension_lines[2], [[0.0, -3.0], [12.5, -3.0]]);
+        assert_eq!(sheet.labels[0].text, "12.50");
+        assert_eq!(sheet.labels[1], Label { position: [-3.4, 2.0], text: "4.00".to_string(), anchor: TextAnchor::End });
+        sheet.update_bounds();
+        assert!(sheet.min[0] < -3.0 && sheet.min[1] < -3.0 && sheet.max[0] >= 12.5);
+
+        let dir = std::env::temp_dir();
+        let svg = dir.join(format!("rust_engine_vistas_{}.svg", std::process::id()));
+        save_svg(&sheet, &svg.to_string_lossy()).unwrap();
+        let text = fs::read_to_string(&svg).unwrap();
+        assert_eq!(text.matches("<line").count(), 6);
+        assert!(text.contains(">12.50</text>"));
+        let _ = fs::remove_file(svg);
+        #[cfg(feature = "image-export")]
+        {
+            let png = dir.join(format!("rust_engine_vistas_{}.png", std::process::id()));
+            save_png(&sheet, &png.to_string_lossy(), 200).unwrap();
+            assert_eq!(image::open(&png).unwrap().width(), 200);
+            let _ = fs::remove_file(png);
+        }
+
+        // Caja de 1 x 1 x 2: en cada vista solo el rectángulo de adelante
+        let mut part = SceneObject::new(0, 0);
+        let positions = (0..8).map(|i| [(i & 1) as f32, ((i >> 1) & 1) as f32, ((i >> 2) & 1) as f32 * 2.0]).collect();
+        #[rustfmt::skip]
+        let indices = vec![
+            0, 2, 1, 1, 2, 3, 4, 5, 6, 5, 7, 6, 0, 1, 4, 1, 5, 4,
+            2, 6, 3, 3, 6, 7, 0, 4, 2, 2, 4, 6, 1, 3, 5, 3, 7, 5,
+        ];
+        part.set_mesh(crate::graphics::mesh::Mesh::new(positions, indices));
+        for (view, perimeter) in [(View::Front, 4.0), (View::Top, 6.0), (View::Right, 6.0)] {
+            let edges = visible_edges(std::slice::from_ref(&part), 1.0, view, 30.0);
+            let length: f32 = edges.iter().map(|[a, b]| ((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2)).sqrt()).sum();
+            assert_eq!(edges.len(), 4);
+            assert!((length - perimeter).abs() < 1e-3, "{:?}: {}", view, length);
+        }
+        let sheet = drawing(std::slice::from_ref(&part), 1.0, &OrthoSettings::default()).unwrap();
+        assert_eq!(sheet.outlines.len(), 12);
+        assert!(sheet.labels.last().unwrap().text.ends_with("1.00 x 1.00 x 2.00"));
+    }
+}
@@ -0,0 +1,485 @@
+// src/graphics/passes.rs
+//
+// Pases de render incluidos en el motor
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::graphics::environment::CubeMesh;
+use crate::graphics::gpu_resources::ContextGeneration;
+use crate::graphics::gl_validation;
+use crate::graphics::material::MaterialLibrary;
+use crate::graphics::render_graph::{FrameContext, PassStage, RenderPass, ResourceId, BACKBUFFER, SCENE_DEPTH};
+use crate::graphics::render_settings::RenderSettings;
+use crate::graphics::shader_variants::{ShaderFeatures, ShaderVariants};
+use crate::graphics::shaders::{build_program, uniform_location, ShaderLibrary};
+use crate::graphics::uniforms;
+use crate::math::{matrix_4_by_4::Matrix4, vec3::Vec3};
+
+/// Color base sRGB de los objetos sin color propio
+pub const OBJECT_COLOR: [f32; 3] = [0.8, 0.8, 0.8];
+
+/// Unidad de textura de la textura de color de los materiales (la 0 es la
+/// del lightmap y los overrides de `uniforms` usan las siguientes)
+const COLOR_MAP_UNIT: u32 = 15;
+/// Unidad del cubemap prefiltrado del entorno
+const ENVIRONMENT_UNIT: u32 = 14;
+
+/// Color sRGB, rugosidad y metalicidad con que se dibuja un objeto
+#[derive(Clone, Copy, PartialEq)]
+struct Surface {
+    color: [f32; 3],
+    roughness: f32,
+    metallic: f32,
+}
+
+/// Sin material: gris mate
+const DEFAULT_SURFACE: Surface = Surface { color: OBJECT_COLOR, roughness: 1.0, metallic: 0.0 };
+
+/// Dibuja todos los objetos con el shader principal (Lambert + ambiente),
+/// cada uno con la variante más barata que le sirve. Los `overlay` quedan
+/// para `OverlayPass`.
+pub struct OpaquePass {
+    variants: Rc<RefCell<ShaderVariants>>,
+    materials: Rc<RefCell<MaterialLibrary>>,
+}
+
+impl OpaquePass {
+    pub fn new(variants: ShaderVariants, materials: Rc<RefCell<MaterialLibrary>>) -> Self {
+        Self { variants: Rc::new(RefCell::new(variants)), materials }
+    }
+
+    /// Pase de la capa superpuesta con las mismas variantes del shader
+    pub fn overlay_pass(&self) -> OverlayPass {
+        OverlayPass { variants: self.variants.clone(), materials: self.materials.clone() }
+    }
+
+    /// Uniforms comunes a todo el frame; se cargan al pasar a otra variante
+    unsafe fn bind_frame_uniforms(program: u32, frame: &FrameContext) {
+        gl::UseProgram(program);
+
+        let sun = frame.lighting.sun_direction;
+        gl::Uniform3f(uniform_location(program, "lightDir"), sun.x, sun.y, sun.z);
+        let [r, g, b] = frame.lighting.sun_color;
+        gl::Uniform3f(uniform_location(program, "lightColor"), r, g, b);
+        gl::Uniform1f(uniform_location(program, "ambient"), frame.lighting.ambient);
+        for (i, fill) in frame.lighting.fill_lights.iter().enumerate() {
+            let d = fill.direction;
+            gl::Uniform3f(uniform_location(program, &format!("fillDirs[{}]", i)), d.x, d.y, d.z);
+            let [r, g, b] = fill.color;
+            gl::Uniform3f(uniform_location(program, &format!("fillColors[{}]", i)), r, g, b);
+        }
+        let [r, g, b] = frame.settings.shader_color(DEFAULT_SURFACE.color);
+        gl::Uniform3f(uniform_location(program, "objectColor"), r, g, b);
+        gl::Uniform1f(uniform_location(program, "roughness"), DEFAULT_SURFACE.roughness);
+        gl::Uniform1f(uniform_location(program, "metallic"), DEFAULT_SURFACE.metallic);
+        let eye = camera_position(&frame.view);
+        gl::Uniform3f(uniform_location(program, "viewPos"), eye.x, eye.y, eye.z);
+        gl::Uniform1f(uniform_location(program, "gamma"), frame.settings.gamma);
+
+        gl::UniformMatrix4fv(uniform_location(program, "view"), 1, gl::FALSE, frame.view.as_ptr());
+        gl::UniformMatrix4fv(uniform_location(program, "projection"), 1, gl::FALSE, frame.projection.as_ptr());
+        gl::Uniform1i(uniform_location(program, "lightmap"), 0);
+        gl::Uniform1i(uniform_location(program, "colorMap"), COLOR_MAP_UNIT as i32);
+        if let Some(environment) = frame.environment {
+            environment.bind_prefiltered(ENVIRONMENT_UNIT);
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::Uniform1i(uniform_location(program, "environmentMap"), ENVIRONMENT_UNIT as i32);
+            gl::Uniform1f(uniform_location(program, "environmentExposure"), environment.exposure);
+            let lod = environment.lod_for_roughness(DEFAULT_SURFACE.roughness);
+            gl::Uniform1f(uniform_location(program, "environmentLod"), lod);
+        }
+        frame.settings.clipping.bind(program);
+    }
+}
+
+/// Posición de la cámara de una matriz de vista rígida (rotación + traslación)
+fn camera_position(view: &Matrix4) -> Vec3 {
+    let m = &view.m;
+    let t = Vec3::new(m[12], m[13], m[14]);
+    // -Rᵀ·t, con R guardada por columnas
+    Vec3::new(
+        -(m[0] * t.x + m[1] * t.y + m[2] * t.z),
+        -(m[4] * t.x + m[5] * t.y + m[6] * t.z),
+        -(m[8] * t.x + m[9] * t.y + m[10] * t.z),
+    )
+}
+
+impl RenderPass for OpaquePass {
+    fn name(&self) -> &str {
+        "opaque"
+    }
+
+    fn stage(&self) -> PassStage {
+        PassStage::Opaque
+    }
+
+    fn outputs(&self) -> &[ResourceId] {
+        &[BACKBUFFER, SCENE_DEPTH]
+    }
+
+    fn settings(&self, global: &RenderSettings) -> RenderSettings {
+        if !global.outlines || global.wireframe {
+            return *global;
+        }
+        // Caras empujadas hacia atrás para que les ganen las aristas de `HiddenLinePass`
+        let [factor, units] = global.polygon_offset;
+        RenderSettings { polygon_offset: [factor.max(1.0), units.max(1.0)], ..*global }
+    }
+
+    fn execute(&mut self, frame: &FrameContext) {
+        // En líneas ocultas la escena la dibuja `HiddenLinePass`
+        if frame.settings.hidden_line {
+            return;
+        }
+        draw_objects(&mut self.variants.borrow_mut(), &self.materials.borrow(), frame, &frame.draw_order(false));
+    }
+}
+
+/// Dibuja `order` con el shader principal. Dentro de cada `render_order` se
+/// agrupa por variante para cambiar de programa lo menos posible.
+fn draw_objects(variants: &mut ShaderVariants, materials: &MaterialLibrary, frame: &FrameContext, order: &[usize]) {
+    let reflections = if frame.environment.is_some() { ShaderFeatures::ENVIRONMENT_MAP } else { ShaderFeatures::NONE };
+    // Un draw por sub-mesh; el orden estable deja los de un objeto en su orden
+    let mut draws: Vec<(ShaderFeatures, usize, usize)> = order
+        .iter()
+        .flat_map(|&index| {
+            let obj = &frame.objects[index];
+            let has_uvs = !obj.mesh.uvs.is_empty();
+            (0..obj.part_count()).map(move |part| {
+                let color_map = obj.part(part).2.and_then(|name| materials.color_map(name));
+                // El damero reemplaza color, lightmap y reflejos
+                let features = if frame.settings.uv_checker && has_uvs {
+                    ShaderFeatures::UV_CHECKER
+                } else if color_map.is_some() && has_uvs {
+                    ShaderFeatures::for_object(obj) | ShaderFeatures::COLOR_MAP | reflections
+                } else {
+                    ShaderFeatures::for_object(obj) | reflections
+                };
+                (features, index, part)
+            })
+        })
+        .collect();
+    draws.sort_by_key(|(features, index, _)| (frame.objects[*index].render_order, features.bits()));
+
+    let mut current = None;
+    let mut program = 0;
+    let mut current_surface = DEFAULT_SURFACE;
+    unsafe {
+        gl::ActiveTexture(gl::TEXTURE0);
+        for (features, index, part) in draws {
+            if current != Some(features) {
+                program = variants.get(features);
+                OpaquePass::bind_frame_uniforms(program, frame);
+                current = Some(features);
+                current_surface = DEFAULT_SURFACE;
+            }
+            let obj = &frame.objects[index];
+            let (start, count, material_name) = obj.part(part);
+            // El color propio del objeto gana sobre el del material
+            let material = material_name.and_then(|name| materials.get(name));
+            let surface = Surface {
+                color: obj.color.or(material.map(|m| m.color)).or(obj.part_color(part)).unwrap_or(OBJECT_COLOR),
+                roughness: material.map_or(1.0, |m| m.roughness),
+                metallic: material.map_or(0.0, |m| m.metallic),
+            };
+            if surface != current_surface {
+                let [r, g, b] = frame.settings.shader_color(surface.color);
+                gl::Uniform3f(uniform_location(program, "objectColor"), r, g, b);
+                gl::Uniform1f(uniform_location(program, "roughness"), surface.roughness);
+                gl::Uniform1f(uniform_location(program, "metallic"), surface.metallic);
+                if let Some(environment) = frame.environment {
+                    let lod = environment.lod_for_roughness(surface.roughness);
+                    gl::Uniform1f(uniform_location(program, "environmentLod"), lod);
+                }
+                current_surface = surface;
+            }
+            if features.contains(ShaderFeatures::COLOR_MAP) {
+                if let Some(texture) = material_name.and_then(|name| materials.color_map(name)) {
+                    gl::ActiveTexture(gl::TEXTURE0 + COLOR_MAP_UNIT);
+                    gl::BindTexture(gl::TEXTURE_2D, texture);
+                    gl::ActiveTexture(gl::TEXTURE0);
+                }
+            }
+            let final_model = obj.model_matrix(frame.global_scale);
+
+            if let Some(lightmap) = obj.lightmap {
+                gl::BindTexture(gl::TEXTURE_2D, lightmap.texture);
+            }
+            gl::UniformMatrix4fv(uniform_location(program, "model"), 1, gl::FALSE, final_model.as_ptr());
+            let normal_matrix = obj.normal_matrix(frame.global_scale);
+            gl::UniformMatrix4fv(uniform_location(program, "normalMatrix"), 1, gl::FALSE, normal_matrix.as_ptr());
+            gl::Uniform1i(uniform_location(program, "ignoreClipping"), obj.ignore_clipping as i32);
+            if features.contains(ShaderFeatures::MORPH_TARGETS) {
+                for (i, weight) in obj.morph_weights.iter().enumerate() {
+                    gl::Uniform1f(uniform_location(program, &format!("morphWeights[{}]", i)), *weight);
+                }
+            }
+            let saved = uniforms::apply(program, &obj.uniforms);
+            gl::BindVertexArray(obj.vao);
+            gl_validation::draw_elements(gl::TRIANGLES, count, start as usize * std::mem::size_of::<u32>());
+            uniforms::restore(saved);
+        }
+        gl::BindTexture(gl::TEXTURE_2D, 0);
+    }
+}
+
+/// Objetos marcados `overlay` (gizmos, ayudas de medición...): se dibujan
+/// después de la escena y del cielo, sobre la profundidad limpia, así quedan
+/// siempre encima del modelo pero se ocultan bien entre ellos
+pub struct OverlayPass {
+    variants: Rc<RefCell<ShaderVariants>>,
+    materials: Rc<RefCell<MaterialLibrary>>,
+}
+
+impl RenderPass for OverlayPass {
+    fn name(&self) -> &str {
+        "overlay"
+    }
+
+    fn stage(&self) -> PassStage {
+        PassStage::Transparent
+    }
+
+    fn outputs(&self) -> &[ResourceId] {
+        &[BACKBUFFER, SCENE_DEPTH]
+    }
+
+    fn execute(&mut self, frame: &FrameContext) {
+        let order = frame.draw_order(true);
+        if order.is_empty() {
+            return;
+        }
+        unsafe {
+            // Solo la profundidad de la vista actual (puede haber varias en el framebuffer)
+            let mut viewport = [0i32; 4];
+            gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+            gl::Enable(gl::SCISSOR_TEST);
+            gl::Scissor(viewport[0], viewport[1], viewport[2], viewport[3]);
+            gl::Clear(gl::DEPTH_BUFFER_BIT);
+            gl::Disable(gl::SCISSOR_TEST);
+        }
+        draw_objects(&mut self.variants.borrow_mut(), &self.materials.borrow(), frame, &order);
+    }
+}
+
+/// Dibuja el cubemap del entorno detrás de la escena (donde la profundidad quedó en 1.0)
+pub struct SkyboxPass {
+    program: u32,
+    cube: CubeMesh,
+    generation: ContextGeneration,
+}
+
+impl SkyboxPass {
+    pub fn new() -> Result<Self, String> {
+        let program = build_program(
+            include_str!("shaders/skybox.vert"),
+            include_str!("shaders/skybox.frag"),
+        )?;
+        Ok(Self {
+            program,
+            cube: CubeMesh::new(),
+            generation: ContextGeneration::current(),
+        })
+    }
+}
+
+impl RenderPass for SkyboxPass {
+    fn name(&self) -> &str {
+        "skybox"
+    }
+
+    fn stage(&self) -> PassStage {
+        PassStage::Opaque
+    }
+
+    fn inputs(&self) -> &[ResourceId] {
+        &[SCENE_DEPTH]
+    }
+
+    fn outputs(&self) -> &[ResourceId] {
+        &[BACKBUFFER]
+    }
+
+    fn settings(&self, global: &RenderSettings) -> RenderSettings {
+        // El cubo se ve desde dentro y siempre relleno
+        RenderSettings {
+            backface_culling: false,
+            wireframe: false,
+            depth_test: true,
+            ..*global
+        }
+    }
+
+    fn rebuild_gpu(&mut self, _shaders: &ShaderLibrary) -> Result<(), String> {
+        *self = Self::new()?;
+        Ok(())
+    }
+
+    fn execute(&mut self, frame: &FrameContext) {
+        let Some(environment) = frame.environment else {
+            return;
+        };
+
+        // Quitar la traslación de la vista: el cielo está en el infinito
+        let mut view = frame.view;
+        view.m[12] = 0.0;
+        view.m[13] = 0.0;
+        view.m[14] = 0.0;
+
+        unsafe {
+            gl::DepthFunc(gl::LEQUAL);
+            gl::UseProgram(self.program);
+            gl::UniformMatrix4fv(uniform_location(self.program, "view"), 1, gl::FALSE, view.as_ptr());
+            gl::UniformMatrix4fv(uniform_location(self.program, "projection"), 1, gl::FALSE, frame.projection.as_ptr());
+            gl::Uniform1f(uniform_location(self.program, "exposure"), environment.exposure);
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, environment.cubemap);
+            gl::Uniform1i(uniform_location(self.program, "environment"), 0);
+        }
+        self.cube.draw();
+        unsafe {
+            gl::DepthFunc(gl::LESS);
+        }
+    }
+}
+
+impl Drop for SkyboxPass {
+    fn drop(&mut self) {
+        if !self.generation.is_current() {
+            return;
+        }
+        unsafe {
+            gl::DeleteProgram(self.program);
+        }
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::gl_mock::{self, drawn_vaos, uniform, UniformValue};
+    use crate::graphics::lighting::Lighting;
+    use crate::graphics::material::Material;
+    use crate::graphics::scene_object::{SceneObject, SubMesh};
+    use crate::graphics::uniforms;
+    use crate::math::matrix_4_by_4::Matrix4;
+
+    #[test]
+    fn test_opaque_pass_skips_culled_objects() {
+        gl_mock::install();
+        let mut objects: Vec<SceneObject> = (1..=3).map(|vao| SceneObject::new(vao, 3)).collect();
+        objects[2].transform.scale = Vec3::new(2.0, 2.0, 2.0);
+        objects[0].uniforms.set("objectColor", uniforms::UniformValue::Vec3([1.0, 0.0, 0.0]));
+        // El tercero en dos sub-meshes, el segundo metálico
+        objects[2].index_count = 9;
+        let submeshes = vec![
+            SubMesh { start: 0, count: 6, material: None, color: None },
+            SubMesh { start: 6, count: 3, material: Some("metal".to_string()), color: None },
+        ];
+        assert!(objects[2].set_submeshes(vec![SubMesh { start: 6, count: 6, material: None, color: None }]).is_err());
+        objects[2].set_submeshes(submeshes).unwrap();
+        let mut materials = MaterialLibrary::new();
+        materials.insert("metal", Material { metallic: 1.0, ..Material::default() });
+        let visible = [true, false, true];
+        let (settings, lighting) = (RenderSettings::default(), Lighting::default());
+        let frame = FrameContext {
+            objects: &objects,
+            view: Matrix4::identity(),
+            projection: Matrix4::identity(),
+            global_scale: 1.0,
+            viewport: (64, 64),
+            program: 9,
+            settings: &settings,
+            environment: None,
+            lighting: &lighting,
+            visible: Some(&visible),
+            targets: None,
+        };
+        let source = "#version 330 core\nvoid main() {}";
+        let mut pass = OpaquePass::new(
+            ShaderVariants::new(ShaderLibrary::new(), source, source).unwrap(),
+            Rc::new(RefCell::new(materials)),
+        );
+        let base = pass.variants.borrow().base();
+        gl_mock::take_calls();
+        pass.execute(&frame);
+
+        let calls = gl_mock::take_calls();
+        assert_eq!(drawn_vaos(&calls), vec![1, 3, 3]);
+        let counts: Vec<i32> = calls
+            .iter()
+            .filter_map(|c| match c {
+                gl_mock::GlCall::DrawElements(_, count) => Some(*count),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(counts, vec![3, 6, 3]);
+        assert_eq!(uniform(&calls, "metallic"), Some(UniformValue::Float(1.0)));
+        // Ningún objeto necesita características: una sola variante, la base
+        assert_eq!(calls.iter().filter(|c| matches!(c, gl_mock::GlCall::UseProgram(_))).count(), 1);
+        assert!(calls.contains(&gl_mock::GlCall::UseProgram(base)));
+        // El color propio del primer objeto se aplica antes de su draw y se restaura después
+        let draw = calls.iter().position(|c| matches!(c, gl_mock::GlCall::DrawElements(..))).unwrap();
+        assert_eq!(uniform(&calls[..draw], "objectColor"), Some(UniformValue::Vec3([1.0, 0.0, 0.0])));
+        let [r, g, b] = settings.shader_color([0.8, 0.8, 0.8]);
+        assert_eq!(uniform(&calls, "objectColor"), Some(UniformValue::Vec3([r, g, b])));
+        assert_eq!(uniform(&calls, "gamma"), Some(UniformValue::Float(settings.gamma)));
+        let sun = lighting.sun_direction;
+        assert_eq!(uniform(&calls, "lightDir"), Some(UniformValue::Vec3([sun.x, sun.y, sun.z])));
+        // El último modelo cargado es el del último objeto dibujado
+        assert_eq!(uniform(&calls, "model"), Some(UniformValue::Mat4(objects[2].model_matrix(1.0).m)));
+        // Escalado x2 en los tres ejes: las normales se achican a la mitad
+        let normal_matrix = uniform(&calls, "normalMatrix");
+        assert_eq!(normal_matrix, Some(UniformValue::Mat4(Matrix4::scale(0.5).m)));
+    }
+
+    #[test]
+    fn test_overlay_objects_drawn_on_top() {
+        gl_mock::install();
+        let mut objects: Vec<SceneObject> = (1..=4).map(|vao| SceneObject::new(vao, 3)).collect();
+        objects[0].render_order = 1;
+        objects[1].overlay = true;
+        objects[2].overlay = true;
+        objects[2].render_order = -1;
+        objects[3].color = Some([0.2, 0.4, 0.6]);
+        let (settings, lighting) = (RenderSettings::default(), Lighting::default());
+        let frame = FrameContext {
+            objects: &objects,
+            view: Matrix4::identity(),
+            projection: Matrix4::identity(),
+            global_scale: 1.0,
+            viewport: (64, 64),
+            program: 9,
+            settings: &settings,
+            environment: None,
+            lighting: &lighting,
+            visible: None,
+            targets: None,
+        };
+        let source = "#version 330 core\nvoid main() {}";
+        let mut opaque = OpaquePass::new(ShaderVariants::new(ShaderLibrary::new(), source, source).unwrap(), Rc::default());
+        let mut overlay = opaque.overlay_pass();
+        gl_mock::take_calls();
+
+        // La escena por render_order, sin los objetos superpuestos
+        opaque.execute(&frame);
+        let calls = gl_mock::take_calls();
+        assert_eq!(drawn_vaos(&calls), vec![4, 1]);
+        // Color propio para el primero y de vuelta el gris para el siguiente
+        let draw = calls.iter().position(|c| matches!(c, gl_mock::GlCall::DrawElements(..))).unwrap();
+        let [r, g, b] = settings.shader_color([0.2, 0.4, 0.6]);
+        assert_eq!(uniform(&calls[..draw], "objectColor"), Some(UniformValue::Vec3([r, g, b])));
+        let [r, g, b] = settings.shader_color(OBJECT_COLOR);
+        assert_eq!(uniform(&calls, "objectColor"), Some(UniformValue::Vec3([r, g, b])));
+
+        // La capa superpuesta limpia la profundidad antes de dibujar
+        overlay.execute(&frame);
+        let calls = gl_mock::take_calls();
+        assert_eq!(drawn_vaos(&calls), vec![3, 2]);
+        let clear = calls.iter().position(|c| *c == gl_mock::GlCall::Clear(gl::DEPTH_BUFFER_BIT)).unwrap();
+        let draw = calls.iter().position(|c| matches!(c, gl_mock::GlCall::DrawElements(..))).unwrap();
+        assert!(clear < draw);
+    }
+}
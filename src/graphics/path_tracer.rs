@@ -0,0 +1,274 @@
+// src/graphics/path_tracer.rs
+//
+// Trazado de caminos en CPU sobre los BVH de las mallas. Trabaja sobre una
+// copia de la escena (`TraceScene`) para poder usarse desde otro hilo sin
+// tocar los objetos del render.
+// Todas las superficies son difusas (el albedo de `Lighting`), iluminadas por
+// un sol direccional y un cielo de color uniforme.
+
+use crate::graphics::lighting::Lighting;
+use crate::graphics::mesh::Mesh;
+use crate::graphics::scene_object::SceneObject;
+use crate::math::{aabb::Aabb, matrix_4_by_4::Matrix4, ray::Ray, vec3::Vec3};
+
+/// Generador pseudoaleatorio pequeño (xorshift64*), suficiente para muestrear
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    /// Número uniforme en [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        let value = self.0.wrapping_mul(0x2545_F491_4F6C_DD1D);
+        (value >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+/// Dirección al azar en el hemisferio de `normal`, con densidad proporcional al coseno
+pub fn cosine_sample(normal: Vec3, rng: &mut Rng) -> Vec3 {
+    let helper = if normal.x.abs() > 0.9 { Vec3::new(0.0, 1.0, 0.0) } else { Vec3::new(1.0, 0.0, 0.0) };
+    let tangent = helper.cross(&normal).normalize();
+    let bitangent = normal.cross(&tangent);
+
+    let u1 = rng.next_f32();
+    let phi = std::f32::consts::TAU * rng.next_f32();
+    let r = u1.sqrt();
+    tangent * (r * phi.cos()) + bitangent * (r * phi.sin()) + normal * (1.0 - u1).max(0.0).sqrt()
+}
+
+/// Corte de un rayo con la escena trazada
+#[derive(Debug, Clone, Copy)]
+pub struct TraceHit {
+    pub object: usize,
+    pub t: f32,
+    pub point: Vec3,
+    /// Normal geométrica unitaria en espacio mundo, del lado de donde vino el rayo
+    pub normal: Vec3,
+}
+
+struct TraceMesh {
+    mesh: Mesh,
+    model: Matrix4,
+    inverse: Matrix4,
+}
+
+/// Copia de la geometría de la escena con sus transformaciones de un instante
+pub struct TraceScene {
+    meshes: Vec<TraceMesh>,
+    pub lighting: Lighting,
+    bounds: Aabb,
+    /// Separación de los rayos secundarios respecto de la superficie
+    bias: f32,
+}
+
+fn mul(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] * b[0], a[1] * b[1], a[2] * b[2]]
+}
+
+fn add_scaled(acc: &mut [f32; 3], value: [f32; 3], factor: f32) {
+    for (a, v) in acc.iter_mut().zip(value) {
+        *a += v * factor;
+    }
+}
+
+impl TraceScene {
+    /// Copia las mallas (con su BVH ya construido) y las matrices actuales de los objetos.
+    /// Los índices de objeto son los mismos que en `objects`.
+    pub fn from_objects(objects: &[SceneObject], global_scale: f32, lighting: Lighting) -> Self {
+        let meshes: Vec<TraceMesh> = objects
+            .iter()
+            .map(|obj| {
+                let model = obj.model_matrix(global_scale);
+                match model.inverse() {
+                    Some(inverse) => {
+                        obj.mesh.bvh();
+                        TraceMesh { mesh: obj.mesh.clone(), model, inverse }
+                    }
+                    // Objeto aplastado: queda vacío para conservar los índices
+                    None => TraceMesh { mesh: Mesh::default(), model, inverse: Matrix4::identity() },
+                }
+            })
+            .collect();
+        let bounds = objects
+            .iter()
+            .fold(Aabb::EMPTY, |acc, obj| acc.union(&obj.world_bounds(global_scale)));
+        let bias = if bounds.is_empty() { 1e-4 } else { (bounds.size().magnitude() * 1e-4).max(1e-5) };
+        Self { meshes, lighting, bounds, bias }
+    }
+
+    pub fn object_count(&self) -> usize {
+        self.meshes.len()
+    }
+
+    pub fn mesh(&self, object: usize) -> &Mesh {
+        &self.meshes[object].mesh
+    }
+
+    pub fn model(&self, object: usize) -> &Matrix4 {
+        &self.meshes[object].model
+    }
+
+    pub fn bounds(&self) -> Aabb {
+        self.bounds
+    }
+
+    /// Normal unitaria en espacio mundo de un triángulo (sin orientar), o None si es degenerado
+    pub fn triangle_normal(&self, object: usize, triangle: usize) -> Option<Vec3> {
+        let entry = &self.meshes[object];
+        let [a, b, c] = entry.mesh.triangle(triangle)?;
+        let (pa, pb, pc) = (entry.mesh.position(a), entry.mesh.position(b), entry.mesh.position(c));
+        let e1 = pb - pa;
+        let e2 = pc - pa;
+        let local = e1.cross(&e2);
+        // Las normales se transforman con la inversa traspuesta
+        let inv = &entry.inverse.m;
+        let world = Vec3::new(
+            inv[0] * local.x + inv[1] * local.y + inv[2] * local.z,
+            inv[4] * local.x + inv[5] * local.y + inv[6] * local.z,
+            inv[8] * local.x + inv[9] * local.y + inv[10] * local.z,
+        );
+        let length = world.magnitude();
+        (length > 1e-12).then(|| world / length)
+    }
+
+    /// Corte más cercano con t <= max_t
+    pub fn intersect(&self, ray: &Ray, max_t: f32) -> Option<TraceHit> {
+        let mut best: Option<(usize, usize, f32)> = None;
+        for (index, entry) in self.meshes.iter().enumerate() {
+            // La dirección se transforma sin normalizar para conservar el mismo t
+            let origin = entry.inverse.transform_point(ray.origin);
+            let [dx, dy, dz, _] = entry
+                .inverse
+                .transform_vec4([ray.direction.x, ray.direction.y, ray.direction.z, 0.0]);
+            let local = Ray::new(origin, Vec3::new(dx, dy, dz));
+            let limit = best.map_or(max_t, |(_, _, t)| t);
+            if let Some((t, triangle)) = entry.mesh.bvh().raycast(&entry.mesh, &local, limit) {
+                best = Some((index, triangle, t));
+            }
+        }
+
+        let (object, triangle, t) = best?;
+        let mut normal = self.triangle_normal(object, triangle)?;
+        if normal.dot(&ray.direction) > 0.0 {
+            normal *= -1.0;
+        }
+        Some(TraceHit { object, t, point: ray.at(t), normal })
+    }
+
+    /// Punto separado de la superficie para lanzar rayos secundarios sin cortarse a sí mismo
+    pub fn offset(&self, point: Vec3, normal: Vec3) -> Vec3 {
+        point + normal * self.bias
+    }
+
+    fn occluded(&self, origin: Vec3, direction: Vec3, max_t: f32) -> bool {
+        self.intersect(&Ray::new(origin, direction), max_t).is_some()
+    }
+
+    /// Fracción del hemisferio que no está tapada dentro de `max_distance`
+    pub fn ambient_occlusion(&self, point: Vec3, normal: Vec3, samples: u32, max_distance: f32, rng: &mut Rng) -> f32 {
+        let origin = self.offset(point, normal);
+        let open = (0..samples)
+            .filter(|_| !self.occluded(origin, cosine_sample(normal, rng), max_distance))
+            .count();
+        open as f32 / samples.max(1) as f32
+    }
+
+    /// Irradiancia directa del sol y de las luces de relleno (con sombra) en un punto
+    pub fn direct(&self, point: Vec3, normal: Vec3) -> [f32; 3] {
+        let sun = (self.lighting.sun_direction, self.lighting.sun_color);
+        let fills = self.lighting.fill_lights.iter().map(|fill| (fill.direction, fill.color));
+        let mut total = [0.0; 3];
+        for (direction, color) in std::iter::once(sun).chain(fills) {
+            let cos = normal.dot(&direction);
+            if color == [0.0; 3] || cos <= 0.0 || self.occluded(self.offset(point, normal), direction, f32::INFINITY) {
+                continue;
+            }
+            add_scaled(&mut total, color, cos);
+        }
+        total
+    }
+
+    /// Radiancia que llega por `ray`, siguiendo hasta `bounces` rebotes difusos
+    /// después del primer corte
+    pub fn radiance(&self, ray: &Ray, bounces: u32, rng: &mut Rng) -> [f32; 3] {
+        let mut color = [0.0; 3];
+        let mut throughput = [1.0; 3];
+        let mut ray = *ray;
+        for bounce in 0..=bounces {
+            let Some(hit) = self.intersect(&ray, f32::INFINITY) else {
+                add_scaled(&mut color, mul(throughput, self.lighting.sky_color), 1.0);
+                break;
+            };
+            throughput = mul(throughput, self.lighting.albedo);
+            add_scaled(&mut color, mul(throughput, self.direct(hit.point, hit.normal)), 1.0);
+            if bounce == bounces {
+                break;
+            }
+            // Con muestreo por coseno el peso del rebote es solo el albedo
+            ray = Ray::new(self.offset(hit.point, hit.normal), cosine_sample(hit.normal, rng));
+        }
+        color
+    }
+
+    /// Irradiancia total (sol + cielo + luz rebotada) en un punto de una superficie
+    pub fn irradiance(&self, point: Vec3, normal: Vec3, samples: u32, bounces: u32, rng: &mut Rng) -> [f32; 3] {
+        let mut total = self.direct(point, normal);
+        let origin = self.offset(point, normal);
+        let weight = 1.0 / samples.max(1) as f32;
+        for _ in 0..samples {
+            let ray = Ray::new(origin, cosine_sample(normal, rng));
+            add_scaled(&mut total, self.radiance(&ray, bounces, rng), weight);
+        }
+        total
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cuadrado de 2x2 en el plano y = height, centrado en el origen
+    fn floor_object(height: f32) -> SceneObject {
+        let mut obj = SceneObject::new(0, 0);
+        obj.mesh = Mesh::new(
+            vec![[-1.0, height, -1.0], [1.0, height, -1.0], [1.0, height, 1.0], [-1.0, height, 1.0]],
+            vec![0, 2, 1, 0, 3, 2],
+        );
+        obj
+    }
+
+    #[test]
+    fn test_cosine_sample_in_hemisphere() {
+        let mut rng = Rng::new(7);
+        let normal = Vec3::new(0.0, 0.0, 1.0);
+        for _ in 0..100 {
+            let dir = cosine_sample(normal, &mut rng);
+            assert!(dir.dot(&normal) >= 0.0);
+            assert!((dir.magnitude() - 1.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_ambient_occlusion_under_roof() {
+        let scene = TraceScene::from_objects(&[floor_object(0.0), floor_object(0.1)], 1.0, Lighting::default());
+        let mut rng = Rng::new(1);
+        let up = Vec3::new(0.0, 1.0, 0.0);
+        let covered = scene.ambient_occlusion(Vec3::ZERO, up, 64, 1.0, &mut rng);
+        let open = scene.ambient_occlusion(Vec3::new(0.0, 0.1, 0.0), up, 64, 1.0, &mut rng);
+        assert!(covered < 0.3);
+        assert_eq!(open, 1.0);
+    }
+
+    #[test]
+    fn test_radiance_miss_is_sky() {
+        let scene = TraceScene::from_objects(&[floor_object(0.0)], 1.0, Lighting::default());
+        let ray = Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(scene.radiance(&ray, 2, &mut Rng::new(3)), Lighting::default().sky_color);
+    }
+}
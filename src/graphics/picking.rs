@@ -0,0 +1,604 @@
+// src/graphics/picking.rs
+//
+// Picking por buffer de IDs: cada objeto se dibuja con su índice en un
+// framebuffer entero fuera de pantalla y luego se lee el píxel bajo el cursor.
+// Es exacto a nivel de píxel aunque las mallas sean muy densas.
+//
+// Un segundo attachment guarda el triángulo (gl_PrimitiveID), lo que permite
+// el picking de sub-objetos: vértice, arista o cara más cercana al cursor.
+//
+// El buffer solo se vuelve a dibujar cuando cambia algo que se vería en él
+// (cámara, tamaño, corte u objetos visibles y sus matrices); con la escena
+// quieta los clicks leen el del último dibujo.
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::ptr;
+use std::rc::Rc;
+
+use crate::graphics::render_graph::{FrameContext, PassStage, RenderPass, ResourceId};
+use crate::graphics::render_settings::{Clipping, RenderSettings};
+use crate::graphics::scene_object::SceneObject;
+use crate::graphics::gpu_resources::ContextGeneration;
+use crate::graphics::gl_validation;
+use crate::graphics::shaders::{build_program, uniform_location, ShaderLibrary};
+use crate::math::{matrix_4_by_4::Matrix4, vec3::Vec3};
+
+pub const PICKING_IDS: ResourceId = "picking_ids";
+
+/// Qué devuelve un click en el viewport
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickMode {
+    Object,
+    SubObject,
+    /// El grupo del objeto (ver `Scene::create_group`)
+    Group,
+}
+
+/// Elemento de la malla seleccionado (índices en `SceneObject::mesh`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubObjectElement {
+    Vertex(u32),
+    Edge(u32, u32),
+    /// Índice del triángulo
+    Face(usize),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SubObjectHit {
+    pub object: usize,
+    pub element: SubObjectElement,
+    /// Punto en espacio mundo: el vértice, el punto más cercano de la arista
+    /// o el punto de la cara bajo el cursor
+    pub point: Vec3,
+}
+
+/// Framebuffer de IDs (R32UI objeto + R32UI triángulo + depth), compartido
+/// entre el pase y el Renderer
+pub struct PickBuffer {
+    fbo: u32,
+    ids: u32,
+    primitives: u32,
+    depth: u32,
+    width: i32,
+    height: i32,
+    /// Cámara y escala del último frame, para proyectar la malla al resolver sub-objetos
+    view_projection: Matrix4,
+    global_scale: f32,
+    generation: ContextGeneration,
+}
+
+impl Default for PickBuffer {
+    fn default() -> Self {
+        Self {
+            fbo: 0,
+            ids: 0,
+            primitives: 0,
+            depth: 0,
+            width: 0,
+            height: 0,
+            view_projection: Matrix4::identity(),
+            global_scale: 1.0,
+            generation: ContextGeneration::current(),
+        }
+    }
+}
+
+fn create_id_texture(width: i32, height: i32) -> u32 {
+    let mut texture = 0;
+    unsafe {
+        gl::GenTextures(1, &mut texture);
+        gl::BindTexture(gl::TEXTURE_2D, texture);
+        gl::TexImage2D(
+            gl::TEXTURE_2D, 0, gl::R32UI as i32, width, height, 0,
+            gl::RED_INTEGER, gl::UNSIGNED_INT, ptr::null(),
+        );
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+    }
+    texture
+}
+
+impl PickBuffer {
+    /// (Re)crea los attachments si cambió el tamaño
+    fn ensure_size(&mut self, width: i32, height: i32) {
+        if width == self.width && height == self.height && self.fbo != 0 {
+            return;
+        }
+        self.release();
+        unsafe {
+            gl::GenFramebuffers(1, &mut self.fbo);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.fbo);
+
+            self.ids = create_id_texture(width, height);
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, self.ids, 0);
+            self.primitives = create_id_texture(width, height);
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT1, gl::TEXTURE_2D, self.primitives, 0);
+            let draw_buffers = [gl::COLOR_ATTACHMENT0, gl::COLOR_ATTACHMENT1];
+            gl::DrawBuffers(2, draw_buffers.as_ptr());
+
+            gl::GenRenderbuffers(1, &mut self.depth);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, self.depth);
+            gl::RenderbufferStorage(gl::RENDERBUFFER, gl::DEPTH_COMPONENT24, width, height);
+            gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, gl::RENDERBUFFER, self.depth);
+
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Lee un rectángulo de un attachment. (x, y) es la esquina inferior izquierda en coordenadas GL.
+    fn read_rect(&self, attachment: u32, x: i32, y: i32, width: i32, height: i32) -> Vec<u32> {
+        let mut values = vec![0u32; (width * height) as usize];
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.fbo);
+            gl::ReadBuffer(attachment);
+            gl::ReadPixels(
+                x, y, width, height,
+                gl::RED_INTEGER, gl::UNSIGNED_INT, values.as_mut_ptr() as *mut _,
+            );
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+        }
+        values
+    }
+
+    /// Índice del objeto en el píxel (x, y), con origen arriba a la izquierda
+    /// como las coordenadas del cursor de la ventana.
+    pub fn read(&self, x: i32, y: i32) -> Option<usize> {
+        if self.fbo == 0 || x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return None;
+        }
+        let id = self.read_rect(gl::COLOR_ATTACHMENT0, x, self.height - 1 - y, 1, 1)[0];
+        if id == 0 {
+            None
+        } else {
+            Some(id as usize - 1)
+        }
+    }
+
+    /// Índice del punto de mundo que cae más cerca del píxel (x, y) en el
+    /// último frame, si está a menos de `radius` píxeles (p. ej. articulaciones)
+    pub fn nearest_point(&self, points: &[Vec3], x: i32, y: i32, radius: f32) -> Option<usize> {
+        if self.fbo == 0 {
+            return None;
+        }
+        let projector = ScreenProjector {
+            view_projection: self.view_projection,
+            width: self.width as f32,
+            height: self.height as f32,
+        };
+        let cursor = [x as f32 + 0.5, y as f32 + 0.5];
+        points
+            .iter()
+            .enumerate()
+            .filter_map(|(index, point)| Some((index, distance_2d(projector.project(*point)?.0, cursor))))
+            .filter(|(_, distance)| *distance <= radius)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(index, _)| index)
+    }
+
+    /// Vértice, arista o cara más cercana al cursor. Los vértices y aristas se
+    /// enganchan si están a menos de `snap_radius` píxeles; si no, se devuelve
+    /// la cara bajo el cursor.
+    pub fn read_sub_object(
+        &self,
+        objects: &[SceneObject],
+        x: i32,
+        y: i32,
+        snap_radius: f32,
+    ) -> Option<SubObjectHit> {
+        if self.fbo == 0 || x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return None;
+        }
+
+        // Ventana de píxeles alrededor del cursor (en coordenadas GL)
+        let radius = snap_radius.ceil().max(0.0) as i32;
+        let gl_y = self.height - 1 - y;
+        let x0 = (x - radius).max(0);
+        let y0 = (gl_y - radius).max(0);
+        let x1 = (x + radius).min(self.width - 1);
+        let y1 = (gl_y + radius).min(self.height - 1);
+        let (w, h) = (x1 - x0 + 1, y1 - y0 + 1);
+
+        let ids = self.read_rect(gl::COLOR_ATTACHMENT0, x0, y0, w, h);
+        let primitives = self.read_rect(gl::COLOR_ATTACHMENT1, x0, y0, w, h);
+
+        // Ordenados: ante empates de distancia siempre gana el mismo candidato
+        let mut candidates: BTreeSet<(usize, usize)> = BTreeSet::new();
+        for (id, primitive) in ids.iter().zip(&primitives) {
+            if *id != 0 {
+                candidates.insert((*id as usize - 1, *primitive as usize));
+            }
+        }
+        let center = ((gl_y - y0) * w + (x - x0)) as usize;
+        let under_cursor = (ids[center] != 0).then(|| (ids[center] as usize - 1, primitives[center] as usize));
+
+        let projector = ScreenProjector {
+            view_projection: self.view_projection,
+            width: self.width as f32,
+            height: self.height as f32,
+        };
+        resolve_sub_object(
+            objects,
+            &candidates,
+            under_cursor,
+            &projector,
+            self.global_scale,
+            [x as f32 + 0.5, y as f32 + 0.5],
+            snap_radius,
+        )
+    }
+
+    fn release(&mut self) {
+        unsafe {
+            if self.fbo != 0 && self.generation.is_current() {
+                gl::DeleteFramebuffers(1, &self.fbo);
+                gl::DeleteTextures(1, &self.ids);
+                gl::DeleteTextures(1, &self.primitives);
+                gl::DeleteRenderbuffers(1, &self.depth);
+            }
+        }
+        self.fbo = 0;
+    }
+}
+
+impl Drop for PickBuffer {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Proyecta puntos de mundo a píxeles de la ventana (origen arriba a la izquierda)
+struct ScreenProjector {
+    view_projection: Matrix4,
+    width: f32,
+    height: f32,
+}
+
+impl ScreenProjector {
+    /// Devuelve (píxel, w de clip) o None si el punto queda detrás de la cámara
+    fn project(&self, world: Vec3) -> Option<([f32; 2], f32)> {
+        let [cx, cy, _, cw] = self.view_projection.transform_vec4([world.x, world.y, world.z, 1.0]);
+        if cw <= 1e-6 {
+            return None;
+        }
+        let screen = [
+            (cx / cw + 1.0) * 0.5 * self.width,
+            (1.0 - cy / cw) * 0.5 * self.height,
+        ];
+        Some((screen, cw))
+    }
+}
+
+fn distance_2d(a: [f32; 2], b: [f32; 2]) -> f32 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()
+}
+
+/// Parámetro t en [0, 1] del punto del segmento ab más cercano a p
+fn closest_on_segment(p: [f32; 2], a: [f32; 2], b: [f32; 2]) -> f32 {
+    let ab = [b[0] - a[0], b[1] - a[1]];
+    let len2 = ab[0] * ab[0] + ab[1] * ab[1];
+    if len2 <= f32::EPSILON {
+        return 0.0;
+    }
+    (((p[0] - a[0]) * ab[0] + (p[1] - a[1]) * ab[1]) / len2).clamp(0.0, 1.0)
+}
+
+/// Coordenadas baricéntricas de p en el triángulo (a, b, c) en 2D
+fn barycentric(p: [f32; 2], a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> Option<[f32; 3]> {
+    let det = (b[1] - c[1]) * (a[0] - c[0]) + (c[0] - b[0]) * (a[1] - c[1]);
+    if det.abs() <= f32::EPSILON {
+        return None;
+    }
+    let l0 = ((b[1] - c[1]) * (p[0] - c[0]) + (c[0] - b[0]) * (p[1] - c[1])) / det;
+    let l1 = ((c[1] - a[1]) * (p[0] - c[0]) + (a[0] - c[0]) * (p[1] - c[1])) / det;
+    Some([l0, l1, 1.0 - l0 - l1])
+}
+
+/// Corrige pesos interpolados en pantalla para que sean lineales en 3D
+fn perspective_correct(weights: &[f32], clip_w: &[f32]) -> Vec<f32> {
+    let scaled: Vec<f32> = weights.iter().zip(clip_w).map(|(l, w)| l / w).collect();
+    let sum: f32 = scaled.iter().sum();
+    scaled.iter().map(|s| s / sum).collect()
+}
+
+fn resolve_sub_object(
+    objects: &[SceneObject],
+    candidates: &BTreeSet<(usize, usize)>,
+    under_cursor: Option<(usize, usize)>,
+    projector: &ScreenProjector,
+    global_scale: f32,
+    cursor: [f32; 2],
+    snap_radius: f32,
+) -> Option<SubObjectHit> {
+    let mut best_vertex: Option<(f32, SubObjectHit)> = None;
+    let mut best_edge: Option<(f32, SubObjectHit)> = None;
+
+    for &(object, triangle) in candidates {
+        let Some(obj) = objects.get(object) else { continue };
+        let Some(tri) = obj.mesh.triangle(triangle) else { continue };
+        let model = obj.model_matrix(global_scale);
+        let world = tri.map(|v| model.transform_point(obj.mesh.position(v)));
+        let projected = [
+            projector.project(world[0]),
+            projector.project(world[1]),
+            projector.project(world[2]),
+        ];
+
+        for i in 0..3 {
+            let Some((screen, _)) = projected[i] else { continue };
+            let distance = distance_2d(cursor, screen);
+            if distance <= snap_radius && best_vertex.as_ref().is_none_or(|(d, _)| distance < *d) {
+                let hit = SubObjectHit { object, element: SubObjectElement::Vertex(tri[i]), point: world[i] };
+                best_vertex = Some((distance, hit));
+            }
+
+            let j = (i + 1) % 3;
+            let (Some((sa, wa)), Some((sb, wb))) = (projected[i], projected[j]) else { continue };
+            let t = closest_on_segment(cursor, sa, sb);
+            let on_screen = [sa[0] + (sb[0] - sa[0]) * t, sa[1] + (sb[1] - sa[1]) * t];
+            let distance = distance_2d(cursor, on_screen);
+            if distance <= snap_radius && best_edge.as_ref().is_none_or(|(d, _)| distance < *d) {
+                let weights = perspective_correct(&[1.0 - t, t], &[wa, wb]);
+                let point = world[i] * weights[0] + world[j] * weights[1];
+                let edge = (tri[i].min(tri[j]), tri[i].max(tri[j]));
+                let hit = SubObjectHit { object, element: SubObjectElement::Edge(edge.0, edge.1), point };
+                best_edge = Some((distance, hit));
+            }
+        }
+    }
+
+    if let Some((_, hit)) = best_vertex.or(best_edge) {
+        return Some(hit);
+    }
+
+    // Sin vértices ni aristas cerca: la cara bajo el cursor
+    let (object, triangle) = under_cursor?;
+    let obj = objects.get(object)?;
+    let tri = obj.mesh.triangle(triangle)?;
+    let model = obj.model_matrix(global_scale);
+    let world = tri.map(|v| model.transform_point(obj.mesh.position(v)));
+    let (s0, w0) = projector.project(world[0])?;
+    let (s1, w1) = projector.project(world[1])?;
+    let (s2, w2) = projector.project(world[2])?;
+    let point = match barycentric(cursor, s0, s1, s2) {
+        Some(l) => {
+            let weights = perspective_correct(&l, &[w0, w1, w2]);
+            world[0] * weights[0] + world[1] * weights[1] + world[2] * weights[2]
+        }
+        None => world[0],
+    };
+    Some(SubObjectHit { object, element: SubObjectElement::Face(triangle), point })
+}
+
+/// Lo que decide el contenido del buffer de IDs. Si es igual al del último
+/// dibujo, el buffer sigue valiendo.
+#[derive(Debug, Clone, PartialEq)]
+struct PickKey {
+    view: Matrix4,
+    projection: Matrix4,
+    viewport: (i32, i32),
+    global_scale: f32,
+    clipping: Clipping,
+    /// (índice, VAO, índices, versión de la matriz, ignora el corte) en orden de dibujo
+    objects: Vec<(usize, u32, i32, u64, bool)>,
+}
+
+impl PickKey {
+    fn new(frame: &FrameContext) -> Self {
+        let objects = [false, true]
+            .into_iter()
+            .flat_map(|overlay| frame.draw_order(overlay))
+            .map(|index| {
+                let obj = &frame.objects[index];
+                // Calcula la matriz si hace falta, así la versión está al día
+                obj.model_matrix(frame.global_scale);
+                (index, obj.vao, obj.index_count, obj.transform_version(), obj.ignore_clipping)
+            })
+            .collect();
+        Self {
+            view: frame.view,
+            projection: frame.projection,
+            viewport: frame.viewport,
+            global_scale: frame.global_scale,
+            clipping: frame.settings.clipping,
+            objects,
+        }
+    }
+}
+
+/// Pase que dibuja los IDs de los objetos en el PickBuffer
+pub struct PickingPass {
+    program: u32,
+    buffer: Rc<RefCell<PickBuffer>>,
+    /// Estado del último dibujo del buffer
+    drawn: Option<PickKey>,
+    generation: ContextGeneration,
+}
+
+impl PickingPass {
+    pub fn new(buffer: Rc<RefCell<PickBuffer>>) -> Result<Self, String> {
+        let program = build_program(
+            include_str!("shaders/picking.vert"),
+            include_str!("shaders/picking.frag"),
+        )?;
+        Ok(Self { program, buffer, drawn: None, generation: ContextGeneration::current() })
+    }
+}
+
+impl RenderPass for PickingPass {
+    fn name(&self) -> &str {
+        "picking"
+    }
+
+    fn stage(&self) -> PassStage {
+        PassStage::Debug
+    }
+
+    fn outputs(&self) -> &[ResourceId] {
+        &[PICKING_IDS]
+    }
+
+    fn settings(&self, global: &RenderSettings) -> RenderSettings {
+        // Siempre caras rellenas y con profundidad, aunque la vista esté en wireframe
+        RenderSettings {
+            wireframe: false,
+            depth_test: true,
+            ..*global
+        }
+    }
+
+    fn rebuild_gpu(&mut self, _shaders: &ShaderLibrary) -> Result<(), String> {
+        // El framebuffer de IDs se vuelve a crear en el próximo frame
+        *self.buffer.borrow_mut() = PickBuffer::default();
+        *self = Self::new(self.buffer.clone())?;
+        Ok(())
+    }
+
+    fn execute(&mut self, frame: &FrameContext) {
+        let (width, height) = frame.viewport;
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        let key = PickKey::new(frame);
+        let mut buffer = self.buffer.borrow_mut();
+        if buffer.fbo != 0 && self.drawn.as_ref() == Some(&key) {
+            return;
+        }
+        self.drawn = Some(key);
+        buffer.ensure_size(width, height);
+        buffer.view_projection = frame.projection.multiply(&frame.view);
+        buffer.global_scale = frame.global_scale;
+
+        unsafe {
+            let mut previous_fbo = 0;
+            gl::GetIntegerv(gl::DRAW_FRAMEBUFFER_BINDING, &mut previous_fbo);
+
+            gl::BindFramebuffer(gl::FRAMEBUFFER, buffer.fbo);
+            let clear_id: [u32; 4] = [0; 4];
+            gl::ClearBufferuiv(gl::COLOR, 0, clear_id.as_ptr());
+            gl::ClearBufferuiv(gl::COLOR, 1, clear_id.as_ptr());
+            gl::Clear(gl::DEPTH_BUFFER_BIT);
+
+            gl::UseProgram(self.program);
+            gl::UniformMatrix4fv(uniform_location(self.program, "view"), 1, gl::FALSE, frame.view.as_ptr());
+            gl::UniformMatrix4fv(uniform_location(self.program, "projection"), 1, gl::FALSE, frame.projection.as_ptr());
+            let model_loc = uniform_location(self.program, "model");
+            let id_loc = uniform_location(self.program, "objectId");
+            let ignore_clipping_loc = uniform_location(self.program, "ignoreClipping");
+            frame.settings.clipping.bind(self.program);
+
+            // Igual que en pantalla: la capa superpuesta tapa a la escena
+            for overlay in [false, true] {
+                let order = frame.draw_order(overlay);
+                if overlay && !order.is_empty() {
+                    gl::Clear(gl::DEPTH_BUFFER_BIT);
+                }
+                for index in order {
+                    let obj = &frame.objects[index];
+                    let model = obj.model_matrix(frame.global_scale);
+                    gl::UniformMatrix4fv(model_loc, 1, gl::FALSE, model.as_ptr());
+                    gl::Uniform1ui(id_loc, index as u32 + 1);
+                    gl::Uniform1i(ignore_clipping_loc, obj.ignore_clipping as i32);
+                    gl::BindVertexArray(obj.vao);
+                    gl_validation::draw_elements(gl::TRIANGLES, obj.index_count, 0);
+                }
+            }
+
+            gl::BindFramebuffer(gl::FRAMEBUFFER, previous_fbo as u32);
+        }
+    }
+}
+
+impl Drop for PickingPass {
+    fn drop(&mut self) {
+        if !self.generation.is_current() {
+            return;
+        }
+        unsafe {
+            gl::DeleteProgram(self.program);
+        }
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::mesh::Mesh;
+
+    fn triangle_object() -> SceneObject {
+        let mut obj = SceneObject::new(0, 3);
+        obj.mesh = Mesh::new(vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]], vec![0, 1, 2]);
+        obj
+    }
+
+    /// Proyección ortográfica: el cuadrado [-1, 1] ocupa una ventana de 200x200
+    fn projector() -> ScreenProjector {
+        ScreenProjector { view_projection: Matrix4::identity(), width: 200.0, height: 200.0 }
+    }
+
+    #[test]
+    fn test_snaps_to_vertex() {
+        let objects = [triangle_object()];
+        let candidates = BTreeSet::from([(0, 0)]);
+        // vértice 1 = (1, 0) -> píxel (200, 100)
+        let hit = resolve_sub_object(&objects, &candidates, None, &projector(), 1.0, [196.0, 101.0], 8.0).unwrap();
+        assert_eq!(hit.element, SubObjectElement::Vertex(1));
+    }
+
+    #[test]
+    fn test_snaps_to_edge() {
+        let objects = [triangle_object()];
+        let candidates = BTreeSet::from([(0, 0)]);
+        // punto medio de la arista 0-1 = (0.5, 0) -> píxel (150, 100)
+        let hit = resolve_sub_object(&objects, &candidates, None, &projector(), 1.0, [150.0, 103.0], 8.0).unwrap();
+        assert_eq!(hit.element, SubObjectElement::Edge(0, 1));
+        assert!((hit.point.x - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_face_under_cursor() {
+        let objects = [triangle_object()];
+        let candidates = BTreeSet::from([(0, 0)]);
+        // (0.25, 0.25) -> píxel (125, 75), lejos de vértices y aristas
+        let hit = resolve_sub_object(&objects, &candidates, Some((0, 0)), &projector(), 1.0, [125.0, 75.0], 4.0).unwrap();
+        assert_eq!(hit.element, SubObjectElement::Face(0));
+        assert!((hit.point.x - 0.25).abs() < 1e-4 && (hit.point.y - 0.25).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_redraw_only_when_frame_changes() {
+        use crate::graphics::lighting::Lighting;
+
+        let mut objects = vec![triangle_object(), triangle_object()];
+        let (settings, lighting) = (RenderSettings::default(), Lighting::default());
+        let key = |objects: &[SceneObject], view: Matrix4| {
+            PickKey::new(&FrameContext {
+                objects,
+                view,
+                projection: Matrix4::identity(),
+                global_scale: 1.0,
+                viewport: (64, 64),
+                program: 0,
+                settings: &settings,
+                environment: None,
+                lighting: &lighting,
+                visible: None,
+                targets: None,
+            })
+        };
+        let drawn = key(&objects, Matrix4::identity());
+        assert_eq!(key(&objects, Matrix4::identity()), drawn);
+        // Mover la cámara, mover un objeto u ocultarlo obliga a redibujar
+        assert_ne!(key(&objects, Matrix4::translate(0.0, 0.0, -1.0)), drawn);
+        objects[1].set_position(Vec3::new(1.0, 0.0, 0.0));
+        let moved = key(&objects, Matrix4::identity());
+        assert_ne!(moved, drawn);
+        objects[0].hidden = true;
+        assert_ne!(key(&objects, Matrix4::identity()), moved);
+    }
+}
@@ -0,0 +1,134 @@
+// src/graphics/printability.rs
+//
+// Análisis para impresión 3D: el ángulo de voladizo de cada cara respecto de
+// la dirección de construcción. Una pared vertical tiene 0°, un techo plano
+// 90°; las caras con más voladizo que el umbral necesitan soporte, salvo las
+// que apoyan en la cama (la altura mínima de la pieza). Se muestra con el mismo
+// mapa de calor por vértice que la comparación de mallas.
+
+use crate::graphics::compare::heatmap_color;
+use crate::graphics::mesh::Mesh;
+use crate::graphics::mesh_ops::transformed;
+use crate::graphics::scene_object::SceneObject;
+use crate::math::vec3::Vec3;
+
+#[derive(Debug, Clone, Copy)]
+pub struct OverhangSettings {
+    /// Dirección en la que crece la pieza (hacia arriba de la cama), en espacio mundo
+    pub build_direction: Vec3,
+    /// Voladizo máximo imprimible sin soporte, en grados desde la vertical
+    pub threshold_degrees: f32,
+    /// Distancia a la cama dentro de la cual una cara se considera apoyada
+    /// (fracción de la altura de la pieza)
+    pub bed_tolerance: f32,
+}
+
+impl Default for OverhangSettings {
+    fn default() -> Self {
+        Self {
+            build_direction: Vec3::UNIT_Y,
+            threshold_degrees: 45.0,
+            bed_tolerance: 1e-3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OverhangReport {
+    /// Voladizo por triángulo en grados (0 para las caras que miran hacia arriba)
+    pub angles: Vec<f32>,
+    /// Triángulos que necesitan soporte
+    pub flagged: Vec<bool>,
+    /// Área total de las caras marcadas
+    pub support_area: f32,
+    pub total_area: f32,
+}
+
+/// Analiza una malla ya en espacio mundo
+pub fn analyze_mesh(mesh: &Mesh, settings: &OverhangSettings) -> OverhangReport {
+    let up = settings.build_direction.normalize();
+    // Altura de la cama: el punto más bajo a lo largo de la dirección de construcción
+    let heights: Vec<f32> = (0..mesh.positions.len() as u32).map(|v| mesh.position(v).dot(&up)).collect();
+    let bed = heights.iter().copied().fold(f32::INFINITY, f32::min);
+    let top = heights.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let bed_tolerance = (top - bed).max(0.0) * settings.bed_tolerance;
+
+    let mut report = OverhangReport::default();
+    for triangle in 0..mesh.triangle_count() {
+        let Some(corners) = mesh.triangle(triangle) else { continue };
+        let [a, b, c] = corners.map(|v| mesh.position(v));
+        let (e1, e2) = (b - a, c - a);
+        let normal = e1.cross(&e2);
+        let area = normal.magnitude() * 0.5;
+        if area <= 0.0 {
+            report.angles.push(0.0);
+            report.flagged.push(false);
+            continue;
+        }
+
+        let downward = -normal.dot(&up) / (area * 2.0);
+        let angle = downward.clamp(0.0, 1.0).asin().to_degrees();
+        let on_bed = corners.iter().all(|&v| heights[v as usize] - bed <= bed_tolerance);
+        let flagged = angle > settings.threshold_degrees && !on_bed;
+
+        report.angles.push(angle);
+        report.flagged.push(flagged);
+        report.total_area += area;
+        if flagged {
+            report.support_area += area;
+        }
+    }
+    report
+}
+
+/// Analiza un objeto con su transformación actual
+pub fn analyze(obj: &SceneObject, global_scale: f32, settings: &OverhangSettings) -> OverhangReport {
+    analyze_mesh(&transformed(&obj.mesh, &obj.model_matrix(global_scale)), settings)
+}
+
+/// Colores por vértice: verde sin voladizo, rojo en el umbral o más. Cada
+/// vértice toma el peor voladizo de sus caras; las apoyadas en la cama cuentan como 0°.
+pub fn overhang_colors(mesh: &Mesh, report: &OverhangReport, settings: &OverhangSettings) -> Vec<[f32; 3]> {
+    let mut worst = vec![0.0_f32; mesh.positions.len()];
+    for (triangle, (&angle, &flagged)) in report.angles.iter().zip(&report.flagged).enumerate() {
+        let Some(corners) = mesh.triangle(triangle) else { continue };
+        // Una cara sobre la cama no necesita soporte aunque mire hacia abajo
+        let angle = if flagged || angle <= settings.threshold_degrees { angle } else { 0.0 };
+        for v in corners {
+            worst[v as usize] = worst[v as usize].max(angle);
+        }
+    }
+    worst
+        .into_iter()
+        .map(|angle| heatmap_color(Some(angle), settings.threshold_degrees))
+        .collect()
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overhang_flags_ceiling_not_bed() {
+        // Puente: base apoyada en y = 0 mirando hacia abajo, techo del arco en y = 1
+        // mirando hacia abajo, y una rampa a 30° del vertical
+        let mesh = Mesh::new(
+            vec![
+                [0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0],
+                [0.0, 1.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 1.0],
+                [0.0, 2.0, 0.0], [0.0, 2.0, 1.0], [0.5, 2.0 + 3.0_f32.sqrt() / 2.0, 0.0],
+            ],
+            vec![0, 1, 2, 3, 4, 5, 6, 8, 7],
+        );
+        let report = analyze_mesh(&mesh, &OverhangSettings::default());
+        assert_eq!(report.flagged, vec![false, true, false]);
+        assert!((report.angles[1] - 90.0).abs() < 1e-3);
+        assert!((report.angles[2] - 30.0).abs() < 1e-3);
+        assert!((report.support_area - 0.5).abs() < 1e-5);
+
+        let colors = overhang_colors(&mesh, &report, &OverhangSettings::default());
+        assert_eq!(colors[0], [0.0, 1.0, 0.0]);
+        assert_eq!(colors[3], [1.0, 0.0, 0.0]);
+    }
+}
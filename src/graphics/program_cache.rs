@@ -0,0 +1,168 @@
+// src/graphics/program_cache.rs
+//
+// Caché en disco de programas ya enlazados (glGetProgramBinary /
+// glProgramBinary). La clave es un hash del código preprocesado de ambos
+// shaders y del driver (vendor + renderer + versión): si cambia cualquiera de
+// los dos se vuelve a compilar. Si el driver rechaza un binario (otra versión,
+// archivo corrupto) se borra y se compila de nuevo sin avisar.
+
+use std::ffi::{c_void, CStr};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+pub struct ProgramCache {
+    dir: PathBuf,
+    /// Identifica al driver que generó los binarios
+    driver: String,
+}
+
+impl ProgramCache {
+    /// Caché en `dir` para el driver del contexto GL actual
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into(), driver: driver_string() }
+    }
+
+    /// Carpeta por defecto, dentro del directorio temporal del sistema
+    pub fn default_dir() -> PathBuf {
+        std::env::temp_dir().join("rust_engine").join("shaders")
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Clave de un programa a partir del código ya preprocesado
+    pub fn key(&self, vert_code: &str, frag_code: &str) -> String {
+        let hash = [self.driver.as_str(), vert_code, frag_code]
+            .iter()
+            .fold(FNV_OFFSET, |hash, part| fnv1a(fnv1a(hash, part.as_bytes()), &[0]));
+        format!("{:016x}", hash)
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.bin", key))
+    }
+
+    /// Programa guardado con esa clave, o None si no hay o el driver no lo acepta
+    pub fn load(&self, key: &str) -> Option<u32> {
+        if !binaries_supported() {
+            return None;
+        }
+        let path = self.path(key);
+        let bytes = fs::read(&path).ok()?;
+        let Some((format, binary)) = decode_entry(&bytes) else {
+            let _ = fs::remove_file(&path);
+            return None;
+        };
+        unsafe {
+            let program = gl::CreateProgram();
+            gl::ProgramBinary(program, format, binary.as_ptr() as *const c_void, binary.len() as i32);
+            let mut success = gl::FALSE as i32;
+            gl::GetProgramiv(program, gl::LINK_STATUS, &mut success);
+            if success == gl::TRUE as i32 {
+                return Some(program);
+            }
+            gl::DeleteProgram(program);
+        }
+        let _ = fs::remove_file(&path);
+        None
+    }
+
+    /// Guarda el binario de un programa enlazado (los errores se ignoran: en
+    /// el peor caso se vuelve a compilar la próxima vez)
+    pub fn store(&self, key: &str, program: u32) {
+        if !binaries_supported() {
+            return;
+        }
+        let mut length = 0;
+        unsafe { gl::GetProgramiv(program, gl::PROGRAM_BINARY_LENGTH, &mut length) };
+        if length <= 0 {
+            return;
+        }
+        let mut binary = vec![0u8; length as usize];
+        let (mut written, mut format) = (0, 0);
+        unsafe {
+            gl::GetProgramBinary(program, length, &mut written, &mut format, binary.as_mut_ptr() as *mut c_void);
+        }
+        binary.truncate(written.max(0) as usize);
+        if binary.is_empty() {
+            return;
+        }
+        if fs::create_dir_all(&self.dir).is_ok() {
+            let _ = fs::write(self.path(key), encode_entry(format, &binary));
+        }
+    }
+}
+
+/// El driver sabe devolver y cargar binarios (GL 4.1 o ARB_get_program_binary,
+/// ver `GlCapabilities::program_binary`) y tiene al menos un formato
+pub fn binaries_supported() -> bool {
+    if !gl::GetProgramBinary::is_loaded() || !gl::ProgramBinary::is_loaded() {
+        return false;
+    }
+    let mut formats = 0;
+    unsafe { gl::GetIntegerv(gl::NUM_PROGRAM_BINARY_FORMATS, &mut formats) };
+    formats > 0
+}
+
+fn driver_string() -> String {
+    [gl::VENDOR, gl::RENDERER, gl::VERSION]
+        .iter()
+        .map(|&name| unsafe {
+            let text = gl::GetString(name);
+            if text.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(text as *const _).to_string_lossy().into_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// Archivo: formato del binario (u32 little endian) y después el binario
+fn encode_entry(format: u32, binary: &[u8]) -> Vec<u8> {
+    let mut bytes = format.to_le_bytes().to_vec();
+    bytes.extend_from_slice(binary);
+    bytes
+}
+
+fn decode_entry(bytes: &[u8]) -> Option<(u32, &[u8])> {
+    if bytes.len() <= 4 {
+        return None;
+    }
+    let (format, binary) = bytes.split_at(4);
+    Some((u32::from_le_bytes(format.try_into().ok()?), binary))
+}
+
+/// FNV-1a de 64 bits: estable entre compilaciones, a diferencia de DefaultHasher
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &b| (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_and_entries() {
+        let cache = ProgramCache { dir: PathBuf::from("cache"), driver: "Mesa | llvmpipe | 4.5".to_string() };
+        let other = ProgramCache { driver: "NVIDIA | RTX | 4.6".to_string(), ..cache.clone() };
+        let key = cache.key("void main() {}", "void main() {}");
+        assert_eq!(key.len(), 16);
+        assert_eq!(key, cache.key("void main() {}", "void main() {}"));
+        assert_ne!(key, other.key("void main() {}", "void main() {}"));
+        assert_ne!(key, cache.key("void main() {}", "void main() { }"));
+        // Mover texto de un shader al otro no da la misma clave
+        assert_ne!(cache.key("ab", "c"), cache.key("a", "bc"));
+        assert_eq!(cache.path(&key), PathBuf::from("cache").join(format!("{}.bin", key)));
+
+        let entry = encode_entry(0x8741, &[1, 2, 3]);
+        assert_eq!(decode_entry(&entry), Some((0x8741, &[1u8, 2, 3][..])));
+        assert_eq!(decode_entry(&entry[..4]), None);
+    }
+}
@@ -1,15 +1,116 @@
 // src/graphics/render.rs
 
-use crate::graphics::shaders::{compile_shader, link_program};
+use crate::graphics::shaders::{build_program, uniform_location, ShaderLibrary};
+use crate::graphics::shader_variants::ShaderVariants;
+use crate::graphics::program_cache::ProgramCache;
 use crate::graphics::window::Window;
+use crate::graphics::gpu_resources::ContextGeneration;
+use crate::graphics::gl_validation;
 use crate::graphics::scene_object::SceneObject;
 use crate::graphics::camara::Camera;
-use crate::math::matrix_4_by_4::Matrix4;
+use crate::graphics::render_graph::{FrameContext, RenderGraph};
+use crate::graphics::passes::{OpaquePass, SkyboxPass};
+use crate::graphics::environment::Environment;
+use crate::graphics::lighting::Lighting;
+use crate::graphics::material::MaterialLibrary;
+use crate::graphics::picking::{PickBuffer, PickingPass, SubObjectHit};
+use crate::graphics::nav_cube::{NavCube, NavCubePass, NavRegion};
+use crate::graphics::uv_debug::{UvLayout, UvLayoutPass};
+use crate::graphics::edges::HiddenLinePass;
+use crate::graphics::lines::{LineOverlay, LinePass};
+use crate::graphics::text::TextOverlay;
+#[cfg(feature = "ui")]
+use crate::graphics::text::TextPass;
+use crate::graphics::scale_bar::{self, LengthUnit, ScaleBar, SCALE_BAR_LAYER};
+use crate::graphics::render_plugin::{plugin_pass_names, plugin_passes, RenderPlugin};
+use crate::graphics::texture::TextureCache;
+use crate::graphics::uniforms::UniformValue;
+use crate::graphics::capture::{is_float_format, save_data_image, save_image, save_image_rgba, AuxBuffer, OffscreenTarget};
+use crate::graphics::render_settings::{Clipping, RenderSettings, SettingChange, SettingsListener, ShadowQuality};
+use crate::math::{matrix_4_by_4::Matrix4, vec3::Vec3};
 
-use std::{fs, ptr, str};
+use std::cell::{Ref, RefCell, RefMut};
+use std::rc::Rc;
+use std::{fs, str};
+
+/// Campo de visión vertical de la cámara (grados)
+pub const FOV_Y_DEGREES: f32 = 45.0;
+
+/// Pases que la captura activa o desactiva y después deja como estaban
+const CAPTURE_TOGGLED_PASSES: [&str; 5] = ["picking", "nav_cube", "uv_layout", "text", "skybox"];
+
+/// Valores de un buffer auxiliar y su tamaño (ancho, alto)
+type AuxPixels = (Vec<[f32; 3]>, (i32, i32));
+
+/// Lo que se dibujó en el último frame de la ventana, para exportar buffers
+/// auxiliares de esa misma vista
+struct FrameSnapshot {
+    view: Matrix4,
+    projection: Matrix4,
+    viewport: (i32, i32),
+    /// (vao, cantidad de índices, matriz de modelo) por objeto
+    draws: Vec<(u32, i32, Matrix4)>,
+}
+
+impl FrameSnapshot {
+    fn new(objects: &[SceneObject], view: Matrix4, projection: Matrix4, viewport: (i32, i32), global_scale: f32) -> Self {
+        Self {
+            view,
+            projection,
+            viewport,
+            draws: objects
+                .iter()
+                .map(|obj| (obj.vao, obj.index_count, obj.model_matrix(global_scale)))
+                .collect(),
+        }
+    }
+}
+
+/// Proyección de la cámara para un framebuffer de `size` píxeles
+fn camera_projection(size: (i32, i32)) -> Matrix4 {
+    // Con la ventana minimizada el tamaño llega en 0 y el aspect sería 0 o NaN
+    let aspect = size.0.max(1) as f32 / size.1.max(1) as f32;
+    Matrix4::perspective(FOV_Y_DEGREES.to_radians(), aspect, 0.01, 1000.0)
+}
 
 pub struct Renderer {
+    /// Variante base del shader principal (las demás las compila OpaquePass)
     pub program: u32,
+    /// Código del shader principal, para recompilarlo si se pierde el contexto
+    main_sources: (String, String),
+    /// Includes y defines para compilar programas (los de los plugins también)
+    pub shaders: ShaderLibrary,
+    /// Pases que se ejecutan cada frame, en orden
+    pub graph: RenderGraph,
+    settings: RenderSettings,
+    settings_listeners: Vec<SettingsListener>,
+    /// Texturas compartidas por ruta (carga en segundo plano)
+    pub textures: TextureCache,
+    /// Entorno HDR para el cielo y los materiales reflectantes
+    pub environment: Option<Environment>,
+    /// Archivo del que se cargó `environment`
+    environment_path: Option<String>,
+    /// Sol y cielo con los que se ilumina la escena
+    pub lighting: Lighting,
+    /// Materiales con nombre que usan los objetos (ver `set_material_library`)
+    materials: Rc<RefCell<MaterialLibrary>>,
+    /// Buffer de IDs que escribe el pase de picking
+    pick_buffer: Rc<RefCell<PickBuffer>>,
+    /// Estado del cubo de navegación de la esquina
+    nav_cube: Rc<RefCell<NavCube>>,
+    /// Vista 2D de las UVs de un objeto
+    uv_layout: Rc<RefCell<UvLayout>>,
+    /// Líneas en espacio mundo que se dibujan encima de la escena
+    lines: Rc<RefCell<LineOverlay>>,
+    /// Paneles de texto en pantalla (ayuda, avisos)
+    text: Rc<RefCell<TextOverlay>>,
+    /// Shader de profundidad / normales / ids para `capture_aux`
+    aux_program: u32,
+    last_frame: Option<FrameSnapshot>,
+    /// Resultado del culling para la vista de la ventana (ver `set_visible_objects`)
+    visible: Option<Vec<bool>>,
+    /// Barra de escala en pantalla y en capturas, con las unidades de la escena
+    scale_bar: Option<LengthUnit>,
     // Podrías guardar uniform locations, etc.
 }
 
@@ -21,73 +122,605 @@ impl Renderer {
         let frag_source = fs::read_to_string(frag_path)
             .map_err(|e| format!("No se pudo leer {}: {}", frag_path, e))?;
 
-        // 2) Compilar
-        let vs = compile_shader(&vert_source, gl::VERTEX_SHADER)?;
-        let fs = compile_shader(&frag_source, gl::FRAGMENT_SHADER)?;
-        // 3) Link
-        let program = link_program(vs, fs)?;
+        // 2) Compilar y enlazar (con los includes y defines de la librería)
+        //    La variante base se compila ya; el resto cuando un objeto la pida
+        let mut shaders = ShaderLibrary::new();
+        shaders.set_program_cache(Some(ProgramCache::new(ProgramCache::default_dir())));
+        let variants = ShaderVariants::new(shaders.clone(), &vert_source, &frag_source)?;
+        let program = variants.base();
+
+        // 4) Grafo de pases por defecto
+        let mut graph = RenderGraph::new();
+        let materials = Rc::new(RefCell::new(MaterialLibrary::new()));
+        let opaque = OpaquePass::new(variants, materials.clone());
+        let overlay = opaque.overlay_pass();
+        graph.add_pass(Box::new(opaque))?;
+        graph.add_pass(Box::new(HiddenLinePass::new()?))?;
+        graph.add_pass(Box::new(SkyboxPass::new()?))?;
+        graph.add_pass(Box::new(overlay))?;
+        let pick_buffer = Rc::new(RefCell::new(PickBuffer::default()));
+        graph.add_pass(Box::new(PickingPass::new(pick_buffer.clone())?))?;
+        let nav_cube = Rc::new(RefCell::new(NavCube::default()));
+        graph.add_pass(Box::new(NavCubePass::new(nav_cube.clone())?))?;
+        let uv_layout = Rc::new(RefCell::new(UvLayout::default()));
+        graph.add_pass(Box::new(UvLayoutPass::new(uv_layout.clone())?))?;
+        let lines = Rc::new(RefCell::new(LineOverlay::default()));
+        graph.add_pass(Box::new(LinePass::new(lines.clone())?))?;
+        let text = Rc::new(RefCell::new(TextOverlay::default()));
+        #[cfg(feature = "ui")]
+        graph.add_pass(Box::new(TextPass::new(text.clone())?))?;
+
+        let aux_program = build_program(
+            include_str!("shaders/aux.vert"),
+            include_str!("shaders/aux.frag"),
+        )?;
+
+        // 5) Estado GL inicial
+        let settings = RenderSettings::default();
+        settings.apply();
 
         Ok(Self {
-            program
+            program,
+            main_sources: (vert_source, frag_source),
+            shaders,
+            graph,
+            textures: TextureCache::new(settings.linear_workflow),
+            environment: None,
+            environment_path: None,
+            lighting: Lighting::default(),
+            materials,
+            pick_buffer,
+            nav_cube,
+            uv_layout,
+            lines,
+            text,
+            aux_program,
+            last_frame: None,
+            visible: None,
+            scale_bar: None,
+            settings,
+            settings_listeners: Vec::new(),
         })
     }
 
+    /// Carga un panorama .hdr como cielo y entorno de reflejos
+    pub fn set_environment_hdr(&mut self, path: &str) -> Result<(), String> {
+        self.environment = Some(Environment::from_hdr(path)?);
+        self.environment_path = Some(path.to_string());
+        Ok(())
+    }
+
+    /// Vuelve a crear todos los objetos GL (shaders, pases, framebuffers,
+    /// texturas, entorno y la geometría de `objects`) después de perder el
+    /// contexto o de recrear la ventana (ver `graphics::gpu_resources`). Se
+    /// llama con el contexto nuevo ya activo y antes de crear nada en él.
+    pub fn rebuild_gpu(&mut self, objects: &mut [SceneObject]) -> Result<(), String> {
+        ContextGeneration::advance();
+
+        // Los binarios guardados dependen del driver, que puede ser otro
+        if let Some(cache) = self.shaders.program_cache() {
+            let dir = cache.dir().to_path_buf();
+            self.shaders.set_program_cache(Some(ProgramCache::new(dir)));
+        }
+        let (vert_source, frag_source) = &self.main_sources;
+        let variants = ShaderVariants::new(self.shaders.clone(), vert_source, frag_source)?;
+        self.program = variants.base();
+        self.graph.rebuild_gpu(&self.shaders)?;
+        // Si la aplicación quitó el pase opaco o el superpuesto no hay nada que reemplazar
+        let opaque = OpaquePass::new(variants, self.materials.clone());
+        let _ = self.graph.replace_pass(Box::new(opaque.overlay_pass()));
+        let _ = self.graph.replace_pass(Box::new(opaque));
+        self.aux_program = build_program(
+            include_str!("shaders/aux.vert"),
+            include_str!("shaders/aux.frag"),
+        )?;
+
+        self.textures.rebuild_gpu();
+        let mut materials = self.materials.borrow_mut();
+        for (name, path) in materials.color_map_paths() {
+            if let Some(texture) = self.textures.get(&path) {
+                materials.set_color_map(&name, texture);
+            }
+        }
+        drop(materials);
+        if let Some(previous) = self.environment.take() {
+            match &self.environment_path {
+                Some(path) => {
+                    let mut environment = Environment::from_hdr(path)?;
+                    environment.exposure = previous.exposure;
+                    self.environment = Some(environment);
+                }
+                None => eprintln!("El entorno no se cargó de un archivo y no se puede recrear"),
+            }
+        }
+
+        for obj in objects.iter_mut() {
+            obj.rebuild_gpu();
+        }
+        self.last_frame = None;
+        self.settings.apply();
+        Ok(())
+    }
+
+    /// Índice del objeto visible en el píxel (x, y) de la ventana (origen arriba a
+    /// la izquierda, en píxeles físicos) según el último frame dibujado.
+    pub fn pick(&self, x: i32, y: i32) -> Option<usize> {
+        self.pick_buffer.borrow().read(x, y)
+    }
+
+    /// Vértice, arista o cara bajo el cursor, enganchando vértices y aristas a
+    /// menos de `snap_radius` píxeles. `objects` debe ser la lista del último frame.
+    pub fn pick_sub_object(&self, objects: &[SceneObject], x: i32, y: i32, snap_radius: f32) -> Option<SubObjectHit> {
+        self.pick_buffer.borrow().read_sub_object(objects, x, y, snap_radius)
+    }
+
+    /// Punto de mundo más cercano al píxel (x, y) dentro de `radius` píxeles
+    pub fn pick_point(&self, points: &[Vec3], x: i32, y: i32, radius: f32) -> Option<usize> {
+        self.pick_buffer.borrow().nearest_point(points, x, y, radius)
+    }
+
+    /// Cara, arista o esquina del cubo de navegación bajo el píxel (x, y)
+    pub fn nav_cube_hit(&self, x: i32, y: i32) -> Option<NavRegion> {
+        self.nav_cube.borrow().hit(x, y)
+    }
+
+    /// Resalta la región del cubo de navegación bajo el cursor
+    pub fn hover_nav_cube(&mut self, x: i32, y: i32) {
+        self.nav_cube.borrow_mut().set_hover(x, y);
+    }
+
+    /// Registra un plugin de dibujo (ver `graphics::render_plugin`): llama a su
+    /// `setup` y agrega sus ganchos al grafo
+    pub fn add_plugin(&mut self, plugin: Box<dyn RenderPlugin>) -> Result<(), String> {
+        let [before, after] = plugin_passes(plugin)?;
+        let before_name = before.name().to_string();
+        if self.graph.pass_names().contains(&"opaque") {
+            self.graph.insert_before("opaque", before)?;
+        } else {
+            self.graph.add_pass(before)?;
+        }
+        if let Err(e) = self.graph.add_pass(after) {
+            self.graph.remove_pass(&before_name);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Quita los ganchos de un plugin; devuelve si estaba registrado
+    pub fn remove_plugin(&mut self, name: &str) -> bool {
+        let [before, after] = plugin_pass_names(name);
+        let removed = self.graph.remove_pass(&before).is_some();
+        self.graph.remove_pass(&after).is_some() || removed
+    }
+
+    /// Pisa un uniform del shader principal solo para `obj`. Falla si la
+    /// variante base no tiene un uniform activo con ese nombre y tipo.
+    pub fn set_object_uniform(&self, obj: &mut SceneObject, name: &str, value: UniformValue) -> Result<(), String> {
+        obj.uniforms.set_checked(self.program, name, value)
+    }
+
+    /// Capas de líneas superpuestas (ver `graphics::lines`)
+    pub fn lines(&self) -> RefMut<'_, LineOverlay> {
+        self.lines.borrow_mut()
+    }
+
+    /// Reemplaza la biblioteca de materiales y pide sus texturas de color
+    /// al caché (las de la biblioteca anterior se liberan)
+    pub fn set_material_library(&mut self, mut library: MaterialLibrary) {
+        for (_, path) in self.materials.borrow().color_map_paths() {
+            self.textures.release(&path);
+        }
+        for (name, path) in library.color_map_paths() {
+            let texture = self.textures.acquire(&path);
+            library.set_color_map(&name, texture);
+        }
+        *self.materials.borrow_mut() = library;
+    }
+
+    /// Materiales con que se dibujan los objetos (ver `graphics::material`)
+    pub fn materials(&self) -> Ref<'_, MaterialLibrary> {
+        self.materials.borrow()
+    }
+
+    /// Vista de UVs de la esquina (ver `graphics::uv_debug`)
+    pub fn uv_layout(&self) -> RefMut<'_, UvLayout> {
+        self.uv_layout.borrow_mut()
+    }
+
+    /// Paneles de texto en pantalla (ver `graphics::text`)
+    pub fn text(&self) -> RefMut<'_, TextOverlay> {
+        self.text.borrow_mut()
+    }
+
+    /// Muestra la barra de escala midiendo en `units`, o la quita con None
+    pub fn set_scale_bar(&mut self, units: Option<LengthUnit>) {
+        self.scale_bar = units;
+        if units.is_none() {
+            self.text.borrow_mut().clear(SCALE_BAR_LAYER);
+        }
+    }
+
+    pub fn scale_bar(&self) -> Option<LengthUnit> {
+        self.scale_bar
+    }
+
+    /// Rearma la barra de escala para una vista de `viewport` píxeles físicos
+    fn update_scale_bar(&self, objects: &[SceneObject], view: &Matrix4, projection: &Matrix4, viewport: (i32, i32), global_scale: f32) {
+        let Some(units) = self.scale_bar else {
+            return;
+        };
+        let mut text = self.text.borrow_mut();
+        let screen = (viewport.0 as f32 / text.scale, viewport.1 as f32 / text.scale);
+        let bar = scale_bar::reference_depth(objects, view, global_scale)
+            .and_then(|depth| ScaleBar::new(scale_bar::pixels_per_unit(projection, screen.1, depth, global_scale), units));
+        match bar {
+            Some(bar) => {
+                let (panel, rects) = bar.overlay(screen);
+                text.set(SCALE_BAR_LAYER, panel);
+                text.set_rects(SCALE_BAR_LAYER, rects);
+            }
+            None => text.clear(SCALE_BAR_LAYER),
+        }
+    }
+
+    pub fn settings(&self) -> &RenderSettings {
+        &self.settings
+    }
+
+    /// Registra un callback que se llama cada vez que cambia un ajuste
+    pub fn add_settings_listener(&mut self, listener: SettingsListener) {
+        self.settings_listeners.push(listener);
+    }
+
+    /// Aplica un cambio de ajustes, actualiza OpenGL y avisa a los listeners
+    pub fn change_setting(&mut self, change: SettingChange) {
+        if self.settings.change(change) {
+            self.settings.apply();
+            for listener in &mut self.settings_listeners {
+                listener(&self.settings, change);
+            }
+        }
+    }
+
+    pub fn set_backface_culling(&mut self, enabled: bool) {
+        self.change_setting(SettingChange::BackfaceCulling(enabled));
+    }
+
+    pub fn set_depth_test(&mut self, enabled: bool) {
+        self.change_setting(SettingChange::DepthTest(enabled));
+    }
+
+    pub fn set_wireframe(&mut self, enabled: bool) {
+        self.change_setting(SettingChange::Wireframe(enabled));
+    }
+
+    pub fn set_gamma(&mut self, gamma: f32) {
+        self.change_setting(SettingChange::Gamma(gamma));
+    }
+
+    pub fn set_linear_workflow(&mut self, enabled: bool) {
+        // Afecta a las texturas que se carguen desde ahora
+        self.textures.srgb = enabled;
+        self.change_setting(SettingChange::LinearWorkflow(enabled));
+    }
+
+    pub fn set_msaa(&mut self, enabled: bool) {
+        self.change_setting(SettingChange::Msaa(enabled));
+    }
+
+    pub fn set_shadow_quality(&mut self, quality: ShadowQuality) {
+        self.change_setting(SettingChange::ShadowQuality(quality));
+    }
+
+    pub fn set_clear_color(&mut self, color: [f32; 4]) {
+        self.change_setting(SettingChange::ClearColor(color));
+    }
+
+    /// Corrimiento de profundidad de las caras rellenas (factor, unidades); [0, 0] lo quita
+    pub fn set_polygon_offset(&mut self, factor: f32, units: f32) {
+        self.change_setting(SettingChange::PolygonOffset([factor, units]));
+    }
+
+    /// Modo de líneas ocultas (dibujo técnico)
+    pub fn set_hidden_line(&mut self, enabled: bool) {
+        self.change_setting(SettingChange::HiddenLine(enabled));
+    }
+
+    /// Ángulo diedro (grados) desde el que una arista cuenta como canto vivo
+    pub fn set_crease_angle(&mut self, degrees: f32) {
+        self.change_setting(SettingChange::CreaseAngle(degrees));
+    }
+
+    /// Aristas (cantos vivos y contornos) sobre el sombreado
+    pub fn set_outlines(&mut self, enabled: bool) {
+        self.change_setting(SettingChange::Outlines(enabled));
+    }
+
+    /// Damero sobre las UVs en lugar del color de los objetos
+    pub fn set_uv_checker(&mut self, enabled: bool) {
+        self.change_setting(SettingChange::UvChecker(enabled));
+    }
+
+    /// Planos de corte y caja de sección (ver `Clipping`)
+    pub fn set_clipping(&mut self, clipping: Clipping) {
+        self.change_setting(SettingChange::Clipping(clipping));
+    }
+
+    /// Fondo transparente en las capturas (ver `RenderSettings::transparent_background`)
+    pub fn set_transparent_background(&mut self, enabled: bool) {
+        self.change_setting(SettingChange::TransparentBackground(enabled));
+    }
+
+    /// projection * view de la cámara en la ventana, para armar el frustum
+    pub fn view_projection(&self, window: &Window, camera: &Camera) -> Matrix4 {
+        let size = window.inner_size();
+        camera_projection((size.width as i32, size.height as i32)).multiply(&camera.get_view_matrix())
+    }
+
+    /// Objetos a dibujar en `render_scene` (por índice, `None` dibuja todos).
+    /// Las capturas y las vistas de VR ignoran este filtro.
+    pub fn set_visible_objects(&mut self, visible: Option<Vec<bool>>) {
+        self.visible = visible;
+    }
+
     pub fn render_scene(
-        &self,
+        &mut self,
         window: &Window,
         objects: &mut [SceneObject],
         camera: &Camera,
         global_scale: f32,
     ) {
+        // Minimizada: no hay framebuffer donde dibujar
+        let size = window.inner_size();
+        if size.width == 0 || size.height == 0 {
+            return;
+        }
+
+        // Subir texturas que terminaron de decodificarse
+        self.textures.update();
+
         // Limpieza de buffers
         unsafe {
             gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
         }
 
+        // Construir view y projection
+        let view = camera.get_view_matrix();
+        let viewport = (size.width as i32, size.height as i32);
+        let projection = camera_projection(viewport);
+
+        for obj in objects.iter_mut() {
+            obj.spin(0.016); // si deseas dt aquí
+        }
+
+        self.update_scale_bar(objects, &view, &projection, viewport, global_scale);
+        let visible = self.visible.take();
+        self.execute_view(objects, view, projection, global_scale, viewport, visible.as_deref());
+        self.visible = visible;
+        self.last_frame = Some(FrameSnapshot::new(objects, view, projection, viewport, global_scale));
+
+        // Intercambiar buffers
+        if let Err(e) = window.swap_buffers() {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Dibuja la vista de `camera` en un framebuffer propio de `size` píxeles y
+    /// devuelve el color lineal (sin gamma), filas de arriba hacia abajo.
+    /// No usa la ventana, así que el tamaño puede ser mayor que el de la pantalla.
+    pub fn capture_pixels(
+        &mut self,
+        objects: &[SceneObject],
+        camera: &Camera,
+        global_scale: f32,
+        size: (i32, i32),
+    ) -> Result<Vec<[f32; 3]>, String> {
+        let pixels = self.capture_rgba_pixels(objects, camera, global_scale, size)?;
+        Ok(pixels.into_iter().map(|[r, g, b, _]| [r, g, b]).collect())
+    }
+
+    /// Como `capture_pixels` pero con el alfa, premultiplicado. Con
+    /// `transparent_background` el fondo queda en 0 (sin cielo); si no, con el
+    /// alfa del color de fondo.
+    pub fn capture_rgba_pixels(
+        &mut self,
+        objects: &[SceneObject],
+        camera: &Camera,
+        global_scale: f32,
+        size: (i32, i32),
+    ) -> Result<Vec<[f32; 4]>, String> {
+        let samples = if self.settings.msaa { self.settings.msaa_samples } else { 0 };
+        let target = OffscreenTarget::with_samples(size.0, size.1, gl::RGBA16F, samples)?;
+        let view = camera.get_view_matrix();
+        let projection = camera_projection(size);
+        let transparent = self.settings.transparent_background;
+
+        // El picking, el cubo de navegación, la vista de UVs y el texto son de
+        // la ventana; de los textos solo va la barra de escala, medida para esta
+        // imagen. Al final cada pase vuelve a como estaba.
+        let previous = CAPTURE_TOGGLED_PASSES.map(|name| (name, self.graph.is_enabled(name)));
+        self.graph.set_enabled("picking", false);
+        self.graph.set_enabled("nav_cube", false);
+        self.graph.set_enabled("uv_layout", false);
+        self.graph.set_enabled("text", self.scale_bar.is_some());
+        self.update_scale_bar(objects, &view, &projection, size, global_scale);
+        self.text.borrow_mut().set_only(Some(SCALE_BAR_LAYER));
+        // El cielo taparía el fondo transparente (la luz del entorno se mantiene)
+        self.graph.set_enabled("skybox", !transparent && self.graph.is_enabled("skybox"));
+        let settings = RenderSettings { gamma: 1.0, ..self.settings };
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, target.draw_fbo());
+            gl::Viewport(0, 0, size.0, size.1);
+            if transparent {
+                gl::ClearColor(0.0, 0.0, 0.0, 0.0);
+            }
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+        }
+        let frame = FrameContext {
+            objects,
+            view,
+            projection,
+            global_scale,
+            viewport: size,
+            program: self.program,
+            settings: &settings,
+            environment: self.environment.as_ref(),
+            lighting: &self.lighting,
+            visible: None,
+            targets: None,
+        };
+        self.graph.execute(&frame);
+        let pixels = target.read_rgba();
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+        // El próximo frame de la ventana la vuelve a medir
+        self.text.borrow_mut().set_only(None);
+        if transparent {
+            // Vuelve el color de fondo de la ventana
+            self.settings.apply();
+        }
+        for (name, enabled) in previous {
+            self.graph.set_enabled(name, enabled);
+        }
+        Ok(pixels)
+    }
+
+    /// Captura la vista y la guarda según la extensión: `.exr` / `.hdr` con la
+    /// radiancia lineal, o PNG/JPEG de 8 bits. Con `transparent_background` el
+    /// EXR y el PNG llevan alfa. El llamador restaura el viewport de la ventana
+    /// si hace falta.
+    pub fn capture(
+        &mut self,
+        objects: &[SceneObject],
+        camera: &Camera,
+        global_scale: f32,
+        size: (i32, i32),
+        path: &str,
+    ) -> Result<(), String> {
+        if self.settings.transparent_background {
+            let pixels = self.capture_rgba_pixels(objects, camera, global_scale, size)?;
+            return save_image_rgba(path, size.0 as u32, size.1 as u32, &pixels);
+        }
+        let pixels = self.capture_pixels(objects, camera, global_scale, size)?;
+        save_image(path, size.0 as u32, size.1 as u32, &pixels)
+    }
+
+    /// Valores crudos de un buffer auxiliar del último frame dibujado en la
+    /// ventana (filas de arriba hacia abajo) y su tamaño
+    pub fn capture_aux_pixels(&self, buffer: AuxBuffer) -> Result<AuxPixels, String> {
+        let frame = self
+            .last_frame
+            .as_ref()
+            .ok_or_else(|| "Todavía no se dibujó ningún frame".to_string())?;
+        self.render_aux(frame, buffer)
+    }
+
+    /// Buffer auxiliar de la vista de `camera` en un framebuffer de `size` píxeles
+    pub fn capture_aux_view(
+        &self,
+        objects: &[SceneObject],
+        camera: &Camera,
+        global_scale: f32,
+        size: (i32, i32),
+        buffer: AuxBuffer,
+    ) -> Result<AuxPixels, String> {
+        let frame = FrameSnapshot::new(objects, camera.get_view_matrix(), camera_projection(size), size, global_scale);
+        self.render_aux(&frame, buffer)
+    }
+
+    fn render_aux(&self, frame: &FrameSnapshot, buffer: AuxBuffer) -> Result<AuxPixels, String> {
+        let (width, height) = frame.viewport;
+        let target = OffscreenTarget::new(width, height, gl::RGBA32F)?;
+        let program = self.aux_program;
+        let mask_id = match buffer {
+            AuxBuffer::ObjectMask(index) => index as f32 + 1.0,
+            _ => 0.0,
+        };
+
         unsafe {
-            // Activar shader
-            gl::UseProgram(self.program);
-
-            // Ubicar uniformes
-            let light_dir_loc = gl::GetUniformLocation(self.program, b"lightDir\0".as_ptr() as *const i8);
-            let light_color_loc = gl::GetUniformLocation(self.program, b"lightColor\0".as_ptr() as *const i8);
-            let object_color_loc = gl::GetUniformLocation(self.program, b"objectColor\0".as_ptr() as *const i8);
-
-            gl::Uniform3f(light_dir_loc, 1.0, 1.0, 1.0);
-            gl::Uniform3f(light_color_loc, 1.0, 1.0, 1.0);
-            gl::Uniform3f(object_color_loc, 0.8, 0.8, 0.8);
-
-            let model_loc = gl::GetUniformLocation(self.program, b"model\0".as_ptr() as *const i8);
-            let view_loc  = gl::GetUniformLocation(self.program, b"view\0".as_ptr() as *const i8);
-            let proj_loc  = gl::GetUniformLocation(self.program, b"projection\0".as_ptr() as *const i8);
-
-            // Construir view y projection
-            let view = camera.get_view_matrix();
-            let size = window.context.window().inner_size();
-            let aspect = size.width as f32 / size.height as f32;
-            let projection = Matrix4::perspective(45.0_f32.to_radians(), aspect, 0.01, 1000.0);
-
-            gl::UniformMatrix4fv(view_loc, 1, gl::FALSE, view.as_ptr());
-            gl::UniformMatrix4fv(proj_loc, 1, gl::FALSE, projection.as_ptr());
-
-            // Dibujar cada objeto
-            for obj in objects {
-                obj.angle += obj.angular_speed * 0.016; // si deseas dt aquí
-                // rotar en Y con obj.angle
-                let rot_mat = Matrix4::rotate_y(obj.angle);
-                // escala global
-                let scale_mat = Matrix4::scale(global_scale);
-                let local_anim = Matrix4::multiply(&scale_mat, &rot_mat);
-
-                let final_model = Matrix4::multiply(&local_anim, &obj.base_transform);
-
-                gl::UniformMatrix4fv(model_loc, 1, gl::FALSE, final_model.as_ptr());
-                gl::BindVertexArray(obj.vao);
-                gl::DrawElements(gl::TRIANGLES, obj.index_count, gl::UNSIGNED_INT, ptr::null());
+            gl::BindFramebuffer(gl::FRAMEBUFFER, target.fbo);
+            gl::Viewport(0, 0, width, height);
+            // Fondo en 0 y sin estados de la ventana que cambien los datos
+            gl::ClearColor(0.0, 0.0, 0.0, 0.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+            gl::Enable(gl::DEPTH_TEST);
+            gl::Disable(gl::FRAMEBUFFER_SRGB);
+            gl::PolygonMode(gl::FRONT_AND_BACK, gl::FILL);
+
+            gl::UseProgram(program);
+            gl::UniformMatrix4fv(uniform_location(program, "view"), 1, gl::FALSE, frame.view.as_ptr());
+            gl::UniformMatrix4fv(uniform_location(program, "projection"), 1, gl::FALSE, frame.projection.as_ptr());
+            gl::Uniform1i(uniform_location(program, "mode"), buffer.shader_mode());
+            gl::Uniform1f(uniform_location(program, "maskId"), mask_id);
+            let model_loc = uniform_location(program, "model");
+            let normal_loc = uniform_location(program, "normalMatrix");
+            let id_loc = uniform_location(program, "objectId");
+            for (index, (vao, index_count, model)) in frame.draws.iter().enumerate() {
+                gl::UniformMatrix4fv(model_loc, 1, gl::FALSE, model.as_ptr());
+                gl::UniformMatrix4fv(normal_loc, 1, gl::FALSE, model.normal_matrix().as_ptr());
+                gl::Uniform1f(id_loc, index as f32 + 1.0);
+                gl::BindVertexArray(*vao);
+                gl_validation::draw_elements(gl::TRIANGLES, *index_count, 0);
             }
+            gl::BindVertexArray(0);
+        }
+        let pixels = target.read_rgb();
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::Viewport(0, 0, width, height);
+        }
+        self.settings.apply();
+        Ok((pixels, (width, height)))
+    }
 
-            // Intercambiar buffers
-            window.context.swap_buffers().unwrap();
+    /// Exporta un buffer auxiliar de la vista actual (profundidad lineal,
+    /// normales en mundo, ids o máscara de un objeto). `.exr` / `.hdr` guardan
+    /// los valores crudos; otros formatos una versión de 8 bits para verla.
+    pub fn capture_aux(&self, buffer: AuxBuffer, path: &str) -> Result<(), String> {
+        let (mut pixels, (width, height)) = self.capture_aux_pixels(buffer)?;
+        if !is_float_format(path) {
+            buffer.encode_8bit(&mut pixels);
         }
+        save_data_image(path, width as u32, height as u32, &pixels)
+    }
+
+    /// Ejecuta el grafo de pases sobre el framebuffer actualmente enlazado.
+    /// No limpia ni intercambia buffers, así sirve tanto para la ventana como para
+    /// cada ojo en VR. Dibuja todos los objetos (sin el culling de la ventana).
+    pub fn render_view(
+        &mut self,
+        objects: &[SceneObject],
+        view: Matrix4,
+        projection: Matrix4,
+        global_scale: f32,
+        viewport: (i32, i32),
+    ) {
+        self.execute_view(objects, view, projection, global_scale, viewport, None);
+    }
+
+    fn execute_view(
+        &mut self,
+        objects: &[SceneObject],
+        view: Matrix4,
+        projection: Matrix4,
+        global_scale: f32,
+        viewport: (i32, i32),
+        visible: Option<&[bool]>,
+    ) {
+        let frame = FrameContext {
+            objects,
+            view,
+            projection,
+            global_scale,
+            viewport,
+            program: self.program,
+            settings: &self.settings,
+            environment: self.environment.as_ref(),
+            lighting: &self.lighting,
+            visible,
+            targets: None,
+        };
+        self.graph.execute(&frame);
     }
 }
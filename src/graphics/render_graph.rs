@@ -0,0 +1,477 @@
+// src/graphics/render_graph.rs
+//
+// Lista de pases de render ordenada por etapa. Cada pase declara qué recursos
+// lee y cuáles escribe, para poder insertar pases nuevos (SSAO, contornos,
+// picking...) sin reescribir el Renderer.
+//
+// Con `set_profiling` cada pase se mide en la GPU con una timer query
+// (GL_TIME_ELAPSED). El resultado se lee cuando el driver lo tiene listo,
+// uno o más frames después, así medir no frena el pipeline.
+
+use std::collections::HashMap;
+
+use crate::graphics::environment::Environment;
+use crate::graphics::gpu_resources::ContextGeneration;
+use crate::graphics::gl_validation;
+use crate::graphics::lighting::Lighting;
+use crate::graphics::render_settings::RenderSettings;
+use crate::graphics::render_targets::{RenderTarget, RenderTargetDesc, TargetPool, TargetUsage};
+use crate::graphics::scene_object::SceneObject;
+use crate::graphics::shaders::ShaderLibrary;
+use crate::math::matrix_4_by_4::Matrix4;
+
+/// Nombre de un recurso del frame (framebuffer, textura intermedia...)
+pub type ResourceId = &'static str;
+
+/// Recursos que existen siempre al empezar el frame
+pub const BACKBUFFER: ResourceId = "backbuffer";
+pub const SCENE_DEPTH: ResourceId = "scene_depth";
+
+/// Etapas del frame, en orden de ejecución
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PassStage {
+    Shadow,
+    Opaque,
+    Transparent,
+    Debug,
+    Post,
+}
+
+/// Datos compartidos por todos los pases durante un frame
+pub struct FrameContext<'a> {
+    pub objects: &'a [SceneObject],
+    pub view: Matrix4,
+    pub projection: Matrix4,
+    pub global_scale: f32,
+    /// Tamaño del framebuffer destino en píxeles
+    pub viewport: (i32, i32),
+    /// Programa de shading principal del Renderer
+    pub program: u32,
+    /// Ajustes globales del Renderer
+    pub settings: &'a RenderSettings,
+    /// Entorno HDR activo (cielo / reflejos), si hay
+    pub environment: Option<&'a Environment>,
+    /// Sol y cielo de la escena
+    pub lighting: &'a Lighting,
+    /// Objetos que pasaron el culling por frustum, por índice (None: todos)
+    pub visible: Option<&'a [bool]>,
+    /// Framebuffers intermedios del grafo; lo completa `RenderGraph::execute`
+    pub targets: Option<&'a TargetPool>,
+}
+
+impl FrameContext<'_> {
+    pub fn is_visible(&self, object: usize) -> bool {
+        self.visible.and_then(|visible| visible.get(object)).copied().unwrap_or(true)
+    }
+
+    /// Objetos visibles y no ocultos de la escena (`overlay` false) o de la capa
+    /// superpuesta (`overlay` true), en el orden en que se dibujan: por `render_order` y,
+    /// a igualdad, por índice
+    pub fn draw_order(&self, overlay: bool) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.objects.len())
+            .filter(|&index| {
+                let obj = &self.objects[index];
+                self.is_visible(index) && !obj.hidden && obj.overlay == overlay
+            })
+            .collect();
+        order.sort_by_key(|&index| self.objects[index].render_order);
+        order
+    }
+
+    /// Framebuffer de un recurso declarado en `RenderPass::targets`
+    pub fn target(&self, resource: ResourceId) -> Option<&RenderTarget> {
+        self.targets?.get(resource)
+    }
+}
+
+pub trait RenderPass {
+    fn name(&self) -> &str;
+    fn stage(&self) -> PassStage;
+
+    /// Recursos que el pase necesita que alguien haya escrito antes
+    fn inputs(&self) -> &[ResourceId] {
+        &[]
+    }
+
+    /// Recursos que el pase escribe
+    fn outputs(&self) -> &[ResourceId] {
+        &[]
+    }
+
+    /// Recursos intermedios que escribe el pase y que el grafo crea, ajusta al
+    /// viewport y comparte entre pases (no hace falta repetirlos en `outputs`).
+    /// El pase los busca con `FrameContext::target` y los enlaza él mismo.
+    fn targets(&self) -> &[(ResourceId, RenderTargetDesc)] {
+        &[]
+    }
+
+    /// Ajustes con los que se ejecuta el pase. Por defecto los globales;
+    /// un pase puede sobreescribir algunos (p. ej. un pase de debug en wireframe).
+    fn settings(&self, global: &RenderSettings) -> RenderSettings {
+        *global
+    }
+
+    /// Vuelve a crear los objetos GL del pase después de perder el contexto
+    /// (ver `graphics::gpu_resources`). Los anteriores ya no existen y no se
+    /// deben borrar. `shaders` es la librería del Renderer.
+    fn rebuild_gpu(&mut self, _shaders: &ShaderLibrary) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn execute(&mut self, frame: &FrameContext);
+}
+
+/// Timer query de un pase
+struct PassTimer {
+    query: u32,
+    /// Hay una medición en vuelo que todavía no se leyó
+    pending: bool,
+    /// Última medición en milisegundos
+    ms: f32,
+    generation: ContextGeneration,
+}
+
+impl PassTimer {
+    fn new() -> Self {
+        let mut query = 0;
+        unsafe {
+            gl::GenQueries(1, &mut query);
+        }
+        Self { query, pending: false, ms: 0.0, generation: ContextGeneration::current() }
+    }
+
+    /// Lee la medición anterior si el driver ya la tiene
+    fn collect(&mut self) {
+        if !self.pending {
+            return;
+        }
+        let mut available = 0;
+        unsafe {
+            gl::GetQueryObjectiv(self.query, gl::QUERY_RESULT_AVAILABLE, &mut available);
+        }
+        if available != 0 {
+            let mut nanoseconds = 0u64;
+            unsafe {
+                gl::GetQueryObjectui64v(self.query, gl::QUERY_RESULT, &mut nanoseconds);
+            }
+            self.ms = nanoseconds as f32 / 1.0e6;
+            self.pending = false;
+        }
+    }
+}
+
+impl Drop for PassTimer {
+    fn drop(&mut self) {
+        if self.generation.is_current() {
+            unsafe {
+                gl::DeleteQueries(1, &self.query);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RenderGraph {
+    passes: Vec<Box<dyn RenderPass>>,
+    enabled: Vec<bool>,
+    targets: TargetPool,
+    /// Timers por nombre de pase; None sin perfilado
+    timers: Option<HashMap<String, PassTimer>>,
+}
+
+impl RenderGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Agrega un pase al final de su etapa.
+    /// Falla si ya hay un pase con el mismo nombre o si alguno de sus inputs
+    /// no lo produce un pase anterior.
+    pub fn add_pass(&mut self, pass: Box<dyn RenderPass>) -> Result<(), String> {
+        let index = self
+            .passes
+            .iter()
+            .position(|p| p.stage() > pass.stage())
+            .unwrap_or(self.passes.len());
+        self.insert_at(index, pass)
+    }
+
+    /// Inserta un pase justo antes de otro (ignora la etapa del nuevo pase)
+    pub fn insert_before(&mut self, existing: &str, pass: Box<dyn RenderPass>) -> Result<(), String> {
+        let index = self
+            .find(existing)
+            .ok_or_else(|| format!("No existe el pase '{}'", existing))?;
+        self.insert_at(index, pass)
+    }
+
+    /// Inserta un pase justo después de otro
+    pub fn insert_after(&mut self, existing: &str, pass: Box<dyn RenderPass>) -> Result<(), String> {
+        let index = self
+            .find(existing)
+            .ok_or_else(|| format!("No existe el pase '{}'", existing))?;
+        self.insert_at(index + 1, pass)
+    }
+
+    pub fn remove_pass(&mut self, name: &str) -> Option<Box<dyn RenderPass>> {
+        let index = self.find(name)?;
+        self.enabled.remove(index);
+        Some(self.passes.remove(index))
+    }
+
+    /// Reemplaza el pase con el mismo nombre, en su lugar y con su estado
+    /// de activado
+    pub fn replace_pass(&mut self, pass: Box<dyn RenderPass>) -> Result<Box<dyn RenderPass>, String> {
+        let index = self
+            .find(pass.name())
+            .ok_or_else(|| format!("No existe el pase '{}'", pass.name()))?;
+        Ok(std::mem::replace(&mut self.passes[index], pass))
+    }
+
+    /// Activa o desactiva un pase sin quitarlo del grafo
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) {
+        if let Some(index) = self.find(name) {
+            self.enabled[index] = enabled;
+        }
+    }
+
+    /// Si el pase está activado (false si no existe)
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.find(name).is_some_and(|index| self.enabled[index])
+    }
+
+    pub fn pass_names(&self) -> Vec<&str> {
+        self.passes.iter().map(|p| p.name()).collect()
+    }
+
+    /// Mide (o deja de medir) el tiempo de GPU de cada pase
+    pub fn set_profiling(&mut self, enabled: bool) {
+        if enabled != self.timers.is_some() {
+            self.timers = enabled.then(HashMap::new);
+        }
+    }
+
+    /// Última medición de GPU de cada pase activo, en ms y en orden de ejecución
+    pub fn gpu_timings(&self) -> Vec<(String, f32)> {
+        let Some(timers) = &self.timers else {
+            return Vec::new();
+        };
+        self.passes
+            .iter()
+            .zip(&self.enabled)
+            .filter(|(_, enabled)| **enabled)
+            .filter_map(|(pass, _)| Some((pass.name().to_string(), timers.get(pass.name())?.ms)))
+            .collect()
+    }
+
+    /// Recrea los objetos GL de todos los pases en un contexto nuevo. Los
+    /// framebuffers intermedios se vuelven a crear en el próximo frame.
+    pub fn rebuild_gpu(&mut self, shaders: &ShaderLibrary) -> Result<(), String> {
+        self.targets = TargetPool::default();
+        if let Some(timers) = &mut self.timers {
+            timers.clear();
+        }
+        for pass in &mut self.passes {
+            pass.rebuild_gpu(shaders)
+                .map_err(|e| format!("No se pudo recrear el pase '{}': {}", pass.name(), e))?;
+        }
+        Ok(())
+    }
+
+    pub fn execute(&mut self, frame: &FrameContext) {
+        let usages = self.target_usages();
+        if let Err(e) = self.targets.prepare(&usages, frame.viewport) {
+            eprintln!("{}", e);
+        }
+        let frame = FrameContext { targets: Some(&self.targets), ..*frame };
+
+        // Solo se toca el estado GL cuando un pase pide algo distinto al anterior
+        let mut current = *frame.settings;
+        for (pass, enabled) in self.passes.iter_mut().zip(&self.enabled) {
+            if *enabled {
+                let wanted = pass.settings(frame.settings);
+                if wanted != current {
+                    wanted.apply();
+                    current = wanted;
+                }
+                // Sin medición en vuelo se empieza otra; si no, este frame no se mide
+                let timer = self.timers.as_mut().and_then(|timers| {
+                    let timer = timers.entry(pass.name().to_string()).or_insert_with(PassTimer::new);
+                    timer.collect();
+                    (!timer.pending).then_some(timer)
+                });
+                if let Some(timer) = &timer {
+                    unsafe {
+                        gl::BeginQuery(gl::TIME_ELAPSED, timer.query);
+                    }
+                }
+                gl_validation::set_scope(pass.name());
+                pass.execute(&frame);
+                if let Some(timer) = timer {
+                    unsafe {
+                        gl::EndQuery(gl::TIME_ELAPSED);
+                    }
+                    timer.pending = true;
+                }
+            }
+        }
+        if current != *frame.settings {
+            frame.settings.apply();
+        }
+    }
+
+    /// Vida de cada recurso intermedio entre los pases activos: desde el
+    /// primer pase que lo escribe hasta el último que lo lee
+    fn target_usages(&self) -> Vec<TargetUsage> {
+        let active: Vec<(usize, &dyn RenderPass)> = self
+            .passes
+            .iter()
+            .zip(&self.enabled)
+            .enumerate()
+            .filter(|(_, (_, enabled))| **enabled)
+            .map(|(index, (pass, _))| (index, pass.as_ref()))
+            .collect();
+
+        let mut usages: Vec<TargetUsage> = Vec::new();
+        for &(index, pass) in &active {
+            for &(resource, desc) in pass.targets() {
+                if usages.iter().any(|u| u.resource == resource) {
+                    continue;
+                }
+                let last = active
+                    .iter()
+                    .filter(|(_, p)| p.inputs().contains(&resource))
+                    .map(|&(i, _)| i)
+                    .max()
+                    .unwrap_or(index)
+                    .max(index);
+                usages.push(TargetUsage { resource, desc, first: index, last });
+            }
+        }
+        usages
+    }
+
+    fn find(&self, name: &str) -> Option<usize> {
+        self.passes.iter().position(|p| p.name() == name)
+    }
+
+    fn insert_at(&mut self, index: usize, pass: Box<dyn RenderPass>) -> Result<(), String> {
+        if self.find(pass.name()).is_some() {
+            return Err(format!("Ya existe un pase llamado '{}'", pass.name()));
+        }
+
+        // Validar que los inputs los escribe alguien antes
+        for input in pass.inputs() {
+            let produced = *input == BACKBUFFER
+                || *input == SCENE_DEPTH
+                || self.passes[..index].iter().any(|p| {
+                    p.outputs().contains(input) || p.targets().iter().any(|(id, _)| id == input)
+                });
+            if !produced {
+                return Err(format!(
+                    "El pase '{}' lee '{}' pero ningún pase anterior lo escribe",
+                    pass.name(),
+                    input
+                ));
+            }
+        }
+
+        self.passes.insert(index, pass);
+        self.enabled.insert(index, true);
+        Ok(())
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::gl_mock::{self, GlCall};
+
+    /// Pase que solo enlaza su programa, para ver en qué orden corre
+    struct RecordingPass {
+        name: &'static str,
+        stage: PassStage,
+        program: u32,
+        wireframe: bool,
+    }
+
+    impl RenderPass for RecordingPass {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn stage(&self) -> PassStage {
+            self.stage
+        }
+
+        fn settings(&self, global: &RenderSettings) -> RenderSettings {
+            RenderSettings { wireframe: self.wireframe, ..*global }
+        }
+
+        fn execute(&mut self, _frame: &FrameContext) {
+            unsafe {
+                gl::UseProgram(self.program);
+            }
+        }
+    }
+
+    fn pass(name: &'static str, stage: PassStage, program: u32) -> Box<dyn RenderPass> {
+        Box::new(RecordingPass { name, stage, program, wireframe: false })
+    }
+
+    #[test]
+    fn test_passes_run_in_stage_order_and_skip_disabled() {
+        gl_mock::install();
+        let mut graph = RenderGraph::new();
+        graph.add_pass(pass("post", PassStage::Post, 5)).unwrap();
+        graph.add_pass(pass("opaque", PassStage::Opaque, 2)).unwrap();
+        let debug = RecordingPass { name: "debug", stage: PassStage::Debug, program: 4, wireframe: true };
+        graph.add_pass(Box::new(debug)).unwrap();
+        graph.add_pass(pass("shadow", PassStage::Shadow, 1)).unwrap();
+        graph.insert_before("debug", pass("outline", PassStage::Post, 3)).unwrap();
+        assert!(graph.add_pass(pass("opaque", PassStage::Opaque, 9)).is_err());
+        assert_eq!(graph.pass_names(), vec!["shadow", "opaque", "outline", "debug", "post"]);
+
+        graph.set_enabled("shadow", false);
+        assert!(!graph.is_enabled("shadow") && graph.is_enabled("post") && !graph.is_enabled("otro"));
+        let (settings, lighting) = (RenderSettings::default(), Lighting::default());
+        let frame = FrameContext {
+            objects: &[],
+            view: Matrix4::identity(),
+            projection: Matrix4::identity(),
+            global_scale: 1.0,
+            viewport: (64, 64),
+            program: 0,
+            settings: &settings,
+            environment: None,
+            lighting: &lighting,
+            visible: None,
+            targets: None,
+        };
+        gl_mock::take_calls();
+        graph.execute(&frame);
+
+        let calls = gl_mock::take_calls();
+        let programs: Vec<u32> = calls
+            .iter()
+            .filter_map(|c| match c {
+                GlCall::UseProgram(program) => Some(*program),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(programs, vec![2, 3, 4, 5]);
+        // Solo el pase de debug cambia el estado GL, y se restaura después
+        let modes: Vec<(usize, u32)> = calls
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| match c {
+                GlCall::PolygonMode(_, mode) => Some((i, *mode)),
+                _ => None,
+            })
+            .collect();
+        let debug_call = calls.iter().position(|c| *c == GlCall::UseProgram(4)).unwrap();
+        assert_eq!(modes.len(), 2);
+        assert!(modes[0].0 < debug_call && modes[0].1 == gl::LINE);
+        assert!(modes[1].0 > debug_call && modes[1].1 == gl::FILL);
+    }
+}
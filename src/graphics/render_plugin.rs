@@ -0,0 +1,151 @@
+// src/graphics/render_plugin.rs
+//
+// Código de dibujo de la aplicación dentro del frame sin tocar el Renderer:
+// un plugin se registra una vez y el Renderer lo llama antes de los opacos y
+// al final del post-proceso. Por dentro cada gancho es un pase más del grafo,
+// así que respeta el mismo orden y los mismos ajustes que los pases propios.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::graphics::render_graph::{FrameContext, PassStage, RenderPass};
+use crate::graphics::shaders::ShaderLibrary;
+
+pub trait RenderPlugin {
+    /// Nombre único; los pases del plugin se llaman "<nombre>:before_opaque" y
+    /// "<nombre>:after_post"
+    fn name(&self) -> &str;
+
+    /// Se llama al registrarlo, con el contexto GL ya activo: el lugar para
+    /// compilar shaders y crear buffers. Se vuelve a llamar si se pierde el
+    /// contexto; los objetos anteriores ya no existen y no hay que borrarlos.
+    fn setup(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Antes de dibujar los objetos (fondos, rejillas...)
+    fn before_opaque(&mut self, _frame: &FrameContext) {}
+
+    /// Después de todo lo demás (overlays, anotaciones)
+    fn after_post(&mut self, _frame: &FrameContext) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Hook {
+    BeforeOpaque,
+    AfterPost,
+}
+
+/// Pase del grafo que llama a uno de los ganchos del plugin
+struct PluginPass {
+    name: String,
+    hook: Hook,
+    plugin: Rc<RefCell<Box<dyn RenderPlugin>>>,
+}
+
+impl RenderPass for PluginPass {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn stage(&self) -> PassStage {
+        match self.hook {
+            Hook::BeforeOpaque => PassStage::Opaque,
+            Hook::AfterPost => PassStage::Post,
+        }
+    }
+
+    fn rebuild_gpu(&mut self, _shaders: &ShaderLibrary) -> Result<(), String> {
+        // Los dos ganchos comparten el plugin: se inicializa una sola vez
+        if self.hook == Hook::BeforeOpaque {
+            self.plugin.borrow_mut().setup()?;
+        }
+        Ok(())
+    }
+
+    fn execute(&mut self, frame: &FrameContext) {
+        let mut plugin = self.plugin.borrow_mut();
+        match self.hook {
+            Hook::BeforeOpaque => plugin.before_opaque(frame),
+            Hook::AfterPost => plugin.after_post(frame),
+        }
+    }
+}
+
+/// Nombres de los dos pases de un plugin
+pub fn plugin_pass_names(plugin: &str) -> [String; 2] {
+    [format!("{}:before_opaque", plugin), format!("{}:after_post", plugin)]
+}
+
+/// Los dos pases de un plugin ya inicializado: (antes de "opaque", al final del post)
+pub(crate) fn plugin_passes(mut plugin: Box<dyn RenderPlugin>) -> Result<[Box<dyn RenderPass>; 2], String> {
+    plugin.setup()?;
+    let [before, after] = plugin_pass_names(plugin.name());
+    let plugin = Rc::new(RefCell::new(plugin));
+    Ok([
+        Box::new(PluginPass { name: before, hook: Hook::BeforeOpaque, plugin: plugin.clone() }),
+        Box::new(PluginPass { name: after, hook: Hook::AfterPost, plugin }),
+    ])
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::lighting::Lighting;
+    use crate::graphics::render_graph::RenderGraph;
+    use crate::graphics::render_settings::RenderSettings;
+    use crate::math::matrix_4_by_4::Matrix4;
+
+    /// Anota en `log` cada gancho que se llama
+    struct Recorder {
+        log: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl RenderPlugin for Recorder {
+        fn name(&self) -> &str {
+            "recorder"
+        }
+
+        fn setup(&mut self) -> Result<(), String> {
+            self.log.borrow_mut().push("setup".to_string());
+            Ok(())
+        }
+
+        fn before_opaque(&mut self, _frame: &FrameContext) {
+            self.log.borrow_mut().push("before_opaque".to_string());
+        }
+
+        fn after_post(&mut self, _frame: &FrameContext) {
+            self.log.borrow_mut().push("after_post".to_string());
+        }
+    }
+
+    #[test]
+    fn test_plugin_hooks_run_in_order() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let [before, after] = plugin_passes(Box::new(Recorder { log: log.clone() })).unwrap();
+        let mut graph = RenderGraph::new();
+        // Registrados al revés: el grafo los ordena por etapa
+        graph.add_pass(after).unwrap();
+        graph.add_pass(before).unwrap();
+        assert_eq!(graph.pass_names(), vec!["recorder:before_opaque", "recorder:after_post"]);
+
+        let (settings, lighting) = (RenderSettings::default(), Lighting::default());
+        let frame = FrameContext {
+            objects: &[],
+            view: Matrix4::identity(),
+            projection: Matrix4::identity(),
+            global_scale: 1.0,
+            viewport: (64, 64),
+            program: 0,
+            settings: &settings,
+            environment: None,
+            lighting: &lighting,
+            visible: None,
+            targets: None,
+        };
+        graph.execute(&frame);
+        assert_eq!(*log.borrow(), vec!["setup", "before_opaque", "after_post"]);
+    }
+}
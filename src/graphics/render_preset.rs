@@ -0,0 +1,261 @@
+// src/graphics/render_preset.rs
+//
+// Presets de render con nombre: modo de sombreado, fondo, contornos, sombras,
+// iluminación y análisis por colores juntos, para pasar de una captura
+// "técnica" a una "de presentación" con una tecla en vez de tocar cada ajuste.
+// Vienen tres armados (ver `RenderPreset::builtin`) y se pueden guardar más en
+// un archivo RON, indicado en el script de arranque o con
+// RUST_ENGINE_RENDER_PRESETS:
+//
+// (
+//     presets: [
+//         (name: "Folleto", shading: Solid, background: (1.0, 1.0, 1.0, 1.0),
+//          outlines: true, shadows: High, lighting: Some(Studio)),
+//     ],
+// )
+
+use serde::{Deserialize, Serialize};
+
+use crate::graphics::lighting::LightingRig;
+use crate::graphics::render::Renderer;
+use crate::graphics::render_settings::{RenderSettings, ShadowQuality};
+use crate::graphics::scene::Scene;
+
+/// Variable de entorno con la ruta del archivo de presets que se carga al arrancar
+pub const RENDER_PRESETS_ENV: &str = "RUST_ENGINE_RENDER_PRESETS";
+
+/// Archivo donde se guardan los presets si no se cargaron de ninguno
+pub const DEFAULT_PRESETS_FILE: &str = "render_presets.ron";
+
+/// Cómo se dibujan las caras
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShadingMode {
+    Solid,
+    Wireframe,
+    /// Dibujo técnico con líneas ocultas
+    HiddenLine,
+    /// Damero sobre las UVs
+    UvChecker,
+}
+
+/// Análisis que colorea los objetos por vértice
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Heatmap {
+    /// Caras que necesitan soporte al imprimir (ver `graphics::printability`)
+    Overhangs,
+    /// Espesor de pared (ver `graphics::thickness`)
+    Thickness,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenderPreset {
+    pub name: String,
+    pub shading: ShadingMode,
+    /// Color de fondo sRGB
+    pub background: [f32; 4],
+    /// Cantos vivos y contornos sobre el sombreado (ver `RenderSettings::outlines`)
+    pub outlines: bool,
+    pub shadows: ShadowQuality,
+    /// None deja la iluminación de la escena
+    pub lighting: Option<LightingRig>,
+    /// El visor lo aplica con el mismo análisis de las teclas O y H
+    pub heatmap: Option<Heatmap>,
+}
+
+impl Default for RenderPreset {
+    fn default() -> Self {
+        Self::from_settings("", &RenderSettings::default(), None)
+    }
+}
+
+impl RenderPreset {
+    /// Los que trae el motor: "Técnico", "Presentación" y "Mapa de calor"
+    pub fn builtin() -> Vec<Self> {
+        vec![
+            Self {
+                name: "Técnico".to_string(),
+                shading: ShadingMode::HiddenLine,
+                background: [1.0, 1.0, 1.0, 1.0],
+                outlines: false,
+                shadows: ShadowQuality::Off,
+                lighting: Some(LightingRig::Ambient),
+                heatmap: None,
+            },
+            Self {
+                name: "Presentación".to_string(),
+                shading: ShadingMode::Solid,
+                background: [0.16, 0.17, 0.2, 1.0],
+                outlines: true,
+                shadows: ShadowQuality::High,
+                lighting: Some(LightingRig::Studio),
+                heatmap: None,
+            },
+            Self {
+                name: "Mapa de calor".to_string(),
+                shading: ShadingMode::Solid,
+                background: [0.1, 0.1, 0.1, 1.0],
+                outlines: false,
+                shadows: ShadowQuality::Off,
+                lighting: Some(LightingRig::Ambient),
+                heatmap: Some(Heatmap::Thickness),
+            },
+        ]
+    }
+
+    /// Preset con los ajustes actuales (sin análisis por colores)
+    pub fn from_settings(name: &str, settings: &RenderSettings, lighting: Option<LightingRig>) -> Self {
+        let shading = if settings.hidden_line {
+            ShadingMode::HiddenLine
+        } else if settings.wireframe {
+            ShadingMode::Wireframe
+        } else if settings.uv_checker {
+            ShadingMode::UvChecker
+        } else {
+            ShadingMode::Solid
+        };
+        Self {
+            name: name.to_string(),
+            shading,
+            background: settings.clear_color,
+            outlines: settings.outlines,
+            shadows: settings.shadow_quality,
+            lighting,
+            heatmap: None,
+        }
+    }
+
+    /// Aplica los ajustes de render y la iluminación. El análisis por colores
+    /// (`heatmap`) queda a cargo de quien llama.
+    pub fn apply(&self, renderer: &mut Renderer, scene: &mut Scene) {
+        renderer.set_hidden_line(self.shading == ShadingMode::HiddenLine);
+        renderer.set_wireframe(self.shading == ShadingMode::Wireframe);
+        renderer.set_uv_checker(self.shading == ShadingMode::UvChecker);
+        renderer.set_clear_color(self.background);
+        renderer.set_outlines(self.outlines);
+        renderer.set_shadow_quality(self.shadows);
+        if let Some(rig) = self.lighting {
+            renderer.lighting = scene.apply_lighting_preset(rig);
+        }
+    }
+}
+
+/// Presets en el orden en que se recorren
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresetLibrary {
+    presets: Vec<RenderPreset>,
+    /// Archivo del que se cargó (ahí se guardan los cambios)
+    #[serde(skip)]
+    path: Option<String>,
+}
+
+impl Default for PresetLibrary {
+    fn default() -> Self {
+        Self { presets: RenderPreset::builtin(), path: None }
+    }
+}
+
+impl PresetLibrary {
+    /// Los presets del motor más los del archivo (los del archivo reemplazan
+    /// a los del motor con el mismo nombre)
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("No se pudo leer {}: {}", path, e))?;
+        let file: Self = ron::from_str(&text).map_err(|e| format!("Presets de render inválidos {}: {}", path, e))?;
+        let mut library = Self::default();
+        for preset in file.presets {
+            library.set(preset);
+        }
+        library.path = Some(path.to_string());
+        Ok(library)
+    }
+
+    /// Presets de RUST_ENGINE_RENDER_PRESETS, si está definida y se puede leer
+    pub fn from_env() -> Option<Self> {
+        let path = std::env::var(RENDER_PRESETS_ENV).ok()?;
+        match Self::load(&path) {
+            Ok(library) => Some(library),
+            Err(e) => {
+                eprintln!("{} ignorado: {}", RENDER_PRESETS_ENV, e);
+                None
+            }
+        }
+    }
+
+    /// Guarda todos los presets en el archivo del que se cargaron (o en
+    /// `DEFAULT_PRESETS_FILE`) y devuelve la ruta
+    pub fn save(&mut self) -> Result<String, String> {
+        let path = self.path.clone().unwrap_or_else(|| DEFAULT_PRESETS_FILE.to_string());
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| format!("No se pudieron serializar los presets: {}", e))?;
+        std::fs::write(&path, text).map_err(|e| format!("No se pudo escribir {}: {}", path, e))?;
+        self.path = Some(path.clone());
+        Ok(path)
+    }
+
+    /// Agrega o reemplaza el preset con el mismo nombre
+    pub fn set(&mut self, preset: RenderPreset) {
+        match self.presets.iter_mut().find(|p| p.name == preset.name) {
+            Some(existing) => *existing = preset,
+            None => self.presets.push(preset),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&RenderPreset> {
+        self.presets.iter().find(|p| p.name == name)
+    }
+
+    /// El que sigue a `current` (el primero si no hay o no existe)
+    pub fn next(&self, current: Option<&str>) -> Option<&RenderPreset> {
+        let index = current.and_then(|name| self.presets.iter().position(|p| p.name == name));
+        let next = index.map_or(0, |index| (index + 1) % self.presets.len());
+        self.presets.get(next)
+    }
+
+    pub fn presets(&self) -> &[RenderPreset] {
+        &self.presets
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_file_and_cycle() {
+        let library = PresetLibrary::default();
+        let names: Vec<&str> = library.presets().iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Técnico", "Presentación", "Mapa de calor"]);
+        assert_eq!(library.next(None).unwrap().name, "Técnico");
+        assert_eq!(library.next(Some("Mapa de calor")).unwrap().name, "Técnico");
+        assert_eq!(library.next(Some("Técnico")).unwrap().name, "Presentación");
+
+        // Los del archivo reemplazan por nombre y se agregan al final
+        let dir = std::env::temp_dir().join(format!("rust_engine_presets_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("presets.ron");
+        std::fs::write(
+            &path,
+            r#"(presets: [
+                (name: "Técnico", shading: Wireframe),
+                (name: "Folleto", outlines: true, lighting: Some(Studio)),
+            ])"#,
+        )
+        .unwrap();
+        let mut library = PresetLibrary::load(&path.to_string_lossy()).unwrap();
+        assert_eq!(library.presets().len(), 4);
+        assert_eq!(library.get("Técnico").unwrap().shading, ShadingMode::Wireframe);
+        let folleto = library.get("Folleto").unwrap();
+        assert!(folleto.outlines);
+        assert_eq!(folleto.shadows, RenderSettings::default().shadow_quality);
+
+        // Guardar los ajustes actuales y volver a leerlos
+        let settings = RenderSettings { wireframe: true, clear_color: [1.0; 4], ..RenderSettings::default() };
+        library.set(RenderPreset::from_settings("Actual", &settings, Some(LightingRig::MORNING)));
+        assert_eq!(library.save().unwrap(), path.to_string_lossy());
+        let reloaded = PresetLibrary::load(&path.to_string_lossy()).unwrap();
+        assert_eq!(reloaded.get("Actual"), library.get("Actual"));
+        assert_eq!(reloaded.get("Actual").unwrap().shading, ShadingMode::Wireframe);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,276 @@
+// src/graphics/render_settings.rs
+//
+// Estado global de render (culling, depth, wireframe...) en un solo lugar.
+// El Renderer es el dueño y lo aplica a OpenGL; cada pase puede sobreescribirlo.
+
+use serde::{Deserialize, Serialize};
+
+use crate::graphics::color::srgb_to_linear_rgb;
+use crate::graphics::shaders::uniform_location;
+use crate::math::{aabb::Aabb, vec3::Vec3};
+
+/// Planos de corte que admite el shader (ver `shaders/clipping.glsl`)
+pub const MAX_CLIP_PLANES: usize = 4;
+
+/// Corte global de la escena: planos y caja de sección en espacio mundo. Se
+/// descarta lo que queda del lado negativo de un plano o fuera de la caja,
+/// salvo en los objetos con `SceneObject::ignore_clipping`. Afecta al dibujo
+/// y al picking por GPU; los rayos en CPU siguen viendo la malla entera.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Clipping {
+    /// (nx, ny, nz, d): se conserva lo que cumple n·p + d >= 0
+    pub planes: [Option<[f32; 4]>; MAX_CLIP_PLANES],
+    pub section_box: Option<Aabb>,
+}
+
+impl Clipping {
+    pub fn is_active(&self) -> bool {
+        self.section_box.is_some() || self.planes.iter().any(Option::is_some)
+    }
+
+    /// Agrega un plano por `point` que conserva el lado hacia el que apunta `normal`
+    pub fn add_plane(&mut self, point: Vec3, normal: Vec3) -> Result<(), String> {
+        let n = normal.try_normalize().ok_or("La normal del plano de corte no puede ser nula")?;
+        let slot = self
+            .planes
+            .iter_mut()
+            .find(|plane| plane.is_none())
+            .ok_or_else(|| format!("Ya hay {} planos de corte", MAX_CLIP_PLANES))?;
+        *slot = Some([n.x, n.y, n.z, -n.dot(&point)]);
+        Ok(())
+    }
+
+    /// Si el corte descarta el punto `p` (igual que `isClipped` en el shader)
+    pub fn clips(&self, p: Vec3) -> bool {
+        let outside_plane = self.planes.iter().flatten().any(|[a, b, c, d]| a * p.x + b * p.y + c * p.z + d < 0.0);
+        let outside_box = self.section_box.is_some_and(|b| {
+            p.x < b.min.x || p.y < b.min.y || p.z < b.min.z || p.x > b.max.x || p.y > b.max.y || p.z > b.max.z
+        });
+        outside_plane || outside_box
+    }
+
+    /// Sube el corte a los uniforms de `shaders/clipping.glsl` del programa activo
+    ///
+    /// # Safety
+    /// Requiere un contexto GL activo con `program` en uso.
+    pub unsafe fn bind(&self, program: u32) {
+        let planes: Vec<&[f32; 4]> = self.planes.iter().flatten().collect();
+        gl::Uniform1i(uniform_location(program, "clipPlaneCount"), planes.len() as i32);
+        for (i, [a, b, c, d]) in planes.into_iter().enumerate() {
+            gl::Uniform4f(uniform_location(program, &format!("clipPlanes[{}]", i)), *a, *b, *c, *d);
+        }
+        gl::Uniform1i(uniform_location(program, "sectionBox"), self.section_box.is_some() as i32);
+        if let Some(b) = self.section_box {
+            gl::Uniform3f(uniform_location(program, "sectionBoxMin"), b.min.x, b.min.y, b.min.z);
+            gl::Uniform3f(uniform_location(program, "sectionBoxMax"), b.max.x, b.max.y, b.max.z);
+        }
+    }
+}
+
+/// Calidad del mapa de sombras
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShadowQuality {
+    Off,
+    Low,
+    Medium,
+    High,
+}
+
+impl ShadowQuality {
+    /// Resolución del shadow map en píxeles (0 = sin sombras)
+    pub fn map_size(&self) -> u32 {
+        match self {
+            ShadowQuality::Off => 0,
+            ShadowQuality::Low => 512,
+            ShadowQuality::Medium => 1024,
+            ShadowQuality::High => 2048,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderSettings {
+    pub backface_culling: bool,
+    pub depth_test: bool,
+    pub wireframe: bool,
+    /// Gamma aplicada al color final en el shader (1.0 = sin corrección).
+    /// Con `linear_workflow` el framebuffer sRGB ya codifica la salida, así que
+    /// normalmente se deja en 1.0.
+    pub gamma: f32,
+    /// Iluminación en espacio lineal: los colores de la API (sRGB) se convierten
+    /// a lineal antes de subirlos y se activa GL_FRAMEBUFFER_SRGB
+    pub linear_workflow: bool,
+    /// Resolver el MSAA del framebuffer (GL_MULTISAMPLE)
+    pub msaa: bool,
+    /// Muestras por píxel que se piden al crear la ventana
+    pub msaa_samples: u16,
+    pub shadow_quality: ShadowQuality,
+    pub clear_color: [f32; 4],
+    /// Corrimiento de profundidad de las caras rellenas (factor y unidades de
+    /// glPolygonOffset) para que líneas o calcos coplanares queden delante;
+    /// [0, 0] lo desactiva
+    pub polygon_offset: [f32; 2],
+    /// Dibujo técnico: caras del color de fondo y solo las aristas visibles
+    /// (ver `graphics::edges`)
+    pub hidden_line: bool,
+    /// Ángulo diedro en grados a partir del cual una arista se dibuja en
+    /// el modo de líneas ocultas aunque no sea contorno
+    pub crease_angle: f32,
+    /// Cantos vivos y contornos dibujados sobre el sombreado normal (las
+    /// mismas aristas que en líneas ocultas)
+    pub outlines: bool,
+    /// Damero procedural sobre las UVs en vez del color de los objetos (los
+    /// que no tienen UVs se dibujan como siempre)
+    pub uv_checker: bool,
+    /// Planos y caja de sección globales
+    pub clipping: Clipping,
+    /// Capturas con el fondo en alfa 0 y alfa premultiplicado, para componer
+    /// sobre otra imagen (ver `graphics::capture`). La ventana sigue mostrando
+    /// `clear_color`.
+    pub transparent_background: bool,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            backface_culling: false,
+            depth_test: true,
+            wireframe: false,
+            gamma: 1.0,
+            linear_workflow: true,
+            msaa: true,
+            msaa_samples: 4,
+            shadow_quality: ShadowQuality::Medium,
+            clear_color: [0.1, 0.2, 0.3, 1.0],
+            polygon_offset: [0.0, 0.0],
+            hidden_line: false,
+            crease_angle: 30.0,
+            outlines: false,
+            uv_checker: false,
+            clipping: Clipping::default(),
+            transparent_background: false,
+        }
+    }
+}
+
+/// Evento emitido cuando cambia un ajuste
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SettingChange {
+    BackfaceCulling(bool),
+    DepthTest(bool),
+    Wireframe(bool),
+    Gamma(f32),
+    LinearWorkflow(bool),
+    Msaa(bool),
+    ShadowQuality(ShadowQuality),
+    ClearColor([f32; 4]),
+    PolygonOffset([f32; 2]),
+    HiddenLine(bool),
+    CreaseAngle(f32),
+    Outlines(bool),
+    UvChecker(bool),
+    Clipping(Clipping),
+    TransparentBackground(bool),
+}
+
+/// Callback que recibe los ajustes nuevos y qué cambió
+pub type SettingsListener = Box<dyn FnMut(&RenderSettings, SettingChange)>;
+
+impl RenderSettings {
+    /// Aplica el estado fijo a OpenGL. Requiere un contexto activo.
+    pub fn apply(&self) {
+        unsafe {
+            set_capability(gl::CULL_FACE, self.backface_culling);
+            if self.backface_culling {
+                gl::CullFace(gl::BACK);
+            }
+            set_capability(gl::DEPTH_TEST, self.depth_test);
+            set_capability(gl::MULTISAMPLE, self.msaa);
+            gl::PolygonMode(
+                gl::FRONT_AND_BACK,
+                if self.wireframe { gl::LINE } else { gl::FILL },
+            );
+            let [factor, units] = self.polygon_offset;
+            let offset = factor != 0.0 || units != 0.0;
+            set_capability(gl::POLYGON_OFFSET_FILL, offset);
+            if offset {
+                gl::PolygonOffset(factor, units);
+            }
+            set_capability(gl::FRAMEBUFFER_SRGB, self.linear_workflow);
+            // glClear también pasa por la conversión sRGB del framebuffer
+            let [r, g, b] = self.shader_color([self.clear_color[0], self.clear_color[1], self.clear_color[2]]);
+            gl::ClearColor(r, g, b, self.clear_color[3]);
+        }
+    }
+
+    /// Convierte un color sRGB de la API al espacio en el que trabaja el shader
+    pub fn shader_color(&self, srgb: [f32; 3]) -> [f32; 3] {
+        if self.linear_workflow {
+            srgb_to_linear_rgb(srgb)
+        } else {
+            srgb
+        }
+    }
+
+    /// Aplica un cambio y devuelve si realmente modificó algo
+    pub fn change(&mut self, change: SettingChange) -> bool {
+        let before = *self;
+        match change {
+            SettingChange::BackfaceCulling(v) => self.backface_culling = v,
+            SettingChange::DepthTest(v) => self.depth_test = v,
+            SettingChange::Wireframe(v) => self.wireframe = v,
+            SettingChange::Gamma(v) => self.gamma = v,
+            SettingChange::LinearWorkflow(v) => self.linear_workflow = v,
+            SettingChange::Msaa(v) => self.msaa = v,
+            SettingChange::ShadowQuality(v) => self.shadow_quality = v,
+            SettingChange::ClearColor(v) => self.clear_color = v,
+            SettingChange::PolygonOffset(v) => self.polygon_offset = v,
+            SettingChange::HiddenLine(v) => self.hidden_line = v,
+            SettingChange::CreaseAngle(v) => self.crease_angle = v,
+            SettingChange::Outlines(v) => self.outlines = v,
+            SettingChange::UvChecker(v) => self.uv_checker = v,
+            SettingChange::Clipping(v) => self.clipping = v,
+            SettingChange::TransparentBackground(v) => self.transparent_background = v,
+        }
+        before != *self
+    }
+}
+
+unsafe fn set_capability(cap: u32, enabled: bool) {
+    if enabled {
+        gl::Enable(cap);
+    } else {
+        gl::Disable(cap);
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clipping_planes_and_box() {
+        let mut clipping = Clipping::default();
+        assert!(!clipping.is_active());
+        // Conserva y >= 1
+        clipping.add_plane(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 2.0, 0.0)).unwrap();
+        assert!(clipping.is_active());
+        assert!(clipping.clips(Vec3::new(5.0, 0.5, 0.0)));
+        assert!(!clipping.clips(Vec3::new(5.0, 1.5, 0.0)));
+        assert!(clipping.add_plane(Vec3::ZERO, Vec3::ZERO).is_err());
+
+        clipping.section_box = Some(Aabb::new(Vec3::new(-1.0, -1.0, -1.0), Vec3::new(1.0, 2.0, 1.0)));
+        assert!(clipping.clips(Vec3::new(5.0, 1.5, 0.0)));
+        assert!(!clipping.clips(Vec3::new(0.0, 1.5, 0.0)));
+
+        for _ in 1..MAX_CLIP_PLANES {
+            clipping.add_plane(Vec3::ZERO, Vec3::UNIT_X).unwrap();
+        }
+        assert!(clipping.add_plane(Vec3::ZERO, Vec3::UNIT_X).is_err());
+
+        let mut settings = RenderSettings::default();
+        assert!(settings.change(SettingChange::Clipping(clipping)));
+        assert!(!settings.change(SettingChange::Clipping(clipping)));
+    }
+}
@@ -0,0 +1,244 @@
+// src/graphics/render_targets.rs
+//
+// Framebuffers intermedios que maneja el grafo de pases. Un pase declara en
+// `RenderPass::targets` qué recursos escribe y con qué formato; el grafo los
+// crea la primera vez que hacen falta, los recrea cuando cambia el tamaño del
+// viewport y reutiliza el mismo framebuffer para recursos con el mismo
+// formato cuyas vidas no se superponen (el último pase que lee uno va antes
+// del primero que escribe el otro).
+
+use std::ptr;
+
+use crate::graphics::gpu_resources::ContextGeneration;
+use crate::graphics::render_graph::ResourceId;
+
+/// Formato de un render target, relativo al viewport
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderTargetDesc {
+    /// Formato interno del color (gl::RGBA16F, gl::RGBA8, gl::R32UI...)
+    pub format: u32,
+    /// Fracción del tamaño del viewport (0.5 = mitad de resolución)
+    pub scale: f32,
+    /// Con textura de profundidad además del color
+    pub depth: bool,
+}
+
+impl RenderTargetDesc {
+    pub const fn color(format: u32) -> Self {
+        Self { format, scale: 1.0, depth: false }
+    }
+
+    pub const fn color_depth(format: u32) -> Self {
+        Self { format, scale: 1.0, depth: true }
+    }
+
+    /// Tamaño en píxeles para un viewport (como mínimo 1 x 1)
+    pub fn size(&self, viewport: (i32, i32)) -> (i32, i32) {
+        let scaled = |v: i32| ((v as f32 * self.scale).round() as i32).max(1);
+        (scaled(viewport.0), scaled(viewport.1))
+    }
+}
+
+/// Framebuffer con una textura de color y, si se pidió, una de profundidad
+pub struct RenderTarget {
+    pub fbo: u32,
+    pub color: u32,
+    /// 0 si el target no tiene profundidad
+    pub depth: u32,
+    pub width: i32,
+    pub height: i32,
+    pub desc: RenderTargetDesc,
+    generation: ContextGeneration,
+}
+
+/// Formato y tipo de transferencia válidos para crear la textura sin datos
+fn pixel_transfer(format: u32) -> (u32, u32) {
+    match format {
+        gl::R32UI | gl::R16UI | gl::R8UI => (gl::RED_INTEGER, gl::UNSIGNED_INT),
+        gl::RG32UI => (gl::RG_INTEGER, gl::UNSIGNED_INT),
+        gl::RGBA32UI => (gl::RGBA_INTEGER, gl::UNSIGNED_INT),
+        _ => (gl::RGBA, gl::FLOAT),
+    }
+}
+
+impl RenderTarget {
+    fn new(desc: RenderTargetDesc, viewport: (i32, i32)) -> Result<Self, String> {
+        let (width, height) = desc.size(viewport);
+        let mut target = Self { fbo: 0, color: 0, depth: 0, width, height, desc, generation: ContextGeneration::current() };
+        let (transfer_format, transfer_type) = pixel_transfer(desc.format);
+        unsafe {
+            gl::GenFramebuffers(1, &mut target.fbo);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, target.fbo);
+
+            gl::GenTextures(1, &mut target.color);
+            gl::BindTexture(gl::TEXTURE_2D, target.color);
+            gl::TexImage2D(
+                gl::TEXTURE_2D, 0, desc.format as i32, width, height, 0,
+                transfer_format, transfer_type, ptr::null(),
+            );
+            let filter = if transfer_format == gl::RGBA { gl::LINEAR } else { gl::NEAREST };
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, filter as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, filter as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0, gl::TEXTURE_2D, target.color, 0);
+
+            if desc.depth {
+                // Textura (no renderbuffer) para que otro pase pueda leer la profundidad
+                gl::GenTextures(1, &mut target.depth);
+                gl::BindTexture(gl::TEXTURE_2D, target.depth);
+                gl::TexImage2D(
+                    gl::TEXTURE_2D, 0, gl::DEPTH_COMPONENT24 as i32, width, height, 0,
+                    gl::DEPTH_COMPONENT, gl::FLOAT, ptr::null(),
+                );
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+                gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, gl::TEXTURE_2D, target.depth, 0);
+            }
+
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            if status != gl::FRAMEBUFFER_COMPLETE {
+                return Err(format!("Render target incompleto (0x{:x})", status));
+            }
+        }
+        Ok(target)
+    }
+
+    /// Enlaza el framebuffer y ajusta el viewport a su tamaño
+    pub fn bind(&self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.fbo);
+            gl::Viewport(0, 0, self.width, self.height);
+        }
+    }
+}
+
+impl Drop for RenderTarget {
+    fn drop(&mut self) {
+        if !self.generation.is_current() {
+            return;
+        }
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.fbo);
+            gl::DeleteTextures(1, &self.color);
+            if self.depth != 0 {
+                gl::DeleteTextures(1, &self.depth);
+            }
+        }
+    }
+}
+
+/// Uso de un recurso en el frame: formato y rango de pases [primero que lo
+/// escribe, último que lo lee]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TargetUsage {
+    pub resource: ResourceId,
+    pub desc: RenderTargetDesc,
+    pub first: usize,
+    pub last: usize,
+}
+
+/// Asigna un framebuffer físico a cada uso. Dos recursos comparten
+/// framebuffer si tienen el mismo formato y uno termina antes de que empiece
+/// el otro. Devuelve (framebuffer de cada uso, formato de cada framebuffer).
+pub fn plan_aliases(usages: &[TargetUsage]) -> (Vec<usize>, Vec<RenderTargetDesc>) {
+    let mut order: Vec<usize> = (0..usages.len()).collect();
+    order.sort_by_key(|&i| usages[i].first);
+
+    let mut assignment = vec![0; usages.len()];
+    // Por framebuffer: formato y último pase que lo usa
+    let mut slots: Vec<(RenderTargetDesc, usize)> = Vec::new();
+    for i in order {
+        let usage = &usages[i];
+        let free = slots.iter().position(|(desc, busy_until)| *desc == usage.desc && *busy_until < usage.first);
+        let slot = match free {
+            Some(slot) => slot,
+            None => {
+                slots.push((usage.desc, 0));
+                slots.len() - 1
+            }
+        };
+        slots[slot].1 = usage.last;
+        assignment[i] = slot;
+    }
+    (assignment, slots.into_iter().map(|(desc, _)| desc).collect())
+}
+
+/// Framebuffers del grafo y a qué recurso corresponde cada uno este frame
+#[derive(Default)]
+pub struct TargetPool {
+    targets: Vec<RenderTarget>,
+    bindings: Vec<(ResourceId, usize)>,
+}
+
+impl TargetPool {
+    /// Deja listos los framebuffers para los usos del frame: crea los que
+    /// faltan, recrea los que cambiaron de formato o de tamaño y libera los que
+    /// sobran
+    pub fn prepare(&mut self, usages: &[TargetUsage], viewport: (i32, i32)) -> Result<(), String> {
+        let (assignment, descs) = plan_aliases(usages);
+        self.targets.truncate(descs.len());
+        for (slot, desc) in descs.into_iter().enumerate() {
+            let current = self.targets.get(slot);
+            if current.is_some_and(|t| t.desc == desc && (t.width, t.height) == desc.size(viewport)) {
+                continue;
+            }
+            let target = RenderTarget::new(desc, viewport)?;
+            if slot < self.targets.len() {
+                self.targets[slot] = target;
+            } else {
+                self.targets.push(target);
+            }
+        }
+        self.bindings = usages.iter().zip(assignment).map(|(usage, slot)| (usage.resource, slot)).collect();
+        Ok(())
+    }
+
+    /// Framebuffer asignado al recurso en este frame
+    pub fn get(&self, resource: ResourceId) -> Option<&RenderTarget> {
+        let (_, slot) = self.bindings.iter().find(|(id, _)| *id == resource)?;
+        self.targets.get(*slot)
+    }
+
+    /// Cantidad de framebuffers creados (menor o igual a la de recursos)
+    pub fn len(&self) -> usize {
+        self.targets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(resource: ResourceId, desc: RenderTargetDesc, first: usize, last: usize) -> TargetUsage {
+        TargetUsage { resource, desc, first, last }
+    }
+
+    #[test]
+    fn test_aliasing_reuses_disjoint_targets() {
+        let hdr = RenderTargetDesc::color(gl::RGBA16F);
+        let half = RenderTargetDesc { scale: 0.5, ..hdr };
+        let usages = [
+            // ssao (1 -> 2) y bloom (3 -> 4) no se pisan; blur (2 -> 3) se superpone con los dos
+            usage("ssao", hdr, 1, 2),
+            usage("blur", hdr, 2, 3),
+            usage("bloom", hdr, 3, 4),
+            usage("bloom_half", half, 3, 4),
+        ];
+        let (assignment, descs) = plan_aliases(&usages);
+        assert_eq!(assignment[0], assignment[2]);
+        assert_ne!(assignment[0], assignment[1]);
+        assert_ne!(assignment[3], assignment[0]);
+        assert_eq!(descs.len(), 3);
+
+        assert_eq!(half.size((801, 600)), (401, 300));
+        assert_eq!(half.size((1, 0)), (1, 1));
+    }
+}
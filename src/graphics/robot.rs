@@ -0,0 +1,319 @@
+// src/graphics/robot.rs
+//
+// Robots articulados (los de un URDF, ver `graphics::loaders::urdf`): un árbol
+// de eslabones (links) unidos por articulaciones (joints) que giran o se
+// deslizan sobre un eje. Cada articulación guarda su posición actual (radianes
+// o metros, como en URDF) y la cinemática directa arma la transform de cada
+// eslabón desde la raíz. Las posiciones se cambian de golpe, se llevan hasta
+// un destino a la velocidad máxima de cada articulación o se reproducen de
+// una trayectoria (ver `graphics::trajectory`) con `Robot::update`, cada
+// frame. Las piezas que se ven son `SceneObject` comunes
+// enganchados a un eslabón con `LinkAttachment`: la escena les reescribe la
+// `transform` cada vez que cambia una articulación, así que se
+// seleccionan, colorean y agrupan como cualquier otro objeto.
+
+use crate::graphics::trajectory::{JointTrajectory, TrajectoryPlayer};
+use crate::math::{matrix_4_by_4::Matrix4, quaternion::Quat, vec3::Vec3};
+
+/// Velocidad de `Robot::move_joint` en las articulaciones sin una propia,
+/// en radianes o metros por segundo
+pub const DEFAULT_JOINT_SPEED: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JointKind {
+    /// Une dos eslabones sin movimiento
+    Fixed,
+    /// Gira sobre su eje entre dos límites
+    Revolute,
+    /// Gira sobre su eje sin límites
+    Continuous,
+    /// Se desliza sobre su eje entre dos límites
+    Prismatic,
+}
+
+impl JointKind {
+    /// Nombre del atributo `type` de URDF; `floating` y `planar` no se mueven
+    pub fn parse(text: &str) -> Option<Self> {
+        match text {
+            "fixed" | "floating" | "planar" => Some(Self::Fixed),
+            "revolute" => Some(Self::Revolute),
+            "continuous" => Some(Self::Continuous),
+            "prismatic" => Some(Self::Prismatic),
+            _ => None,
+        }
+    }
+
+    pub fn is_movable(self) -> bool {
+        self != Self::Fixed
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RobotJoint {
+    pub name: String,
+    pub kind: JointKind,
+    /// Índices en `Robot::links`
+    pub parent: usize,
+    pub child: usize,
+    /// Transform del hijo respecto del padre con la articulación en cero
+    pub origin: Matrix4,
+    /// Eje de giro o desplazamiento, unitario, en el sistema del hijo
+    pub axis: Vec3,
+    /// (mínimo, máximo) de las de giro y desplazamiento
+    pub limits: Option<(f32, f32)>,
+    /// Radianes o metros
+    pub position: f32,
+    /// Velocidad máxima en radianes o metros por segundo (el `velocity` de URDF)
+    pub velocity: Option<f32>,
+    /// Destino al que se está moviendo (ver `Robot::move_joint`)
+    pub target: Option<f32>,
+}
+
+impl RobotJoint {
+    /// `position` recortada a los límites, si tiene
+    pub fn clamp(&self, position: f32) -> f32 {
+        match self.limits {
+            Some((lower, upper)) => position.clamp(lower, upper.max(lower)),
+            None => position,
+        }
+    }
+
+    /// Transform del hijo respecto del padre en la posición actual
+    pub fn transform(&self) -> Matrix4 {
+        let motion = match self.kind {
+            JointKind::Fixed => return self.origin,
+            JointKind::Revolute | JointKind::Continuous => Quat::from_axis_angle(self.axis, self.position).to_matrix(),
+            JointKind::Prismatic => {
+                let offset = self.axis * self.position;
+                Matrix4::translate(offset.x, offset.y, offset.z)
+            }
+        };
+        self.origin.multiply(&motion)
+    }
+}
+
+/// Dónde va un `SceneObject` de un robot: su `transform` es la del
+/// eslabón por `offset`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkAttachment {
+    pub robot: String,
+    pub link: String,
+    /// Origen y escala de la pieza en el sistema del eslabón
+    pub offset: Matrix4,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Robot {
+    pub name: String,
+    /// Archivo del que se leyó (para guardarlo con la escena)
+    pub source: Option<String>,
+    /// Dónde está la raíz en la escena
+    pub base: Matrix4,
+    links: Vec<String>,
+    /// Los padres antes que los hijos
+    joints: Vec<RobotJoint>,
+    /// Trayectoria en reproducción (ver `play_trajectory`)
+    pub player: Option<TrajectoryPlayer>,
+}
+
+impl Robot {
+    /// Arma el árbol y ordena las articulaciones desde la raíz. Falla si un
+    /// eslabón tiene dos padres, si no hay una única raíz o si hay ciclos.
+    pub fn new(name: &str, links: Vec<String>, joints: Vec<RobotJoint>) -> Result<Self, String> {
+        if links.is_empty() {
+            return Err(format!("El robot '{}' no tiene eslabones", name));
+        }
+        let mut parent_joint = vec![None; links.len()];
+        for (index, joint) in joints.iter().enumerate() {
+            if joint.parent >= links.len() || joint.child >= links.len() {
+                return Err(format!("La articulación '{}' une eslabones inexistentes", joint.name));
+            }
+            if parent_joint[joint.child].replace(index).is_some() {
+                return Err(format!("El eslabón '{}' tiene más de un padre", links[joint.child]));
+            }
+        }
+        let roots: Vec<usize> = (0..links.len()).filter(|&link| parent_joint[link].is_none()).collect();
+        if roots.len() != 1 {
+            let names: Vec<&str> = roots.iter().map(|&link| links[link].as_str()).collect();
+            return Err(format!("El robot '{}' necesita una sola raíz y tiene: {:?}", name, names));
+        }
+
+        // Recorrido en anchura desde la raíz: los ciclos quedan afuera
+        let mut ordered = Vec::with_capacity(joints.len());
+        let mut reached = vec![roots[0]];
+        let mut next = 0;
+        while next < reached.len() {
+            let link = reached[next];
+            next += 1;
+            for joint in joints.iter().filter(|joint| joint.parent == link) {
+                reached.push(joint.child);
+                ordered.push(joint.clone());
+            }
+        }
+        if ordered.len() != joints.len() {
+            return Err(format!("Las articulaciones del robot '{}' forman un ciclo", name));
+        }
+        Ok(Self { name: name.to_string(), source: None, base: Matrix4::identity(), links, joints: ordered, player: None })
+    }
+
+    pub fn links(&self) -> &[String] {
+        &self.links
+    }
+
+    pub fn joints(&self) -> &[RobotJoint] {
+        &self.joints
+    }
+
+    pub fn find_link(&self, name: &str) -> Option<usize> {
+        self.links.iter().position(|link| link == name)
+    }
+
+    /// Articulación móvil `name`
+    fn movable_joint(&mut self, name: &str) -> Result<&mut RobotJoint, String> {
+        let robot = &self.name;
+        let joint = self
+            .joints
+            .iter_mut()
+            .find(|joint| joint.name == name)
+            .ok_or_else(|| format!("El robot '{}' no tiene la articulación '{}'", robot, name))?;
+        if !joint.kind.is_movable() {
+            return Err(format!("La articulación '{}' es fija", name));
+        }
+        Ok(joint)
+    }
+
+    /// Mueve una articulación de golpe, recortada a sus límites (y cancela
+    /// su `move_joint`); devuelve la posición que quedó. Las fijas no se pueden mover.
+    pub fn set_joint_position(&mut self, name: &str, position: f32) -> Result<f32, String> {
+        let joint = self.movable_joint(name)?;
+        joint.position = joint.clamp(position);
+        joint.target = None;
+        Ok(joint.position)
+    }
+
+    /// Lleva una articulación hacia `target` (recortado a sus límites) a su
+    /// velocidad máxima, de a poco en cada `update`; devuelve el destino
+    pub fn move_joint(&mut self, name: &str, target: f32) -> Result<f32, String> {
+        let joint = self.movable_joint(name)?;
+        let target = joint.clamp(target);
+        joint.target = Some(target);
+        Ok(target)
+    }
+
+    /// Reproduce una trayectoria desde el principio. Todas sus columnas tienen
+    /// que ser articulaciones móviles del robot; las demás quedan como están.
+    pub fn play_trajectory(&mut self, trajectory: JointTrajectory, looping: bool) -> Result<(), String> {
+        for name in trajectory.joints() {
+            self.movable_joint(name)?;
+        }
+        self.player = Some(TrajectoryPlayer::new(trajectory, looping));
+        Ok(())
+    }
+
+    /// Corta la trayectoria y los `move_joint` en curso; las articulaciones
+    /// quedan donde están
+    pub fn stop(&mut self) {
+        self.player = None;
+        for joint in &mut self.joints {
+            joint.target = None;
+        }
+    }
+
+    /// Avanza la trayectoria y los `move_joint` en curso `dt` segundos.
+    /// Devuelve si se movió alguna articulación.
+    pub fn update(&mut self, dt: f32) -> bool {
+        let mut moved = false;
+        if let Some(player) = &mut self.player {
+            for (name, position) in player.update(dt) {
+                if let Some(joint) = self.joints.iter_mut().find(|joint| joint.name == name) {
+                    joint.position = joint.clamp(position);
+                    joint.target = None;
+                    moved = true;
+                }
+            }
+        }
+        for joint in &mut self.joints {
+            let Some(target) = joint.target else { continue };
+            let step = joint.velocity.filter(|&v| v > 0.0).unwrap_or(DEFAULT_JOINT_SPEED) * dt;
+            let remaining = target - joint.position;
+            if remaining.abs() <= step {
+                joint.position = target;
+                joint.target = None;
+            } else {
+                joint.position += step.copysign(remaining);
+            }
+            moved = true;
+        }
+        moved
+    }
+
+    /// Transform de cada eslabón en la escena (`base` por la cadena desde la
+    /// raíz), en el orden de `links`
+    pub fn link_transforms(&self) -> Vec<Matrix4> {
+        let mut transforms = vec![self.base; self.links.len()];
+        for joint in &self.joints {
+            transforms[joint.child] = transforms[joint.parent].multiply(&joint.transform());
+        }
+        transforms
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn joint(name: &str, kind: JointKind, parent: usize, child: usize, origin: Matrix4, axis: Vec3) -> RobotJoint {
+        RobotJoint { name: name.to_string(), kind, parent, child, origin, axis, limits: None, position: 0.0, velocity: None, target: None }
+    }
+
+    #[test]
+    fn test_forward_kinematics() {
+        // base -> (hombro, gira en Z) -> brazo de 1 m -> (deslizador en X) -> pinza.
+        // Las articulaciones vienen desordenadas.
+        let links = ["base", "brazo", "pinza"].map(str::to_string).to_vec();
+        let mut slider = joint("deslizador", JointKind::Prismatic, 1, 2, Matrix4::translate(1.0, 0.0, 0.0), Vec3::UNIT_X);
+        slider.limits = Some((0.0, 0.5));
+        let shoulder = joint("hombro", JointKind::Revolute, 0, 1, Matrix4::translate(0.0, 0.0, 0.5), Vec3::UNIT_Z);
+        let mut robot = Robot::new("brazo", links.clone(), vec![slider, shoulder]).unwrap();
+        assert_eq!(robot.joints()[0].name, "hombro");
+
+        let tip = |robot: &Robot| robot.link_transforms()[2].transform_point(Vec3::ZERO);
+        assert!(tip(&robot).approx_eq(&Vec3::new(1.0, 0.0, 0.5), 1e-5));
+        robot.set_joint_position("hombro", std::f32::consts::FRAC_PI_2).unwrap();
+        assert_eq!(robot.set_joint_position("deslizador", 2.0), Ok(0.5));
+        assert!(tip(&robot).approx_eq(&Vec3::new(0.0, 1.5, 0.5), 1e-5));
+        assert!(robot.set_joint_position("codo", 0.0).is_err());
+
+        // Hacia un destino a 1 rad/s (sin velocidad propia), de a poco
+        assert_eq!(robot.move_joint("hombro", 0.0), Ok(0.0));
+        assert!(robot.update(1.0));
+        assert!((robot.joints()[0].position - (std::f32::consts::FRAC_PI_2 - 1.0)).abs() < 1e-5);
+        assert!(robot.update(1.0) && !robot.update(1.0));
+        assert_eq!((robot.joints()[0].position, robot.joints()[0].target), (0.0, None));
+
+        // La trayectoria se recorta a los límites y valida las columnas
+        let trajectory = JointTrajectory::parse_csv("t,deslizador\n0,0\n1,1\n").unwrap();
+        robot.play_trajectory(trajectory, false).unwrap();
+        assert!(robot.update(0.25));
+        assert_eq!(robot.joints()[1].position, 0.25);
+        robot.update(0.5);
+        assert_eq!(robot.joints()[1].position, 0.5);
+        robot.stop();
+        assert!(!robot.update(1.0));
+        let fixed = JointTrajectory::parse_csv("t,codo\n0,0\n").unwrap();
+        assert!(robot.play_trajectory(fixed, false).is_err());
+
+        // Un eslabón con dos padres deja dos raíces... o un ciclo
+        let twice = vec![
+            joint("a", JointKind::Fixed, 0, 1, Matrix4::identity(), Vec3::UNIT_X),
+            joint("b", JointKind::Fixed, 2, 1, Matrix4::identity(), Vec3::UNIT_X),
+        ];
+        assert!(Robot::new("mal", links.clone(), twice).is_err());
+        let cycle = vec![
+            joint("a", JointKind::Fixed, 1, 2, Matrix4::identity(), Vec3::UNIT_X),
+            joint("b", JointKind::Fixed, 2, 1, Matrix4::identity(), Vec3::UNIT_X),
+        ];
+        assert!(Robot::new("mal", links, cycle).is_err());
+    }
+}
@@ -0,0 +1,233 @@
+// src/graphics/scale_bar.rs
+//
+// Barra de escala abajo a la izquierda, para que una captura muestre el
+// tamaño real de las piezas. El largo sale de cuántos píxeles ocupa una
+// unidad a la profundidad del centro de lo que se ve, y se redondea a 1, 2 o
+// 5 por una potencia de 10 en las unidades de la escena (`Scene::units`, que
+// se guarda en el archivo de escena; por defecto milímetros, como los STL).
+// La etiqueta pasa a la unidad más grande del mismo sistema en que el número
+// queda redondo: 20 mm se muestra como "2 cm", 24 in como "2 ft".
+//
+// El renderer la recalcula en cada frame y en cada captura con el tamaño de
+// esa imagen (ver `Renderer::set_scale_bar`), así sale bien en capturas más
+// grandes que la ventana.
+
+use serde::{Deserialize, Serialize};
+
+use crate::graphics::scene_object::SceneObject;
+use crate::graphics::text::{ScreenRect, TextPanel};
+use crate::math::{aabb::Aabb, matrix_4_by_4::Matrix4};
+
+/// Capa del `TextOverlay` donde se dibuja la barra
+pub const SCALE_BAR_LAYER: &str = "escala";
+
+/// Ancho máximo de la barra en píxeles lógicos (el mínimo es 2/5 de esto)
+const MAX_WIDTH: f32 = 200.0;
+const BAR_HEIGHT: f32 = 6.0;
+/// Tramos alternados blanco / negro
+const SEGMENTS: usize = 4;
+const MARGIN: f32 = 16.0;
+/// Alto del panel de la etiqueta (una línea de `TEXT_SIZE` con su relleno)
+const LABEL_HEIGHT: f32 = 36.0;
+
+/// Qué mide una unidad de los modelos
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LengthUnit {
+    #[default]
+    Millimeters,
+    Centimeters,
+    Meters,
+    Inches,
+    Feet,
+}
+
+impl LengthUnit {
+    pub const ALL: [LengthUnit; 5] = [Self::Millimeters, Self::Centimeters, Self::Meters, Self::Inches, Self::Feet];
+
+    pub fn meters(self) -> f64 {
+        match self {
+            Self::Millimeters => 0.001,
+            Self::Centimeters => 0.01,
+            Self::Meters => 1.0,
+            Self::Inches => 0.0254,
+            Self::Feet => 0.3048,
+        }
+    }
+
+    /// `mm` milímetros en esta unidad
+    pub fn from_mm(self, mm: f32) -> f32 {
+        (mm as f64 * 0.001 / self.meters()) as f32
+    }
+
+    /// `length` en esta unidad pasado a milímetros
+    pub fn to_mm(self, length: f32) -> f32 {
+        (length as f64 * self.meters() * 1000.0) as f32
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Millimeters => "mm",
+            Self::Centimeters => "cm",
+            Self::Meters => "m",
+            Self::Inches => "in",
+            Self::Feet => "ft",
+        }
+    }
+
+    /// Unidades del mismo sistema, de menor a mayor
+    fn system(self) -> &'static [LengthUnit] {
+        match self {
+            Self::Millimeters | Self::Centimeters | Self::Meters => &[Self::Millimeters, Self::Centimeters, Self::Meters],
+            Self::Inches | Self::Feet => &[Self::Inches, Self::Feet],
+        }
+    }
+
+    /// `length` (en esta unidad) como texto, en la unidad más grande del
+    /// sistema en que da al menos 1 y con no más de tres decimales
+    pub fn format(self, length: f32) -> String {
+        let meters = length as f64 * self.meters();
+        let (value, unit) = self
+            .system()
+            .iter()
+            .rev()
+            .map(|&unit| (meters / unit.meters(), unit))
+            .find(|&(value, _)| value >= 1.0 && is_round(value))
+            .unwrap_or((length as f64, self));
+        let text = format!("{:.3}", value);
+        let text = text.trim_end_matches('0').trim_end_matches('.');
+        format!("{} {}", text, unit.symbol())
+    }
+}
+
+/// Sin más de tres decimales
+fn is_round(value: f64) -> bool {
+    let scaled = value * 1000.0;
+    (scaled - scaled.round()).abs() < 1e-6 * scaled.max(1.0)
+}
+
+/// El mayor 1, 2 o 5 por una potencia de 10 que no pasa de `max`
+pub fn nice_length(max: f32) -> f32 {
+    let power = 10f32.powf(max.log10().floor());
+    [5.0, 2.0, 1.0].into_iter().map(|step| step * power).find(|&length| length <= max * 1.0001).unwrap_or(power)
+}
+
+/// Profundidad de referencia: la del centro de los objetos visibles en
+/// coordenadas de vista, o su distancia a la cámara si queda detrás
+/// (caminando adentro de un edificio). None si no hay nada que medir.
+pub fn reference_depth(objects: &[SceneObject], view: &Matrix4, global_scale: f32) -> Option<f32> {
+    let bounds = objects
+        .iter()
+        .filter(|obj| !obj.hidden)
+        .fold(Aabb::EMPTY, |bounds, obj| bounds.union(&obj.world_bounds(global_scale)));
+    if bounds.is_empty() {
+        return None;
+    }
+    let center = view.transform_point(bounds.center());
+    let depth = if center.z < 0.0 { -center.z } else { center.magnitude() };
+    (depth > 0.0).then_some(depth)
+}
+
+/// Píxeles lógicos que ocupa una unidad de los modelos a `depth`, con una
+/// proyección perspectiva en una pantalla de `height` píxeles lógicos de alto
+pub fn pixels_per_unit(projection: &Matrix4, height: f32, depth: f32, global_scale: f32) -> f32 {
+    // m[5] = 1 / tan(fov_y / 2): a `depth` se ven 2 * depth / m[5] unidades de mundo
+    height * projection.m[5] / (2.0 * depth) * global_scale
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaleBar {
+    /// Largo en unidades de los modelos
+    pub length: f32,
+    /// Ancho en píxeles lógicos
+    pub width: f32,
+    pub label: String,
+}
+
+impl ScaleBar {
+    /// La barra más larga que entra en `MAX_WIDTH`
+    pub fn new(pixels_per_unit: f32, units: LengthUnit) -> Option<Self> {
+        if !(pixels_per_unit.is_finite() && pixels_per_unit > 0.0) {
+            return None;
+        }
+        let length = nice_length(MAX_WIDTH / pixels_per_unit);
+        Some(Self { length, width: length * pixels_per_unit, label: units.format(length) })
+    }
+
+    /// Etiqueta y barra abajo a la izquierda de una pantalla de `screen`
+    /// píxeles lógicos
+    pub fn overlay(&self, screen: (f32, f32)) -> (TextPanel, Vec<ScreenRect>) {
+        let left = MARGIN;
+        let top = screen.1 - MARGIN - BAR_HEIGHT;
+        // Borde oscuro para que se vea sobre fondos claros y oscuros
+        let mut rects = vec![ScreenRect {
+            min: (left - 1.0, top - 1.0),
+            max: (left + self.width + 1.0, top + BAR_HEIGHT + 1.0),
+            color: [0.0, 0.0, 0.0, 0.8],
+        }];
+        let segment = self.width / SEGMENTS as f32;
+        for index in 0..SEGMENTS {
+            let x = left + index as f32 * segment;
+            let shade = if index % 2 == 0 { 0.95 } else { 0.05 };
+            rects.push(ScreenRect {
+                min: (x, top),
+                max: (x + segment, top + BAR_HEIGHT),
+                color: [shade, shade, shade, 1.0],
+            });
+        }
+        let panel = TextPanel {
+            position: (left - 1.0, top - 4.0 - LABEL_HEIGHT),
+            background: [0.0, 0.0, 0.0, 0.5],
+            ..TextPanel::new(vec![self.label.clone()])
+        };
+        (panel, rects)
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::mesh::Mesh;
+
+    #[test]
+    fn test_nice_lengths_and_labels() {
+        assert_eq!(nice_length(7.3), 5.0);
+        assert_eq!(nice_length(199.0), 100.0);
+        assert_eq!(nice_length(2.0), 2.0);
+        assert!((nice_length(0.031) - 0.02).abs() < 1e-6);
+
+        assert_eq!(LengthUnit::Millimeters.format(20.0), "2 cm");
+        assert_eq!(LengthUnit::Millimeters.format(5.0), "5 mm");
+        assert_eq!(LengthUnit::Millimeters.format(0.5), "0.5 mm");
+        assert_eq!(LengthUnit::Millimeters.format(2000.0), "2 m");
+        assert_eq!(LengthUnit::Meters.format(0.05), "5 cm");
+        assert_eq!(LengthUnit::Inches.format(24.0), "2 ft");
+        assert_eq!(LengthUnit::Inches.format(50.0), "50 in");
+        assert_eq!(LengthUnit::Feet.format(0.5), "6 in");
+
+        // Pantalla de 600 px de alto, fov de 90°: a profundidad 3 se ven 6 unidades
+        let projection = Matrix4::perspective(90f32.to_radians(), 1.0, 0.01, 100.0);
+        let pixels = pixels_per_unit(&projection, 600.0, 3.0, 1.0);
+        assert!((pixels - 100.0).abs() < 1e-3);
+        let bar = ScaleBar::new(pixels, LengthUnit::Millimeters).unwrap();
+        assert_eq!((bar.length, bar.label.as_str()), (2.0, "2 mm"));
+        assert!((bar.width - 200.0).abs() < 1e-3);
+        // Con la escena al doble cada unidad ocupa el doble
+        let bar = ScaleBar::new(pixels_per_unit(&projection, 600.0, 3.0, 2.0), LengthUnit::Millimeters).unwrap();
+        assert_eq!(bar.length, 1.0);
+        assert!(ScaleBar::new(0.0, LengthUnit::Meters).is_none());
+
+        let (panel, rects) = bar.overlay((800.0, 600.0));
+        assert_eq!(panel.lines, vec!["1 mm".to_string()]);
+        assert_eq!(rects.len(), 1 + SEGMENTS);
+        assert_eq!(rects[1].min, (MARGIN, 600.0 - MARGIN - BAR_HEIGHT));
+
+        // Centro de los objetos visibles delante de la cámara
+        let mut obj = SceneObject::new(0, 0);
+        obj.set_mesh(Mesh::new(vec![[-1.0, -1.0, -6.0], [1.0, 1.0, -4.0], [1.0, -1.0, -4.0]], vec![0, 1, 2]));
+        let depth = reference_depth(std::slice::from_ref(&obj), &Matrix4::identity(), 1.0).unwrap();
+        assert!((depth - 5.0).abs() < 1e-4);
+        obj.hidden = true;
+        assert_eq!(reference_depth(&[obj], &Matrix4::identity(), 1.0), None);
+    }
+}
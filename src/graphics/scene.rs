@@ -0,0 +1,1379 @@
+// src/graphics/scene.rs
+//
+// Escena: los objetos cargados y los datos que se guardan junto a ellos
+// (bookmarks de vista, grupos, marcos de referencia, iluminación, unidades). Se persiste como
+// archivo RON que referencia los modelos por ruta.
+
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::graphics::bvh::{raycast_all_object, raycast_all_objects, raycast_object, raycast_objects, RayHit};
+use crate::graphics::camara::{Camera, CameraPose};
+use crate::graphics::color::distinct_colors;
+use crate::graphics::frames::ReferenceFrame;
+#[cfg(feature = "urdf")]
+use crate::graphics::loaders::urdf::{self, Geometry, UrdfModel};
+#[cfg(feature = "urdf")]
+use crate::graphics::robot::LinkAttachment;
+use crate::graphics::robot::Robot;
+use crate::graphics::trajectory::JointTrajectory;
+use crate::graphics::lighting::{Lighting, LightingRig};
+use crate::graphics::mesh::Mesh;
+use crate::graphics::mesh_ops::{merge, transformed};
+use crate::graphics::scale_bar::LengthUnit;
+use crate::graphics::scene_object::{file_stem, SceneObject, Weld};
+use crate::graphics::spatial::SceneBvh;
+use crate::math::{
+    aabb::Aabb, frustum::Frustum, matrix_4_by_4::Matrix4, quaternion::Quat, ray::Ray, transform::Transform, vec3::Vec3,
+};
+
+/// Pose de cámara guardada por el usuario
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViewBookmark {
+    pub name: String,
+    pub pose: CameraPose,
+}
+
+/// Objetos que se mueven, ocultan y colorean juntos (ver `Scene::create_group`).
+/// Los miembros se marcan con `SceneObject::group`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectGroup {
+    pub name: String,
+    /// Transformación del grupo; gira y escala alrededor del centro de los miembros
+    pub transform: Matrix4,
+    /// Todos los miembros están ocultos
+    pub hidden: bool,
+    /// Vista explotada: cada miembro se aleja del centro `explode` veces su distancia
+    pub explode: f32,
+}
+
+#[derive(Default)]
+pub struct Scene {
+    pub objects: Vec<SceneObject>,
+    pub bookmarks: Vec<ViewBookmark>,
+    /// Marcos de referencia con nombre (ver `graphics::frames`)
+    pub frames: Vec<ReferenceFrame>,
+    /// Robots de URDF; sus piezas son los objetos con `SceneObject::link`.
+    /// Las articulaciones se mueven con `set_joint_position`, que acomoda las piezas.
+    pub robots: Vec<Robot>,
+    /// Se modifican con los métodos de grupos, que actualizan a los miembros
+    groups: Vec<ObjectGroup>,
+    /// `SceneObject::hidden` de cada objeto antes de cada cambio de
+    /// visibilidad, el último al final (ver `undo_visibility`)
+    visibility_history: Vec<Vec<bool>>,
+    /// Iluminación armada (None: la de `Lighting::default`)
+    pub lighting_rig: Option<LightingRig>,
+    /// Qué mide una unidad de los modelos (ver `graphics::scale_bar`)
+    pub units: LengthUnit,
+    /// Cajas de los objetos para culling, rayos y colisiones (ver `update_spatial`)
+    spatial: SceneBvh,
+    /// `transform_version` de cada objeto en el último reajuste
+    spatial_versions: Vec<u64>,
+}
+
+/// Formato del archivo de escena
+#[derive(Serialize, Deserialize)]
+struct SceneFile {
+    objects: Vec<ObjectEntry>,
+    #[serde(default)]
+    bookmarks: Vec<ViewBookmark>,
+    #[serde(default)]
+    groups: Vec<GroupEntry>,
+    #[serde(default)]
+    frames: Vec<ReferenceFrame>,
+    /// Sus piezas no van en `objects`: se vuelven a leer del .urdf
+    #[serde(default)]
+    robots: Vec<RobotEntry>,
+    #[serde(default)]
+    lighting: Option<LightingRig>,
+    #[serde(default)]
+    units: LengthUnit,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GroupEntry {
+    name: String,
+    transform: [f32; 16],
+    #[serde(default)]
+    hidden: bool,
+    #[serde(default)]
+    explode: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ObjectEntry {
+    path: String,
+    #[serde(default)]
+    name: String,
+    /// Matriz de `SceneObject::transform`
+    transform: [f32; 16],
+    /// Giro continuo: eje por radianes por segundo
+    #[serde(default)]
+    angular_velocity: Vec3,
+    /// Escenas viejas: giro acumulado y velocidad de giro en Y, que se pasan
+    /// a `transform` y `angular_velocity` al abrirlas
+    #[serde(default, skip_serializing)]
+    angle: f32,
+    #[serde(default, skip_serializing)]
+    angular_speed: f32,
+    /// Color base sRGB propio
+    #[serde(default)]
+    color: Option<[f32; 3]>,
+    /// Nombre en la biblioteca de materiales
+    #[serde(default)]
+    material: Option<String>,
+    /// Nombre del grupo al que pertenece
+    #[serde(default)]
+    group: Option<String>,
+    #[serde(default)]
+    hidden: bool,
+    /// Soldadura de vértices al importar (sin ella, la de `WELD_ENV`)
+    #[serde(default)]
+    weld: Option<Weld>,
+    /// Punto de giro propio, en coordenadas de la malla
+    #[serde(default)]
+    pivot: Option<Vec3>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RobotEntry {
+    path: String,
+    base: [f32; 16],
+    /// Posición de cada articulación móvil
+    #[serde(default)]
+    joints: Vec<(String, f32)>,
+}
+
+/// Cambios de visibilidad que se pueden deshacer
+const VISIBILITY_HISTORY: usize = 64;
+
+impl Scene {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Caja en espacio mundo de todos los objetos
+    pub fn bounds(&self, global_scale: f32) -> Aabb {
+        self.objects
+            .iter()
+            .fold(Aabb::EMPTY, |acc, obj| acc.union(&obj.world_bounds(global_scale)))
+    }
+
+    /// Reajusta el BVH de la escena a la transformación actual de los objetos.
+    /// Llamar una vez por frame después de moverlos; mientras no se llame, las
+    /// consultas usan las posiciones anteriores.
+    pub fn update_spatial(&mut self, global_scale: f32) {
+        let bounds: Vec<Aabb> = self.objects.iter().map(|obj| obj.world_bounds(global_scale)).collect();
+        let versions: Vec<u64> = self.objects.iter().map(|obj| obj.transform_version()).collect();
+        // Nada se movió desde el último reajuste
+        if versions == self.spatial_versions && self.spatial_ready() {
+            return;
+        }
+        self.spatial.refit(&bounds);
+        self.spatial_versions = versions;
+    }
+
+    pub fn spatial(&self) -> &SceneBvh {
+        &self.spatial
+    }
+
+    /// El BVH corresponde a la lista de objetos actual (si no, se recorren todos)
+    fn spatial_ready(&self) -> bool {
+        self.spatial.len() == self.objects.len()
+    }
+
+    /// Corte más cercano de un rayo en espacio mundo con los objetos, con su
+    /// transformación actual. `RayHit::object_id` es el índice en `objects`.
+    pub fn raycast(&self, global_scale: f32, ray: &Ray) -> Option<RayHit> {
+        if !self.spatial_ready() {
+            return raycast_objects(&self.objects, global_scale, ray);
+        }
+        let length = ray.direction.magnitude();
+        let mut best: Option<RayHit> = None;
+        for (entry_t, index) in self.spatial.ray_candidates(ray, f32::INFINITY) {
+            let max_distance = best.map_or(f32::INFINITY, |hit| hit.distance);
+            // Las cajas vienen ordenadas: ninguna de las que siguen puede estar más cerca
+            if entry_t * length > max_distance {
+                break;
+            }
+            if let Some(hit) = raycast_object(&self.objects[index], index, global_scale, ray, max_distance) {
+                best = Some(hit);
+            }
+        }
+        best
+    }
+
+    /// Todos los cortes del rayo, del más cercano al más lejano
+    pub fn raycast_all(&self, global_scale: f32, ray: &Ray) -> Vec<RayHit> {
+        if !self.spatial_ready() {
+            return raycast_all_objects(&self.objects, global_scale, ray);
+        }
+        let mut hits: Vec<RayHit> = self
+            .spatial
+            .ray_candidates(ray, f32::INFINITY)
+            .into_iter()
+            .flat_map(|(_, index)| raycast_all_object(&self.objects[index], index, global_scale, ray))
+            .collect();
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    /// Qué objetos pueden verse desde el frustum (por índice en `objects`)
+    pub fn visible_objects(&self, frustum: &Frustum, global_scale: f32) -> Vec<bool> {
+        let mut visible = vec![false; self.objects.len()];
+        if self.spatial_ready() {
+            for index in self.spatial.frustum_objects(frustum) {
+                visible[index] = true;
+            }
+        } else {
+            for (flag, obj) in visible.iter_mut().zip(&self.objects) {
+                *flag = frustum.intersects_aabb(&obj.world_bounds(global_scale));
+            }
+        }
+        visible
+    }
+
+    /// Importa todos los modelos de una carpeta (orden alfabético), cada uno
+    /// nombrado como su archivo, y los reparte en una grilla. Los archivos que
+    /// fallan se informan y se saltan.
+    pub fn load_directory(path: &str) -> Result<Self, String> {
+        let mut scene = Scene::new();
+        let mut files = Vec::new();
+        for file in &model_files(path)? {
+            match load_model_file(&file.to_string_lossy()) {
+                Ok(objects) => {
+                    let start = scene.objects.len();
+                    scene.objects.extend(objects);
+                    files.push(start..scene.objects.len());
+                }
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+        if scene.objects.is_empty() {
+            return Err(format!("No se encontraron modelos en {}", path));
+        }
+        scene.layout_grid(&files, 0.1);
+        Ok(scene)
+    }
+
+    /// Reparte los objetos en una grilla cuadrada sobre el plano XZ, apoyados en
+    /// y = 0, una celda por rango de `cells` (los objetos de un archivo): dentro
+    /// de la celda los objetos se mueven juntos y no pierden su posición
+    /// relativa (un ensamble de STEP sigue armado). Cada celda mide lo que el
+    /// grupo más grande más `spacing` (relativo a ese tamaño).
+    pub fn layout_grid(&mut self, cells: &[Range<usize>], spacing: f32) {
+        let bounds: Vec<Aabb> = cells
+            .iter()
+            .map(|range| {
+                self.objects[range.clone()]
+                    .iter()
+                    .fold(Aabb::EMPTY, |all, obj| all.union(&obj.mesh.bvh().bounds()))
+            })
+            .collect();
+        let cell = bounds
+            .iter()
+            .filter(|b| !b.is_empty())
+            .map(|b| b.size().x.max(b.size().z))
+            .fold(0.0_f32, f32::max)
+            * (1.0 + spacing);
+        let columns = (cells.len() as f32).sqrt().ceil().max(1.0) as usize;
+        let rows = cells.len().div_ceil(columns);
+
+        // Centrar la grilla en el origen
+        let offset_x = (columns as f32 - 1.0) * cell * 0.5;
+        let offset_z = (rows as f32 - 1.0) * cell * 0.5;
+
+        for (index, (range, bounds)) in cells.iter().zip(&bounds).enumerate() {
+            let center = if bounds.is_empty() { Vec3::ZERO } else { bounds.center() };
+            let floor = if bounds.is_empty() { 0.0 } else { bounds.min.y };
+            let x = (index % columns) as f32 * cell - offset_x;
+            let z = (index / columns) as f32 * cell - offset_z;
+            for obj in &mut self.objects[range.clone()] {
+                obj.transform = Transform::from_translation(Vec3::new(x - center.x, -floor, z - center.z));
+            }
+        }
+    }
+
+    /// Guarda la pose actual de la cámara con un nombre (reemplaza si ya existe)
+    pub fn save_bookmark(&mut self, name: &str, camera: &Camera) {
+        let bookmark = ViewBookmark {
+            name: name.to_string(),
+            pose: camera.pose(),
+        };
+        match self.bookmarks.iter_mut().find(|b| b.name == name) {
+            Some(existing) => *existing = bookmark,
+            None => self.bookmarks.push(bookmark),
+        }
+    }
+
+    pub fn bookmark(&self, name: &str) -> Option<&ViewBookmark> {
+        self.bookmarks.iter().find(|b| b.name == name)
+    }
+
+    pub fn remove_bookmark(&mut self, name: &str) -> bool {
+        let before = self.bookmarks.len();
+        self.bookmarks.retain(|b| b.name != name);
+        self.bookmarks.len() != before
+    }
+
+    /// Agrega el marco o reemplaza al que tenga el mismo nombre
+    pub fn set_frame(&mut self, frame: ReferenceFrame) {
+        match self.frames.iter_mut().find(|f| f.name == frame.name) {
+            Some(existing) => *existing = frame,
+            None => self.frames.push(frame),
+        }
+    }
+
+    pub fn frame(&self, name: &str) -> Result<&ReferenceFrame, String> {
+        self.frames.iter().find(|f| f.name == name).ok_or_else(|| format!("No existe el marco '{}'", name))
+    }
+
+    /// El marco `name`, o uno en el origen de la escena sin girar
+    pub fn frame_or_scene(&self, name: Option<&str>) -> Result<ReferenceFrame, String> {
+        match name {
+            Some(name) => self.frame(name).cloned(),
+            None => Ok(ReferenceFrame::new("escena", Vec3::ZERO, Quat::IDENTITY)),
+        }
+    }
+
+    pub fn remove_frame(&mut self, name: &str) -> bool {
+        let before = self.frames.len();
+        self.frames.retain(|f| f.name != name);
+        self.frames.len() != before
+    }
+
+    /// Lleva la cámara (animada) al bookmark `name`
+    pub fn recall_bookmark(&self, name: &str, camera: &mut Camera) -> Result<(), String> {
+        let bookmark = self
+            .bookmark(name)
+            .ok_or_else(|| format!("No existe el bookmark '{}'", name))?;
+        camera.recall_pose(bookmark.pose);
+        Ok(())
+    }
+
+    /// Un color distinto para cada objeto (ver `color::distinct_colors`), así
+    /// las piezas de un ensamble se distinguen a simple vista
+    pub fn color_objects_distinct(&mut self) {
+        let colors = distinct_colors(self.objects.len());
+        for (obj, color) in self.objects.iter_mut().zip(colors) {
+            obj.color = Some(color);
+        }
+    }
+
+    /// Color base sRGB de un objeto (None vuelve al gris por defecto)
+    pub fn set_object_color(&mut self, index: usize, color: Option<[f32; 3]>) -> Result<(), String> {
+        let count = self.objects.len();
+        let obj = self
+            .objects
+            .get_mut(index)
+            .ok_or_else(|| format!("No hay objeto {} (la escena tiene {})", index, count))?;
+        obj.color = color;
+        Ok(())
+    }
+
+    /// Todos los objetos vuelven al color por defecto
+    pub fn clear_object_colors(&mut self) {
+        for obj in &mut self.objects {
+            obj.color = None;
+        }
+    }
+
+    /// Agrupa los objetos `members` bajo `name` (si ya había un grupo con ese
+    /// nombre se deshace antes). Un objeto está en un solo grupo: sale del que tenía.
+    pub fn create_group(&mut self, name: &str, members: &[usize]) -> Result<(), String> {
+        if name.is_empty() {
+            return Err("El grupo necesita un nombre".to_string());
+        }
+        if members.is_empty() {
+            return Err(format!("El grupo '{}' no tiene objetos", name));
+        }
+        if let Some(&index) = members.iter().find(|&&index| index >= self.objects.len()) {
+            return Err(format!("No hay objeto {} (la escena tiene {})", index, self.objects.len()));
+        }
+        self.ungroup(name);
+        for &index in members {
+            self.objects[index].group = Some(name.to_string());
+        }
+        self.groups.push(ObjectGroup {
+            name: name.to_string(),
+            transform: Matrix4::identity(),
+            hidden: false,
+            explode: 0.0,
+        });
+        self.apply_groups();
+        self.sync_group_visibility();
+        Ok(())
+    }
+
+    /// Un grupo por cada archivo de origen con varios objetos (los sólidos de
+    /// un STEP), nombrado como el archivo. Devuelve cuántos se crearon.
+    pub fn group_by_source(&mut self) -> usize {
+        let mut files: Vec<(String, Vec<usize>)> = Vec::new();
+        for (index, obj) in self.objects.iter().enumerate() {
+            let Some(file) = obj.source.as_deref().map(source_file) else { continue };
+            match files.iter_mut().find(|(name, _)| name == file) {
+                Some((_, members)) => members.push(index),
+                None => files.push((file.to_string(), vec![index])),
+            }
+        }
+        let mut created = 0;
+        for (file, members) in files.into_iter().filter(|(_, members)| members.len() > 1) {
+            if self.create_group(&file_stem(&file), &members).is_ok() {
+                created += 1;
+            }
+        }
+        created
+    }
+
+    /// Deshace el grupo: los miembros vuelven a su transformación propia
+    pub fn ungroup(&mut self, name: &str) -> bool {
+        let before = self.groups.len();
+        self.groups.retain(|group| group.name != name);
+        for obj in self.objects.iter_mut().filter(|obj| obj.group.as_deref() == Some(name)) {
+            obj.group = None;
+            obj.group_transform = Matrix4::identity();
+        }
+        self.groups.len() != before
+    }
+
+    pub fn groups(&self) -> &[ObjectGroup] {
+        &self.groups
+    }
+
+    pub fn group(&self, name: &str) -> Option<&ObjectGroup> {
+        self.groups.iter().find(|group| group.name == name)
+    }
+
+    /// Grupo del objeto `index`, si tiene
+    pub fn group_of(&self, index: usize) -> Option<&ObjectGroup> {
+        self.group(self.objects.get(index)?.group.as_deref()?)
+    }
+
+    /// Índices de los objetos del grupo
+    pub fn group_members(&self, name: &str) -> Vec<usize> {
+        (0..self.objects.len())
+            .filter(|&index| self.objects[index].group.as_deref() == Some(name))
+            .collect()
+    }
+
+    fn group_mut(&mut self, name: &str) -> Result<&mut ObjectGroup, String> {
+        self.groups
+            .iter_mut()
+            .find(|group| group.name == name)
+            .ok_or_else(|| format!("No existe el grupo '{}'", name))
+    }
+
+    /// Mueve, gira o escala todos los miembros juntos
+    pub fn set_group_transform(&mut self, name: &str, transform: Matrix4) -> Result<(), String> {
+        self.group_mut(name)?.transform = transform;
+        self.apply_groups();
+        Ok(())
+    }
+
+    /// Oculta o muestra todos los miembros (se deshace con `undo_visibility`)
+    pub fn set_group_hidden(&mut self, name: &str, hidden: bool) -> Result<(), String> {
+        self.group_mut(name)?;
+        self.push_visibility();
+        for obj in self.objects.iter_mut().filter(|obj| obj.group.as_deref() == Some(name)) {
+            obj.hidden = hidden;
+        }
+        self.sync_group_visibility();
+        Ok(())
+    }
+
+    /// Vista explotada del grupo (0 la vuelve a armar)
+    pub fn set_group_explode(&mut self, name: &str, explode: f32) -> Result<(), String> {
+        self.group_mut(name)?.explode = explode.max(0.0);
+        self.apply_groups();
+        Ok(())
+    }
+
+    /// Color base sRGB de todos los miembros (None vuelve al gris por defecto)
+    pub fn set_group_color(&mut self, name: &str, color: Option<[f32; 3]>) -> Result<(), String> {
+        self.group_mut(name)?;
+        for obj in self.objects.iter_mut().filter(|obj| obj.group.as_deref() == Some(name)) {
+            obj.color = color;
+        }
+        Ok(())
+    }
+
+    /// Pasa la transformación, la explosión y la visibilidad de cada grupo a
+    /// sus miembros. Los centros se toman de la posición actual de cada
+    /// objeto, así que mover un miembro por su cuenta también mueve el pivote.
+    fn apply_groups(&mut self) {
+        let objects = &self.objects;
+        // Grupos que se quedaron sin miembros
+        self.groups.retain(|group| objects.iter().any(|obj| obj.group.as_ref() == Some(&group.name)));
+        for group in &self.groups {
+            let members: Vec<usize> = (0..self.objects.len())
+                .filter(|&index| self.objects[index].group.as_ref() == Some(&group.name))
+                .collect();
+            let centers: Vec<Vec3> = members
+                .iter()
+                .map(|&index| {
+                    let obj = &self.objects[index];
+                    let local = obj.transform.matrix();
+                    let bounds = obj.mesh.bvh().bounds().transformed(&local);
+                    if bounds.is_empty() { local.transform_point(Vec3::ZERO) } else { bounds.center() }
+                })
+                .collect();
+            let center = centers.iter().fold(Vec3::ZERO, |sum, &c| sum + c) / centers.len() as f32;
+            let pivot = Matrix4::translate(center.x, center.y, center.z)
+                .multiply(&group.transform)
+                .multiply(&Matrix4::translate(-center.x, -center.y, -center.z));
+            for (&index, &member_center) in members.iter().zip(&centers) {
+                let offset = (member_center - center) * group.explode;
+                let obj = &mut self.objects[index];
+                obj.group_transform = pivot.multiply(&Matrix4::translate(offset.x, offset.y, offset.z));
+            }
+        }
+    }
+
+    /// Agrega un robot de un .urdf (ver `graphics::loaders::urdf`): un objeto
+    /// por pieza visual, enganchado a su eslabón. Con la escena vacía pasa a
+    /// metros, la unidad de URDF. Las mallas que no cargan se informan y se
+    /// saltean. Devuelve el nombre del robot (con un número si ya había otro).
+    #[cfg(feature = "urdf")]
+    pub fn load_urdf(&mut self, path: &str) -> Result<String, String> {
+        let UrdfModel { mut robot, visuals } = urdf::load(path)?;
+        let base_name = robot.name.clone();
+        let mut copy = 1;
+        while self.robots.iter().any(|other| other.name == robot.name) {
+            copy += 1;
+            robot.name = format!("{} {}", base_name, copy);
+        }
+        if self.objects.is_empty() {
+            self.units = LengthUnit::Meters;
+        }
+        for visual in visuals {
+            let (mut obj, scale) = match visual.geometry {
+                Geometry::Mesh { path, scale } => match load_mesh_file(&path) {
+                    Ok(obj) => (obj, scale),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        continue;
+                    }
+                },
+                Geometry::Shape(mesh) => (SceneObject::from_mesh(*mesh), Vec3::new(1.0, 1.0, 1.0)),
+            };
+            obj.name = visual.name;
+            if visual.color.is_some() {
+                obj.color = visual.color;
+            }
+            let offset = visual.origin.multiply(&Matrix4::from_trs(Vec3::ZERO, Quat::IDENTITY, scale));
+            obj.link = Some(LinkAttachment { robot: robot.name.clone(), link: visual.link, offset });
+            self.objects.push(obj);
+        }
+        let name = robot.name.clone();
+        self.robots.push(robot);
+        self.pose_robot(&name);
+        Ok(name)
+    }
+
+    #[cfg(not(feature = "urdf"))]
+    pub fn load_urdf(&mut self, path: &str) -> Result<String, String> {
+        Err(format!("No se pudo cargar {}: compilado sin la feature urdf", path))
+    }
+
+    pub fn robot(&self, name: &str) -> Result<&Robot, String> {
+        self.robots.iter().find(|robot| robot.name == name).ok_or_else(|| format!("No existe el robot '{}'", name))
+    }
+
+    fn robot_mut(&mut self, name: &str) -> Result<&mut Robot, String> {
+        self.robots.iter_mut().find(|robot| robot.name == name).ok_or_else(|| format!("No existe el robot '{}'", name))
+    }
+
+    /// Mueve una articulación (ver `Robot::set_joint_position`) y las piezas
+    /// que cuelgan de ella; devuelve la posición que quedó
+    pub fn set_joint_position(&mut self, robot: &str, joint: &str, position: f32) -> Result<f32, String> {
+        let position = self.robot_mut(robot)?.set_joint_position(joint, position)?;
+        self.pose_robot(robot);
+        Ok(position)
+    }
+
+    /// Lleva una articulación hacia `target` de a poco (ver `Robot::move_joint`
+    /// y `update_robots`); devuelve el destino
+    pub fn move_joint(&mut self, robot: &str, joint: &str, target: f32) -> Result<f32, String> {
+        self.robot_mut(robot)?.move_joint(joint, target)
+    }
+
+    /// Reproduce en el robot la trayectoria del CSV `path` (ver
+    /// `graphics::trajectory`); devuelve su duración en segundos
+    pub fn play_trajectory(&mut self, robot: &str, path: &str, looping: bool) -> Result<f32, String> {
+        let trajectory = JointTrajectory::load(path)?;
+        let duration = trajectory.duration();
+        self.robot_mut(robot)?.play_trajectory(trajectory, looping)?;
+        Ok(duration)
+    }
+
+    /// Corta la trayectoria y los `move_joint` del robot donde estén
+    pub fn stop_robot(&mut self, robot: &str) -> Result<(), String> {
+        self.robot_mut(robot)?.stop();
+        Ok(())
+    }
+
+    /// Avanza las trayectorias y movimientos de articulaciones `dt` segundos y
+    /// mueve las piezas de los robots que cambiaron
+    pub fn update_robots(&mut self, dt: f32) {
+        let moved: Vec<String> = self.robots.iter_mut().filter_map(|robot| robot.update(dt).then(|| robot.name.clone())).collect();
+        for name in moved {
+            self.pose_robot(&name);
+        }
+    }
+
+    /// Lleva la raíz del robot a `base` y mueve sus piezas
+    pub fn set_robot_base(&mut self, robot: &str, base: Matrix4) -> Result<(), String> {
+        self.robot_mut(robot)?.base = base;
+        self.pose_robot(robot);
+        Ok(())
+    }
+
+    /// Reescribe la `transform` de las piezas del robot `name`
+    fn pose_robot(&mut self, name: &str) {
+        let Some(robot) = self.robots.iter().find(|robot| robot.name == name) else { return };
+        let transforms = robot.link_transforms();
+        for obj in &mut self.objects {
+            let Some(link) = obj.link.as_ref().filter(|link| link.robot == name) else { continue };
+            if let Some(index) = robot.find_link(&link.link) {
+                obj.transform = Transform::from_matrix(&transforms[index].multiply(&link.offset));
+            }
+        }
+    }
+
+    pub fn object(&self, index: usize) -> Result<&SceneObject, String> {
+        self.check_indices(&[index])?;
+        Ok(&self.objects[index])
+    }
+
+    pub fn object_mut(&mut self, index: usize) -> Result<&mut SceneObject, String> {
+        self.check_indices(&[index])?;
+        Ok(&mut self.objects[index])
+    }
+
+    fn check_indices(&self, ids: &[usize]) -> Result<(), String> {
+        match ids.iter().find(|&&index| index >= self.objects.len()) {
+            Some(index) => Err(format!("No hay objeto {} (la escena tiene {})", index, self.objects.len())),
+            None => Ok(()),
+        }
+    }
+
+    /// Guarda la visibilidad actual para poder volver a ella
+    fn push_visibility(&mut self) {
+        if self.visibility_history.len() == VISIBILITY_HISTORY {
+            self.visibility_history.remove(0);
+        }
+        self.visibility_history.push(self.objects.iter().map(|obj| obj.hidden).collect());
+    }
+
+    /// Un grupo está oculto si lo están todos sus miembros
+    fn sync_group_visibility(&mut self) {
+        for group in &mut self.groups {
+            let mut members = self.objects.iter().filter(|obj| obj.group.as_ref() == Some(&group.name));
+            group.hidden = members.all(|obj| obj.hidden);
+        }
+    }
+
+    /// Oculta los objetos `ids`
+    pub fn hide(&mut self, ids: &[usize]) -> Result<(), String> {
+        self.check_indices(ids)?;
+        self.push_visibility();
+        for &index in ids {
+            self.objects[index].hidden = true;
+        }
+        self.sync_group_visibility();
+        Ok(())
+    }
+
+    /// Deja a la vista solo los objetos `ids` (ocultos o no) y oculta el resto
+    pub fn isolate(&mut self, ids: &[usize]) -> Result<(), String> {
+        if ids.is_empty() {
+            return Err("No hay nada que aislar".to_string());
+        }
+        self.check_indices(ids)?;
+        self.push_visibility();
+        for (index, obj) in self.objects.iter_mut().enumerate() {
+            obj.hidden = !ids.contains(&index);
+        }
+        self.sync_group_visibility();
+        Ok(())
+    }
+
+    /// Muestra todos los objetos
+    pub fn show_all(&mut self) {
+        if self.objects.iter().all(|obj| !obj.hidden) {
+            return;
+        }
+        self.push_visibility();
+        for obj in &mut self.objects {
+            obj.hidden = false;
+        }
+        self.sync_group_visibility();
+    }
+
+    /// Vuelve a la visibilidad anterior al último `hide`, `isolate`, `show_all`
+    /// o `set_group_hidden`. false si no hay nada que deshacer (o la lista de
+    /// objetos cambió desde entonces y el historial ya no vale).
+    pub fn undo_visibility(&mut self) -> bool {
+        let Some(previous) = self.visibility_history.pop() else { return false };
+        if previous.len() != self.objects.len() {
+            self.visibility_history.clear();
+            return false;
+        }
+        for (obj, hidden) in self.objects.iter_mut().zip(previous) {
+            obj.hidden = hidden;
+        }
+        self.sync_group_visibility();
+        true
+    }
+
+    /// Reemplaza el objeto `index` por una pieza por cada parte suelta de su
+    /// malla (ver `Mesh::split_components`), en el mismo lugar de la lista.
+    /// Las piezas conservan la transformación, el material, el grupo y la
+    /// visibilidad del original, se llaman "nombre_1", "nombre_2"... y cada
+    /// una tiene su color. Se guardan en la escena como `archivo.stl#índice`.
+    /// Devuelve cuántas piezas quedaron.
+    pub fn split_object(&mut self, index: usize) -> Result<usize, String> {
+        self.check_indices(&[index])?;
+        let original = &self.objects[index];
+        let parts = original.mesh.split_components();
+        if parts.len() < 2 {
+            return Err(format!("{} tiene una sola pieza", original.name));
+        }
+        // Un sólido de un STEP (`archivo#índice`) no se puede volver a numerar
+        let source = original.source.as_deref().filter(|&source| source_file(source) == source);
+        let colors = distinct_colors(parts.len());
+        let pieces: Vec<SceneObject> = parts
+            .into_iter()
+            .zip(colors)
+            .enumerate()
+            .map(|(part, (mesh, color))| {
+                let mut piece = SceneObject::from_mesh(mesh);
+                piece.name = format!("{}_{}", original.name, part + 1);
+                piece.source = source.map(|source| format!("{}#{}", source, part));
+                piece.color = Some(color);
+                piece.transform = original.transform.clone();
+                piece.angular_velocity = original.angular_velocity;
+                piece.material = original.material.clone();
+                piece.uniforms = original.uniforms.clone();
+                piece.group = original.group.clone();
+                piece.hidden = original.hidden;
+                piece.weld = original.weld;
+                piece
+            })
+            .collect();
+        let count = pieces.len();
+        for mut removed in self.objects.splice(index..=index, pieces) {
+            removed.release_gpu();
+        }
+        self.apply_groups();
+        Ok(count)
+    }
+
+    /// Junta los objetos `ids` en uno solo, con la transformación de cada uno
+    /// (grupo incluido) aplicada a sus vértices; con `weld` además
+    /// se unen los vértices de las costuras (ver `mesh_ops::merge`). El nuevo
+    /// queda en el lugar del primero, con su nombre, material y color, y los
+    /// originales se quitan liberando lo que tenían en la GPU. No tiene
+    /// archivo de origen, así que no se guarda con la escena (exportarlo
+    /// antes como STL). Devuelve su índice.
+    pub fn merge_objects(&mut self, ids: &[usize], weld: Option<f32>) -> Result<usize, String> {
+        let mut ids = ids.to_vec();
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.len() < 2 {
+            return Err("Hacen falta al menos dos objetos para unir".to_string());
+        }
+        self.check_indices(&ids)?;
+        let meshes: Vec<Mesh> = ids
+            .iter()
+            .map(|&index| transformed(&self.objects[index].mesh, &self.objects[index].model_matrix(1.0)))
+            .collect();
+        let first = &self.objects[ids[0]];
+        let mut merged = SceneObject::from_mesh(merge(&meshes, weld));
+        merged.name = first.name.clone();
+        merged.color = first.color;
+        merged.material = first.material.clone();
+        merged.uniforms = first.uniforms.clone();
+        for &index in ids.iter().rev() {
+            self.objects.remove(index).release_gpu();
+        }
+        self.objects.insert(ids[0], merged);
+        self.apply_groups();
+        self.sync_group_visibility();
+        Ok(ids[0])
+    }
+
+    /// Cuántos objetos están ocultos
+    pub fn hidden_count(&self) -> usize {
+        self.objects.iter().filter(|obj| obj.hidden).count()
+    }
+
+    /// Usa una iluminación armada (se guarda con la escena) y devuelve la luz
+    /// resultante para el Renderer
+    pub fn apply_lighting_preset(&mut self, rig: LightingRig) -> Lighting {
+        self.lighting_rig = Some(rig);
+        rig.lighting()
+    }
+
+    /// Luz de la escena según su rig
+    pub fn lighting(&self) -> Lighting {
+        self.lighting_rig.map(|rig| rig.lighting()).unwrap_or_default()
+    }
+
+    /// Hace correr el día de un rig animado; devuelve la luz nueva si cambió
+    pub fn update_lighting(&mut self, dt: f32) -> Option<Lighting> {
+        let rig = self.lighting_rig.as_mut()?;
+        rig.advance(dt).then(|| rig.lighting())
+    }
+
+    /// Guarda la escena como RON. Los objetos sin archivo de origen no se guardan.
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let file = SceneFile {
+            objects: self
+                .objects
+                .iter()
+                .filter(|obj| obj.link.is_none())
+                .filter_map(|obj| {
+                    Some(ObjectEntry {
+                        path: obj.source.clone()?,
+                        name: obj.name.clone(),
+                        transform: obj.transform.matrix().m,
+                        angular_velocity: obj.angular_velocity,
+                        angle: 0.0,
+                        angular_speed: 0.0,
+                        color: obj.color,
+                        material: obj.material.clone(),
+                        group: obj.group.clone(),
+                        hidden: obj.hidden,
+                        weld: Some(obj.weld),
+                        pivot: obj.pivot,
+                    })
+                })
+                .collect(),
+            bookmarks: self.bookmarks.clone(),
+            frames: self.frames.clone(),
+            robots: self
+                .robots
+                .iter()
+                .filter_map(|robot| {
+                    Some(RobotEntry {
+                        path: robot.source.clone()?,
+                        base: robot.base.m,
+                        joints: robot
+                            .joints()
+                            .iter()
+                            .filter(|joint| joint.kind.is_movable())
+                            .map(|joint| (joint.name.clone(), joint.position))
+                            .collect(),
+                    })
+                })
+                .collect(),
+            groups: self
+                .groups
+                .iter()
+                .map(|group| GroupEntry {
+                    name: group.name.clone(),
+                    transform: group.transform.m,
+                    hidden: group.hidden,
+                    explode: group.explode,
+                })
+                .collect(),
+            lighting: self.lighting_rig,
+            units: self.units,
+        };
+        let text = ron::ser::to_string_pretty(&file, ron::ser::PrettyConfig::default())
+            .map_err(|e| format!("No se pudo serializar la escena: {}", e))?;
+        fs::write(path, text).map_err(|e| format!("No se pudo escribir {}: {}", path, e))
+    }
+
+    /// Carga una escena RON y los modelos que referencia
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("No se pudo leer {}: {}", path, e))?;
+        let file: SceneFile =
+            ron::from_str(&text).map_err(|e| format!("Archivo de escena inválido {}: {}", path, e))?;
+
+        let objects = file
+            .objects
+            .into_iter()
+            .map(|entry| {
+                let weld = entry.weld.unwrap_or_else(Weld::from_env);
+                let mut obj = load_mesh_file_with(&entry.path, weld)?;
+                if !entry.name.is_empty() {
+                    obj.name = entry.name;
+                }
+                obj.transform = Transform::from_matrix(&Matrix4::rotate_y(entry.angle).multiply(&Matrix4 { m: entry.transform }));
+                obj.angular_velocity = entry.angular_velocity + Vec3::UNIT_Y * entry.angular_speed;
+                obj.color = entry.color;
+                obj.material = entry.material;
+                obj.group = entry.group;
+                obj.hidden = entry.hidden;
+                obj.pivot = entry.pivot;
+                Ok(obj)
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let mut scene = Self {
+            objects,
+            bookmarks: file.bookmarks,
+            frames: file.frames,
+            robots: Vec::new(),
+            groups: file
+                .groups
+                .into_iter()
+                .map(|entry| ObjectGroup {
+                    name: entry.name,
+                    transform: Matrix4 { m: entry.transform },
+                    hidden: entry.hidden,
+                    explode: entry.explode,
+                })
+                .collect(),
+            lighting_rig: file.lighting,
+            units: file.units,
+            ..Self::default()
+        };
+        for entry in file.robots {
+            let name = scene.load_urdf(&entry.path)?;
+            for (joint, position) in entry.joints {
+                if let Err(e) = scene.set_joint_position(&name, &joint, position) {
+                    eprintln!("{}", e);
+                }
+            }
+            scene.set_robot_base(&name, Matrix4 { m: entry.base })?;
+        }
+        scene.units = file.units;
+        scene.apply_groups();
+        // Escenas guardadas antes de que cada objeto tuviera su visibilidad
+        for group in scene.groups.iter().filter(|group| group.hidden) {
+            for obj in scene.objects.iter_mut().filter(|obj| obj.group.as_ref() == Some(&group.name)) {
+                obj.hidden = true;
+            }
+        }
+        scene.sync_group_visibility();
+        Ok(scene)
+    }
+
+    /// Carpeta de modelos, escena `.ron`, robot `.urdf` o un solo archivo de modelo
+    pub fn open(path: &str) -> Result<Self, String> {
+        if Path::new(path).is_dir() {
+            Self::load_directory(path)
+        } else if path.to_lowercase().ends_with(".ron") {
+            Self::load(path)
+        } else if path.to_lowercase().ends_with(".urdf") {
+            let mut scene = Self::new();
+            scene.load_urdf(path)?;
+            Ok(scene)
+        } else {
+            Ok(Self { objects: load_model_file(path)?, ..Self::default() })
+        }
+    }
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Archivo de un `SceneObject::source` (sin el "#índice" de los STEP con varios sólidos)
+pub fn source_file(source: &str) -> &str {
+    match source.rsplit_once('#') {
+        Some((file, index)) if index.parse::<usize>().is_ok() => file,
+        _ => source,
+    }
+}
+
+/// Extensiones de modelo que sabe importar `load_model_file`
+// Con todas las features cada rama da true, pero sin ellas no
+#[allow(clippy::match_like_matches_macro)]
+fn is_mesh_file(path: &Path) -> bool {
+    match extension(path).as_str() {
+        "stl" | "obj" => true,
+        "gltf" | "glb" => cfg!(feature = "gltf"),
+        "step" | "stp" | "iges" | "igs" => cfg!(feature = "step"),
+        _ => false,
+    }
+}
+
+/// Archivos de modelo importables de una carpeta, en orden alfabético
+pub fn model_files(path: &str) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(path).map_err(|e| format!("No se pudo leer la carpeta {}: {}", path, e))?;
+    let mut files: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && is_mesh_file(p))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Carga todos los objetos de un archivo de modelo (un STEP o IGES puede traer varios
+/// sólidos), con la soldadura de vértices de `WELD_ENV`
+pub fn load_model_file(path: &str) -> Result<Vec<SceneObject>, String> {
+    load_model_file_with(path, Weld::from_env())
+}
+
+/// `load_model_file` con otra soldadura de vértices. Solo cambia los STL: los
+/// STEP y los IGES conservan las aristas vivas de cada cara y los glTF ya
+/// llegan con los vértices compartidos.
+pub fn load_model_file_with(path: &str, weld: Weld) -> Result<Vec<SceneObject>, String> {
+    match extension(Path::new(path)).as_str() {
+        "stl" => Ok(vec![SceneObject::load_stl(path, weld)?]),
+        #[cfg(feature = "gltf")]
+        "gltf" | "glb" => Ok(vec![SceneObject::create_object_from_gltf(path)?]),
+        "obj" => Ok(vec![SceneObject::create_object_from_obj(path)?]),
+        #[cfg(feature = "step")]
+        "step" | "stp" | "iges" | "igs" => crate::graphics::cad_import::load_cad(path),
+        _ => Err(format!("Formato de modelo no soportado: {}", path)),
+    }
+}
+
+/// Carga un único objeto. Acepta `ruta#índice` para un sólido de un STEP con
+/// varios o una pieza suelta de un STL (ver `Scene::split_object`).
+pub fn load_mesh_file(path: &str) -> Result<SceneObject, String> {
+    load_mesh_file_with(path, Weld::from_env())
+}
+
+/// `load_mesh_file` con otra soldadura de vértices (ver `load_model_file_with`)
+pub fn load_mesh_file_with(path: &str, weld: Weld) -> Result<SceneObject, String> {
+    if let Some((file, index)) = path.rsplit_once('#') {
+        if let Ok(index) = index.parse() {
+            return load_part(file, index, weld);
+        }
+    }
+    let mut objects = load_model_file_with(path, weld)?;
+    match objects.len() {
+        1 => Ok(objects.remove(0)),
+        n => Err(format!("{} contiene {} objetos, indicar cuál con {}#índice", path, n, path)),
+    }
+}
+
+/// Objeto `index` de un archivo: un sólido de un STEP o una pieza suelta de un STL
+fn load_part(file: &str, index: usize, weld: Weld) -> Result<SceneObject, String> {
+    match extension(Path::new(file)).as_str() {
+        "stl" => {
+            let mut obj = SceneObject::load_stl(file, weld)?;
+            let mut parts = obj.mesh.split_components();
+            if index >= parts.len() {
+                obj.release_gpu();
+                return Err(format!("{} tiene {} piezas, no hay una con índice {}", file, parts.len(), index));
+            }
+            obj.set_mesh(parts.swap_remove(index));
+            obj.source = Some(format!("{}#{}", file, index));
+            obj.name = format!("{}_{}", obj.name, index + 1);
+            Ok(obj)
+        }
+        #[cfg(feature = "step")]
+        "step" | "stp" | "iges" | "igs" => crate::graphics::cad_import::load_cad_solid(file, index),
+        _ => Err(format!("{} no tiene objetos numerados", file)),
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::vec3::Vec3;
+
+    #[test]
+    fn test_bookmarks_replace_by_name() {
+        let mut scene = Scene::new();
+        let mut camera = Camera::new(Vec3::new(0.0, 0.0, 10.0));
+        scene.save_bookmark("detalle", &camera);
+        camera.position = Vec3::new(1.0, 2.0, 3.0);
+        scene.save_bookmark("detalle", &camera);
+        assert_eq!(scene.bookmarks.len(), 1);
+        assert_eq!(scene.bookmark("detalle").unwrap().pose.position, Vec3::new(1.0, 2.0, 3.0));
+        assert!(scene.remove_bookmark("detalle"));
+        assert!(scene.bookmark("detalle").is_none());
+    }
+
+    #[test]
+    fn test_distinct_object_colors() {
+        let mut scene = Scene::new();
+        scene.objects = (0..3).map(|_| SceneObject::new(0, 0)).collect();
+        scene.color_objects_distinct();
+        let colors: Vec<_> = scene.objects.iter().map(|obj| obj.color.unwrap()).collect();
+        assert!(colors[0] != colors[1] && colors[1] != colors[2] && colors[0] != colors[2]);
+        scene.set_object_color(1, None).unwrap();
+        assert_eq!(scene.objects[1].color, None);
+        assert!(scene.set_object_color(3, Some([1.0; 3])).is_err());
+        scene.clear_object_colors();
+        assert!(scene.objects.iter().all(|obj| obj.color.is_none()));
+    }
+
+    #[test]
+    fn test_lighting_rig_animation() {
+        let mut scene = Scene::new();
+        assert_eq!(scene.lighting(), Lighting::default());
+        let rig = LightingRig::Outdoor { hour: 17.0, speed: 0.5 };
+        assert_eq!(scene.apply_lighting_preset(rig), rig.lighting());
+        assert_eq!(scene.update_lighting(2.0), Some(LightingRig::Outdoor { hour: 18.0, speed: 0.5 }.lighting()));
+        scene.apply_lighting_preset(LightingRig::Studio);
+        assert_eq!(scene.update_lighting(2.0), None);
+    }
+
+    #[test]
+    fn test_transform_version_tracks_changes() {
+        use crate::graphics::mesh::Mesh;
+
+        let mut obj = SceneObject::new(0, 0);
+        obj.mesh = Mesh::new(vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]], vec![0, 1, 2]);
+        obj.world_bounds(1.0);
+        let version = obj.transform_version();
+        obj.model_matrix(1.0);
+        assert_eq!(obj.transform_version(), version);
+
+        obj.transform.scale = Vec3::new(1.0, 2.0, 1.0);
+        obj.model_matrix(1.0);
+        assert_ne!(obj.transform_version(), version);
+
+        // Otra malla con la misma matriz: cambia la caja y la versión
+        obj.world_bounds(1.0);
+        let version = obj.transform_version();
+        obj.mesh = Mesh::new(vec![[0.0, 0.0, 0.0], [5.0, 0.0, 0.0], [0.0, 5.0, 0.0]], vec![0, 1, 2]);
+        assert!(obj.world_bounds(1.0).size().magnitude() > 5.0);
+        assert_ne!(obj.transform_version(), version);
+
+        let mut scene = Scene::new();
+        scene.objects.push(obj);
+        scene.update_spatial(1.0);
+        assert_eq!(scene.spatial().len(), 1);
+    }
+
+    #[test]
+    fn test_layout_grid_separates_objects() {
+        use crate::graphics::mesh::Mesh;
+
+        let mut scene = Scene::new();
+        for x in [0.0, 0.0, 0.0, 0.0, 0.0, 5.0] {
+            let mut obj = SceneObject::new(0, 0);
+            obj.mesh = Mesh::new(vec![[x, 0.0, 0.0], [x + 2.0, 1.0, 2.0], [x, 1.0, 2.0]], vec![0, 1, 2]);
+            scene.objects.push(obj);
+        }
+        // Los dos últimos son sólidos de un mismo archivo: comparten celda
+        scene.layout_grid(&[0..1, 1..2, 2..3, 3..4, 4..6], 0.1);
+        let (a, b) = (scene.objects[4].world_bounds(1.0), scene.objects[5].world_bounds(1.0));
+        assert!((b.min - a.min).approx_eq(&Vec3::new(5.0, 0.0, 0.0), 1e-5));
+
+        let bounds: Vec<Aabb> = scene.objects[..5].iter().map(|obj| obj.world_bounds(1.0)).collect();
+        for (i, a) in bounds.iter().enumerate() {
+            assert!(a.min.y.abs() < 1e-5);
+            for b in &bounds[i + 1..] {
+                let overlap_x = a.min.x < b.max.x && b.min.x < a.max.x;
+                let overlap_z = a.min.z < b.max.z && b.min.z < a.max.z;
+                assert!(!(overlap_x && overlap_z));
+            }
+        }
+    }
+
+    #[test]
+    fn test_object_groups() {
+        use crate::graphics::mesh::Mesh;
+
+        let mut scene = Scene::new();
+        for x in [-1.0, 1.0, 5.0] {
+            let mut obj = SceneObject::new(0, 0);
+            obj.mesh = Mesh::new(vec![[-0.5, 0.0, 0.0], [0.5, 0.0, 0.0], [0.0, 1.0, 0.0]], vec![0, 1, 2]);
+            obj.transform = Transform::from_translation(Vec3::new(x, 0.0, 0.0));
+            obj.source = Some("conjunto.step#0".to_string());
+            scene.objects.push(obj);
+        }
+        scene.objects[2].source = Some("suelta.stl".to_string());
+        assert_eq!(scene.group_by_source(), 1);
+        assert_eq!(scene.group_members("conjunto"), vec![0, 1]);
+        assert_eq!(scene.group_of(0).map(|g| g.name.as_str()), Some("conjunto"));
+        assert!(scene.group_of(2).is_none());
+
+        // Media vuelta alrededor del centro del grupo: los miembros se intercambian
+        scene.set_group_transform("conjunto", Matrix4::rotate_y(std::f32::consts::PI)).unwrap();
+        let center = |scene: &Scene, index: usize| scene.objects[index].world_bounds(1.0).center();
+        assert!((center(&scene, 0).x - 1.0).abs() < 1e-4);
+        assert!((center(&scene, 1).x + 1.0).abs() < 1e-4);
+        assert!((center(&scene, 2).x - 5.0).abs() < 1e-4);
+
+        // Explotar aleja a cada miembro del centro
+        scene.set_group_transform("conjunto", Matrix4::identity()).unwrap();
+        scene.set_group_explode("conjunto", 1.0).unwrap();
+        assert!((center(&scene, 0).x + 2.0).abs() < 1e-4);
+
+        scene.set_group_hidden("conjunto", true).unwrap();
+        scene.set_group_color("conjunto", Some([1.0, 0.0, 0.0])).unwrap();
+        assert!(scene.objects[0].hidden && scene.objects[1].hidden && !scene.objects[2].hidden);
+        assert_eq!(scene.objects[1].color, Some([1.0, 0.0, 0.0]));
+        assert!(scene.set_group_hidden("otro", true).is_err());
+        assert!(scene.create_group("vacío", &[]).is_err());
+
+        // Al deshacerlo cada objeto vuelve a su lugar
+        assert!(scene.ungroup("conjunto"));
+        assert!(scene.groups().is_empty());
+        assert!((center(&scene, 0).x + 1.0).abs() < 1e-4);
+        // La visibilidad es de cada objeto: siguen ocultos hasta mostrarlos
+        assert!(scene.objects[0].hidden && scene.objects[1].hidden && !scene.objects[2].hidden);
+        scene.show_all();
+        assert_eq!(scene.hidden_count(), 0);
+    }
+
+    #[test]
+    fn test_isolate_and_undo_visibility() {
+        let mut scene = Scene::new();
+        scene.objects = (0..4).map(|_| SceneObject::new(0, 0)).collect();
+        scene.create_group("soporte", &[2, 3]).unwrap();
+
+        scene.isolate(&[1]).unwrap();
+        assert_eq!(scene.hidden_count(), 3);
+        assert!(scene.group("soporte").unwrap().hidden);
+        scene.hide(&[1]).unwrap();
+        assert_eq!(scene.hidden_count(), 4);
+        scene.show_all();
+        assert_eq!(scene.hidden_count(), 0);
+        assert!(!scene.group("soporte").unwrap().hidden);
+
+        // Se deshace en orden inverso
+        assert!(scene.undo_visibility());
+        assert_eq!(scene.hidden_count(), 4);
+        assert!(scene.undo_visibility());
+        assert!(!scene.objects[1].hidden && scene.objects[0].hidden);
+        assert!(scene.undo_visibility());
+        assert_eq!(scene.hidden_count(), 0);
+        assert!(!scene.undo_visibility());
+
+        // Ocultar un grupo oculta a sus miembros; mostrar uno lo deja visible
+        scene.set_group_hidden("soporte", true).unwrap();
+        assert!(scene.objects[2].hidden && scene.objects[3].hidden);
+        assert!(scene.isolate(&[]).is_err());
+        assert!(scene.hide(&[9]).is_err());
+        scene.isolate(&[3]).unwrap();
+        assert!(!scene.group("soporte").unwrap().hidden);
+
+        // Con otros objetos el historial deja de valer
+        scene.objects.push(SceneObject::new(0, 0));
+        assert!(!scene.undo_visibility());
+    }
+
+    #[test]
+    fn test_split_object_into_pieces() {
+        use crate::graphics::gl_mock::{self, GlCall};
+        use crate::graphics::mesh::Mesh;
+
+        gl_mock::install();
+        // Dos triángulos sin vértices en común
+        let positions =
+            vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [5.0, 0.0, 0.0], [6.0, 0.0, 0.0], [5.0, 1.0, 0.0]];
+        let mut obj = SceneObject::from_mesh(Mesh::new(positions, vec![0, 1, 2, 3, 4, 5]));
+        obj.name = "ensamble".to_string();
+        obj.source = Some("ensamble.stl".to_string());
+        obj.transform = Transform::from_translation(Vec3::new(0.0, 2.0, 0.0));
+        let original = obj.vao;
+        let mut scene = Scene::new();
+        scene.objects = vec![SceneObject::new(0, 0), obj];
+        gl_mock::take_calls();
+
+        assert_eq!(scene.split_object(1), Ok(2));
+        let pieces = &scene.objects[1..];
+        assert_eq!(pieces.iter().map(|obj| obj.name.as_str()).collect::<Vec<_>>(), vec!["ensamble_1", "ensamble_2"]);
+        assert_eq!(pieces[1].source.as_deref(), Some("ensamble.stl#1"));
+        assert_eq!(pieces[1].mesh.positions[0], [5.0, 0.0, 0.0]);
+        assert_eq!(pieces[1].transform, Transform::from_translation(Vec3::new(0.0, 2.0, 0.0)));
+        assert!(pieces[0].color.is_some() && pieces[0].color != pieces[1].color);
+        // El original libera su VAO y sus tres buffers
+        let calls = gl_mock::take_calls();
+        assert!(calls.contains(&GlCall::DeleteVertexArrays(vec![original])));
+        assert!(calls.iter().any(|call| matches!(call, GlCall::DeleteBuffers(buffers) if buffers.len() == 3)));
+        // Una sola pieza no se separa
+        assert!(scene.split_object(1).is_err());
+        assert!(scene.split_object(5).is_err());
+    }
+
+    #[test]
+    fn test_merge_objects_bakes_transforms() {
+        use crate::graphics::gl_mock::{self, GlCall};
+
+        gl_mock::install();
+        let triangle = || Mesh::new(vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]], vec![0, 1, 2]);
+        let mut scene = Scene::new();
+        scene.objects = (0..3).map(|_| SceneObject::from_mesh(triangle())).collect();
+        scene.objects[0].name = "base".to_string();
+        scene.objects[2].transform = Transform::from_translation(Vec3::new(0.0, 0.0, 3.0));
+        scene.create_group("par", &[0, 2]).unwrap();
+        let removed = [0, 2].map(|index| scene.objects[index].vao);
+        gl_mock::take_calls();
+
+        assert_eq!(scene.merge_objects(&[2, 0], None), Ok(0));
+        assert_eq!(scene.objects.len(), 2);
+        let merged = &scene.objects[0];
+        assert_eq!((merged.name.as_str(), merged.mesh.positions.len()), ("base", 6));
+        assert_eq!(merged.transform, Transform::identity());
+        assert!(merged.group.is_none() && scene.groups().is_empty());
+        let last = Vec3::from(merged.mesh.positions[5]);
+        assert!((last - Vec3::new(0.0, 1.0, 3.0)).magnitude() < 1e-5);
+        let calls = gl_mock::take_calls();
+        assert!(removed.iter().all(|&vao| calls.contains(&GlCall::DeleteVertexArrays(vec![vao]))));
+
+        assert!(scene.merge_objects(&[1, 1], None).is_err());
+        assert!(scene.merge_objects(&[0, 4], None).is_err());
+    }
+
+    #[test]
+    fn test_scene_file_roundtrip() {
+        let file = SceneFile {
+            objects: vec![ObjectEntry {
+                path: "pieza.stl".to_string(),
+                name: "pieza".to_string(),
+                transform: Matrix4::translate(1.0, 2.0, 3.0).m,
+                angular_velocity: Vec3::new(0.0, 0.0, 2.0),
+                angle: 0.0,
+                angular_speed: 0.0,
+                color: Some([1.0, 0.5, 0.0]),
+                material: Some("aluminio".to_string()),
+                group: Some("conjunto".to_string()),
+                hidden: true,
+                weld: Some(Weld::Off),
+                pivot: Some(Vec3::new(0.0, 0.0, 5.0)),
+            }],
+            bookmarks: vec![ViewBookmark {
+                name: "frente".to_string(),
+                pose: CameraPose { position: Vec3::new(0.0, 1.0, 5.0), yaw: 0.0, pitch: 0.1 },
+            }],
+            groups: vec![GroupEntry {
+                name: "conjunto".to_string(),
+                transform: Matrix4::rotate_y(0.5).m,
+                hidden: true,
+                explode: 0.5,
+            }],
+            frames: vec![ReferenceFrame {
+                name: "base".to_string(),
+                origin: Vec3::new(100.0, 0.0, 0.0),
+                rotation: Vec3::new(0.0, 0.0, 90.0),
+            }],
+            robots: vec![RobotEntry {
+                path: "brazo.urdf".to_string(),
+                base: Matrix4::translate(0.0, 0.0, 1.0).m,
+                joints: vec![("codo".to_string(), 0.5)],
+            }],
+            lighting: Some(LightingRig::MORNING),
+            units: LengthUnit::Inches,
+        };
+        let text = ron::to_string(&file).unwrap();
+        let parsed: SceneFile = ron::from_str(&text).unwrap();
+        assert_eq!(parsed.objects[0].transform[12], 1.0);
+        assert_eq!(parsed.objects[0].angular_velocity, Vec3::new(0.0, 0.0, 2.0));
+        assert!(!text.contains("angular_speed"));
+        assert_eq!(parsed.bookmarks, file.bookmarks);
+        assert_eq!(parsed.frames, file.frames);
+        assert_eq!(parsed.robots[0].joints, vec![("codo".to_string(), 0.5)]);
+        assert_eq!(parsed.lighting, Some(LightingRig::MORNING));
+        assert_eq!(parsed.units, LengthUnit::Inches);
+        assert_eq!(parsed.objects[0].color, Some([1.0, 0.5, 0.0]));
+        assert_eq!(parsed.objects[0].pivot, Some(Vec3::new(0.0, 0.0, 5.0)));
+        assert_eq!(parsed.objects[0].material.as_deref(), Some("aluminio"));
+        assert_eq!(parsed.objects[0].group.as_deref(), Some("conjunto"));
+        assert_eq!(parsed.objects[0].weld, Some(Weld::Off));
+        assert_eq!((parsed.groups[0].hidden, parsed.groups[0].explode), (true, 0.5));
+        // Las escenas sin iluminación guardada se siguen leyendo
+        let old: SceneFile = ron::from_str("(objects: [])").unwrap();
+        assert_eq!(old.lighting, None);
+        assert!(old.frames.is_empty());
+    }
+}
@@ -1,26 +1,198 @@
-use stl_io::{self};
 use std::{
-    collections::HashMap, fs::File, str
+    borrow::Cow, cell::Cell, collections::HashMap, str,
+    sync::atomic::{AtomicU64, Ordering},
 };
 
-use crate::math::{float3_eps::Float3Eps, matrix_4_by_4::Matrix4};
+use serde::{Deserialize, Serialize};
 
-/// Estructura para acumular datos de cada vértice
-/// - pos: posición (x, y, z)
-/// - normal: normal acumulada (nx, ny, nz)
-#[derive(Debug)]
-pub struct VertexData {
-    pos: [f32; 3],
-    normal: [f32; 3],
+use crate::graphics::mesh::{MassProperties, Mesh};
+use crate::graphics::morph::{
+    MorphTarget, MAX_MORPH_TARGETS, MORPH_NORMAL_LOCATION, MORPH_POSITION_LOCATION,
+};
+use crate::graphics::mesh_ops::{transformed, vertex_normals};
+use crate::graphics::uv::{lightmap_uvs, project_uvs, UvProjection, UvTransform};
+use crate::graphics::animation::Animator;
+use crate::graphics::lightmap::{Lightmap, LightmapTexture, LIGHTMAP_UV_LOCATION};
+use crate::graphics::skeleton::Skeleton;
+use crate::graphics::frames::ReferenceFrame;
+use crate::graphics::robot::LinkAttachment;
+use crate::graphics::stl::{self, StlError};
+#[cfg(feature = "gltf")]
+use crate::graphics::loaders::gltf;
+use crate::graphics::loaders::{obj, LoadedMesh};
+use crate::engine::progress::{ProgressToken, CANCELLED};
+use crate::graphics::uniforms::UniformOverrides;
+use crate::graphics::scale_bar::LengthUnit;
+use crate::math::{
+    aabb::Aabb, matrix_4_by_4::Matrix4, quaternion::Quat, transform::Transform,
+    vec3::Vec3, weld::{VertexWelder, DEFAULT_TOLERANCE},
+};
+
+/// Ejes en los que se interpretan movimientos y giros de un objeto
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransformSpace {
+    /// Los ejes de la escena
+    #[default]
+    World,
+    /// Los ejes propios del objeto (giran con él)
+    Local,
+    /// X a la derecha, Y arriba y Z hacia el observador, según la cámara
+    Screen,
+}
+
+impl TransformSpace {
+    pub const ALL: [TransformSpace; 3] = [Self::World, Self::Local, Self::Screen];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::World => "mundo",
+            Self::Local => "local",
+            Self::Screen => "pantalla",
+        }
+    }
+
+    /// Lee "world" / "mundo", "local" o "screen" / "pantalla"
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "world" | "mundo" => Ok(Self::World),
+            "local" => Ok(Self::Local),
+            "screen" | "pantalla" => Ok(Self::Screen),
+            other => Err(format!("Espacio de transformación desconocido '{}' (world, local o screen)", other)),
+        }
+    }
+
+    /// El que sigue, para alternarlos con una tecla
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&space| space == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// STL desde el que `load_stl` muestra el avance (64 MB)
+pub const LARGE_STL_BYTES: u64 = 64 << 20;
+
+/// Variable de entorno con la tolerancia de soldadura de los STL ("off" no suelda)
+pub const WELD_ENV: &str = "RUST_ENGINE_WELD";
+
+/// Cómo se unen los vértices repetidos de un STL al importarlo. Los STL traen
+/// cada triángulo con sus tres esquinas; soldar las que coinciden da una malla
+/// conectada y normales suaves. La tolerancia fija de antes unía vértices
+/// distintos en piezas muy chicas y no unía nada en modelos muy grandes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Weld {
+    /// Cada esquina queda como vértice propio (normales planas por cara)
+    Off,
+    /// Se unen los vértices a menos de esta distancia, en unidades del modelo
+    Distance(f32),
+}
+
+impl Default for Weld {
+    fn default() -> Self {
+        Self::Distance(DEFAULT_TOLERANCE)
+    }
+}
+
+impl Weld {
+    /// Lee "off" o una distancia positiva ("0.001")
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("off") {
+            return Ok(Self::Off);
+        }
+        let distance = value.parse::<f32>().map_err(|e| format!("Tolerancia inválida '{}': {}", value, e))?;
+        if !(distance.is_finite() && distance > 0.0) {
+            return Err(format!("La tolerancia tiene que ser positiva: {}", distance));
+        }
+        Ok(Self::Distance(distance))
+    }
+
+    /// La de `WELD_ENV` o la de siempre (un valor inválido se informa y se ignora)
+    pub fn from_env() -> Self {
+        match std::env::var(WELD_ENV) {
+            Ok(value) => Self::parse(&value).unwrap_or_else(|e| {
+                eprintln!("{} ignorado: {}", WELD_ENV, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+}
+
+/// (positions, normals, indices) tal como se suben a la GPU
+type MeshBuffers = (Vec<f32>, Vec<f32>, Vec<u32>);
+
+/// Versiones únicas entre todos los objetos, así un objeto nuevo nunca
+/// repite la versión de otro que estaba en el mismo lugar de la lista
+static NEXT_TRANSFORM_VERSION: AtomicU64 = AtomicU64::new(1);
+
+/// Última matriz de modelo y caja en espacio mundo, con las entradas con que
+/// se calcularon. Los campos de transformación son públicos, así que los
+/// cambios se detectan comparando entradas en vez de con setters.
+#[derive(Debug, Clone, Copy)]
+struct TransformCache {
+    base: [f32; 16],
+    group: [f32; 16],
+    global_scale: f32,
+    matrix: Matrix4,
+    /// Inversa traspuesta de `matrix` para las normales (ver `Matrix4::normal_matrix`)
+    normal: Matrix4,
+    /// Caja de la malla con la que se calculó `world_bounds`
+    local_bounds: Aabb,
+    world_bounds: Option<Aabb>,
+    version: u64,
+}
+
+/// Rango del buffer de índices con su propio material (un grupo de caras
+/// de un OBJ o una primitiva de glTF). Todos comparten los vértices del objeto.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubMesh {
+    /// Primer índice del rango (múltiplo de 3)
+    pub start: u32,
+    /// Cantidad de índices (múltiplo de 3)
+    pub count: u32,
+    /// Nombre en la biblioteca de materiales; None usa el del objeto
+    pub material: Option<String>,
+    /// Color base sRGB que trae el archivo (el `Kd` del .mtl), si la
+    /// biblioteca no tiene un material con ese nombre
+    pub color: Option<[f32; 3]>,
 }
 
 pub struct SceneObject {
     pub vao: u32,
     pub index_count: i32,
-    pub base_transform: Matrix4,  // posición inicial
-    pub angle: f32,               // rotación acumulada
-    pub angular_speed: f32,       // rotación por segundo
-    pub scale_factor: f32,        // escala actual
+    pub transform: Transform,     // posición, giro y escala en la escena (sin grupo ni escala global)
+    pub angular_velocity: Vec3,   // giro continuo en los ejes de la escena: eje por radianes por segundo
+    pub mesh: Mesh,               // copia en CPU de la geometría
+    normals: Vec<[f32; 3]>,       // normales por vértice tal como se subieron (location = 1)
+    pub source: Option<String>,   // archivo de origen (para guardar la escena)
+    pub name: String,             // nombre visible (por defecto el del archivo)
+    geometry_buffers: [u32; 3],   // VBOs de posiciones y normales y EBO (para liberarlos)
+    uv_buffer: u32,               // VBO de UVs (location = 2), 0 si no hay
+    pub lightmap: Option<LightmapTexture>, // iluminación horneada (usa `mesh.lightmap_uvs`)
+    lightmap_uv_buffer: u32,      // VBO de UVs del lightmap (location = 12), 0 si no hay
+    gpu_vertices: Vec<u32>,       // vértice de `mesh` de cada vértice en la GPU (vacío: los mismos)
+    color_buffer: u32,            // VBO de colores por vértice (location = 3), 0 si no hay
+    vertex_colors: Option<Vec<[f32; 3]>>, // colores por vértice que reemplazan el color base
+    pub color: Option<[f32; 3]>,  // color base sRGB propio (None: el del material o el gris por defecto)
+    pub material: Option<String>, // nombre en la biblioteca de materiales del Renderer
+    submeshes: Vec<SubMesh>,      // rangos de índices con material propio (vacío: uno solo)
+    morph_targets: Vec<MorphTarget>, // deltas por vértice (ver `graphics::morph`)
+    morph_buffers: Vec<u32>,      // VBOs de los deltas (locations 4 a 11)
+    pub morph_weights: [f32; MAX_MORPH_TARGETS], // peso de cada target
+    pub skeleton: Option<Skeleton>, // articulaciones en espacio del objeto (ver `graphics::skeleton`)
+    pub animator: Option<Animator>, // clips que animan pesos y poses (ver `graphics::animation`)
+    pub uniforms: UniformOverrides, // uniforms del shader propios de este objeto
+    pub render_order: i32,        // orden de dibujo: menor primero (a igualdad, el de la escena)
+    pub overlay: bool,            // encima de la escena sin importar la profundidad (gizmos, ayudas)
+    pub group: Option<String>,    // grupo al que pertenece (ver `Scene::create_group`)
+    pub group_transform: Matrix4, // transformación del grupo, la mantiene la escena
+    pub hidden: bool,             // no se dibuja ni se puede seleccionar
+    pub ignore_clipping: bool,    // queda entero con planos de corte o caja de sección (referencias)
+    pub weld: Weld,               // soldadura con que se importó (se repite al recargar)
+    pub pivot: Option<Vec3>,      // punto de giro en coordenadas de la malla (None: centro de su caja)
+    pub link: Option<LinkAttachment>, // eslabón de robot que mueve `transform` (ver `Scene::load_urdf`)
+    transform_cache: Cell<Option<TransformCache>>,
 }
 
 impl SceneObject{
@@ -29,106 +201,684 @@ impl SceneObject{
         Self {
             vao,
             index_count,
-            base_transform: Matrix4::identity(),
-            angle: 0.0,
-            angular_speed: 0.0,
-            scale_factor: 1.0,
+            transform: Transform::identity(),
+            angular_velocity: Vec3::ZERO,
+            mesh: Mesh::default(),
+            normals: Vec::new(),
+            source: None,
+            name: String::new(),
+            geometry_buffers: [0; 3],
+            uv_buffer: 0,
+            lightmap: None,
+            lightmap_uv_buffer: 0,
+            gpu_vertices: Vec::new(),
+            color_buffer: 0,
+            vertex_colors: None,
+            color: None,
+            material: None,
+            submeshes: Vec::new(),
+            morph_targets: Vec::new(),
+            morph_buffers: Vec::new(),
+            morph_weights: [0.0; MAX_MORPH_TARGETS],
+            animator: None,
+            skeleton: None,
+            uniforms: UniformOverrides::default(),
+            render_order: 0,
+            overlay: false,
+            group: None,
+            group_transform: Matrix4::identity(),
+            hidden: false,
+            ignore_clipping: false,
+            weld: Weld::default(),
+            pivot: None,
+            link: None,
+            transform_cache: Cell::new(None),
+        }
+    }
+
+    /// Matriz de modelo final: escala global * grupo * `transform`.
+    /// Se recalcula solo si cambió alguna de sus entradas.
+    pub fn model_matrix(&self, global_scale: f32) -> Matrix4 {
+        self.cached_transform(global_scale).matrix
+    }
+
+    fn cached_transform(&self, global_scale: f32) -> TransformCache {
+        let base = self.transform.matrix();
+        if let Some(cache) = self.transform_cache.get() {
+            if cache.base == base.m && cache.group == self.group_transform.m && cache.global_scale == global_scale {
+                return cache;
+            }
+        }
+        // escala global
+        let scale_mat = Matrix4::scale(global_scale);
+        let group = scale_mat.multiply(&self.group_transform);
+
+        let matrix = Matrix4::multiply(&group, &base);
+        let cache = TransformCache {
+            base: base.m,
+            group: self.group_transform.m,
+            global_scale,
+            matrix,
+            normal: matrix.normal_matrix(),
+            local_bounds: Aabb::EMPTY,
+            world_bounds: None,
+            version: NEXT_TRANSFORM_VERSION.fetch_add(1, Ordering::Relaxed),
+        };
+        self.transform_cache.set(Some(cache));
+        cache
+    }
+
+    /// Matriz para llevar las normales al mundo (uniform `normalMatrix`):
+    /// con escala distinta en cada eje no alcanza con la de modelo
+    pub fn normal_matrix(&self, global_scale: f32) -> Matrix4 {
+        self.cached_transform(global_scale).normal
+    }
+
+    /// Cambia cada vez que cambia la matriz de modelo o la caja en espacio
+    /// mundo (0 si todavía no se calcularon). Sirve para saltear trabajo
+    /// por objeto cuando nada se movió.
+    pub fn transform_version(&self) -> u64 {
+        self.transform_cache.get().map_or(0, |cache| cache.version)
+    }
+
+    /// Posición del objeto: la de `transform`, en unidades de la escena
+    pub fn position(&self) -> Vec3 {
+        self.transform.position
+    }
+
+    /// Mueve el objeto a `position` (unidades de la escena) sin tocar su giro ni su escala
+    pub fn set_position(&mut self, position: Vec3) {
+        self.transform.position = position;
+    }
+
+    /// `set_position` con la posición en milímetros, en una escena en `units`
+    pub fn set_position_mm(&mut self, position: Vec3, units: LengthUnit) {
+        self.set_position(Vec3::new(units.from_mm(position.x), units.from_mm(position.y), units.from_mm(position.z)));
+    }
+
+    /// Posición y giro del objeto vistos desde `frame`
+    pub fn pose_in(&self, frame: &ReferenceFrame) -> (Vec3, Quat) {
+        (frame.to_frame(self.transform.position), frame.orientation().conjugate() * self.transform.rotation)
+    }
+
+    /// Lleva el objeto a `position` con el giro `rotation`, ambos en `frame`,
+    /// sin tocar su escala
+    pub fn set_pose_in(&mut self, frame: &ReferenceFrame, position: Vec3, rotation: Quat) {
+        self.transform.position = frame.from_frame(position);
+        self.transform.rotation = frame.orientation() * rotation;
+    }
+
+    /// Punto de giro (`pivot`, o el centro de la caja de la malla) en el
+    /// espacio de `transform`: la escena sin grupo ni escala global
+    pub fn pivot_point(&self) -> Vec3 {
+        let local = self.pivot.unwrap_or_else(|| self.mesh.bvh().bounds().center());
+        self.transform.transform_point(local)
+    }
+
+    /// Ejes X, Y y Z de `space` en el espacio de `transform` (unitarios).
+    /// `view` es la matriz de vista de la cámara, para `TransformSpace::Screen`.
+    pub fn space_axes(&self, space: TransformSpace, view: &Matrix4) -> [Vec3; 3] {
+        match space {
+            TransformSpace::World => [Vec3::UNIT_X, Vec3::UNIT_Y, Vec3::UNIT_Z],
+            TransformSpace::Local => [0, 1, 2].map(|index| self.transform.axis(index)),
+            // Las filas de la rotación de la vista son los ejes de la cámara
+            TransformSpace::Screen => {
+                let m = &view.m;
+                [
+                    Vec3::new(m[0], m[4], m[8]).normalize_or(Vec3::UNIT_X),
+                    Vec3::new(m[1], m[5], m[9]).normalize_or(Vec3::UNIT_Y),
+                    Vec3::new(m[2], m[6], m[10]).normalize_or(Vec3::UNIT_Z),
+                ]
+            }
+        }
+    }
+
+    /// `v` dado en los ejes de `space`, en el espacio de `transform`
+    pub fn space_vector(&self, space: TransformSpace, v: Vec3, view: &Matrix4) -> Vec3 {
+        let [x, y, z] = self.space_axes(space, view);
+        x * v.x + y * v.y + z * v.z
+    }
+
+    /// Mueve el objeto `delta` (unidades de la escena) en los ejes de `space`
+    pub fn translate_in(&mut self, space: TransformSpace, delta: Vec3, view: &Matrix4) {
+        let delta = self.space_vector(space, delta, view);
+        self.set_position(self.position() + delta);
+    }
+
+    /// `rotate_degrees` con el eje dado en los ejes de `space`, por el punto de giro
+    pub fn rotate_in(&mut self, space: TransformSpace, axis: Vec3, degrees: f32, view: &Matrix4) -> Result<(), String> {
+        let axis = self.space_vector(space, axis, view);
+        self.rotate_degrees(axis, degrees, None)
+    }
+
+    /// Gira `degrees` grados alrededor de `axis` por `pivot` (None: `pivot_point`),
+    /// ambos en el espacio de `transform`. El giro se suma al que ya tenía.
+    pub fn rotate_degrees(&mut self, axis: Vec3, degrees: f32, pivot: Option<Vec3>) -> Result<(), String> {
+        let axis = axis.try_normalize().ok_or("El eje de giro no puede ser nulo")?;
+        let pivot = pivot.unwrap_or_else(|| self.pivot_point());
+        self.transform.rotate_about(Quat::from_axis_angle(axis, degrees.to_radians()), pivot);
+        Ok(())
+    }
+
+    /// Avanza `dt` segundos el giro continuo de `angular_velocity`, sobre la
+    /// posición del objeto
+    pub fn spin(&mut self, dt: f32) {
+        let speed = self.angular_velocity.magnitude();
+        if speed > 0.0 {
+            let rotation = Quat::from_axis_angle(self.angular_velocity, speed * dt);
+            self.transform.rotate_about(rotation, self.transform.position);
+        }
+    }
+
+    /// Material por nombre (ver `graphics::material`); se busca en la
+    /// biblioteca del Renderer al dibujar, así que puede cargarse después
+    pub fn set_material(&mut self, name: &str) {
+        self.material = Some(name.to_string());
+    }
+
+    /// Divide el dibujo en rangos del buffer de índices, cada uno con su
+    /// material. Los rangos se dibujan en el orden dado; vacío vuelve a un
+    /// solo draw con el material del objeto.
+    pub fn set_submeshes(&mut self, submeshes: Vec<SubMesh>) -> Result<(), String> {
+        for (i, sub) in submeshes.iter().enumerate() {
+            if sub.start % 3 != 0 || sub.count % 3 != 0 {
+                return Err(format!("El sub-mesh {} no empieza ni termina en un triángulo", i));
+            }
+            if sub.start as u64 + sub.count as u64 > self.index_count.max(0) as u64 {
+                return Err(format!(
+                    "El sub-mesh {} ({}..{}) se pasa de los {} índices del objeto",
+                    i,
+                    sub.start,
+                    sub.start + sub.count,
+                    self.index_count
+                ));
+            }
+        }
+        self.submeshes = submeshes;
+        Ok(())
+    }
+
+    pub fn submeshes(&self) -> &[SubMesh] {
+        &self.submeshes
+    }
+
+    /// Cuántos draws necesita el objeto (al menos uno)
+    pub fn part_count(&self) -> usize {
+        self.submeshes.len().max(1)
+    }
+
+    /// (primer índice, cantidad de índices, material) del draw `part`. Sin
+    /// sub-meshes es toda la malla con el material del objeto.
+    pub fn part(&self, part: usize) -> (u32, i32, Option<&str>) {
+        match self.submeshes.get(part) {
+            Some(sub) => (sub.start, sub.count as i32, sub.material.as_deref().or(self.material.as_deref())),
+            None => (0, self.index_count, self.material.as_deref()),
+        }
+    }
+
+    /// Color del archivo para el draw `part`, si su sub-mesh trae uno
+    pub fn part_color(&self, part: usize) -> Option<[f32; 3]> {
+        self.submeshes.get(part).and_then(|sub| sub.color)
+    }
+
+    /// Reemplaza los morph targets y sube sus deltas. Vacío los quita.
+    pub fn set_morph_targets(&mut self, targets: Vec<MorphTarget>) -> Result<(), String> {
+        if targets.len() > MAX_MORPH_TARGETS {
+            return Err(format!("{} morph targets, el máximo es {}", targets.len(), MAX_MORPH_TARGETS));
+        }
+        let count = self.mesh.positions.len();
+        for target in &targets {
+            if target.positions.len() != count || !(target.normals.is_empty() || target.normals.len() == count) {
+                return Err(format!("El morph target '{}' no tiene un delta por vértice ({})", target.name, count));
+            }
+        }
+        self.morph_targets = targets;
+        self.upload_morph_targets();
+        Ok(())
+    }
+
+    pub fn morph_targets(&self) -> &[MorphTarget] {
+        &self.morph_targets
+    }
+
+    pub fn has_morph_targets(&self) -> bool {
+        !self.morph_targets.is_empty()
+    }
+
+    /// Avanza el `animator`, si hay uno, y aplica sus pesos y poses
+    pub fn advance_animation(&mut self, dt: f32) {
+        if let Some(animator) = &mut self.animator {
+            animator.update(dt);
+            animator.apply(&mut self.morph_weights, self.skeleton.as_mut());
+        }
+    }
+
+    fn upload_morph_targets(&mut self) {
+        if self.vao == 0 {
+            return;
+        }
+        unsafe {
+            if !self.morph_buffers.is_empty() {
+                gl::DeleteBuffers(self.morph_buffers.len() as i32, self.morph_buffers.as_ptr());
+                self.morph_buffers.clear();
+            }
+            gl::BindVertexArray(self.vao);
+            for (i, target) in self.morph_targets.iter().enumerate() {
+                let i = i as u32;
+                for (location, deltas) in [
+                    (MORPH_POSITION_LOCATION + i, &target.positions),
+                    (MORPH_NORMAL_LOCATION + i, &target.normals),
+                ] {
+                    // Sin deltas de normal el atributo queda apagado y vale 0
+                    if deltas.is_empty() {
+                        continue;
+                    }
+                    let deltas = for_gpu(&self.gpu_vertices, deltas);
+                    let mut buffer = 0;
+                    gl::GenBuffers(1, &mut buffer);
+                    gl::BindBuffer(gl::ARRAY_BUFFER, buffer);
+                    gl::BufferData(
+                        gl::ARRAY_BUFFER,
+                        std::mem::size_of_val(deltas.as_ref()) as isize,
+                        deltas.as_ptr() as *const _,
+                        gl::STATIC_DRAW,
+                    );
+                    gl::VertexAttribPointer(location, 3, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+                    gl::EnableVertexAttribArray(location);
+                    self.morph_buffers.push(buffer);
+                }
+            }
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
+        }
+    }
+
+    /// Normales por vértice con que se sombrea el objeto (vacío si no se subió)
+    pub fn normals(&self) -> &[[f32; 3]] {
+        &self.normals
+    }
+
+    /// Genera UVs por proyección (ver `graphics::uv`) y las sube como atributo 2
+    pub fn generate_uvs(&mut self, projection: UvProjection, transform: &UvTransform) {
+        let uvs = project_uvs(&self.mesh, projection, transform);
+        self.set_uvs(uvs);
+    }
+
+    /// Reemplaza las UVs de la malla y las sube a la GPU
+    pub fn set_uvs(&mut self, uvs: Vec<[f32; 2]>) {
+        self.mesh.uvs = uvs;
+        if self.vao == 0 {
+            return;
+        }
+        unsafe {
+            if self.uv_buffer == 0 {
+                gl::GenBuffers(1, &mut self.uv_buffer);
+            }
+            let uvs = for_gpu(&self.gpu_vertices, &self.mesh.uvs);
+            gl::BindVertexArray(self.vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.uv_buffer);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                std::mem::size_of_val(uvs.as_ref()) as isize,
+                uvs.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+            // (location=2)
+            gl::VertexAttribPointer(2, 2, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+            gl::EnableVertexAttribArray(2);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
         }
     }
 
+    /// Colores por vértice (lineales) que reemplazan el color base, p. ej. un
+    /// mapa de calor. `None` vuelve al color base.
+    pub fn set_vertex_colors(&mut self, colors: Option<&[[f32; 3]]>) {
+        let Some(colors) = colors.filter(|c| c.len() == self.mesh.positions.len()) else {
+            self.vertex_colors = None;
+            return;
+        };
+        self.vertex_colors = Some(colors.to_vec());
+        if self.vao == 0 {
+            return;
+        }
+        unsafe {
+            if self.color_buffer == 0 {
+                gl::GenBuffers(1, &mut self.color_buffer);
+            }
+            let colors = for_gpu(&self.gpu_vertices, colors);
+            gl::BindVertexArray(self.vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.color_buffer);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                std::mem::size_of_val(colors.as_ref()) as isize,
+                colors.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+            // (location=3)
+            gl::VertexAttribPointer(3, 3, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+            gl::EnableVertexAttribArray(3);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
+        }
+    }
+
+    /// Se dibuja con colores por vértice
+    pub fn has_vertex_colors(&self) -> bool {
+        self.vertex_colors.is_some()
+    }
+
+    /// Reemplaza la geometría (p. ej. después de reparar o decimar) y la vuelve
+    /// a subir con normales suavizadas. El lightmap (con sus UVs), los colores
+    /// por vértice, los sub-meshes y los morph targets dejan de valer y se descartan.
+    pub fn set_mesh(&mut self, mut mesh: Mesh) {
+        // La caja cacheada es de la malla anterior
+        self.transform_cache.set(None);
+        self.submeshes.clear();
+        self.morph_targets.clear();
+        if let Some(previous) = self.lightmap.take() {
+            unsafe {
+                gl::DeleteTextures(1, &previous.texture);
+            }
+        }
+        mesh.lightmap_uvs.clear();
+        self.index_count = mesh.indices.len() as i32;
+        self.vertex_colors = None;
+        self.normals = vertex_normals(&mesh);
+        self.mesh = mesh;
+        if self.vao != 0 {
+            self.release_gpu();
+            self.upload_gpu();
+        }
+    }
+
+    /// Genera UVs de lightmap sin solapes para un lightmap de `resolution`
+    /// texels (ver `uv::lightmap_uvs`) y vuelve a subir la geometría con los
+    /// vértices de las costuras repetidos. Las UVs de textura no cambian; el
+    /// lightmap anterior deja de valer y se descarta.
+    pub fn generate_lightmap_uvs(&mut self, resolution: u32) {
+        self.mesh.lightmap_uvs = lightmap_uvs(&self.mesh, resolution);
+        if let Some(previous) = self.lightmap.take() {
+            unsafe {
+                gl::DeleteTextures(1, &previous.texture);
+            }
+        }
+        if self.vao != 0 {
+            self.release_gpu();
+            self.upload_gpu();
+        }
+    }
+
+    /// Vuelve a subir la geometría, las UVs y los colores por vértice desde
+    /// la copia en CPU en un contexto GL nuevo (ver `graphics::gpu_resources`).
+    /// Los objetos anteriores se perdieron con el contexto y no se borran; el
+    /// lightmap no tiene copia en CPU y hay que volver a hornearlo.
+    pub fn rebuild_gpu(&mut self) {
+        if self.vao == 0 {
+            return;
+        }
+        self.lightmap = None;
+        self.upload_gpu();
+    }
+
+    /// Sube a buffers nuevos la geometría, las UVs, los colores por vértice y
+    /// los morph targets de la copia en CPU. Con UVs de lightmap, los vértices
+    /// de las costuras entre islas se repiten (solo en la GPU).
+    fn upload_gpu(&mut self) {
+        let (gpu_vertices, indices, lightmap_uvs) = split_lightmap_seams(&self.mesh);
+        self.gpu_vertices = gpu_vertices;
+        let positions: Vec<f32> = for_gpu(&self.gpu_vertices, &self.mesh.positions).iter().flatten().copied().collect();
+        // Las mismas normales que antes (las de un STL vienen del archivo)
+        if self.normals.len() != self.mesh.positions.len() {
+            self.normals = vertex_normals(&self.mesh);
+        }
+        let normals: Vec<f32> = for_gpu(&self.gpu_vertices, &self.normals).iter().flatten().copied().collect();
+        let uploaded = SceneObject::from_buffers(&positions, &normals, indices);
+        self.vao = uploaded.vao;
+        self.geometry_buffers = uploaded.geometry_buffers;
+        self.index_count = uploaded.index_count;
+        self.uv_buffer = 0;
+        self.color_buffer = 0;
+        self.lightmap_uv_buffer = 0;
+        let uvs = std::mem::take(&mut self.mesh.uvs);
+        if !uvs.is_empty() {
+            self.set_uvs(uvs);
+        }
+        if let Some(colors) = self.vertex_colors.take() {
+            self.set_vertex_colors(Some(colors.as_slice()));
+        }
+        self.morph_buffers.clear();
+        self.upload_morph_targets();
+        if lightmap_uvs.is_empty() {
+            return;
+        }
+        unsafe {
+            gl::GenBuffers(1, &mut self.lightmap_uv_buffer);
+            gl::BindVertexArray(self.vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.lightmap_uv_buffer);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                std::mem::size_of_val(lightmap_uvs.as_slice()) as isize,
+                lightmap_uvs.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+            gl::VertexAttribPointer(LIGHTMAP_UV_LOCATION, 2, gl::FLOAT, gl::FALSE, 0, std::ptr::null());
+            gl::EnableVertexAttribArray(LIGHTMAP_UV_LOCATION);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
+        }
+    }
+
+    /// Libera todo lo que el objeto tiene en la GPU (geometría, UVs, colores,
+    /// morph targets y lightmap). Queda la copia en CPU; hay que llamarla
+    /// antes de quitar un objeto de la escena que no se va a volver a dibujar.
+    pub fn release_gpu(&mut self) {
+        if self.vao == 0 {
+            return;
+        }
+        let buffers: Vec<u32> = self
+            .geometry_buffers
+            .iter()
+            .chain([&self.uv_buffer, &self.color_buffer, &self.lightmap_uv_buffer])
+            .chain(&self.morph_buffers)
+            .copied()
+            .filter(|&buffer| buffer != 0)
+            .collect();
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteBuffers(buffers.len() as i32, buffers.as_ptr());
+            if let Some(lightmap) = self.lightmap.take() {
+                gl::DeleteTextures(1, &lightmap.texture);
+            }
+        }
+        self.vao = 0;
+        self.geometry_buffers = [0; 3];
+        self.uv_buffer = 0;
+        self.color_buffer = 0;
+        self.lightmap_uv_buffer = 0;
+        self.morph_buffers.clear();
+    }
+
+    /// Sube un lightmap horneado y libera el anterior
+    pub fn set_lightmap(&mut self, lightmap: &Lightmap) {
+        if let Some(previous) = self.lightmap.take() {
+            unsafe {
+                gl::DeleteTextures(1, &previous.texture);
+            }
+        }
+        self.lightmap = Some(lightmap.upload());
+    }
+
+    /// Caja en espacio mundo que contiene la malla transformada
+    pub fn world_bounds(&self, global_scale: f32) -> Aabb {
+        let mut cache = self.cached_transform(global_scale);
+        let local = self.mesh.bvh().bounds();
+        if let Some(bounds) = cache.world_bounds {
+            if cache.local_bounds == local {
+                return bounds;
+            }
+            // Cambió la malla: la caja es otra aunque la matriz sea la misma
+            cache.version = NEXT_TRANSFORM_VERSION.fetch_add(1, Ordering::Relaxed);
+        }
+        let bounds = local.transformed(&cache.matrix);
+        cache.local_bounds = local;
+        cache.world_bounds = Some(bounds);
+        self.transform_cache.set(Some(cache));
+        bounds
+    }
+
+    /// Propiedades de masa en espacio mundo (con la escala y rotación actuales)
+    pub fn mass_properties(&self, density: f32, global_scale: f32) -> Result<MassProperties, String> {
+        transformed(&self.mesh, &self.model_matrix(global_scale)).mass_properties(density)
+    }
+
     /// Carga un STL y calcula normales "smooth" promediadas.
     /// Devuelve (positions, normals, indices).
     /// - `positions`: [x0, y0, z0, x1, y1, z1, ...]
     /// - `normals`:   [nx0, ny0, nz0, nx1, ny1, nz1, ...]
     /// - `indices`:   [i0, i1, i2, ...] (u32)
-    fn load_stl_model_smooth(path: &str) -> (Vec<f32>, Vec<f32>, Vec<u32>) {
-        // 1. Abrir el archivo
-        let mut file = File::open(path)
-            .unwrap_or_else(|_| panic!("No se pudo abrir el archivo STL: {}", path));
-
-        // 2. Parsear con stl_io
-        let mesh = stl_io::read_stl(&mut file)
-            .expect("Error parseando el archivo STL");
-
-        // Mapa para unificar vértices:
-        //  key: (x, y, z)
-        //  val: índice en el vector "unique_vertices"
-        let mut vertex_map: HashMap<Float3Eps, u32> = HashMap::new();
-        let mut unique_vertices: Vec<VertexData> = Vec::new();
+    ///
+    /// Los triángulos llegan de a uno (ver `graphics::stl::read_file`) y se
+    /// sueldan a medida que llegan: en memoria solo están el hash espacial de
+    /// vértices únicos y los buffers finales, nunca el archivo entero. Con `Weld::Off`
+    /// no se unen vértices y cada uno se queda con la normal de su cara.
+    fn load_stl_model_smooth(path: &str, weld: Weld, progress: &ProgressToken) -> Result<MeshBuffers, String> {
+        // Vértices únicos: el índice del welder es el de `positions` / `normals`
+        let mut welder = match weld {
+            Weld::Off => None,
+            Weld::Distance(tolerance) => Some(VertexWelder::new(tolerance)),
+        };
+        let mut positions: Vec<f32> = Vec::new();
+        let mut normals: Vec<f32> = Vec::new();
         let mut indices: Vec<u32> = Vec::new();
 
-        // 3. Recorrer todas las caras
-        for face in &mesh.faces {
-            let face_normal = face.normal;
-
-            for &idx in &face.vertices {
-                let vpos = mesh.vertices[idx];
-                let key = Float3Eps::new(vpos[0], vpos[1], vpos[2]);
-
-                // ********** IMPORTANTE **********
-                let vert_index = if let Some(&existing_idx) = vertex_map.get(&key) {
-                    // Si ya existe, devolvemos su índice
-                    existing_idx
-                } else {
-                    // No existe, creamos uno nuevo
-                    let new_idx = unique_vertices.len() as u32;
-                    vertex_map.insert(key, new_idx);
-
-                    unique_vertices.push(VertexData {
-                        pos: [vpos[0], vpos[1], vpos[2]],
-                        normal: [0.0, 0.0, 0.0],
-                    });
-                    
-                    new_idx
-                };
-
-                // Acumulamos la normal de la cara en ese vértice
-                let vdata_mut = &mut unique_vertices[vert_index as usize];
-                vdata_mut.normal[0] += face_normal[0];
-                vdata_mut.normal[1] += face_normal[1];
-                vdata_mut.normal[2] += face_normal[2];
-
-                // Agregar índice al EBO
-                indices.push(vert_index);
-            }
-        }
-
-        // 4. Normalizar las normales de cada vértice
-        for v in &mut unique_vertices {
-            let nx = v.normal[0];
-            let ny = v.normal[1];
-            let nz = v.normal[2];
-            let length = (nx * nx + ny * ny + nz * nz).sqrt();
+        // 1. Recorrer todas las caras mientras se lee el archivo
+        let summary = stl::read_file(
+            path,
+            |face| {
+                for vpos in face.vertices {
+                    // Si ya existe, su índice; si no, uno nuevo con normal en cero
+                    let (vert_index, new) = match welder.as_mut() {
+                        Some(welder) => welder.weld(vpos),
+                        None => ((positions.len() / 3) as u32, true),
+                    };
+                    if new {
+                        positions.extend_from_slice(&vpos);
+                        normals.extend_from_slice(&[0.0; 3]);
+                    }
+
+                    // Acumulamos la normal de la cara en ese vértice
+                    let normal = &mut normals[vert_index as usize * 3..vert_index as usize * 3 + 3];
+                    for (n, f) in normal.iter_mut().zip(face.normal) {
+                        *n += f;
+                    }
+
+                    // Agregar índice al EBO
+                    indices.push(vert_index);
+                }
+            },
+            |done, total| {
+                progress.report(done as usize, total as usize);
+                !progress.is_cancelled()
+            },
+        )
+        .map_err(|e| match e {
+            StlError::Cancelled => CANCELLED.to_string(),
+            e => format!("Error parseando el archivo STL {}: {}", path, e),
+        })?;
+        for warning in &summary.warnings {
+            eprintln!("{}: {}", path, warning);
+        }
+
+        // 2. Normalizar las normales de cada vértice
+        for normal in normals.chunks_exact_mut(3) {
+            let length = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
             if length > 1e-8 {
-                v.normal[0] /= length;
-                v.normal[1] /= length;
-                v.normal[2] /= length;
+                normal.iter_mut().for_each(|n| *n /= length);
             }
             // si length=0 => dejarla en (0,0,0) => vértice aislado o degenerado
         }
 
-        // 5. Construir los vectores finales (positions, normals)
-        let mut positions: Vec<f32> = Vec::with_capacity(unique_vertices.len() * 3);
-        let mut normals: Vec<f32>   = Vec::with_capacity(unique_vertices.len() * 3);
+        Ok((positions, normals, indices))
+    }
 
-        for v in &unique_vertices {
-            positions.push(v.pos[0]);
-            positions.push(v.pos[1]);
-            positions.push(v.pos[2]);
+    /// Como `load_stl`, pero aborta si el archivo no se puede leer
+    pub fn create_object_from_stl(path: &str) -> SceneObject {
+        SceneObject::load_stl(path, Weld::default()).unwrap_or_else(|e| panic!("{}", e))
+    }
 
-            normals.push(v.normal[0]);
-            normals.push(v.normal[1]);
-            normals.push(v.normal[2]);
+    /// Carga un STL; los de más de `LARGE_STL_BYTES` informan el avance por consola
+    pub fn load_stl(path: &str, weld: Weld) -> Result<SceneObject, String> {
+        let large = std::fs::metadata(path).is_ok_and(|metadata| metadata.len() >= LARGE_STL_BYTES);
+        if !large {
+            return SceneObject::load_stl_with_progress(path, weld, &ProgressToken::new());
         }
+        let (name, mut reported) = (path.to_string(), 0);
+        let progress = ProgressToken::with_callback(move |fraction| {
+            let step = (fraction * 10.0) as u32;
+            if step > reported {
+                reported = step;
+                println!("{}: {}%", name, step * 10);
+            }
+        });
+        SceneObject::load_stl_with_progress(path, weld, &progress)
+    }
 
-        (positions, normals, indices)
+    /// `load_stl` informando el avance a `progress`; si lo cancelan devuelve
+    /// `Err(CANCELLED)` sin haber creado nada en la GPU
+    pub fn load_stl_with_progress(path: &str, weld: Weld, progress: &ProgressToken) -> Result<SceneObject, String> {
+        // Carga el STL con tus normales "smooth"
+        let (positions, normals, indices) = SceneObject::load_stl_model_smooth(path, weld, progress)?;
+        let mut obj = SceneObject::from_buffers(&positions, &normals, indices);
+        obj.source = Some(path.to_string());
+        obj.weld = weld;
+        obj.name = file_stem(path);
+        Ok(obj)
     }
 
-    pub fn create_object_from_stl(path: &str) -> SceneObject {
-        // 1) Carga el STL con tus normales "smooth"
-        let (positions, normals, indices) = SceneObject::load_stl_model_smooth(path);
-    
-        // 2) Genera VAO, VBO pos, VBO normal, EBO
+    /// Carga un .gltf o .glb (ver `graphics::loaders::gltf`) como un solo objeto
+    #[cfg(feature = "gltf")]
+    pub fn create_object_from_gltf(path: &str) -> Result<SceneObject, String> {
+        SceneObject::from_loaded(gltf::load(path)?, path)
+    }
+
+    /// Carga un .obj (ver `graphics::loaders::obj`) como un objeto con un
+    /// sub-mesh por material
+    pub fn create_object_from_obj(path: &str) -> Result<SceneObject, String> {
+        SceneObject::from_loaded(obj::load(path)?, path)
+    }
+
+    /// Sube lo que leyó un cargador y le pone `path` como origen y nombre
+    fn from_loaded(loaded: LoadedMesh, path: &str) -> Result<SceneObject, String> {
+        let LoadedMesh { mesh, normals, submeshes } = loaded;
+        let positions: Vec<f32> = mesh.positions.iter().flatten().copied().collect();
+        let normals: Vec<f32> = normals.into_iter().flatten().collect();
+        let mut obj = SceneObject::from_buffers(&positions, &normals, mesh.indices);
+        if !mesh.uvs.is_empty() {
+            obj.set_uvs(mesh.uvs);
+        }
+        obj.set_submeshes(submeshes)?;
+        obj.source = Some(path.to_string());
+        obj.name = file_stem(path);
+        Ok(obj)
+    }
+
+    /// Sube una malla con normales suavizadas (y sus UVs, si tiene) y crea el
+    /// objeto, como `set_mesh`
+    pub fn from_mesh(mesh: Mesh) -> SceneObject {
+        let positions: Vec<f32> = mesh.positions.iter().flatten().copied().collect();
+        let normals: Vec<f32> = vertex_normals(&mesh).into_iter().flatten().collect();
+        let mut obj = SceneObject::from_buffers(&positions, &normals, mesh.indices);
+        if !mesh.uvs.is_empty() {
+            obj.set_uvs(mesh.uvs);
+        }
+        obj
+    }
+
+    /// Sube posiciones y normales (x, y, z intercalados) e índices de triángulos
+    /// a la GPU y crea el objeto. Lo usan todos los cargadores de modelos.
+    pub fn from_buffers(positions: &[f32], normals: &[f32], indices: Vec<u32>) -> SceneObject {
+        // 1) Genera VAO, VBO pos, VBO normal, EBO
         let mut vao = 0;
         let mut vbo_pos = 0;
         let mut vbo_nor = 0;
@@ -147,7 +897,7 @@ impl SceneObject{
             gl::BindBuffer(gl::ARRAY_BUFFER, vbo_pos);
             gl::BufferData(
                 gl::ARRAY_BUFFER,
-                (positions.len() * std::mem::size_of::<f32>()) as isize,
+                std::mem::size_of_val(positions) as isize,
                 positions.as_ptr() as *const _,
                 gl::STATIC_DRAW,
             );
@@ -161,7 +911,7 @@ impl SceneObject{
             gl::BindBuffer(gl::ARRAY_BUFFER, vbo_nor);
             gl::BufferData(
                 gl::ARRAY_BUFFER,
-                (normals.len() * std::mem::size_of::<f32>()) as isize,
+                std::mem::size_of_val(normals) as isize,
                 normals.as_ptr() as *const _,
                 gl::STATIC_DRAW,
             );
@@ -184,15 +934,191 @@ impl SceneObject{
             gl::BindVertexArray(0);
         }
     
+        // 2) Guardar la geometría en CPU (picking de sub-objetos, medidas)
+        let mesh = Mesh::new(
+            positions.chunks_exact(3).map(|p| [p[0], p[1], p[2]]).collect(),
+            indices,
+        );
+
         // 3) Crear el SceneObject
         SceneObject {
             vao,
             index_count,
-            base_transform: Matrix4::identity(),
-            angle: 0.0,           // <--- valor por defecto
-            angular_speed: 0.0,   // <--- valor por defecto
-            scale_factor: 1.0,    // <--- valor por defecto
+            transform: Transform::identity(),
+            angular_velocity: Vec3::ZERO,
+            mesh,
+            normals: normals.chunks_exact(3).map(|n| [n[0], n[1], n[2]]).collect(),
+            source: None,
+            name: String::new(),
+            geometry_buffers: [vbo_pos, vbo_nor, ebo],
+            uv_buffer: 0,
+            lightmap: None,
+            lightmap_uv_buffer: 0,
+            gpu_vertices: Vec::new(),
+            color_buffer: 0,
+            vertex_colors: None,
+            color: None,
+            material: None,
+            submeshes: Vec::new(),
+            morph_targets: Vec::new(),
+            morph_buffers: Vec::new(),
+            morph_weights: [0.0; MAX_MORPH_TARGETS],
+            animator: None,
+            skeleton: None,
+            uniforms: UniformOverrides::default(),
+            render_order: 0,
+            overlay: false,
+            group: None,
+            group_transform: Matrix4::identity(),
+            hidden: false,
+            ignore_clipping: false,
+            weld: Weld::default(),
+            pivot: None,
+            link: None,
+            transform_cache: Cell::new(None),
         }
     }
     
-}
\ No newline at end of file
+}
+
+/// Datos por vértice de la malla en el orden de los vértices de la GPU
+/// (`SceneObject::gpu_vertices`; vacío: el mismo)
+fn for_gpu<'a, T: Clone>(gpu_vertices: &[u32], data: &'a [T]) -> Cow<'a, [T]> {
+    if gpu_vertices.is_empty() {
+        Cow::Borrowed(data)
+    } else {
+        Cow::Owned(gpu_vertices.iter().map(|&vertex| data[vertex as usize].clone()).collect())
+    }
+}
+
+/// Vértices que se suben a la GPU para dibujar `mesh` con sus UVs de
+/// lightmap: un vértice por cada par (vértice, UV) distinto. Devuelve el
+/// vértice de la malla de cada uno, los índices y la UV de cada uno. Sin UVs
+/// de lightmap son los mismos vértices de la malla (primera lista vacía).
+fn split_lightmap_seams(mesh: &Mesh) -> (Vec<u32>, Vec<u32>, Vec<[f32; 2]>) {
+    if mesh.lightmap_uvs.len() != mesh.indices.len() {
+        return (Vec::new(), mesh.indices.clone(), Vec::new());
+    }
+    let mut vertices = Vec::new();
+    let mut uvs = Vec::new();
+    let mut ids: HashMap<(u32, [u32; 2]), u32> = HashMap::new();
+    let indices = mesh
+        .indices
+        .iter()
+        .zip(&mesh.lightmap_uvs)
+        .map(|(&vertex, uv)| {
+            *ids.entry((vertex, uv.map(f32::to_bits))).or_insert_with(|| {
+                vertices.push(vertex);
+                uvs.push(*uv);
+                vertices.len() as u32 - 1
+            })
+        })
+        .collect();
+    (vertices, indices, uvs)
+}
+
+/// Nombre del archivo sin carpeta ni extensión
+pub fn file_stem(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numeric_transform_input() {
+        // Cubo de 2 de lado centrado en (1, 1, 1)
+        let mut obj = SceneObject::new(0, 0);
+        obj.mesh = Mesh::new(vec![[0.0, 0.0, 0.0], [2.0, 2.0, 2.0], [2.0, 0.0, 0.0]], vec![0, 1, 2]);
+        obj.set_position_mm(Vec3::new(15.0, 0.0, -2.5), LengthUnit::Centimeters);
+        assert!(obj.position().approx_eq(&Vec3::new(1.5, 0.0, -0.25), 1e-6));
+
+        // Sin pivote propio gira alrededor del centro de la caja, que queda quieto
+        let center = obj.pivot_point();
+        obj.rotate_degrees(Vec3::UNIT_Z, 90.0, None).unwrap();
+        assert!(obj.pivot_point().approx_eq(&center, 1e-5));
+        let corner = obj.transform.transform_point(Vec3::new(2.0, 0.0, 0.0));
+        assert!(corner.approx_eq(&Vec3::new(3.5, 2.0, -0.25), 1e-5));
+
+        // Con pivote en el origen de la malla y un punto de giro explícito
+        obj.pivot = Some(Vec3::new(0.0, 0.0, 0.0));
+        assert!(obj.pivot_point().approx_eq(&obj.position(), 1e-6));
+        obj.transform = Transform::identity();
+        obj.rotate_degrees(Vec3::new(0.0, 0.0, 2.0), 180.0, Some(Vec3::new(1.0, 0.0, 0.0))).unwrap();
+        assert!(obj.position().approx_eq(&Vec3::new(2.0, 0.0, 0.0), 1e-5));
+        assert!(obj.rotate_degrees(Vec3::new(0.0, 0.0, 0.0), 10.0, None).is_err());
+
+        // Girado 180° en Z, el X local apunta a -X; en pantalla X es la derecha de la cámara
+        let view = Matrix4::look_at(Vec3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, 0.0), Vec3::UNIT_Y);
+        obj.translate_in(TransformSpace::Local, Vec3::new(1.0, 0.0, 0.0), &view);
+        assert!(obj.position().approx_eq(&Vec3::new(1.0, 0.0, 0.0), 1e-5));
+        let side = Matrix4::look_at(Vec3::new(5.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 0.0), Vec3::UNIT_Y);
+        obj.translate_in(TransformSpace::Screen, Vec3::new(2.0, 0.0, 0.0), &side);
+        assert!(obj.position().approx_eq(&Vec3::new(1.0, 0.0, -2.0), 1e-5));
+        // Un giro en el eje Z local es el mismo que en el Z del mundo, por el pivote
+        obj.rotate_in(TransformSpace::Local, Vec3::UNIT_Z, 180.0, &view).unwrap();
+        assert!(obj.position().approx_eq(&Vec3::new(1.0, 0.0, -2.0), 1e-5));
+        assert!(obj.space_axes(TransformSpace::Local, &view)[0].approx_eq(&Vec3::UNIT_X, 1e-5));
+        assert_eq!(TransformSpace::parse(" Pantalla ").unwrap(), TransformSpace::Screen);
+        assert_eq!(TransformSpace::Screen.next(), TransformSpace::World);
+    }
+
+    #[test]
+    fn test_weld_tolerance() {
+        assert_eq!(Weld::parse("off").unwrap(), Weld::Off);
+        assert_eq!(Weld::parse(" 0.5 ").unwrap(), Weld::Distance(0.5));
+        assert!(Weld::parse("0").is_err());
+        assert!(Weld::parse("mucho").is_err());
+
+        // Dos triángulos que comparten una arista, con una esquina corrida 0.01
+        let path = std::env::temp_dir().join(format!("rust_engine_weld_{}.stl", std::process::id()));
+        let facet = |v: [[f32; 3]; 3]| {
+            let vertices: String = v.iter().map(|p| format!("vertex {} {} {}\n", p[0], p[1], p[2])).collect();
+            format!("facet normal 0 0 1\nouter loop\n{}endloop\nendfacet\n", vertices)
+        };
+        let text = format!(
+            "solid pieza\n{}{}endsolid pieza\n",
+            facet([[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]),
+            facet([[1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.01, 0.0]]),
+        );
+        std::fs::write(&path, text).unwrap();
+        let path = path.to_string_lossy();
+        let vertices = |weld| {
+            let (positions, _, indices) = SceneObject::load_stl_model_smooth(&path, weld, &ProgressToken::new()).unwrap();
+            assert_eq!(indices.len(), 6);
+            positions.len() / 3
+        };
+        assert_eq!(vertices(Weld::default()), 5);
+        assert_eq!(vertices(Weld::Distance(0.05)), 4);
+        assert_eq!(vertices(Weld::Off), 6);
+        std::fs::remove_file(path.as_ref()).ok();
+    }
+
+    #[test]
+    fn test_lightmap_seams_split_only_on_gpu() {
+        // Dos caras de una esquina en L: comparten la arista 0-1 pero miran a
+        // lados distintos, así que caen en islas distintas del lightmap
+        let mut obj = SceneObject::new(0, 0);
+        obj.mesh = Mesh::new(
+            vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 0.0, 1.0], [0.0, 0.0, 1.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+            vec![0, 2, 1, 0, 3, 2, 0, 1, 4, 0, 4, 5],
+        );
+        assert_eq!(split_lightmap_seams(&obj.mesh), (Vec::new(), obj.mesh.indices.clone(), Vec::new()));
+        obj.generate_lightmap_uvs(64);
+        assert_eq!(obj.mesh.positions.len(), 6);
+        assert_eq!(obj.mesh.lightmap_uvs.len(), 12);
+
+        // Los vértices 0 y 1 de la arista compartida se repiten en la GPU
+        let (vertices, indices, uvs) = split_lightmap_seams(&obj.mesh);
+        assert_eq!(vertices.len(), 8);
+        assert_eq!(uvs.len(), 8);
+        let corners: Vec<u32> = indices.iter().map(|&i| vertices[i as usize]).collect();
+        assert_eq!(corners, obj.mesh.indices);
+        assert_eq!(for_gpu(&vertices, &obj.mesh.positions).len(), 8);
+    }
+}
@@ -0,0 +1,196 @@
+// src/graphics/shader_variants.rs
+//
+// Variantes del shader principal según lo que usa cada objeto (colores por
+// vértice, lightmap...). Cada combinación de características se compila con
+// sus `#define` la primera vez que un objeto la pide y queda cacheada, así un
+// objeto sin lightmap no paga el muestreo de una textura que no tiene.
+
+use std::collections::HashMap;
+use std::ops::BitOr;
+
+use crate::graphics::gpu_resources::ContextGeneration;
+use crate::graphics::lightmap::LightmapMode;
+use crate::graphics::scene_object::SceneObject;
+use crate::graphics::shaders::ShaderLibrary;
+
+/// Características del shader principal, una por bit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ShaderFeatures(u32);
+
+impl ShaderFeatures {
+    pub const NONE: Self = Self(0);
+    /// Color por vértice en vez de `objectColor`
+    pub const VERTEX_COLORS: Self = Self(1 << 0);
+    /// Lightmap de oclusión ambiental
+    pub const LIGHTMAP_AO: Self = Self(1 << 1);
+    /// Lightmap de iluminación completa
+    pub const LIGHTMAP_GI: Self = Self(1 << 2);
+    /// Damero sobre las UVs en lugar del color (depuración del mapeo)
+    pub const UV_CHECKER: Self = Self(1 << 3);
+    /// Textura de color del material (sobre las UVs)
+    pub const COLOR_MAP: Self = Self(1 << 4);
+    /// Deltas de morph targets sumados en el vertex shader
+    pub const MORPH_TARGETS: Self = Self(1 << 5);
+    /// Reflejo del entorno HDR prefiltrado según la rugosidad
+    pub const ENVIRONMENT_MAP: Self = Self(1 << 6);
+
+    /// Nombre del `#define` de cada bit
+    const DEFINES: [(Self, &'static str); 7] = [
+        (Self::VERTEX_COLORS, "VERTEX_COLORS"),
+        (Self::LIGHTMAP_AO, "LIGHTMAP_AO"),
+        (Self::LIGHTMAP_GI, "LIGHTMAP_GI"),
+        (Self::UV_CHECKER, "UV_CHECKER"),
+        (Self::COLOR_MAP, "COLOR_MAP"),
+        (Self::MORPH_TARGETS, "MORPH_TARGETS"),
+        (Self::ENVIRONMENT_MAP, "ENVIRONMENT_MAP"),
+    ];
+
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Lo mínimo que necesita el objeto para dibujarse bien
+    pub fn for_object(obj: &SceneObject) -> Self {
+        let mut features = Self::NONE;
+        if obj.has_vertex_colors() {
+            features = features | Self::VERTEX_COLORS;
+        }
+        if obj.has_morph_targets() {
+            features = features | Self::MORPH_TARGETS;
+        }
+        if let Some(lightmap) = obj.lightmap.filter(|_| !obj.mesh.lightmap_uvs.is_empty()) {
+            features = features | match lightmap.mode {
+                LightmapMode::AmbientOcclusion => Self::LIGHTMAP_AO,
+                LightmapMode::GlobalIllumination => Self::LIGHTMAP_GI,
+            };
+        }
+        features
+    }
+
+    /// Defines con los que se compila la variante
+    pub fn defines(&self) -> Vec<(&'static str, &'static str)> {
+        Self::DEFINES
+            .iter()
+            .filter(|(bit, _)| self.contains(*bit))
+            .map(|(_, name)| (*name, ""))
+            .collect()
+    }
+}
+
+impl BitOr for ShaderFeatures {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Programas del shader principal compilados a pedido, uno por combinación
+pub struct ShaderVariants {
+    library: ShaderLibrary,
+    vert_src: String,
+    frag_src: String,
+    /// None si la variante no compiló (se usa la base en su lugar)
+    programs: HashMap<ShaderFeatures, Option<u32>>,
+    base: u32,
+    generation: ContextGeneration,
+}
+
+impl ShaderVariants {
+    /// Compila la variante base en el momento, para que un error en el
+    /// shader se note al crear el renderer y no en el primer frame
+    pub fn new(library: ShaderLibrary, vert_src: &str, frag_src: &str) -> Result<Self, String> {
+        let base = library.build(vert_src, frag_src, &[])?;
+        Ok(Self {
+            library,
+            vert_src: vert_src.to_string(),
+            frag_src: frag_src.to_string(),
+            programs: HashMap::from([(ShaderFeatures::NONE, Some(base))]),
+            base,
+            generation: ContextGeneration::current(),
+        })
+    }
+
+    /// Programa sin características
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+
+    /// Programa de la variante, compilándolo si es la primera vez. Si no
+    /// compila se avisa una sola vez y se usa la base.
+    pub fn get(&mut self, features: ShaderFeatures) -> u32 {
+        let (library, vert_src, frag_src) = (&self.library, &self.vert_src, &self.frag_src);
+        let program = *self.programs.entry(features).or_insert_with(|| {
+            match library.build(vert_src, frag_src, &features.defines()) {
+                Ok(program) => Some(program),
+                Err(e) => {
+                    eprintln!("Variante de shader {:#x} desactivada: {}", features.bits(), e);
+                    None
+                }
+            }
+        });
+        program.unwrap_or(self.base)
+    }
+
+    /// Cantidad de variantes compiladas (incluida la base)
+    pub fn compiled(&self) -> usize {
+        self.programs.values().filter(|p| p.is_some()).count()
+    }
+}
+
+impl Drop for ShaderVariants {
+    fn drop(&mut self) {
+        if !self.generation.is_current() {
+            return;
+        }
+        for program in self.programs.values().flatten() {
+            unsafe {
+                gl::DeleteProgram(*program);
+            }
+        }
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::gl_mock::{self, GlCall};
+
+    #[test]
+    fn test_variants_compile_lazily() {
+        gl_mock::install();
+        let mut obj = SceneObject::new(1, 3);
+        assert_eq!(ShaderFeatures::for_object(&obj), ShaderFeatures::NONE);
+        obj.set_vertex_colors(Some(&[]));
+        let features = ShaderFeatures::for_object(&obj);
+        assert_eq!(features, ShaderFeatures::VERTEX_COLORS);
+        assert_eq!((features | ShaderFeatures::LIGHTMAP_AO).defines(), vec![("VERTEX_COLORS", ""), ("LIGHTMAP_AO", "")]);
+
+        let frag = "#version 330 core\nvoid main() {}";
+        let mut variants = ShaderVariants::new(ShaderLibrary::new(), frag, frag).unwrap();
+        assert_eq!(variants.get(ShaderFeatures::NONE), variants.base());
+        gl_mock::take_calls();
+
+        let colored = variants.get(features);
+        assert_ne!(colored, variants.base());
+        let sources: Vec<String> = gl_mock::take_calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                GlCall::ShaderSource(source) => Some(source),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(sources.len(), 2);
+        assert!(sources.iter().all(|s| s.contains("#define VERTEX_COLORS\n")));
+
+        // La segunda vez sale de la caché
+        assert_eq!(variants.get(features), colored);
+        assert!(gl_mock::take_calls().is_empty());
+        assert_eq!(variants.compiled(), 2);
+    }
+}
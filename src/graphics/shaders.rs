@@ -1,6 +1,16 @@
 // src/graphics/shaders.rs
+//
+// Compilación de shaders y un preprocesador mínimo: `#include "archivo.glsl"`
+// pega un fragmento registrado en la `ShaderLibrary` y los `#define` de la
+// librería (MAX_LIGHTS, USE_NORMAL_MAP...) se inyectan después de `#version`.
+// Se emiten directivas `#line` para que los errores del driver apunten al
+// archivo y la línea originales ("<archivo>(<línea>)", ver la leyenda del error).
+// Con una `ProgramCache` los programas enlazados se guardan en disco y en los
+// arranques siguientes se cargan sin compilar.
 
+use std::collections::HashMap;
 use std::ffi::CString;
+use crate::graphics::program_cache::ProgramCache;
 use gl::types::*; // para GLchar, GLuint, etc.
 use std::ptr;
 use std::str;
@@ -27,8 +37,16 @@ pub fn compile_shader(src: &str, shader_type: GLenum) -> Result<u32, String> {
 }
 
 pub fn link_program(vertex_shader: u32, fragment_shader: u32) -> Result<u32, String> {
+    link(vertex_shader, fragment_shader, false)
+}
+
+/// `retrievable`: pedirle al driver que conserve el binario para `ProgramCache`
+fn link(vertex_shader: u32, fragment_shader: u32, retrievable: bool) -> Result<u32, String> {
     unsafe {
         let program = gl::CreateProgram();
+        if retrievable && gl::ProgramParameteri::is_loaded() {
+            gl::ProgramParameteri(program, gl::PROGRAM_BINARY_RETRIEVABLE_HINT, gl::TRUE as GLint);
+        }
         gl::AttachShader(program, vertex_shader);
         gl::AttachShader(program, fragment_shader);
         gl::LinkProgram(program);
@@ -50,3 +68,228 @@ pub fn link_program(vertex_shader: u32, fragment_shader: u32) -> Result<u32, Str
         Ok(program)
     }
 }
+
+/// Compila y enlaza un programa a partir del código fuente de ambos shaders
+/// (con los fragmentos incluidos en el motor disponibles para `#include`)
+pub fn build_program(vert_src: &str, frag_src: &str) -> Result<u32, String> {
+    ShaderLibrary::new().build(vert_src, frag_src, &[])
+}
+
+/// Fragmentos de GLSL que trae el motor
+const BUILTIN_CHUNKS: [(&str, &str); 2] = [
+    ("lighting.glsl", include_str!("shaders/lighting.glsl")),
+    ("clipping.glsl", include_str!("shaders/clipping.glsl")),
+];
+
+/// Fragmentos para `#include` y defines comunes a todos los programas
+#[derive(Debug, Clone)]
+pub struct ShaderLibrary {
+    chunks: HashMap<String, String>,
+    defines: Vec<(String, String)>,
+    program_cache: Option<ProgramCache>,
+}
+
+impl Default for ShaderLibrary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShaderLibrary {
+    /// Librería con los fragmentos del motor y sin defines
+    pub fn new() -> Self {
+        let chunks = BUILTIN_CHUNKS.iter().map(|(name, source)| (name.to_string(), source.to_string())).collect();
+        Self { chunks, defines: Vec::new(), program_cache: None }
+    }
+
+    /// Guardar y reusar los programas enlazados en disco (None para no usar caché)
+    pub fn set_program_cache(&mut self, cache: Option<ProgramCache>) {
+        self.program_cache = cache;
+    }
+
+    pub fn program_cache(&self) -> Option<&ProgramCache> {
+        self.program_cache.as_ref()
+    }
+
+    /// Registra (o reemplaza) un fragmento para `#include "<name>"`
+    pub fn add_chunk(&mut self, name: &str, source: &str) {
+        self.chunks.insert(name.to_string(), source.to_string());
+    }
+
+    /// Define para todos los programas que se compilen con la librería
+    /// (`value` vacío para un simple `#define NAME`)
+    pub fn define(&mut self, name: &str, value: &str) {
+        match self.defines.iter_mut().find(|(n, _)| n == name) {
+            Some((_, current)) => *current = value.to_string(),
+            None => self.defines.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn undefine(&mut self, name: &str) {
+        self.defines.retain(|(n, _)| n != name);
+    }
+
+    /// Resuelve los includes e inyecta los defines (los de la librería y
+    /// después `extra`). Devuelve el código y los archivos por número de
+    /// fuente de las directivas `#line` (0 = el código principal).
+    pub fn preprocess(&self, source: &str, extra: &[(&str, &str)]) -> Result<(String, Vec<String>), String> {
+        let mut files = vec!["<principal>".to_string()];
+        let mut lines = source.lines().enumerate().peekable();
+        let mut output = String::new();
+
+        // `#version` tiene que quedar primero
+        if let Some((_, first)) = lines.peek() {
+            if first.trim_start().starts_with("#version") {
+                output.push_str(first);
+                output.push('\n');
+                lines.next();
+            }
+        }
+        let defines = self.defines.iter().map(|(n, v)| (n.as_str(), v.as_str())).chain(extra.iter().copied());
+        for (name, value) in defines {
+            if value.is_empty() {
+                output.push_str(&format!("#define {}\n", name));
+            } else {
+                output.push_str(&format!("#define {} {}\n", name, value));
+            }
+        }
+        let first_line = lines.peek().map(|(i, _)| i + 1).unwrap_or(1);
+        output.push_str(&format!("#line {} 0\n", first_line));
+
+        let rest: Vec<(usize, &str)> = lines.collect();
+        self.expand(&rest, 0, &mut files, &mut Vec::new(), &mut output)?;
+        Ok((output, files))
+    }
+
+    fn expand<'a>(
+        &'a self,
+        lines: &[(usize, &'a str)],
+        file: usize,
+        files: &mut Vec<String>,
+        stack: &mut Vec<&'a str>,
+        output: &mut String,
+    ) -> Result<(), String> {
+        for &(index, line) in lines {
+            let Some(name) = include_target(line) else {
+                output.push_str(line);
+                output.push('\n');
+                continue;
+            };
+            if stack.contains(&name) {
+                return Err(format!("Include circular: {} -> {}", stack.join(" -> "), name));
+            }
+            let chunk = self
+                .chunks
+                .get(name)
+                .ok_or_else(|| format!("{}({}): no existe el include \"{}\"", files[file], index + 1, name))?;
+            files.push(name.to_string());
+            let id = files.len() - 1;
+            output.push_str(&format!("#line 1 {}\n", id));
+            stack.push(name);
+            let chunk_lines: Vec<(usize, &str)> = chunk.lines().enumerate().collect();
+            self.expand(&chunk_lines, id, files, stack, output)?;
+            stack.pop();
+            output.push_str(&format!("#line {} {}\n", index + 2, file));
+        }
+        Ok(())
+    }
+
+    /// Preprocesa y compila un shader; los errores llevan la leyenda de archivos
+    pub fn compile(&self, source: &str, shader_type: GLenum, defines: &[(&str, &str)]) -> Result<u32, String> {
+        let (code, files) = self.preprocess(source, defines)?;
+        compile_shader(&code, shader_type).map_err(|e| {
+            let legend: Vec<String> = files.iter().enumerate().map(|(i, f)| format!("{} = {}", i, f)).collect();
+            format!("{}(archivos: {})", e, legend.join(", "))
+        })
+    }
+
+    /// Compila y enlaza un programa con los includes y defines de la librería
+    /// (o lo carga de la caché de programas si ya se compiló antes)
+    pub fn build(&self, vert_src: &str, frag_src: &str, defines: &[(&str, &str)]) -> Result<u32, String> {
+        let key = match &self.program_cache {
+            Some(cache) => {
+                let (vert_code, _) = self.preprocess(vert_src, defines)?;
+                let (frag_code, _) = self.preprocess(frag_src, defines)?;
+                let key = cache.key(&vert_code, &frag_code);
+                if let Some(program) = cache.load(&key) {
+                    return Ok(program);
+                }
+                Some(key)
+            }
+            None => None,
+        };
+
+        let vs = self.compile(vert_src, gl::VERTEX_SHADER, defines)?;
+        let fs = match self.compile(frag_src, gl::FRAGMENT_SHADER, defines) {
+            Ok(fs) => fs,
+            Err(e) => {
+                unsafe { gl::DeleteShader(vs) };
+                return Err(e);
+            }
+        };
+        let program = link(vs, fs, key.is_some());
+        unsafe {
+            gl::DeleteShader(vs);
+            gl::DeleteShader(fs);
+        }
+        if let (Ok(program), Some(cache), Some(key)) = (&program, &self.program_cache, &key) {
+            cache.store(key, *program);
+        }
+        program
+    }
+}
+
+/// Nombre del archivo si la línea es `#include "nombre"`
+fn include_target(line: &str) -> Option<&str> {
+    let rest = line.trim().strip_prefix("#include")?;
+    rest.trim().strip_prefix('"')?.strip_suffix('"')
+}
+
+/// Ubicación de un uniform por nombre (-1 si no existe o el compilador lo eliminó)
+pub fn uniform_location(program: u32, name: &str) -> i32 {
+    let c_name = CString::new(name).unwrap();
+    unsafe { gl::GetUniformLocation(program, c_name.as_ptr()) }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preprocess_includes_and_defines() {
+        let mut library = ShaderLibrary::new();
+        library.add_chunk("a.glsl", "float a() { return 1.0; }\n#include \"b.glsl\"");
+        library.add_chunk("b.glsl", "float b() { return MAX_LIGHTS; }");
+        library.define("MAX_LIGHTS", "4");
+
+        let source = "#version 330 core\n#include \"a.glsl\"\nvoid main() {}";
+        let (code, files) = library.preprocess(source, &[("USE_NORMAL_MAP", "")]).unwrap();
+        let lines: Vec<&str> = code.lines().collect();
+        assert_eq!(lines[..4], ["#version 330 core", "#define MAX_LIGHTS 4", "#define USE_NORMAL_MAP", "#line 2 0"]);
+        assert!(code.contains("#line 1 1\nfloat a()"));
+        assert!(code.contains("#line 1 2\nfloat b()"));
+        assert!(code.ends_with("#line 3 0\nvoid main() {}\n"));
+        assert_eq!(files, vec!["<principal>", "a.glsl", "b.glsl"]);
+        assert!(library.preprocess("#include \"lighting.glsl\"", &[]).is_ok());
+
+        library.add_chunk("b.glsl", "#include \"a.glsl\"");
+        assert!(library.preprocess(source, &[]).unwrap_err().contains("circular"));
+        assert!(library.preprocess("#include \"nada.glsl\"", &[]).is_err());
+    }
+
+    #[test]
+    fn test_shaders_are_core_profile() {
+        // macOS solo da perfiles core: nada de GLSL de compatibilidad
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/src/graphics/shaders");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let source = std::fs::read_to_string(&path).unwrap();
+            let is_chunk = path.extension().is_some_and(|e| e == "glsl");
+            assert!(is_chunk || source.starts_with("#version 330 core"), "{:?} sin #version 330 core", path);
+            for legacy in ["gl_FragColor", "texture2D", "attribute ", "varying ", "gl_ModelViewMatrix"] {
+                assert!(!source.contains(legacy), "{:?} usa {}", path, legacy);
+            }
+        }
+    }
+}
@@ -0,0 +1,25 @@
+#version 330 core
+
+in vec3 vNormal;
+in float vDepth;
+
+uniform int mode;       // 0 = profundidad, 1 = normal, 2 = id, 3 = máscara
+uniform float objectId; // índice del objeto + 1 (0 = fondo)
+uniform float maskId;   // objeto de la máscara + 1
+
+out vec4 FragColor;
+
+void main()
+{
+    if (mode == 0) {
+        FragColor = vec4(vec3(vDepth), 1.0);
+    } else if (mode == 1) {
+        // Normal del lado visible, en espacio mundo
+        vec3 n = normalize(vNormal);
+        FragColor = vec4(gl_FrontFacing ? n : -n, 1.0);
+    } else if (mode == 2) {
+        FragColor = vec4(vec3(objectId), 1.0);
+    } else {
+        FragColor = vec4(vec3(objectId == maskId ? 1.0 : 0.0), 1.0);
+    }
+}
@@ -0,0 +1,21 @@
+#version 330 core
+layout(location = 0) in vec3 aPos;
+layout(location = 1) in vec3 aNormal;
+
+uniform mat4 model;
+// Inversa traspuesta de model, calculada en la CPU
+uniform mat4 normalMatrix;
+uniform mat4 view;
+uniform mat4 projection;
+
+out vec3 vNormal;
+out float vDepth; // distancia a la cámara a lo largo de la vista
+
+void main()
+{
+    vec4 worldPos = model * vec4(aPos, 1.0);
+    vec4 viewPos = view * worldPos;
+    vDepth = -viewPos.z;
+    vNormal = mat3(normalMatrix) * aNormal;
+    gl_Position = projection * viewPos;
+}
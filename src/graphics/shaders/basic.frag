@@ -2,6 +2,9 @@
 
 in vec3 vNormal;    // Viene del vertex shader
 in vec3 vWorldPos;  // no lo usamos mucho ahora, pero podría servir
+in vec2 vUV;
+in vec3 vColor;
+in vec2 vLightmapUV;
 
 out vec4 FragColor;
 
@@ -9,25 +12,88 @@ out vec4 FragColor;
 uniform vec3 lightDir;   // dirección de la luz
 uniform vec3 lightColor; // color de la luz
 uniform vec3 objectColor; // color base del objeto
+uniform float ambient;    // fracción del color base sin luz directa
+uniform float roughness;  // del material: 1 mate (sin brillo)
+uniform float metallic;   // del material: 1 el brillo toma el color base y no hay difuso
+uniform vec3 viewPos;     // posición de la cámara en espacio mundo
+// Luces de relleno sin sombra (ver lighting.rs, FILL_LIGHTS); negras = apagadas
+uniform vec3 fillDirs[2];
+uniform vec3 fillColors[2];
+uniform float gamma;      // corrección gamma del color final (1.0 = ninguna)
+
+// Variantes (ver shader_variants.rs):
+//   VERTEX_COLORS: usar el color por vértice (aColor) en vez de objectColor
+//   LIGHTMAP_AO / LIGHTMAP_GI: lightmap horneado de oclusión ambiental o de iluminación completa
+//   UV_CHECKER: damero sobre las UVs en vez del color (depuración del mapeo)
+//   COLOR_MAP: textura de color del material, multiplicada por el color base
+//   ENVIRONMENT_MAP: reflejo del entorno HDR, del mip prefiltrado según la rugosidad
+#if defined(LIGHTMAP_AO) || defined(LIGHTMAP_GI)
+uniform sampler2D lightmap;
+#endif
+#ifdef COLOR_MAP
+uniform sampler2D colorMap;
+#endif
+#ifdef ENVIRONMENT_MAP
+uniform samplerCube environmentMap;
+uniform float environmentLod;      // mip del cubemap prefiltrado para la rugosidad del material
+uniform float environmentExposure; // la del cielo
+#endif
+
+#include "lighting.glsl"
+#include "clipping.glsl"
 
 void main()
 {
-    // 1) Normalizar la normal
-    vec3 N = normalize(vNormal);
-    // 2) Direccion de la luz
-    //    Si 'lightDir' apunta DESDE el objeto hacia la luz, pon L = -lightDir, o viceversa.
-    vec3 L = normalize(lightDir);
+    if (isClipped(vWorldPos)) {
+        discard;
+    }
+#if defined(UV_CHECKER)
+    // 8x8 casillas por unidad de UV, teñidas por la posición en la textura
+    // (u hacia el rojo, v hacia el verde) para ver orientación y costuras
+    vec2 cell = floor(vUV * 8.0);
+    float dark = mod(cell.x + cell.y, 2.0);
+    vec3 tint = vec3(0.25 + 0.75 * fract(vUV), 0.6);
+    vec3 baseColor = tint * mix(1.0, 0.3, dark);
+#elif defined(VERTEX_COLORS)
+    vec3 baseColor = vColor;
+#else
+    vec3 baseColor = objectColor;
+#endif
+#ifdef COLOR_MAP
+    baseColor *= texture(colorMap, vUV).rgb;
+#endif
 
-    // 3) Difuso (Lambert)
-    float diff = max(dot(N, L), 0.0);
+    // 1) Difuso (Lambert) del sol y de las luces de relleno + ambiente, más el brillo
+    //    especular del material (blanco en dieléctricos, del color base en metales)
+    //    Si 'lightDir' apunta DESDE el objeto hacia la luz, pon L = -lightDir, o viceversa.
+    vec3 viewDir = normalize(viewPos - vWorldPos);
+    vec3 diffuseColor = baseColor * (1.0 - metallic);
+    vec3 specColor = mix(vec3(0.04), baseColor, metallic);
+    vec3 finalColor = ambient * baseColor + diffuse(vNormal, lightDir, lightColor, diffuseColor)
+        + specular(vNormal, lightDir, viewDir, lightColor, specColor, roughness);
+    for (int i = 0; i < 2; i++) {
+        finalColor += diffuse(vNormal, fillDirs[i], fillColors[i], diffuseColor)
+            + specular(vNormal, fillDirs[i], viewDir, fillColors[i], specColor, roughness);
+    }
 
-    // 4) Color difuso
-    vec3 diffuse = diff * lightColor * objectColor;
+    // 2) Aplicar el lightmap
+#if defined(LIGHTMAP_AO)
+    finalColor *= texture(lightmap, vLightmapUV).r;
+#elif defined(LIGHTMAP_GI)
+    finalColor = baseColor * texture(lightmap, vLightmapUV).rgb;
+#endif
 
-    // 5) Pequeña componente ambiental
-    vec3 ambient = 0.1 * objectColor;
+    // 3) Reflejo del entorno con Fresnel (Schlick); mate = mip borroso y poco reflejo
+#ifdef ENVIRONMENT_MAP
+    vec3 N = normalize(vNormal);
+    vec3 R = reflect(-viewDir, N);
+    float fresnel = pow(1.0 - max(dot(N, viewDir), 0.0), 5.0);
+    vec3 F = specColor + (max(vec3(1.0 - roughness), specColor) - specColor) * fresnel;
+    vec3 sky = textureLod(environmentMap, R, environmentLod).rgb * environmentExposure;
+    // Mismo tone mapping que el cielo (skybox.frag) para que el reflejo se vea igual
+    finalColor += F * sky / (sky + vec3(1.0));
+#endif
 
-    // 6) Sumar y escribir
-    vec3 finalColor = ambient + diffuse;
-    FragColor = vec4(finalColor, 1.0);
+    // 4) Escribir
+    FragColor = vec4(encodeGamma(finalColor, gamma), 1.0);
 }
\ No newline at end of file
@@ -1,23 +1,56 @@
 #version 330 core
 layout(location = 0) in vec3 aPos;
 layout(location = 1) in vec3 aNormal;
+layout(location = 2) in vec2 aUV;
+layout(location = 3) in vec3 aColor;
+// UVs del lightmap, aparte de las de textura (ver graphics::lightmap)
+layout(location = 12) in vec2 aLightmapUV;
+#ifdef MORPH_TARGETS
+// Deltas de hasta 4 morph targets (ver graphics::morph)
+layout(location = 4) in vec3 aMorphPos0;
+layout(location = 5) in vec3 aMorphPos1;
+layout(location = 6) in vec3 aMorphPos2;
+layout(location = 7) in vec3 aMorphPos3;
+layout(location = 8) in vec3 aMorphNormal0;
+layout(location = 9) in vec3 aMorphNormal1;
+layout(location = 10) in vec3 aMorphNormal2;
+layout(location = 11) in vec3 aMorphNormal3;
+uniform float morphWeights[4];
+#endif
 
 uniform mat4 model;
+// Inversa traspuesta de model, calculada una vez por objeto en la CPU
+uniform mat4 normalMatrix;
 uniform mat4 view;
 uniform mat4 projection;
 
 out vec3 vNormal;
 out vec3 vWorldPos;
+out vec2 vUV;
+out vec3 vColor;
+out vec2 vLightmapUV;
 
 void main()
 {
+    vec3 position = aPos;
+    vec3 normal = aNormal;
+#ifdef MORPH_TARGETS
+    position += morphWeights[0] * aMorphPos0 + morphWeights[1] * aMorphPos1
+              + morphWeights[2] * aMorphPos2 + morphWeights[3] * aMorphPos3;
+    normal += morphWeights[0] * aMorphNormal0 + morphWeights[1] * aMorphNormal1
+            + morphWeights[2] * aMorphNormal2 + morphWeights[3] * aMorphNormal3;
+#endif
+
     // Transformar la posición
-    vec4 worldPos = model * vec4(aPos, 1.0);
+    vec4 worldPos = model * vec4(position, 1.0);
     vWorldPos = worldPos.xyz;
+    vUV = aUV;
+    vColor = aColor;
+    vLightmapUV = aLightmapUV;
 
     // Normal Matrix
-    mat3 normalMat = mat3(transpose(inverse(model)));
-    vNormal = normalize(normalMat * aNormal);
+    mat3 normalMat = mat3(normalMatrix);
+    vNormal = normalize(normalMat * normal);
 
     gl_Position = projection * view * worldPos;
 }
\ No newline at end of file
@@ -0,0 +1,22 @@
+// Corte global de la escena (ver render_settings.rs, Clipping): planos y caja
+// de sección en espacio mundo. Los objetos con ignoreClipping (grilla,
+// anotaciones, ejes de referencia) quedan enteros.
+uniform bool ignoreClipping;
+uniform int clipPlaneCount;
+uniform vec4 clipPlanes[4];  // (normal, d): se conserva dot(normal, p) + d >= 0
+uniform bool sectionBox;
+uniform vec3 sectionBoxMin;
+uniform vec3 sectionBoxMax;
+
+bool isClipped(vec3 worldPos)
+{
+    if (ignoreClipping) {
+        return false;
+    }
+    for (int i = 0; i < clipPlaneCount; i++) {
+        if (dot(clipPlanes[i].xyz, worldPos) + clipPlanes[i].w < 0.0) {
+            return true;
+        }
+    }
+    return sectionBox && (any(lessThan(worldPos, sectionBoxMin)) || any(greaterThan(worldPos, sectionBoxMax)));
+}
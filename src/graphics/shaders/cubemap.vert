@@ -0,0 +1,13 @@
+#version 330 core
+layout(location = 0) in vec3 aPos;
+
+uniform mat4 view;
+uniform mat4 projection;
+
+out vec3 vDir; // dirección de muestreo (posición local del cubo)
+
+void main()
+{
+    vDir = aPos;
+    gl_Position = projection * view * vec4(aPos, 1.0);
+}
@@ -0,0 +1,15 @@
+#version 330 core
+
+in vec3 vDir;
+out vec4 FragColor;
+
+uniform sampler2D equirect; // panorama HDR lat-long
+
+const vec2 invAtan = vec2(0.1591, 0.3183); // 1/(2*pi), 1/pi
+
+void main()
+{
+    vec3 d = normalize(vDir);
+    vec2 uv = vec2(atan(d.z, d.x), asin(d.y)) * invAtan + 0.5;
+    FragColor = vec4(texture(equirect, uv).rgb, 1.0);
+}
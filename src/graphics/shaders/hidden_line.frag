@@ -0,0 +1,13 @@
+#version 330 core
+
+uniform vec3 color;
+uniform float gamma;
+
+out vec4 FragColor;
+
+#include "lighting.glsl"
+
+void main()
+{
+    FragColor = vec4(encodeGamma(color, gamma), 1.0);
+}
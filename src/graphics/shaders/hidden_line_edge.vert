@@ -0,0 +1,32 @@
+#version 330 core
+layout(location = 0) in vec3 aPos;
+layout(location = 1) in vec3 aMid;
+layout(location = 2) in vec3 aNormal0;
+layout(location = 3) in vec3 aNormal1;
+
+uniform mat4 model;
+// Inversa traspuesta de model, calculada en la CPU
+uniform mat4 normalMatrix;
+uniform mat4 view;
+uniform mat4 projection;
+// Coseno del ángulo diedro desde el que una arista es canto vivo
+uniform float creaseCos;
+
+void main()
+{
+    vec3 n0 = normalize(mat3(normalMatrix) * aNormal0);
+    vec3 n1 = normalize(mat3(normalMatrix) * aNormal1);
+    // La vista es una transformación rígida: la cámara está en -R^T * t
+    vec3 eye = -transpose(mat3(view)) * view[3].xyz;
+    // Los dos extremos usan el punto medio, así deciden lo mismo
+    vec3 toEye = eye - (model * vec4(aMid, 1.0)).xyz;
+
+    bool crease = dot(n0, n1) < creaseCos;
+    bool silhouette = (dot(n0, toEye) > 0.0) != (dot(n1, toEye) > 0.0);
+    if (crease || silhouette) {
+        gl_Position = projection * view * model * vec4(aPos, 1.0);
+    } else {
+        // Fuera del volumen de recorte: la arista no se dibuja
+        gl_Position = vec4(0.0, 0.0, 2.0, 1.0);
+    }
+}
@@ -0,0 +1,37 @@
+// Funciones de iluminación compartidas (se incluyen con #include "lighting.glsl")
+
+// Difuso de Lambert de una luz direccional
+vec3 diffuse(vec3 normal, vec3 lightDir, vec3 lightColor, vec3 baseColor)
+{
+    vec3 N = normalize(normal);
+    vec3 L = normalize(lightDir);
+    float diff = max(dot(N, L), 0.0);
+    return diff * lightColor * baseColor;
+}
+
+// Brillo especular (Blinn-Phong) de una luz direccional: más chico e intenso
+// cuanto menor es la rugosidad; con roughness = 1 no brilla
+vec3 specular(vec3 normal, vec3 lightDir, vec3 viewDir, vec3 lightColor, vec3 specColor, float roughness)
+{
+    if (roughness >= 1.0) {
+        return vec3(0.0);
+    }
+    vec3 N = normalize(normal);
+    vec3 L = normalize(lightDir);
+    vec3 H = normalize(L + viewDir);
+    float shininess = exp2(10.0 * (1.0 - roughness) + 1.0);
+    float highlight = pow(max(dot(N, H), 0.0), shininess) * max(dot(N, L), 0.0);
+    return highlight * (1.0 - roughness) * specColor * lightColor;
+}
+
+// Difuso de una luz direccional más una pequeña componente ambiental
+vec3 lambert(vec3 normal, vec3 lightDir, vec3 lightColor, vec3 baseColor)
+{
+    return 0.1 * baseColor + diffuse(normal, lightDir, lightColor, baseColor);
+}
+
+// Corrección gamma del color final (gamma = 1.0 no cambia nada)
+vec3 encodeGamma(vec3 color, float gamma)
+{
+    return pow(color, vec3(1.0 / gamma));
+}
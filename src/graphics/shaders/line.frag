@@ -0,0 +1,14 @@
+#version 330 core
+
+in vec3 vColor;
+
+uniform float gamma;
+
+out vec4 FragColor;
+
+#include "lighting.glsl"
+
+void main()
+{
+    FragColor = vec4(encodeGamma(vColor, gamma), 1.0);
+}
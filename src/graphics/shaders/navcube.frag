@@ -0,0 +1,35 @@
+#version 330 core
+
+in vec3 vLocal;
+
+uniform float border;     // ancho de la franja de aristas/esquinas (igual que en Rust)
+uniform ivec3 hovered;    // región bajo el cursor
+uniform int hasHover;
+
+out vec4 FragColor;
+
+void main()
+{
+    vec3 a = abs(vLocal);
+    vec3 nearEdge = step(1.0 - border, a);
+    ivec3 region = ivec3(nearEdge * sign(vLocal));
+
+    // Color por eje de la cara: X rojo, Y verde, Z azul
+    vec3 color;
+    if (a.x >= a.y && a.x >= a.z) {
+        color = vec3(0.85, 0.45, 0.45);
+    } else if (a.y >= a.z) {
+        color = vec3(0.45, 0.80, 0.45);
+    } else {
+        color = vec3(0.45, 0.55, 0.90);
+    }
+
+    // Aristas y esquinas más oscuras para distinguirlas de las caras
+    if (nearEdge.x + nearEdge.y + nearEdge.z >= 2.0) {
+        color *= 0.7;
+    }
+    if (hasHover == 1 && region == hovered) {
+        color = mix(color, vec3(1.0, 0.9, 0.3), 0.6);
+    }
+    FragColor = vec4(color, 1.0);
+}
@@ -0,0 +1,13 @@
+#version 330 core
+layout(location = 0) in vec3 aPos;
+
+uniform mat4 view;       // solo la rotación de la cámara
+uniform mat4 projection; // ortográfica
+
+out vec3 vLocal;
+
+void main()
+{
+    vLocal = aPos;
+    gl_Position = projection * view * vec4(aPos, 1.0);
+}
@@ -0,0 +1,20 @@
+#version 330 core
+
+in vec3 vWorldPos;
+
+uniform uint objectId; // índice del objeto + 1 (0 = fondo)
+
+layout(location = 0) out uint FragId;
+layout(location = 1) out uint FragPrimitive; // triángulo dentro del objeto
+
+// Lo cortado no se puede seleccionar
+#include "clipping.glsl"
+
+void main()
+{
+    if (isClipped(vWorldPos)) {
+        discard;
+    }
+    FragId = objectId;
+    FragPrimitive = uint(gl_PrimitiveID);
+}
@@ -0,0 +1,74 @@
+#version 330 core
+
+in vec3 vDir;
+out vec4 FragColor;
+
+uniform samplerCube environment;
+uniform float roughness;   // rugosidad del nivel de mip actual
+uniform float resolution;  // lado de la cara del cubemap origen
+
+const float PI = 3.14159265359;
+const uint SAMPLE_COUNT = 512u;
+
+// Secuencia de Hammersley para muestreo cuasi-aleatorio
+float radicalInverse(uint bits)
+{
+    bits = (bits << 16u) | (bits >> 16u);
+    bits = ((bits & 0x55555555u) << 1u) | ((bits & 0xAAAAAAAAu) >> 1u);
+    bits = ((bits & 0x33333333u) << 2u) | ((bits & 0xCCCCCCCCu) >> 2u);
+    bits = ((bits & 0x0F0F0F0Fu) << 4u) | ((bits & 0xF0F0F0F0u) >> 4u);
+    bits = ((bits & 0x00FF00FFu) << 8u) | ((bits & 0xFF00FF00u) >> 8u);
+    return float(bits) * 2.3283064365386963e-10;
+}
+
+// Muestreo por importancia de la GGX alrededor de N
+vec3 importanceSampleGGX(vec2 Xi, vec3 N, float a)
+{
+    float phi = 2.0 * PI * Xi.x;
+    float cosTheta = sqrt((1.0 - Xi.y) / (1.0 + (a * a - 1.0) * Xi.y));
+    float sinTheta = sqrt(1.0 - cosTheta * cosTheta);
+    vec3 H = vec3(cos(phi) * sinTheta, sin(phi) * sinTheta, cosTheta);
+
+    vec3 up = abs(N.z) < 0.999 ? vec3(0.0, 0.0, 1.0) : vec3(1.0, 0.0, 0.0);
+    vec3 tangent = normalize(cross(up, N));
+    vec3 bitangent = cross(N, tangent);
+    return normalize(tangent * H.x + bitangent * H.y + N * H.z);
+}
+
+float distributionGGX(float NdotH, float a)
+{
+    float a2 = a * a;
+    float d = NdotH * NdotH * (a2 - 1.0) + 1.0;
+    return a2 / (PI * d * d);
+}
+
+void main()
+{
+    // Aproximación habitual: N = V = R
+    vec3 N = normalize(vDir);
+    vec3 V = N;
+    float a = roughness * roughness;
+
+    vec3 color = vec3(0.0);
+    float totalWeight = 0.0;
+    for (uint i = 0u; i < SAMPLE_COUNT; ++i)
+    {
+        vec2 Xi = vec2(float(i) / float(SAMPLE_COUNT), radicalInverse(i));
+        vec3 H = importanceSampleGGX(Xi, N, a);
+        vec3 L = normalize(2.0 * dot(V, H) * H - V);
+        float NdotL = max(dot(N, L), 0.0);
+        if (NdotL > 0.0)
+        {
+            // Muestrear un mip más bajo según la densidad de la muestra (evita puntos brillantes)
+            float NdotH = max(dot(N, H), 0.0);
+            float pdf = distributionGGX(NdotH, a) * 0.25 + 0.0001;
+            float saTexel = 4.0 * PI / (6.0 * resolution * resolution);
+            float saSample = 1.0 / (float(SAMPLE_COUNT) * pdf + 0.0001);
+            float mip = roughness == 0.0 ? 0.0 : 0.5 * log2(saSample / saTexel);
+
+            color += textureLod(environment, L, mip).rgb * NdotL;
+            totalWeight += NdotL;
+        }
+    }
+    FragColor = vec4(color / totalWeight, 1.0);
+}
@@ -0,0 +1,15 @@
+#version 330 core
+
+in vec3 vDir;
+out vec4 FragColor;
+
+uniform samplerCube environment;
+uniform float exposure;
+
+void main()
+{
+    vec3 color = texture(environment, vDir).rgb * exposure;
+    // Tone mapping simple (Reinhard) para llevar el HDR a [0,1]
+    color = color / (color + vec3(1.0));
+    FragColor = vec4(color, 1.0);
+}
@@ -0,0 +1,15 @@
+#version 330 core
+layout(location = 0) in vec3 aPos;
+
+uniform mat4 view;       // sin traslación
+uniform mat4 projection;
+
+out vec3 vDir;
+
+void main()
+{
+    vDir = aPos;
+    vec4 pos = projection * view * vec4(aPos, 1.0);
+    // z = w => profundidad 1.0, el cielo queda detrás de todo
+    gl_Position = pos.xyww;
+}
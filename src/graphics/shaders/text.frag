@@ -0,0 +1,18 @@
+#version 330 core
+
+in vec2 vUv;
+in vec4 vColor;
+
+uniform sampler2D atlas;
+uniform float gamma;
+
+out vec4 FragColor;
+
+#include "lighting.glsl"
+
+void main()
+{
+    // El atlas guarda la cobertura de cada glifo en el canal rojo
+    float coverage = texture(atlas, vUv).r;
+    FragColor = vec4(encodeGamma(vColor.rgb, gamma), vColor.a * coverage);
+}
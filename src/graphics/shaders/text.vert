@@ -0,0 +1,18 @@
+#version 330 core
+layout(location = 0) in vec4 aPosUv;
+layout(location = 1) in vec4 aColor;
+
+// Tamaño del framebuffer en píxeles; las posiciones llegan en píxeles desde
+// arriba a la izquierda
+uniform vec2 screenSize;
+
+out vec2 vUv;
+out vec4 vColor;
+
+void main()
+{
+    vUv = aPosUv.zw;
+    vColor = aColor;
+    vec2 ndc = aPosUv.xy / screenSize * 2.0 - 1.0;
+    gl_Position = vec4(ndc.x, -ndc.y, 0.0, 1.0);
+}
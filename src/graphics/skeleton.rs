@@ -0,0 +1,248 @@
+// src/graphics/skeleton.rs
+//
+// Jerarquía de articulaciones de un objeto y su vista de depuración. Cada
+// articulación guarda su transform local respecto del padre dos veces: la de
+// reposo (bind) y la animada (pose). El overlay dibuja un hueso como un
+// octaedro estirado desde el padre hasta el hijo y los ejes locales de cada
+// articulación (X rojo, Y verde, Z azul) en una capa del `LineOverlay`; al
+// hacer click en una articulación se informa su nombre, padre, reposo y pose.
+// Sin esto, depurar un skinning o una cadena de eslabones es a ciegas.
+
+use crate::graphics::lines::Polyline;
+use crate::math::{matrix_4_by_4::Matrix4, vec3::Vec3};
+
+/// Capa del `LineOverlay` con los esqueletos
+pub const SKELETON_LAYER: &str = "esqueleto";
+
+/// Ancho de los octaedros respecto del largo del hueso
+const BONE_WIDTH: f32 = 0.1;
+/// Punto del hueso (desde el padre) donde el octaedro es más ancho
+const BONE_WAIST: f32 = 0.2;
+/// Largo de los ejes respecto del hueso que llega a la articulación
+const AXIS_LENGTH: f32 = 0.25;
+
+const BONE_COLOR: [f32; 3] = [0.85, 0.85, 0.4];
+const SELECTED_COLOR: [f32; 3] = [1.0, 0.5, 0.1];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Joint {
+    pub name: String,
+    /// Siempre anterior a la articulación (los padres se recorren primero)
+    pub parent: Option<usize>,
+    /// Transform local de reposo, respecto del padre
+    pub bind: Matrix4,
+    /// Transform local animada, respecto del padre
+    pub pose: Matrix4,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Skeleton {
+    joints: Vec<Joint>,
+}
+
+impl Skeleton {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Agrega una articulación en su pose de reposo y devuelve su índice
+    pub fn add_joint(&mut self, name: &str, parent: Option<usize>, bind: Matrix4) -> Result<usize, String> {
+        if let Some(parent) = parent.filter(|&p| p >= self.joints.len()) {
+            return Err(format!("La articulación '{}' tiene un padre inexistente ({})", name, parent));
+        }
+        self.joints.push(Joint { name: name.to_string(), parent, bind, pose: bind });
+        Ok(self.joints.len() - 1)
+    }
+
+    pub fn joints(&self) -> &[Joint] {
+        &self.joints
+    }
+
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.joints.iter().position(|joint| joint.name == name)
+    }
+
+    /// Cambia la transform animada de una articulación
+    pub fn set_pose(&mut self, joint: usize, local: Matrix4) -> Result<(), String> {
+        let count = self.joints.len();
+        let joint = self
+            .joints
+            .get_mut(joint)
+            .ok_or_else(|| format!("No hay articulación {} (el esqueleto tiene {})", joint, count))?;
+        joint.pose = local;
+        Ok(())
+    }
+
+    /// Todas las articulaciones vuelven al reposo
+    pub fn reset_pose(&mut self) {
+        for joint in &mut self.joints {
+            joint.pose = joint.bind;
+        }
+    }
+
+    /// Transform de cada articulación en espacio del objeto, en reposo o animada
+    pub fn object_transforms(&self, animated: bool) -> Vec<Matrix4> {
+        let mut transforms: Vec<Matrix4> = Vec::with_capacity(self.joints.len());
+        for joint in &self.joints {
+            let local = if animated { joint.pose } else { joint.bind };
+            let transform = match joint.parent {
+                Some(parent) => transforms[parent].multiply(&local),
+                None => local,
+            };
+            transforms.push(transform);
+        }
+        transforms
+    }
+
+    /// Posición de cada articulación animada en espacio mundo
+    pub fn world_positions(&self, model: &Matrix4) -> Vec<Vec3> {
+        self.object_transforms(true).iter().map(|t| model.transform_point(translation(t))).collect()
+    }
+}
+
+fn translation(transform: &Matrix4) -> Vec3 {
+    Vec3::new(transform.m[12], transform.m[13], transform.m[14])
+}
+
+/// Huesos y ejes de la pose animada en espacio mundo; `selected` se resalta
+pub fn skeleton_lines(skeleton: &Skeleton, model: &Matrix4, selected: Option<usize>) -> Vec<Polyline> {
+    let transforms: Vec<Matrix4> = skeleton.object_transforms(true).iter().map(|t| model.multiply(t)).collect();
+    let positions: Vec<Vec3> = transforms.iter().map(translation).collect();
+    let bone_length = |joint: usize| skeleton.joints[joint].parent.map(|p| (positions[joint] - positions[p]).magnitude());
+    let lengths: Vec<f32> = (0..positions.len()).filter_map(bone_length).filter(|l| *l > 0.0).collect();
+    let average = if lengths.is_empty() { 0.1 } else { lengths.iter().sum::<f32>() / lengths.len() as f32 };
+
+    let mut polylines = Vec::new();
+    for (index, joint) in skeleton.joints.iter().enumerate() {
+        let color = if selected == Some(index) { SELECTED_COLOR } else { BONE_COLOR };
+        if let Some(parent) = joint.parent {
+            polylines.extend(bone_octahedron(positions[parent], positions[index], color));
+        }
+        let length = bone_length(index).filter(|l| *l > 0.0).unwrap_or(average) * AXIS_LENGTH;
+        let m = &transforms[index].m;
+        for (axis, axis_color) in [
+            (Vec3::new(m[0], m[1], m[2]), [1.0, 0.1, 0.1]),
+            (Vec3::new(m[4], m[5], m[6]), [0.1, 1.0, 0.1]),
+            (Vec3::new(m[8], m[9], m[10]), [0.2, 0.4, 1.0]),
+        ] {
+            let Some(axis) = axis.try_normalize() else { continue };
+            polylines.push(Polyline {
+                points: vec![positions[index], positions[index] + axis * length],
+                color: axis_color,
+                closed: false,
+            });
+        }
+    }
+    polylines
+}
+
+/// Octaedro alargado de `from` a `to`: un anillo de 4 puntos cerca del padre
+/// unido a los dos extremos, así se ve hacia dónde apunta el hueso
+fn bone_octahedron(from: Vec3, to: Vec3, color: [f32; 3]) -> Vec<Polyline> {
+    let bone = to - from;
+    let length = bone.magnitude();
+    let Some(direction) = bone.try_normalize() else {
+        return Vec::new();
+    };
+    let helper = if direction.y.abs() < 0.9 { Vec3::UNIT_Y } else { Vec3::UNIT_X };
+    let side = direction.cross(&helper).normalize() * (length * BONE_WIDTH);
+    let up = direction.cross(&side);
+    let waist = from + direction * (length * BONE_WAIST);
+    let ring = [waist + side, waist + up, waist - side, waist - up];
+    let mut polylines = vec![Polyline { points: ring.to_vec(), color, closed: true }];
+    for corner in ring {
+        polylines.push(Polyline { points: vec![from, corner, to], color, closed: false });
+    }
+    polylines
+}
+
+/// Lo que se informa de una articulación al hacer click
+#[derive(Debug, Clone, PartialEq)]
+pub struct JointReport {
+    pub object: usize,
+    pub joint: usize,
+    pub name: String,
+    pub parent: Option<String>,
+    /// Posición animada en espacio mundo
+    pub position: Vec3,
+    pub bind: Matrix4,
+    pub pose: Matrix4,
+}
+
+impl JointReport {
+    pub fn new(skeleton: &Skeleton, object: usize, joint: usize, model: &Matrix4) -> Option<Self> {
+        let data = skeleton.joints.get(joint)?;
+        Some(Self {
+            object,
+            joint,
+            name: data.name.clone(),
+            parent: data.parent.map(|p| skeleton.joints[p].name.clone()),
+            position: *skeleton.world_positions(model).get(joint)?,
+            bind: data.bind,
+            pose: data.pose,
+        })
+    }
+
+    /// Texto para el log y el panel en pantalla
+    pub fn lines(&self) -> Vec<String> {
+        let describe = |label: &str, local: &Matrix4| {
+            let (t, rotation, _) = local.decompose();
+            let euler = rotation.to_euler();
+            format!(
+                "{} ({:.4}, {:.4}, {:.4}), giro ({:.1}°, {:.1}°, {:.1}°)",
+                label,
+                t.x,
+                t.y,
+                t.z,
+                euler.x.to_degrees(),
+                euler.y.to_degrees(),
+                euler.z.to_degrees()
+            )
+        };
+        let p = self.position;
+        vec![
+            format!("Objeto {}, articulación {} '{}'", self.object, self.joint, self.name),
+            format!("Padre: {}", self.parent.as_deref().unwrap_or("ninguno (raíz)")),
+            format!("Posición ({:.4}, {:.4}, {:.4})", p.x, p.y, p.z),
+            describe("Reposo", &self.bind),
+            describe("Pose", &self.pose),
+        ]
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skeleton_pose_and_lines() {
+        let mut skeleton = Skeleton::new();
+        let root = skeleton.add_joint("cadera", None, Matrix4::identity()).unwrap();
+        let knee = skeleton.add_joint("rodilla", Some(root), Matrix4::translate(0.0, -1.0, 0.0)).unwrap();
+        skeleton.add_joint("tobillo", Some(knee), Matrix4::translate(0.0, -1.0, 0.0)).unwrap();
+        assert!(skeleton.add_joint("suelto", Some(7), Matrix4::identity()).is_err());
+        assert_eq!(skeleton.find("tobillo"), Some(2));
+
+        // Girar la rodilla 90° mueve el tobillo pero no la rodilla
+        skeleton
+            .set_pose(knee, Matrix4::translate(0.0, -1.0, 0.0).multiply(&Matrix4::rotate_x(std::f32::consts::FRAC_PI_2)))
+            .unwrap();
+        let positions = skeleton.world_positions(&Matrix4::identity());
+        assert_eq!(positions[1], Vec3::new(0.0, -1.0, 0.0));
+        assert!(((positions[2] - positions[1]).magnitude() - 1.0).abs() < 1e-5);
+        assert!((positions[2].y + 1.0).abs() < 1e-5 && positions[2].z.abs() > 0.99);
+        assert!((translation(&skeleton.object_transforms(false)[2]) - Vec3::new(0.0, -2.0, 0.0)).magnitude() < 1e-5);
+
+        // Dos huesos de 5 líneas más 3 ejes por articulación; el seleccionado resaltado
+        let lines = skeleton_lines(&skeleton, &Matrix4::identity(), Some(2));
+        assert_eq!(lines.len(), 2 * 5 + 3 * 3);
+        assert!(lines.iter().any(|l| l.color == SELECTED_COLOR));
+
+        let report = JointReport::new(&skeleton, 0, 2, &Matrix4::identity()).unwrap();
+        assert_eq!(report.parent.as_deref(), Some("rodilla"));
+        assert_eq!(report.lines()[0], "Objeto 0, articulación 2 'tobillo'");
+        skeleton.reset_pose();
+        assert_eq!(skeleton.joints()[1].pose, skeleton.joints()[1].bind);
+    }
+}
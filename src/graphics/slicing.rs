@@ -0,0 +1,220 @@
+// src/graphics/slicing.rs
+//
+// Corte de mallas con planos paralelos: cada plano deja contornos (líneas
+// quebradas, cerradas si la malla es cerrada) que se pueden ver sobre la
+// escena o exportar como SVG / DXF para revisar capas antes de imprimir.
+// Los segmentos se encadenan por las aristas de la malla que cortan, así que
+// la malla tiene que estar soldada (los STL cargados lo están).
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+
+use crate::graphics::mesh::Mesh;
+use crate::graphics::mesh_ops::transformed;
+use crate::graphics::scene_object::SceneObject;
+use crate::math::{plane::Plane, vec3::Vec3};
+
+/// Contorno de un corte
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contour {
+    pub points: Vec<Vec3>,
+    pub closed: bool,
+}
+
+/// Arista de la malla (índices ordenados)
+type EdgeKey = (u32, u32);
+
+/// Contornos del corte de `mesh` con el plano `normal · p = offset`
+pub fn slice_mesh(mesh: &Mesh, normal: Vec3, offset: f32) -> Vec<Contour> {
+    let distances: Vec<f32> = (0..mesh.positions.len() as u32).map(|v| mesh.position(v).dot(&normal) - offset).collect();
+
+    // 1) Un segmento por triángulo cortado, identificado por las dos aristas que cruza
+    let mut points: HashMap<EdgeKey, Vec3> = HashMap::new();
+    let mut segments: Vec<[EdgeKey; 2]> = Vec::new();
+    for triangle in 0..mesh.triangle_count() {
+        let Some([a, b, c]) = mesh.triangle(triangle) else { continue };
+        let mut crossing = [(0, 0); 2];
+        let mut count = 0;
+        for (u, v) in [(a, b), (b, c), (c, a)] {
+            let (du, dv) = (distances[u as usize], distances[v as usize]);
+            // Los vértices sobre el plano cuentan como de arriba
+            if (du >= 0.0) == (dv >= 0.0) {
+                continue;
+            }
+            let key = (u.min(v), u.max(v));
+            points.entry(key).or_insert_with(|| {
+                let (pu, pv) = (mesh.position(u), mesh.position(v));
+                pu + (pv - pu) * (du / (du - dv))
+            });
+            if count < 2 {
+                crossing[count] = key;
+            }
+            count += 1;
+        }
+        if count == 2 {
+            segments.push(crossing);
+        }
+    }
+
+    // 2) Encadenar los segmentos que comparten arista
+    let mut by_edge: HashMap<EdgeKey, Vec<usize>> = HashMap::new();
+    for (index, segment) in segments.iter().enumerate() {
+        for key in segment {
+            by_edge.entry(*key).or_default().push(index);
+        }
+    }
+    let mut used = vec![false; segments.len()];
+    let next_from = |key: EdgeKey, used: &mut Vec<bool>| -> Option<EdgeKey> {
+        let index = *by_edge.get(&key)?.iter().find(|&&i| !used[i])?;
+        used[index] = true;
+        let [first, second] = segments[index];
+        Some(if first == key { second } else { first })
+    };
+
+    let mut contours = Vec::new();
+    for start in 0..segments.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let [head, tail] = segments[start];
+        let mut chain = vec![head, tail];
+        while let Some(key) = next_from(*chain.last().unwrap(), &mut used) {
+            chain.push(key);
+        }
+        let closed = chain.len() > 3 && chain.first() == chain.last();
+        if closed {
+            chain.pop();
+        } else {
+            // Abierto: seguir también hacia atrás desde el inicio
+            let mut backwards = Vec::new();
+            while let Some(key) = next_from(*backwards.last().unwrap_or(&head), &mut used) {
+                backwards.push(key);
+            }
+            backwards.reverse();
+            backwards.extend(chain);
+            chain = backwards;
+        }
+        contours.push(Contour { points: chain.iter().map(|key| points[key]).collect(), closed });
+    }
+    contours
+}
+
+/// Contornos en espacio mundo de todos los objetos
+pub fn slice_objects(objects: &[SceneObject], global_scale: f32, normal: Vec3, offset: f32) -> Vec<Contour> {
+    objects
+        .iter()
+        .flat_map(|obj| slice_mesh(&transformed(&obj.mesh, &obj.model_matrix(global_scale)), normal, offset))
+        .collect()
+}
+
+/// Cortes cada `layer_height` a lo largo de `normal`, empezando medio paso
+/// por encima del punto más bajo: (offset del plano, contornos)
+pub fn slice_layers(mesh: &Mesh, normal: Vec3, layer_height: f32) -> Vec<(f32, Vec<Contour>)> {
+    let heights = (0..mesh.positions.len() as u32).map(|v| mesh.position(v).dot(&normal));
+    let (low, high) = heights.fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), h| (lo.min(h), hi.max(h)));
+    if low > high || layer_height <= 0.0 {
+        return Vec::new();
+    }
+    let count = ((high - low) / layer_height).floor() as usize;
+    (0..count)
+        .map(|layer| {
+            let offset = low + (layer as f32 + 0.5) * layer_height;
+            (offset, slice_mesh(mesh, normal, offset))
+        })
+        .collect()
+}
+
+/// Contornos en coordenadas 2D del plano
+fn project(contours: &[Contour], normal: Vec3) -> Vec<(Vec<[f32; 2]>, bool)> {
+    let (u, v) = Plane::from_point_normal(Vec3::ZERO, normal).basis();
+    contours
+        .iter()
+        .map(|c| (c.points.iter().map(|p| [p.dot(&u), p.dot(&v)]).collect(), c.closed))
+        .collect()
+}
+
+/// Guarda un corte como SVG (unidades de la malla, y hacia arriba)
+pub fn save_svg(contours: &[Contour], normal: Vec3, path: &str) -> Result<(), String> {
+    let flat = project(contours, normal);
+    let (mut min, mut max) = ([f32::INFINITY; 2], [f32::NEG_INFINITY; 2]);
+    for [x, y] in flat.iter().flat_map(|(points, _)| points) {
+        min = [min[0].min(*x), min[1].min(*y)];
+        max = [max[0].max(*x), max[1].max(*y)];
+    }
+    if min[0] > max[0] {
+        (min, max) = ([0.0; 2], [1.0; 2]);
+    }
+    let (width, height) = ((max[0] - min[0]).max(1e-6), (max[1] - min[1]).max(1e-6));
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+        min[0], -max[1], width, height
+    );
+    let stroke = width.max(height) * 0.002;
+    for (points, closed) in &flat {
+        let coords: Vec<String> = points.iter().map(|[x, y]| format!("{},{}", x, -y)).collect();
+        let element = if *closed { "polygon" } else { "polyline" };
+        let _ = writeln!(
+            svg,
+            r#"  <{} points="{}" fill="none" stroke="black" stroke-width="{}"/>"#,
+            element, coords.join(" "), stroke
+        );
+    }
+    svg.push_str("</svg>\n");
+    fs::write(path, svg).map_err(|e| format!("No se pudo escribir {}: {}", path, e))
+}
+
+/// Guarda un corte como DXF R12 (una POLYLINE por contorno, capa 0)
+pub fn save_dxf(contours: &[Contour], normal: Vec3, path: &str) -> Result<(), String> {
+    let mut dxf = String::from("0\nSECTION\n2\nENTITIES\n");
+    for (points, closed) in project(contours, normal) {
+        let _ = write!(dxf, "0\nPOLYLINE\n8\n0\n66\n1\n70\n{}\n", closed as i32);
+        for [x, y] in points {
+            let _ = write!(dxf, "0\nVERTEX\n8\n0\n10\n{}\n20\n{}\n30\n0.0\n", x, y);
+        }
+        dxf.push_str("0\nSEQEND\n");
+    }
+    dxf.push_str("0\nENDSEC\n0\nEOF\n");
+    fs::write(path, dxf).map_err(|e| format!("No se pudo escribir {}: {}", path, e))
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tetraedro cerrado con la base en y = 0 y el vértice en y = 1
+    fn tetrahedron() -> Mesh {
+        Mesh::new(
+            vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.2, 1.0, 0.2]],
+            vec![0, 1, 2, 0, 3, 1, 1, 3, 2, 2, 3, 0],
+        )
+    }
+
+    #[test]
+    fn test_slice_closed_mesh() {
+        let contours = slice_mesh(&tetrahedron(), Vec3::UNIT_Y, 0.5);
+        assert_eq!(contours.len(), 1);
+        assert!(contours[0].closed);
+        assert_eq!(contours[0].points.len(), 3);
+        assert!(contours[0].points.iter().all(|p| (p.y - 0.5).abs() < 1e-6));
+
+        assert!(slice_mesh(&tetrahedron(), Vec3::UNIT_Y, 2.0).is_empty());
+        assert_eq!(slice_layers(&tetrahedron(), Vec3::UNIT_Y, 0.25).len(), 4);
+    }
+
+    #[test]
+    fn test_slice_open_mesh() {
+        // Sin la base ni una cara lateral: el corte queda abierto pero en una sola pieza
+        let mut mesh = tetrahedron();
+        mesh.indices = vec![0, 3, 1, 1, 3, 2];
+        let contours = slice_mesh(&mesh, Vec3::UNIT_Y, 0.5);
+        assert_eq!(contours.len(), 1);
+        assert!(!contours[0].closed);
+        assert_eq!(contours[0].points.len(), 3);
+    }
+}
@@ -0,0 +1,226 @@
+// src/graphics/spatial.rs
+//
+// BVH de la escena sobre las cajas en espacio mundo de los objetos. Sirve para
+// descartar objetos enteros en el culling por frustum, los rayos y la fase
+// amplia de colisiones sin recorrer toda la lista. Cuando los objetos se
+// mueven solo se reajustan las cajas (refit); el árbol se reconstruye si
+// cambia la cantidad de objetos.
+
+use crate::math::{aabb::{axis, Aabb}, frustum::Frustum, ray::Ray};
+
+/// Objetos por hoja como máximo
+const LEAF_SIZE: usize = 2;
+
+#[derive(Debug, Clone)]
+struct SceneNode {
+    bounds: Aabb,
+    /// Hoja: rango `first..first + count` en `SceneBvh::objects`.
+    /// Interno (count == 0): el hijo izquierdo es el nodo siguiente y el derecho `first`.
+    first: u32,
+    count: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SceneBvh {
+    nodes: Vec<SceneNode>,
+    /// Índices de objetos, reordenados por hoja
+    objects: Vec<u32>,
+    /// Caja en espacio mundo de cada objeto (por índice de objeto)
+    bounds: Vec<Aabb>,
+}
+
+impl SceneBvh {
+    /// `bounds[i]` es la caja en espacio mundo del objeto `i`
+    pub fn build(bounds: &[Aabb]) -> Self {
+        let mut bvh = SceneBvh {
+            nodes: Vec::with_capacity(bounds.len() * 2),
+            objects: (0..bounds.len() as u32).collect(),
+            bounds: bounds.to_vec(),
+        };
+        if !bounds.is_empty() {
+            bvh.build_node(bounds, 0, bounds.len());
+            bvh.refit_nodes();
+        }
+        bvh
+    }
+
+    fn build_node(&mut self, bounds: &[Aabb], start: usize, end: usize) -> usize {
+        let mut centroids = Aabb::EMPTY;
+        for &obj in &self.objects[start..end] {
+            if !bounds[obj as usize].is_empty() {
+                centroids.grow(bounds[obj as usize].center());
+            }
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(SceneNode { bounds: Aabb::EMPTY, first: start as u32, count: (end - start) as u32 });
+        if end - start > LEAF_SIZE {
+            // Mediana en el eje más largo de los centroides
+            let split_axis = centroids.largest_axis();
+            let mid = (start + end) / 2;
+            self.objects[start..end].select_nth_unstable_by(mid - start, |a, b| {
+                let ca = axis(bounds[*a as usize].center(), split_axis);
+                let cb = axis(bounds[*b as usize].center(), split_axis);
+                ca.total_cmp(&cb)
+            });
+            self.build_node(bounds, start, mid);
+            let right = self.build_node(bounds, mid, end);
+            self.nodes[index].first = right as u32;
+            self.nodes[index].count = 0;
+        }
+        index
+    }
+
+    /// Cantidad de objetos indexados
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Actualiza las cajas después de mover objetos. Si cambió la cantidad de
+    /// objetos reconstruye el árbol.
+    pub fn refit(&mut self, bounds: &[Aabb]) {
+        if bounds.len() != self.objects.len() {
+            *self = Self::build(bounds);
+        } else {
+            self.bounds.copy_from_slice(bounds);
+            self.refit_nodes();
+        }
+    }
+
+    fn refit_nodes(&mut self) {
+        // Los hijos siempre tienen índice mayor que el padre
+        for index in (0..self.nodes.len()).rev() {
+            let node = &self.nodes[index];
+            let (first, count) = (node.first as usize, node.count as usize);
+            let node_bounds = if count > 0 {
+                self.objects[first..first + count]
+                    .iter()
+                    .fold(Aabb::EMPTY, |acc, &obj| acc.union(&self.bounds[obj as usize]))
+            } else {
+                self.nodes[index + 1].bounds.union(&self.nodes[first].bounds)
+            };
+            self.nodes[index].bounds = node_bounds;
+        }
+    }
+
+    /// Recorre el árbol entrando solo en los nodos que cumplen `visit` y
+    /// devuelve los objetos de las hojas alcanzadas
+    fn collect(&self, mut visit: impl FnMut(&Aabb) -> bool) -> Vec<usize> {
+        let mut found = Vec::new();
+        let mut stack = vec![0usize];
+        if self.nodes.is_empty() {
+            return found;
+        }
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !visit(&node.bounds) {
+                continue;
+            }
+            if node.count == 0 {
+                stack.push(node.first as usize);
+                stack.push(index + 1);
+            } else {
+                let first = node.first as usize;
+                found.extend(self.objects[first..first + node.count as usize].iter().map(|&obj| obj as usize));
+            }
+        }
+        found
+    }
+
+    /// Objetos cuya caja corta el rayo con t <= `max_t`: (t de entrada, objeto),
+    /// ordenados por t para poder cortar la búsqueda en el primer impacto
+    pub fn ray_candidates(&self, ray: &Ray, max_t: f32) -> Vec<(f32, usize)> {
+        let mut candidates: Vec<(f32, usize)> = self
+            .collect(|b| ray.intersect_aabb(b).is_some_and(|t| t <= max_t))
+            .into_iter()
+            .filter_map(|obj| Some((ray.intersect_aabb(&self.bounds[obj]).filter(|&t| t <= max_t)?, obj)))
+            .collect();
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+        candidates
+    }
+
+    /// Objetos que pueden verse desde el frustum
+    pub fn frustum_objects(&self, frustum: &Frustum) -> Vec<usize> {
+        self.collect(|b| frustum.intersects_aabb(b))
+            .into_iter()
+            .filter(|&obj| frustum.intersects_aabb(&self.bounds[obj]))
+            .collect()
+    }
+
+    /// Objetos cuya caja se superpone con `aabb`
+    pub fn overlapping(&self, aabb: &Aabb) -> Vec<usize> {
+        self.collect(|b| overlaps(b, aabb))
+            .into_iter()
+            .filter(|&obj| overlaps(&self.bounds[obj], aabb))
+            .collect()
+    }
+
+    /// Fase amplia de colisiones: pares (i < j) de objetos con cajas superpuestas
+    pub fn overlapping_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs: Vec<(usize, usize)> = (0..self.bounds.len())
+            .flat_map(|i| {
+                self.overlapping(&self.bounds[i])
+                    .into_iter()
+                    .filter(move |&j| j > i)
+                    .map(move |j| (i, j))
+            })
+            .collect();
+        pairs.sort_unstable();
+        pairs
+    }
+}
+
+fn overlaps(a: &Aabb, b: &Aabb) -> bool {
+    !a.is_empty()
+        && !b.is_empty()
+        && a.min.x <= b.max.x && b.min.x <= a.max.x
+        && a.min.y <= b.max.y && b.min.y <= a.max.y
+        && a.min.z <= b.max.z && b.min.z <= a.max.z
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::vec3::Vec3;
+
+    /// Cubos unitarios en fila sobre X, separados por `gap`
+    fn row(count: usize, gap: f32) -> Vec<Aabb> {
+        (0..count)
+            .map(|i| {
+                let x = i as f32 * (1.0 + gap);
+                Aabb::new(Vec3::new(x, 0.0, 0.0), Vec3::new(x + 1.0, 1.0, 1.0))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_queries_and_refit() {
+        let mut bounds = row(100, 1.0);
+        let mut bvh = SceneBvh::build(&bounds);
+        assert_eq!(bvh.len(), 100);
+
+        // Rayo a lo largo de la fila: todos los cubos, del más cercano al más lejano
+        let ray = Ray::new(Vec3::new(-1.0, 0.5, 0.5), Vec3::UNIT_X);
+        let candidates = bvh.ray_candidates(&ray, f32::INFINITY);
+        assert_eq!(candidates.len(), 100);
+        assert_eq!(candidates[0].1, 0);
+        assert_eq!(bvh.ray_candidates(&ray, 5.0).len(), 3);
+
+        let probe = Aabb::new(Vec3::new(9.5, 0.0, 0.0), Vec3::new(12.5, 1.0, 1.0));
+        let mut near = bvh.overlapping(&probe);
+        near.sort_unstable();
+        assert_eq!(near, vec![5, 6]);
+        assert!(bvh.overlapping_pairs().is_empty());
+
+        // Mover el último cubo encima del primero y reajustar
+        bounds[99] = bounds[0];
+        bvh.refit(&bounds);
+        assert_eq!(bvh.overlapping_pairs(), vec![(0, 99)]);
+        assert_eq!(bvh.ray_candidates(&ray, 1.5).len(), 2);
+    }
+}
@@ -0,0 +1,54 @@
+// src/graphics/stereo.rs
+
+use crate::graphics::camara::Camera;
+use crate::math::{matrix_4_by_4::Matrix4, quaternion::Quat, vec3::Vec3};
+
+/// Campo de visión asimétrico de un ojo (ángulos en radianes, left/down negativos)
+#[derive(Debug, Clone, Copy)]
+pub struct EyeFov {
+    pub angle_left: f32,
+    pub angle_right: f32,
+    pub angle_up: f32,
+    pub angle_down: f32,
+}
+
+/// Pose y fov de un ojo relativos al origen del "stage" (espacio de tracking)
+#[derive(Debug, Clone, Copy)]
+pub struct EyeView {
+    pub position: Vec3,
+    pub orientation: Quat,
+    pub fov: EyeFov,
+}
+
+/// Transformación del origen del stage en el mundo.
+/// El stage se ancla en la posición de la cámara y gira con su yaw,
+/// así el usuario puede "caminar" por la escena con el mando.
+pub fn stage_origin(camera: &Camera) -> (Vec3, Quat) {
+    (camera.position, Quat::from_axis_angle(Vec3::UNIT_Y, camera.yaw))
+}
+
+/// Pasa una pose local del stage a coordenadas de mundo
+pub fn stage_to_world(camera: &Camera, position: Vec3, orientation: Quat) -> (Vec3, Quat) {
+    let (origin, rotation) = stage_origin(camera);
+    (origin + rotation.rotate(position), rotation * orientation)
+}
+
+impl EyeView {
+    /// Matriz de vista del ojo: inversa de su pose en el mundo
+    pub fn view_matrix(&self, camera: &Camera) -> Matrix4 {
+        let (position, orientation) = stage_to_world(camera, self.position, self.orientation);
+        let rotation = orientation.conjugate().to_matrix();
+        rotation.multiply(&Matrix4::translate(-position.x, -position.y, -position.z))
+    }
+
+    pub fn projection_matrix(&self, near: f32, far: f32) -> Matrix4 {
+        Matrix4::frustum(
+            self.fov.angle_left.tan() * near,
+            self.fov.angle_right.tan() * near,
+            self.fov.angle_down.tan() * near,
+            self.fov.angle_up.tan() * near,
+            near,
+            far,
+        )
+    }
+}
@@ -0,0 +1,261 @@
+// src/graphics/still_render.rs
+//
+// Render de alta calidad en CPU: trazado de caminos desde la cámara actual,
+// refinado progresivamente en un hilo aparte (una muestra por píxel en cada
+// pasada). Mientras corre se puede consultar el avance, guardar la imagen
+// intermedia o cancelarlo. La escena se copia al empezar, así que mover la
+// cámara o los objetos después no afecta la imagen.
+// Con `transparent_background` los rayos que no tocan nada cuentan como alfa
+// 0 en vez de cielo; el promedio de las muestras deja los bordes con alfa
+// parcial y el color premultiplicado (ver `graphics::capture`).
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::graphics::camara::Camera;
+use crate::graphics::capture::{save_image, save_image_rgba};
+use crate::graphics::lighting::Lighting;
+use crate::graphics::path_tracer::{Rng, TraceScene};
+use crate::graphics::render::FOV_Y_DEGREES;
+use crate::graphics::scene_object::SceneObject;
+use crate::math::{ray::Ray, vec3::Vec3};
+
+#[derive(Debug, Clone, Copy)]
+pub struct StillRenderSettings {
+    pub width: u32,
+    pub height: u32,
+    /// Muestras por píxel (= pasadas) al terminar
+    pub samples: u32,
+    /// Rebotes difusos después del primer corte
+    pub bounces: u32,
+    /// Campo de visión vertical en radianes (el mismo del render en tiempo real)
+    pub fov_y: f32,
+    pub seed: u64,
+    /// Fondo en alfa 0 en vez del cielo
+    pub transparent_background: bool,
+}
+
+impl Default for StillRenderSettings {
+    fn default() -> Self {
+        Self {
+            width: 1920,
+            height: 1080,
+            samples: 256,
+            bounces: 3,
+            fov_y: FOV_Y_DEGREES.to_radians(),
+            seed: 1,
+            transparent_background: false,
+        }
+    }
+}
+
+/// Suma de las pasadas terminadas (RGB premultiplicado y alfa)
+struct Accumulation {
+    sum: Vec<[f32; 4]>,
+    passes: u32,
+}
+
+/// Base de la cámara con la que se generan los rayos primarios
+#[derive(Debug, Clone, Copy)]
+struct PinholeCamera {
+    position: Vec3,
+    forward: Vec3,
+    right: Vec3,
+    up: Vec3,
+    tan_half_fov: f32,
+    aspect: f32,
+}
+
+impl PinholeCamera {
+    fn new(camera: &Camera, fov_y: f32, aspect: f32) -> Self {
+        let forward = camera.get_forward_vector();
+        let right = forward.cross(&Vec3::UNIT_Y).normalize();
+        let up = right.cross(&forward);
+        Self {
+            position: camera.position,
+            forward,
+            right,
+            up,
+            tan_half_fov: (fov_y * 0.5).tan(),
+            aspect,
+        }
+    }
+
+    /// Rayo por el punto (u, v) de la imagen, ambos en [0, 1] con v = 0 arriba
+    fn ray(&self, u: f32, v: f32) -> Ray {
+        let x = (u * 2.0 - 1.0) * self.tan_half_fov * self.aspect;
+        let y = (1.0 - v * 2.0) * self.tan_half_fov;
+        let direction = self.forward + self.right * x + self.up * y;
+        Ray::new(self.position, direction.normalize())
+    }
+}
+
+/// Render en curso (o terminado). Al soltarlo se cancela el hilo.
+pub struct StillRender {
+    width: u32,
+    height: u32,
+    samples: u32,
+    transparent_background: bool,
+    accumulation: Arc<Mutex<Accumulation>>,
+    cancel: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl StillRender {
+    /// Copia la escena y arranca el render desde la vista de `camera`
+    pub fn start(objects: &[SceneObject], global_scale: f32, camera: &Camera, settings: StillRenderSettings) -> Self {
+        let width = settings.width.max(1);
+        let height = settings.height.max(1);
+        let scene = TraceScene::from_objects(objects, global_scale, Lighting::default());
+        let pinhole = PinholeCamera::new(camera, settings.fov_y, width as f32 / height as f32);
+
+        let accumulation = Arc::new(Mutex::new(Accumulation {
+            sum: vec![[0.0; 4]; (width * height) as usize],
+            passes: 0,
+        }));
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let worker = {
+            let accumulation = accumulation.clone();
+            let cancel = cancel.clone();
+            thread::spawn(move || {
+                for pass in 0..settings.samples {
+                    let mut rng = Rng::new(settings.seed.wrapping_add(pass as u64));
+                    let mut pixels = Vec::with_capacity((width * height) as usize);
+                    for y in 0..height {
+                        if cancel.load(Ordering::Relaxed) {
+                            return;
+                        }
+                        for x in 0..width {
+                            // Punto al azar dentro del píxel (antialiasing)
+                            let u = (x as f32 + rng.next_f32()) / width as f32;
+                            let v = (y as f32 + rng.next_f32()) / height as f32;
+                            let ray = pinhole.ray(u, v);
+                            if settings.transparent_background && scene.intersect(&ray, f32::INFINITY).is_none() {
+                                pixels.push([0.0; 4]);
+                                continue;
+                            }
+                            let [r, g, b] = scene.radiance(&ray, settings.bounces, &mut rng);
+                            // Una muestra inválida no debe arruinar el píxel entero
+                            let valid = [r, g, b].iter().all(|c| c.is_finite());
+                            pixels.push(if valid { [r, g, b, 1.0] } else { [0.0, 0.0, 0.0, 1.0] });
+                        }
+                    }
+
+                    let mut acc = accumulation.lock().unwrap();
+                    for (sum, pixel) in acc.sum.iter_mut().zip(&pixels) {
+                        for (s, p) in sum.iter_mut().zip(pixel) {
+                            *s += p;
+                        }
+                    }
+                    acc.passes += 1;
+                }
+            })
+        };
+
+        Self {
+            width,
+            height,
+            samples: settings.samples,
+            transparent_background: settings.transparent_background,
+            accumulation,
+            cancel,
+            worker: Some(worker),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Pasadas terminadas y totales
+    pub fn progress(&self) -> (u32, u32) {
+        (self.accumulation.lock().unwrap().passes, self.samples)
+    }
+
+    /// Terminó todas las pasadas (o se canceló)
+    pub fn is_finished(&self) -> bool {
+        self.worker.as_ref().is_none_or(|worker| worker.is_finished())
+    }
+
+    /// Detiene el hilo; la imagen queda con las pasadas ya terminadas
+    pub fn cancel(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+
+    /// Imagen actual (promedio de las pasadas terminadas), en valores lineales
+    pub fn image(&self) -> Vec<[f32; 3]> {
+        self.image_rgba().into_iter().map(|[r, g, b, _]| [r, g, b]).collect()
+    }
+
+    /// Como `image` pero con el alfa (premultiplicado)
+    pub fn image_rgba(&self) -> Vec<[f32; 4]> {
+        let acc = self.accumulation.lock().unwrap();
+        let weight = 1.0 / acc.passes.max(1) as f32;
+        acc.sum.iter().map(|pixel| pixel.map(|c| c * weight)).collect()
+    }
+
+    /// Guarda la imagen actual: `.exr` / `.hdr` lineal o cualquier formato de
+    /// 8 bits (PNG, JPEG). Con fondo transparente el EXR y el PNG llevan alfa.
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        if self.transparent_background {
+            return save_image_rgba(path, self.width, self.height, &self.image_rgba());
+        }
+        save_image(path, self.width, self.height, &self.image())
+    }
+}
+
+impl Drop for StillRender {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::mesh::Mesh;
+
+    #[test]
+    fn test_render_refines_and_finishes() {
+        // Piso bajo la cámara: la mitad de abajo de la imagen ve el piso, la de arriba el cielo
+        let mut floor = SceneObject::new(0, 0);
+        floor.mesh = Mesh::new(
+            vec![[-100.0, 0.0, -100.0], [100.0, 0.0, -100.0], [100.0, 0.0, 100.0], [-100.0, 0.0, 100.0]],
+            vec![0, 2, 1, 0, 3, 2],
+        );
+        let camera = Camera::new(Vec3::new(0.0, 1.0, 0.0));
+        let settings = StillRenderSettings { width: 8, height: 8, samples: 2, bounces: 1, ..Default::default() };
+        let mut render = StillRender::start(std::slice::from_ref(&floor), 1.0, &camera, settings);
+        while !render.is_finished() {
+            thread::yield_now();
+        }
+        render.cancel();
+
+        assert_eq!(render.progress(), (2, 2));
+        let image = render.image();
+        assert_eq!(image[0], Lighting::default().sky_color);
+        assert_ne!(image[7 * 8], Lighting::default().sky_color);
+        assert!(render.image_rgba().iter().all(|pixel| pixel[3] == 1.0));
+
+        // Con fondo transparente el cielo queda en alfa 0 y el piso en 1
+        let settings = StillRenderSettings { transparent_background: true, ..settings };
+        let mut render = StillRender::start(&[floor], 1.0, &camera, settings);
+        while !render.is_finished() {
+            thread::yield_now();
+        }
+        render.cancel();
+        let image = render.image_rgba();
+        assert_eq!(image[0], [0.0; 4]);
+        assert_eq!(image[7 * 8][3], 1.0);
+    }
+}
@@ -0,0 +1,492 @@
+// src/graphics/stl.rs
+//
+// Lectura de STL ASCII y binario. Los archivos de proveedores traen de todo:
+// nombres de sólido con acentos en Latin-1, finales de línea CRLF, binarios
+// cuyo encabezado de 80 bytes empieza con "solid" y archivos sin triángulos.
+//
+// Un binario se reconoce por el tamaño: 84 bytes de encabezado y cantidad más
+// 50 por triángulo. Si el tamaño cierra es binario aunque el encabezado diga
+// "solid"; si no cierra, es ASCII solo si además de empezar con "solid" tiene
+// `facet` o `endsolid` en el primer kilobyte. El texto se lee byte a byte sin
+// exigir UTF-8 (solo las palabras clave y los números son ASCII). Lo que se
+// puede salvar (bytes de más, falta `endsolid`, triángulos con NaN) se informa
+// en `StlFile::warnings`; lo que no, con un `StlError` que dice dónde falló.
+//
+// Los escaneos llegan a varios GB: `read_file` no carga el binario entero sino
+// que lo recorre de a `STREAM_CHUNK` triángulos con un único buffer y entrega
+// cada triángulo a quien arma la malla, así en memoria solo queda la malla
+// final (ver `SceneObject::load_stl`). El ASCII se lee entero (no hay ASCII
+// de ese tamaño).
+
+use std::fmt;
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+/// Bytes antes del primer triángulo de un binario
+const BINARY_HEADER: usize = 84;
+const BINARY_TRIANGLE: usize = 50;
+
+/// Triángulos por lectura al recorrer un binario (3,2 MB)
+pub const STREAM_CHUNK: usize = 65_536;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StlFormat {
+    Ascii,
+    Binary,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StlTriangle {
+    /// Normal del archivo, o la de los vértices si venía en cero o inválida
+    pub normal: [f32; 3],
+    pub vertices: [[f32; 3]; 3],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StlFile {
+    pub format: StlFormat,
+    /// Nombre después de `solid` (ASCII) o texto del encabezado (binario)
+    pub name: String,
+    pub triangles: Vec<StlTriangle>,
+    /// Problemas que no impidieron leerlo
+    pub warnings: Vec<String>,
+}
+
+/// Lo que queda de un STL recorrido por partes (los triángulos ya se entregaron)
+#[derive(Debug, Clone, PartialEq)]
+pub struct StlSummary {
+    pub format: StlFormat,
+    pub name: String,
+    pub triangle_count: usize,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StlError {
+    /// No se pudo leer el archivo
+    Io(String),
+    /// La función de avance pidió cortar
+    Cancelled,
+    /// Menos bytes que un encabezado binario y no es ASCII
+    TooShort(usize),
+    /// El binario declara más triángulos de los que hay
+    Truncated { declared: u32, available: usize },
+    /// Se leyó bien pero no tiene ningún triángulo válido
+    Empty { format: StlFormat },
+    /// ASCII mal formado en `line` (empieza en 1)
+    Syntax { line: usize, message: String },
+}
+
+impl fmt::Display for StlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(message) => write!(f, "{}", message),
+            Self::Cancelled => write!(f, "lectura cancelada"),
+            Self::TooShort(len) => write!(f, "archivo de {} bytes, demasiado corto para un STL", len),
+            Self::Truncated { declared, available } => {
+                write!(f, "STL binario truncado: declara {} triángulos pero trae {}", declared, available)
+            }
+            Self::Empty { format } => write!(f, "STL {} sin triángulos", format_name(*format)),
+            Self::Syntax { line, message } => write!(f, "STL ASCII inválido en la línea {}: {}", line, message),
+        }
+    }
+}
+
+fn format_name(format: StlFormat) -> &'static str {
+    match format {
+        StlFormat::Ascii => "ASCII",
+        StlFormat::Binary => "binario",
+    }
+}
+
+/// Decide el formato y lee los triángulos
+pub fn parse(bytes: &[u8]) -> Result<StlFile, StlError> {
+    let file = match detect(bytes) {
+        StlFormat::Binary => parse_binary(bytes)?,
+        StlFormat::Ascii => parse_ascii(bytes)?,
+    };
+    if file.triangles.is_empty() {
+        return Err(StlError::Empty { format: file.format });
+    }
+    Ok(file)
+}
+
+/// Binario si el tamaño cierra con la cantidad declarada; si no, ASCII si
+/// empieza con "solid" (con BOM o espacios antes) y al principio aparece
+/// `facet` o `endsolid`, y binario si no
+pub fn detect(bytes: &[u8]) -> StlFormat {
+    detect_start(&bytes[..bytes.len().min(1024)], bytes.len() as u64)
+}
+
+/// `detect` con solo el primer kilobyte (`start`) y el tamaño total
+fn detect_start(start: &[u8], len: u64) -> StlFormat {
+    if start.len() >= BINARY_HEADER {
+        let count = u32::from_le_bytes([start[80], start[81], start[82], start[83]]) as u64;
+        if BINARY_HEADER as u64 + count * BINARY_TRIANGLE as u64 == len {
+            return StlFormat::Binary;
+        }
+    }
+    let text = start.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(start);
+    let start = text.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(text.len());
+    let keyword = &text[start..(start + 5).min(text.len())];
+    let contains = |word: &[u8]| text.windows(word.len()).any(|window| window.eq_ignore_ascii_case(word));
+    if keyword.eq_ignore_ascii_case(b"solid") && (contains(b"facet") || contains(b"endsolid")) {
+        StlFormat::Ascii
+    } else {
+        StlFormat::Binary
+    }
+}
+
+fn parse_binary(bytes: &[u8]) -> Result<StlFile, StlError> {
+    let mut triangles = Vec::new();
+    let summary = stream_binary(
+        &mut Cursor::new(bytes),
+        bytes.len() as u64,
+        STREAM_CHUNK,
+        |triangle| triangles.push(*triangle),
+        |_, _| true,
+    )?;
+    Ok(StlFile { format: StlFormat::Binary, name: summary.name, triangles, warnings: summary.warnings })
+}
+
+/// Recorre un STL binario de `len` bytes de a `chunk` triángulos.
+/// `progress` recibe los bytes leídos y el total después de cada lectura, y
+/// devuelve false para cortar (`StlError::Cancelled`).
+pub fn stream_binary<R: Read>(
+    reader: &mut R,
+    len: u64,
+    chunk: usize,
+    mut on_triangle: impl FnMut(&StlTriangle),
+    mut progress: impl FnMut(u64, u64) -> bool,
+) -> Result<StlSummary, StlError> {
+    if len < BINARY_HEADER as u64 {
+        return Err(StlError::TooShort(len as usize));
+    }
+    let io = |e: std::io::Error| StlError::Io(format!("error leyendo el STL: {}", e));
+    let mut header = [0u8; BINARY_HEADER];
+    reader.read_exact(&mut header).map_err(io)?;
+    let mut warnings = Vec::new();
+    let declared = u32::from_le_bytes([header[80], header[81], header[82], header[83]]);
+    let available = (len - BINARY_HEADER as u64) / BINARY_TRIANGLE as u64;
+    if declared as u64 > available {
+        return Err(StlError::Truncated { declared, available: available as usize });
+    }
+    let extra = len - BINARY_HEADER as u64 - declared as u64 * BINARY_TRIANGLE as u64;
+    if extra > 0 {
+        warnings.push(format!("{} bytes de más después del último triángulo", extra));
+    }
+
+    let mut buffer = vec![0u8; chunk.max(1) * BINARY_TRIANGLE];
+    let (mut remaining, mut read, mut invalid) = (declared as usize, BINARY_HEADER as u64, 0);
+    if !progress(read, len) {
+        return Err(StlError::Cancelled);
+    }
+    while remaining > 0 {
+        let count = remaining.min(chunk.max(1));
+        let bytes = &mut buffer[..count * BINARY_TRIANGLE];
+        reader.read_exact(bytes).map_err(io)?;
+        let float = |offset: usize| f32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]);
+        let vector = |offset: usize| [float(offset), float(offset + 4), float(offset + 8)];
+        for index in 0..count {
+            let offset = index * BINARY_TRIANGLE;
+            let vertices = [vector(offset + 12), vector(offset + 24), vector(offset + 36)];
+            match triangle(vector(offset), vertices) {
+                Some(triangle) => on_triangle(&triangle),
+                None => invalid += 1,
+            }
+        }
+        remaining -= count;
+        read += bytes.len() as u64;
+        if !progress(read, len) {
+            return Err(StlError::Cancelled);
+        }
+    }
+    if invalid > 0 {
+        warnings.push(format!("{} triángulos con coordenadas NaN o infinitas descartados", invalid));
+    }
+
+    let header = String::from_utf8_lossy(&header[..80]);
+    let name = header.trim_end_matches(['\0', ' ']).trim_start_matches("solid").trim().to_string();
+    Ok(StlSummary { format: StlFormat::Binary, name, triangle_count: declared as usize - invalid, warnings })
+}
+
+/// Lee el STL de `path` entregando los triángulos de a uno; el binario se
+/// recorre por partes sin cargarlo entero. `progress` es el de `stream_binary`.
+pub fn read_file(
+    path: &str,
+    mut on_triangle: impl FnMut(&StlTriangle),
+    mut progress: impl FnMut(u64, u64) -> bool,
+) -> Result<StlSummary, StlError> {
+    let io = |e: std::io::Error| StlError::Io(format!("no se pudo leer {}: {}", path, e));
+    let mut file = File::open(path).map_err(io)?;
+    let len = file.metadata().map_err(io)?.len();
+    let mut start = Vec::with_capacity(1024);
+    file.by_ref().take(1024).read_to_end(&mut start).map_err(io)?;
+
+    let summary = match detect_start(&start, len) {
+        StlFormat::Binary => {
+            file.seek(SeekFrom::Start(0)).map_err(io)?;
+            stream_binary(&mut file, len, STREAM_CHUNK, &mut on_triangle, &mut progress)?
+        }
+        StlFormat::Ascii => {
+            file.read_to_end(&mut start).map_err(io)?;
+            let parsed = parse_ascii(&start)?;
+            if !progress(len, len) {
+                return Err(StlError::Cancelled);
+            }
+            parsed.triangles.iter().for_each(&mut on_triangle);
+            StlSummary {
+                format: StlFormat::Ascii,
+                name: parsed.name,
+                triangle_count: parsed.triangles.len(),
+                warnings: parsed.warnings,
+            }
+        }
+    };
+    if summary.triangle_count == 0 {
+        return Err(StlError::Empty { format: summary.format });
+    }
+    Ok(summary)
+}
+
+/// Palabras separadas por espacios o finales de línea (LF o CRLF), con su línea
+struct Tokens<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    line: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn next(&mut self) -> Option<(&'a [u8], usize)> {
+        while self.position < self.bytes.len() && self.bytes[self.position].is_ascii_whitespace() {
+            if self.bytes[self.position] == b'\n' {
+                self.line += 1;
+            }
+            self.position += 1;
+        }
+        if self.position == self.bytes.len() {
+            return None;
+        }
+        let start = self.position;
+        while self.position < self.bytes.len() && !self.bytes[self.position].is_ascii_whitespace() {
+            self.position += 1;
+        }
+        Some((&self.bytes[start..self.position], self.line))
+    }
+
+    /// El resto de la línea actual, sin espacios en los extremos
+    fn rest_of_line(&mut self) -> &'a [u8] {
+        let start = self.position;
+        while self.position < self.bytes.len() && self.bytes[self.position] != b'\n' {
+            self.position += 1;
+        }
+        self.bytes[start..self.position].trim_ascii()
+    }
+
+    fn error(&self, message: String) -> StlError {
+        StlError::Syntax { line: self.line, message }
+    }
+
+    fn expect(&mut self, keyword: &str) -> Result<(), StlError> {
+        match self.next() {
+            Some((token, _)) if token.eq_ignore_ascii_case(keyword.as_bytes()) => Ok(()),
+            Some((token, _)) => Err(self.error(format!("se esperaba '{}' y hay '{}'", keyword, String::from_utf8_lossy(token)))),
+            None => Err(self.error(format!("el archivo termina donde se esperaba '{}'", keyword))),
+        }
+    }
+
+    fn vector(&mut self) -> Result<[f32; 3], StlError> {
+        let mut vector = [0.0; 3];
+        for value in &mut vector {
+            let (token, _) = self.next().ok_or_else(|| self.error("el archivo termina en medio de un número".to_string()))?;
+            *value = std::str::from_utf8(token)
+                .ok()
+                .and_then(|text| text.parse().ok())
+                .ok_or_else(|| self.error(format!("'{}' no es un número", String::from_utf8_lossy(token))))?;
+        }
+        Ok(vector)
+    }
+}
+
+fn parse_ascii(bytes: &[u8]) -> Result<StlFile, StlError> {
+    let text = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let mut tokens = Tokens { bytes: text, position: 0, line: 1 };
+    tokens.expect("solid")?;
+    // El nombre puede tener espacios y cualquier codificación
+    let name = String::from_utf8_lossy(tokens.rest_of_line()).into_owned();
+
+    let mut triangles = Vec::new();
+    let mut warnings = Vec::new();
+    let mut invalid = 0;
+    let mut closed = false;
+    while let Some((token, line)) = tokens.next() {
+        let keyword = token.to_ascii_lowercase();
+        match keyword.as_slice() {
+            b"facet" => {
+                closed = false;
+                tokens.expect("normal")?;
+                let normal = tokens.vector()?;
+                tokens.expect("outer")?;
+                tokens.expect("loop")?;
+                // Algunos exportadores escriben polígonos: se abren en abanico
+                let mut polygon = Vec::new();
+                loop {
+                    match tokens.next() {
+                        Some((token, _)) if token.eq_ignore_ascii_case(b"vertex") => polygon.push(tokens.vector()?),
+                        Some((token, _)) if token.eq_ignore_ascii_case(b"endloop") => break,
+                        Some((token, _)) => {
+                            return Err(tokens.error(format!(
+                                "se esperaba 'vertex' o 'endloop' y hay '{}'",
+                                String::from_utf8_lossy(token)
+                            )))
+                        }
+                        None => return Err(tokens.error("el archivo termina dentro de una cara".to_string())),
+                    }
+                }
+                tokens.expect("endfacet")?;
+                if polygon.len() < 3 {
+                    return Err(StlError::Syntax { line, message: format!("cara con {} vértices", polygon.len()) });
+                }
+                if polygon.len() > 3 {
+                    warnings.push(format!("línea {}: cara de {} vértices dividida en triángulos", line, polygon.len()));
+                }
+                for index in 1..polygon.len() - 1 {
+                    match triangle(normal, [polygon[0], polygon[index], polygon[index + 1]]) {
+                        Some(triangle) => triangles.push(triangle),
+                        None => invalid += 1,
+                    }
+                }
+            }
+            b"endsolid" => {
+                tokens.rest_of_line();
+                closed = true;
+            }
+            // Varios sólidos seguidos en un archivo: se juntan
+            b"solid" => {
+                tokens.rest_of_line();
+                closed = false;
+            }
+            _ => {
+                return Err(StlError::Syntax {
+                    line,
+                    message: format!("se esperaba 'facet' o 'endsolid' y hay '{}'", String::from_utf8_lossy(token)),
+                })
+            }
+        }
+    }
+    if !closed {
+        warnings.push("falta 'endsolid' al final (¿archivo cortado?)".to_string());
+    }
+    if invalid > 0 {
+        warnings.push(format!("{} triángulos con coordenadas NaN o infinitas descartados", invalid));
+    }
+    Ok(StlFile { format: StlFormat::Ascii, name, triangles, warnings })
+}
+
+/// Triángulo con normal utilizable; None si alguna coordenada no es finita
+fn triangle(normal: [f32; 3], vertices: [[f32; 3]; 3]) -> Option<StlTriangle> {
+    if vertices.iter().flatten().any(|v| !v.is_finite()) {
+        return None;
+    }
+    let length = normal.iter().map(|n| n * n).sum::<f32>().sqrt();
+    if length.is_finite() && length > 1e-6 {
+        return Some(StlTriangle { normal: normal.map(|n| n / length), vertices });
+    }
+    // Normal en cero (muy común): la del orden de los vértices
+    let [a, b, c] = vertices;
+    let (u, v) = ([b[0] - a[0], b[1] - a[1], b[2] - a[2]], [c[0] - a[0], c[1] - a[1], c[2] - a[2]]);
+    let cross = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+    let length = cross.iter().map(|n| n * n).sum::<f32>().sqrt();
+    let normal = if length > 0.0 { cross.map(|n| n / length) } else { [0.0; 3] };
+    Some(StlTriangle { normal, vertices })
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binary(header: &[u8], triangles: &[[[f32; 3]; 4]], declared: u32) -> Vec<u8> {
+        let mut bytes = vec![0u8; 80];
+        bytes[..header.len()].copy_from_slice(header);
+        bytes.extend_from_slice(&declared.to_le_bytes());
+        for triangle in triangles {
+            for value in triangle.iter().flatten() {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            bytes.extend_from_slice(&[0, 0]);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_vendor_edge_cases() {
+        // ASCII con nombre en Latin-1, CRLF, mayúsculas y normal en cero
+        let mut ascii = b"solid pi\xE8ce \xF1and\xFA\r\n".to_vec();
+        ascii.extend_from_slice(
+            b"  FACET NORMAL 0 0 0\r\n    outer loop\r\n      vertex 0 0 0\r\n      vertex 1 0 0\r\n      vertex 0 1 0\r\n    endloop\r\n  endfacet\r\nendsolid pi\xE8ce\r\n",
+        );
+        let file = parse(&ascii).unwrap();
+        assert_eq!(file.format, StlFormat::Ascii);
+        assert!(file.name.starts_with("pi") && file.name.ends_with("and\u{FFFD}"));
+        assert_eq!(file.triangles.len(), 1);
+        assert_eq!(file.triangles[0].normal, [0.0, 0.0, 1.0]);
+        assert!(file.warnings.is_empty());
+
+        // Binario con "solid" en el encabezado: decide el tamaño
+        let face = [[0.0, 0.0, 1.0], [0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        let bytes = binary(b"solid exportado por un CAD", &[face, face], 2);
+        let file = parse(&bytes).unwrap();
+        assert_eq!((file.format, file.triangles.len()), (StlFormat::Binary, 2));
+        assert_eq!(file.name, "exportado por un CAD");
+
+        // Bytes de más: se avisa; triángulos de menos: error
+        let mut padded = bytes.clone();
+        padded.extend_from_slice(&[0; 7]);
+        let file = parse(&padded).unwrap();
+        assert_eq!(file.warnings, vec!["7 bytes de más después del último triángulo".to_string()]);
+        let truncated = binary(b"", &[face], 3);
+        assert_eq!(parse(&truncated), Err(StlError::Truncated { declared: 3, available: 1 }));
+
+        // Sin triángulos, en los dos formatos
+        assert_eq!(parse(&binary(b"", &[], 0)), Err(StlError::Empty { format: StlFormat::Binary }));
+        assert_eq!(parse(b"solid vacio\nendsolid vacio\n"), Err(StlError::Empty { format: StlFormat::Ascii }));
+        assert_eq!(parse(b"abc"), Err(StlError::TooShort(3)));
+
+        // Errores con la línea donde fallan; polígonos en abanico
+        let broken = b"solid a\nfacet normal 0 0 1\nouter loop\nvertex 0 0 x\n";
+        assert_eq!(parse(broken), Err(StlError::Syntax { line: 4, message: "'x' no es un número".to_string() }));
+        let quad = b"solid a\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\nvertex 1 1 0\nvertex 0 1 0\nendloop\nendfacet\n";
+        let file = parse(quad).unwrap();
+        assert_eq!(file.triangles.len(), 2);
+        assert_eq!(file.warnings.len(), 2);
+        assert!(StlError::Truncated { declared: 3, available: 1 }.to_string().contains("3 triángulos"));
+
+        // Por partes: de a 2 triángulos, con el avance después de cada lectura
+        let bytes = binary(b"", &[face, face, face], 3);
+        let (mut count, mut steps) = (0, Vec::new());
+        let summary = stream_binary(&mut Cursor::new(&bytes), bytes.len() as u64, 2, |_| count += 1, |done, _| {
+            steps.push(done);
+            true
+        })
+        .unwrap();
+        assert_eq!((count, summary.triangle_count), (3, 3));
+        assert_eq!(steps, vec![84, 184, 234]);
+        // Cortar después de la primera lectura
+        let cancelled = stream_binary(&mut Cursor::new(&bytes), bytes.len() as u64, 2, |_| {}, |done, _| done < 184);
+        assert_eq!(cancelled, Err(StlError::Cancelled));
+
+        // Desde archivo: el binario se recorre y el ASCII se lee entero
+        let dir = std::env::temp_dir().join(format!("rust_engine_stl_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, contents, format) in [("b.stl", &bytes, StlFormat::Binary), ("a.stl", &ascii, StlFormat::Ascii)] {
+            let path = dir.join(name);
+            std::fs::write(&path, contents).unwrap();
+            let mut count = 0;
+            let summary = read_file(&path.to_string_lossy(), |_| count += 1, |_, _| true).unwrap();
+            assert_eq!((summary.format, summary.triangle_count), (format, count));
+        }
+        assert!(matches!(read_file(&dir.join("no.stl").to_string_lossy(), |_| {}, |_, _| true), Err(StlError::Io(_))));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,553 @@
+// src/graphics/text.rs
+//
+// Texto en pantalla (ayuda de teclas, avisos) en paneles por nombre, como las
+// capas de `LineOverlay`. Los glifos ASCII y los acentos del español se
+// rasterizan una vez en un atlas con ab_glyph, a partir de una fuente del
+// sistema o de la que indique RUST_ENGINE_FONT; sin fuente el pase no dibuja
+// nada y lo avisa una sola vez. Las posiciones van en píxeles lógicos y se
+// escalan con el factor de la ventana. Además de texto se pueden poner
+// rectángulos lisos (barras y gráficos simples, como los del perfilador).
+// Sin la feature `ui` los paneles se siguen armando pero no hay pase que los
+// dibuje.
+
+#[cfg(feature = "ui")]
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+#[cfg(feature = "ui")]
+use std::collections::HashMap;
+#[cfg(feature = "ui")]
+use std::rc::Rc;
+
+#[cfg(feature = "ui")]
+use ab_glyph::{point, Font, FontVec, PxScale, ScaleFont};
+
+#[cfg(feature = "ui")]
+use crate::graphics::gpu_resources::ContextGeneration;
+#[cfg(feature = "ui")]
+use crate::graphics::gl_validation;
+#[cfg(feature = "ui")]
+use crate::graphics::render_graph::{FrameContext, PassStage, RenderPass, ResourceId, BACKBUFFER};
+#[cfg(feature = "ui")]
+use crate::graphics::render_settings::RenderSettings;
+#[cfg(feature = "ui")]
+use crate::graphics::shaders::{build_program, uniform_location, ShaderLibrary};
+
+/// Variable de entorno con la ruta de una fuente .ttf / .otf / .ttc
+#[cfg(feature = "ui")]
+pub const FONT_ENV: &str = "RUST_ENGINE_FONT";
+
+/// Fuentes monoespaciadas habituales (las columnas de la ayuda quedan alineadas)
+#[cfg(feature = "ui")]
+const FONT_CANDIDATES: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf",
+    "/usr/share/fonts/TTF/DejaVuSansMono.ttf",
+    "/usr/share/fonts/dejavu-sans-mono-fonts/DejaVuSansMono.ttf",
+    "/System/Library/Fonts/Menlo.ttc",
+    "C:\\Windows\\Fonts\\consola.ttf",
+];
+
+/// Alto del texto en píxeles lógicos
+#[cfg(feature = "ui")]
+pub const TEXT_SIZE: f32 = 16.0;
+
+/// Margen entre el borde del panel y el texto, en píxeles lógicos
+#[cfg(feature = "ui")]
+const PANEL_PADDING: f32 = 8.0;
+
+#[cfg(feature = "ui")]
+const ATLAS_SIZE: usize = 512;
+
+/// Caracteres que entran en el atlas; el resto se dibuja como '?'
+#[cfg(feature = "ui")]
+fn charset() -> impl Iterator<Item = char> {
+    (' '..='~').chain("áéíóúÁÉÍÓÚñÑüÜ¿¡°".chars())
+}
+
+/// Bloque de texto con fondo
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextPanel {
+    pub lines: Vec<String>,
+    /// Esquina superior izquierda en píxeles lógicos
+    pub position: (f32, f32),
+    /// Color lineal del texto
+    pub color: [f32; 3],
+    /// Color de cada línea (p. ej. para leyendas); las que faltan usan `color`
+    pub line_colors: Vec<[f32; 3]>,
+    /// Color lineal y opacidad del fondo
+    pub background: [f32; 4],
+}
+
+impl TextPanel {
+    /// Texto claro sobre fondo oscuro semitransparente, arriba a la izquierda
+    pub fn new(lines: Vec<String>) -> Self {
+        Self {
+            lines,
+            position: (16.0, 16.0),
+            color: [0.9, 0.9, 0.9],
+            line_colors: Vec::new(),
+            background: [0.0, 0.0, 0.0, 0.7],
+        }
+    }
+}
+
+/// Rectángulo liso, en píxeles lógicos desde arriba a la izquierda
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenRect {
+    pub min: (f32, f32),
+    pub max: (f32, f32),
+    /// Color lineal y opacidad
+    pub color: [f32; 4],
+}
+
+/// Paneles de texto y grupos de rectángulos por nombre
+#[derive(Debug)]
+pub struct TextOverlay {
+    panels: BTreeMap<String, TextPanel>,
+    /// Se dibujan encima de todos los paneles
+    rects: BTreeMap<String, Vec<ScreenRect>>,
+    /// Píxeles físicos por píxel lógico de la ventana
+    pub scale: f32,
+    /// Si hay, solo se dibuja esa capa (ver `set_only`)
+    only: Option<String>,
+    #[cfg_attr(not(feature = "ui"), allow(dead_code))]
+    dirty: bool,
+}
+
+impl Default for TextOverlay {
+    fn default() -> Self {
+        Self { panels: BTreeMap::new(), rects: BTreeMap::new(), scale: 1.0, only: None, dirty: true }
+    }
+}
+
+impl TextOverlay {
+    /// Reemplaza el panel `name`
+    pub fn set(&mut self, name: &str, panel: TextPanel) {
+        self.panels.insert(name.to_string(), panel);
+        self.dirty = true;
+    }
+
+    /// Reemplaza los rectángulos `name`
+    pub fn set_rects(&mut self, name: &str, rects: Vec<ScreenRect>) {
+        self.rects.insert(name.to_string(), rects);
+        self.dirty = true;
+    }
+
+    /// Quita el panel y los rectángulos `name`
+    pub fn clear(&mut self, name: &str) {
+        let panel = self.panels.remove(name).is_some();
+        if self.rects.remove(name).is_some() || panel {
+            self.dirty = true;
+        }
+    }
+
+    /// Quita los paneles y rectángulos cuyo nombre empieza con `prefix`
+    pub fn clear_prefix(&mut self, prefix: &str) {
+        let (panels, rects) = (self.panels.len(), self.rects.len());
+        self.panels.retain(|name, _| !name.starts_with(prefix));
+        self.rects.retain(|name, _| !name.starts_with(prefix));
+        if panels != self.panels.len() || rects != self.rects.len() {
+            self.dirty = true;
+        }
+    }
+
+    pub fn panel(&self, name: &str) -> Option<&TextPanel> {
+        self.panels.get(name)
+    }
+
+    /// Dibuja solo el panel y los rectángulos `name` (las capturas llevan la
+    /// barra de escala pero no la ayuda ni el profiler); None vuelve a todos
+    pub fn set_only(&mut self, name: Option<&str>) {
+        if self.only.as_deref() != name {
+            self.only = name.map(str::to_string);
+            self.dirty = true;
+        }
+    }
+
+    #[cfg_attr(not(feature = "ui"), allow(dead_code))]
+    fn shown(&self, name: &str) -> bool {
+        self.only.as_deref().is_none_or(|only| only == name)
+    }
+
+    pub fn set_scale(&mut self, scale: f32) {
+        if scale != self.scale {
+            self.scale = scale;
+            self.dirty = true;
+        }
+    }
+}
+
+/// Un glifo del atlas, en píxeles físicos
+#[cfg(feature = "ui")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GlyphInfo {
+    /// Esquina superior izquierda respecto del lápiz sobre la línea base
+    offset: [f32; 2],
+    size: [f32; 2],
+    uv_min: [f32; 2],
+    uv_max: [f32; 2],
+    advance: f32,
+}
+
+/// Métricas y cobertura de los glifos rasterizados a un tamaño
+#[cfg(feature = "ui")]
+struct GlyphAtlas {
+    glyphs: HashMap<char, GlyphInfo>,
+    ascent: f32,
+    line_height: f32,
+    /// Texel siempre opaco, para los fondos
+    white_uv: [f32; 2],
+    /// Cobertura (R8), ATLAS_SIZE x ATLAS_SIZE
+    pixels: Vec<u8>,
+}
+
+#[cfg(feature = "ui")]
+impl GlyphAtlas {
+    fn rasterize(font: &FontVec, px: f32) -> Self {
+        let scaled = font.as_scaled(PxScale::from(px));
+        let mut pixels = vec![0u8; ATLAS_SIZE * ATLAS_SIZE];
+        // Bloque blanco de 2x2 en la esquina, muestreado en su centro
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            pixels[y * ATLAS_SIZE + x] = 255;
+        }
+        let texel = 1.0 / ATLAS_SIZE as f32;
+        let mut glyphs = HashMap::new();
+        // Filas de glifos con 1 píxel de separación para que no se mezclen al filtrar
+        let (mut x, mut y, mut row_height) = (3usize, 0usize, 2usize);
+        for c in charset() {
+            let mut glyph = scaled.scaled_glyph(c);
+            glyph.position = point(0.0, 0.0);
+            let advance = scaled.h_advance(glyph.id);
+            let Some(outline) = scaled.outline_glyph(glyph) else {
+                // Espacios: solo avanzan
+                glyphs.insert(c, GlyphInfo { offset: [0.0; 2], size: [0.0; 2], uv_min: [0.0; 2], uv_max: [0.0; 2], advance });
+                continue;
+            };
+            let bounds = outline.px_bounds();
+            let (width, height) = (bounds.width().ceil() as usize, bounds.height().ceil() as usize);
+            if x + width + 1 > ATLAS_SIZE {
+                x = 0;
+                y += row_height + 1;
+                row_height = 0;
+            }
+            if y + height > ATLAS_SIZE {
+                break;
+            }
+            outline.draw(|gx, gy, coverage| {
+                let (px, py) = (x + gx as usize, y + gy as usize);
+                if px < ATLAS_SIZE && py < ATLAS_SIZE {
+                    pixels[py * ATLAS_SIZE + px] = (coverage.clamp(0.0, 1.0) * 255.0) as u8;
+                }
+            });
+            glyphs.insert(
+                c,
+                GlyphInfo {
+                    offset: [bounds.min.x, bounds.min.y],
+                    size: [width as f32, height as f32],
+                    uv_min: [x as f32 * texel, y as f32 * texel],
+                    uv_max: [(x + width) as f32 * texel, (y + height) as f32 * texel],
+                    advance,
+                },
+            );
+            x += width + 1;
+            row_height = row_height.max(height);
+        }
+        Self {
+            glyphs,
+            ascent: scaled.ascent(),
+            line_height: scaled.height() + scaled.line_gap(),
+            white_uv: [texel, texel],
+            pixels,
+        }
+    }
+
+    fn glyph(&self, c: char) -> Option<&GlyphInfo> {
+        self.glyphs.get(&c).or_else(|| self.glyphs.get(&'?'))
+    }
+
+    fn line_width(&self, line: &str) -> f32 {
+        line.chars().filter_map(|c| self.glyph(c)).map(|g| g.advance).sum()
+    }
+
+    /// Triángulos de todos los paneles: (x, y) en píxeles físicos desde
+    /// arriba a la izquierda, (u, v) y color lineal con opacidad
+    fn layout(&self, overlay: &TextOverlay) -> Vec<[f32; 8]> {
+        let mut vertices = Vec::new();
+        let padding = PANEL_PADDING * overlay.scale;
+        for panel in overlay.panels.iter().filter(|(name, _)| overlay.shown(name)).map(|(_, panel)| panel) {
+            let origin = [panel.position.0 * overlay.scale, panel.position.1 * overlay.scale];
+            let width = panel.lines.iter().map(|line| self.line_width(line)).fold(0.0, f32::max);
+            let height = panel.lines.len() as f32 * self.line_height;
+            let corner = [origin[0] + width + 2.0 * padding, origin[1] + height + 2.0 * padding];
+            push_quad(&mut vertices, origin, corner, self.white_uv, self.white_uv, panel.background);
+
+            for (row, line) in panel.lines.iter().enumerate() {
+                let [r, g, b] = panel.line_colors.get(row).copied().unwrap_or(panel.color);
+                let mut pen = origin[0] + padding;
+                let baseline = origin[1] + padding + self.ascent + row as f32 * self.line_height;
+                for glyph in line.chars().filter_map(|c| self.glyph(c)) {
+                    if glyph.size[0] > 0.0 {
+                        let min = [pen + glyph.offset[0], baseline + glyph.offset[1]];
+                        let max = [min[0] + glyph.size[0], min[1] + glyph.size[1]];
+                        push_quad(&mut vertices, min, max, glyph.uv_min, glyph.uv_max, [r, g, b, 1.0]);
+                    }
+                    pen += glyph.advance;
+                }
+            }
+        }
+        for rect in overlay.rects.iter().filter(|(name, _)| overlay.shown(name)).flat_map(|(_, rects)| rects) {
+            let min = [rect.min.0 * overlay.scale, rect.min.1 * overlay.scale];
+            let max = [rect.max.0 * overlay.scale, rect.max.1 * overlay.scale];
+            push_quad(&mut vertices, min, max, self.white_uv, self.white_uv, rect.color);
+        }
+        vertices
+    }
+}
+
+#[cfg(feature = "ui")]
+fn push_quad(vertices: &mut Vec<[f32; 8]>, min: [f32; 2], max: [f32; 2], uv_min: [f32; 2], uv_max: [f32; 2], color: [f32; 4]) {
+    let [r, g, b, a] = color;
+    let corner = |x: usize, y: usize| {
+        let (px, u) = if x == 0 { (min[0], uv_min[0]) } else { (max[0], uv_max[0]) };
+        let (py, v) = if y == 0 { (min[1], uv_min[1]) } else { (max[1], uv_max[1]) };
+        [px, py, u, v, r, g, b, a]
+    };
+    vertices.extend([corner(0, 0), corner(1, 0), corner(1, 1), corner(0, 0), corner(1, 1), corner(0, 1)]);
+}
+
+/// Primera fuente que se pueda leer: la de RUST_ENGINE_FONT o una del sistema
+#[cfg(feature = "ui")]
+pub(crate) fn load_font() -> Result<FontVec, String> {
+    let requested = std::env::var(FONT_ENV).ok();
+    for path in requested.iter().map(String::as_str).chain(FONT_CANDIDATES.iter().copied()) {
+        if let Ok(bytes) = std::fs::read(path) {
+            return FontVec::try_from_vec_and_index(bytes, 0).map_err(|e| format!("Fuente inválida {}: {}", path, e));
+        }
+    }
+    Err(format!("no se encontró ninguna fuente (se puede indicar una con {})", FONT_ENV))
+}
+
+/// Pase que dibuja el `TextOverlay` encima de todo
+#[cfg(feature = "ui")]
+pub struct TextPass {
+    program: u32,
+    vao: u32,
+    vbo: u32,
+    texture: u32,
+    vertex_count: i32,
+    /// None hasta que haya texto que dibujar; Err si no hay fuente
+    font: Option<Result<FontVec, ()>>,
+    /// Atlas subido y el factor de escala con que se rasterizó
+    atlas: Option<(GlyphAtlas, f32)>,
+    overlay: Rc<RefCell<TextOverlay>>,
+    generation: ContextGeneration,
+}
+
+#[cfg(feature = "ui")]
+impl TextPass {
+    pub fn new(overlay: Rc<RefCell<TextOverlay>>) -> Result<Self, String> {
+        let program = build_program(
+            include_str!("shaders/text.vert"),
+            include_str!("shaders/text.frag"),
+        )?;
+        let (mut vao, mut vbo, mut texture) = (0, 0, 0);
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+            gl::GenBuffers(1, &mut vbo);
+            gl::BindVertexArray(vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+            let stride = (8 * std::mem::size_of::<f32>()) as i32;
+            // (location=0) posición + uv, (location=1) color
+            gl::VertexAttribPointer(0, 4, gl::FLOAT, gl::FALSE, stride, std::ptr::null());
+            gl::EnableVertexAttribArray(0);
+            gl::VertexAttribPointer(1, 4, gl::FLOAT, gl::FALSE, stride, (4 * std::mem::size_of::<f32>()) as *const _);
+            gl::EnableVertexAttribArray(1);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
+
+            gl::GenTextures(1, &mut texture);
+            gl::BindTexture(gl::TEXTURE_2D, texture);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+        Ok(Self {
+            program,
+            vao,
+            vbo,
+            texture,
+            vertex_count: 0,
+            font: None,
+            atlas: None,
+            overlay,
+            generation: ContextGeneration::current(),
+        })
+    }
+
+    /// Rasteriza y sube el atlas si todavía no está o cambió la escala
+    fn ensure_atlas(&mut self, scale: f32) -> bool {
+        if self.atlas.as_ref().is_some_and(|(_, atlas_scale)| *atlas_scale == scale) {
+            return true;
+        }
+        let font = self.font.get_or_insert_with(|| {
+            load_font().map_err(|e| eprintln!("Texto en pantalla desactivado: {}", e))
+        });
+        let Ok(font) = font else {
+            return false;
+        };
+        let atlas = GlyphAtlas::rasterize(font, TEXT_SIZE * scale);
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.texture);
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            gl::TexImage2D(
+                gl::TEXTURE_2D, 0, gl::R8 as i32, ATLAS_SIZE as i32, ATLAS_SIZE as i32, 0,
+                gl::RED, gl::UNSIGNED_BYTE, atlas.pixels.as_ptr() as *const _,
+            );
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 4);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+        self.atlas = Some((atlas, scale));
+        true
+    }
+}
+
+#[cfg(feature = "ui")]
+impl RenderPass for TextPass {
+    fn name(&self) -> &str {
+        "text"
+    }
+
+    fn stage(&self) -> PassStage {
+        PassStage::Post
+    }
+
+    fn outputs(&self) -> &[ResourceId] {
+        &[BACKBUFFER]
+    }
+
+    fn settings(&self, global: &RenderSettings) -> RenderSettings {
+        RenderSettings {
+            depth_test: false,
+            wireframe: false,
+            backface_culling: false,
+            ..*global
+        }
+    }
+
+    fn rebuild_gpu(&mut self, _shaders: &ShaderLibrary) -> Result<(), String> {
+        *self = Self::new(self.overlay.clone())?;
+        self.overlay.borrow_mut().dirty = true;
+        Ok(())
+    }
+
+    fn execute(&mut self, frame: &FrameContext) {
+        let overlay = self.overlay.clone();
+        let mut overlay = overlay.borrow_mut();
+        if overlay.panels.is_empty() && overlay.rects.is_empty() {
+            return;
+        }
+        let rebuilt = self.atlas.as_ref().map(|(_, scale)| *scale) != Some(overlay.scale);
+        if !self.ensure_atlas(overlay.scale) {
+            return;
+        }
+        if overlay.dirty || rebuilt {
+            let Some((atlas, _)) = &self.atlas else {
+                return;
+            };
+            let vertices = atlas.layout(&overlay);
+            self.vertex_count = vertices.len() as i32;
+            unsafe {
+                gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+                gl::BufferData(
+                    gl::ARRAY_BUFFER,
+                    std::mem::size_of_val(vertices.as_slice()) as isize,
+                    vertices.as_ptr() as *const _,
+                    gl::DYNAMIC_DRAW,
+                );
+                gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            }
+            overlay.dirty = false;
+        }
+
+        let (width, height) = frame.viewport;
+        unsafe {
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            gl::UseProgram(self.program);
+            gl::Uniform2f(uniform_location(self.program, "screenSize"), width as f32, height as f32);
+            gl::Uniform1f(uniform_location(self.program, "gamma"), frame.settings.gamma);
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture);
+            gl::Uniform1i(uniform_location(self.program, "atlas"), 0);
+            gl::BindVertexArray(self.vao);
+            gl_validation::draw_arrays(gl::TRIANGLES, 0, self.vertex_count);
+            gl::BindVertexArray(0);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::Disable(gl::BLEND);
+        }
+    }
+}
+
+#[cfg(feature = "ui")]
+impl Drop for TextPass {
+    fn drop(&mut self) {
+        if !self.generation.is_current() {
+            return;
+        }
+        unsafe {
+            gl::DeleteTextures(1, &self.texture);
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteProgram(self.program);
+        }
+    }
+}
+
+// Pruebas unitarias
+#[cfg(all(test, feature = "ui"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panel_layout() {
+        // Atlas a mano: 'a' de 6x8 con 10 de avance, espacio de 5
+        let a = GlyphInfo { offset: [1.0, -8.0], size: [6.0, 8.0], uv_min: [0.1, 0.1], uv_max: [0.2, 0.2], advance: 10.0 };
+        let space = GlyphInfo { offset: [0.0; 2], size: [0.0; 2], uv_min: [0.0; 2], uv_max: [0.0; 2], advance: 5.0 };
+        let atlas = GlyphAtlas {
+            glyphs: HashMap::from([('a', a), (' ', space), ('?', a)]),
+            ascent: 12.0,
+            line_height: 16.0,
+            white_uv: [0.001, 0.001],
+            pixels: Vec::new(),
+        };
+        assert_eq!(atlas.line_width("a a"), 25.0);
+
+        let mut overlay = TextOverlay::default();
+        overlay.set("ayuda", TextPanel { position: (10.0, 20.0), ..TextPanel::new(vec!["a a".into(), "é".into()]) });
+        overlay.set_scale(2.0);
+        let vertices = atlas.layout(&overlay);
+        // Fondo + 2 'a' + el '?' que reemplaza a 'é' (el espacio no dibuja nada)
+        assert_eq!(vertices.len(), 6 * 4);
+
+        // Fondo: desde la posición escalada, con margen y dos líneas de alto
+        let padding = PANEL_PADDING * 2.0;
+        assert_eq!(&vertices[0][..2], &[20.0, 40.0]);
+        assert_eq!(&vertices[2][..2], &[20.0 + 25.0 + 2.0 * padding, 40.0 + 32.0 + 2.0 * padding]);
+        assert_eq!(vertices[0][7], 0.7);
+
+        // Primer glifo: sobre la línea base, corrido por su offset
+        let baseline = 40.0 + padding + 12.0;
+        assert_eq!(&vertices[6][..4], &[20.0 + padding + 1.0, baseline - 8.0, 0.1, 0.1]);
+        // El segundo 'a' empieza después del espacio
+        assert_eq!(vertices[12][0], 20.0 + padding + 15.0 + 1.0);
+
+        // Los rectángulos van al final, escalados y con el texel blanco
+        overlay.set_rects("barras", vec![ScreenRect { min: (1.0, 2.0), max: (3.0, 4.0), color: [1.0, 0.0, 0.0, 0.5] }]);
+        let vertices = atlas.layout(&overlay);
+        assert_eq!(vertices.len(), 6 * 5);
+        assert_eq!(vertices[24], [2.0, 4.0, 0.001, 0.001, 1.0, 0.0, 0.0, 0.5]);
+        overlay.clear("barras");
+        assert_eq!(atlas.layout(&overlay).len(), 6 * 4);
+    }
+}
@@ -0,0 +1,191 @@
+// src/graphics/texture/bcn.rs
+//
+// Descompresión en CPU de BC1..BC5 a RGBA8, para cuando el driver no soporta
+// el formato comprimido.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BcFormat {
+    Bc1,
+    Bc2,
+    Bc3,
+    Bc4,
+    Bc5,
+}
+
+impl BcFormat {
+    pub fn block_size(&self) -> usize {
+        match self {
+            BcFormat::Bc1 | BcFormat::Bc4 => 8,
+            _ => 16,
+        }
+    }
+}
+
+/// Descomprime una imagen completa. `data` son los bloques 4x4 en orden de filas.
+pub fn decompress(format: BcFormat, data: &[u8], width: u32, height: u32) -> Result<Vec<u8>, String> {
+    let (width, height) = (width as usize, height as usize);
+    let blocks_x = width.div_ceil(4);
+    let blocks_y = height.div_ceil(4);
+    let block_size = format.block_size();
+    if data.len() < blocks_x * blocks_y * block_size {
+        return Err(format!(
+            "Datos {:?} truncados: {} bytes para {}x{}",
+            format,
+            data.len(),
+            width,
+            height
+        ));
+    }
+
+    let mut out = vec![0u8; width * height * 4];
+    let mut texels = [[0u8; 4]; 16];
+
+    for by in 0..blocks_y {
+        for bx in 0..blocks_x {
+            let offset = (by * blocks_x + bx) * block_size;
+            let block = &data[offset..offset + block_size];
+            decode_block(format, block, &mut texels);
+
+            for ty in 0..4 {
+                for tx in 0..4 {
+                    let (x, y) = (bx * 4 + tx, by * 4 + ty);
+                    if x < width && y < height {
+                        let dst = (y * width + x) * 4;
+                        out[dst..dst + 4].copy_from_slice(&texels[ty * 4 + tx]);
+                    }
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn decode_block(format: BcFormat, block: &[u8], texels: &mut [[u8; 4]; 16]) {
+    match format {
+        BcFormat::Bc1 => decode_color(block, texels, true),
+        BcFormat::Bc2 => {
+            decode_color(&block[8..], texels, false);
+            // Alpha explícito de 4 bits por texel
+            for (i, texel) in texels.iter_mut().enumerate() {
+                let nibble = (block[i / 2] >> ((i % 2) * 4)) & 0x0F;
+                texel[3] = nibble * 17;
+            }
+        }
+        BcFormat::Bc3 => {
+            decode_color(&block[8..], texels, false);
+            let mut alpha = [0u8; 16];
+            decode_channel(&block[..8], &mut alpha);
+            for (texel, a) in texels.iter_mut().zip(alpha) {
+                texel[3] = a;
+            }
+        }
+        BcFormat::Bc4 => {
+            let mut red = [0u8; 16];
+            decode_channel(block, &mut red);
+            for (texel, r) in texels.iter_mut().zip(red) {
+                *texel = [r, r, r, 255];
+            }
+        }
+        BcFormat::Bc5 => {
+            let mut red = [0u8; 16];
+            let mut green = [0u8; 16];
+            decode_channel(&block[..8], &mut red);
+            decode_channel(&block[8..], &mut green);
+            for i in 0..16 {
+                texels[i] = [red[i], green[i], 0, 255];
+            }
+        }
+    }
+}
+
+fn rgb565(c: u16) -> [u8; 3] {
+    let r = ((c >> 11) & 0x1F) as u32;
+    let g = ((c >> 5) & 0x3F) as u32;
+    let b = (c & 0x1F) as u32;
+    [(r * 255 / 31) as u8, (g * 255 / 63) as u8, (b * 255 / 31) as u8]
+}
+
+fn mix(a: [u8; 3], b: [u8; 3], wa: u32, wb: u32) -> [u8; 4] {
+    let total = wa + wb;
+    [
+        ((a[0] as u32 * wa + b[0] as u32 * wb) / total) as u8,
+        ((a[1] as u32 * wa + b[1] as u32 * wb) / total) as u8,
+        ((a[2] as u32 * wa + b[2] as u32 * wb) / total) as u8,
+        255,
+    ]
+}
+
+/// Bloque de color de 8 bytes (BC1). `punch_through` habilita el modo de 3 colores
+/// + transparente cuando c0 <= c1.
+fn decode_color(block: &[u8], texels: &mut [[u8; 4]; 16], punch_through: bool) {
+    let c0 = u16::from_le_bytes([block[0], block[1]]);
+    let c1 = u16::from_le_bytes([block[2], block[3]]);
+    let (a, b) = (rgb565(c0), rgb565(c1));
+
+    let palette = if c0 > c1 || !punch_through {
+        [mix(a, b, 1, 0), mix(a, b, 0, 1), mix(a, b, 2, 1), mix(a, b, 1, 2)]
+    } else {
+        [mix(a, b, 1, 0), mix(a, b, 0, 1), mix(a, b, 1, 1), [0, 0, 0, 0]]
+    };
+
+    let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+    for (i, texel) in texels.iter_mut().enumerate() {
+        *texel = palette[((indices >> (i * 2)) & 0x3) as usize];
+    }
+}
+
+/// Bloque de un canal de 8 bytes (BC4, alpha de BC3)
+fn decode_channel(block: &[u8], out: &mut [u8; 16]) {
+    let (a0, a1) = (block[0] as u32, block[1] as u32);
+    let mut palette = [0u8; 8];
+    palette[0] = a0 as u8;
+    palette[1] = a1 as u8;
+    if a0 > a1 {
+        for i in 1..7 {
+            palette[i + 1] = (((7 - i) as u32 * a0 + i as u32 * a1) / 7) as u8;
+        }
+    } else {
+        for i in 1..5 {
+            palette[i + 1] = (((5 - i) as u32 * a0 + i as u32 * a1) / 5) as u8;
+        }
+        palette[6] = 0;
+        palette[7] = 255;
+    }
+
+    let mut bits = 0u64;
+    for (i, byte) in block[2..8].iter().enumerate() {
+        bits |= (*byte as u64) << (8 * i);
+    }
+    for (i, value) in out.iter_mut().enumerate() {
+        *value = palette[((bits >> (3 * i)) & 0x7) as usize];
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bc1_solid_red() {
+        // c0 = c1 = rojo puro, todos los índices a 0
+        let block = [0x00, 0xF8, 0x00, 0xF8, 0, 0, 0, 0];
+        let rgba = decompress(BcFormat::Bc1, &block, 4, 4).unwrap();
+        assert_eq!(&rgba[0..4], &[255, 0, 0, 255]);
+        assert_eq!(&rgba[60..64], &[255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_bc4_endpoints() {
+        // texel 0 -> índice 0 (a0 = 200), texel 1 -> índice 1 (a1 = 10)
+        let block = [200, 10, 0b0000_1000, 0, 0, 0, 0, 0];
+        let rgba = decompress(BcFormat::Bc4, &block, 4, 4).unwrap();
+        assert_eq!(rgba[0], 200);
+        assert_eq!(rgba[4], 10);
+    }
+
+    #[test]
+    fn test_truncated() {
+        assert!(decompress(BcFormat::Bc3, &[0u8; 8], 4, 4).is_err());
+    }
+}
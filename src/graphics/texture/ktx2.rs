@@ -0,0 +1,132 @@
+// src/graphics/texture/ktx2.rs
+//
+// Carga de texturas KTX2 con formatos comprimidos de GPU (BC / ETC2).
+// Si el driver no soporta el formato se descomprime en CPU (solo BC1..BC5).
+// Las texturas Basis Universal (BasisLZ / UASTC) necesitan transcodificarse
+// antes, por ejemplo con `toktx` o `basisu` a BC7/ETC2.
+
+use std::io::Read;
+
+use ktx2::{Format, SupercompressionScheme};
+
+use super::bcn::{self, BcFormat};
+use super::{CompressedSupport, LevelFormat, MipLevel};
+
+// S3TC no viene en los bindings core de `gl`
+const COMPRESSED_RGB_S3TC_DXT1: u32 = 0x83F0;
+const COMPRESSED_RGBA_S3TC_DXT1: u32 = 0x83F1;
+const COMPRESSED_RGBA_S3TC_DXT3: u32 = 0x83F2;
+const COMPRESSED_RGBA_S3TC_DXT5: u32 = 0x83F3;
+const COMPRESSED_SRGB_S3TC_DXT1: u32 = 0x8C4C;
+const COMPRESSED_SRGB_ALPHA_S3TC_DXT1: u32 = 0x8C4D;
+const COMPRESSED_SRGB_ALPHA_S3TC_DXT3: u32 = 0x8C4E;
+const COMPRESSED_SRGB_ALPHA_S3TC_DXT5: u32 = 0x8C4F;
+
+/// Cómo se puede subir un formato KTX2
+struct FormatInfo {
+    internal_format: u32,
+    supported: bool,
+    /// Decodificador en CPU si el driver no lo soporta
+    fallback: Option<BcFormat>,
+}
+
+fn format_info(format: Format, support: &CompressedSupport) -> Option<FormatInfo> {
+    let info = |internal_format, supported, fallback| {
+        Some(FormatInfo { internal_format, supported, fallback })
+    };
+    match format {
+        Format::BC1_RGB_UNORM_BLOCK => info(COMPRESSED_RGB_S3TC_DXT1, support.s3tc, Some(BcFormat::Bc1)),
+        Format::BC1_RGB_SRGB_BLOCK => info(COMPRESSED_SRGB_S3TC_DXT1, support.s3tc_srgb, Some(BcFormat::Bc1)),
+        Format::BC1_RGBA_UNORM_BLOCK => info(COMPRESSED_RGBA_S3TC_DXT1, support.s3tc, Some(BcFormat::Bc1)),
+        Format::BC1_RGBA_SRGB_BLOCK => info(COMPRESSED_SRGB_ALPHA_S3TC_DXT1, support.s3tc_srgb, Some(BcFormat::Bc1)),
+        Format::BC2_UNORM_BLOCK => info(COMPRESSED_RGBA_S3TC_DXT3, support.s3tc, Some(BcFormat::Bc2)),
+        Format::BC2_SRGB_BLOCK => info(COMPRESSED_SRGB_ALPHA_S3TC_DXT3, support.s3tc_srgb, Some(BcFormat::Bc2)),
+        Format::BC3_UNORM_BLOCK => info(COMPRESSED_RGBA_S3TC_DXT5, support.s3tc, Some(BcFormat::Bc3)),
+        Format::BC3_SRGB_BLOCK => info(COMPRESSED_SRGB_ALPHA_S3TC_DXT5, support.s3tc_srgb, Some(BcFormat::Bc3)),
+        Format::BC4_UNORM_BLOCK => info(gl::COMPRESSED_RED_RGTC1, support.rgtc, Some(BcFormat::Bc4)),
+        Format::BC5_UNORM_BLOCK => info(gl::COMPRESSED_RG_RGTC2, support.rgtc, Some(BcFormat::Bc5)),
+        Format::BC7_UNORM_BLOCK => info(gl::COMPRESSED_RGBA_BPTC_UNORM, support.bptc, None),
+        Format::BC7_SRGB_BLOCK => info(gl::COMPRESSED_SRGB_ALPHA_BPTC_UNORM, support.bptc, None),
+        Format::ETC2_R8G8B8_UNORM_BLOCK => info(gl::COMPRESSED_RGB8_ETC2, support.etc2, None),
+        Format::ETC2_R8G8B8_SRGB_BLOCK => info(gl::COMPRESSED_SRGB8_ETC2, support.etc2, None),
+        Format::ETC2_R8G8B8A1_UNORM_BLOCK => {
+            info(gl::COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2, support.etc2, None)
+        }
+        Format::ETC2_R8G8B8A1_SRGB_BLOCK => {
+            info(gl::COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2, support.etc2, None)
+        }
+        Format::ETC2_R8G8B8A8_UNORM_BLOCK => info(gl::COMPRESSED_RGBA8_ETC2_EAC, support.etc2, None),
+        Format::ETC2_R8G8B8A8_SRGB_BLOCK => info(gl::COMPRESSED_SRGB8_ALPHA8_ETC2_EAC, support.etc2, None),
+        _ => None,
+    }
+}
+
+/// Decodifica un archivo KTX2 2D en su cadena de mips
+pub(super) fn decode(bytes: &[u8], support: &CompressedSupport) -> Result<Vec<MipLevel>, String> {
+    let reader = ktx2::Reader::new(bytes).map_err(|e| format!("KTX2 inválido: {:?}", e))?;
+    let header = reader.header();
+
+    if header.supercompression_scheme == Some(SupercompressionScheme::BasisLZ) || header.format.is_none() {
+        return Err("KTX2 con Basis Universal: transcodificar a BC7/ETC2 antes de cargar".to_string());
+    }
+    if header.face_count != 1 || header.layer_count > 1 || header.pixel_depth > 1 {
+        return Err("Solo se soportan texturas KTX2 2D (sin caras, capas ni profundidad)".to_string());
+    }
+
+    let format = header.format.unwrap();
+    let is_rgba8 = format == Format::R8G8B8A8_UNORM || format == Format::R8G8B8A8_SRGB;
+    let info = format_info(format, support);
+    if info.is_none() && !is_rgba8 {
+        return Err(format!("Formato KTX2 no soportado: {:?}", format));
+    }
+
+    let mut levels = Vec::new();
+    for (i, data) in reader.levels().enumerate() {
+        let data = match header.supercompression_scheme {
+            None => data.to_vec(),
+            Some(SupercompressionScheme::Zstandard) => {
+                let mut decoder = ruzstd::StreamingDecoder::new(data)
+                    .map_err(|e| format!("Zstd inválido en KTX2: {:?}", e))?;
+                let mut out = Vec::new();
+                decoder
+                    .read_to_end(&mut out)
+                    .map_err(|e| format!("Zstd inválido en KTX2: {}", e))?;
+                out
+            }
+            Some(scheme) => return Err(format!("Supercompresión KTX2 no soportada: {:?}", scheme)),
+        };
+
+        let width = (header.pixel_width >> i).max(1);
+        let height = (header.pixel_height >> i).max(1);
+
+        let level = match &info {
+            None => MipLevel { level: i as i32, width, height, format: LevelFormat::Rgba8, pixels: data },
+            Some(info) if info.supported => MipLevel {
+                level: i as i32,
+                width,
+                height,
+                format: LevelFormat::Compressed(info.internal_format),
+                pixels: data,
+            },
+            Some(FormatInfo { fallback: Some(bc), .. }) => MipLevel {
+                level: i as i32,
+                width,
+                height,
+                format: LevelFormat::Rgba8,
+                pixels: bcn::decompress(*bc, &data, width, height)?,
+            },
+            Some(_) => {
+                return Err(format!(
+                    "El driver no soporta {:?} y no hay descompresión en CPU para ese formato",
+                    format
+                ))
+            }
+        };
+        levels.push(level);
+    }
+
+    if levels.is_empty() {
+        return Err("KTX2 sin niveles de mip".to_string());
+    }
+    Ok(levels)
+}
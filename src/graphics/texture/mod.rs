@@ -0,0 +1,380 @@
+// src/graphics/texture/mod.rs
+//
+// Caché de texturas por ruta con conteo de referencias.
+// Las imágenes se decodifican en un hilo aparte (incluyendo la cadena de mips)
+// y se suben a la GPU poco a poco desde el mip más pequeño, con un presupuesto
+// de bytes por frame, para no trabar el render cuando aparece un asset nuevo.
+// Los .ktx2 se suben comprimidos si el driver soporta el formato.
+
+pub mod bcn;
+#[cfg(feature = "ktx2")]
+mod ktx2;
+
+use std::collections::{HashMap, HashSet};
+use std::ffi::CStr;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+
+#[cfg(feature = "image-export")]
+use image::imageops::FilterType;
+
+use crate::graphics::gpu_resources::ContextGeneration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LevelFormat {
+    #[cfg_attr(not(feature = "image-export"), allow(dead_code))]
+    Rgba8,
+    /// Bloques comprimidos listos para glCompressedTexImage2D (formato interno GL)
+    #[cfg_attr(not(feature = "ktx2"), allow(dead_code))]
+    Compressed(u32),
+}
+
+/// Un nivel de mip ya decodificado
+struct MipLevel {
+    level: i32,
+    width: u32,
+    height: u32,
+    format: LevelFormat,
+    pixels: Vec<u8>,
+}
+
+/// Familias de compresión que soporta el driver actual
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CompressedSupport {
+    pub s3tc: bool,
+    pub s3tc_srgb: bool,
+    pub rgtc: bool,
+    pub bptc: bool,
+    pub etc2: bool,
+}
+
+impl CompressedSupport {
+    /// Consulta las extensiones del contexto GL actual
+    pub fn query() -> Self {
+        let mut extensions = HashSet::new();
+        let (mut major, mut minor) = (0, 0);
+        unsafe {
+            gl::GetIntegerv(gl::MAJOR_VERSION, &mut major);
+            gl::GetIntegerv(gl::MINOR_VERSION, &mut minor);
+            let mut count = 0;
+            gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut count);
+            for i in 0..count {
+                let name = gl::GetStringi(gl::EXTENSIONS, i as u32);
+                if !name.is_null() {
+                    let name = CStr::from_ptr(name as *const _).to_string_lossy().into_owned();
+                    extensions.insert(name);
+                }
+            }
+        }
+        let version = (major, minor);
+        let s3tc = extensions.contains("GL_EXT_texture_compression_s3tc");
+        Self {
+            s3tc,
+            s3tc_srgb: s3tc && extensions.contains("GL_EXT_texture_sRGB"),
+            rgtc: version >= (3, 0) || extensions.contains("GL_ARB_texture_compression_rgtc"),
+            bptc: version >= (4, 2) || extensions.contains("GL_ARB_texture_compression_bptc"),
+            etc2: version >= (4, 3) || extensions.contains("GL_ARB_ES3_compatibility"),
+        }
+    }
+}
+
+struct Decoded {
+    path: PathBuf,
+    result: Result<Vec<MipLevel>, String>,
+}
+
+struct CacheEntry {
+    texture: u32,
+    refs: usize,
+    /// Niveles que faltan subir, ordenados del más pequeño al más grande
+    pending: Vec<MipLevel>,
+    loaded: bool,
+}
+
+pub struct TextureCache {
+    entries: HashMap<PathBuf, CacheEntry>,
+    sender: Sender<Decoded>,
+    receiver: Receiver<Decoded>,
+    /// Las texturas de color se guardan como sRGB para que el muestreo devuelva lineal
+    pub srgb: bool,
+    /// Bytes máximos que se suben por llamada a `update` (siempre al menos un nivel)
+    pub upload_budget: usize,
+    support: CompressedSupport,
+    generation: ContextGeneration,
+}
+
+impl TextureCache {
+    /// Requiere un contexto GL activo (consulta los formatos comprimidos soportados)
+    pub fn new(srgb: bool) -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            entries: HashMap::new(),
+            sender,
+            receiver,
+            srgb,
+            upload_budget: 4 * 1024 * 1024,
+            support: CompressedSupport::query(),
+            generation: ContextGeneration::current(),
+        }
+    }
+
+    /// Devuelve el id GL de la textura de `path`, sumando una referencia.
+    /// La primera vez lanza la decodificación en segundo plano y devuelve una
+    /// textura gris de 1x1 que se rellena cuando llega la imagen.
+    pub fn acquire(&mut self, path: impl AsRef<Path>) -> u32 {
+        let path = path.as_ref().to_path_buf();
+        if let Some(entry) = self.entries.get_mut(&path) {
+            entry.refs += 1;
+            return entry.texture;
+        }
+
+        let texture = create_placeholder(self.internal_format());
+        self.entries.insert(
+            path.clone(),
+            CacheEntry {
+                texture,
+                refs: 1,
+                pending: Vec::new(),
+                loaded: false,
+            },
+        );
+
+        spawn_decode(path, self.sender.clone(), self.support);
+        texture
+    }
+
+    /// Vuelve a crear todas las texturas en un contexto GL nuevo (ver
+    /// `graphics::gpu_resources`): cada una vuelve a ser el gris de 1x1 hasta
+    /// que se decodifica otra vez. Los ids cambian; hay que pedirlos de nuevo.
+    pub fn rebuild_gpu(&mut self) {
+        self.generation = ContextGeneration::current();
+        self.support = CompressedSupport::query();
+        let internal_format = self.internal_format();
+        for (path, entry) in &mut self.entries {
+            entry.texture = create_placeholder(internal_format);
+            entry.pending.clear();
+            entry.loaded = false;
+            spawn_decode(path.clone(), self.sender.clone(), self.support);
+        }
+    }
+
+    /// Quita una referencia; al llegar a cero se libera la textura
+    pub fn release(&mut self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let remove = match self.entries.get_mut(path) {
+            Some(entry) => {
+                entry.refs = entry.refs.saturating_sub(1);
+                entry.refs == 0
+            }
+            None => false,
+        };
+        if remove {
+            if let Some(entry) = self.entries.remove(path) {
+                unsafe {
+                    gl::DeleteTextures(1, &entry.texture);
+                }
+            }
+        }
+    }
+
+    /// Rutas de todas las texturas pedidas (cargadas o no)
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.entries.keys().map(PathBuf::as_path)
+    }
+
+    pub fn get(&self, path: impl AsRef<Path>) -> Option<u32> {
+        self.entries.get(path.as_ref()).map(|e| e.texture)
+    }
+
+    /// true cuando la textura tiene todos sus mips en la GPU
+    pub fn is_loaded(&self, path: impl AsRef<Path>) -> bool {
+        self.entries
+            .get(path.as_ref())
+            .map(|e| e.loaded && e.pending.is_empty())
+            .unwrap_or(false)
+    }
+
+    /// Recoge las imágenes decodificadas y sube mips pendientes. Llamar una vez por frame.
+    pub fn update(&mut self) {
+        while let Ok(decoded) = self.receiver.try_recv() {
+            let internal_format = self.internal_format();
+            let Some(entry) = self.entries.get_mut(&decoded.path) else {
+                // Se liberó antes de terminar de decodificar
+                continue;
+            };
+            match decoded.result {
+                Ok(mut levels) => {
+                    allocate_levels(entry.texture, internal_format, &levels);
+                    // Del más pequeño al más grande
+                    levels.sort_by_key(|l| std::cmp::Reverse(l.level));
+                    entry.pending = levels;
+                    entry.loaded = true;
+                }
+                Err(e) => eprintln!("No se pudo cargar la textura {}: {}", decoded.path.display(), e),
+            }
+        }
+
+        let mut budget = self.upload_budget;
+        for entry in self.entries.values_mut() {
+            while let Some(level) = entry.pending.first() {
+                if budget < level.pixels.len() && budget != self.upload_budget {
+                    return;
+                }
+                budget = budget.saturating_sub(level.pixels.len());
+                let level = entry.pending.remove(0);
+                upload_level(entry.texture, &level);
+            }
+        }
+    }
+
+    pub fn compressed_support(&self) -> CompressedSupport {
+        self.support
+    }
+
+    fn internal_format(&self) -> i32 {
+        if self.srgb {
+            gl::SRGB8_ALPHA8 as i32
+        } else {
+            gl::RGBA8 as i32
+        }
+    }
+}
+
+impl Drop for TextureCache {
+    fn drop(&mut self) {
+        if !self.generation.is_current() {
+            return;
+        }
+        for entry in self.entries.values() {
+            unsafe {
+                gl::DeleteTextures(1, &entry.texture);
+            }
+        }
+    }
+}
+
+/// Decodifica en otro hilo y manda el resultado a la caché
+fn spawn_decode(path: PathBuf, sender: Sender<Decoded>, support: CompressedSupport) {
+    thread::spawn(move || {
+        let result = decode_with_mips(&path, &support);
+        // Si la caché ya no existe no hay nada que hacer
+        let _ = sender.send(Decoded { path, result });
+    });
+}
+
+fn decode_with_mips(path: &Path, support: &CompressedSupport) -> Result<Vec<MipLevel>, String> {
+    let is_ktx2 = path
+        .extension()
+        .map(|e| e.eq_ignore_ascii_case("ktx2"))
+        .unwrap_or(false);
+    if is_ktx2 {
+        #[cfg(feature = "ktx2")]
+        {
+            let bytes = std::fs::read(path).map_err(|e| format!("{}", e))?;
+            return ktx2::decode(&bytes, support);
+        }
+        #[cfg(not(feature = "ktx2"))]
+        {
+            let _ = support;
+            return Err("compilado sin la feature ktx2".to_string());
+        }
+    }
+
+    #[cfg(feature = "image-export")]
+    {
+        let mut image = image::open(path)
+            .map_err(|e| format!("{}", e))?
+            .to_rgba8();
+
+        let mut levels = Vec::new();
+        let mut level = 0;
+        loop {
+            let (width, height) = image.dimensions();
+            levels.push(MipLevel {
+                level,
+                width,
+                height,
+                format: LevelFormat::Rgba8,
+                pixels: image.as_raw().clone(),
+            });
+            if width == 1 && height == 1 {
+                break;
+            }
+            image = image::imageops::resize(&image, (width / 2).max(1), (height / 2).max(1), FilterType::Triangle);
+            level += 1;
+        }
+        Ok(levels)
+    }
+    #[cfg(not(feature = "image-export"))]
+    Err("compilado sin la feature image-export".to_string())
+}
+
+fn create_placeholder(internal_format: i32) -> u32 {
+    let mut texture = 0;
+    let gray: [u8; 4] = [128, 128, 128, 255];
+    unsafe {
+        gl::GenTextures(1, &mut texture);
+        gl::BindTexture(gl::TEXTURE_2D, texture);
+        gl::TexImage2D(
+            gl::TEXTURE_2D, 0, internal_format, 1, 1, 0,
+            gl::RGBA, gl::UNSIGNED_BYTE, gray.as_ptr() as *const _,
+        );
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        gl::BindTexture(gl::TEXTURE_2D, 0);
+    }
+    texture
+}
+
+/// Reserva todos los niveles (sin datos) y deja visible solo el más pequeño
+/// hasta que se suba algo.
+fn allocate_levels(texture: u32, internal_format: i32, levels: &[MipLevel]) {
+    let max_level = levels.len() as i32 - 1;
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_2D, texture);
+        for level in levels {
+            match level.format {
+                LevelFormat::Rgba8 => gl::TexImage2D(
+                    gl::TEXTURE_2D, level.level, internal_format,
+                    level.width as i32, level.height as i32, 0,
+                    gl::RGBA, gl::UNSIGNED_BYTE, ptr::null(),
+                ),
+                LevelFormat::Compressed(format) => gl::CompressedTexImage2D(
+                    gl::TEXTURE_2D, level.level, format,
+                    level.width as i32, level.height as i32, 0,
+                    level.pixels.len() as i32, ptr::null(),
+                ),
+            }
+        }
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_BASE_LEVEL, max_level);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAX_LEVEL, max_level);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR_MIPMAP_LINEAR as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
+        gl::BindTexture(gl::TEXTURE_2D, 0);
+    }
+}
+
+/// Sube un nivel y baja TEXTURE_BASE_LEVEL para que pase a usarse
+fn upload_level(texture: u32, level: &MipLevel) {
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_2D, texture);
+        gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+        match level.format {
+            LevelFormat::Rgba8 => gl::TexSubImage2D(
+                gl::TEXTURE_2D, level.level, 0, 0,
+                level.width as i32, level.height as i32,
+                gl::RGBA, gl::UNSIGNED_BYTE, level.pixels.as_ptr() as *const _,
+            ),
+            LevelFormat::Compressed(format) => gl::CompressedTexSubImage2D(
+                gl::TEXTURE_2D, level.level, 0, 0,
+                level.width as i32, level.height as i32,
+                format, level.pixels.len() as i32, level.pixels.as_ptr() as *const _,
+            ),
+        }
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_BASE_LEVEL, level.level);
+        gl::BindTexture(gl::TEXTURE_2D, 0);
+    }
+}
@@ -0,0 +1,141 @@
+// src/graphics/thickness.rs
+//
+// Espesor de pared por vértice: desde cada vértice se lanzan rayos hacia
+// adentro (contra la normal, en un cono chico) y el espesor es la mediana de
+// las distancias a la pared opuesta, buscada con el BVH de la malla. Las zonas
+// por debajo del umbral se pintan de rojo; es la causa más común de piezas
+// impresas que fallan.
+
+use crate::graphics::compare::heatmap_color;
+use crate::graphics::mesh::Mesh;
+use crate::graphics::mesh_ops::{transformed, vertex_normals};
+use crate::graphics::scene_object::SceneObject;
+use crate::math::{ray::Ray, vec3::Vec3};
+
+#[derive(Debug, Clone, Copy)]
+pub struct ThicknessSettings {
+    /// Espesor mínimo aceptable, en unidades del mundo
+    pub threshold: f32,
+    /// Rayos por vértice (el primero sobre la normal, el resto en el cono)
+    pub rays: u32,
+    /// Apertura del cono de rayos, en grados desde la normal
+    pub cone_degrees: f32,
+}
+
+impl Default for ThicknessSettings {
+    fn default() -> Self {
+        Self {
+            threshold: 1.0,
+            rays: 5,
+            cone_degrees: 15.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ThicknessReport {
+    /// Espesor por vértice (None si ningún rayo encontró la pared opuesta)
+    pub thickness: Vec<Option<f32>>,
+    /// Vértices por debajo del umbral
+    pub thin_vertices: usize,
+    pub min: Option<f32>,
+}
+
+/// Analiza una malla ya en espacio mundo, con las normales hacia afuera
+pub fn analyze_mesh(mesh: &Mesh, settings: &ThicknessSettings) -> ThicknessReport {
+    let bvh = mesh.bvh();
+    // Separación del origen de los rayos para no chocar con las caras del propio vértice
+    let epsilon = bvh.bounds().size().magnitude() * 1e-5;
+    let normals = vertex_normals(mesh);
+    let cone = settings.cone_degrees.to_radians();
+
+    let thickness: Vec<Option<f32>> = normals
+        .iter()
+        .enumerate()
+        .map(|(vertex, normal)| {
+            let inward = Vec3::from(*normal) * -1.0;
+            if inward.magnitude() < 0.5 {
+                return None;
+            }
+            let origin = mesh.position(vertex as u32) + inward * epsilon;
+            let mut hits: Vec<f32> = cone_directions(inward, cone, settings.rays.max(1))
+                .into_iter()
+                .filter_map(|direction| bvh.raycast(mesh, &Ray::new(origin, direction), f32::INFINITY))
+                .map(|(t, _)| t + epsilon)
+                .collect();
+            if hits.is_empty() {
+                return None;
+            }
+            hits.sort_by(f32::total_cmp);
+            Some(hits[hits.len() / 2])
+        })
+        .collect();
+
+    let thin_vertices = thickness.iter().flatten().filter(|&&t| t < settings.threshold).count();
+    let min = thickness.iter().flatten().copied().reduce(f32::min);
+    ThicknessReport { thickness, thin_vertices, min }
+}
+
+/// Analiza un objeto con su transformación actual
+pub fn analyze(obj: &SceneObject, global_scale: f32, settings: &ThicknessSettings) -> ThicknessReport {
+    analyze_mesh(&transformed(&obj.mesh, &obj.model_matrix(global_scale)), settings)
+}
+
+/// `count` direcciones unitarias: `axis` y las demás repartidas en un cono de `angle`
+fn cone_directions(axis: Vec3, angle: f32, count: u32) -> Vec<Vec3> {
+    let helper = if axis.x.abs() < 0.9 { Vec3::UNIT_X } else { Vec3::UNIT_Y };
+    let u = (helper - axis * helper.dot(&axis)).normalize();
+    let v = axis.cross(&u);
+    let mut directions = vec![axis];
+    for i in 1..count {
+        let phi = (i - 1) as f32 / (count - 1) as f32 * std::f32::consts::TAU;
+        let direction = axis * angle.cos() + (u * phi.cos() + v * phi.sin()) * angle.sin();
+        directions.push(direction.normalize());
+    }
+    directions
+}
+
+/// Colores por vértice: rojo debajo del umbral, verde en el umbral y azul a
+/// partir del doble. Gris donde no se pudo medir.
+pub fn thickness_colors(report: &ThicknessReport, settings: &ThicknessSettings) -> Vec<[f32; 3]> {
+    report
+        .thickness
+        .iter()
+        .map(|&thickness| match thickness {
+            Some(t) if t < settings.threshold => [1.0, 0.0, 0.0],
+            Some(t) => heatmap_color(Some(settings.threshold - t), settings.threshold),
+            None => heatmap_color(None, 1.0),
+        })
+        .collect()
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Placa cerrada de 4 x 4 y espesor `height` (y entre 0 y height)
+    fn plate(height: f32) -> Mesh {
+        let (min, max) = ([0.0, 0.0, 0.0], [4.0, height, 4.0]);
+        let positions = (0..8)
+            .map(|i| [0, 1, 2].map(|axis| if i & (1 << axis) != 0 { max[axis] } else { min[axis] }))
+            .collect();
+        let indices = vec![
+            0, 2, 1, 1, 2, 3, 4, 5, 6, 5, 7, 6, 0, 1, 4, 1, 5, 4,
+            2, 6, 3, 3, 6, 7, 0, 4, 2, 2, 4, 6, 1, 3, 5, 3, 7, 5,
+        ];
+        Mesh::new(positions, indices)
+    }
+
+    #[test]
+    fn test_thin_plate_is_flagged() {
+        let settings = ThicknessSettings { threshold: 0.5, ..Default::default() };
+        let thin = analyze_mesh(&plate(0.2), &settings);
+        assert_eq!(thin.thin_vertices, 8);
+        assert!(thin.min.unwrap() < 0.5);
+        assert_eq!(thickness_colors(&thin, &settings)[0], [1.0, 0.0, 0.0]);
+
+        let thick = analyze_mesh(&plate(2.0), &settings);
+        assert_eq!(thick.thin_vertices, 0);
+    }
+}
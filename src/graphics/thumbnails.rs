@@ -0,0 +1,174 @@
+// src/graphics/thumbnails.rs
+//
+// Miniaturas estandarizadas de una carpeta de modelos, para gestores de
+// archivos y PDM con miles de STL:
+//
+//   rust_engine thumbnails piezas/ miniaturas/ [--size 256] [--transparent]
+//
+// Todas salen iguales: vista isométrica encuadrada al modelo, iluminación de
+// estudio y fondo gris claro (o transparente). Cada archivo se carga, se
+// renderiza y se suelta antes del siguiente, así la memoria no crece con la
+// cantidad, y uno que no se puede leer se informa sin cortar el resto.
+// Un archivo con varios sólidos (STEP) da una sola miniatura con todos.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::graphics::camara::{Camera, CameraPose, View};
+use crate::graphics::dataset::framing;
+use crate::graphics::lighting::LightingRig;
+use crate::graphics::render::{Renderer, FOV_Y_DEGREES};
+use crate::graphics::scene::{load_model_file, model_files};
+use crate::graphics::scene_object::SceneObject;
+use crate::math::{aabb::Aabb, vec3::Vec3};
+
+/// Fondo de las miniaturas opacas (sRGB)
+pub const THUMBNAIL_BACKGROUND: [f32; 4] = [0.93, 0.93, 0.93, 1.0];
+
+/// Margen alrededor del modelo (1 = la esfera que lo contiene toca los bordes)
+const THUMBNAIL_MARGIN: f32 = 1.05;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThumbnailSettings {
+    /// Lado de la imagen en píxeles
+    pub size: u32,
+    /// PNG con alfa 0 en el fondo en vez de `THUMBNAIL_BACKGROUND`
+    pub transparent: bool,
+}
+
+impl Default for ThumbnailSettings {
+    fn default() -> Self {
+        Self { size: 256, transparent: false }
+    }
+}
+
+impl ThumbnailSettings {
+    /// Lee `--size N` y `--transparent` (los argumentos que siguen a las carpetas)
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut settings = Self::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--transparent" => settings.transparent = true,
+                "--size" => {
+                    let value = args.next().ok_or("Falta el valor de --size")?;
+                    settings.size = value
+                        .parse()
+                        .ok()
+                        .filter(|&size| size > 0)
+                        .ok_or_else(|| format!("Tamaño de miniatura inválido: {}", value))?;
+                }
+                other => return Err(format!("Opción desconocida: {}", other)),
+            }
+        }
+        Ok(settings)
+    }
+}
+
+/// Resultado de una carpeta
+#[derive(Debug, Default)]
+pub struct ThumbnailReport {
+    /// Imágenes guardadas
+    pub rendered: Vec<PathBuf>,
+    /// Modelos que no se pudieron leer o renderizar, con el motivo
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// Pose isométrica (desde +X, +Y, +Z) que encuadra `bounds`
+pub fn isometric_pose(bounds: &Aabb) -> CameraPose {
+    let (center, distance) = framing(bounds, FOV_Y_DEGREES.to_radians(), THUMBNAIL_MARGIN);
+    CameraPose::orbiting(center, View::Iso.direction(), distance, 0.0)
+}
+
+/// Imagen de salida de un modelo: mismo nombre, extensión `.png`
+pub fn thumbnail_path(model: &Path, output: &Path) -> PathBuf {
+    let stem = model.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    output.join(format!("{}.png", stem))
+}
+
+/// Miniatura de `objects` (todos juntos) en `path`. Usa el fondo y la luz
+/// que tenga el renderer (ver `prepare`).
+pub fn render_thumbnail(
+    renderer: &mut Renderer,
+    objects: &[SceneObject],
+    settings: &ThumbnailSettings,
+    path: &str,
+) -> Result<(), String> {
+    let bounds = objects.iter().fold(Aabb::EMPTY, |bounds, obj| bounds.union(&obj.world_bounds(1.0)));
+    let pose = isometric_pose(&bounds);
+    let mut camera = Camera::new(Vec3::ZERO);
+    camera.position = pose.position;
+    camera.yaw = pose.yaw;
+    camera.pitch = pose.pitch;
+    let size = (settings.size as i32, settings.size as i32);
+    renderer.capture(objects, &camera, 1.0, size, path)
+}
+
+/// Deja el renderer con la luz y el fondo de las miniaturas
+pub fn prepare(renderer: &mut Renderer, settings: &ThumbnailSettings) {
+    renderer.lighting = LightingRig::Studio.lighting();
+    renderer.set_clear_color(THUMBNAIL_BACKGROUND);
+    renderer.set_transparent_background(settings.transparent);
+}
+
+/// Una miniatura por cada modelo de la carpeta `input` en la carpeta `output`
+pub fn generate(
+    renderer: &mut Renderer,
+    input: &str,
+    output: &str,
+    settings: &ThumbnailSettings,
+) -> Result<ThumbnailReport, String> {
+    let files = model_files(input)?;
+    fs::create_dir_all(output).map_err(|e| format!("No se pudo crear {}: {}", output, e))?;
+    prepare(renderer, settings);
+
+    let mut report = ThumbnailReport::default();
+    for (index, file) in files.iter().enumerate() {
+        let path = thumbnail_path(file, Path::new(output));
+        let result = load_model_file(&file.to_string_lossy())
+            .and_then(|objects| render_thumbnail(renderer, &objects, settings, &path.to_string_lossy()));
+        match result {
+            Ok(()) => report.rendered.push(path),
+            Err(e) => {
+                eprintln!("  {}: {}", file.display(), e);
+                report.failed.push((file.clone(), e));
+            }
+        }
+        if (index + 1) % 100 == 0 {
+            println!("  {}/{} modelos", index + 1, files.len());
+        }
+    }
+    Ok(report)
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arguments_and_framing() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(ThumbnailSettings::from_args(&[]).unwrap(), ThumbnailSettings::default());
+        let settings = ThumbnailSettings::from_args(&args(&["--size", "512", "--transparent"])).unwrap();
+        assert_eq!(settings, ThumbnailSettings { size: 512, transparent: true });
+        assert!(ThumbnailSettings::from_args(&args(&["--size", "0"])).is_err());
+        assert!(ThumbnailSettings::from_args(&args(&["--size"])).is_err());
+        assert!(ThumbnailSettings::from_args(&args(&["--fondo"])).is_err());
+
+        assert_eq!(
+            thumbnail_path(Path::new("piezas/brida.v2.stl"), Path::new("miniaturas")),
+            Path::new("miniaturas/brida.v2.png")
+        );
+
+        // La cámara queda sobre la diagonal (+X, +Y, +Z) mirando al centro
+        let bounds = Aabb::new(Vec3::new(0.0, 0.0, 0.0), Vec3::new(2.0, 2.0, 2.0));
+        let pose = isometric_pose(&bounds);
+        let offset = pose.position - bounds.center();
+        assert!(offset.x > 0.0 && (offset.x - offset.y).abs() < 1e-3 && (offset.x - offset.z).abs() < 1e-3);
+        let mut camera = Camera::new(Vec3::ZERO);
+        camera.yaw = pose.yaw;
+        camera.pitch = pose.pitch;
+        assert!((camera.get_forward_vector().dot(&offset.normalize()) + 1.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,267 @@
+// src/graphics/topology.rs
+//
+// Adyacencia de una malla armada a partir del buffer de índices: qué caras
+// tocan cada vértice, qué caras comparten cada arista y qué piezas sueltas hay.
+// La usan el chequeo de bordes (`mesh_ops::edge_report`), la separación de
+// sólidos de un STEP y la inspección de sub-objetos, en vez de que cada uno
+// arme su propio mapa de aristas.
+//
+// No es una half-edge: los STL reales traen aristas de tres o más caras y
+// caras con la orientación invertida, que una half-edge no puede representar.
+// Las listas se guardan compactas (un arreglo de desplazamientos y uno de
+// valores) para que alcance con pocas asignaciones en mallas de millones de
+// triángulos. Es por índice de vértice: en un STL sin soldar cada triángulo
+// queda aislado (ver `Weld`). `Mesh::topology` la arma la primera vez que se
+// pide y la guarda hasta `Mesh::invalidate_bvh`.
+
+use std::collections::HashMap;
+
+use crate::graphics::mesh::Mesh;
+use crate::graphics::picking::SubObjectElement;
+
+/// Lado de un triángulo que no es arista (dos esquinas en el mismo vértice)
+const NO_EDGE: u32 = u32::MAX;
+
+/// Listas de largo variable, una detrás de otra
+#[derive(Debug, Clone, Default)]
+struct Lists {
+    /// Comienzo de la lista `i` en `values`; la última termina en `values.len()`
+    offsets: Vec<u32>,
+    values: Vec<u32>,
+}
+
+impl Lists {
+    /// `count` listas con los valores de cada par (lista, valor), en orden
+    fn build(count: usize, pairs: &[(u32, u32)]) -> Self {
+        let mut offsets = vec![0u32; count + 1];
+        for &(list, _) in pairs {
+            offsets[list as usize + 1] += 1;
+        }
+        for i in 0..count {
+            offsets[i + 1] += offsets[i];
+        }
+        let mut next = offsets.clone();
+        let mut values = vec![0u32; pairs.len()];
+        for &(list, value) in pairs {
+            values[next[list as usize] as usize] = value;
+            next[list as usize] += 1;
+        }
+        offsets.pop();
+        Self { offsets, values }
+    }
+
+    fn get(&self, list: usize) -> &[u32] {
+        let Some(&start) = self.offsets.get(list) else { return &[] };
+        let end = self.offsets.get(list + 1).map_or(self.values.len(), |&end| end as usize);
+        &self.values[start as usize..end]
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MeshTopology {
+    /// Aristas sin orientación, con el vértice menor primero
+    edges: Vec<[u32; 2]>,
+    edge_index: HashMap<[u32; 2], u32>,
+    /// Aristas (a, b), (b, c) y (c, a) de cada triángulo
+    face_edges: Vec<[u32; 3]>,
+    edge_faces: Lists,
+    vertex_faces: Lists,
+}
+
+impl MeshTopology {
+    /// Triángulos con índices fuera de `positions` se ignoran
+    pub fn build(mesh: &Mesh) -> Self {
+        let vertex_count = mesh.positions.len();
+        let mut topology = Self::default();
+        let mut edge_pairs = Vec::new();
+        let mut vertex_pairs = Vec::new();
+        for face in 0..mesh.triangle_count() {
+            let mut sides = [NO_EDGE; 3];
+            let corners = mesh.triangle(face).filter(|corners| corners.iter().all(|&v| (v as usize) < vertex_count));
+            if let Some([a, b, c]) = corners {
+                for (side, (u, v)) in [(a, b), (b, c), (c, a)].into_iter().enumerate() {
+                    let key = [u.min(v), u.max(v)];
+                    // En un triángulo degenerado dos lados son la misma arista
+                    if u == v || sides.iter().any(|&edge| edge != NO_EDGE && topology.edges[edge as usize] == key) {
+                        continue;
+                    }
+                    let edge = *topology.edge_index.entry(key).or_insert_with(|| {
+                        topology.edges.push(key);
+                        topology.edges.len() as u32 - 1
+                    });
+                    sides[side] = edge;
+                    edge_pairs.push((edge, face as u32));
+                }
+                // Cada vértice distinto una sola vez
+                for (i, v) in [a, b, c].into_iter().enumerate() {
+                    if ![a, b, c][..i].contains(&v) {
+                        vertex_pairs.push((v, face as u32));
+                    }
+                }
+            }
+            topology.face_edges.push(sides);
+        }
+        topology.edge_faces = Lists::build(topology.edges.len(), &edge_pairs);
+        topology.vertex_faces = Lists::build(vertex_count, &vertex_pairs);
+        topology
+    }
+
+    /// Triángulos que usan el vértice, en orden
+    pub fn vertex_faces(&self, vertex: u32) -> &[u32] {
+        self.vertex_faces.get(vertex as usize)
+    }
+
+    /// Vértices unidos a `vertex` por una arista, en orden
+    pub fn vertex_neighbors(&self, vertex: u32) -> Vec<u32> {
+        let mut neighbors: Vec<u32> = self
+            .vertex_faces(vertex)
+            .iter()
+            .flat_map(|&face| self.face_edges[face as usize])
+            .filter(|&edge| edge != NO_EDGE)
+            .map(|edge| self.edges[edge as usize])
+            .filter_map(|[a, b]| if a == vertex { Some(b) } else if b == vertex { Some(a) } else { None })
+            .collect();
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        neighbors
+    }
+
+    /// Índice de la arista entre `a` y `b` (en cualquier orden)
+    pub fn find_edge(&self, a: u32, b: u32) -> Option<usize> {
+        self.edge_index.get(&[a.min(b), a.max(b)]).map(|&edge| edge as usize)
+    }
+
+    /// Triángulos que comparten la arista: uno en un borde abierto, dos en
+    /// una superficie cerrada y más en una arista no-manifold
+    pub fn edge_faces(&self, edge: usize) -> &[u32] {
+        self.edge_faces.get(edge)
+    }
+
+    /// Triángulos que comparten una arista con `face`, en orden
+    pub fn face_neighbors(&self, face: usize) -> Vec<u32> {
+        let Some(sides) = self.face_edges.get(face) else { return Vec::new() };
+        let mut neighbors: Vec<u32> = sides
+            .iter()
+            .filter(|&&edge| edge != NO_EDGE)
+            .flat_map(|&edge| self.edge_faces(edge as usize))
+            .copied()
+            .filter(|&other| other as usize != face)
+            .collect();
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        neighbors
+    }
+
+    /// Aristas de un solo triángulo (agujeros)
+    pub fn open_edges(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.edges.len()).filter(|&edge| self.edge_faces(edge).len() == 1)
+    }
+
+    /// Aristas de tres o más triángulos
+    pub fn non_manifold_edges(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.edges.len()).filter(|&edge| self.edge_faces(edge).len() > 2)
+    }
+
+    /// Triángulos de cada pieza suelta (unidos por al menos un vértice), en
+    /// orden dentro de cada pieza y las piezas por su primer triángulo
+    pub fn connected_components(&self) -> Vec<Vec<u32>> {
+        let mut visited = vec![false; self.face_edges.len()];
+        let mut components = Vec::new();
+        let mut stack = Vec::new();
+        for start in 0..self.face_edges.len() {
+            if visited[start] {
+                continue;
+            }
+            visited[start] = true;
+            stack.push(start as u32);
+            let mut component = Vec::new();
+            while let Some(face) = stack.pop() {
+                component.push(face);
+                let vertices = self.face_edges[face as usize]
+                    .iter()
+                    .filter(|&&edge| edge != NO_EDGE)
+                    .flat_map(|&edge| self.edges[edge as usize]);
+                for vertex in vertices {
+                    for &other in self.vertex_faces(vertex) {
+                        if !visited[other as usize] {
+                            visited[other as usize] = true;
+                            stack.push(other);
+                        }
+                    }
+                }
+            }
+            component.sort_unstable();
+            components.push(component);
+        }
+        components
+    }
+
+    /// Vecinos del elemento elegido con el picking de sub-objetos, en una línea
+    pub fn summary(&self, element: SubObjectElement) -> String {
+        match element {
+            SubObjectElement::Vertex(vertex) => format!(
+                "{} caras, {} vértices vecinos",
+                self.vertex_faces(vertex).len(),
+                self.vertex_neighbors(vertex).len()
+            ),
+            SubObjectElement::Edge(a, b) => match self.find_edge(a, b).map(|edge| self.edge_faces(edge).len()) {
+                Some(1) => "borde abierto".to_string(),
+                Some(2) => "arista entre 2 caras".to_string(),
+                Some(faces) => format!("arista no-manifold ({} caras)", faces),
+                None => "no es una arista de la malla".to_string(),
+            },
+            SubObjectElement::Face(face) => {
+                let components = self.connected_components();
+                let piece = components.iter().position(|component| component.binary_search(&(face as u32)).is_ok());
+                format!(
+                    "{} caras vecinas, pieza {} de {}",
+                    self.face_neighbors(face).len(),
+                    piece.map_or(0, |piece| piece + 1),
+                    components.len()
+                )
+            }
+        }
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjacency_queries() {
+        // Cuadrado de dos triángulos (0, 1) y un triángulo suelto (2), más
+        // uno degenerado (3) y una aleta sobre la diagonal (4)
+        let positions = vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [5.0, 0.0, 0.0],
+            [6.0, 0.0, 0.0],
+            [5.0, 1.0, 0.0],
+            [0.5, 0.5, 1.0],
+        ];
+        let indices = vec![0, 1, 2, 0, 2, 3, 4, 5, 6, 4, 4, 5, 0, 2, 7];
+        let topology = MeshTopology::build(&Mesh::new(positions, indices));
+
+        assert_eq!(topology.vertex_faces(0), &[0, 1, 4]);
+        assert_eq!(topology.vertex_faces(4), &[2, 3]);
+        assert_eq!(topology.vertex_neighbors(0), vec![1, 2, 3, 7]);
+        assert_eq!(topology.face_neighbors(0), vec![1, 4]);
+        assert_eq!(topology.face_neighbors(2), vec![3]);
+
+        let diagonal = topology.find_edge(2, 0).unwrap();
+        assert_eq!(topology.edge_faces(diagonal), &[0, 1, 4]);
+        assert_eq!(topology.find_edge(0, 5), None);
+        assert_eq!(topology.non_manifold_edges().collect::<Vec<_>>(), vec![diagonal]);
+        // Contorno del cuadrado (4), la aleta (2) y el triángulo suelto (2:
+        // el degenerado cubre la tercera)
+        assert_eq!(topology.open_edges().count(), 8);
+
+        assert_eq!(topology.connected_components(), vec![vec![0, 1, 4], vec![2, 3]]);
+        assert_eq!(topology.summary(SubObjectElement::Face(2)), "1 caras vecinas, pieza 2 de 2");
+        assert_eq!(topology.summary(SubObjectElement::Edge(0, 2)), "arista no-manifold (3 caras)");
+    }
+}
@@ -0,0 +1,164 @@
+// src/graphics/trajectory.rs
+//
+// Trayectorias de las articulaciones de un robot (ver `graphics::robot`): la
+// posición de varias articulaciones en el tiempo, como las que graba un
+// controlador o planifica MoveIt. Se leen de un CSV con una fila de
+// encabezado: la primera columna es el tiempo en segundos y cada una de las
+// demás es una articulación (radianes o metros). El separador puede ser coma
+// o punto y coma; las líneas vacías y las que empiezan con '#' se saltean.
+// Entre filas se interpola en línea recta.
+//
+//   time,shoulder_pan_joint,elbow_joint
+//   0.0,0.0,0.0
+//   2.0,1.57,-0.8
+
+use std::fs;
+
+use crate::graphics::animation::wrap_time;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JointTrajectory {
+    joints: Vec<String>,
+    /// Segundos desde la primera fila (que queda en cero), en orden
+    times: Vec<f32>,
+    /// Una fila por tiempo, una columna por articulación
+    positions: Vec<Vec<f32>>,
+}
+
+impl JointTrajectory {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("No se pudo leer {}: {}", path, e))?;
+        Self::parse_csv(&text).map_err(|e| format!("Trayectoria inválida {}: {}", path, e))
+    }
+
+    pub fn parse_csv(text: &str) -> Result<Self, String> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(number, line)| (number + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+        let (_, header) = lines.next().ok_or("está vacía")?;
+        let separator = if header.contains(';') { ';' } else { ',' };
+        let joints: Vec<String> = header.split(separator).skip(1).map(|name| name.trim().to_string()).collect();
+        if joints.is_empty() || joints.iter().any(String::is_empty) {
+            return Err(format!("el encabezado necesita el tiempo y al menos una articulación: '{}'", header));
+        }
+
+        let mut times = Vec::new();
+        let mut positions = Vec::new();
+        for (number, line) in lines {
+            let values = line
+                .split(separator)
+                .map(|value| value.trim().parse::<f32>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| format!("línea {}: número inválido en '{}'", number, line))?;
+            if values.len() != joints.len() + 1 {
+                return Err(format!("línea {}: {} columnas, el encabezado tiene {}", number, values.len(), joints.len() + 1));
+            }
+            if times.last().is_some_and(|&last| values[0] <= last) {
+                return Err(format!("línea {}: el tiempo {} no avanza", number, values[0]));
+            }
+            times.push(values[0]);
+            positions.push(values[1..].to_vec());
+        }
+        let Some(&start) = times.first() else {
+            return Err("no tiene filas".to_string());
+        };
+        for time in &mut times {
+            *time -= start;
+        }
+        Ok(Self { joints, times, positions })
+    }
+
+    /// Nombres de las articulaciones, en el orden de las columnas
+    pub fn joints(&self) -> &[String] {
+        &self.joints
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.times.last().copied().unwrap_or(0.0)
+    }
+
+    /// Posición de cada articulación en `time` (limitado a la trayectoria)
+    pub fn sample(&self, time: f32) -> Vec<f32> {
+        let next = self.times.partition_point(|&t| t <= time);
+        if next == 0 {
+            return self.positions[0].clone();
+        }
+        let Some(&end) = self.times.get(next) else {
+            return self.positions[next - 1].clone();
+        };
+        let (start, t) = (self.times[next - 1], next - 1);
+        let blend = (time - start) / (end - start);
+        self.positions[t].iter().zip(&self.positions[next]).map(|(a, b)| a + (b - a) * blend).collect()
+    }
+}
+
+/// Reproducción de una trayectoria, como `Animator` con los clips
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrajectoryPlayer {
+    pub trajectory: JointTrajectory,
+    /// Segundos reproducidos
+    pub time: f32,
+    pub playing: bool,
+    /// Al terminar vuelve a empezar; si no, se detiene en la última fila
+    pub looping: bool,
+}
+
+impl TrajectoryPlayer {
+    pub fn new(trajectory: JointTrajectory, looping: bool) -> Self {
+        Self { trajectory, time: 0.0, playing: true, looping }
+    }
+
+    /// Avanza el tiempo y devuelve (articulación, posición) del instante
+    /// actual, o nada si está en pausa
+    pub fn update(&mut self, dt: f32) -> Vec<(&str, f32)> {
+        if !self.playing {
+            return Vec::new();
+        }
+        let duration = self.trajectory.duration();
+        let time = self.time + dt;
+        self.time = wrap_time(time, duration, self.looping);
+        if !self.looping && time >= duration {
+            self.playing = false;
+        }
+        let positions = self.trajectory.sample(self.time);
+        self.trajectory.joints.iter().map(String::as_str).zip(positions).collect()
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_trajectory_playback() {
+        let csv = "# grabada en el banco\n\
+                   tiempo; hombro; codo\n\
+                   10.0; 0.0; 1.0\n\
+                   \n\
+                   11.0; 1.0; 1.0\n\
+                   13.0; 0.0; 0.0\n";
+        let trajectory = JointTrajectory::parse_csv(csv).unwrap();
+        assert_eq!(trajectory.joints(), ["hombro", "codo"]);
+        assert_eq!(trajectory.duration(), 3.0);
+        assert_eq!(trajectory.sample(-1.0), vec![0.0, 1.0]);
+        assert_eq!(trajectory.sample(0.5), vec![0.5, 1.0]);
+        assert_eq!(trajectory.sample(2.0), vec![0.5, 0.5]);
+        assert_eq!(trajectory.sample(9.0), vec![0.0, 0.0]);
+
+        let mut player = TrajectoryPlayer::new(trajectory.clone(), false);
+        assert_eq!(player.update(0.5), vec![("hombro", 0.5), ("codo", 1.0)]);
+        assert_eq!(player.update(5.0), vec![("hombro", 0.0), ("codo", 0.0)]);
+        assert!(!player.playing && player.update(1.0).is_empty());
+        let mut looping = TrajectoryPlayer::new(trajectory, true);
+        looping.update(3.5);
+        assert_eq!(looping.time, 0.5);
+
+        assert!(JointTrajectory::parse_csv("t,a\n0,1\n0,2\n").is_err());
+        assert!(JointTrajectory::parse_csv("t,a\n0,1,2\n").is_err());
+        assert!(JointTrajectory::parse_csv("t\n0\n").is_err());
+        assert!(JointTrajectory::parse_csv("t,a\n").is_err());
+    }
+}
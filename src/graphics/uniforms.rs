@@ -0,0 +1,221 @@
+// src/graphics/uniforms.rs
+//
+// Uniforms que un objeto pisa al dibujarse (un tinte propio, un parpadeo de
+// selección...) sin necesitar un shader o material aparte. Los valores se
+// validan contra los uniforms activos del programa al asignarlos; al dibujar
+// se aplican antes del draw del objeto y después se restaura el valor
+// anterior para que no se filtren al siguiente objeto.
+
+use std::ffi::CString;
+
+/// Primera unidad de textura para las texturas de los overrides (la 0 es la del lightmap)
+const FIRST_TEXTURE_UNIT: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UniformValue {
+    Float(f32),
+    Vec3([f32; 3]),
+    Mat4([f32; 16]),
+    /// Textura 2D (id de GL) para un sampler2D
+    Texture(u32),
+}
+
+impl UniformValue {
+    /// Tipo GL que tiene que tener el uniform en el shader
+    fn gl_type(&self) -> u32 {
+        match self {
+            UniformValue::Float(_) => gl::FLOAT,
+            UniformValue::Vec3(_) => gl::FLOAT_VEC3,
+            UniformValue::Mat4(_) => gl::FLOAT_MAT4,
+            UniformValue::Texture(_) => gl::SAMPLER_2D,
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            UniformValue::Float(_) => "float",
+            UniformValue::Vec3(_) => "vec3",
+            UniformValue::Mat4(_) => "mat4",
+            UniformValue::Texture(_) => "sampler2D",
+        }
+    }
+}
+
+/// Uniforms propios de un objeto, en el orden en que se asignaron
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UniformOverrides {
+    values: Vec<(String, UniformValue)>,
+}
+
+impl UniformOverrides {
+    /// Asigna (o reemplaza) un valor sin validar
+    pub fn set(&mut self, name: &str, value: UniformValue) {
+        match self.values.iter_mut().find(|(n, _)| n == name) {
+            Some((_, current)) => *current = value,
+            None => self.values.push((name.to_string(), value)),
+        }
+    }
+
+    /// Asigna un valor comprobando que el programa tenga un uniform activo con
+    /// ese nombre y tipo
+    pub fn set_checked(&mut self, program: u32, name: &str, value: UniformValue) -> Result<(), String> {
+        validate(&active_uniforms(program), name, &value)?;
+        self.set(name, value);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<UniformValue> {
+        self.values.iter().find(|(n, _)| n == name).map(|(_, v)| *v)
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.values.len();
+        self.values.retain(|(n, _)| n != name);
+        self.values.len() != before
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, UniformValue)> {
+        self.values.iter().map(|(n, v)| (n.as_str(), *v))
+    }
+}
+
+/// Uniforms activos del programa: (nombre, tipo GL). Los arrays aparecen
+/// como "nombre" sin el "[0]".
+pub fn active_uniforms(program: u32) -> Vec<(String, u32)> {
+    let mut count = 0;
+    let mut max_length = 0;
+    unsafe {
+        gl::GetProgramiv(program, gl::ACTIVE_UNIFORMS, &mut count);
+        gl::GetProgramiv(program, gl::ACTIVE_UNIFORM_MAX_LENGTH, &mut max_length);
+    }
+    (0..count.max(0) as u32)
+        .map(|index| {
+            let mut buffer = vec![0u8; max_length.max(1) as usize];
+            let (mut length, mut size, mut kind) = (0, 0, 0);
+            unsafe {
+                gl::GetActiveUniform(
+                    program, index, buffer.len() as i32,
+                    &mut length, &mut size, &mut kind, buffer.as_mut_ptr() as *mut i8,
+                );
+            }
+            buffer.truncate(length.max(0) as usize);
+            let name = String::from_utf8_lossy(&buffer);
+            (name.trim_end_matches("[0]").to_string(), kind)
+        })
+        .collect()
+}
+
+/// Comprueba un valor contra la lista de uniforms activos
+pub fn validate(active: &[(String, u32)], name: &str, value: &UniformValue) -> Result<(), String> {
+    let Some((_, kind)) = active.iter().find(|(n, _)| n == name) else {
+        return Err(format!("El shader no tiene un uniform activo '{}'", name));
+    };
+    if *kind != value.gl_type() {
+        return Err(format!("El uniform '{}' no es de tipo {}", name, value.type_name()));
+    }
+    Ok(())
+}
+
+/// Valor anterior de un uniform pisado, para restaurarlo después del draw
+pub(crate) enum Saved {
+    Floats(i32, Vec<f32>),
+    Sampler(i32, i32, u32),
+}
+
+/// Aplica los overrides al programa activo y devuelve lo necesario para
+/// deshacerlos con `restore`
+pub(crate) fn apply(program: u32, overrides: &UniformOverrides) -> Vec<Saved> {
+    let mut saved = Vec::new();
+    let mut unit = FIRST_TEXTURE_UNIT;
+    for (name, value) in overrides.iter() {
+        let Ok(c_name) = CString::new(name) else { continue };
+        let location = unsafe { gl::GetUniformLocation(program, c_name.as_ptr()) };
+        if location < 0 {
+            continue;
+        }
+        unsafe {
+            match value {
+                UniformValue::Float(v) => {
+                    saved.push(Saved::Floats(location, read_floats(program, location, 1)));
+                    gl::Uniform1f(location, v);
+                }
+                UniformValue::Vec3([x, y, z]) => {
+                    saved.push(Saved::Floats(location, read_floats(program, location, 3)));
+                    gl::Uniform3f(location, x, y, z);
+                }
+                UniformValue::Mat4(m) => {
+                    saved.push(Saved::Floats(location, read_floats(program, location, 16)));
+                    gl::UniformMatrix4fv(location, 1, gl::FALSE, m.as_ptr());
+                }
+                UniformValue::Texture(texture) => {
+                    let mut previous = 0;
+                    gl::GetUniformiv(program, location, &mut previous);
+                    saved.push(Saved::Sampler(location, previous, unit));
+                    gl::ActiveTexture(gl::TEXTURE0 + unit);
+                    gl::BindTexture(gl::TEXTURE_2D, texture);
+                    gl::Uniform1i(location, unit as i32);
+                    unit += 1;
+                }
+            }
+        }
+    }
+    if unit != FIRST_TEXTURE_UNIT {
+        unsafe { gl::ActiveTexture(gl::TEXTURE0) };
+    }
+    saved
+}
+
+/// Deshace `apply`
+pub(crate) fn restore(saved: Vec<Saved>) {
+    for entry in saved {
+        unsafe {
+            match entry {
+                Saved::Floats(location, values) => match values.len() {
+                    1 => gl::Uniform1f(location, values[0]),
+                    3 => gl::Uniform3f(location, values[0], values[1], values[2]),
+                    _ => gl::UniformMatrix4fv(location, 1, gl::FALSE, values.as_ptr()),
+                },
+                Saved::Sampler(location, previous, unit) => {
+                    gl::Uniform1i(location, previous);
+                    gl::ActiveTexture(gl::TEXTURE0 + unit);
+                    gl::BindTexture(gl::TEXTURE_2D, 0);
+                    gl::ActiveTexture(gl::TEXTURE0);
+                }
+            }
+        }
+    }
+}
+
+unsafe fn read_floats(program: u32, location: i32, count: usize) -> Vec<f32> {
+    let mut values = vec![0.0; 16];
+    gl::GetUniformfv(program, location, values.as_mut_ptr());
+    values.truncate(count);
+    values
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_and_validation() {
+        let active = vec![("tint".to_string(), gl::FLOAT_VEC3), ("lightmap".to_string(), gl::SAMPLER_2D)];
+        assert!(validate(&active, "tint", &UniformValue::Vec3([1.0, 0.0, 0.0])).is_ok());
+        assert!(validate(&active, "tint", &UniformValue::Float(1.0)).is_err());
+        assert!(validate(&active, "glow", &UniformValue::Float(1.0)).is_err());
+        assert!(validate(&active, "lightmap", &UniformValue::Texture(7)).is_ok());
+
+        let mut overrides = UniformOverrides::default();
+        overrides.set("tint", UniformValue::Vec3([1.0, 0.0, 0.0]));
+        overrides.set("tint", UniformValue::Vec3([0.0, 1.0, 0.0]));
+        assert_eq!(overrides.iter().count(), 1);
+        assert_eq!(overrides.get("tint"), Some(UniformValue::Vec3([0.0, 1.0, 0.0])));
+        assert!(overrides.remove("tint"));
+        assert!(overrides.is_empty());
+    }
+}
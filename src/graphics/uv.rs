@@ -0,0 +1,439 @@
+// src/graphics/uv.rs
+//
+// Generación básica de coordenadas UV por proyección (los STL no traen UVs).
+// Se calcula una UV por vértice, así que en mallas con vértices compartidos
+// las proyecciones cilíndrica/esférica muestran una costura estirada y la de
+// caja mezcla lados en las aristas. Suficiente para texturas de detalle.
+//
+// Los lightmaps usan otro juego de UVs (`lightmap_uvs`) que no puede solapar:
+// dos caras en el mismo texel recibirían la misma luz. Es una UV por esquina
+// de triángulo, así las costuras entre islas no obligan a tocar los vértices
+// de la malla (`SceneObject` los repite solo en la GPU).
+
+use std::collections::HashMap;
+
+use crate::graphics::mesh::Mesh;
+use crate::graphics::mesh_ops::vertex_normals;
+use crate::math::{aabb::{axis, Aabb}, vec3::Vec3};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UvAxis {
+    X,
+    Y,
+    Z,
+}
+
+impl UvAxis {
+    fn index(self) -> usize {
+        match self {
+            UvAxis::X => 0,
+            UvAxis::Y => 1,
+            UvAxis::Z => 2,
+        }
+    }
+
+    /// Los otros dos ejes, en orden (u, v)
+    fn plane(self) -> (usize, usize) {
+        match self {
+            UvAxis::X => (2, 1),
+            UvAxis::Y => (0, 2),
+            UvAxis::Z => (0, 1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UvProjection {
+    /// Proyección paralela a lo largo de un eje
+    Planar(UvAxis),
+    /// Planar por el eje dominante de la normal de cada vértice
+    Box,
+    /// Ángulo alrededor del eje y altura a lo largo de él
+    Cylindrical(UvAxis),
+    /// Longitud/latitud alrededor del centro de la malla
+    Spherical,
+}
+
+/// Escala y desplazamiento aplicados después de proyectar
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UvTransform {
+    pub scale: [f32; 2],
+    pub offset: [f32; 2],
+}
+
+impl Default for UvTransform {
+    fn default() -> Self {
+        Self {
+            scale: [1.0, 1.0],
+            offset: [0.0, 0.0],
+        }
+    }
+}
+
+/// Calcula una UV por vértice de `mesh`. Las proyecciones planas usan el lado
+/// más largo de la caja de la malla como unidad, para que la densidad de
+/// textura sea la misma en todos los ejes.
+pub fn project_uvs(mesh: &Mesh, projection: UvProjection, transform: &UvTransform) -> Vec<[f32; 2]> {
+    let mut bounds = Aabb::EMPTY;
+    for p in &mesh.positions {
+        bounds.grow(Vec3::from(*p));
+    }
+    if bounds.is_empty() {
+        return Vec::new();
+    }
+    let size = bounds.size();
+    let extent = size.x.max(size.y).max(size.z).max(1e-6);
+    let center = bounds.center();
+
+    let planar = |p: Vec3, plane: (usize, usize)| {
+        [
+            (axis(p, plane.0) - axis(bounds.min, plane.0)) / extent,
+            (axis(p, plane.1) - axis(bounds.min, plane.1)) / extent,
+        ]
+    };
+
+    let normals = match projection {
+        UvProjection::Box => vertex_normals(mesh),
+        _ => Vec::new(),
+    };
+
+    mesh.positions
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let p = Vec3::from(*p);
+            let uv = match projection {
+                UvProjection::Planar(along) => planar(p, along.plane()),
+                UvProjection::Box => {
+                    let n = Vec3::from(normals[i]);
+                    let dominant = if n.x.abs() >= n.y.abs() && n.x.abs() >= n.z.abs() {
+                        UvAxis::X
+                    } else if n.y.abs() >= n.z.abs() {
+                        UvAxis::Y
+                    } else {
+                        UvAxis::Z
+                    };
+                    planar(p, dominant.plane())
+                }
+                UvProjection::Cylindrical(around) => {
+                    let (a, b) = around.plane();
+                    let d = p - center;
+                    let angle = axis(d, b).atan2(axis(d, a));
+                    let height = (axis(p, around.index()) - axis(bounds.min, around.index())) / extent;
+                    [angle / std::f32::consts::TAU + 0.5, height]
+                }
+                UvProjection::Spherical => {
+                    let d = p - center;
+                    let r = d.magnitude();
+                    if r <= 1e-9 {
+                        [0.5, 0.5]
+                    } else {
+                        let longitude = d.x.atan2(d.z) / std::f32::consts::TAU + 0.5;
+                        let latitude = 1.0 - (d.y / r).clamp(-1.0, 1.0).acos() / std::f32::consts::PI;
+                        [longitude, latitude]
+                    }
+                }
+            };
+            [
+                uv[0] * transform.scale[0] + transform.offset[0],
+                uv[1] * transform.scale[1] + transform.offset[1],
+            ]
+        })
+        .collect()
+}
+
+/// Tangente por vértice a partir de las UVs: dirección en que crece u,
+/// ortogonalizada contra la normal. El cuarto componente es el signo de la
+/// bitangente (`cross(n, t) * w` apunta hacia donde crece v), -1 donde la UV
+/// está espejada. Los vértices sin triángulos con UV útil quedan en cero.
+/// Vacío si la malla no tiene una UV por vértice.
+pub fn vertex_tangents(mesh: &Mesh, normals: &[[f32; 3]]) -> Vec<[f32; 4]> {
+    let count = mesh.positions.len();
+    if mesh.uvs.len() != count || normals.len() != count {
+        return Vec::new();
+    }
+    let mut tangents = vec![Vec3::ZERO; count];
+    let mut bitangents = vec![Vec3::ZERO; count];
+    for triangle in 0..mesh.triangle_count() {
+        let Some(corners) = mesh.triangle(triangle) else { continue };
+        let [a, b, c] = corners.map(|v| mesh.position(v));
+        let [ta, tb, tc] = corners.map(|v| mesh.uvs[v as usize]);
+        let (e1, e2) = (b - a, c - a);
+        let (du1, dv1) = (tb[0] - ta[0], tb[1] - ta[1]);
+        let (du2, dv2) = (tc[0] - ta[0], tc[1] - ta[1]);
+        let det = du1 * dv2 - du2 * dv1;
+        // Triángulo degenerado en UV: no define dirección
+        if det.abs() <= 1e-12 {
+            continue;
+        }
+        let tangent = (e1 * dv2 - e2 * dv1) / det;
+        let bitangent = (e2 * du1 - e1 * du2) / det;
+        for v in corners {
+            tangents[v as usize] += tangent;
+            bitangents[v as usize] += bitangent;
+        }
+    }
+    (0..count)
+        .map(|i| {
+            let n = Vec3::from(normals[i]).normalize_or(Vec3::ZERO);
+            let Some(t) = (tangents[i] - n * n.dot(&tangents[i])).try_normalize() else {
+                return [0.0; 4];
+            };
+            let w = if n.cross(&t).dot(&bitangents[i]) < 0.0 { -1.0 } else { 1.0 };
+            [t.x, t.y, t.z, w]
+        })
+        .collect()
+}
+
+/// Separación entre islas del lightmap, en texels: el horneado extiende cada
+/// isla dos texels (ver `lightmap::dilate`) y así no se tocan
+const LIGHTMAP_PADDING: f32 = 4.0;
+
+/// Triángulo proyectado en el plano de su isla
+type Triangle2 = [[f32; 2]; 3];
+
+/// Eje dominante de la normal con su signo (0 a 5); las caras degeneradas van al 0
+fn face_direction(mesh: &Mesh, triangle: usize) -> usize {
+    let Some([a, b, c]) = mesh.triangle(triangle) else { return 0 };
+    let (pa, pb, pc) = (mesh.position(a), mesh.position(b), mesh.position(c));
+    let n = (pb - pa).cross(&(pc - pa));
+    let magnitudes = [n.x.abs(), n.y.abs(), n.z.abs()];
+    let dominant = (0..3).fold(0, |best, i| if magnitudes[i] > magnitudes[best] { i } else { best });
+    dominant * 2 + (axis(n, dominant) < 0.0) as usize
+}
+
+/// Los interiores de dos triángulos se pisan (ejes separadores; compartir
+/// una arista o un vértice no cuenta)
+fn triangles_overlap(a: &Triangle2, b: &Triangle2, eps: f32) -> bool {
+    for triangle in [a, b] {
+        for i in 0..3 {
+            let [x0, y0] = triangle[i];
+            let [x1, y1] = triangle[(i + 1) % 3];
+            let normal = [y1 - y0, x0 - x1];
+            let project = |t: &Triangle2| {
+                t.iter().map(|p| p[0] * normal[0] + p[1] * normal[1]).fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), d| (lo.min(d), hi.max(d)))
+            };
+            let ((a_lo, a_hi), (b_lo, b_hi)) = (project(a), project(b));
+            let scale = normal[0].abs() + normal[1].abs();
+            if a_hi <= b_lo + eps * scale || b_hi <= a_lo + eps * scale {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Isla del lightmap: triángulos y la UV de cada esquina, antes de ubicarla
+struct Chart {
+    triangles: Vec<usize>,
+    corners: Vec<Triangle2>,
+    min: [f32; 2],
+    size: [f32; 2],
+}
+
+/// Agrupa en islas los triángulos vecinos que miran al mismo lado de la caja,
+/// proyectados en ese plano. Un triángulo que pisaría a otro de la isla (una
+/// rampa en espiral, por ejemplo) queda para otra isla.
+fn build_charts(mesh: &Mesh) -> Vec<Chart> {
+    let count = mesh.triangle_count();
+    let directions: Vec<usize> = (0..count).map(|t| face_direction(mesh, t)).collect();
+    let planes = [UvAxis::X, UvAxis::Y, UvAxis::Z].map(UvAxis::plane);
+    let project = |triangle: usize| -> Triangle2 {
+        let (u, v) = planes[directions[triangle] / 2];
+        let corners = mesh.triangle(triangle).unwrap_or([0; 3]);
+        corners.map(|vertex| {
+            let p = mesh.position(vertex);
+            [axis(p, u), axis(p, v)]
+        })
+    };
+
+    // Grilla para buscar solapes del tamaño de la arista promedio
+    let mut bounds = Aabb::EMPTY;
+    for p in &mesh.positions {
+        bounds.grow(Vec3::from(*p));
+    }
+    let extent = bounds.size().magnitude().max(1e-6);
+    let cell = (extent / (count as f32).sqrt().max(1.0)).max(1e-6);
+    let eps = extent * 1e-6;
+    let cells_of = |t: &Triangle2| {
+        let lo = [0, 1].map(|i| (t.iter().map(|p| p[i]).fold(f32::INFINITY, f32::min) / cell).floor() as i64);
+        let hi = [0, 1].map(|i| (t.iter().map(|p| p[i]).fold(f32::NEG_INFINITY, f32::max) / cell).floor() as i64);
+        (lo[0]..=hi[0]).flat_map(move |x| (lo[1]..=hi[1]).map(move |y| (x, y)))
+    };
+
+    let topology = mesh.topology();
+    let mut assigned = vec![false; count];
+    let mut charts = Vec::new();
+    for seed in 0..count {
+        if assigned[seed] {
+            continue;
+        }
+        assigned[seed] = true;
+        let mut chart = Chart { triangles: vec![seed], corners: vec![project(seed)], min: [0.0; 2], size: [0.0; 2] };
+        let mut grid: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        for key in cells_of(&chart.corners[0]) {
+            grid.entry(key).or_default().push(0);
+        }
+        let mut next = 0;
+        while next < chart.triangles.len() {
+            let current = chart.triangles[next];
+            next += 1;
+            for neighbor in topology.face_neighbors(current) {
+                let neighbor = neighbor as usize;
+                if assigned[neighbor] || directions[neighbor] != directions[seed] {
+                    continue;
+                }
+                let projected = project(neighbor);
+                let overlaps = cells_of(&projected)
+                    .filter_map(|key| grid.get(&key))
+                    .flatten()
+                    .any(|&other| triangles_overlap(&projected, &chart.corners[other], eps));
+                if overlaps {
+                    continue;
+                }
+                assigned[neighbor] = true;
+                for key in cells_of(&projected) {
+                    grid.entry(key).or_default().push(chart.corners.len());
+                }
+                chart.triangles.push(neighbor);
+                chart.corners.push(projected);
+            }
+        }
+        let points = || chart.corners.iter().flatten();
+        chart.min = [0, 1].map(|i| points().map(|p| p[i]).fold(f32::INFINITY, f32::min));
+        chart.size = [0, 1].map(|i| points().map(|p| p[i]).fold(f32::NEG_INFINITY, f32::max) - chart.min[i]);
+        charts.push(chart);
+    }
+    charts
+}
+
+/// Ubica las islas en estantes dentro de un cuadrado de lado `side` (en
+/// unidades de la malla), de la más alta a la más baja, separadas por
+/// `padding`. None si no entran.
+fn pack_charts(charts: &[Chart], order: &[usize], side: f32, padding: f32) -> Option<Vec<[f32; 2]>> {
+    let mut offsets = vec![[0.0; 2]; charts.len()];
+    let (mut x, mut y, mut shelf) = (padding, padding, 0.0_f32);
+    for &index in order {
+        let [width, height] = charts[index].size;
+        if x + width + padding > side {
+            x = padding;
+            y += shelf + padding;
+            shelf = 0.0;
+        }
+        if x + width + padding > side || y + height + padding > side {
+            return None;
+        }
+        offsets[index] = [x, y];
+        x += width + padding;
+        shelf = shelf.max(height);
+    }
+    Some(offsets)
+}
+
+/// UVs de lightmap sin solapes para un lightmap de `resolution` texels de
+/// lado: una por esquina de triángulo, en el orden de `indices`. Cada isla
+/// conserva su escala, así la densidad de texels es la misma en toda la malla.
+pub fn lightmap_uvs(mesh: &Mesh, resolution: u32) -> Vec<[f32; 2]> {
+    let charts = build_charts(mesh);
+    if charts.is_empty() {
+        return Vec::new();
+    }
+    let mut order: Vec<usize> = (0..charts.len()).collect();
+    order.sort_by(|&a, &b| charts[b].size[1].total_cmp(&charts[a].size[1]));
+
+    // La separación se fija con el primer tamaño: si hay que agrandar el
+    // cuadrado quedan menos texels entre islas, pero siempre entran
+    let area: f32 = charts.iter().map(|c| c.size[0] * c.size[1]).sum();
+    let largest = charts.iter().map(|c| c.size[0].max(c.size[1])).fold(0.0, f32::max);
+    let mut side = area.sqrt().max(largest).max(1e-6);
+    let padding = LIGHTMAP_PADDING * side / resolution.max(1) as f32;
+    let offsets = loop {
+        if let Some(offsets) = pack_charts(&charts, &order, side, padding) {
+            break offsets;
+        }
+        side *= 1.1;
+    };
+
+    let mut uvs = vec![[0.0; 2]; mesh.triangle_count() * 3];
+    for (chart, offset) in charts.iter().zip(&offsets) {
+        for (&triangle, corners) in chart.triangles.iter().zip(&chart.corners) {
+            for (k, [u, v]) in corners.iter().enumerate() {
+                uvs[triangle * 3 + k] = [
+                    (offset[0] + u - chart.min[0]) / side,
+                    (offset[1] + v - chart.min[1]) / side,
+                ];
+            }
+        }
+    }
+    uvs
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quad() -> Mesh {
+        // Cuadrado de 2x2 en el plano XZ
+        Mesh::new(
+            vec![[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [2.0, 0.0, 2.0], [0.0, 0.0, 2.0]],
+            vec![0, 1, 2, 0, 2, 3],
+        )
+    }
+
+    #[test]
+    fn test_planar_with_transform() {
+        let transform = UvTransform { scale: [2.0, 2.0], offset: [0.5, 0.0] };
+        let uvs = project_uvs(&quad(), UvProjection::Planar(UvAxis::Y), &transform);
+        assert_eq!(uvs[0], [0.5, 0.0]);
+        assert_eq!(uvs[2], [2.5, 2.0]);
+    }
+
+    #[test]
+    fn test_box_matches_planar_on_flat_mesh() {
+        let transform = UvTransform::default();
+        let planar = project_uvs(&quad(), UvProjection::Planar(UvAxis::Y), &transform);
+        let boxed = project_uvs(&quad(), UvProjection::Box, &transform);
+        assert_eq!(planar, boxed);
+    }
+
+    #[test]
+    fn test_tangents_follow_uv() {
+        let mut mesh = quad();
+        mesh.uvs = project_uvs(&mesh, UvProjection::Planar(UvAxis::Y), &UvTransform::default());
+        let up = vec![[0.0, 1.0, 0.0]; 4];
+        // u crece con x; v crece con z, que en el plano XZ con normal +Y es el lado espejado
+        assert!(vertex_tangents(&mesh, &up).iter().all(|t| *t == [1.0, 0.0, 0.0, -1.0]));
+        mesh.uvs.pop();
+        assert!(vertex_tangents(&mesh, &up).is_empty());
+    }
+
+    #[test]
+    fn test_lightmap_uvs_do_not_overlap() {
+        // Cubo con vértices compartidos: con la caja las caras opuestas caen
+        // en el mismo lugar, en el lightmap cada una tiene su isla
+        let corners = (0..8).map(|i| [(i & 1) as f32, (i >> 1 & 1) as f32, (i >> 2 & 1) as f32]).collect();
+        let faces = [[0, 2, 3, 1], [4, 5, 7, 6], [0, 1, 5, 4], [2, 6, 7, 3], [0, 4, 6, 2], [1, 3, 7, 5]];
+        let indices = faces.iter().flat_map(|[a, b, c, d]| [*a, *b, *c, *a, *c, *d]).collect();
+        let cube = Mesh::new(corners, indices);
+
+        let uvs = lightmap_uvs(&cube, 64);
+        assert_eq!(uvs.len(), 36);
+        assert!(uvs.iter().flatten().all(|c| (0.0..=1.0).contains(c)));
+        let triangles: Vec<Triangle2> = uvs.chunks_exact(3).map(|t| [t[0], t[1], t[2]]).collect();
+        for (i, a) in triangles.iter().enumerate() {
+            for b in &triangles[i + 1..] {
+                assert!(!triangles_overlap(a, b, 1e-6));
+            }
+        }
+        // Las dos mitades de cada cara quedan en la misma isla, unidas por la diagonal
+        assert_eq!(uvs[0], uvs[3]);
+        assert_eq!(uvs[2], uvs[4]);
+    }
+
+    #[test]
+    fn test_cylindrical_range() {
+        let uvs = project_uvs(&quad(), UvProjection::Cylindrical(UvAxis::Y), &UvTransform::default());
+        assert!(uvs.iter().all(|uv| (0.0..=1.0).contains(&uv[0])));
+    }
+}
@@ -0,0 +1,244 @@
+// src/graphics/uv_debug.rs
+//
+// Vista 2D de las UVs de un objeto en un recuadro de la esquina inferior
+// izquierda: el cuadrado [0, 1] con una grilla y las aristas de cada triángulo
+// en espacio de textura. Los triángulos espejados (orientación invertida en UV)
+// van en rojo. Sirve para ver solapamientos, estiramientos y UVs fuera de
+// rango antes de hornear o texturizar.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::graphics::gpu_resources::ContextGeneration;
+use crate::graphics::gl_validation;
+use crate::graphics::mesh::Mesh;
+use crate::graphics::render_graph::{FrameContext, PassStage, RenderPass, ResourceId, BACKBUFFER};
+use crate::graphics::render_settings::RenderSettings;
+use crate::graphics::shaders::{build_program, uniform_location, ShaderLibrary};
+use crate::math::matrix_4_by_4::Matrix4;
+
+/// Margen alrededor del cuadrado [0, 1] dentro del recuadro, en unidades de UV
+const PADDING: f32 = 0.05;
+/// Divisiones de la grilla por lado
+const GRID: usize = 4;
+
+/// Qué objeto se muestra; compartido entre el pase y el Renderer
+pub struct UvLayout {
+    /// Lado del recuadro en píxeles
+    pub size: i32,
+    /// Separación del borde de la vista en píxeles
+    pub margin: i32,
+    object: Option<usize>,
+    dirty: bool,
+}
+
+impl Default for UvLayout {
+    fn default() -> Self {
+        Self { size: 256, margin: 10, object: None, dirty: false }
+    }
+}
+
+impl UvLayout {
+    /// Muestra las UVs del objeto `index` de la escena
+    pub fn show(&mut self, index: usize) {
+        self.object = Some(index);
+        self.dirty = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.object = None;
+    }
+
+    pub fn object(&self) -> Option<usize> {
+        self.object
+    }
+
+    /// Vuelve a armar la vista en el próximo frame (cambiaron las UVs o la malla)
+    pub fn refresh(&mut self) {
+        self.dirty = true;
+    }
+}
+
+/// Segmentos de la vista como pares de vértices (posición, color), con la UV en x, y
+fn layout_segments(mesh: &Mesh) -> Vec<[f32; 6]> {
+    let mut vertices = Vec::new();
+    let mut segment = |a: [f32; 2], b: [f32; 2], [r, g, bl]: [f32; 3]| {
+        vertices.push([a[0], a[1], 0.0, r, g, bl]);
+        vertices.push([b[0], b[1], 0.0, r, g, bl]);
+    };
+    for i in 0..=GRID {
+        let t = i as f32 / GRID as f32;
+        let color = if i == 0 || i == GRID { [0.6, 0.6, 0.6] } else { [0.2, 0.2, 0.2] };
+        segment([t, 0.0], [t, 1.0], color);
+        segment([0.0, t], [1.0, t], color);
+    }
+    if mesh.uvs.len() != mesh.positions.len() {
+        return vertices;
+    }
+    for triangle in 0..mesh.triangle_count() {
+        let Some(corners) = mesh.triangle(triangle) else { continue };
+        let [a, b, c] = corners.map(|v| mesh.uvs[v as usize]);
+        let area = (b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1]);
+        let color = if area < 0.0 { [1.0, 0.2, 0.2] } else { [0.3, 0.9, 1.0] };
+        segment(a, b, color);
+        segment(b, c, color);
+        segment(c, a, color);
+    }
+    vertices
+}
+
+/// Pase que dibuja la vista de UVs encima de todo, en su propio viewport
+pub struct UvLayoutPass {
+    program: u32,
+    vao: u32,
+    vbo: u32,
+    vertex_count: i32,
+    state: Rc<RefCell<UvLayout>>,
+    generation: ContextGeneration,
+}
+
+impl UvLayoutPass {
+    pub fn new(state: Rc<RefCell<UvLayout>>) -> Result<Self, String> {
+        // Mismo shader que las líneas en espacio mundo, con una proyección 2D
+        let program = build_program(
+            include_str!("shaders/line.vert"),
+            include_str!("shaders/line.frag"),
+        )?;
+        let (mut vao, mut vbo) = (0, 0);
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+            gl::GenBuffers(1, &mut vbo);
+            gl::BindVertexArray(vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+            let stride = (6 * std::mem::size_of::<f32>()) as i32;
+            // (location=0) posición, (location=1) color
+            gl::VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, stride, std::ptr::null());
+            gl::EnableVertexAttribArray(0);
+            gl::VertexAttribPointer(1, 3, gl::FLOAT, gl::FALSE, stride, (3 * std::mem::size_of::<f32>()) as *const _);
+            gl::EnableVertexAttribArray(1);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
+        }
+        Ok(Self { program, vao, vbo, vertex_count: 0, state, generation: ContextGeneration::current() })
+    }
+}
+
+impl RenderPass for UvLayoutPass {
+    fn name(&self) -> &str {
+        "uv_layout"
+    }
+
+    fn stage(&self) -> PassStage {
+        PassStage::Post
+    }
+
+    fn outputs(&self) -> &[ResourceId] {
+        &[BACKBUFFER]
+    }
+
+    fn settings(&self, global: &RenderSettings) -> RenderSettings {
+        RenderSettings {
+            depth_test: false,
+            wireframe: false,
+            ..*global
+        }
+    }
+
+    fn rebuild_gpu(&mut self, _shaders: &ShaderLibrary) -> Result<(), String> {
+        *self = Self::new(self.state.clone())?;
+        // La vista se vuelve a subir en el próximo frame
+        self.state.borrow_mut().dirty = true;
+        Ok(())
+    }
+
+    fn execute(&mut self, frame: &FrameContext) {
+        let mut state = self.state.borrow_mut();
+        // El objeto pudo desaparecer de la escena (otra escena cargada)
+        let Some(obj) = state.object.and_then(|index| frame.objects.get(index)) else {
+            return;
+        };
+        if state.dirty {
+            let vertices = layout_segments(&obj.mesh);
+            self.vertex_count = vertices.len() as i32;
+            unsafe {
+                gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+                gl::BufferData(
+                    gl::ARRAY_BUFFER,
+                    std::mem::size_of_val(vertices.as_slice()) as isize,
+                    vertices.as_ptr() as *const _,
+                    gl::DYNAMIC_DRAW,
+                );
+                gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            }
+            state.dirty = false;
+        }
+
+        let projection = Matrix4::orthographic(-PADDING, 1.0 + PADDING, -PADDING, 1.0 + PADDING, -1.0, 1.0);
+        unsafe {
+            // Recuadro en la esquina inferior izquierda de la vista actual
+            let mut viewport = [0i32; 4];
+            gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+            if viewport[2] < state.size + state.margin || viewport[3] < state.size + state.margin {
+                return;
+            }
+            let (left, bottom) = (viewport[0] + state.margin, viewport[1] + state.margin);
+            gl::Viewport(left, bottom, state.size, state.size);
+            gl::Enable(gl::SCISSOR_TEST);
+            gl::Scissor(left, bottom, state.size, state.size);
+            gl::ClearColor(0.02, 0.02, 0.02, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+            gl::Disable(gl::SCISSOR_TEST);
+            // Devolver el color de fondo de los ajustes
+            let [r, g, b] = frame.settings.shader_color([
+                frame.settings.clear_color[0],
+                frame.settings.clear_color[1],
+                frame.settings.clear_color[2],
+            ]);
+            gl::ClearColor(r, g, b, frame.settings.clear_color[3]);
+
+            gl::UseProgram(self.program);
+            gl::UniformMatrix4fv(uniform_location(self.program, "view"), 1, gl::FALSE, Matrix4::identity().as_ptr());
+            gl::UniformMatrix4fv(uniform_location(self.program, "projection"), 1, gl::FALSE, projection.as_ptr());
+            gl::Uniform1f(uniform_location(self.program, "gamma"), frame.settings.gamma);
+            gl::BindVertexArray(self.vao);
+            gl_validation::draw_arrays(gl::LINES, 0, self.vertex_count);
+            gl::BindVertexArray(0);
+            gl::Viewport(viewport[0], viewport[1], viewport[2], viewport[3]);
+        }
+    }
+}
+
+impl Drop for UvLayoutPass {
+    fn drop(&mut self) {
+        if !self.generation.is_current() {
+            return;
+        }
+        unsafe {
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteProgram(self.program);
+        }
+    }
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_segments_flag_mirrored_triangles() {
+        let mut mesh = Mesh::new(vec![[0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [1.0, 1.0, 0.0]], vec![0, 1, 2, 1, 3, 2]);
+        let grid = 2 * (GRID + 1) * 2;
+        // Sin UVs solo la grilla
+        assert_eq!(layout_segments(&mesh).len(), grid);
+
+        // El segundo triángulo queda espejado en UV
+        mesh.uvs = vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [0.0, 0.0]];
+        let segments = layout_segments(&mesh);
+        assert_eq!(segments.len(), grid + 2 * 6);
+        assert_eq!(segments[grid][3..], [0.3, 0.9, 1.0]);
+        assert_eq!(segments[grid + 6][3..], [1.0, 0.2, 0.2]);
+        assert_eq!(segments[grid + 1][..3], [1.0, 0.0, 0.0]);
+    }
+}
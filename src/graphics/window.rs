@@ -1,60 +1,295 @@
 // src/graphics/window.rs
 
-use glutin::{
-    dpi::LogicalSize,
-    event_loop::EventLoop,
-    window::WindowBuilder,
-    ContextBuilder,
-    ContextWrapper,
-    PossiblyCurrent,
+use std::cell::Cell;
+use std::ffi::CString;
+use std::num::NonZeroU32;
+
+use glutin::config::{Config, ConfigTemplateBuilder, GlConfig};
+use glutin::context::{
+    ContextApi, ContextAttributes, ContextAttributesBuilder, GlProfile, NotCurrentGlContext, PossiblyCurrentContext,
+    PossiblyCurrentGlContext, Robustness, Version,
 };
-use glutin::window::Window as GlutinWindow;
+use glutin::display::{GetGlDisplay, GlDisplay};
+use glutin::error::ErrorKind;
+use glutin::surface::{GlSurface, Surface, SurfaceAttributesBuilder, SwapInterval, WindowSurface};
+use glutin_winit::{DisplayBuilder, GlWindow};
+use raw_window_handle::HasWindowHandle;
+use winit::dpi::{LogicalSize, PhysicalSize};
+use winit::event_loop::{ActiveEventLoop, EventLoop};
+use winit::window::{Window as WinitWindow, WindowAttributes};
+
+use crate::graphics::capabilities::{GlCapabilities, MIN_GL_VERSION};
+use crate::graphics::render_settings::RenderSettings;
+
+/// Variable de entorno para elegir el backend de ventanas en Linux ("wayland" o "x11")
+pub const BACKEND_ENV: &str = "RUST_ENGINE_BACKEND";
+
+/// Sistema de ventanas a usar en Linux (en el resto de plataformas solo hay uno)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// Wayland si hay un compositor, si no X11
+    #[default]
+    Auto,
+    Wayland,
+    /// X11 (o XWayland); lo necesita OpenXR, que usa GLX
+    X11,
+}
+
+impl Backend {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "" | "auto" => Ok(Backend::Auto),
+            "wayland" => Ok(Backend::Wayland),
+            "x11" | "xorg" => Ok(Backend::X11),
+            other => Err(format!("Backend de ventanas desconocido '{}' (auto, wayland o x11)", other)),
+        }
+    }
 
+    /// Backend pedido en el entorno (un valor inválido se informa y se usa Auto)
+    pub fn from_env() -> Self {
+        let Ok(value) = std::env::var(BACKEND_ENV) else {
+            return Backend::Auto;
+        };
+        Self::parse(&value).unwrap_or_else(|e| {
+            eprintln!("{} ignorado: {}", BACKEND_ENV, e);
+            Backend::Auto
+        })
+    }
+
+    /// Event loop conectado a este backend
+    pub fn event_loop(self) -> Result<EventLoop<()>, String> {
+        #[allow(unused_mut)] // solo se configura en Linux
+        let mut builder = EventLoop::builder();
+        #[cfg(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android"))))]
+        {
+            use winit::platform::wayland::EventLoopBuilderExtWayland;
+            use winit::platform::x11::EventLoopBuilderExtX11;
+            match self {
+                Backend::Auto => {}
+                Backend::Wayland => {
+                    builder.with_wayland();
+                }
+                Backend::X11 => {
+                    builder.with_x11();
+                }
+            }
+        }
+        #[cfg(not(all(unix, not(any(target_os = "macos", target_os = "ios", target_os = "android")))))]
+        if self != Backend::Auto {
+            return Err(format!("El backend {:?} solo existe en Linux", self));
+        }
+        builder.build().map_err(|e| format!("No se pudo crear el event loop ({:?}): {}", self, e))
+    }
+}
+
+/// Ventana con su contexto OpenGL actual en este hilo
 pub struct Window {
-    pub context: ContextWrapper<PossiblyCurrent, GlutinWindow>,
+    // El contexto y la superficie se sueltan antes que la ventana
+    pub context: PossiblyCurrentContext,
+    pub surface: Surface<WindowSurface>,
+    pub window: WinitWindow,
+    /// Versión y extensiones del contexto que dio el driver
+    pub capabilities: GlCapabilities,
+    /// Formato con el que se creó la superficie (para volver a crearla)
+    config: Config,
+    /// Tamaño al que están ajustados la superficie y el viewport
+    size: Cell<PhysicalSize<u32>>,
+    surface_lost: Cell<bool>,
 }
 
 impl Window {
-    pub fn new(title: &str, width: u32, height: u32, event_loop: &EventLoop<()>) 
-        -> Result<Self, String> 
-    {
-        let wb = WindowBuilder::new()
+    /// Ventana de la aplicación; se crea desde `ApplicationHandler::resumed`
+    pub fn new(title: &str, width: u32, height: u32, event_loop: &ActiveEventLoop) -> Result<Self, String> {
+        let attributes = Self::attributes(title, width, height, true);
+        let built = DisplayBuilder::new()
+            .with_window_attributes(Some(attributes))
+            .build(event_loop, ConfigTemplateBuilder::new(), pick_config);
+        Self::finish(built)
+    }
+
+    /// Ventana oculta: solo para tener un contexto OpenGL (render por lotes,
+    /// sin correr el event loop)
+    pub fn hidden(title: &str, width: u32, height: u32, event_loop: &EventLoop<()>) -> Result<Self, String> {
+        let attributes = Self::attributes(title, width, height, false);
+        let built = DisplayBuilder::new()
+            .with_window_attributes(Some(attributes))
+            .build(event_loop, ConfigTemplateBuilder::new(), pick_config);
+        Self::finish(built)
+    }
+
+    fn attributes(title: &str, width: u32, height: u32, visible: bool) -> WindowAttributes {
+        WinitWindow::default_attributes()
             .with_title(title)
-            .with_inner_size(LogicalSize::new(width, height));
+            .with_inner_size(LogicalSize::new(width, height))
+            .with_visible(visible)
+    }
 
-        let windowed_context = ContextBuilder::new()
-            .with_vsync(true)
-            .build_windowed(wb, event_loop)
-            .map_err(|e| format!("Error build_windowed: {:?}", e))?;
+    fn finish(built: Result<(Option<WinitWindow>, Config), Box<dyn std::error::Error>>) -> Result<Self, String> {
+        let failed = |e: &dyn std::fmt::Display| {
+            format!("No se pudo crear un contexto OpenGL {}.{} core: {}", MIN_GL_VERSION.0, MIN_GL_VERSION.1, e)
+        };
+        let (window, config) = built.map_err(|e| failed(&e))?;
+        let window = window.ok_or_else(|| failed(&"no se creó la ventana"))?;
+        let raw_handle = window.window_handle().map_err(|e| failed(&e))?.as_raw();
+        let display = config.display();
 
-        // Activar el contexto
-        let context = unsafe {
-            windowed_context.make_current()
-                .map_err(|(_, e)| format!("Error make_current: {:?}", e))?
+        // Core 3.3 explícito: sin esto cada driver elige (a veces un perfil de
+        // compatibilidad 2.1, y en macOS un contexto legacy donde los shaders
+        // 330 no compilan). Los drivers suelen devolver la versión core más
+        // alta que tengan (macOS da 4.1), y lo de 4.x se activa según `GlCapabilities`.
+        //
+        // Con robustez el driver avisa si resetea el contexto (ver
+        // `gpu_resources::context_lost`); si no la ofrece se sigue sin ella.
+        let context_attributes = |robustness| -> ContextAttributes {
+            ContextAttributesBuilder::new()
+                .with_context_api(ContextApi::OpenGl(Some(Version::new(MIN_GL_VERSION.0 as u8, MIN_GL_VERSION.1 as u8))))
+                .with_profile(GlProfile::Core)
+                .with_debug(cfg!(debug_assertions))
+                .with_robustness(robustness)
+                .build(Some(raw_handle))
         };
+        let context = unsafe {
+            display
+                .create_context(&config, &context_attributes(Robustness::RobustLoseContextOnReset))
+                .or_else(|_| display.create_context(&config, &context_attributes(Robustness::NotRobust)))
+        }
+        .map_err(|e| failed(&e))?;
+
+        // Superficie sRGB (si el formato la tiene) para que GL_FRAMEBUFFER_SRGB
+        // codifique la salida lineal; sin ella la imagen sale algo más oscura
+        let surface = create_surface(&window, &config).map_err(|e| failed(&e))?;
+
+        // Activar el contexto
+        let context = context
+            .make_current(&surface)
+            .map_err(|e| format!("Error make_current: {}", e))?;
+        // Sin vsync se sigue igual (algunos compositores no lo permiten)
+        let _ = surface.set_swap_interval(&context, SwapInterval::Wait(NonZeroU32::MIN));
 
         // Cargar funciones de OpenGL
-        gl::load_with(|s| context.get_proc_address(s) as *const _);
+        gl::load_with(|s| match CString::new(s) {
+            Ok(name) => display.get_proc_address(&name),
+            Err(_) => std::ptr::null(),
+        });
 
-        // Config inicial
-        unsafe {
-            gl::Enable(gl::DEPTH_TEST);
-            gl::ClearColor(0.1, 0.2, 0.3, 1.0);
+        let capabilities = GlCapabilities::query();
+        capabilities.check_minimum()?;
+        if cfg!(debug_assertions) {
+            capabilities.enable_debug_output();
         }
 
+        // El estado GL inicial (depth test, color de fondo...) lo aplica el Renderer
+        // a partir de sus RenderSettings
+
+        let size = Cell::new(window.inner_size());
         Ok(Self {
-            context
+            context,
+            surface,
+            window,
+            capabilities,
+            config,
+            size,
+            surface_lost: Cell::new(false),
         })
     }
 
+    /// Tamaño del área de dibujo en píxeles físicos
+    pub fn inner_size(&self) -> PhysicalSize<u32> {
+        self.window.inner_size()
+    }
+
     pub fn request_redraw(&self) {
-        self.context.window().request_redraw();
+        self.window.request_redraw();
+    }
+
+    /// Intercambia buffers. Si la superficie se perdió (el compositor la
+    /// invalidó o el driver la soltó) queda marcada para `restore_surface`.
+    pub fn swap_buffers(&self) -> Result<(), String> {
+        self.surface.swap_buffers(&self.context).map_err(|e| {
+            if matches!(e.error_kind(), ErrorKind::BadSurface | ErrorKind::BadCurrentSurface | ErrorKind::BadNativeWindow) {
+                self.surface_lost.set(true);
+            }
+            format!("Error swap_buffers: {}", e)
+        })
+    }
+
+    /// Se perdió la superficie o la aplicación se suspendió
+    pub fn surface_lost(&self) -> bool {
+        self.surface_lost.get()
+    }
+
+    /// Marca la superficie como inválida (al suspender la aplicación)
+    pub fn invalidate_surface(&self) {
+        self.surface_lost.set(true);
+    }
+
+    /// Crea una superficie nueva con el mismo formato si la anterior se
+    /// perdió; no hace nada si sigue siendo válida
+    pub fn restore_surface(&mut self) -> Result<(), String> {
+        if !self.surface_lost.get() {
+            return Ok(());
+        }
+        self.surface = create_surface(&self.window, &self.config)
+            .map_err(|e| format!("No se pudo recrear la superficie: {}", e))?;
+        self.context
+            .make_current(&self.surface)
+            .map_err(|e| format!("Error make_current: {}", e))?;
+        self.surface_lost.set(false);
+        self.resize(self.window.inner_size());
+        Ok(())
     }
 
-    pub fn resize(&self, new_size: glutin::dpi::PhysicalSize<u32>) {
-        self.context.resize(new_size);
+    pub fn resize(&self, new_size: PhysicalSize<u32>) {
+        if let (Some(width), Some(height)) = (NonZeroU32::new(new_size.width), NonZeroU32::new(new_size.height)) {
+            self.surface.resize(&self.context, width, height);
+        }
+        self.size.set(new_size);
         unsafe {
             gl::Viewport(0, 0, new_size.width as i32, new_size.height as i32);
         }
     }
+
+    /// Ajusta superficie y viewport si la ventana cambió de tamaño sin que
+    /// llegara un `Resized` (cambio de monitor o de escala en algunos
+    /// compositores); sin esto el frame sale estirado
+    pub fn sync_size(&self) {
+        let size = self.inner_size();
+        if size != self.size.get() {
+            self.resize(size);
+        }
+    }
+}
+
+fn create_surface(window: &WinitWindow, config: &Config) -> Result<Surface<WindowSurface>, String> {
+    let attributes = window
+        .build_surface_attributes(SurfaceAttributesBuilder::new().with_srgb(Some(config.srgb_capable())))
+        .map_err(|e| e.to_string())?;
+    unsafe { config.display().create_window_surface(config, &attributes) }.map_err(|e| e.to_string())
+}
+
+/// Elige el formato de píxel: con sRGB y las muestras de MSAA por defecto si
+/// lo hay. Algunos drivers (macOS, máquinas virtuales) no ofrecen MSAA o sRGB
+/// con perfil core; entonces se usa el más parecido.
+fn pick_config(configs: Box<dyn Iterator<Item = Config> + '_>) -> Config {
+    let samples = RenderSettings::default().msaa_samples.min(u8::MAX as u16) as u8;
+    configs
+        .max_by_key(|config| {
+            let msaa = config.num_samples();
+            (config.srgb_capable(), msaa <= samples, msaa)
+        })
+        .expect("El driver no ofrece ningún formato de píxel para OpenGL")
+}
+
+// Pruebas unitarias
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_backend() {
+        assert_eq!(Backend::parse("Wayland"), Ok(Backend::Wayland));
+        assert_eq!(Backend::parse(" x11 "), Ok(Backend::X11));
+        assert_eq!(Backend::parse(""), Ok(Backend::Auto));
+        assert!(Backend::parse("mir").is_err());
+    }
 }
@@ -0,0 +1,481 @@
+// src/graphics/xr.rs
+//
+// Integración opcional con OpenXR (feature "openxr").
+// Renderiza la escena en los dos ojos del visor con head tracking y
+// mapea los mandos a acciones de cámara:
+//  - stick izquierdo: desplazarse en la dirección de la cabeza
+//  - gatillo/grip derecho: "agarrar" el mundo y arrastrarlo
+
+use std::ptr;
+
+use glutin::context::{AsRawContext, RawContext};
+use glutin::display::{AsRawDisplay, GetGlDisplay, RawDisplay};
+use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+use openxr as xr;
+
+use crate::graphics::camara::Camera;
+use crate::graphics::gpu_resources::ContextGeneration;
+use crate::graphics::render::Renderer;
+use crate::graphics::scene_object::SceneObject;
+use crate::graphics::stereo::{stage_to_world, EyeFov, EyeView};
+use crate::graphics::window::Window;
+use crate::math::{quaternion::Quat, vec3::Vec3};
+
+const VIEW_TYPE: xr::ViewConfigurationType = xr::ViewConfigurationType::PRIMARY_STEREO;
+
+// Framebuffer propio de cada ojo: las imágenes del swapchain + un depth compartido
+struct EyeTarget {
+    swapchain: xr::Swapchain<xr::OpenGL>,
+    images: Vec<u32>,
+    fbo: u32,
+    depth: u32,
+    width: i32,
+    height: i32,
+}
+
+pub struct XrSession {
+    instance: xr::Instance,
+    session: xr::Session<xr::OpenGL>,
+    frame_waiter: xr::FrameWaiter,
+    frame_stream: xr::FrameStream<xr::OpenGL>,
+    stage: xr::Space,
+    eyes: Vec<EyeTarget>,
+    running: bool,
+
+    action_set: xr::ActionSet,
+    move_action: xr::Action<xr::Vector2f>,
+    grab_action: xr::Action<bool>,
+    hand_space: xr::Space,
+    // Posición del mando en el frame anterior mientras se agarra
+    last_grab: Option<Vec3>,
+    generation: ContextGeneration,
+}
+
+fn xr_err(what: &str) -> impl Fn(xr::sys::Result) -> String + '_ {
+    move |e| format!("OpenXR: error en {}: {:?}", what, e)
+}
+
+fn to_vec3(v: xr::Vector3f) -> Vec3 {
+    Vec3::new(v.x, v.y, v.z)
+}
+
+fn to_quat(q: xr::Quaternionf) -> Quat {
+    Quat::new(q.x, q.y, q.z, q.w)
+}
+
+impl XrSession {
+    /// Crea la instancia y la sesión OpenXR compartiendo el contexto GL de la ventana.
+    /// Solo soporta GLX (X11).
+    pub fn new(window: &Window) -> Result<Self, String> {
+        let entry = unsafe { xr::Entry::load() }
+            .map_err(|e| format!("No se pudo cargar el runtime OpenXR: {:?}", e))?;
+
+        let available = entry.enumerate_extensions().map_err(xr_err("enumerate_extensions"))?;
+        if !available.khr_opengl_enable {
+            return Err("El runtime OpenXR no soporta XR_KHR_opengl_enable".to_string());
+        }
+        let mut extensions = xr::ExtensionSet::default();
+        extensions.khr_opengl_enable = true;
+
+        let instance = entry
+            .create_instance(
+                &xr::ApplicationInfo {
+                    application_name: "Rust_Engine",
+                    application_version: 0,
+                    engine_name: "rust_engine",
+                    engine_version: 0,
+                },
+                &extensions,
+                &[],
+            )
+            .map_err(xr_err("create_instance"))?;
+
+        let system = instance
+            .system(xr::FormFactor::HEAD_MOUNTED_DISPLAY)
+            .map_err(xr_err("system (¿visor conectado?)"))?;
+
+        // Obligatorio consultarlo antes de crear la sesión
+        let _requirements = instance
+            .graphics_requirements::<xr::OpenGL>(system)
+            .map_err(xr_err("graphics_requirements"))?;
+
+        // Handles nativos de X11/GLX de la ventana
+        let x_window = match window.window.window_handle().map(|handle| handle.as_raw()) {
+            Ok(RawWindowHandle::Xlib(handle)) => handle.window,
+            _ => return Err("OpenXR: la ventana no es X11".to_string()),
+        };
+        let (x_display, glx_context) = match (window.context.display().raw_display(), window.context.raw_context()) {
+            (RawDisplay::Glx(display), RawContext::Glx(context)) => (display, context),
+            _ => return Err("OpenXR: solo se soportan contextos GLX".to_string()),
+        };
+
+        // visualid y fb_config no se pasan; los runtimes actuales usan solo
+        // display, drawable y context.
+        let (session, frame_waiter, frame_stream) = unsafe {
+            instance.create_session::<xr::OpenGL>(
+                system,
+                &xr::opengl::SessionCreateInfo::Xlib {
+                    x_display: x_display as _,
+                    visualid: 0,
+                    glx_fb_config: ptr::null_mut(),
+                    glx_drawable: x_window as _,
+                    glx_context: glx_context as _,
+                },
+            )
+        }
+        .map_err(xr_err("create_session"))?;
+
+        let stage = session
+            .create_reference_space(xr::ReferenceSpaceType::STAGE, xr::Posef::IDENTITY)
+            .or_else(|_| {
+                session.create_reference_space(xr::ReferenceSpaceType::LOCAL, xr::Posef::IDENTITY)
+            })
+            .map_err(xr_err("create_reference_space"))?;
+
+        // Swapchain por ojo
+        let formats = session
+            .enumerate_swapchain_formats()
+            .map_err(xr_err("enumerate_swapchain_formats"))?;
+        let format = if formats.contains(&gl::RGBA8) { gl::RGBA8 } else { formats[0] };
+
+        let views = instance
+            .enumerate_view_configuration_views(system, VIEW_TYPE)
+            .map_err(xr_err("enumerate_view_configuration_views"))?;
+
+        let mut eyes = Vec::with_capacity(views.len());
+        for view in &views {
+            let width = view.recommended_image_rect_width;
+            let height = view.recommended_image_rect_height;
+            let swapchain = session
+                .create_swapchain(&xr::SwapchainCreateInfo {
+                    create_flags: xr::SwapchainCreateFlags::EMPTY,
+                    usage_flags: xr::SwapchainUsageFlags::COLOR_ATTACHMENT
+                        | xr::SwapchainUsageFlags::SAMPLED,
+                    format,
+                    sample_count: 1,
+                    width,
+                    height,
+                    face_count: 1,
+                    array_size: 1,
+                    mip_count: 1,
+                })
+                .map_err(xr_err("create_swapchain"))?;
+            let images = swapchain
+                .enumerate_images()
+                .map_err(xr_err("enumerate_images"))?;
+
+            let mut fbo = 0;
+            let mut depth = 0;
+            unsafe {
+                gl::GenFramebuffers(1, &mut fbo);
+                gl::GenRenderbuffers(1, &mut depth);
+                gl::BindRenderbuffer(gl::RENDERBUFFER, depth);
+                gl::RenderbufferStorage(
+                    gl::RENDERBUFFER,
+                    gl::DEPTH_COMPONENT24,
+                    width as i32,
+                    height as i32,
+                );
+                gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+            }
+
+            eyes.push(EyeTarget {
+                swapchain,
+                images,
+                fbo,
+                depth,
+                width: width as i32,
+                height: height as i32,
+            });
+        }
+
+        // Acciones de los mandos
+        let action_set = instance
+            .create_action_set("rust_engine", "Rust Engine", 0)
+            .map_err(xr_err("create_action_set"))?;
+        let move_action = action_set
+            .create_action::<xr::Vector2f>("move", "Mover", &[])
+            .map_err(xr_err("create_action move"))?;
+        let grab_action = action_set
+            .create_action::<bool>("grab", "Agarrar", &[])
+            .map_err(xr_err("create_action grab"))?;
+        let hand_action = action_set
+            .create_action::<xr::Posef>("hand_pose", "Pose mano", &[])
+            .map_err(xr_err("create_action hand_pose"))?;
+
+        let path = |p: &str| instance.string_to_path(p).map_err(xr_err("string_to_path"));
+
+        instance
+            .suggest_interaction_profile_bindings(
+                path("/interaction_profiles/khr/simple_controller")?,
+                &[
+                    xr::Binding::new(&grab_action, path("/user/hand/right/input/select/click")?),
+                    xr::Binding::new(&hand_action, path("/user/hand/right/input/grip/pose")?),
+                ],
+            )
+            .map_err(xr_err("suggest_interaction_profile_bindings simple"))?;
+        instance
+            .suggest_interaction_profile_bindings(
+                path("/interaction_profiles/oculus/touch_controller")?,
+                &[
+                    xr::Binding::new(&move_action, path("/user/hand/left/input/thumbstick")?),
+                    xr::Binding::new(&grab_action, path("/user/hand/right/input/squeeze/value")?),
+                    xr::Binding::new(&hand_action, path("/user/hand/right/input/grip/pose")?),
+                ],
+            )
+            .map_err(xr_err("suggest_interaction_profile_bindings touch"))?;
+
+        session
+            .attach_action_sets(&[&action_set])
+            .map_err(xr_err("attach_action_sets"))?;
+
+        let hand_space = hand_action
+            .create_space(session.clone(), xr::Path::NULL, xr::Posef::IDENTITY)
+            .map_err(xr_err("create_space"))?;
+
+        Ok(Self {
+            instance,
+            session,
+            frame_waiter,
+            frame_stream,
+            stage,
+            eyes,
+            running: false,
+            action_set,
+            move_action,
+            grab_action,
+            hand_space,
+            last_grab: None,
+            generation: ContextGeneration::current(),
+        })
+    }
+
+    /// Procesa los eventos del runtime. Devuelve false si hay que cerrar la aplicación.
+    pub fn poll_events(&mut self) -> Result<bool, String> {
+        let mut buffer = xr::EventDataBuffer::new();
+        while let Some(event) = self
+            .instance
+            .poll_event(&mut buffer)
+            .map_err(xr_err("poll_event"))?
+        {
+            match event {
+                xr::Event::SessionStateChanged(e) => match e.state() {
+                    xr::SessionState::READY => {
+                        self.session.begin(VIEW_TYPE).map_err(xr_err("session.begin"))?;
+                        self.running = true;
+                    }
+                    xr::SessionState::STOPPING => {
+                        self.session.end().map_err(xr_err("session.end"))?;
+                        self.running = false;
+                    }
+                    xr::SessionState::EXITING | xr::SessionState::LOSS_PENDING => {
+                        return Ok(false);
+                    }
+                    _ => {}
+                },
+                xr::Event::InstanceLossPending(_) => return Ok(false),
+                _ => {}
+            }
+        }
+        Ok(true)
+    }
+
+    /// Renderiza un frame en el visor. Bloquea hasta el siguiente frame del runtime.
+    pub fn render_frame(
+        &mut self,
+        renderer: &mut Renderer,
+        objects: &[SceneObject],
+        camera: &mut Camera,
+        global_scale: f32,
+        dt: f32,
+    ) -> Result<(), String> {
+        if !self.running {
+            return Ok(());
+        }
+
+        let frame_state = self.frame_waiter.wait().map_err(xr_err("wait"))?;
+        self.frame_stream.begin().map_err(xr_err("begin"))?;
+        let time = frame_state.predicted_display_time;
+
+        if !frame_state.should_render {
+            self.frame_stream
+                .end(time, xr::EnvironmentBlendMode::OPAQUE, &[])
+                .map_err(xr_err("end"))?;
+            return Ok(());
+        }
+
+        let (_, views) = self
+            .session
+            .locate_views(VIEW_TYPE, time, &self.stage)
+            .map_err(xr_err("locate_views"))?;
+
+        self.process_input(camera, &views, time, dt)?;
+
+        // El buffer de picking, el cubo de navegación, la vista de UVs y el texto son de la ventana, no de los ojos del visor
+        renderer.graph.set_enabled("picking", false);
+        renderer.graph.set_enabled("nav_cube", false);
+        renderer.graph.set_enabled("uv_layout", false);
+        renderer.graph.set_enabled("text", false);
+        for (eye, view) in self.eyes.iter_mut().zip(&views) {
+            let index = eye.swapchain.acquire_image().map_err(xr_err("acquire_image"))?;
+            eye.swapchain
+                .wait_image(xr::Duration::INFINITE)
+                .map_err(xr_err("wait_image"))?;
+
+            let eye_view = EyeView {
+                position: to_vec3(view.pose.position),
+                orientation: to_quat(view.pose.orientation),
+                fov: EyeFov {
+                    angle_left: view.fov.angle_left,
+                    angle_right: view.fov.angle_right,
+                    angle_up: view.fov.angle_up,
+                    angle_down: view.fov.angle_down,
+                },
+            };
+
+            unsafe {
+                gl::BindFramebuffer(gl::FRAMEBUFFER, eye.fbo);
+                gl::FramebufferTexture2D(
+                    gl::FRAMEBUFFER,
+                    gl::COLOR_ATTACHMENT0,
+                    gl::TEXTURE_2D,
+                    eye.images[index as usize],
+                    0,
+                );
+                gl::FramebufferRenderbuffer(
+                    gl::FRAMEBUFFER,
+                    gl::DEPTH_ATTACHMENT,
+                    gl::RENDERBUFFER,
+                    eye.depth,
+                );
+                gl::Viewport(0, 0, eye.width, eye.height);
+                gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+            }
+
+            renderer.render_view(
+                objects,
+                eye_view.view_matrix(camera),
+                eye_view.projection_matrix(0.01, 1000.0),
+                global_scale,
+                (eye.width, eye.height),
+            );
+
+            unsafe {
+                gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            }
+            eye.swapchain.release_image().map_err(xr_err("release_image"))?;
+        }
+        renderer.graph.set_enabled("picking", true);
+        renderer.graph.set_enabled("nav_cube", true);
+        renderer.graph.set_enabled("uv_layout", true);
+        renderer.graph.set_enabled("text", true);
+
+        let projection_views: Vec<_> = self
+            .eyes
+            .iter()
+            .zip(&views)
+            .map(|(eye, view)| {
+                xr::CompositionLayerProjectionView::new()
+                    .pose(view.pose)
+                    .fov(view.fov)
+                    .sub_image(
+                        xr::SwapchainSubImage::new()
+                            .swapchain(&eye.swapchain)
+                            .image_array_index(0)
+                            .image_rect(xr::Rect2Di {
+                                offset: xr::Offset2Di { x: 0, y: 0 },
+                                extent: xr::Extent2Di {
+                                    width: eye.width,
+                                    height: eye.height,
+                                },
+                            }),
+                    )
+            })
+            .collect();
+
+        self.frame_stream
+            .end(
+                time,
+                xr::EnvironmentBlendMode::OPAQUE,
+                &[&xr::CompositionLayerProjection::new()
+                    .space(&self.stage)
+                    .views(&projection_views)],
+            )
+            .map_err(xr_err("end"))?;
+
+        Ok(())
+    }
+
+    // Stick -> desplazamiento, grab -> arrastrar el mundo
+    fn process_input(
+        &mut self,
+        camera: &mut Camera,
+        views: &[xr::View],
+        time: xr::Time,
+        dt: f32,
+    ) -> Result<(), String> {
+        self.session
+            .sync_actions(&[(&self.action_set).into()])
+            .map_err(xr_err("sync_actions"))?;
+
+        // Desplazamiento en el plano horizontal según hacia dónde mira la cabeza
+        let stick = self
+            .move_action
+            .state(&self.session, xr::Path::NULL)
+            .map_err(xr_err("move state"))?;
+        if stick.is_active {
+            if let Some(view) = views.first() {
+                let (_, head) = stage_to_world(camera, Vec3::ZERO, to_quat(view.pose.orientation));
+                let mut forward = head.rotate(Vec3::new(0.0, 0.0, -1.0));
+                forward.y = 0.0;
+                let mut right = head.rotate(Vec3::UNIT_X);
+                right.y = 0.0;
+                let velocity = camera.speed * dt;
+                camera.position += forward * (stick.current_state.y * velocity);
+                camera.position += right * (stick.current_state.x * velocity);
+            }
+        }
+
+        // Agarrar: mover la cámara en sentido contrario a la mano
+        let grab = self
+            .grab_action
+            .state(&self.session, xr::Path::NULL)
+            .map_err(xr_err("grab state"))?;
+        let hand = self
+            .hand_space
+            .locate(&self.stage, time)
+            .map_err(xr_err("hand locate"))?;
+        let tracked = hand
+            .location_flags
+            .contains(xr::SpaceLocationFlags::POSITION_VALID);
+
+        if grab.is_active && grab.current_state && tracked {
+            let (hand_world, _) = stage_to_world(camera, to_vec3(hand.pose.position), Quat::IDENTITY);
+            // La mano se mide relativa a la cámara, que se mueve con el arrastre:
+            // comparamos en coordenadas locales del stage para no realimentar el movimiento.
+            let hand_local = hand_world - camera.position;
+            if let Some(last) = self.last_grab {
+                camera.position -= hand_local - last;
+            }
+            self.last_grab = Some(hand_local);
+        } else {
+            self.last_grab = None;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for XrSession {
+    fn drop(&mut self) {
+        if !self.generation.is_current() {
+            return;
+        }
+        unsafe {
+            for eye in &self.eyes {
+                gl::DeleteFramebuffers(1, &eye.fbo);
+                gl::DeleteRenderbuffers(1, &eye.depth);
+            }
+        }
+    }
+}
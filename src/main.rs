@@ -1,152 +1,1799 @@
 // src/main.rs
 
-pub mod math;
+pub use rust_engine_math as math;
 pub mod graphics;
+pub mod engine;
 
-use graphics::window::Window; // nuestra abstracción de la ventana
+use engine::input::Input;
+use engine::keymap::Keymap;
+use engine::plugin::Engine;
+use engine::time::{Determinism, FrameClock};
+use engine::startup::{StartupScript, TurntablePlugin};
+use engine::remote::RemotePlugin;
+use engine::watch::WatchPlugin;
+use engine::profiler::Profiler;
+use engine::progress::{progress_overlay, BackgroundTask, PROGRESS_LAYER};
+use engine::crash;
+use engine::settings::{EditSettings, EngineSettings, FrameSchedule, WindowActivity};
+use graphics::gpu_resources;
+use graphics::window::{Backend, Window}; // nuestra abstracción de la ventana
 use graphics::render::Renderer;
 use graphics::scene_object::SceneObject;
-use graphics::camara::Camera;
+use graphics::camara::{Camera, CameraMode, View, VIEW_TRANSITION};
+use graphics::scene::Scene;
+use graphics::lighting::{Lighting, LightingRig};
+use graphics::color::distinct_colors;
+use graphics::material::MaterialLibrary;
+use graphics::render_preset::{Heatmap, PresetLibrary, RenderPreset};
+use graphics::picking::{PickMode, SubObjectHit};
+use graphics::align::{self, Mate};
+use graphics::lightmap::{bake_scene, BakeSettings, Lightmap, LightmapMode};
+use graphics::path_tracer::TraceScene;
+use graphics::still_render::{StillRender, StillRenderSettings};
+use graphics::capture::AuxBuffer;
+use graphics::dataset::{self, DatasetSpec};
+use graphics::batch::{self, BatchScript};
+use graphics::thumbnails::{self, ThumbnailSettings};
+use graphics::compare::compare;
+use graphics::printability::{self, overhang_colors, OverhangSettings};
+use graphics::thickness::{self, thickness_colors, ThicknessSettings};
+use graphics::slicing::{save_dxf, save_svg, slice_objects};
+use graphics::ortho_views::{self, OrthoSettings};
+use graphics::scale_bar::LengthUnit;
+use graphics::lines::Polyline;
+use graphics::normal_debug::{invalid_normals, normal_lines, tangent_lines, VertexNormalReport, NORMALS_LAYER, TANGENTS_LAYER};
+use graphics::skeleton::{skeleton_lines, JointReport, SKELETON_LAYER};
+use graphics::robot::JointKind;
+use graphics::frames::{frame_labels, frame_lines, to_degrees, ReferenceFrame, AXIS_LENGTH, FRAMES_LAYER, LABEL_PREFIX};
+use graphics::text::TextPanel;
+use graphics::hull::DecompositionSettings;
+use graphics::mesh_ops::transformed;
 
-use math::{matrix_4_by_4::Matrix4, vec3::Vec3};
+use math::{
+    aabb::Aabb, frustum::Frustum, matrix_4_by_4::Matrix4, quaternion::Quat, ray::Ray,
+    transform::Transform, vec3::Vec3, weld::DEFAULT_TOLERANCE,
+};
 
-use glutin::event::{DeviceEvent, ElementState, Event, KeyboardInput, MouseButton, VirtualKeyCode, WindowEvent};
-use glutin::event_loop::{ControlFlow, EventLoop};
+use winit::application::ApplicationHandler;
+use winit::event::{DeviceEvent, DeviceId, ElementState, KeyEvent, MouseButton, WindowEvent};
+use winit::event_loop::{ActiveEventLoop, ControlFlow};
+use winit::keyboard::{KeyCode, PhysicalKey};
+use winit::window::WindowId;
 use std::collections::HashSet;
 use std::time::Instant;
 
+/// Segundos que mueven las teclas , y . en las animaciones
+const ANIMATION_STEP: f32 = 0.25;
+/// Duración en segundos del fundido al pasar de clip con /
+const ANIMATION_FADE: f32 = 0.5;
+/// Giro en radianes de las teclas - y = sobre el grupo seleccionado
+const GROUP_ROTATION_STEP: f32 = std::f32::consts::PI / 12.0;
+/// Radianes que mueven las teclas 4 y 6 del teclado numérico una articulación de giro
+const JOINT_ROTATION_STEP: f32 = std::f32::consts::PI / 36.0;
+/// Metros que mueven las teclas 4 y 6 del teclado numérico una articulación deslizante
+const JOINT_SLIDE_STEP: f32 = 0.01;
+/// Cuánto separan o juntan las teclas [ y ] los objetos del grupo
+const GROUP_EXPLODE_STEP: f32 = 0.25;
+/// Cada cuántos frames se actualiza lo que va al informe de errores
+const CRASH_CONTEXT_FRAMES: u64 = 120;
+
 fn main() {
-    // 1) Crear event loop
-    let event_loop = EventLoop::new();
+    // Un panic deja un crash-<segundos>.log con el estado del motor
+    crash::install();
 
-    // 2) Crear ventana y contexto OpenGL
-    let window = Window::new("Rust_Engine", 1200, 900, &event_loop)
-        .expect("No se pudo crear la ventana!");
+    // Modos por lotes sin ventana visible:
+    //   `rust_engine dataset spec.ron`   genera un dataset sintético
+    //   `rust_engine batch script.ron`   ejecuta un script de operaciones sobre mallas
+    //   `rust_engine thumbnails piezas/ miniaturas/ [--size N] [--transparent]`
+    //                                    una miniatura isométrica por modelo
+    //   `rust_engine views pieza.stl vistas.svg`  vistas ortogonales acotadas (SVG o PNG)
+    //   `rust_engine check-gl`           informa qué OpenGL hay (o por qué no se pudo crear el contexto)
+    // `rust_engine startup kiosco.ron` abre la ventana con un script de arranque (ver engine::startup).
+    let command = std::env::args().nth(1);
+    if command.as_deref() == Some("check-gl") {
+        if let Err(e) = check_gl() {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if command.as_deref() == Some("thumbnails") {
+        let args: Vec<String> = std::env::args().skip(2).collect();
+        let [input, output, options @ ..] = args.as_slice() else {
+            eprintln!("Uso: rust_engine thumbnails <carpeta de modelos> <carpeta de salida> [--size N] [--transparent]");
+            std::process::exit(2);
+        };
+        if let Err(e) = run_thumbnails(input, output, options) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if command.as_deref() == Some("views") {
+        let (Some(model), Some(output)) = (std::env::args().nth(2), std::env::args().nth(3)) else {
+            eprintln!("Uso: rust_engine views <modelo o escena.ron> <salida.svg|png>");
+            std::process::exit(2);
+        };
+        if let Err(e) = run_views(&model, &output) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if let Some(command @ ("dataset" | "batch")) = command.as_deref() {
+        let Some(file) = std::env::args().nth(2) else {
+            eprintln!("Uso: rust_engine {} <archivo.ron>", command);
+            std::process::exit(2);
+        };
+        let result = if command == "dataset" { run_dataset(&file) } else { run_batch(&file) };
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
-    // 3) Crear un Renderer
-    let renderer = Renderer::new("src/graphics/shaders/basic.vert", "src/graphics/shaders/basic.frag")
-        .expect("No se pudo inicializar el renderer");
+    // 1) Crear event loop. La ventana, el renderer y la escena se crean en
+    //    `App::resumed`, que es cuando la plataforma deja crear ventanas.
+    //    El backend (Wayland / X11) se elige con RUST_ENGINE_BACKEND.
+    let event_loop = Backend::from_env().event_loop().expect("No se pudo crear el event loop");
+    event_loop.set_control_flow(ControlFlow::Poll);
+    let mut app = App::default();
+    event_loop.run_app(&mut app).expect("Error en el event loop");
+}
 
-    // 4) Crear lista de objetos
-    let mut objects: Vec<SceneObject> = Vec::new();
+/// Controles del visor, en el orden en que se muestran en la ayuda (F1)
+fn viewer_keymap() -> Result<Keymap, String> {
+    let mut keymap = Keymap::new();
+    keymap.bind(KeyCode::F1, "Mostrar / ocultar esta ayuda")?;
+    keymap.bind(KeyCode::Tab, "Perfilador: tiempos de frame, CPU y GPU")?;
+    keymap.bind(KeyCode::Escape, "Cancelar el horneado o render en curso / salir")?;
+    keymap.bind_input("W A S D", "Mover la cámara");
+    keymap.bind_input("Espacio / Shift", "Subir / bajar (al caminar: saltar)");
+    keymap.bind_input("Click derecho", "Arrastrar para girar la cámara");
+    keymap.bind_input("Click izquierdo", "Seleccionar / cubo de navegación");
+    keymap.bind(KeyCode::KeyP, "Selección de objetos / sub-objetos / grupos")?;
+    keymap.bind(KeyCode::KeyG, "Vuelo libre / caminar")?;
+    keymap.bind(KeyCode::Digit1, "Vista frontal")?;
+    keymap.bind(KeyCode::Digit2, "Vista derecha")?;
+    keymap.bind(KeyCode::Digit3, "Vista superior")?;
+    keymap.bind(KeyCode::Digit4, "Vista isométrica")?;
+    keymap.bind(KeyCode::Home, "Encuadrar la escena")?;
+    keymap.bind(KeyCode::KeyB, "Guardar la vista (bookmark)")?;
+    keymap.bind(KeyCode::KeyN, "Siguiente bookmark")?;
+    keymap.bind(KeyCode::KeyQ, "Agrandar la escena")?;
+    keymap.bind(KeyCode::KeyE, "Achicar la escena")?;
+    keymap.bind(KeyCode::KeyF, "Alambre")?;
+    keymap.bind(KeyCode::KeyC, "Descartar caras traseras")?;
+    keymap.bind(KeyCode::KeyV, "Líneas ocultas (dibujo técnico)")?;
+    keymap.bind(KeyCode::F5, "Guardar la escena en scene.ron")?;
+    keymap.bind(KeyCode::KeyL, "Hornear oclusión ambiental")?;
+    keymap.bind(KeyCode::KeyK, "Hornear iluminación global")?;
+    keymap.bind(KeyCode::KeyR, "Render de alta calidad")?;
+    keymap.bind(KeyCode::KeyT, "Guardar el render en curso")?;
+    keymap.bind(KeyCode::F12, "Captura PNG + EXR")?;
+    keymap.bind(KeyCode::F11, "Profundidad, normales e ids")?;
+    keymap.bind(KeyCode::KeyO, "Análisis de voladizos")?;
+    keymap.bind(KeyCode::KeyH, "Análisis de espesor")?;
+    keymap.bind(KeyCode::KeyI, "Volumen, área y centro de masa")?;
+    keymap.bind(KeyCode::KeyX, "Vista de corte")?;
+    keymap.bind(KeyCode::PageUp, "Subir el plano de corte")?;
+    keymap.bind(KeyCode::PageDown, "Bajar el plano de corte")?;
+    keymap.bind(KeyCode::F6, "Exportar el corte a SVG y DXF")?;
+    keymap.bind(KeyCode::KeyJ, "Partes convexas para colisión")?;
+    keymap.bind(KeyCode::KeyM, "Normales por vértice (click: inspeccionar)")?;
+    keymap.bind(KeyCode::KeyU, "Damero sobre las UVs")?;
+    keymap.bind(KeyCode::KeyY, "Tangentes y bitangentes")?;
+    keymap.bind(KeyCode::F9, "Esqueletos (click: inspeccionar articulación)")?;
+    keymap.bind(KeyCode::F10, "Animaciones: reproducir / pausar")?;
+    keymap.bind(KeyCode::Comma, "Animaciones: retroceder")?;
+    keymap.bind(KeyCode::Period, "Animaciones: adelantar")?;
+    keymap.bind(KeyCode::Slash, "Animaciones: siguiente clip (con fundido)")?;
+    keymap.bind(KeyCode::KeyZ, "Vista 2D de UVs (siguiente objeto)")?;
+    keymap.bind(KeyCode::F2, "Siguiente iluminación (estudio, exterior, ambiente)")?;
+    keymap.bind(KeyCode::F3, "Exterior: una hora antes")?;
+    keymap.bind(KeyCode::F4, "Exterior: una hora después")?;
+    keymap.bind(KeyCode::F7, "Exterior: hacer correr el día")?;
+    keymap.bind(KeyCode::F8, "Un color distinto por objeto")?;
+    keymap.bind(KeyCode::Digit5, "Agrupar los sólidos de cada archivo")?;
+    keymap.bind(KeyCode::Digit6, "Grupo seleccionado: ocultar / mostrar")?;
+    keymap.bind(KeyCode::Digit7, "Grupo seleccionado: color propio")?;
+    keymap.bind(KeyCode::Digit8, "Deshacer el grupo seleccionado")?;
+    keymap.bind(KeyCode::Minus, "Grupo seleccionado: girar a la izquierda")?;
+    keymap.bind(KeyCode::Equal, "Grupo seleccionado: girar a la derecha")?;
+    keymap.bind(KeyCode::BracketLeft, "Grupo seleccionado: juntar")?;
+    keymap.bind(KeyCode::BracketRight, "Grupo seleccionado: explotar")?;
+    keymap.bind(KeyCode::Digit9, "Aislar la selección (objeto o grupo)")?;
+    keymap.bind(KeyCode::Digit0, "Ocultar la selección")?;
+    keymap.bind(KeyCode::Backquote, "Mostrar todo")?;
+    keymap.bind(KeyCode::Backspace, "Deshacer el último cambio de visibilidad")?;
+    keymap.bind(KeyCode::Semicolon, "Caja de sección alrededor de la selección")?;
+    keymap.bind(KeyCode::Quote, "La selección ignora / respeta el corte")?;
+    keymap.bind(KeyCode::NumpadSubtract, "Separar el objeto seleccionado en sus piezas sueltas")?;
+    keymap.bind(KeyCode::NumpadAdd, "Unir los objetos del grupo seleccionado en una malla")?;
+    keymap.bind(KeyCode::Numpad1, "Apoyar la cara elegida sobre la otra (dos clicks en modo sub-objetos)")?;
+    keymap.bind(KeyCode::Numpad2, "Alinear el eje del cilindro elegido con el otro")?;
+    keymap.bind(KeyCode::Numpad3, "Llevar el punto elegido sobre el otro")?;
+    keymap.bind(KeyCode::NumpadDivide, "Ejes de los giros: mundo, local o pantalla")?;
+    keymap.bind(KeyCode::Numpad7, "Girar el objeto seleccionado 90° en X")?;
+    keymap.bind(KeyCode::Numpad8, "Girar el objeto seleccionado 90° en Y")?;
+    keymap.bind(KeyCode::Numpad9, "Girar el objeto seleccionado 90° en Z")?;
+    keymap.bind(KeyCode::NumpadMultiply, "Marcos de referencia (click: pose del objeto en cada uno)")?;
+    keymap.bind(KeyCode::NumpadDecimal, "Marco de referencia en el objeto seleccionado")?;
+    keymap.bind(KeyCode::Numpad5, "Robots: siguiente articulación")?;
+    keymap.bind(KeyCode::Numpad4, "Robots: mover la articulación hacia atrás")?;
+    keymap.bind(KeyCode::Numpad6, "Robots: mover la articulación hacia adelante")?;
+    keymap.bind(KeyCode::Numpad0, "Robots: pausar o seguir las trayectorias")?;
+    keymap.bind(KeyCode::Backslash, "Siguiente preset de render")?;
+    keymap.bind(KeyCode::End, "Guardar los ajustes como preset \"Personalizado\"")?;
+    keymap.bind(KeyCode::Insert, "Fondo transparente en capturas y renders")?;
+    keymap.bind(KeyCode::Delete, "Vistas ortogonales acotadas (SVG y PNG)")?;
+    keymap.bind(KeyCode::Pause, "Barra de escala (también en capturas)")?;
+    keymap.bind(KeyCode::ScrollLock, "Unidades de la escena (mm, cm, m, in, ft)")?;
+    Ok(keymap)
+}
 
-    // objeto 1
-    let mut obj1 = SceneObject::create_object_from_stl("src/assets/pieza.stl");
-    obj1.base_transform = Matrix4::translate(0.0, 0.0, 0.0);
-    obj1.angle = 0.0;
-    obj1.angular_speed = 1.0;
-    obj1.scale_factor = 1.0;
-    objects.push(obj1);
+/// Aplicación con ventana; el estado existe desde el primer `resumed`
+#[derive(Default)]
+struct App {
+    viewer: Option<Viewer>,
+}
 
-    // objeto 2
-    let mut obj2 = SceneObject::create_object_from_stl("src/assets/pieza1.stl");
-    obj2.base_transform = Matrix4::translate(-60.01, 0.01, 0.01);
-    obj2.angle = 0.5;
-    obj2.angular_speed = -2.0;
-    obj2.scale_factor = 1.0;
-    objects.push(obj2);
+/// Lightmaps horneados en un hilo: (índice del objeto, lightmap)
+type BakeTask = BackgroundTask<Vec<(usize, Lightmap)>>;
 
-    // 5) Cámara
-    let mut camera = Camera::new(Vec3::new(0.0, 0.0, 100.5));
+/// Todo lo que vive mientras la ventana está abierta
+struct Viewer {
+    window: Window,
+    renderer: Renderer,
+    #[cfg(feature = "openxr")]
+    xr_session: Option<graphics::xr::XrSession>,
+    scene: Scene,
+    camera: Camera,
+    bookmark_index: usize,
+    // Estado de inputs
+    right_button_pressed: bool,
+    pick_mode: PickMode,
+    /// Objeto elegido con click en modo objetos
+    selected_object: Option<usize>,
+    /// Grupo elegido con click en modo grupos (las teclas 6 a 8 y - = [ ] lo modifican)
+    selected_group: Option<String>,
+    scale_factor: f32,
+    /// Render de alta calidad en CPU en curso
+    still_render: Option<StillRender>,
+    /// Horneado de lightmaps en segundo plano y cuándo empezó
+    bake_task: Option<(BakeTask, Instant)>,
+    /// Análisis que colorea los objetos (tecla que lo activó: O voladizos, H espesor)
+    color_view: Option<KeyCode>,
+    /// Presets de render y el último aplicado
+    presets: PresetLibrary,
+    render_preset: Option<String>,
+    /// Altura del plano de corte horizontal (None = sin vista de corte)
+    slice_height: Option<f32>,
+    hulls_visible: bool,
+    /// Normales por vértice dibujadas; el click informa el vértice
+    normals_visible: bool,
+    tangents_visible: bool,
+    /// Esqueletos dibujados; el click elige una articulación (objeto, índice)
+    skeletons_visible: bool,
+    selected_joint: Option<(usize, usize)>,
+    /// Marcos de referencia dibujados; al seleccionar un objeto se informa
+    /// su pose en cada uno
+    frames_visible: bool,
+    /// Articulación (robot, nombre) que mueven las teclas 4 y 6 del teclado numérico
+    robot_joint: Option<(String, String)>,
+    /// Últimos dos elementos elegidos en modo sub-objetos: las teclas del
+    /// teclado numérico 1 a 3 alinean el primero con el segundo
+    mate_picks: Vec<SubObjectHit>,
+    /// Ayuda de teclas en pantalla (F1)
+    help_visible: bool,
+    /// Tiempos de los últimos frames; se dibujan con Tab
+    profiler: Profiler,
+    profiler_visible: bool,
+    /// Subsistemas opcionales (se registran con `engine.add_plugin`)
+    engine: Engine,
+    /// Para delta_time
+    clock: FrameClock,
+    /// Guarda las teclas presionadas
+    pressed_keys: HashSet<KeyCode>,
+    /// La plataforma suspendió la aplicación (sin superficie hasta `resumed`)
+    suspended: bool,
+    /// La ventana tiene el foco del teclado
+    focused: bool,
+    /// El compositor avisó que la ventana no se ve (tapada o en otro escritorio)
+    occluded: bool,
+    /// Inicio del último frame, para el ritmo sin el foco
+    last_frame: Instant,
+    /// Script con que arrancó (vacío si no hubo); la ventana se vuelve a configurar al recrearla
+    startup: StartupScript,
+}
 
-    // 6) Estado de inputs
-    let mut right_button_pressed = false;
-    let mut scale_factor = 0.05;
+impl Viewer {
+    fn new(event_loop: &ActiveEventLoop) -> Self {
+        // 2) Crear ventana y contexto OpenGL
+        let window = Window::new("Rust_Engine", 1200, 900, event_loop)
+            .expect("No se pudo crear la ventana!");
 
-    // Para delta_time
-    let mut last_frame_time = Instant::now();
+        // 3) Crear un Renderer
+        let mut renderer = Renderer::new("src/graphics/shaders/basic.vert", "src/graphics/shaders/basic.frag")
+            .expect("No se pudo inicializar el renderer");
+
+        // 3b) Sesión VR opcional (si no hay runtime/visor se sigue solo con la ventana)
+        #[cfg(feature = "openxr")]
+        let xr_session = match graphics::xr::XrSession::new(&window) {
+            Ok(session) => Some(session),
+            Err(e) => {
+                eprintln!("VR desactivado: {}", e);
+                None
+            }
+        };
+
+        // 4) Script de arranque opcional (`rust_engine startup kiosco.ron` o RUST_ENGINE_STARTUP)
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let startup = StartupScript::from_args_or_env(&args)
+            .expect("No se pudo cargar el script de arranque")
+            .unwrap_or_default();
+
+        // 4b) Crear la escena: la del script, carpeta, modelo o archivo .ron por línea de comandos,
+        //     o la escena de ejemplo.
+        //     `rust_engine compare medido.stl referencia.stl` muestra la desviación entre dos versiones.
+        let scene = match (&startup.scene, args.first().map(String::as_str)) {
+            (Some(path), _) => Scene::open(path),
+            (None, Some("compare")) => match &args[1..] {
+                [measured, reference] => comparison_scene(measured, reference),
+                _ => Err("Uso: rust_engine compare <medido> <referencia>".to_string()),
+            },
+            (None, Some("startup") | None) => Ok(default_scene()),
+            (None, Some(path)) => Scene::open(path),
+        };
+        let mut scene = scene.expect("No se pudo cargar la escena");
+        // Escena .ron abierta, para el modo watch
+        let scene_file = match (&startup.scene, args.first().map(String::as_str)) {
+            (Some(path), _) => Some(path.as_str()),
+            (None, Some("compare" | "startup") | None) => None,
+            (None, Some(path)) => Some(path),
+        }
+        .filter(|path| path.to_lowercase().ends_with(".ron"));
 
-    //Guarda la letra precioada 
-    let mut pressed_keys: HashSet<VirtualKeyCode> = HashSet::new();
+        // 5) Cámara
+        let mut camera = Camera::new(Vec3::new(0.0, 0.0, 100.5));
+        let scale_factor = 0.05;
 
-    // 7) Event loop
-    event_loop.run(move |event, _, control_flow| {
-        *control_flow = ControlFlow::Poll;
+        // 6) Plugins. Modo determinista: paso fijo y Rng con semilla, para capturas reproducibles
+        let mut engine = Engine::new();
+        let determinism = Determinism::from_env();
+        if let Some(determinism) = &determinism {
+            engine.seed(determinism.seed);
+            println!("Modo determinista: semilla {}, paso {:.4} s", determinism.seed, determinism.timestep);
+        }
+        engine.settings = EngineSettings::from_env();
+        engine.editing = EditSettings::from_env();
+        engine.input = Input::new(window.inner_size(), window.window.scale_factor());
+        // Los controles del visor primero; los plugins suman los suyos en `init`
+        engine.keymap = viewer_keymap().expect("Teclas del visor repetidas");
+        renderer.text().set_scale(window.window.scale_factor() as f32);
+        if let Some(turntable) = startup.turntable {
+            engine.add_plugin(TurntablePlugin::new(turntable)).expect("Plugin de giro repetido");
+        }
+        set_crash_gl_info(&window);
+        if let Some(remote) = startup.remote.as_deref().map(RemotePlugin::new).or_else(RemotePlugin::from_env) {
+            engine.add_plugin(remote).expect("Plugin remoto repetido");
+        }
+        #[cfg(feature = "bridge")]
+        if let Some(bridge) = engine::bridge::BridgePlugin::from_env() {
+            engine.add_plugin(bridge).expect("Plugin de puente repetido");
+        }
+        if startup.watch || WatchPlugin::enabled_by_env() {
+            engine.add_plugin(WatchPlugin::new(scene_file)).expect("Plugin de watch repetido");
+        }
+        engine.init(&mut scene, &mut renderer, &mut camera, scale_factor);
+        renderer.lighting = scene.lighting();
+
+        // 7) Resto del script de arranque
+        if let Some(path) = &startup.environment {
+            if let Err(e) = renderer.set_environment_hdr(path) {
+                eprintln!("{}", e);
+            }
+        }
+        let materials = match &startup.materials {
+            Some(path) => MaterialLibrary::load(path).map_err(|e| eprintln!("{}", e)).ok(),
+            None => MaterialLibrary::from_env(),
+        };
+        if let Some(materials) = materials {
+            for obj in &scene.objects {
+                if let Some(name) = obj.material.as_deref().filter(|name| !materials.contains(name)) {
+                    eprintln!("El objeto '{}' usa el material '{}', que no está en la biblioteca", obj.name, name);
+                }
+            }
+            renderer.set_material_library(materials);
+        }
+        if let Some(name) = &startup.animation {
+            let animators = scene.objects.iter_mut().filter_map(|obj| obj.animator.as_mut());
+            let started = animators.map(|animator| animator.play(name)).filter(Result::is_ok).count();
+            if started == 0 {
+                eprintln!("Ningún objeto tiene la animación '{}'", name);
+            }
+        }
+        if let Some(name) = &startup.bookmark {
+            if let Err(e) = scene.recall_bookmark(name, &mut camera) {
+                eprintln!("{}", e);
+            }
+        }
+        let presets = match &startup.render_presets {
+            Some(path) => PresetLibrary::load(path).map_err(|e| eprintln!("{}", e)).ok(),
+            None => PresetLibrary::from_env(),
+        }
+        .unwrap_or_default();
+        let mut color_view = None;
+        let render_preset = startup.render_preset.as_deref().and_then(|name| match presets.get(name) {
+            Some(preset) => {
+                color_view = apply_render_preset(preset, &mut renderer, &mut scene, scale_factor);
+                Some(name.to_string())
+            }
+            None => {
+                eprintln!("No existe el preset de render '{}'", name);
+                None
+            }
+        });
+        startup.apply_window(&window.window);
+
+        Self {
+            window,
+            renderer,
+            #[cfg(feature = "openxr")]
+            xr_session,
+            scene,
+            camera,
+            bookmark_index: 0,
+            right_button_pressed: false,
+            pick_mode: PickMode::Object,
+            selected_object: None,
+            selected_group: None,
+            scale_factor,
+            still_render: None,
+            bake_task: None,
+            color_view,
+            presets,
+            render_preset,
+            slice_height: None,
+            hulls_visible: false,
+            normals_visible: false,
+            tangents_visible: false,
+            skeletons_visible: false,
+            selected_joint: None,
+            frames_visible: false,
+            robot_joint: None,
+            mate_picks: Vec::new(),
+            help_visible: false,
+            profiler: Profiler::new(),
+            profiler_visible: false,
+            engine,
+            clock: FrameClock::new(determinism.as_ref()),
+            pressed_keys: HashSet::new(),
+            suspended: false,
+            focused: true,
+            occluded: false,
+            last_frame: Instant::now(),
+            startup,
+        }
+    }
+
+    /// input de ventana (KeyboardInput, MouseInput, etc.)
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, event: WindowEvent) {
+        // Los plugins ven cada evento de la ventana antes que el motor
+        self.engine.event(&mut self.scene, &mut self.renderer, &mut self.camera, self.scale_factor, &event);
 
         match event {
-            // input de mouse a nivel de Device
-            Event::DeviceEvent { event, .. } => {
-                match event {
-                    DeviceEvent::MouseMotion { delta: (dx, dy) } => {
-                        if right_button_pressed {
-                            camera.process_mouse(dx as f32, dy as f32);
+            WindowEvent::CloseRequested => event_loop.exit(),
+            // `engine.input` ya tiene la posición nueva
+            WindowEvent::CursorMoved { .. } => {
+                if let Some((x, y)) = self.engine.input.cursor_pixel() {
+                    self.renderer.hover_nav_cube(x, y);
+                }
+            }
+            WindowEvent::MouseInput { button, state, .. } => self.mouse_input(button, state),
+            WindowEvent::KeyboardInput {
+                event: KeyEvent { physical_key: PhysicalKey::Code(key), state, .. },
+                ..
+            } => match state {
+                ElementState::Pressed => {
+                    // Insertamos en el HashSet
+                    self.pressed_keys.insert(key);
+                    self.key_pressed(event_loop, key);
+                }
+                ElementState::Released => {
+                    // Quitamos la tecla del set
+                    self.pressed_keys.remove(&key);
+                }
+            },
+            WindowEvent::Resized(new_size) => {
+                self.window.resize(new_size);
+            }
+            // Cambio de monitor o de escala: el tamaño físico cambia aunque no
+            // llegue un Resized en todos los compositores
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                self.window.sync_size();
+                self.renderer.text().set_scale(scale_factor as f32);
+            }
+            WindowEvent::Focused(focused) => {
+                self.focused = focused;
+                if !focused {
+                    // Las teclas que se suelten en otra ventana no llegan
+                    self.pressed_keys.clear();
+                }
+            }
+            WindowEvent::Occluded(occluded) => self.occluded = occluded,
+            // Redibujar
+            WindowEvent::RedrawRequested => self.redraw(event_loop),
+            _ => {}
+        }
+    }
+
+    fn mouse_input(&mut self, button: MouseButton, state: ElementState) {
+        if button == MouseButton::Right {
+            self.right_button_pressed = state == ElementState::Pressed;
+        }
+        // Click izquierdo: seleccionar el objeto bajo el cursor
+        if button == MouseButton::Left && state == ElementState::Pressed {
+            let Some((x, y)) = self.engine.input.cursor_pixel() else {
+                return;
+            };
+            // El cubo de navegación tiene prioridad sobre la escena
+            if let Some(region) = self.renderer.nav_cube_hit(x, y) {
+                self.camera.orbit_to(region.direction(), self.camera.pivot, VIEW_TRANSITION);
+                return;
+            }
+            // Con los esqueletos a la vista el click elige una articulación
+            if self.skeletons_visible {
+                self.inspect_joint(x, y);
+                return;
+            }
+            // Con las normales a la vista el click inspecciona el vértice más cercano
+            if self.normals_visible {
+                self.inspect_vertex(x, y);
+                return;
+            }
+            match self.pick_mode {
+                PickMode::Object => {
+                    self.selected_object = self.renderer.pick(x, y);
+                    match self.selected_object {
+                        Some(index) => println!("Objeto seleccionado: {} ({})", index, self.scene.objects[index].name),
+                        None => println!("Ningún objeto bajo el cursor"),
+                    }
+                    if let Some(obj) = self.selected_object.filter(|_| self.frames_visible).map(|index| &self.scene.objects[index]) {
+                        for frame in &self.scene.frames {
+                            let (position, rotation) = obj.pose_in(frame);
+                            let rotation = to_degrees(rotation.to_euler());
+                            println!(
+                                "  En {}: ({:.3}, {:.3}, {:.3}) {}, giro ({:.1}°, {:.1}°, {:.1}°)",
+                                frame.name,
+                                position.x,
+                                position.y,
+                                position.z,
+                                self.scene.units.symbol(),
+                                rotation.x,
+                                rotation.y,
+                                rotation.z
+                            );
                         }
                     }
-                    _ => {}
-                }
-            }
-            // input de ventana (KeyboardInput, MouseInput, etc.)
-            Event::WindowEvent { event, .. } => match event {
-                WindowEvent::CloseRequested => {
-                    *control_flow = ControlFlow::Exit;
-                }
-                WindowEvent::MouseInput { button, state, .. } => {
-                    if button == MouseButton::Right {
-                        right_button_pressed = state == ElementState::Pressed;
-                    }
-                }
-                WindowEvent::KeyboardInput { input, .. } => {
-                    // Destructuramos la info
-                    if let KeyboardInput {
-                        virtual_keycode: Some(key),
-                        state,
-                        ..
-                    } = input
-                    {
-                        match state {
-                            ElementState::Pressed => {
-                                // Insertamos en el HashSet
-                                pressed_keys.insert(key);
-
-                                // Pulsos instantáneos (por ejemplo ESC, Q, E)
-                                match key {
-                                    VirtualKeyCode::Escape => {
-                                        *control_flow = ControlFlow::Exit;
-                                    }
-                                    // Cambios de escala global "instantáneos"
-                                    VirtualKeyCode::Q => {
-                                        scale_factor *= 1.1;
-                                    }
-                                    VirtualKeyCode::E => {
-                                        scale_factor *= 0.9;
-                                    }
-                                    _ => {}
-                                }
-                            }
-                            ElementState::Released => {
-                                // Quitamos la tecla del set
-                                pressed_keys.remove(&key);
-                            }
+                }
+                PickMode::SubObject => match self.renderer.pick_sub_object(&self.scene.objects, x, y, 8.0) {
+                    Some(hit) => {
+                        println!(
+                            "Objeto {}: {:?} en ({:.3}, {:.3}, {:.3}): {}",
+                            hit.object,
+                            hit.element,
+                            hit.point.x,
+                            hit.point.y,
+                            hit.point.z,
+                            self.scene.objects[hit.object].mesh.topology().summary(hit.element)
+                        );
+                        if self.mate_picks.len() == 2 {
+                            self.mate_picks.remove(0);
+                        }
+                        self.mate_picks.push(hit);
+                    }
+                    None => println!("Ningún objeto bajo el cursor"),
+                },
+                PickMode::Group => {
+                    let index = self.renderer.pick(x, y);
+                    self.selected_group = index.and_then(|index| self.scene.group_of(index)).map(|g| g.name.clone());
+                    match (&self.selected_group, index) {
+                        (Some(name), _) => {
+                            println!("Grupo seleccionado: {} ({} objetos)", name, self.scene.group_members(name).len())
                         }
+                        (None, Some(index)) => println!("{} no está en ningún grupo", self.scene.objects[index].name),
+                        (None, None) => println!("Ningún objeto bajo el cursor"),
                     }
                 }
-                WindowEvent::Resized(new_size) => {
-                    window.resize(new_size);
+            }
+        }
+    }
+
+    /// Informa posición y normal del vértice bajo el cursor, en el log y en pantalla
+    fn inspect_vertex(&mut self, x: i32, y: i32) {
+        let report = self.renderer.pick_sub_object(&self.scene.objects, x, y, 8.0).and_then(|hit| {
+            VertexNormalReport::from_hit(&self.scene.objects[hit.object], &hit, self.scale_factor)
+        });
+        let Some(report) = report else {
+            println!("Ningún vértice bajo el cursor");
+            self.renderer.text().clear("vertice");
+            return;
+        };
+        let lines = report.lines();
+        println!("{}", lines.join("\n"));
+        self.renderer.text().set("vertice", TextPanel::new(lines));
+    }
+
+    /// Informa reposo y pose de la articulación bajo el cursor y la resalta
+    /// Objetos elegidos: los del grupo en modo grupos, si no el del último click
+    fn selection(&self) -> Vec<usize> {
+        match (self.pick_mode, &self.selected_group) {
+            (PickMode::Group, Some(name)) => self.scene.group_members(name),
+            _ => self.selected_object.filter(|&index| index < self.scene.objects.len()).into_iter().collect(),
+        }
+    }
+
+    /// Assets y escena para el informe de errores (ver `engine::crash`)
+    fn update_crash_context(&self) {
+        let mut assets: Vec<String> = self.scene.objects.iter().filter_map(|obj| obj.source.clone()).collect();
+        assets.extend(self.renderer.textures.paths().map(|path| path.display().to_string()));
+        assets.sort();
+        assets.dedup();
+        let scene = crash::scene_summary(&self.scene);
+        crash::update_context(|context| {
+            context.assets = assets;
+            context.scene = scene;
+        });
+    }
+
+    fn inspect_joint(&mut self, x: i32, y: i32) {
+        let mut joints = Vec::new();
+        let mut positions = Vec::new();
+        for (index, obj) in self.scene.objects.iter().enumerate() {
+            let Some(skeleton) = &obj.skeleton else { continue };
+            let world = skeleton.world_positions(&obj.model_matrix(self.scale_factor));
+            joints.extend((0..world.len()).map(|joint| (index, joint)));
+            positions.extend(world);
+        }
+        self.selected_joint = self.renderer.pick_point(&positions, x, y, 10.0).map(|i| joints[i]);
+        let report = self.selected_joint.and_then(|(index, joint)| {
+            let obj = &self.scene.objects[index];
+            JointReport::new(obj.skeleton.as_ref()?, index, joint, &obj.model_matrix(self.scale_factor))
+        });
+        let Some(report) = report else {
+            println!("Ninguna articulación bajo el cursor");
+            self.renderer.text().clear("articulacion");
+            return;
+        };
+        let lines = report.lines();
+        println!("{}", lines.join("\n"));
+        self.renderer.text().set("articulacion", TextPanel::new(lines));
+    }
+
+    /// Pulsos instantáneos (por ejemplo ESC, Q, E)
+    fn key_pressed(&mut self, event_loop: &ActiveEventLoop, key: KeyCode) {
+        let scale_factor = self.scale_factor;
+        let selection = self.selection();
+        let (renderer, scene, camera) = (&mut self.renderer, &mut self.scene, &mut self.camera);
+        match key {
+            // Primero cancela lo que esté corriendo; sin nada en curso, sale
+            KeyCode::Escape => {
+                if let Some((task, _)) = &self.bake_task {
+                    task.cancel();
+                    println!("Cancelando: {}", task.label);
+                } else if self.still_render.take().is_some() {
+                    renderer.text().clear(PROGRESS_LAYER);
+                    println!("Render cancelado");
+                } else {
+                    event_loop.exit();
                 }
-                _ => {}
+            }
+            // Ayuda con todos los controles registrados (también los de los plugins)
+            KeyCode::F1 => {
+                self.help_visible = !self.help_visible;
+                if self.help_visible {
+                    renderer.text().set("ayuda", TextPanel::new(self.engine.keymap.help_lines()));
+                } else {
+                    renderer.text().clear("ayuda");
+                }
+            }
+            KeyCode::Tab => {
+                self.profiler_visible = !self.profiler_visible;
+                renderer.graph.set_profiling(self.profiler_visible);
+                if !self.profiler_visible {
+                    renderer.text().clear("perfil");
+                }
+            }
+            // Cambios de escala global "instantáneos"
+            KeyCode::KeyQ => {
+                self.scale_factor *= 1.1;
+            }
+            KeyCode::KeyE => {
+                self.scale_factor *= 0.9;
+            }
+            // Ajustes de render
+            KeyCode::KeyF => {
+                let wireframe = renderer.settings().wireframe;
+                renderer.set_wireframe(!wireframe);
+            }
+            KeyCode::KeyC => {
+                let culling = renderer.settings().backface_culling;
+                renderer.set_backface_culling(!culling);
+            }
+            KeyCode::KeyV => {
+                let hidden_line = renderer.settings().hidden_line;
+                renderer.set_hidden_line(!hidden_line);
+            }
+            // Alternar vuelo libre / caminar
+            KeyCode::KeyG => {
+                let mode = match camera.mode {
+                    CameraMode::Fly => CameraMode::Walk,
+                    CameraMode::Walk => CameraMode::Fly,
+                };
+                camera.set_mode(mode);
+                println!("Modo de cámara: {:?}", mode);
+            }
+            // Vistas estándar
+            KeyCode::Digit1 => camera.set_view(View::Front),
+            KeyCode::Digit2 => camera.set_view(View::Right),
+            KeyCode::Digit3 => camera.set_view(View::Top),
+            KeyCode::Digit4 => camera.set_view(View::Iso),
+            // Encuadrar toda la escena
+            KeyCode::Home => {
+                let bounds = scene.bounds(scale_factor);
+                camera.frame(&bounds, 45.0_f32.to_radians(), VIEW_TRANSITION);
+            }
+            // Bookmarks: B guarda la vista actual, N recorre las guardadas
+            KeyCode::KeyB => {
+                let name = format!("vista {}", scene.bookmarks.len() + 1);
+                scene.save_bookmark(&name, camera);
+                println!("Bookmark guardado: {}", name);
+            }
+            KeyCode::KeyN if !scene.bookmarks.is_empty() => {
+                self.bookmark_index = (self.bookmark_index + 1) % scene.bookmarks.len();
+                let bookmark = &scene.bookmarks[self.bookmark_index];
+                println!("Bookmark: {}", bookmark.name);
+                camera.recall_pose(bookmark.pose);
+            }
+            // Guardar la escena (objetos + bookmarks)
+            KeyCode::F5 => match scene.save("scene.ron") {
+                Ok(()) => println!("Escena guardada en scene.ron"),
+                Err(e) => eprintln!("{}", e),
             },
-            // Redibujar
-            Event::RedrawRequested(_) => {
-                let now = Instant::now();
-                let dt = (now - last_frame_time).as_secs_f32();
-                last_frame_time = now;
+            // Hornear lightmaps (L: oclusión ambiental, K: iluminación global)
+            KeyCode::KeyL | KeyCode::KeyK => {
+                let mode = if key == KeyCode::KeyL {
+                    LightmapMode::AmbientOcclusion
+                } else {
+                    LightmapMode::GlobalIllumination
+                };
+                if self.bake_task.is_some() {
+                    println!("Ya hay un horneado en curso (Esc lo cancela)");
+                    return;
+                }
+                let settings = BakeSettings { mode, ..BakeSettings::default() };
+                // UVs propias del lightmap; las de textura quedan como estaban
+                for obj in scene.objects.iter_mut().filter(|obj| obj.mesh.lightmap_uvs.is_empty()) {
+                    obj.generate_lightmap_uvs(settings.resolution);
+                }
+                // Se hornea sobre una copia: el visor sigue andando mientras tanto
+                let trace = TraceScene::from_objects(&scene.objects, scale_factor, Lighting::default());
+                let label = if key == KeyCode::KeyL { "Horneando oclusión ambiental" } else { "Horneando iluminación global" };
+                let task = BackgroundTask::spawn(label, move |progress| bake_scene(&trace, &settings, progress));
+                self.bake_task = Some((task, Instant::now()));
+            }
+            // Render de alta calidad de la vista actual (en segundo plano)
+            KeyCode::KeyR => {
+                let settings = StillRenderSettings {
+                    transparent_background: renderer.settings().transparent_background,
+                    ..StillRenderSettings::default()
+                };
+                println!(
+                    "Render {}x{} con {} muestras por píxel...",
+                    settings.width, settings.height, settings.samples
+                );
+                self.still_render = Some(StillRender::start(&scene.objects, scale_factor, camera, settings));
+            }
+            // Captura de la vista: PNG y EXR lineal de 16 bits
+            KeyCode::F12 => {
+                let size = self.window.inner_size();
+                let size = (size.width as i32, size.height as i32);
+                for path in ["captura.png", "captura.exr"] {
+                    match renderer.capture(&scene.objects, camera, scale_factor, size, path) {
+                        Ok(()) => println!("Captura guardada en {}", path),
+                        Err(e) => eprintln!("{}", e),
+                    }
+                }
+                self.window.resize(self.window.inner_size());
+            }
+            // Capturas (F12, R) con alfa para componer sobre otra imagen
+            KeyCode::Insert => {
+                let transparent = !renderer.settings().transparent_background;
+                renderer.set_transparent_background(transparent);
+                println!("Fondo transparente en capturas: {}", if transparent { "sí" } else { "no" });
+            }
+            // Buffers auxiliares de la vista actual
+            KeyCode::F11 => {
+                let exports = [
+                    (AuxBuffer::Depth, "profundidad.exr"),
+                    (AuxBuffer::Normal, "normales.png"),
+                    (AuxBuffer::ObjectId, "ids.png"),
+                ];
+                for (buffer, path) in exports {
+                    match renderer.capture_aux(buffer, path) {
+                        Ok(()) => println!("{:?} guardado en {}", buffer, path),
+                        Err(e) => eprintln!("{}", e),
+                    }
+                }
+            }
+            // Guardar el render en curso tal como está
+            KeyCode::KeyT => {
+                if let Some(render) = &self.still_render {
+                    let (passes, total) = render.progress();
+                    match render.save("render.png") {
+                        Ok(()) => println!("render.png guardado ({}/{} muestras)", passes, total),
+                        Err(e) => eprintln!("{}", e),
+                    }
+                }
+            }
+            KeyCode::KeyO | KeyCode::KeyH => {
+                // Análisis para impresión 3D: voladizos (Y hacia arriba) o espesor de pared.
+                // La misma tecla lo apaga.
+                self.color_view = if self.color_view == Some(key) { None } else { Some(key) };
+                show_color_view(scene, scale_factor, self.color_view);
+            }
+            KeyCode::Backslash => {
+                let Some(preset) = self.presets.next(self.render_preset.as_deref()).cloned() else { return };
+                self.color_view = apply_render_preset(&preset, renderer, scene, scale_factor);
+                println!("Preset de render: {}", preset.name);
+                self.render_preset = Some(preset.name);
+            }
+            KeyCode::End => {
+                let preset = RenderPreset::from_settings("Personalizado", renderer.settings(), scene.lighting_rig);
+                let preset = RenderPreset {
+                    heatmap: match self.color_view {
+                        Some(KeyCode::KeyO) => Some(Heatmap::Overhangs),
+                        Some(_) => Some(Heatmap::Thickness),
+                        None => None,
+                    },
+                    ..preset
+                };
+                self.presets.set(preset);
+                match self.presets.save() {
+                    Ok(path) => println!("Preset \"Personalizado\" guardado en {}", path),
+                    Err(e) => eprintln!("{}", e),
+                }
+                self.render_preset = Some("Personalizado".to_string());
+            }
+            KeyCode::KeyX => {
+                // Vista de corte: empieza a media altura de la escena
+                self.slice_height = match self.slice_height {
+                    Some(_) => None,
+                    None => Some(scene.bounds(scale_factor).center().y),
+                };
+                show_slice(renderer, scene, scale_factor, self.slice_height);
+            }
+            KeyCode::PageUp | KeyCode::PageDown => {
+                // Barrer el plano de corte de a 1/100 de la altura de la escena
+                if let Some(height) = self.slice_height.as_mut() {
+                    let step = scene.bounds(scale_factor).size().y / 100.0;
+                    *height += if key == KeyCode::PageUp { step } else { -step };
+                    show_slice(renderer, scene, scale_factor, self.slice_height);
+                }
+            }
+            KeyCode::F6 => {
+                if let Some(height) = self.slice_height {
+                    let contours = slice_objects(&scene.objects, scale_factor, Vec3::UNIT_Y, height);
+                    let saved = save_svg(&contours, Vec3::UNIT_Y, "corte.svg")
+                        .and_then(|_| save_dxf(&contours, Vec3::UNIT_Y, "corte.dxf"));
+                    match saved {
+                        Ok(()) => println!("Corte en y = {:.4} guardado en corte.svg y corte.dxf", height),
+                        Err(e) => eprintln!("{}", e),
+                    }
+                }
+            }
+            KeyCode::Delete => {
+                let settings = OrthoSettings { crease_angle: renderer.settings().crease_angle, ..OrthoSettings::default() };
+                for path in ["vistas.svg", "vistas.png"] {
+                    match ortho_views::export(&scene.objects, scale_factor, &settings, path) {
+                        Ok(()) => println!("Vistas acotadas guardadas en {}", path),
+                        Err(e) => eprintln!("{}", e),
+                    }
+                }
+            }
+            KeyCode::Pause => {
+                let units = if renderer.scale_bar().is_some() { None } else { Some(scene.units) };
+                renderer.set_scale_bar(units);
+            }
+            KeyCode::ScrollLock => {
+                let index = LengthUnit::ALL.iter().position(|&unit| unit == scene.units).unwrap_or(0);
+                scene.units = LengthUnit::ALL[(index + 1) % LengthUnit::ALL.len()];
+                println!("Unidades de la escena: {}", scene.units.symbol());
+            }
+            KeyCode::KeyJ => {
+                // Partes convexas para colisión, en alambre
+                self.hulls_visible = !self.hulls_visible;
+                show_hulls(renderer, scene, scale_factor, self.hulls_visible);
+            }
+            KeyCode::KeyM => {
+                self.normals_visible = !self.normals_visible;
+                show_normals(renderer, scene, scale_factor, self.normals_visible);
+            }
+            KeyCode::KeyU => {
+                let checker = !renderer.settings().uv_checker;
+                renderer.set_uv_checker(checker);
+                let without_uvs = scene.objects.iter().filter(|obj| obj.mesh.uvs.is_empty()).count();
+                if checker && without_uvs > 0 {
+                    println!("{} objetos sin UVs se dibujan sin damero", without_uvs);
+                }
+            }
+            KeyCode::KeyY => {
+                self.tangents_visible = !self.tangents_visible;
+                show_tangents(renderer, scene, scale_factor, self.tangents_visible);
+            }
+            // Animaciones de todos los objetos que tienen (ver graphics::animation)
+            KeyCode::F10 => {
+                for (obj, animator) in scene.objects.iter_mut().filter_map(|obj| Some((&obj.name, obj.animator.as_mut()?))) {
+                    if animator.is_playing() {
+                        animator.pause();
+                    } else if animator.current_clip().is_some() {
+                        animator.resume();
+                    } else if let Some(clip) = animator.clips().first().map(|c| c.name.clone()) {
+                        animator.play(&clip).expect("El clip existe");
+                    }
+                    let state = if animator.is_playing() { "en marcha" } else { "en pausa" };
+                    println!("{}: {} {}", obj, animator.current_clip().unwrap_or("sin clips"), state);
+                }
+            }
+            KeyCode::Comma | KeyCode::Period => {
+                let step = if key == KeyCode::Comma { -ANIMATION_STEP } else { ANIMATION_STEP };
+                for animator in scene.objects.iter_mut().filter_map(|obj| obj.animator.as_mut()) {
+                    animator.seek(animator.time() + step);
+                    println!("{} {:.2} / {:.2} s", animator.current_clip().unwrap_or("-"), animator.time(), animator.duration());
+                }
+            }
+            KeyCode::Slash => {
+                for animator in scene.objects.iter_mut().filter_map(|obj| obj.animator.as_mut()) {
+                    let names: Vec<String> = animator.clips().iter().map(|c| c.name.clone()).collect();
+                    let next = match animator.current_clip().and_then(|c| names.iter().position(|n| n == c)) {
+                        Some(index) => (index + 1) % names.len(),
+                        None => 0,
+                    };
+                    if let Some(name) = names.get(next) {
+                        animator.crossfade(name, ANIMATION_FADE).expect("El clip existe");
+                        println!("Animación: {}", name);
+                    }
+                }
+            }
+            KeyCode::F9 => {
+                self.skeletons_visible = !self.skeletons_visible;
+                self.selected_joint = None;
+                if self.skeletons_visible && scene.objects.iter().all(|obj| obj.skeleton.is_none()) {
+                    println!("Ningún objeto tiene esqueleto");
+                }
+                show_skeletons(renderer, scene, scale_factor, self.skeletons_visible, None);
+            }
+            // Iluminaciones armadas (se guardan con la escena)
+            KeyCode::F2 => {
+                let rig = scene.lighting_rig.map_or(LightingRig::Studio, |rig| rig.next());
+                renderer.lighting = scene.apply_lighting_preset(rig);
+                println!("Iluminación: {}", rig.name());
+            }
+            KeyCode::F3 | KeyCode::F4 | KeyCode::F7 => {
+                let (mut hour, mut speed) = match scene.lighting_rig {
+                    Some(LightingRig::Outdoor { hour, speed }) => (hour, speed),
+                    _ => (10.0, 0.0),
+                };
+                match key {
+                    KeyCode::F3 => hour = (hour - 1.0).rem_euclid(24.0),
+                    KeyCode::F4 => hour = (hour + 1.0).rem_euclid(24.0),
+                    // Un día completo en 24 segundos
+                    _ => speed = if speed == 0.0 { 1.0 } else { 0.0 },
+                }
+                renderer.lighting = scene.apply_lighting_preset(LightingRig::Outdoor { hour, speed });
+                println!("Exterior: {:02}:{:02}", hour as u32, (hour.fract() * 60.0) as u32);
+            }
+            KeyCode::F8 => {
+                if scene.objects.iter().any(|obj| obj.color.is_some()) {
+                    scene.clear_object_colors();
+                } else {
+                    scene.color_objects_distinct();
+                }
+            }
+            KeyCode::Digit9 | KeyCode::Digit0 => {
+                if selection.is_empty() {
+                    println!("Nada seleccionado (click sobre un objeto, o un grupo en modo grupos)");
+                    return;
+                }
+                let result = if key == KeyCode::Digit9 { scene.isolate(&selection) } else { scene.hide(&selection) };
+                match result {
+                    Ok(()) => println!("{} objetos ocultos", scene.hidden_count()),
+                    Err(e) => eprintln!("{}", e),
+                }
+            }
+            // Suelda las costuras con la tolerancia de siempre
+            KeyCode::NumpadAdd => match scene.merge_objects(&selection, Some(DEFAULT_TOLERANCE)) {
+                Ok(index) => {
+                    self.selected_group = None;
+                    self.selected_object = Some(index);
+                    println!("{} objetos unidos en {}", selection.len(), scene.objects[index].name);
+                }
+                Err(e) => eprintln!("{}", e),
+            },
+            KeyCode::Backquote => scene.show_all(),
+            KeyCode::NumpadDivide => {
+                let space = self.engine.editing.transform_space.next();
+                self.engine.editing.transform_space = space;
+                println!("Ejes de los giros: {}", space.name());
+            }
+            KeyCode::Numpad7 | KeyCode::Numpad8 | KeyCode::Numpad9 => {
+                let Some(obj) = self.selected_object.and_then(|index| scene.objects.get_mut(index)) else {
+                    println!("Nada seleccionado para girar (click sobre un objeto)");
+                    return;
+                };
+                let axis = match key {
+                    KeyCode::Numpad7 => Vec3::UNIT_X,
+                    KeyCode::Numpad8 => Vec3::UNIT_Y,
+                    _ => Vec3::UNIT_Z,
+                };
+                let space = self.engine.editing.transform_space;
+                match obj.rotate_in(space, axis, 90.0, &camera.get_view_matrix()) {
+                    Ok(()) => println!("{} girado 90° (ejes {})", obj.name, space.name()),
+                    Err(e) => eprintln!("{}", e),
+                }
+            }
+            KeyCode::NumpadMultiply => {
+                self.frames_visible = !self.frames_visible;
+                if self.frames_visible && scene.frames.is_empty() {
+                    println!("No hay marcos de referencia (teclado numérico . crea uno en el objeto seleccionado)");
+                }
+                if !self.frames_visible {
+                    show_frames(renderer, scene, scale_factor, None);
+                }
+            }
+            KeyCode::Numpad5 => {
+                let joints: Vec<(String, String)> = scene
+                    .robots
+                    .iter()
+                    .flat_map(|robot| {
+                        let movable = robot.joints().iter().filter(|joint| joint.kind.is_movable());
+                        movable.map(|joint| (robot.name.clone(), joint.name.clone()))
+                    })
+                    .collect();
+                let next = match joints.iter().position(|joint| Some(joint) == self.robot_joint.as_ref()) {
+                    Some(index) => (index + 1) % joints.len(),
+                    None => 0,
+                };
+                self.robot_joint = joints.into_iter().nth(next);
+                match &self.robot_joint {
+                    Some((robot, joint)) => println!("Articulación: {} / {}", robot, joint),
+                    None => println!("No hay robots con articulaciones móviles"),
+                }
+            }
+            KeyCode::Numpad4 | KeyCode::Numpad6 => {
+                let Some((robot, joint)) = &self.robot_joint else {
+                    println!("Elegir una articulación con la tecla 5 del teclado numérico");
+                    return;
+                };
+                let Some(current) = scene.robot(robot).ok().and_then(|r| r.joints().iter().find(|j| j.name == *joint)) else {
+                    self.robot_joint = None;
+                    return;
+                };
+                let (step, unit) = match current.kind {
+                    JointKind::Prismatic => (JOINT_SLIDE_STEP, "m"),
+                    _ => (JOINT_ROTATION_STEP, "rad"),
+                };
+                let direction = if key == KeyCode::Numpad4 { -1.0 } else { 1.0 };
+                match scene.set_joint_position(robot, joint, current.position + step * direction) {
+                    Ok(position) => println!("{} / {}: {:.3} {}", robot, joint, position, unit),
+                    Err(e) => eprintln!("{}", e),
+                }
+            }
+            KeyCode::Numpad0 => {
+                let players = scene.robots.iter_mut().filter_map(|robot| Some((&robot.name, robot.player.as_mut()?)));
+                let mut any = false;
+                for (robot, player) in players {
+                    // Una que terminó vuelve a empezar
+                    if !player.playing && player.time >= player.trajectory.duration() {
+                        player.time = 0.0;
+                    }
+                    player.playing = !player.playing;
+                    let state = if player.playing { "en marcha" } else { "en pausa" };
+                    println!("{}: trayectoria {} ({:.2} / {:.2} s)", robot, state, player.time, player.trajectory.duration());
+                    any = true;
+                }
+                if !any {
+                    println!("No hay trayectorias (se cargan con el comando remoto play_trajectory)");
+                }
+            }
+            KeyCode::NumpadDecimal => {
+                let Some(obj) = self.selected_object.and_then(|index| scene.objects.get(index)) else {
+                    println!("Nada seleccionado para poner el marco (click sobre un objeto)");
+                    return;
+                };
+                let (origin, rotation) = (obj.transform.position, obj.transform.rotation);
+                let name = format!("marco {}", scene.frames.len() + 1);
+                println!("Marco '{}' en {}", name, obj.name);
+                scene.set_frame(ReferenceFrame::new(&name, origin, rotation));
+                self.frames_visible = true;
+            }
+            KeyCode::Numpad1 | KeyCode::Numpad2 | KeyCode::Numpad3 => {
+                let [moving, fixed] = self.mate_picks[..] else {
+                    println!("Elegí dos elementos en modo sub-objetos: el de la pieza a mover y el de la fija");
+                    return;
+                };
+                let mate = match key {
+                    KeyCode::Numpad1 => Mate::Coplanar,
+                    KeyCode::Numpad2 => Mate::Concentric,
+                    _ => Mate::Coincident,
+                };
+                match align::mate(&mut scene.objects, &moving, &fixed, mate, scale_factor) {
+                    Ok(report) => {
+                        // Los puntos elegidos ya no están donde estaban
+                        self.mate_picks.clear();
+                        println!("Alineado: {}", report);
+                    }
+                    Err(e) => eprintln!("{}", e),
+                }
+            }
+            KeyCode::NumpadSubtract => {
+                let Some(index) = self.selected_object.filter(|&index| index < scene.objects.len()) else {
+                    println!("Nada seleccionado para separar (click sobre un objeto)");
+                    return;
+                };
+                let name = scene.objects[index].name.clone();
+                match scene.split_object(index) {
+                    Ok(count) => {
+                        self.selected_object = None;
+                        println!("{} separado en {} piezas", name, count);
+                    }
+                    Err(e) => eprintln!("{}", e),
+                }
+            }
+            // Caja de sección un 10% más grande que la selección
+            KeyCode::Semicolon => {
+                let mut clipping = renderer.settings().clipping;
+                clipping.section_box = match clipping.section_box {
+                    Some(_) => None,
+                    None if selection.is_empty() => {
+                        println!("Nada seleccionado para la caja de sección");
+                        return;
+                    }
+                    None => {
+                        let bounds = selection
+                            .iter()
+                            .fold(Aabb::EMPTY, |acc, &index| acc.union(&scene.objects[index].world_bounds(scale_factor)));
+                        let margin = bounds.size() * 0.05;
+                        Some(Aabb::new(bounds.min - margin, bounds.max + margin))
+                    }
+                };
+                println!("Caja de sección: {}", if clipping.section_box.is_some() { "sí" } else { "no" });
+                renderer.set_clipping(clipping);
+            }
+            KeyCode::Quote => {
+                let ignore = !selection.iter().all(|&index| scene.objects[index].ignore_clipping);
+                for &index in &selection {
+                    scene.objects[index].ignore_clipping = ignore;
+                }
+                println!("{} objetos {} el corte", selection.len(), if ignore { "ignoran" } else { "respetan" });
+            }
+            KeyCode::Backspace => {
+                if scene.undo_visibility() {
+                    println!("{} objetos ocultos", scene.hidden_count());
+                } else {
+                    println!("No hay cambios de visibilidad para deshacer");
+                }
+            }
+            KeyCode::Digit5 => {
+                let created = scene.group_by_source();
+                println!("{} grupos nuevos ({} en total)", created, scene.groups().len());
+            }
+            KeyCode::Digit6
+            | KeyCode::Digit7
+            | KeyCode::Digit8
+            | KeyCode::Minus
+            | KeyCode::Equal
+            | KeyCode::BracketLeft
+            | KeyCode::BracketRight => {
+                let Some(group) = self.selected_group.as_deref().and_then(|name| scene.group(name)).cloned() else {
+                    println!("Ningún grupo seleccionado (P hasta el modo grupos y click sobre un miembro)");
+                    return;
+                };
+                let name = group.name.as_str();
+                let result = match key {
+                    KeyCode::Digit6 => scene.set_group_hidden(name, !group.hidden),
+                    KeyCode::Digit7 => {
+                        let members = scene.group_members(name);
+                        let color = if members.iter().any(|&index| scene.objects[index].color.is_some()) {
+                            None
+                        } else {
+                            let position = scene.groups().iter().position(|g| g.name == name).unwrap_or(0);
+                            distinct_colors(scene.groups().len()).get(position).copied()
+                        };
+                        scene.set_group_color(name, color)
+                    }
+                    KeyCode::Digit8 => {
+                        scene.ungroup(name);
+                        self.selected_group = None;
+                        Ok(())
+                    }
+                    KeyCode::Minus | KeyCode::Equal => {
+                        let step = if key == KeyCode::Minus { -GROUP_ROTATION_STEP } else { GROUP_ROTATION_STEP };
+                        scene.set_group_transform(name, Matrix4::rotate_y(step).multiply(&group.transform))
+                    }
+                    _ => {
+                        let step = if key == KeyCode::BracketLeft { -GROUP_EXPLODE_STEP } else { GROUP_EXPLODE_STEP };
+                        scene.set_group_explode(name, group.explode + step)
+                    }
+                };
+                if let Err(e) = result {
+                    eprintln!("{}", e);
+                }
+            }
+            // Recorre los objetos en la vista de UVs y después la oculta
+            KeyCode::KeyZ => {
+                let mut layout = renderer.uv_layout();
+                let next = layout.object().map_or(0, |index| index + 1);
+                match scene.objects.get(next) {
+                    Some(obj) => {
+                        layout.show(next);
+                        if obj.mesh.uvs.is_empty() {
+                            println!("UVs de {}: no tiene", obj.name);
+                        } else {
+                            println!("UVs de {}", obj.name);
+                        }
+                    }
+                    None => layout.hide(),
+                }
+            }
+            KeyCode::KeyI => {
+                // Propiedades de masa (densidad 1: la masa es el volumen)
+                for obj in &scene.objects {
+                    match obj.mass_properties(1.0, scale_factor) {
+                        Ok(props) => println!(
+                            "{}: volumen {:.4}, área {:.4}, centro de masa ({:.4}, {:.4}, {:.4})",
+                            obj.name, props.volume, props.surface_area,
+                            props.center_of_mass.x, props.center_of_mass.y, props.center_of_mass.z
+                        ),
+                        Err(e) => println!("{}: {}", obj.name, e),
+                    }
+                }
+            }
+            // Alternar picking de objetos / sub-objetos
+            KeyCode::KeyP => {
+                self.pick_mode = match self.pick_mode {
+                    PickMode::Object => PickMode::SubObject,
+                    PickMode::SubObject => PickMode::Group,
+                    PickMode::Group => PickMode::Object,
+                };
+                println!("Modo de selección: {:?}", self.pick_mode);
+            }
+            _ => {}
+        }
+    }
+
+    /// Para decidir cada cuánto dibujar (ver `EngineSettings::schedule`)
+    fn activity(&self) -> WindowActivity {
+        let size = self.window.inner_size();
+        if self.occluded || size.width == 0 || size.height == 0 || self.window.window.is_minimized() == Some(true) {
+            return WindowActivity::Hidden;
+        }
+        // El visor necesita frames aunque la ventana no tenga el foco
+        #[cfg(feature = "openxr")]
+        if self.xr_session.is_some() {
+            return WindowActivity::Focused;
+        }
+        if self.focused {
+            WindowActivity::Focused
+        } else {
+            WindowActivity::Background
+        }
+    }
+
+    /// El driver reseteó el contexto: ventana y contexto nuevos, y todos los
+    /// objetos GL recreados desde lo que hay en CPU
+    fn recover_context(&mut self, event_loop: &ActiveEventLoop) -> Result<(), String> {
+        eprintln!("Se perdió el contexto OpenGL; recreando la ventana y los recursos de la GPU");
+        let size = self.window.inner_size().to_logical::<u32>(self.window.window.scale_factor());
+        self.window = Window::new("Rust_Engine", size.width, size.height, event_loop)?;
+        set_crash_gl_info(&self.window);
+        self.engine.input.set_window(self.window.inner_size(), self.window.window.scale_factor());
+        self.startup.apply_window(&self.window.window);
+        self.renderer.rebuild_gpu(&mut self.scene.objects)?;
+        // La sesión VR comparte el contexto perdido
+        #[cfg(feature = "openxr")]
+        {
+            self.xr_session = graphics::xr::XrSession::new(&self.window)
+                .map_err(|e| eprintln!("VR desactivado: {}", e))
+                .ok();
+        }
+        Ok(())
+    }
+
+    fn redraw(&mut self, event_loop: &ActiveEventLoop) {
+        // Superficie invalidada por el compositor: recrearla antes de dibujar
+        if let Err(e) = self.window.restore_surface() {
+            eprintln!("{}", e);
+            return;
+        }
+        self.window.sync_size();
+        if gpu_resources::context_lost() {
+            if let Err(e) = self.recover_context(event_loop) {
+                eprintln!("{}", e);
+                event_loop.exit();
+            }
+            return;
+        }
+        // Minimizada: ni se dibuja ni avanza la simulación
+        if self.activity() == WindowActivity::Hidden {
+            return;
+        }
+        let now = Instant::now();
+        self.profiler.end_frame((now - self.last_frame).as_secs_f32() * 1000.0);
+        self.last_frame = now;
+
+        let dt = self.clock.tick();
+        if self.clock.frame() % CRASH_CONTEXT_FRAMES == 1 {
+            self.update_crash_context();
+        }
+        let scale_factor = self.scale_factor;
+        let (renderer, scene, camera) = (&mut self.renderer, &mut self.scene, &mut self.camera);
+        let profiler = &mut self.profiler;
+
+        // Actualizar animación de cada objeto
+        profiler.measure("animación", || {
+            for obj in &mut scene.objects {
+                obj.spin(dt);
+                obj.advance_animation(dt);
+            }
+            scene.update_robots(dt);
+        });
+        profiler.measure("plugins", || self.engine.update(scene, renderer, camera, scale_factor, dt));
+        profiler.measure("escena", || {
+            scene.update_spatial(scale_factor);
+            if let Some(lighting) = scene.update_lighting(dt) {
+                renderer.lighting = lighting;
+            }
+            // Las poses pueden estar animadas: el esqueleto se rearma cada frame
+            if self.skeletons_visible {
+                show_skeletons(renderer, scene, scale_factor, true, self.selected_joint);
+            }
+        });
+
+        // Las vistas estándar orbitan alrededor del centro de la escena
+        let bounds = scene.bounds(scale_factor);
+        if !bounds.is_empty() {
+            camera.pivot = bounds.center();
+        }
 
-                // Actualizar animación de cada objeto
-                for obj in &mut objects {
-                    obj.angle += obj.angular_speed * dt;
+        // Transiciones de cámara (cubo de navegación, vistas, bookmarks)
+        camera.update_animation(dt);
+
+        // *** Mover la cámara en base a las teclas presionadas ***
+        match camera.mode {
+            CameraMode::Fly => camera.process_keys(&self.pressed_keys, dt),
+            CameraMode::Walk => camera.process_walk(&self.pressed_keys, dt, |origin| {
+                let down = Ray::new(origin, Vec3::new(0.0, -1.0, 0.0));
+                scene.raycast(scale_factor, &down).map(|hit| hit.point.y)
+            }),
+        }
+
+        // Lightmaps horneados en segundo plano: se asignan al terminar
+        let screen = self.window.inner_size().to_logical::<f32>(self.window.window.scale_factor());
+        if let Some((task, start)) = self.bake_task.as_mut() {
+            if let Some(result) = task.try_finish() {
+                let elapsed = start.elapsed().as_secs_f32();
+                self.bake_task = None;
+                renderer.text().clear(PROGRESS_LAYER);
+                match result {
+                    Ok(lightmaps) => {
+                        for (index, lightmap) in &lightmaps {
+                            if let Some(obj) = scene.objects.get_mut(*index) {
+                                obj.set_lightmap(lightmap);
+                            }
+                        }
+                        println!("{} lightmaps horneados en {:.1} s", lightmaps.len(), elapsed);
+                    }
+                    Err(e) => eprintln!("{}", e),
                 }
+            } else {
+                let (panel, rects) = task.overlay((screen.width, screen.height));
+                renderer.text().set(PROGRESS_LAYER, panel);
+                renderer.text().set_rects(PROGRESS_LAYER, rects);
+            }
+        } else if let Some(render) = &self.still_render {
+            let (passes, total) = render.progress();
+            let (panel, rects) = progress_overlay("Render", passes as f32 / total.max(1) as f32, (screen.width, screen.height));
+            renderer.text().set(PROGRESS_LAYER, panel);
+            renderer.text().set_rects(PROGRESS_LAYER, rects);
+        }
 
-                // *** Mover la cámara en base a las teclas presionadas ***
-                camera.process_keys(&pressed_keys, dt);
+        // Guardar el render de alta calidad cuando termina
+        if self.still_render.as_ref().is_some_and(|render| render.is_finished()) {
+            renderer.text().clear(PROGRESS_LAYER);
+            if let Some(render) = self.still_render.take() {
+                for path in ["render.png", "render.exr"] {
+                    match render.save(path) {
+                        Ok(()) => println!("Render guardado en {}", path),
+                        Err(e) => eprintln!("{}", e),
+                    }
+                }
+            }
+        }
 
-                // Render
-                renderer.render_scene(&window, &mut objects, &camera, scale_factor);
+        // El perfilador muestra el frame anterior (las mediciones de GPU llegan con retraso)
+        if self.profiler_visible {
+            profiler.set_gpu_timings(renderer.graph.gpu_timings());
+            let size = self.window.inner_size().to_logical::<f32>(self.window.window.scale_factor());
+            let (panel, rects) = profiler.overlay((size.width, size.height));
+            renderer.text().set("perfil", panel);
+            renderer.text().set_rects("perfil", rects);
+        }
+
+        // La escena pudo cambiar de unidades (tecla, recarga de archivo)
+        if renderer.scale_bar().is_some() {
+            renderer.set_scale_bar(Some(scene.units));
+        }
+
+        // Los nombres de los marcos siguen a la cámara
+        let view_projection = renderer.view_projection(&self.window, camera);
+        if self.frames_visible {
+            show_frames(renderer, scene, scale_factor, Some((&view_projection, (screen.width, screen.height))));
+        }
+
+        // Render: solo los objetos dentro del frustum de la cámara
+        let frustum = Frustum::from_matrix(&view_projection);
+        profiler.measure("culling", || {
+            renderer.set_visible_objects(Some(scene.visible_objects(&frustum, scale_factor)));
+        });
+        profiler.measure("render", || renderer.render_scene(&self.window, &mut scene.objects, camera, scale_factor));
+
+        // Render en el visor, después del de la ventana
+        #[cfg(feature = "openxr")]
+        if let Some(xr) = self.xr_session.as_mut() {
+            match xr.poll_events() {
+                Ok(true) => {
+                    if let Err(e) = xr.render_frame(renderer, &scene.objects, camera, scale_factor, dt) {
+                        eprintln!("{}", e);
+                    }
+                    // Restaurar el viewport de la ventana para el siguiente frame
+                    self.window.resize(self.window.inner_size());
+                }
+                Ok(false) => event_loop.exit(),
+                Err(e) => eprintln!("{}", e),
             }
-            // Pide un redraw continuo
-            Event::MainEventsCleared => {
-                window.request_redraw();
+        }
+    }
+}
+
+impl ApplicationHandler for App {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        match self.viewer.as_mut() {
+            // Volviendo de una suspensión: la superficie anterior ya no vale
+            Some(viewer) => {
+                viewer.suspended = false;
+                if let Err(e) = viewer.window.restore_surface() {
+                    eprintln!("{}", e);
+                }
             }
-            _ => {}
+            None => self.viewer = Some(Viewer::new(event_loop)),
+        }
+    }
+
+    fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
+        if let Some(viewer) = self.viewer.as_mut() {
+            viewer.suspended = true;
+            viewer.window.invalidate_surface();
+        }
+    }
+
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, _window_id: WindowId, event: WindowEvent) {
+        if let Some(viewer) = self.viewer.as_mut() {
+            viewer.window_event(event_loop, event);
+        }
+    }
+
+    // input de mouse a nivel de Device
+    fn device_event(&mut self, _event_loop: &ActiveEventLoop, _device_id: DeviceId, event: DeviceEvent) {
+        let Some(viewer) = self.viewer.as_mut() else {
+            return;
+        };
+        if let DeviceEvent::MouseMotion { delta: (dx, dy) } = event {
+            if viewer.right_button_pressed {
+                viewer.camera.process_mouse(dx as f32, dy as f32);
+            }
+        }
+    }
+
+    // Redraw continuo con el foco, más espaciado sin él y ninguno minimizada
+    // o suspendida (no hay superficie donde dibujar)
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        let Some(viewer) = self.viewer.as_ref().filter(|viewer| !viewer.suspended) else {
+            event_loop.set_control_flow(ControlFlow::Wait);
+            return;
+        };
+        match viewer.engine.settings.schedule(viewer.activity(), viewer.last_frame, Instant::now()) {
+            FrameSchedule::Now => {
+                event_loop.set_control_flow(ControlFlow::Poll);
+                viewer.window.request_redraw();
+            }
+            FrameSchedule::At(deadline) => event_loop.set_control_flow(ControlFlow::WaitUntil(deadline)),
+            FrameSchedule::Paused => event_loop.set_control_flow(ControlFlow::Wait),
         }
+    }
+
+    fn exiting(&mut self, _event_loop: &ActiveEventLoop) {
+        if let Some(viewer) = self.viewer.as_mut() {
+            viewer.engine.shutdown(&mut viewer.scene, &mut viewer.renderer, &mut viewer.camera, viewer.scale_factor);
+        }
+    }
+}
+
+/// Driver del contexto actual, para el informe de errores
+fn set_crash_gl_info(window: &Window) {
+    let caps = &window.capabilities;
+    let info = format!("OpenGL {}.{} core - {} ({})", caps.version.0, caps.version.1, caps.renderer, caps.vendor);
+    crash::update_context(|context| context.gl = Some(info));
+}
+
+/// Crea un contexto con una ventana oculta e imprime lo que ofrece el driver;
+/// sirve en CI para saber si la máquina puede correr el motor
+fn check_gl() -> Result<(), String> {
+    let event_loop = Backend::from_env().event_loop()?;
+    let window = Window::hidden("Rust_Engine", 64, 64, &event_loop)?;
+    let caps = &window.capabilities;
+    println!("OpenGL {}.{} core - {} ({})", caps.version.0, caps.version.1, caps.renderer, caps.vendor);
+    println!(
+        "instancing: {}, compute: {}, debug output: {}, binarios de programa: {}, {} extensiones",
+        caps.instancing, caps.compute, caps.debug_output, caps.program_binary, caps.extensions.len(),
+    );
+    // Los shaders del motor tienen que compilar en este driver
+    Renderer::new("src/graphics/shaders/basic.vert", "src/graphics/shaders/basic.frag")?;
+    println!("Shaders OK");
+    Ok(())
+}
+
+/// Genera un dataset sintético con una ventana oculta (solo para el contexto GL)
+fn run_dataset(spec_path: &str) -> Result<(), String> {
+    let spec = DatasetSpec::load(spec_path)?;
+    let event_loop = Backend::from_env().event_loop()?;
+    let _window = Window::hidden("Rust_Engine", spec.width, spec.height, &event_loop)?;
+    let mut renderer = Renderer::new("src/graphics/shaders/basic.vert", "src/graphics/shaders/basic.frag")?;
+
+    let scene = if std::path::Path::new(&spec.scene).is_dir() {
+        Scene::load_directory(&spec.scene)?
+    } else {
+        Scene::load(&spec.scene)?
+    };
+    let frames = dataset::generate(&mut renderer, &scene.objects, &spec)?;
+    println!("{} imágenes generadas en {}", frames, spec.output);
+    Ok(())
+}
+
+/// Colorea los objetos con un análisis para impresión 3D (tecla O voladizos,
+/// H espesor de pared) o les quita los colores
+fn show_color_view(scene: &mut Scene, scale_factor: f32, view: Option<KeyCode>) {
+    let scene_size = scene.bounds(scale_factor).size().magnitude();
+    for obj in &mut scene.objects {
+        let colors = match view {
+            Some(KeyCode::KeyO) => {
+                let settings = OverhangSettings::default();
+                let report = printability::analyze(obj, scale_factor, &settings);
+                println!(
+                    "{}: {:.4} de {:.4} de área necesita soporte",
+                    obj.name, report.support_area, report.total_area
+                );
+                Some(overhang_colors(&obj.mesh, &report, &settings))
+            }
+            Some(_) => {
+                // Umbral: 1 % de la diagonal de la escena
+                let threshold = scene_size * 0.01;
+                let settings = ThicknessSettings { threshold, ..Default::default() };
+                let report = thickness::analyze(obj, scale_factor, &settings);
+                println!(
+                    "{}: espesor mínimo {:.4}, {} vértices por debajo de {:.4}",
+                    obj.name, report.min.unwrap_or(0.0), report.thin_vertices, threshold
+                );
+                Some(thickness_colors(&report, &settings))
+            }
+            None => None,
+        };
+        obj.set_vertex_colors(colors.as_deref());
+    }
+}
+
+/// Aplica un preset de render con su análisis por colores; devuelve el
+/// `Viewer::color_view` que corresponde
+fn apply_render_preset(preset: &RenderPreset, renderer: &mut Renderer, scene: &mut Scene, scale_factor: f32) -> Option<KeyCode> {
+    preset.apply(renderer, scene);
+    let view = preset.heatmap.map(|heatmap| match heatmap {
+        Heatmap::Overhangs => KeyCode::KeyO,
+        Heatmap::Thickness => KeyCode::KeyH,
     });
+    show_color_view(scene, scale_factor, view);
+    view
+}
+
+/// Muestra los contornos del corte horizontal en `height` (o los quita)
+fn show_slice(renderer: &Renderer, scene: &Scene, global_scale: f32, height: Option<f32>) {
+    let Some(height) = height else {
+        renderer.lines().clear("slice");
+        return;
+    };
+    let polylines = slice_objects(&scene.objects, global_scale, Vec3::UNIT_Y, height)
+        .into_iter()
+        .map(|contour| Polyline { points: contour.points, color: [1.0, 0.8, 0.0], closed: contour.closed })
+        .collect();
+    renderer.lines().set("slice", polylines);
+}
+
+/// Dibuja las aristas de la descomposición convexa de cada objeto con la
+/// transformación actual (no sigue la animación hasta volver a activarla)
+fn show_hulls(renderer: &Renderer, scene: &Scene, global_scale: f32, visible: bool) {
+    if !visible {
+        renderer.lines().clear("hulls");
+        return;
+    }
+    let mut polylines = Vec::new();
+    for obj in &scene.objects {
+        let parts = obj.mesh.convex_decomposition(&DecompositionSettings::default());
+        println!("{}: {} partes convexas", obj.name, parts.len());
+        for part in parts {
+            let part = transformed(&part, &obj.model_matrix(global_scale));
+            polylines.extend((0..part.triangle_count()).filter_map(|t| part.triangle(t)).map(|corners| Polyline {
+                points: corners.map(|v| part.position(v)).to_vec(),
+                color: [0.2, 1.0, 1.0],
+                closed: true,
+            }));
+        }
+    }
+    renderer.lines().set("hulls", polylines);
+}
+
+/// Dibuja la normal de cada vértice con la transformación actual (no sigue
+/// la animación hasta volver a activarlas)
+fn show_normals(renderer: &Renderer, scene: &Scene, global_scale: f32, visible: bool) {
+    if !visible {
+        renderer.lines().clear(NORMALS_LAYER);
+        renderer.text().clear("vertice");
+        return;
+    }
+    let mut polylines = Vec::new();
+    for obj in &scene.objects {
+        let invalid = invalid_normals(obj);
+        if invalid > 0 {
+            println!("{}: {} vértices sin normal válida", obj.name, invalid);
+        }
+        polylines.extend(normal_lines(obj, global_scale));
+    }
+    renderer.lines().set(NORMALS_LAYER, polylines);
+}
+
+/// Dibuja los esqueletos de los objetos que tienen, con `selected` resaltada
+fn show_skeletons(renderer: &Renderer, scene: &Scene, global_scale: f32, visible: bool, selected: Option<(usize, usize)>) {
+    if !visible {
+        renderer.lines().clear(SKELETON_LAYER);
+        renderer.text().clear("articulacion");
+        return;
+    }
+    let mut polylines = Vec::new();
+    for (index, obj) in scene.objects.iter().enumerate() {
+        let Some(skeleton) = &obj.skeleton else { continue };
+        let joint = selected.filter(|(object, _)| *object == index).map(|(_, joint)| joint);
+        polylines.extend(skeleton_lines(skeleton, &obj.model_matrix(global_scale), joint));
+    }
+    renderer.lines().set(SKELETON_LAYER, polylines);
+}
+
+/// Dibuja las ternas de los marcos de referencia con sus nombres, con la
+/// cámara y el tamaño de la ventana (píxeles lógicos); None los oculta
+fn show_frames(renderer: &Renderer, scene: &Scene, global_scale: f32, view: Option<(&Matrix4, (f32, f32))>) {
+    renderer.text().clear_prefix(LABEL_PREFIX);
+    let Some((view_projection, screen)) = view else {
+        renderer.lines().clear(FRAMES_LAYER);
+        return;
+    };
+    let bounds = scene.bounds(global_scale);
+    let length = if bounds.is_empty() { 1.0 } else { bounds.size().magnitude() * AXIS_LENGTH };
+    renderer.lines().set(FRAMES_LAYER, frame_lines(&scene.frames, global_scale, length));
+    for (name, panel) in frame_labels(&scene.frames, global_scale, view_projection, screen) {
+        renderer.text().set(&name, panel);
+    }
+}
+
+/// Dibuja tangente y bitangente de cada vértice de los objetos con UVs
+fn show_tangents(renderer: &Renderer, scene: &Scene, global_scale: f32, visible: bool) {
+    if !visible {
+        renderer.lines().clear(TANGENTS_LAYER);
+        return;
+    }
+    let polylines = scene.objects.iter().flat_map(|obj| tangent_lines(obj, global_scale)).collect();
+    renderer.lines().set(TANGENTS_LAYER, polylines);
+}
+
+/// Ejecuta un script de procesamiento por lotes (ver `graphics::batch`)
+fn run_batch(script_path: &str) -> Result<(), String> {
+    let script = BatchScript::load(script_path)?;
+    // La ventana oculta solo aporta el contexto GL para cargar mallas y renderizar miniaturas
+    let event_loop = Backend::from_env().event_loop()?;
+    let _window = Window::hidden("Rust_Engine", 256, 256, &event_loop)?;
+    let mut renderer = Renderer::new("src/graphics/shaders/basic.vert", "src/graphics/shaders/basic.frag")?;
+
+    let objects = batch::run(&mut renderer, &script)?;
+    println!("Script terminado: {} objetos", objects.len());
+    Ok(())
+}
+
+/// Genera las miniaturas de una carpeta de modelos (ver `graphics::thumbnails`).
+/// Falla si algún modelo no se pudo procesar, después de intentar con todos.
+fn run_thumbnails(input: &str, output: &str, options: &[String]) -> Result<(), String> {
+    let settings = ThumbnailSettings::from_args(options)?;
+    let event_loop = Backend::from_env().event_loop()?;
+    let _window = Window::hidden("Rust_Engine", settings.size, settings.size, &event_loop)?;
+    let mut renderer = Renderer::new("src/graphics/shaders/basic.vert", "src/graphics/shaders/basic.frag")?;
+
+    let report = thumbnails::generate(&mut renderer, input, output, &settings)?;
+    println!("{} miniaturas generadas en {}", report.rendered.len(), output);
+    if !report.failed.is_empty() {
+        return Err(format!("{} modelos no se pudieron procesar", report.failed.len()));
+    }
+    Ok(())
+}
+
+/// Exporta las vistas acotadas de un modelo o escena (ver `graphics::ortho_views`)
+fn run_views(model: &str, output: &str) -> Result<(), String> {
+    // La ventana oculta solo aporta el contexto GL para cargar las mallas
+    let event_loop = Backend::from_env().event_loop()?;
+    let _window = Window::hidden("Rust_Engine", 64, 64, &event_loop)?;
+    let objects = if model.to_lowercase().ends_with(".ron") {
+        Scene::load(model)?.objects
+    } else {
+        graphics::scene::load_model_file(model)?
+    };
+    ortho_views::export(&objects, 1.0, &OrthoSettings::default(), output)?;
+    println!("Vistas acotadas guardadas en {}", output);
+    Ok(())
+}
+
+/// Escena con la malla medida coloreada por su desviación respecto de la
+/// referencia (azul adentro, verde coincide, rojo afuera). Imprime el resumen.
+fn comparison_scene(measured: &str, reference: &str) -> Result<Scene, String> {
+    let mut measured = graphics::scene::load_mesh_file(measured)?;
+    let reference = graphics::scene::load_mesh_file(reference)?;
+    let deviation = compare(&measured, &reference, 1.0, f32::INFINITY);
+    let stats = deviation.stats;
+    println!(
+        "Desviación de {} respecto de {}: mín {:.4}, máx {:.4}, media {:.4}, RMS {:.4}",
+        measured.name, reference.name, stats.min, stats.max, stats.mean, stats.rms
+    );
+    measured.set_vertex_colors(Some(&deviation.heatmap(None)));
+
+    let mut scene = Scene::new();
+    scene.objects.push(measured);
+    Ok(scene)
+}
+
+/// Escena de ejemplo con las dos piezas de `src/assets`
+fn default_scene() -> Scene {
+    let mut scene = Scene::new();
+
+    // objeto 1
+    let mut obj1 = SceneObject::create_object_from_stl("src/assets/pieza.stl");
+    obj1.transform = Transform::from_translation(Vec3::new(0.0, 0.0, 0.0));
+    obj1.angular_velocity = Vec3::new(0.0, 1.0, 0.0);
+    scene.objects.push(obj1);
+
+    // objeto 2
+    let mut obj2 = SceneObject::create_object_from_stl("src/assets/pieza1.stl");
+    obj2.transform = Transform::new(
+        Vec3::new(-60.01, 0.01, 0.01),
+        Quat::from_axis_angle(Vec3::UNIT_Y, 0.5),
+        Vec3::new(1.0, 1.0, 1.0),
+    );
+    obj2.angular_velocity = Vec3::new(0.0, -2.0, 0.0);
+    scene.objects.push(obj2);
+
+    scene
 }
@@ -1,3 +0,0 @@
-pub mod vec3;
-pub mod matrix_4_by_4;
-pub mod float3_eps;
\ No newline at end of file